@@ -0,0 +1,26 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use emergence_lib::testing::crafting_app;
+
+/// The number of crafting structures to benchmark with
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+/// Benchmarks a single tick of every crafting structure progressing its recipe,
+/// comparing the parallel system against a serial baseline.
+fn bench_progress_crafting(c: &mut Criterion) {
+    let mut group = c.benchmark_group("progress_crafting");
+
+    for n_structures in SIZES {
+        for (name, parallel) in [("serial", false), ("parallel", true)] {
+            let mut app = crafting_app(n_structures, parallel);
+
+            group.bench_function(BenchmarkId::new(name, n_structures), |b| {
+                b.iter(|| app.update())
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_progress_crafting);
+criterion_main!(benches);
@@ -1,6 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use emergence_lib::asset_management::manifest::Id;
-use emergence_lib::signals::{SignalStrength, SignalType, Signals, DIFFUSION_FRACTION};
+use emergence_lib::signals::{SignalPropagationConfig, SignalStrength, SignalType, Signals};
 use emergence_lib::simulation::geometry::{MapGeometry, TilePos};
 use rand::thread_rng;
 
@@ -26,7 +26,64 @@ fn add_signals(settings: Settings) -> (Signals, MapGeometry) {
 /// Benchmarks the signal diffusion process
 fn signal_diffusion(settings: Settings) {
     let (mut signals, map_geometry) = add_signals(settings);
-    signals.diffuse(&map_geometry, DIFFUSION_FRACTION);
+    signals.diffuse(&map_geometry, &SignalPropagationConfig::default());
+}
+
+/// Emits, diffuses and degrades signals for a while, so they spread out across the map like they do in a running game.
+///
+/// Returns the signal sources, so that they can keep emitting while being benchmarked.
+fn settled_signals(settings: Settings) -> (Signals, MapGeometry, Vec<(SignalType, TilePos)>) {
+    let mut signals = Signals::default();
+    let map_geometry = MapGeometry::new(settings.map_radius);
+    let mut rng = thread_rng();
+
+    let mut sources = Vec::new();
+    for i in 0..settings.n_signals {
+        let signal_type = SignalType::Pull(Id::from_name(&format!("{i}")));
+
+        for _ in 0..settings.n_sources {
+            sources.push((signal_type, TilePos::random(&map_geometry, &mut rng)));
+        }
+    }
+
+    for _ in 0..Settings::SETTLING_STEPS {
+        signal_propagation(&mut signals, &map_geometry, &sources);
+    }
+
+    (signals, map_geometry, sources)
+}
+
+/// Benchmarks a single tick of signal emission and propagation, once the signals have spread out.
+fn signal_propagation(
+    signals: &mut Signals,
+    map_geometry: &MapGeometry,
+    sources: &[(SignalType, TilePos)],
+) {
+    let propagation_config = SignalPropagationConfig::default();
+
+    for &(signal_type, tile_pos) in sources {
+        signals.add_signal(signal_type, tile_pos, SignalStrength::new(1.));
+    }
+
+    signals.diffuse(map_geometry, &propagation_config);
+    signals.degrade(&propagation_config);
+}
+
+/// Benchmarks the lookups performed when units follow signals upstream.
+///
+/// Each query reads the signal strength at a tile and each of its neighbors.
+fn signal_lookup(signals: &Signals, map_geometry: &MapGeometry, queries: &[TilePos]) -> f32 {
+    let signal_type = SignalType::Pull(Id::from_name("0"));
+    let mut total = 0.;
+
+    for &tile_pos in queries {
+        total += signals.get(signal_type, tile_pos).value();
+        for neighbor in tile_pos.ring(1, map_geometry) {
+            total += signals.get(signal_type, neighbor).value();
+        }
+    }
+
+    total
 }
 
 /// Benchmark settings, in a reusable form
@@ -42,6 +99,12 @@ impl Settings {
     /// Realistic values are between 2 and 20 or so.
     const SPARSITY: u32 = 5;
 
+    /// How many ticks signals are propagated for before benchmarking the settled state.
+    const SETTLING_STEPS: usize = 20;
+
+    /// How many upstream queries are made in the lookup benchmark.
+    const N_QUERIES: usize = 1000;
+
     const MINIMAL: Settings = Settings {
         map_radius: 1,
         n_signals: 1,
@@ -88,6 +151,24 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("signal_diffusion_modest", |b| {
         b.iter(|| signal_diffusion(Settings::MODEST))
     });
+
+    let (mut signals, map_geometry, sources) = settled_signals(Settings::TINY);
+    c.bench_function("signal_propagation_settled_tiny", |b| {
+        b.iter(|| signal_propagation(&mut signals, &map_geometry, &sources))
+    });
+
+    let (mut signals, map_geometry, sources) = settled_signals(Settings::MODEST);
+    c.bench_function("signal_propagation_settled_modest", |b| {
+        b.iter(|| signal_propagation(&mut signals, &map_geometry, &sources))
+    });
+
+    let mut rng = thread_rng();
+    let queries: Vec<TilePos> = (0..Settings::N_QUERIES)
+        .map(|_| TilePos::random(&map_geometry, &mut rng))
+        .collect();
+    c.bench_function("signal_lookup_settled_modest", |b| {
+        b.iter(|| signal_lookup(&signals, &map_geometry, &queries))
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
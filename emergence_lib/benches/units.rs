@@ -0,0 +1,45 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use emergence_lib::testing::unit_colony_app;
+
+/// Benchmark settings, in a reusable form
+struct Settings {
+    map_radius: u32,
+    n_units: usize,
+    n_structures: usize,
+}
+
+impl Settings {
+    const TINY: Settings = Settings {
+        map_radius: 10,
+        n_units: 10,
+        n_structures: 10,
+    };
+
+    const MODEST: Settings = Settings {
+        map_radius: 50,
+        n_units: 1000,
+        n_structures: 1000,
+    };
+
+    const LARGE: Settings = Settings {
+        map_radius: 100,
+        n_units: 10000,
+        n_structures: 5000,
+    };
+}
+
+/// Benchmarks a single round of every unit in the colony choosing its next action
+fn bench_choose_actions(c: &mut Criterion, name: &str, settings: Settings) {
+    let mut app = unit_colony_app(settings.map_radius, settings.n_units, settings.n_structures);
+
+    c.bench_function(name, |b| b.iter(|| app.update()));
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    bench_choose_actions(c, "choose_actions_tiny", Settings::TINY);
+    bench_choose_actions(c, "choose_actions_modest", Settings::MODEST);
+    bench_choose_actions(c, "choose_actions_large", Settings::LARGE);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
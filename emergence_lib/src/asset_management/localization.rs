@@ -0,0 +1,216 @@
+//! Translates the raw names of game objects into the text shown to players.
+//!
+//! Manifests identify everything by a string key (like `acacia_leaf`),
+//! which is also used to find its assets.
+//! The [`Localization`] string table maps these keys to a display name in each locale,
+//! and falls back to the raw key when no translation exists.
+
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+
+use super::{
+    manifest::plugin::DetectManifestCreationSet, AssetCollectionExt, AssetState, Loadable,
+};
+
+/// Loads the [`Localization`] string table.
+pub(super) struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Localization>()
+            .init_asset_loader::<RawLocalizationLoader>()
+            .add_asset::<RawLocalization>()
+            .add_asset_collection::<RawLocalizationHandle>()
+            .add_system(
+                detect_localization_creation
+                    // Manifest validation uses the string table to name missing entries
+                    .in_set(DetectManifestCreationSet)
+                    .in_schedule(OnExit(AssetState::LoadManifests)),
+            );
+    }
+}
+
+/// The display strings for game objects, in each locale.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct Localization {
+    /// The locale that strings are currently displayed in.
+    locale: String,
+    /// The display string for each key, stored by locale.
+    strings: HashMap<String, HashMap<String, String>>,
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Localization::new(Localization::DEFAULT_LOCALE)
+    }
+}
+
+impl Localization {
+    /// The locale used when the player has not picked one.
+    pub const DEFAULT_LOCALE: &'static str = "en";
+
+    /// Creates an empty string table, which displays strings in the provided `locale`.
+    pub fn new(locale: &str) -> Self {
+        Localization {
+            locale: locale.to_string(),
+            strings: HashMap::default(),
+        }
+    }
+
+    /// The locale that strings are currently displayed in.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Changes the locale that strings are displayed in.
+    pub fn set_locale(&mut self, locale: &str) {
+        self.locale = locale.to_string();
+    }
+
+    /// Registers that `key` should be displayed as `display` in the provided `locale`.
+    pub fn insert(&mut self, locale: &str, key: &str, display: &str) {
+        self.strings
+            .entry(locale.to_string())
+            .or_default()
+            .insert(key.to_string(), display.to_string());
+    }
+
+    /// The display string for `key` in the current locale.
+    ///
+    /// If no translation exists, the raw `key` is returned.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(&self.locale)
+            .and_then(|strings| strings.get(key))
+            .map(String::as_str)
+            .unwrap_or(key)
+    }
+
+    /// Iterates over every key that has a display string, in any locale.
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &str> {
+        self.strings
+            .values()
+            .flat_map(|strings| strings.keys().map(String::as_str))
+    }
+
+    /// Adds all of the strings stored in `raw_localization`, overwriting any existing strings for the same keys.
+    pub fn load(&mut self, raw_localization: &RawLocalization) {
+        for (key, display) in raw_localization.strings.iter() {
+            self.insert(&raw_localization.locale, key, display);
+        }
+    }
+}
+
+/// The string table for a single locale, as stored on disk.
+#[derive(Debug, Clone, Deserialize, TypeUuid, PartialEq, Eq)]
+#[uuid = "97efd427-12cd-4ffe-a101-e8ac39267222"]
+pub struct RawLocalization {
+    /// The locale that these strings are written in.
+    pub locale: String,
+    /// The display string for each raw key.
+    pub strings: HashMap<String, String>,
+}
+
+impl RawLocalization {
+    /// The file extension of locale files.
+    const EXTENSION: &'static str = "locale.json";
+
+    /// Returns the path to the file for the provided `locale`.
+    fn path(locale: &str) -> PathBuf {
+        Path::new("locales")
+            .join(locale)
+            .with_extension(Self::EXTENSION)
+    }
+}
+
+/// A loader for `.locale.json` files.
+#[derive(Debug, Clone, Default)]
+struct RawLocalizationLoader;
+
+impl AssetLoader for RawLocalizationLoader {
+    fn extensions(&self) -> &[&str] {
+        &[RawLocalization::EXTENSION]
+    }
+
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, anyhow::Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let raw_localization = serde_json::from_slice::<RawLocalization>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(raw_localization));
+            Ok(())
+        })
+    }
+}
+
+/// Resource to store the handle to the [`RawLocalization`] while it is being loaded.
+#[derive(Debug, Clone, Resource)]
+struct RawLocalizationHandle {
+    /// The handle to the string table for the default locale.
+    handle: Handle<RawLocalization>,
+}
+
+impl Loadable for RawLocalizationHandle {
+    const STAGE: AssetState = AssetState::LoadManifests;
+
+    fn initialize(world: &mut World) {
+        let asset_server = world.resource::<AssetServer>();
+        let handle = asset_server.load(RawLocalization::path(Localization::DEFAULT_LOCALE));
+
+        world.insert_resource(Self { handle });
+    }
+
+    fn load_state(&self, asset_server: &AssetServer) -> bevy::asset::LoadState {
+        asset_server.get_load_state(self.handle.clone_weak())
+    }
+}
+
+/// Adds the loaded string table to the [`Localization`] resource.
+fn detect_localization_creation(
+    raw_localization_handle: Res<RawLocalizationHandle>,
+    raw_localizations: Res<Assets<RawLocalization>>,
+    mut localization: ResMut<Localization>,
+) {
+    let Some(raw_localization) = raw_localizations.get(&raw_localization_handle.handle) else {
+        error!("Locale file loaded, but asset not available!");
+        return;
+    };
+
+    localization.load(raw_localization);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_strings_fall_back_to_raw_key() {
+        let mut localization = Localization::default();
+        localization.insert("fr", "acacia_leaf", "Feuille d'acacia");
+
+        assert_eq!(localization.get("acacia_leaf"), "acacia_leaf");
+
+        localization.set_locale("fr");
+        assert_eq!(localization.get("acacia_leaf"), "Feuille d'acacia");
+        assert_eq!(localization.get("leuco_chunk"), "leuco_chunk");
+    }
+
+    #[test]
+    fn locale_files_can_be_deserialized() {
+        let json = r#"{ "locale": "en", "strings": { "acacia_leaf": "Acacia Leaf" } }"#;
+        let raw_localization: RawLocalization = serde_json::from_str(json).unwrap();
+
+        let mut localization = Localization::default();
+        localization.load(&raw_localization);
+        assert_eq!(localization.get("acacia_leaf"), "Acacia Leaf");
+    }
+}
@@ -1,6 +1,7 @@
 //! A loader for manifest assets.
 
 use std::{
+    fmt::{Display, Formatter},
     marker::PhantomData,
     path::{Path, PathBuf},
 };
@@ -12,9 +13,18 @@ use bevy::{
 
 use bevy::reflect::TypeUuid;
 use serde::Deserialize;
+use serde_json::Value;
 
 use super::Manifest;
 
+/// The key of the schema version stored at the top level of each manifest file.
+pub const VERSION_KEY: &str = "version";
+
+/// Upgrades the JSON of a manifest file by a single schema version.
+///
+/// Migrations operate on the raw JSON, as older files cannot be deserialized into the current types.
+pub type ManifestMigration = fn(&mut Value) -> anyhow::Result<()>;
+
 /// The raw manifest data before it has been processed.
 ///
 /// The processing will primarily remove the string IDs and replace them by numbers.
@@ -34,15 +44,121 @@ pub trait RawManifest:
     /// The type of the processed manifest data.
     type Data: std::fmt::Debug + Send + Sync;
 
-    /// Returns the path to the manifest file.
+    /// The migrations used to upgrade older manifest files to the current schema.
+    ///
+    /// The migration at index `n` upgrades files from version `n + 1` to version `n + 2`.
+    /// Add a new migration to the end of this list whenever the schema changes in a way that would break existing files.
+    const MIGRATIONS: &'static [ManifestMigration] = &[];
+
+    /// The current schema version of this manifest type.
+    ///
+    /// Versions start at 1, and increase by one with each of the [`Self::MIGRATIONS`].
+    fn current_version() -> u32 {
+        Self::MIGRATIONS.len() as u32 + 1
+    }
+
+    /// Returns the directory that is searched for manifest files.
+    ///
+    /// Every file in this directory that ends in [`Self::EXTENSION`] is loaded,
+    /// and their contents are merged into a single [`Manifest`].
+    fn directory() -> PathBuf {
+        PathBuf::from("manifests")
+    }
+
+    /// Returns the path to the base game's manifest file.
+    ///
+    /// This file is always loaded first, so that other files can override its entries.
     fn path() -> PathBuf {
-        Path::new("manifests/base_game").with_extension(Self::EXTENSION)
+        Self::directory()
+            .join("base_game")
+            .with_extension(Self::EXTENSION)
+    }
+
+    /// Is the file at `path` a manifest of this type?
+    fn is_manifest_file(path: &Path) -> bool {
+        path.file_name()
+            .and_then(|file_name| file_name.to_str())
+            .is_some_and(|file_name| file_name.ends_with(&format!(".{}", Self::EXTENSION)))
     }
 
     /// Process the raw manifest from the asset file to the manifest data used in-game.
     fn process(&self) -> Manifest<Self::Marker, Self::Data>;
 }
 
+/// The schema version of a manifest file could not be handled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestVersionError {
+    /// The `version` field was not a positive integer.
+    Invalid {
+        /// The value that was found.
+        found: String,
+    },
+    /// The file was written for a newer version of the game.
+    TooNew {
+        /// The version of the file.
+        version: u32,
+        /// The newest version that this build of the game understands.
+        current_version: u32,
+    },
+}
+
+impl Display for ManifestVersionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestVersionError::Invalid { found } => write!(
+                f,
+                "The manifest `{VERSION_KEY}` must be a positive integer, but was {found}"
+            ),
+            ManifestVersionError::TooNew {
+                version,
+                current_version,
+            } => write!(
+                f,
+                "The manifest has version {version}, but only versions up to {current_version} are supported. Try updating the game."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ManifestVersionError {}
+
+/// Deserializes a raw manifest of type `M` from JSON, upgrading it to the current schema if needed.
+///
+/// Files without a `version` field predate versioning, and are treated as version 1.
+pub fn deserialize_raw_manifest<M: RawManifest>(bytes: &[u8]) -> anyhow::Result<M> {
+    let mut json: Value = serde_json::from_slice(bytes)?;
+
+    let version = match json.get(VERSION_KEY) {
+        None => 1,
+        Some(found) => found
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .filter(|&version| version > 0)
+            .ok_or_else(|| ManifestVersionError::Invalid {
+                found: found.to_string(),
+            })?,
+    };
+
+    let current_version = M::current_version();
+    if version > current_version {
+        return Err(ManifestVersionError::TooNew {
+            version,
+            current_version,
+        }
+        .into());
+    }
+
+    for migration in &M::MIGRATIONS[version as usize - 1..] {
+        migration(&mut json)?;
+    }
+
+    if let Some(object) = json.as_object_mut() {
+        object.insert(VERSION_KEY.to_string(), current_version.into());
+    }
+
+    Ok(serde_json::from_value(json)?)
+}
+
 /// A loader for `.manifest.json` files.
 #[derive(Debug, Clone)]
 pub(crate) struct RawManifestLoader<M>
@@ -67,7 +183,7 @@ where
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, anyhow::Result<(), anyhow::Error>> {
         Box::pin(async move {
-            let raw_manifest = serde_json::from_slice::<M>(bytes)?;
+            let raw_manifest = deserialize_raw_manifest::<M>(bytes)?;
             load_context.set_default_asset(LoadedAsset::<M>::new(raw_manifest));
             Ok(())
         })
@@ -84,3 +200,113 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::HashMap;
+
+    use super::*;
+    use crate::asset_management::manifest::Id;
+
+    /// The marker type for the test manifest.
+    struct Widget;
+
+    /// The data for each widget, as of version 2.
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    struct WidgetData {
+        /// How big the widget is.
+        size: u32,
+        /// Can the widget be rotated?
+        rotatable: bool,
+    }
+
+    /// A manifest whose schema changed between versions 1 and 2.
+    ///
+    /// In version 1, each widget was only described by its size.
+    #[derive(Debug, Clone, Deserialize, TypeUuid)]
+    #[uuid = "5c4bb6b0-0b8b-4d43-b8c7-5a3e10e0ae3c"]
+    struct RawWidgetManifest {
+        /// The schema version of this manifest.
+        version: u32,
+        /// The data for each widget.
+        widgets: HashMap<String, WidgetData>,
+    }
+
+    /// Wraps the size of each widget in an object, and makes it non-rotatable.
+    fn widgets_v1_to_v2(json: &mut Value) -> anyhow::Result<()> {
+        let widgets = json
+            .get_mut("widgets")
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| anyhow::anyhow!("Expected a `widgets` object"))?;
+
+        for widget in widgets.values_mut() {
+            *widget = serde_json::json!({ "size": widget.take(), "rotatable": false });
+        }
+
+        Ok(())
+    }
+
+    impl RawManifest for RawWidgetManifest {
+        const EXTENSION: &'static str = "widget_manifest.json";
+        const MIGRATIONS: &'static [ManifestMigration] = &[widgets_v1_to_v2];
+
+        type Marker = Widget;
+        type Data = WidgetData;
+
+        fn process(&self) -> Manifest<Self::Marker, Self::Data> {
+            let mut manifest = Manifest::new();
+
+            for (name, raw_data) in &self.widgets {
+                manifest.insert(name, raw_data.clone())
+            }
+
+            manifest
+        }
+    }
+
+    #[test]
+    fn old_manifests_are_migrated_to_the_current_version() {
+        let v1 = r#"{ "version": 1, "widgets": { "sprocket": 3 } }"#;
+        let raw_manifest = deserialize_raw_manifest::<RawWidgetManifest>(v1.as_bytes()).unwrap();
+
+        assert_eq!(raw_manifest.version, 2);
+        assert_eq!(
+            raw_manifest.process().get(Id::from_name("sprocket")),
+            &WidgetData {
+                size: 3,
+                rotatable: false
+            }
+        );
+
+        // Files that predate versioning are treated as version 1
+        let unversioned = r#"{ "widgets": { "sprocket": 3 } }"#;
+        let raw_manifest =
+            deserialize_raw_manifest::<RawWidgetManifest>(unversioned.as_bytes()).unwrap();
+        assert_eq!(raw_manifest.widgets["sprocket"].size, 3);
+
+        // Current files are left untouched
+        let v2 = r#"{ "version": 2, "widgets": { "sprocket": { "size": 3, "rotatable": true } } }"#;
+        let raw_manifest = deserialize_raw_manifest::<RawWidgetManifest>(v2.as_bytes()).unwrap();
+        assert!(raw_manifest.widgets["sprocket"].rotatable);
+    }
+
+    #[test]
+    fn unknown_manifest_versions_are_rejected() {
+        let too_new = r#"{ "version": 3, "widgets": {} }"#;
+        let error = deserialize_raw_manifest::<RawWidgetManifest>(too_new.as_bytes()).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<ManifestVersionError>(),
+            Some(&ManifestVersionError::TooNew {
+                version: 3,
+                current_version: 2
+            })
+        );
+
+        let invalid = r#"{ "version": "two", "widgets": {} }"#;
+        let error = deserialize_raw_manifest::<RawWidgetManifest>(invalid.as_bytes()).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ManifestVersionError>(),
+            Some(ManifestVersionError::Invalid { .. })
+        ));
+    }
+}
@@ -8,6 +8,7 @@ mod identifier;
 pub use self::identifier::*;
 pub mod loader;
 pub mod plugin;
+pub mod validation;
 
 use bevy::{prelude::*, utils::HashMap};
 use std::fmt::Debug;
@@ -26,6 +27,11 @@ where
 
     /// The human-readable name associated with each Id.
     name_map: HashMap<Id<T>, String>,
+
+    /// Every Id in the manifest, sorted alphabetically by name.
+    ///
+    /// This gives iteration a stable order, regardless of how the manifest was loaded.
+    sorted_ids: Vec<Id<T>>,
 }
 
 impl<T: 'static, Data: Debug> Default for Manifest<T, Data> {
@@ -43,6 +49,7 @@ where
         Self {
             data_map: HashMap::default(),
             name_map: HashMap::default(),
+            sorted_ids: Vec::new(),
         }
     }
 
@@ -51,6 +58,28 @@ where
         &self.data_map
     }
 
+    /// Iterates over the [`Id`] and data of each entry in the manifest.
+    ///
+    /// Entries are sorted alphabetically by name.
+    pub fn iter(&self) -> impl Iterator<Item = (Id<T>, &Data)> + '_ {
+        self.sorted_ids.iter().map(|&id| (id, self.get(id)))
+    }
+
+    /// The number of entries in the manifest.
+    pub fn len(&self) -> usize {
+        self.data_map.len()
+    }
+
+    /// Does this manifest contain any entries?
+    pub fn is_empty(&self) -> bool {
+        self.data_map.is_empty()
+    }
+
+    /// Does this manifest contain an entry for the provided `id`?
+    pub fn contains(&self, id: Id<T>) -> bool {
+        self.data_map.contains_key(&id)
+    }
+
     /// Returns a reference to the internal name map.
     pub fn name_map(&self) -> &HashMap<Id<T>, String> {
         &self.name_map
@@ -63,7 +92,35 @@ where
         let id = Id::from_name(name);
 
         self.data_map.insert(id, data);
-        self.name_map.insert(id, name.to_string());
+        self.insert_name(id, name.to_string());
+    }
+
+    /// Records the `name` of `id`, keeping `sorted_ids` in order.
+    fn insert_name(&mut self, id: Id<T>, name: String) {
+        if self.name_map.insert(id, name.clone()).is_none() {
+            let index = self
+                .sorted_ids
+                .partition_point(|&existing| self.name_map[&existing] < name);
+            self.sorted_ids.insert(index, id);
+        }
+    }
+
+    /// Adds all entries of `other` to this manifest.
+    ///
+    /// Entries in `other` replace any existing entries with the same name.
+    /// Returns the names of the entries that were replaced.
+    pub fn merge(&mut self, other: Self) -> Vec<String> {
+        let mut replaced = Vec::new();
+
+        for (id, data) in other.data_map {
+            let name = other.name_map.get(&id).cloned().unwrap_or_default();
+            if self.data_map.insert(id, data).is_some() {
+                replaced.push(name.clone());
+            }
+            self.insert_name(id, name);
+        }
+
+        replaced
     }
 
     /// Get the data entry for the given ID.
@@ -92,7 +149,7 @@ where
 
     /// Returns the complete list of names of the loaded options.
     ///
-    /// The order is arbitrary.
+    /// Names are sorted alphabetically.
     pub fn names(&self) -> impl IntoIterator<Item = &str> {
         let variants = self.variants();
         variants.into_iter().map(|id| self.name(id))
@@ -100,8 +157,35 @@ where
 
     /// The complete list of loaded options.
     ///
-    /// The order is arbitrary.
+    /// Entries are sorted alphabetically by name.
     pub fn variants(&self) -> impl IntoIterator<Item = Id<T>> + '_ {
-        self.data_map.keys().copied()
+        self.sorted_ids.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A marker type for manifests used in tests.
+    struct Thing;
+
+    #[test]
+    fn manifests_can_be_iterated_over() {
+        let mut manifest: Manifest<Thing, u8> = Manifest::new();
+        assert!(manifest.is_empty());
+
+        manifest.insert("small", 1);
+        manifest.insert("large", 10);
+
+        assert_eq!(manifest.len(), 2);
+        assert!(manifest.contains(Id::from_name("small")));
+        assert!(!manifest.contains(Id::from_name("medium")));
+
+        let entries: Vec<(&str, u8)> = manifest
+            .iter()
+            .map(|(id, &data)| (manifest.name(id), data))
+            .collect();
+        assert_eq!(entries, vec![("large", 10), ("small", 1)]);
     }
 }
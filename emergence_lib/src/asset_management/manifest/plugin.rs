@@ -1,6 +1,9 @@
 //! The plugin to handle loading of manifest assets.
 
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
 
 use bevy::prelude::*;
 
@@ -41,7 +44,10 @@ where
     M: RawManifest,
 {
     fn build(&self, app: &mut App) {
-        info!("Building RawManifestPlugin for {}", M::path().display());
+        info!(
+            "Building RawManifestPlugin for {}",
+            M::directory().join(format!("*.{}", M::EXTENSION)).display()
+        );
 
         app.init_asset_loader::<RawManifestLoader<M>>()
             .add_asset::<M>()
@@ -58,18 +64,90 @@ where
     }
 }
 
-/// Resource to store the handle to a [`RawManifest`] while it is being loaded.
+/// Resource to store the handles to each [`RawManifest`] file while it is being loaded.
 ///
-/// This is necessary to stop the asset from being discarded.
+/// This is necessary to stop the assets from being discarded.
 #[derive(Debug, Clone, Resource)]
 pub struct RawManifestHandle<M>
 where
     M: RawManifest,
 {
-    /// The handle to the raw manifest asset.
+    /// The path and handle of each raw manifest asset, in the order that they should be merged.
     ///
-    /// We mainly need this for the asset to not be unloaded.
-    handle: Handle<M>,
+    /// We mainly need this for the assets to not be unloaded.
+    handles: Vec<(PathBuf, Handle<M>)>,
+}
+
+impl<M> RawManifestHandle<M>
+where
+    M: RawManifest,
+{
+    /// Processes and merges all of the loaded raw manifests into a single [`Manifest`].
+    ///
+    /// Any raw manifests which are not available are skipped.
+    fn merge(&self, raw_manifests: &Assets<M>) -> Manifest<M::Marker, M::Data> {
+        let loaded_manifests = self.handles.iter().filter_map(|(path, handle)| {
+            let raw_manifest = raw_manifests.get(handle);
+            if raw_manifest.is_none() {
+                error!("Raw manifest {} not available!", path.display());
+            }
+
+            raw_manifest.map(|raw_manifest| (path.as_path(), raw_manifest))
+        });
+
+        merge_manifests(loaded_manifests)
+    }
+}
+
+/// Returns the paths of all manifest files of type `M`.
+///
+/// The base game manifest is always first, followed by any other files in alphabetical order.
+fn manifest_paths<M>(asset_server: &AssetServer) -> Vec<PathBuf>
+where
+    M: RawManifest,
+{
+    let base_path = M::path();
+
+    let mut other_paths: Vec<PathBuf> =
+        match asset_server.asset_io().read_directory(&M::directory()) {
+            Ok(paths) => paths
+                .filter(|path| *path != base_path && M::is_manifest_file(path))
+                .collect(),
+            Err(error) => {
+                warn!(
+                    "Could not search {} for manifests: {error}",
+                    M::directory().display()
+                );
+                Vec::new()
+            }
+        };
+    other_paths.sort();
+
+    std::iter::once(base_path).chain(other_paths).collect()
+}
+
+/// Processes each of the `raw_manifests` and merges them into a single [`Manifest`].
+///
+/// Later manifests override any entries with the same name in earlier manifests.
+/// Each of these conflicts is reported.
+pub(crate) fn merge_manifests<'a, M>(
+    raw_manifests: impl IntoIterator<Item = (&'a Path, &'a M)>,
+) -> Manifest<M::Marker, M::Data>
+where
+    M: RawManifest,
+{
+    let mut manifest = Manifest::new();
+
+    for (path, raw_manifest) in raw_manifests {
+        for name in manifest.merge(raw_manifest.process()) {
+            warn!(
+                "{name} is defined in multiple manifests: using the definition from {}",
+                path.display()
+            );
+        }
+    }
+
+    manifest
 }
 
 impl<M> Loadable for RawManifestHandle<M>
@@ -80,13 +158,21 @@ where
 
     fn initialize(world: &mut World) {
         let asset_server = world.resource::<AssetServer>();
-        let handle: Handle<M> = asset_server.load(M::path());
+        let handles = manifest_paths::<M>(asset_server)
+            .into_iter()
+            .map(|path| {
+                info!("Loading manifest {}", path.display());
+                let handle = asset_server.load(path.as_path());
+                (path, handle)
+            })
+            .collect();
 
-        world.insert_resource(Self { handle });
+        world.insert_resource(Self { handles });
     }
 
     fn load_state(&self, asset_server: &AssetServer) -> bevy::asset::LoadState {
-        let load_state = asset_server.get_load_state(self.handle.clone_weak());
+        let load_state =
+            asset_server.get_group_load_state(self.handles.iter().map(|(_, handle)| handle.id()));
 
         debug!("Load state: {load_state:?}");
 
@@ -94,7 +180,7 @@ where
     }
 }
 
-/// Wait for the manifest to be fully loaded and then process it.
+/// Wait for the manifests to be fully loaded and then process them.
 pub fn detect_manifest_creation<M>(
     mut commands: Commands,
     raw_manifest_handle: Res<RawManifestHandle<M>>,
@@ -102,36 +188,96 @@ pub fn detect_manifest_creation<M>(
 ) where
     M: RawManifest,
 {
-    let Some(raw_manifest) = raw_manifests.get(&raw_manifest_handle.handle) else {
-        error!("Raw manifest for {} created, but asset not available!", M::path().display());
-        return;
-    };
-
-    info!("Manifest asset {} loaded!", M::path().display());
+    info!("Manifest assets in {} loaded!", M::directory().display());
 
     // Create the manifest and insert it as a resource
-    commands.insert_resource(raw_manifest.process());
+    commands.insert_resource(raw_manifest_handle.merge(&raw_manifests));
 }
 
-/// Update the manifest after the asset has been changed.
+/// Update the manifest after any of its assets have been changed.
 fn detect_manifest_modification<M>(
     mut ev_asset: EventReader<AssetEvent<M>>,
+    raw_manifest_handle: Res<RawManifestHandle<M>>,
     raw_manifests: Res<Assets<M>>,
     mut manifest: ResMut<Manifest<M::Marker, M::Data>>,
 ) where
     M: RawManifest,
 {
-    for ev in ev_asset.iter() {
-        if let AssetEvent::Modified { handle } = ev {
-            let Some(raw_manifest) = raw_manifests.get(handle) else {
-                warn!("Raw manifest modified, but asset not available!");
-                continue;
-            };
+    let modified = ev_asset
+        .iter()
+        .any(|ev| matches!(ev, AssetEvent::Modified { .. }));
 
-            debug!("Manifest asset {} modified.", M::path().display());
+    if modified {
+        debug!("Manifest assets in {} modified.", M::directory().display());
 
-            // Update the manifest resource
-            *manifest = raw_manifest.process();
-        }
+        // Update the manifest resource
+        *manifest = raw_manifest_handle.merge(&raw_manifests);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::HashMap;
+
+    use super::*;
+    use crate::{
+        asset_management::manifest::Id,
+        items::item_manifest::{Item, ItemData, RawItemManifest},
+    };
+
+    #[test]
+    fn later_manifests_override_and_extend_earlier_ones() {
+        let base_game = RawItemManifest {
+            items: HashMap::from_iter([
+                ("acacia_leaf".to_string(), ItemData::new(10)),
+                ("leuco_chunk".to_string(), ItemData::new(5)),
+            ]),
+        };
+        let mod_manifest = RawItemManifest {
+            items: HashMap::from_iter([
+                ("leuco_chunk".to_string(), ItemData::new(20)),
+                ("ant_egg".to_string(), ItemData::new(1)),
+            ]),
+        };
+
+        let manifest = merge_manifests([
+            (
+                Path::new("manifests/base_game.item_manifest.json"),
+                &base_game,
+            ),
+            (
+                Path::new("manifests/my_mod.item_manifest.json"),
+                &mod_manifest,
+            ),
+        ]);
+
+        assert_eq!(manifest.len(), 3);
+        assert_eq!(
+            manifest
+                .get(Id::<Item>::from_name("acacia_leaf"))
+                .stack_size,
+            10
+        );
+        assert_eq!(
+            manifest
+                .get(Id::<Item>::from_name("leuco_chunk"))
+                .stack_size,
+            20
+        );
+        assert_eq!(manifest.get(Id::<Item>::from_name("ant_egg")).stack_size, 1);
+        assert_eq!(manifest.name(Id::from_name("ant_egg")), "ant_egg");
+    }
+
+    #[test]
+    fn only_matching_files_are_manifests() {
+        assert!(RawItemManifest::is_manifest_file(Path::new(
+            "manifests/my_mod.item_manifest.json"
+        )));
+        assert!(!RawItemManifest::is_manifest_file(Path::new(
+            "manifests/my_mod.recipe_manifest.json"
+        )));
+        assert!(!RawItemManifest::is_manifest_file(Path::new(
+            "manifests/schema/items.schema.json"
+        )));
     }
 }
@@ -0,0 +1,662 @@
+//! Checks that every [`Id`] stored in one manifest refers to an entry that actually exists.
+//!
+//! Values that the rest of the game relies on, such as item stack sizes, are checked here too.
+//!
+//! Manifests are written by hand (and by modders), so typos are easy to make.
+//! Catching them when the manifests are loaded gives a clear report,
+//! rather than a panic deep inside of a system much later on.
+
+use std::fmt::{Debug, Display, Formatter};
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_management::localization::Localization,
+    items::{item_manifest::ItemManifest, recipe::RecipeManifest},
+    organisms::{OrganismId, OrganismVariety},
+    structures::structure_manifest::{AdjacencyRequirement, StructureManifest},
+    terrain::terrain_manifest::TerrainManifest,
+    units::unit_manifest::UnitManifest,
+};
+
+use super::{Id, Manifest};
+
+/// A reference from a manifest entry to an [`Id`] that is not defined in the corresponding manifest.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DanglingReference {
+    /// The entry that contains the reference, such as `recipe "acacia_leaf_production"`.
+    pub source: String,
+    /// The field of the entry that holds the reference, such as `outputs`.
+    pub field: &'static str,
+    /// The kind of manifest entry that could not be found, such as `item`.
+    pub missing_kind: &'static str,
+    /// The name of the entry that could not be found.
+    ///
+    /// Manifest files only store the hashed [`Id`], so the name is recovered from the names used elsewhere
+    /// in the manifests and the [`Localization`].
+    /// If the name is not used anywhere, the hashed [`Id`] is shown instead.
+    pub missing_id: String,
+}
+
+impl Display for DanglingReference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} refers to a missing {} in `{}`: {}",
+            self.source, self.missing_kind, self.field, self.missing_id
+        )
+    }
+}
+
+/// A manifest entry with a value that the game cannot use.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct InvalidValue {
+    /// The entry that contains the value, such as `item "leuco_chunk"`.
+    pub source: String,
+    /// The field of the entry that holds the value, such as `stack_size`.
+    pub field: &'static str,
+    /// What is wrong with the value.
+    pub problem: &'static str,
+}
+
+impl Display for InvalidValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} has an invalid `{}`: {}",
+            self.source, self.field, self.problem
+        )
+    }
+}
+
+/// The manifests refer to entries that do not exist, or contain values that cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestValidationError {
+    /// Every dangling reference that was found, sorted by the entry that contains it.
+    pub dangling_references: Vec<DanglingReference>,
+    /// Every invalid value that was found, sorted by the entry that contains it.
+    pub invalid_values: Vec<InvalidValue>,
+}
+
+impl Display for ManifestValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.dangling_references.is_empty() {
+            write!(
+                f,
+                "Found {} reference(s) to manifest entries that do not exist:",
+                self.dangling_references.len()
+            )?;
+
+            for dangling_reference in &self.dangling_references {
+                write!(f, "\n  - {dangling_reference}")?;
+            }
+        }
+
+        if !self.invalid_values.is_empty() {
+            if !self.dangling_references.is_empty() {
+                writeln!(f)?;
+            }
+
+            write!(
+                f,
+                "Found {} invalid value(s) in the manifests:",
+                self.invalid_values.len()
+            )?;
+
+            for invalid_value in &self.invalid_values {
+                write!(f, "\n  - {invalid_value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Collects the problems found while checking the manifests.
+struct Validator<'a> {
+    /// The dangling references found so far.
+    dangling_references: Vec<DanglingReference>,
+    /// The invalid values found so far.
+    invalid_values: Vec<InvalidValue>,
+    /// Every name that could have been used to create a missing [`Id`].
+    known_names: Vec<&'a str>,
+}
+
+impl<'a> Validator<'a> {
+    /// The name of `id`, or its hashed value if it does not match any of the `known_names`.
+    fn describe<T>(&self, id: Id<T>) -> String {
+        self.known_names
+            .iter()
+            .find(|&&name| Id::<T>::from_name(name) == id)
+            .map_or_else(|| format!("{id:?}"), |name| name.to_string())
+    }
+
+    /// Records a [`DanglingReference`] if `id` is not defined in `manifest`.
+    fn check<T, Data: Debug>(
+        &mut self,
+        manifest: &Manifest<T, Data>,
+        id: Id<T>,
+        source: &str,
+        field: &'static str,
+        missing_kind: &'static str,
+    ) {
+        if !manifest.contains(id) {
+            self.dangling_references.push(DanglingReference {
+                source: source.to_string(),
+                field,
+                missing_kind,
+                missing_id: self.describe(id),
+            });
+        }
+    }
+
+    /// Records an [`InvalidValue`] in the `field` of the `source` entry.
+    fn reject(&mut self, source: &str, field: &'static str, problem: &'static str) {
+        self.invalid_values.push(InvalidValue {
+            source: source.to_string(),
+            field,
+            problem,
+        });
+    }
+
+    /// Records a [`DanglingReference`] if the `organism_id` is not defined in the structure or unit manifest.
+    fn check_organism(
+        &mut self,
+        organism_id: OrganismId,
+        manifests: &ManifestSet,
+        source: &str,
+        field: &'static str,
+    ) {
+        match organism_id {
+            OrganismId::Structure(structure_id) => self.check(
+                manifests.structure_manifest,
+                structure_id,
+                source,
+                field,
+                "structure",
+            ),
+            OrganismId::Unit(unit_id) => {
+                self.check(manifests.unit_manifest, unit_id, source, field, "unit")
+            }
+        }
+    }
+
+    /// Checks the prototypical form and the life paths of an [`OrganismVariety`].
+    fn check_organism_variety(
+        &mut self,
+        organism_variety: &OrganismVariety,
+        manifests: &ManifestSet,
+        source: &str,
+    ) {
+        self.check_organism(
+            organism_variety.prototypical_form,
+            manifests,
+            source,
+            "prototypical_form",
+        );
+
+        for new_form in organism_variety.lifecycle.new_forms() {
+            self.check_organism(new_form, manifests, source, "lifecycle");
+        }
+    }
+}
+
+/// Checks the cross-references between the freshly loaded manifests.
+///
+/// # Panics
+///
+/// Panics with a list of every problem if any are found,
+/// as the game cannot run with an inconsistent set of manifests.
+pub(crate) fn validate_manifests(
+    item_manifest: Res<ItemManifest>,
+    recipe_manifest: Res<RecipeManifest>,
+    structure_manifest: Res<StructureManifest>,
+    terrain_manifest: Res<TerrainManifest>,
+    unit_manifest: Res<UnitManifest>,
+    localization: Res<Localization>,
+) {
+    let manifests = ManifestSet {
+        item_manifest: &item_manifest,
+        recipe_manifest: &recipe_manifest,
+        structure_manifest: &structure_manifest,
+        terrain_manifest: &terrain_manifest,
+        unit_manifest: &unit_manifest,
+        localization: Some(&localization),
+    };
+
+    match manifests.validate() {
+        Ok(()) => info!("All manifests are valid."),
+        Err(error) => panic!("{error}"),
+    }
+}
+
+/// All of the manifests, which may refer to each other.
+pub struct ManifestSet<'a> {
+    /// The item manifest.
+    pub item_manifest: &'a ItemManifest,
+    /// The recipe manifest.
+    pub recipe_manifest: &'a RecipeManifest,
+    /// The structure manifest.
+    pub structure_manifest: &'a StructureManifest,
+    /// The terrain manifest.
+    pub terrain_manifest: &'a TerrainManifest,
+    /// The unit manifest.
+    pub unit_manifest: &'a UnitManifest,
+    /// The display strings, whose keys are used to name missing entries.
+    pub localization: Option<&'a Localization>,
+}
+
+impl<'a> ManifestSet<'a> {
+    /// Every name used in the manifests and the localization.
+    fn known_names(&self) -> Vec<&'a str> {
+        let mut known_names: Vec<&'a str> = Vec::new();
+        known_names.extend(self.item_manifest.names());
+        known_names.extend(self.recipe_manifest.names());
+        known_names.extend(self.structure_manifest.names());
+        known_names.extend(self.terrain_manifest.names());
+        known_names.extend(self.unit_manifest.names());
+        known_names.extend(self.localization.into_iter().flat_map(Localization::keys));
+        known_names
+    }
+
+    /// Checks that every cross-reference between the manifests points to an existing entry,
+    /// and that the values the game relies on can be used.
+    ///
+    /// All problems are collected, rather than stopping at the first one.
+    pub fn validate(&self) -> Result<(), ManifestValidationError> {
+        let mut validator = Validator {
+            dangling_references: Vec::new(),
+            invalid_values: Vec::new(),
+            known_names: self.known_names(),
+        };
+
+        for (item_id, item_data) in self.item_manifest.iter() {
+            // Inventories are sized in stacks, so these items could never be stored or estimated
+            if item_data.stack_size == 0 {
+                let source = format!("item \"{}\"", self.item_manifest.name(item_id));
+                validator.reject(&source, "stack_size", "must be at least 1");
+            }
+        }
+
+        for (recipe_id, recipe_data) in self.recipe_manifest.iter() {
+            let source = format!("recipe \"{}\"", self.recipe_manifest.name(recipe_id));
+
+            for input in &recipe_data.inputs {
+                for &item_id in input.items() {
+                    validator.check(self.item_manifest, item_id, &source, "inputs", "item");
+                }
+            }
+
+            for fluid_input in &recipe_data.fluid_inputs {
+                validator.check(
+                    self.item_manifest,
+                    fluid_input.item_id(),
+                    &source,
+                    "fluid_inputs",
+                    "item",
+                );
+            }
+
+            for output in &recipe_data.outputs {
+                validator.check(
+                    self.item_manifest,
+                    output.item_id(),
+                    &source,
+                    "outputs",
+                    "item",
+                );
+            }
+
+            for fluid_output in &recipe_data.fluid_outputs {
+                validator.check(
+                    self.item_manifest,
+                    fluid_output.item_id(),
+                    &source,
+                    "fluid_outputs",
+                    "item",
+                );
+            }
+
+            for &prerequisite in &recipe_data.prerequisites {
+                validator.check(
+                    self.recipe_manifest,
+                    prerequisite,
+                    &source,
+                    "prerequisites",
+                    "recipe",
+                );
+            }
+
+            for &terrain_id in recipe_data.conditions.required_terrain.iter().flatten() {
+                validator.check(
+                    self.terrain_manifest,
+                    terrain_id,
+                    &source,
+                    "required_terrain",
+                    "terrain type",
+                );
+            }
+
+            let terrain_effect = recipe_data.terrain_effect.as_ref();
+            if let Some(terrain_id) = terrain_effect.and_then(|effect| effect.terrain_type) {
+                validator.check(
+                    self.terrain_manifest,
+                    terrain_id,
+                    &source,
+                    "terrain_effect",
+                    "terrain type",
+                );
+            }
+
+            if let Some(mined) = &recipe_data.mined_deposit {
+                validator.check(
+                    self.item_manifest,
+                    mined.item_id(),
+                    &source,
+                    "mined_deposit",
+                    "item",
+                );
+            }
+
+            if let Some(organism_id) = recipe_data.spawns {
+                validator.check_organism(organism_id, self, &source, "spawns");
+            }
+        }
+
+        for (structure_id, structure_data) in self.structure_manifest.iter() {
+            let source = format!(
+                "structure \"{}\"",
+                self.structure_manifest.name(structure_id)
+            );
+
+            if let Some(organism_variety) = &structure_data.organism_variety {
+                validator.check_organism_variety(organism_variety, self, &source);
+            }
+
+            if let Some(recipe_id) = structure_data.starting_recipe().recipe_id() {
+                validator.check(
+                    self.recipe_manifest,
+                    *recipe_id,
+                    &source,
+                    "starting_recipe",
+                    "recipe",
+                );
+            }
+
+            for item_id in structure_data.starting_reserved_for().items() {
+                validator.check(self.item_manifest, item_id, &source, "reserved_for", "item");
+            }
+
+            if let Some(upgrades_to) = structure_data.upgrades_to {
+                validator.check(
+                    self.structure_manifest,
+                    upgrades_to,
+                    &source,
+                    "upgrades_to",
+                    "structure",
+                );
+            }
+
+            for item_slot in structure_data.upgrade_cost.materials.iter() {
+                validator.check(
+                    self.item_manifest,
+                    item_slot.item_id(),
+                    &source,
+                    "upgrade_cost",
+                    "item",
+                );
+            }
+
+            let construction_strategy = &structure_data.construction_strategy;
+            if let Some(seedling) = construction_strategy.seedling {
+                validator.check(
+                    self.structure_manifest,
+                    seedling,
+                    &source,
+                    "seedling",
+                    "structure",
+                );
+            }
+
+            for item_slot in construction_strategy.materials.iter() {
+                validator.check(
+                    self.item_manifest,
+                    item_slot.item_id(),
+                    &source,
+                    "materials",
+                    "item",
+                );
+            }
+
+            for &terrain_id in &construction_strategy.allowed_terrain_types {
+                validator.check(
+                    self.terrain_manifest,
+                    terrain_id,
+                    &source,
+                    "allowed_terrain_types",
+                    "terrain type",
+                );
+            }
+
+            for &requirement in &construction_strategy.required_adjacency {
+                match requirement {
+                    AdjacencyRequirement::Terrain(terrain_id) => validator.check(
+                        self.terrain_manifest,
+                        terrain_id,
+                        &source,
+                        "required_adjacency",
+                        "terrain type",
+                    ),
+                    AdjacencyRequirement::Structure(structure_id) => validator.check(
+                        self.structure_manifest,
+                        structure_id,
+                        &source,
+                        "required_adjacency",
+                        "structure",
+                    ),
+                }
+            }
+        }
+
+        for (terrain_id, terrain_data) in self.terrain_manifest.iter() {
+            let source = format!(
+                "terrain type \"{}\"",
+                self.terrain_manifest.name(terrain_id)
+            );
+
+            for unit_id in terrain_data.walking_speed.unit_overrides() {
+                validator.check(
+                    self.unit_manifest,
+                    unit_id,
+                    &source,
+                    "walking_speed",
+                    "unit",
+                );
+            }
+        }
+
+        for (unit_id, unit_data) in self.unit_manifest.iter() {
+            let source = format!("unit \"{}\"", self.unit_manifest.name(unit_id));
+
+            validator.check_organism_variety(&unit_data.organism_variety, self, &source);
+            validator.check(
+                self.item_manifest,
+                unit_data.diet.item(),
+                &source,
+                "diet",
+                "item",
+            );
+        }
+
+        let mut dangling_references = validator.dangling_references;
+        let mut invalid_values = validator.invalid_values;
+        if dangling_references.is_empty() && invalid_values.is_empty() {
+            Ok(())
+        } else {
+            dangling_references.sort();
+            invalid_values.sort();
+            Err(ManifestValidationError {
+                dangling_references,
+                invalid_values,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::Duration;
+
+    use bevy::utils::HashMap;
+
+    use super::*;
+    use crate::{
+        items::{
+            item_manifest::ItemData,
+            recipe::{Recipe, RecipeData, RecipeInput},
+            ItemCount,
+        },
+        terrain::terrain_manifest::{TerrainData, WalkingSpeed},
+        units::unit_manifest::Unit,
+    };
+
+    #[test]
+    fn recipes_referencing_missing_items_are_reported() {
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("acacia_leaf", ItemData::new(10));
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "make_unobtainium",
+            RecipeData {
+                inputs: vec![RecipeInput::single(Id::from_name("acacia_leaf"), 1)],
+                outputs: vec![ItemCount::new(Id::from_name("unobtainium"), 1)],
+                craft_time: Duration::from_secs(1),
+                prerequisites: vec![Id::from_name("discover_unobtainium")],
+                ..Default::default()
+            },
+        );
+
+        // Only the localization knows the name of the missing item
+        let mut localization = Localization::default();
+        localization.insert(Localization::DEFAULT_LOCALE, "unobtainium", "Unobtainium");
+
+        let manifests = ManifestSet {
+            item_manifest: &item_manifest,
+            recipe_manifest: &recipe_manifest,
+            structure_manifest: &StructureManifest::new(),
+            terrain_manifest: &TerrainManifest::new(),
+            unit_manifest: &UnitManifest::new(),
+            localization: Some(&localization),
+        };
+
+        let error = manifests.validate().unwrap_err();
+        assert_eq!(
+            error.dangling_references,
+            vec![
+                DanglingReference {
+                    source: "recipe \"make_unobtainium\"".to_string(),
+                    field: "outputs",
+                    missing_kind: "item",
+                    missing_id: "unobtainium".to_string(),
+                },
+                DanglingReference {
+                    source: "recipe \"make_unobtainium\"".to_string(),
+                    field: "prerequisites",
+                    missing_kind: "recipe",
+                    // This name isn't used anywhere, so only the hash is known
+                    missing_id: format!("{:?}", Id::<Recipe>::from_name("discover_unobtainium")),
+                },
+            ]
+        );
+
+        let message = error.to_string();
+        assert!(message.starts_with("Found 2 reference(s)"));
+        assert!(message.contains(
+            "recipe \"make_unobtainium\" refers to a missing item in `outputs`: unobtainium"
+        ));
+
+        // Once the item and recipe exist, the manifests are consistent
+        item_manifest.insert("unobtainium", ItemData::new(1));
+        let mut prerequisite = recipe_manifest
+            .get(Id::from_name("make_unobtainium"))
+            .clone();
+        prerequisite.prerequisites.clear();
+        recipe_manifest.insert("discover_unobtainium", prerequisite);
+
+        let manifests = ManifestSet {
+            item_manifest: &item_manifest,
+            recipe_manifest: &recipe_manifest,
+            structure_manifest: &StructureManifest::new(),
+            terrain_manifest: &TerrainManifest::new(),
+            unit_manifest: &UnitManifest::new(),
+            localization: None,
+        };
+        assert_eq!(manifests.validate(), Ok(()));
+    }
+
+    #[test]
+    fn walking_speeds_for_missing_units_are_reported() {
+        let mut terrain_manifest = TerrainManifest::new();
+        terrain_manifest.insert(
+            "muddy",
+            TerrainData {
+                walking_speed: WalkingSpeed::PerUnit {
+                    default: 0.5,
+                    units: HashMap::from_iter([(Id::from_name("beetle"), 2.0)]),
+                },
+            },
+        );
+
+        let manifests = ManifestSet {
+            item_manifest: &ItemManifest::new(),
+            recipe_manifest: &RecipeManifest::new(),
+            structure_manifest: &StructureManifest::new(),
+            terrain_manifest: &terrain_manifest,
+            unit_manifest: &UnitManifest::new(),
+            localization: None,
+        };
+
+        let error = manifests.validate().unwrap_err();
+        assert_eq!(
+            error.dangling_references,
+            vec![DanglingReference {
+                source: "terrain type \"muddy\"".to_string(),
+                field: "walking_speed",
+                missing_kind: "unit",
+                missing_id: format!("{:?}", Id::<Unit>::from_name("beetle")),
+            }]
+        );
+    }
+
+    #[test]
+    fn items_that_cannot_be_stored_are_reported() {
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+        item_manifest.insert("broken_item", ItemData::new(0));
+
+        let manifests = ManifestSet {
+            item_manifest: &item_manifest,
+            recipe_manifest: &RecipeManifest::new(),
+            structure_manifest: &StructureManifest::new(),
+            terrain_manifest: &TerrainManifest::new(),
+            unit_manifest: &UnitManifest::new(),
+            localization: None,
+        };
+
+        let error = manifests.validate().unwrap_err();
+        assert!(error.dangling_references.is_empty());
+        assert_eq!(
+            error.invalid_values,
+            vec![InvalidValue {
+                source: "item \"broken_item\"".to_string(),
+                field: "stack_size",
+                problem: "must be at least 1",
+            }]
+        );
+        assert_eq!(
+            error.to_string(),
+            "Found 1 invalid value(s) in the manifests:\n  - item \"broken_item\" has an invalid `stack_size`: must be at least 1"
+        );
+    }
+}
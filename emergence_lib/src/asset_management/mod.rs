@@ -15,13 +15,17 @@ use std::{
     fmt::{Display, Formatter},
 };
 
-use self::manifest::plugin::DetectManifestCreationSet;
+use self::{
+    localization::LocalizationPlugin,
+    manifest::{plugin::DetectManifestCreationSet, validation::validate_manifests},
+};
 use bevy::{
     asset::LoadState,
     prelude::*,
     utils::{get_short_name, HashMap},
 };
 
+pub mod localization;
 pub mod manifest;
 
 /// Collects asset management systems and resources.
@@ -31,12 +35,14 @@ impl Plugin for AssetManagementPlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<AssetState>()
             .init_resource::<AssetsToLoad>()
+            .add_plugin(LocalizationPlugin)
             .add_system(check_manifests_loaded.run_if(in_state(AssetState::LoadManifests)))
             .add_system(check_assets_loaded.run_if(in_state(AssetState::LoadAssets)))
             // This is needed to ensure that the manifest resources are actually created in time for AssetState::Loading
             // BLOCKED: this can be removed in Bevy 0.11, as schedules will automatically flush the commands.
-            .add_system(
-                apply_system_buffers
+            .add_systems(
+                (apply_system_buffers, validate_manifests)
+                    .chain()
                     .after(DetectManifestCreationSet)
                     .in_schedule(OnExit(AssetState::LoadManifests)),
             );
@@ -0,0 +1,395 @@
+//! Sound effects and ambient audio.
+//!
+//! Sound effects are played at the tile where the event that caused them occurred,
+//! and get quieter as the camera moves further away.
+//!
+//! This module is only compiled with the `audio` feature, so headless and test builds don't need audio assets.
+//! Sounds whose files are missing or fail to load are skipped, rather than being queued up forever.
+
+use bevy::{prelude::*, utils::HashMap};
+use emergence_macros::IterableEnum;
+use noisy_bevy::simplex_noise_2d;
+
+use crate as emergence_lib;
+use crate::{
+    asset_management::manifest::Id,
+    enum_iter::IterableEnum,
+    organisms::energy::EnergyPool,
+    simulation::geometry::{MapGeometry, TilePos},
+    structures::{construction::ConstructionCompleted, crafting::CraftingState},
+    units::unit_manifest::Unit,
+};
+
+/// Plays sound effects in response to game events, along with ambient background audio.
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VolumeSettings>()
+            .init_resource::<SoundHandles>()
+            .add_event::<PlaySound>()
+            .add_system(start_ambient_audio)
+            .add_systems(
+                (
+                    structure_placement_sounds,
+                    crafting_sounds,
+                    footstep_sounds,
+                    play_sounds,
+                )
+                    .chain(),
+            )
+            .add_system(vary_ambient_volume);
+    }
+}
+
+/// Player preferences for how loud the game should be.
+///
+/// Each value is a multiplier between 0 and 1.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct VolumeSettings {
+    /// Scales the volume of all audio.
+    pub master: f32,
+    /// Scales the volume of sound effects.
+    pub sfx: f32,
+    /// Scales the volume of the ambient background audio.
+    pub ambient: f32,
+}
+
+impl Default for VolumeSettings {
+    fn default() -> Self {
+        VolumeSettings {
+            master: 1.,
+            sfx: 0.8,
+            ambient: 0.5,
+        }
+    }
+}
+
+impl VolumeSettings {
+    /// The volume that sound effects are played at, before any attenuation.
+    fn sfx_volume(&self) -> f32 {
+        (self.master * self.sfx).clamp(0., 1.)
+    }
+
+    /// The volume that the ambient audio is played at, before it is varied over time.
+    fn ambient_volume(&self) -> f32 {
+        (self.master * self.ambient).clamp(0., 1.)
+    }
+}
+
+/// The sound effects that can be played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, IterableEnum)]
+pub enum SoundEffect {
+    /// A structure has finished crafting its recipe.
+    CraftingTick,
+    /// A ghost has been built, placing a new structure.
+    StructurePlaced,
+    /// A unit has moved to a new tile.
+    Footstep,
+}
+
+impl SoundEffect {
+    /// The maximum number of copies of a single sound effect that can be played at once.
+    ///
+    /// Only the loudest copies are played, to avoid a cacophony when many events happen together.
+    const MAX_SIMULTANEOUS: usize = 3;
+
+    /// The path to the audio file for this sound effect.
+    const fn path(&self) -> &'static str {
+        match self {
+            SoundEffect::CraftingTick => "audio/crafting_tick.ogg",
+            SoundEffect::StructurePlaced => "audio/structure_placed.ogg",
+            SoundEffect::Footstep => "audio/footstep.ogg",
+        }
+    }
+
+    /// How loud this sound effect is relative to the others, between 0 and 1.
+    const fn base_volume(&self) -> f32 {
+        match self {
+            SoundEffect::CraftingTick => 0.5,
+            SoundEffect::StructurePlaced => 1.0,
+            SoundEffect::Footstep => 0.2,
+        }
+    }
+}
+
+/// An event requesting that a sound effect is played at the provided tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaySound {
+    /// The sound effect to play.
+    pub effect: SoundEffect,
+    /// The tile that the sound comes from.
+    pub tile_pos: TilePos,
+}
+
+/// The handles to the audio files used by the game.
+///
+/// These are not part of the usual asset loading process, as the game can be played without sound.
+#[derive(Resource, Debug)]
+struct SoundHandles {
+    /// The audio for each sound effect.
+    effects: HashMap<SoundEffect, Handle<AudioSource>>,
+    /// The looping background audio.
+    ambient: Handle<AudioSource>,
+    /// Controls the ambient audio once it has started playing.
+    ambient_sink: Option<Handle<AudioSink>>,
+}
+
+impl SoundHandles {
+    /// The path to the looping background audio.
+    const AMBIENT_PATH: &'static str = "audio/ambient.ogg";
+}
+
+impl FromWorld for SoundHandles {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+
+        let effects = SoundEffect::variants()
+            .map(|effect| (effect, asset_server.load(effect.path())))
+            .collect();
+
+        SoundHandles {
+            effects,
+            ambient: asset_server.load(SoundHandles::AMBIENT_PATH),
+            ambient_sink: None,
+        }
+    }
+}
+
+/// Sounds within this distance of the camera are played at full volume.
+const FULL_VOLUME_DISTANCE: f32 = 30.;
+
+/// Sounds beyond this distance from the camera are not played at all.
+const SILENT_DISTANCE: f32 = 150.;
+
+/// The fraction of its volume that a sound is played at, when it is `distance` away from the camera.
+///
+/// Sounds fade out linearly between [`FULL_VOLUME_DISTANCE`] and [`SILENT_DISTANCE`].
+fn attenuation(distance: f32) -> f32 {
+    let fade = (distance - FULL_VOLUME_DISTANCE) / (SILENT_DISTANCE - FULL_VOLUME_DISTANCE);
+    (1. - fade).clamp(0., 1.)
+}
+
+/// Picks which of the requested `sounds` should actually be played, and how loudly.
+///
+/// Inaudible sounds are skipped, and only the loudest [`SoundEffect::MAX_SIMULTANEOUS`] copies of each effect are kept.
+fn loudest_sounds(sounds: impl IntoIterator<Item = (SoundEffect, f32)>) -> Vec<(SoundEffect, f32)> {
+    let mut volumes_by_effect: HashMap<SoundEffect, Vec<f32>> = HashMap::default();
+    for (effect, volume) in sounds {
+        if volume > 0. {
+            volumes_by_effect.entry(effect).or_default().push(volume);
+        }
+    }
+
+    let mut loudest = Vec::new();
+    for (effect, mut volumes) in volumes_by_effect {
+        volumes.sort_by(|a, b| b.total_cmp(a));
+        loudest.extend(
+            volumes
+                .into_iter()
+                .take(SoundEffect::MAX_SIMULTANEOUS)
+                .map(|volume| (effect, volume)),
+        );
+    }
+
+    loudest
+}
+
+/// Plays a sound whenever a structure has been built.
+fn structure_placement_sounds(
+    mut construction_events: EventReader<ConstructionCompleted>,
+    mut sound_events: EventWriter<PlaySound>,
+) {
+    for event in construction_events.iter() {
+        sound_events.send(PlaySound {
+            effect: SoundEffect::StructurePlaced,
+            tile_pos: event.tile_pos,
+        });
+    }
+}
+
+/// Plays a sound whenever a structure finishes crafting.
+///
+/// Organisms craft constantly, so only buildings make noise.
+fn crafting_sounds(
+    crafting_query: Query<
+        (&TilePos, &CraftingState),
+        (Changed<CraftingState>, Without<EnergyPool>),
+    >,
+    mut sound_events: EventWriter<PlaySound>,
+) {
+    for (&tile_pos, crafting_state) in crafting_query.iter() {
+        if *crafting_state == CraftingState::RecipeComplete {
+            sound_events.send(PlaySound {
+                effect: SoundEffect::CraftingTick,
+                tile_pos,
+            });
+        }
+    }
+}
+
+/// Plays a footstep whenever a unit moves to a new tile.
+fn footstep_sounds(
+    unit_query: Query<&TilePos, (Changed<TilePos>, With<Id<Unit>>)>,
+    mut sound_events: EventWriter<PlaySound>,
+) {
+    for &tile_pos in unit_query.iter() {
+        sound_events.send(PlaySound {
+            effect: SoundEffect::Footstep,
+            tile_pos,
+        });
+    }
+}
+
+/// Plays the requested sound effects, attenuated by their distance from the camera.
+///
+/// Effects whose audio has not loaded are skipped.
+fn play_sounds(
+    mut sound_events: EventReader<PlaySound>,
+    camera_query: Query<&GlobalTransform, With<Camera3d>>,
+    map_geometry: Res<MapGeometry>,
+    settings: Res<VolumeSettings>,
+    handles: Res<SoundHandles>,
+    audio_sources: Res<Assets<AudioSource>>,
+    audio: Res<Audio>,
+) {
+    let Ok(camera_transform) = camera_query.get_single() else {
+        sound_events.clear();
+        return;
+    };
+
+    let sfx_volume = settings.sfx_volume();
+    let camera_pos = camera_transform.translation();
+
+    let requested = sound_events.iter().map(|event| {
+        let distance = camera_pos.distance(event.tile_pos.top_of_tile(&map_geometry));
+        let volume = event.effect.base_volume() * attenuation(distance) * sfx_volume;
+        (event.effect, volume)
+    });
+
+    for (effect, volume) in loudest_sounds(requested) {
+        let Some(handle) = handles.effects.get(&effect) else {
+            continue;
+        };
+
+        // Queued sounds wait until their audio has loaded, which never happens if the file is missing
+        if audio_sources.get(handle).is_some() {
+            audio.play_with_settings(
+                handle.clone_weak(),
+                PlaybackSettings::ONCE.with_volume(volume),
+            );
+        }
+    }
+}
+
+/// Starts looping the ambient background audio, once it has loaded.
+fn start_ambient_audio(
+    audio: Res<Audio>,
+    audio_sources: Res<Assets<AudioSource>>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    settings: Res<VolumeSettings>,
+    mut handles: ResMut<SoundHandles>,
+) {
+    if handles.ambient_sink.is_some() || audio_sources.get(&handles.ambient).is_none() {
+        return;
+    }
+
+    let weak_sink = audio.play_with_settings(
+        handles.ambient.clone(),
+        PlaybackSettings::LOOP.with_volume(settings.ambient_volume()),
+    );
+    handles.ambient_sink = Some(audio_sinks.get_handle(weak_sink));
+}
+
+/// How much the ambient audio swells and fades over time, as a fraction of its volume.
+const AMBIENT_VARIATION: f32 = 0.25;
+
+/// How quickly the ambient audio swells and fades.
+const AMBIENT_VARIATION_SPEED: f32 = 0.05;
+
+/// The volume of the ambient audio `elapsed_seconds` after the game started.
+///
+/// This wanders smoothly using noise, so that the background never feels like a fixed loop.
+fn ambient_volume_at(base_volume: f32, elapsed_seconds: f32) -> f32 {
+    let noise = simplex_noise_2d(Vec2::new(elapsed_seconds * AMBIENT_VARIATION_SPEED, 0.));
+    (base_volume * (1. - AMBIENT_VARIATION + AMBIENT_VARIATION * noise)).clamp(0., 1.)
+}
+
+/// Slowly varies the volume of the ambient audio, respecting the player's [`VolumeSettings`].
+fn vary_ambient_volume(
+    handles: Res<SoundHandles>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    settings: Res<VolumeSettings>,
+    time: Res<Time>,
+) {
+    let Some(sink) = handles
+        .ambient_sink
+        .as_ref()
+        .and_then(|handle| audio_sinks.get(handle))
+    else {
+        return;
+    };
+
+    sink.set_volume(ambient_volume_at(
+        settings.ambient_volume(),
+        time.elapsed_seconds(),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sounds_fade_with_distance() {
+        assert_eq!(attenuation(0.), 1.);
+        assert_eq!(attenuation(FULL_VOLUME_DISTANCE), 1.);
+        assert_eq!(attenuation(SILENT_DISTANCE), 0.);
+        assert_eq!(attenuation(SILENT_DISTANCE * 2.), 0.);
+
+        let halfway = (FULL_VOLUME_DISTANCE + SILENT_DISTANCE) / 2.;
+        assert!((attenuation(halfway) - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn only_the_loudest_sounds_are_played() {
+        let mut requested: Vec<(SoundEffect, f32)> = (1..=10)
+            .map(|i| (SoundEffect::Footstep, i as f32 / 10.))
+            .collect();
+        requested.push((SoundEffect::StructurePlaced, 0.1));
+        requested.push((SoundEffect::CraftingTick, 0.));
+
+        let played = loudest_sounds(requested);
+
+        let mut footsteps: Vec<f32> = played
+            .iter()
+            .filter(|(effect, _)| *effect == SoundEffect::Footstep)
+            .map(|(_, volume)| *volume)
+            .collect();
+        footsteps.sort_by(|a, b| b.total_cmp(a));
+        assert_eq!(footsteps, vec![1.0, 0.9, 0.8]);
+
+        assert!(played.contains(&(SoundEffect::StructurePlaced, 0.1)));
+        // Silent sounds are skipped entirely
+        assert!(!played
+            .iter()
+            .any(|(effect, _)| *effect == SoundEffect::CraftingTick));
+    }
+
+    #[test]
+    fn volume_settings_are_respected() {
+        let muted = VolumeSettings {
+            master: 0.,
+            ..Default::default()
+        };
+        assert_eq!(muted.sfx_volume(), 0.);
+        assert_eq!(ambient_volume_at(muted.ambient_volume(), 42.), 0.);
+
+        let settings = VolumeSettings::default();
+        for seconds in 0..100 {
+            let volume = ambient_volume_at(settings.ambient_volume(), seconds as f32);
+            assert!(volume <= settings.ambient_volume() * (1. + AMBIENT_VARIATION));
+            assert!(volume >= settings.ambient_volume() * (1. - 2. * AMBIENT_VARIATION));
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Hides the parts of the map that have not yet been explored.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_management::manifest::Id,
+    simulation::geometry::{MapGeometry, TilePos},
+    structures::structure_manifest::Structure,
+    terrain::terrain_manifest::Terrain,
+};
+
+/// Hides terrain and structures on tiles that are still covered by the fog of war.
+pub(super) fn hide_unexplored_tiles(
+    mut tile_query: Query<
+        (&TilePos, &mut Visibility),
+        Or<(With<Id<Terrain>>, With<Id<Structure>>)>,
+    >,
+    map_geometry: Res<MapGeometry>,
+) {
+    for (&tile_pos, mut visibility) in tile_query.iter_mut() {
+        let desired_visibility = match map_geometry.is_explored(tile_pos) {
+            true => Visibility::Inherited,
+            false => Visibility::Hidden,
+        };
+
+        // Avoid triggering change detection every frame
+        if *visibility != desired_visibility {
+            *visibility = desired_visibility;
+        }
+    }
+}
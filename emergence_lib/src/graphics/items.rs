@@ -0,0 +1,145 @@
+//! Graphics and animation code for items.
+
+use bevy::prelude::*;
+
+use crate::{structures::item_pile::ItemPile, units::item_interaction::ItemTransferStarted};
+
+/// The handles needed to display items as they are moved around.
+#[derive(Resource, Debug)]
+pub(super) struct ItemTransferHandles {
+    /// The mesh used for items in transit
+    mesh: Handle<Mesh>,
+    /// The material used for items in transit
+    material: Handle<StandardMaterial>,
+}
+
+impl ItemTransferHandles {
+    /// How far above the source and destination the item floats while being moved.
+    const HOVER_HEIGHT: f32 = 0.5;
+}
+
+impl FromWorld for ItemTransferHandles {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let mesh = meshes.add(Mesh::from(shape::Cube { size: 0.15 }));
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let material = materials.add(StandardMaterial {
+            base_color: Color::BEIGE,
+            ..Default::default()
+        });
+
+        ItemTransferHandles { mesh, material }
+    }
+}
+
+/// The handles needed to display piles of items lying on the ground.
+#[derive(Resource, Debug)]
+pub(super) struct ItemPileHandles {
+    /// The mesh used for item piles
+    mesh: Handle<Mesh>,
+    /// The material used for item piles
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for ItemPileHandles {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let mesh = meshes.add(Mesh::from(shape::Box::new(0.3, 0.15, 0.3)));
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let material = materials.add(StandardMaterial {
+            base_color: Color::BEIGE,
+            ..Default::default()
+        });
+
+        ItemPileHandles { mesh, material }
+    }
+}
+
+/// Gives newly spawned item piles something to look at.
+pub(super) fn display_item_piles(
+    item_pile_query: Query<Entity, Added<ItemPile>>,
+    handles: Res<ItemPileHandles>,
+    mut commands: Commands,
+) {
+    for item_pile_entity in item_pile_query.iter() {
+        commands
+            .entity(item_pile_entity)
+            .insert((handles.mesh.clone_weak(), handles.material.clone_weak()));
+    }
+}
+
+/// An item that is being visually moved from one entity to another.
+#[derive(Component, Debug)]
+pub(super) struct ItemTransfer {
+    /// The entity that the item is being taken from.
+    source: Entity,
+    /// The entity that the item is being given to.
+    destination: Entity,
+    /// Tracks how far along the transfer is.
+    timer: Timer,
+}
+
+/// Spawns a marker for each item that has started moving between entities.
+pub(super) fn spawn_item_transfers(
+    mut events: EventReader<ItemTransferStarted>,
+    transform_query: Query<&GlobalTransform>,
+    handles: Res<ItemTransferHandles>,
+    mut commands: Commands,
+) {
+    for event in events.iter() {
+        let Ok(source_transform) = transform_query.get(event.source) else {
+            continue;
+        };
+
+        let translation =
+            source_transform.translation() + Vec3::Y * ItemTransferHandles::HOVER_HEIGHT;
+
+        commands.spawn((
+            PbrBundle {
+                mesh: handles.mesh.clone_weak(),
+                material: handles.material.clone_weak(),
+                transform: Transform::from_translation(translation),
+                ..Default::default()
+            },
+            ItemTransfer {
+                source: event.source,
+                destination: event.destination,
+                timer: Timer::new(event.duration, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+/// Moves items in transit from their source to their destination.
+///
+/// The marker is removed once the transfer is complete, or if either end of the transfer no longer exists.
+pub(super) fn animate_item_transfers(
+    mut transfer_query: Query<(Entity, &mut ItemTransfer, &mut Transform)>,
+    transform_query: Query<&GlobalTransform, Without<ItemTransfer>>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (transfer_entity, mut transfer, mut transform) in transfer_query.iter_mut() {
+        transfer.timer.tick(time.delta());
+
+        let (Ok(source_transform), Ok(destination_transform)) = (
+            transform_query.get(transfer.source),
+            transform_query.get(transfer.destination),
+        ) else {
+            commands.entity(transfer_entity).despawn_recursive();
+            continue;
+        };
+
+        if transfer.timer.finished() {
+            commands.entity(transfer_entity).despawn_recursive();
+            continue;
+        }
+
+        let start = source_transform.translation();
+        let end = destination_transform.translation();
+        transform.translation =
+            start.lerp(end, transfer.timer.percent()) + Vec3::Y * ItemTransferHandles::HOVER_HEIGHT;
+    }
+}
@@ -26,7 +26,8 @@ impl Plugin for LightingPlugin {
         .insert_resource(DirectionalLightShadowMap { size: 8192 })
         // Need to wait for the player camera to spawn
         .add_startup_system(spawn_celestial_bodies.in_base_set(StartupSet::PostStartup))
-        .add_system(set_celestial_body_transform);
+        .add_system(set_celestial_body_transform)
+        .add_system(set_celestial_body_illuminance);
     }
 }
 
@@ -68,7 +69,7 @@ impl CelestialBody {
     }
 
     /// The starting settings for the sun
-    fn sun() -> CelestialBody {
+    pub(crate) fn sun() -> CelestialBody {
         CelestialBody {
             height: 2. * Height::MAX.into_world_pos(),
             hour_angle: -PI / 4.,
@@ -159,3 +160,14 @@ fn set_celestial_body_transform(
         transform.look_at(Vec3::ZERO, Vec3::Y);
     }
 }
+
+/// Dims and brightens celestial bodies as they move across the sky.
+///
+/// This matches the light used for rendering to the light available to organisms.
+fn set_celestial_body_illuminance(
+    mut query: Query<(&mut DirectionalLight, &CelestialBody), Changed<CelestialBody>>,
+) {
+    for (mut directional_light, celestial_body) in query.iter_mut() {
+        directional_light.illuminance = celestial_body.compute_light().0;
+    }
+}
@@ -5,10 +5,20 @@ use bevy::prelude::*;
 use crate::asset_management::AssetState;
 
 use self::{
-    atmosphere::AtmospherePlugin, lighting::LightingPlugin, structures::remove_ghostly_shadows,
+    atmosphere::AtmospherePlugin,
+    fog_of_war::hide_unexplored_tiles,
+    items::{
+        animate_item_transfers, display_item_piles, spawn_item_transfers, ItemPileHandles,
+        ItemTransferHandles,
+    },
+    lighting::LightingPlugin,
+    structures::remove_ghostly_shadows,
+    units::{display_corpses, CorpseHandles},
 };
 
 mod atmosphere;
+mod fog_of_war;
+mod items;
 pub(crate) mod lighting;
 pub(crate) mod palette;
 mod structures;
@@ -23,7 +33,23 @@ impl Plugin for GraphicsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(LightingPlugin)
             .add_plugin(AtmospherePlugin)
-            .add_system(units::display_held_item.run_if(in_state(AssetState::Ready)))
+            .init_resource::<ItemTransferHandles>()
+            .init_resource::<CorpseHandles>()
+            .init_resource::<ItemPileHandles>()
+            .add_systems(
+                (
+                    units::display_held_item,
+                    display_corpses,
+                    display_item_piles,
+                    hide_unexplored_tiles,
+                )
+                    .distributive_run_if(in_state(AssetState::Ready)),
+            )
+            .add_systems(
+                (spawn_item_transfers, animate_item_transfers)
+                    .chain()
+                    .distributive_run_if(in_state(AssetState::Ready)),
+            )
             // Run these after Update to avoid panics due to despawned entities
             .add_systems(
                 (inherit_materials, remove_ghostly_shadows).in_base_set(CoreSet::PostUpdate),
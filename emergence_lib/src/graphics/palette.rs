@@ -45,6 +45,8 @@ pub(crate) mod infovis {
     pub(crate) const GHOST_LIGHTNESS: f32 = 0.9;
     /// The alpha value of ghost-like materials.
     pub(crate) const GHOST_ALPHA: f32 = 0.7;
+    /// The alpha value of ghosts that have not received any of their construction materials.
+    pub(crate) const STARVED_GHOST_ALPHA: f32 = 0.2;
     /// The color used to tint ghosts
     pub(crate) const GHOST_COLOR: Color =
         Color::hsla(GHOST_HUE, GHOST_SATURATION, GHOST_LIGHTNESS, GHOST_ALPHA);
@@ -75,6 +77,11 @@ pub(crate) mod infovis {
         OVERLAY_ALPHA,
     );
 
+    /// The color used to indicate that few items have been moved at a tile.
+    pub(crate) const THROUGHPUT_COLOR_LOW: Color = Color::hsla(60., 0.8, 0.9, OVERLAY_ALPHA);
+    /// The color used to indicate that many items have been moved at a tile.
+    pub(crate) const THROUGHPUT_COLOR_HIGH: Color = Color::hsla(0., 0.9, 0.4, OVERLAY_ALPHA);
+
     impl SignalKind {
         /// The saturation used to indicate that the signal strength is low.
         const SIGNAL_SATURATION_LOW: f32 = 0.0;
@@ -97,6 +104,8 @@ pub(crate) mod infovis {
                 SignalKind::Pull => 20.,
                 // Yellow
                 SignalKind::Stores => 70.,
+                // Olive
+                SignalKind::Contained => 100.,
                 // Green
                 SignalKind::Push => 130.,
                 // Teal
@@ -105,8 +114,16 @@ pub(crate) mod infovis {
                 SignalKind::Work => 300.,
                 // Red
                 SignalKind::Demolish => 0.,
+                // Violet
+                SignalKind::Repair => 270.,
+                // Indigo
+                SignalKind::Upgrade => 245.,
+                // Brown
+                SignalKind::Terraform => 35.,
                 // Blue
                 SignalKind::Unit => 220.,
+                // Pink
+                SignalKind::Stuck => 330.,
             }
         }
 
@@ -146,6 +163,12 @@ pub(crate) mod infovis {
                     lightness: 0.9,
                     alpha: 1.0,
                 },
+                Goal::Explore { .. } => Color::Hsla {
+                    hue: 330.,
+                    saturation: 0.5,
+                    lightness: 0.8,
+                    alpha: 1.0,
+                },
                 Goal::Pickup(_) => Color::Hsla {
                     hue: SignalKind::Pull.hue(),
                     saturation: 0.7,
@@ -182,6 +205,24 @@ pub(crate) mod infovis {
                     lightness: 0.7,
                     alpha: 1.0,
                 },
+                Goal::Repair(_) => Color::Hsla {
+                    hue: SignalKind::Repair.hue(),
+                    saturation: 0.7,
+                    lightness: 0.7,
+                    alpha: 1.0,
+                },
+                Goal::Upgrade(_) => Color::Hsla {
+                    hue: SignalKind::Upgrade.hue(),
+                    saturation: 0.7,
+                    lightness: 0.7,
+                    alpha: 1.0,
+                },
+                Goal::Terraform => Color::Hsla {
+                    hue: SignalKind::Terraform.hue(),
+                    saturation: 0.7,
+                    lightness: 0.7,
+                    alpha: 1.0,
+                },
             }
         }
     }
@@ -193,6 +234,8 @@ pub(crate) mod infovis {
                 CraftingState::NeedsInput => Color::YELLOW,
                 CraftingState::InProgress { .. } => Color::GREEN,
                 CraftingState::FullAndBlocked => Color::YELLOW,
+                CraftingState::PopulationCapped => Color::YELLOW,
+                CraftingState::DepositExhausted => Color::RED,
                 CraftingState::RecipeComplete => Color::PINK,
                 CraftingState::Overproduction => Color::PURPLE,
                 CraftingState::NoRecipe => Color::WHITE,
@@ -260,3 +303,58 @@ pub(crate) mod ui {
         alpha: 1.0,
     };
 }
+
+/// Colors used by the minimap
+pub(crate) mod minimap {
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    use bevy::prelude::Color;
+
+    use crate::{
+        asset_management::manifest::Id, simulation::geometry::Height,
+        terrain::terrain_manifest::Terrain,
+    };
+
+    /// The color used for structures on the minimap
+    pub(crate) const STRUCTURE_COLOR: Color = Color::Hsla {
+        hue: 30.,
+        saturation: 0.9,
+        lightness: 0.6,
+        alpha: 1.0,
+    };
+
+    /// The color used for units on the minimap
+    pub(crate) const UNIT_COLOR: Color = Color::Hsla {
+        hue: 0.,
+        saturation: 0.,
+        lightness: 0.95,
+        alpha: 1.0,
+    };
+
+    /// The color used for tiles that are not part of the map
+    pub(crate) const EMPTY_COLOR: Color = Color::NONE;
+
+    /// The color used to display terrain of the type `terrain_id` at the provided `height` on the minimap.
+    ///
+    /// Terrain types are data-driven, so each type is assigned a stable hue based on its [`Id`].
+    /// Higher tiles are drawn lighter.
+    pub(crate) fn terrain_color(terrain_id: Id<Terrain>, height: Height) -> Color {
+        /// The lightness of tiles at [`Height::MIN`]
+        const LIGHTNESS_LOW: f32 = 0.2;
+        /// How much lighter each step of height makes the tile
+        const LIGHTNESS_PER_STEP: f32 = 0.08;
+        /// The maximum lightness of terrain, reserved so that unit dots remain visible
+        const LIGHTNESS_HIGH: f32 = 0.75;
+
+        let mut hasher = DefaultHasher::new();
+        terrain_id.hash(&mut hasher);
+        let hue = (hasher.finish() % 360) as f32;
+
+        let lightness = (LIGHTNESS_LOW + height.0 as f32 * LIGHTNESS_PER_STEP).min(LIGHTNESS_HIGH);
+
+        Color::hsla(hue, 0.4, lightness, 1.0)
+    }
+}
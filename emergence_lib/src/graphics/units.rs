@@ -4,6 +4,7 @@ use bevy::prelude::*;
 
 use crate::{
     asset_management::manifest::Id,
+    organisms::corpses::Corpse,
     units::{item_interaction::UnitInventory, unit_manifest::Unit},
 };
 
@@ -15,3 +16,40 @@ pub(super) fn display_held_item(
         // TODO: actually display this
     }
 }
+
+/// The handles needed to display the corpses of dead units.
+#[derive(Resource, Debug)]
+pub(super) struct CorpseHandles {
+    /// The mesh used for corpses
+    mesh: Handle<Mesh>,
+    /// The material used for corpses
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for CorpseHandles {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let mesh = meshes.add(Mesh::from(shape::Box::new(0.4, 0.1, 0.2)));
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let material = materials.add(StandardMaterial {
+            base_color: Color::DARK_GRAY,
+            ..Default::default()
+        });
+
+        CorpseHandles { mesh, material }
+    }
+}
+
+/// Gives newly spawned corpses something to look at.
+pub(super) fn display_corpses(
+    corpse_query: Query<Entity, Added<Corpse>>,
+    handles: Res<CorpseHandles>,
+    mut commands: Commands,
+) {
+    for corpse_entity in corpse_query.iter() {
+        commands
+            .entity(corpse_entity)
+            .insert((handles.mesh.clone_weak(), handles.material.clone_weak()));
+    }
+}
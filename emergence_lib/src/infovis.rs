@@ -12,11 +12,12 @@ use core::fmt::Display;
 use crate::{
     asset_management::{manifest::Id, AssetState},
     enum_iter::IterableEnum,
+    graphics::palette::infovis::{THROUGHPUT_COLOR_HIGH, THROUGHPUT_COLOR_LOW},
     player_interaction::{selection::ObjectInteraction, InteractionSystem},
     signals::{SignalKind, SignalStrength, SignalType, Signals},
-    simulation::geometry::TilePos,
+    simulation::{geometry::TilePos, SimulationSet},
     terrain::{terrain_assets::TerrainHandles, terrain_manifest::Terrain},
-    units::unit_manifest::Unit,
+    units::{item_interaction::ItemTransferred, unit_manifest::Unit},
 };
 
 /// Systems and reources for communicating the state of the world to the player.
@@ -27,6 +28,12 @@ impl Plugin for InfoVisPlugin {
         app.add_system(census)
             .init_resource::<Census>()
             .init_resource::<TileOverlay>()
+            .init_resource::<ItemThroughput>()
+            .add_system(
+                track_item_throughput
+                    .in_set(SimulationSet)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
             .add_systems(
                 (
                     set_overlay_material,
@@ -57,6 +64,58 @@ fn census(mut census: ResMut<Census>, unit_query: Query<(), With<Id<Unit>>>) {
     census.total_units = unit_query.iter().len();
 }
 
+/// Tracks how many items have recently been picked up or dropped off at each tile.
+///
+/// Older transfers are gradually forgotten, so this reflects the current flow of items.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct ItemThroughput {
+    /// The decayed number of item transfers at each tile.
+    ///
+    /// Tiles with negligible throughput are not stored.
+    transfers: HashMap<TilePos, f32>,
+}
+
+impl ItemThroughput {
+    /// The number of seconds of simulated time that it takes for old transfers to be half-forgotten.
+    const HALF_LIFE: f32 = 10.;
+
+    /// Throughput below this value is discarded.
+    const MIN_THROUGHPUT: f32 = 0.01;
+
+    /// Records that an item was moved at `tile_pos`.
+    pub(crate) fn record(&mut self, tile_pos: TilePos) {
+        *self.transfers.entry(tile_pos).or_default() += 1.;
+    }
+
+    /// Fades out the transfers recorded so far, after `delta_secs` seconds have elapsed.
+    pub(crate) fn decay(&mut self, delta_secs: f32) {
+        let decay_factor = 0.5f32.powf(delta_secs / Self::HALF_LIFE);
+
+        self.transfers.retain(|_, throughput| {
+            *throughput *= decay_factor;
+            *throughput >= Self::MIN_THROUGHPUT
+        });
+    }
+
+    /// The recent number of item transfers at `tile_pos`.
+    pub(crate) fn get(&self, tile_pos: TilePos) -> f32 {
+        self.transfers.get(&tile_pos).copied().unwrap_or_default()
+    }
+}
+
+/// Records each item transfer in the [`ItemThroughput`].
+fn track_item_throughput(
+    mut item_transferred_events: EventReader<ItemTransferred>,
+    mut item_throughput: ResMut<ItemThroughput>,
+    fixed_time: Res<FixedTime>,
+) {
+    item_throughput.decay(fixed_time.period.as_secs_f32());
+
+    for event in item_transferred_events.iter() {
+        item_throughput.record(event.tile_pos);
+    }
+}
+
 /// Controls the display of the tile overlay.
 #[derive(Resource, Debug)]
 pub(crate) struct TileOverlay {
@@ -69,6 +128,10 @@ pub(crate) struct TileOverlay {
     color_ramps: HashMap<SignalKind, Vec<Handle<StandardMaterial>>>,
     /// The images to be used to display the gradient in order to create a legend.
     legends: HashMap<SignalKind, Handle<Image>>,
+    /// The materials used to visualize the [`ItemThroughput`].
+    throughput_color_ramp: Vec<Handle<StandardMaterial>>,
+    /// The image used to display the legend of the [`ItemThroughput`].
+    throughput_legend: Handle<Image>,
 }
 
 /// The type of information that is being visualized by the overlay.
@@ -81,6 +144,8 @@ pub(crate) enum OverlayType {
     Single(SignalType),
     /// The strongest signal in each cell is being visualized.
     StrongestSignal,
+    /// The recent [`ItemThroughput`] of each cell is being visualized.
+    ItemThroughput,
 }
 
 impl OverlayType {
@@ -100,86 +165,26 @@ impl From<Option<SignalType>> for OverlayType {
 }
 
 impl FromWorld for TileOverlay {
-    #[allow(clippy::identity_op)]
     fn from_world(world: &mut World) -> Self {
         let mut color_ramps = HashMap::new();
         let mut legends = HashMap::new();
 
         for kind in SignalKind::variants() {
-            let mut colors = Vec::with_capacity(Self::N_COLORS);
-            for i in 0..Self::N_COLORS {
-                // Linearly interpolate the colors in the color ramp between SIGNAL_OVERLAY_LOW and SIGNAL_OVERLAY_HIGH
-                // Make sure to use HSLA colorspace to avoid weird artifacts
-                let t = i as f32 / (Self::N_COLORS - 1) as f32;
-                let Color::Hsla { hue: low_hue, saturation: low_saturation, lightness: low_lightness, alpha: low_alpha } = kind.color_low() else {
-                 panic!("Expected HSLA color for `color_low`");
-            };
-
-                let Color::Hsla { hue: high_hue, saturation: high_saturation, lightness: high_lightness, alpha: high_alpha } = kind.color_high() else {
-                panic!("Expected HSLA color for `color_high`");
-            };
-
-                let hue = low_hue * (1.0 - t) + high_hue * t;
-                let saturation = low_saturation * (1.0 - t) + high_saturation * t;
-                let lightness = low_lightness * (1.0 - t) + high_lightness * t;
-                let alpha = low_alpha * (1.0 - t) + high_alpha * t;
-
-                let color = Color::hsla(hue, saturation, lightness, alpha);
-                colors.push(color);
-            }
-            let mut color_ramp = Vec::with_capacity(Self::N_COLORS);
-            let mut material_assets = world.resource_mut::<Assets<StandardMaterial>>();
-
-            for base_color in colors.iter().cloned() {
-                color_ramp.push(material_assets.add(StandardMaterial {
-                    base_color,
-                    unlit: true,
-                    alpha_mode: AlphaMode::Blend,
-                    ..Default::default()
-                }));
-            }
-            color_ramp.shrink_to_fit();
+            let (color_ramp, legend_image_handle) =
+                TileOverlay::build_color_ramp(world, kind.color_low(), kind.color_high());
             color_ramps.insert(kind, color_ramp);
-
-            // Create the legend image
-            let size = Extent3d {
-                width: Self::LEGEND_WIDTH,
-                height: Self::N_COLORS as u32,
-                depth_or_array_layers: 1,
-            };
-            let dimension = TextureDimension::D2;
-            let format = TextureFormat::Rgba8UnormSrgb;
-
-            // Initialize the legend data with all zeros
-            let mut data = vec![0; size.width as usize * size.height as usize * 4];
-
-            // Set the color of each pixel to the corresponding color in the color ramp
-            // Each line is a row of pixels of the same color, corresponding to a value in the color ramp
-            // Each pixel is represented by 4 bytes, in RGBA order
-            // We need to reverse the order of the rows, because the image is stored in memory from top to bottom
-            // and we want the lowest value to be at the bottom of the image.
-            for (row, color) in colors.into_iter().rev().enumerate() {
-                let row_start = row * size.width as usize * 4;
-                for column in 0..size.width as usize {
-                    let pixel_start = row_start + column * 4;
-                    data[pixel_start + 0] = (color.r() * 255.0) as u8;
-                    data[pixel_start + 1] = (color.g() * 255.0) as u8;
-                    data[pixel_start + 2] = (color.b() * 255.0) as u8;
-                    data[pixel_start + 3] = (color.a() * 255.0) as u8;
-                }
-            }
-
-            let legend_image = Image::new(size, dimension, data, format);
-
-            let mut image_assets = world.resource_mut::<Assets<Image>>();
-            let legend_image_handle = image_assets.add(legend_image);
             legends.insert(kind, legend_image_handle);
         }
 
+        let (throughput_color_ramp, throughput_legend) =
+            TileOverlay::build_color_ramp(world, THROUGHPUT_COLOR_LOW, THROUGHPUT_COLOR_HIGH);
+
         Self {
             overlay_type: OverlayType::None,
             color_ramps,
             legends,
+            throughput_color_ramp,
+            throughput_legend,
         }
     }
 }
@@ -194,6 +199,113 @@ impl TileOverlay {
     /// The width of the legend image.
     pub(crate) const LEGEND_WIDTH: u32 = 32;
 
+    /// The maximum displayed value for item throughput.
+    const MAX_THROUGHPUT: f32 = 50.;
+
+    /// Creates the materials for a color ramp between `color_low` and `color_high`, and an image of it to use as a legend.
+    #[allow(clippy::identity_op)]
+    fn build_color_ramp(
+        world: &mut World,
+        color_low: Color,
+        color_high: Color,
+    ) -> (Vec<Handle<StandardMaterial>>, Handle<Image>) {
+        let mut colors = Vec::with_capacity(Self::N_COLORS);
+        for i in 0..Self::N_COLORS {
+            // Linearly interpolate the colors in the color ramp between the low and high colors
+            // Make sure to use HSLA colorspace to avoid weird artifacts
+            let t = i as f32 / (Self::N_COLORS - 1) as f32;
+            let Color::Hsla {
+                hue: low_hue,
+                saturation: low_saturation,
+                lightness: low_lightness,
+                alpha: low_alpha,
+            } = color_low
+            else {
+                panic!("Expected HSLA color for `color_low`");
+            };
+
+            let Color::Hsla {
+                hue: high_hue,
+                saturation: high_saturation,
+                lightness: high_lightness,
+                alpha: high_alpha,
+            } = color_high
+            else {
+                panic!("Expected HSLA color for `color_high`");
+            };
+
+            let hue = low_hue * (1.0 - t) + high_hue * t;
+            let saturation = low_saturation * (1.0 - t) + high_saturation * t;
+            let lightness = low_lightness * (1.0 - t) + high_lightness * t;
+            let alpha = low_alpha * (1.0 - t) + high_alpha * t;
+
+            let color = Color::hsla(hue, saturation, lightness, alpha);
+            colors.push(color);
+        }
+        let mut color_ramp = Vec::with_capacity(Self::N_COLORS);
+        let mut material_assets = world.resource_mut::<Assets<StandardMaterial>>();
+
+        for base_color in colors.iter().cloned() {
+            color_ramp.push(material_assets.add(StandardMaterial {
+                base_color,
+                unlit: true,
+                alpha_mode: AlphaMode::Blend,
+                ..Default::default()
+            }));
+        }
+        color_ramp.shrink_to_fit();
+
+        // Create the legend image
+        let size = Extent3d {
+            width: Self::LEGEND_WIDTH,
+            height: Self::N_COLORS as u32,
+            depth_or_array_layers: 1,
+        };
+        let dimension = TextureDimension::D2;
+        let format = TextureFormat::Rgba8UnormSrgb;
+
+        // Initialize the legend data with all zeros
+        let mut data = vec![0; size.width as usize * size.height as usize * 4];
+
+        // Set the color of each pixel to the corresponding color in the color ramp
+        // Each line is a row of pixels of the same color, corresponding to a value in the color ramp
+        // Each pixel is represented by 4 bytes, in RGBA order
+        // We need to reverse the order of the rows, because the image is stored in memory from top to bottom
+        // and we want the lowest value to be at the bottom of the image.
+        for (row, color) in colors.into_iter().rev().enumerate() {
+            let row_start = row * size.width as usize * 4;
+            for column in 0..size.width as usize {
+                let pixel_start = row_start + column * 4;
+                data[pixel_start + 0] = (color.r() * 255.0) as u8;
+                data[pixel_start + 1] = (color.g() * 255.0) as u8;
+                data[pixel_start + 2] = (color.b() * 255.0) as u8;
+                data[pixel_start + 3] = (color.a() * 255.0) as u8;
+            }
+        }
+
+        let legend_image = Image::new(size, dimension, data, format);
+
+        let mut image_assets = world.resource_mut::<Assets<Image>>();
+        let legend_image_handle = image_assets.add(legend_image);
+
+        (color_ramp, legend_image_handle)
+    }
+
+    /// Maps a `value` between zero and `max_value` onto an index in a color ramp.
+    ///
+    /// The scale is logarithmic, so that small nuances are still pretty visible.
+    fn color_index(value: f32, max_value: f32) -> usize {
+        // By adding 1 to the value, we avoid taking the log of 0
+        // This produces a value in the range [0, 1] for all values that we care about.
+        let normalized_value = value.ln_1p() / max_value.ln_1p();
+
+        // Now that the value is normalized, we can scale it to the number of colors in the ramp
+        // Which should give us a nice distribution of colors that uses the entire range.
+        let color_index: usize = (normalized_value * (Self::N_COLORS as f32)) as usize;
+        // Avoid indexing out of bounds by clamping to the maximum value in the case of extremely large values
+        color_index.min(Self::N_COLORS - 1)
+    }
+
     /// Gets the material that should be used to visualize the given signal strength, if any.
     ///
     /// If this is `None`, then the signal strength is too weak to be visualized and the tile should be invisible.
@@ -209,25 +321,31 @@ impl TileOverlay {
 
         // At MAX_SIGNAL_STRENGTH, we want to fetch the last color in the ramp.
         // At 0, we want to fetch the first color in the ramp.
+        let color_index = Self::color_index(signal_strength.value(), Self::MAX_SIGNAL_STRENGTH);
+        Some(self.color_ramps[&signal_kind][color_index].clone_weak())
+    }
 
-        // The scale is logarithmic, so that small nuances are still pretty visible
-        // By adding 1 to the signal strength, we avoid taking the log of 0
-        // This produces a value in the range [0, 1] for all signal strengths that we care about.
-        let normalized_strength =
-            signal_strength.value().ln_1p() / Self::MAX_SIGNAL_STRENGTH.ln_1p();
+    /// Gets the material that should be used to visualize the given item `throughput`, if any.
+    ///
+    /// If this is `None`, then no items have recently been moved and the tile should be invisible.
+    fn get_throughput_material(&self, throughput: f32) -> Option<Handle<StandardMaterial>> {
+        if throughput < ItemThroughput::MIN_THROUGHPUT {
+            return None;
+        }
 
-        // Now that strength is normalized, we can scale it to the number of colors in the ramp
-        // Which should give us a nice distribution of colors that uses the entire range.
-        let color_index: usize = (normalized_strength * (Self::N_COLORS as f32)) as usize;
-        // Avoid indexing out of bounds by clamping to the maximum value in the case of extremely strong signals
-        let color_index = color_index.min(Self::N_COLORS - 1);
-        Some(self.color_ramps[&signal_kind][color_index].clone_weak())
+        let color_index = Self::color_index(throughput, Self::MAX_THROUGHPUT);
+        Some(self.throughput_color_ramp[color_index].clone_weak())
     }
 
     /// Gets the handle to the image that should be used to display the legend.
     pub(crate) fn legend_image_handle(&self, signal_kind: SignalKind) -> Handle<Image> {
         self.legends[&signal_kind].clone_weak()
     }
+
+    /// Gets the handle to the image that should be used to display the legend of the [`ItemThroughput`].
+    pub(crate) fn throughput_legend_handle(&self) -> Handle<Image> {
+        self.throughput_legend.clone_weak()
+    }
 }
 
 /// Sets the material for the currently visualized map overlay.
@@ -235,6 +353,7 @@ fn set_overlay_material(
     terrain_query: Query<(&TilePos, &Children), With<Id<Terrain>>>,
     mut overlay_query: Query<(&mut Handle<StandardMaterial>, &mut Visibility)>,
     signals: Res<Signals>,
+    item_throughput: Res<ItemThroughput>,
     tile_overlay: Res<TileOverlay>,
 ) {
     if tile_overlay.overlay_type == OverlayType::None {
@@ -252,6 +371,19 @@ fn set_overlay_material(
                 OverlayType::None => None,
                 OverlayType::Single(signal_type) => Some(signal_type),
                 OverlayType::StrongestSignal => signals.strongest_goal_signal_at_position(tile_pos),
+                OverlayType::ItemThroughput => {
+                    let throughput = item_throughput.get(tile_pos);
+                    match tile_overlay.get_throughput_material(throughput) {
+                        Some(material) => {
+                            *overlay_visibility = Visibility::Visible;
+                            *overlay_material = material;
+                        }
+                        None => {
+                            *overlay_visibility = Visibility::Hidden;
+                        }
+                    }
+                    None
+                }
             };
 
             if let Some(signal_type) = maybe_signal_type {
@@ -307,3 +439,31 @@ fn display_tile_overlay(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn item_throughput_fades_over_time() {
+        let busy_tile = TilePos::new(1, 0);
+        let quiet_tile = TilePos::new(-1, 0);
+
+        let mut item_throughput = ItemThroughput::default();
+        for _ in 0..4 {
+            item_throughput.record(busy_tile);
+        }
+        item_throughput.record(quiet_tile);
+        assert_eq!(item_throughput.get(busy_tile), 4.);
+        assert_eq!(item_throughput.get(quiet_tile), 1.);
+
+        item_throughput.decay(ItemThroughput::HALF_LIFE);
+        assert!((item_throughput.get(busy_tile) - 2.).abs() < 1e-4);
+        assert!((item_throughput.get(quiet_tile) - 0.5).abs() < 1e-4);
+
+        // Eventually, the transfers are forgotten entirely
+        item_throughput.decay(ItemThroughput::HALF_LIFE * 20.);
+        assert_eq!(item_throughput.get(busy_tile), 0.);
+        assert!(item_throughput.transfers.is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+//! Tools for analyzing the balance of the in-game economy.
+//!
+//! These are pure functions over the manifests, intended for tests and modding tools rather than gameplay.
+
+use bevy::utils::HashMap;
+
+use crate::asset_management::manifest::Id;
+
+use super::{
+    item_manifest::Item,
+    recipe::{Recipe, RecipeInput, RecipeManifest},
+    ItemCount,
+};
+
+/// The value of a single one of each item.
+pub type ItemValues = HashMap<Id<Item>, f32>;
+
+/// The production graph contains a loop, so item values could not be derived.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductionCycle {
+    /// The items that make up the loop, in the order in which they depend on each other.
+    ///
+    /// The first item is made (possibly indirectly) from the last item.
+    pub items: Vec<Id<Item>>,
+}
+
+/// The total value of the provided `item_counts`.
+///
+/// Items that are missing from `item_values` are treated as worthless.
+fn total_value(item_counts: &[ItemCount], item_values: &ItemValues) -> f32 {
+    item_counts
+        .iter()
+        .map(|item_count| {
+            let value = item_values
+                .get(&item_count.item_id())
+                .copied()
+                .unwrap_or_default();
+            value * item_count.count() as f32
+        })
+        .sum()
+}
+
+/// The total value of the provided recipe `inputs`.
+///
+/// Inputs with several alternatives are valued using their cheapest alternative.
+/// Items that are missing from `item_values` are treated as worthless.
+fn total_input_value(inputs: &[RecipeInput], item_values: &ItemValues) -> f32 {
+    inputs
+        .iter()
+        .map(|input| {
+            let cheapest = input
+                .items()
+                .iter()
+                .map(|item_id| item_values.get(item_id).copied().unwrap_or_default())
+                .reduce(f32::min)
+                .unwrap_or_default();
+            cheapest * input.count() as f32
+        })
+        .sum()
+}
+
+impl RecipeManifest {
+    /// The value of the outputs of `recipe_id`, minus the value of its inputs.
+    ///
+    /// Items that are missing from `item_values` are treated as worthless.
+    pub fn net_value(&self, recipe_id: Id<Recipe>, item_values: &ItemValues) -> f32 {
+        let recipe_data = self.get(recipe_id);
+
+        total_value(&recipe_data.outputs, item_values)
+            - total_input_value(&recipe_data.inputs, item_values)
+    }
+
+    /// Computes the value of every item that can be crafted, starting from the `base_values` of raw resources.
+    ///
+    /// Crafted items are worth the total value of the recipe's inputs, split evenly across each of its outputs.
+    /// When an item can be made by several recipes, the cheapest recipe is used.
+    /// Items in `base_values` always keep their base value,
+    /// and items that are neither valued nor craftable are worthless.
+    ///
+    /// Ways of making an item that loop back on themselves are ignored in favor of the cheapest acyclic one.
+    /// Returns a [`ProductionCycle`] if an item can only be crafted (indirectly) from itself,
+    /// unless one of the items in that loop has a base value.
+    pub fn derive_item_values(
+        &self,
+        base_values: &ItemValues,
+    ) -> Result<ItemValues, ProductionCycle> {
+        let mut producers: HashMap<Id<Item>, Vec<Id<Recipe>>> = HashMap::default();
+        for (recipe_id, recipe_data) in self.iter() {
+            for output in &recipe_data.outputs {
+                producers
+                    .entry(output.item_id())
+                    .or_default()
+                    .push(recipe_id);
+            }
+        }
+
+        // Sort the items so that the reported cycle is deterministic
+        let mut items: Vec<Id<Item>> = producers.keys().copied().collect();
+        items.sort();
+
+        let mut item_values = base_values.clone();
+        let mut in_progress = Vec::new();
+        for item_id in items {
+            let derived =
+                self.derive_item_value(item_id, &producers, &mut item_values, &mut in_progress)?;
+            // Nothing is further up the chain at the top level, so this value is final
+            item_values.insert(item_id, derived.value);
+        }
+
+        Ok(item_values)
+    }
+
+    /// Computes and stores the value of `item_id`, first computing the value of any items it is crafted from.
+    ///
+    /// `in_progress` is the chain of items currently being valued, and is used to detect cycles.
+    /// Input alternatives and recipes that would loop back to an item in `in_progress` are skipped,
+    /// so the cheapest acyclic way to make the item sets its value.
+    /// A [`ProductionCycle`] is only returned if every way of making the item loops back on itself.
+    fn derive_item_value(
+        &self,
+        item_id: Id<Item>,
+        producers: &HashMap<Id<Item>, Vec<Id<Recipe>>>,
+        item_values: &mut ItemValues,
+        in_progress: &mut Vec<Id<Item>>,
+    ) -> Result<DerivedValue, ProductionCycle> {
+        if let Some(&value) = item_values.get(&item_id) {
+            return Ok(DerivedValue {
+                value,
+                provisional: false,
+            });
+        }
+
+        if let Some(position) = in_progress.iter().position(|&id| id == item_id) {
+            return Err(ProductionCycle {
+                items: in_progress[position..].to_vec(),
+            });
+        }
+
+        let Some(recipe_ids) = producers.get(&item_id) else {
+            return Ok(DerivedValue {
+                value: 0.,
+                provisional: false,
+            });
+        };
+
+        in_progress.push(item_id);
+        let mut cheapest: Option<f32> = None;
+        let mut provisional = false;
+        let mut first_cycle: Option<ProductionCycle> = None;
+        'recipes: for &recipe_id in recipe_ids {
+            let recipe_data = self.get(recipe_id);
+
+            let mut input_value = 0.;
+            for input in &recipe_data.inputs {
+                // Any of the alternatives will do, so the cheapest one sets the price
+                let mut cheapest_alternative: Option<f32> = None;
+                for &item_id in input.items() {
+                    match self.derive_item_value(item_id, producers, item_values, in_progress) {
+                        Ok(derived) => {
+                            provisional |= derived.provisional;
+                            cheapest_alternative = Some(
+                                cheapest_alternative
+                                    .map_or(derived.value, |cheapest| cheapest.min(derived.value)),
+                            );
+                        }
+                        Err(cycle) => {
+                            // This alternative is only worth something relative to the chain being valued
+                            provisional = true;
+                            first_cycle.get_or_insert(cycle);
+                        }
+                    }
+                }
+
+                match cheapest_alternative {
+                    Some(value) => input_value += value * input.count() as f32,
+                    // Every alternative loops back on itself, so this recipe can't be used
+                    None if !input.items().is_empty() => continue 'recipes,
+                    None => (),
+                }
+            }
+
+            let n_outputs: usize = recipe_data.outputs.iter().map(ItemCount::count).sum();
+            let value = input_value / n_outputs as f32;
+            cheapest = Some(cheapest.map_or(value, |cheapest| cheapest.min(value)));
+        }
+        in_progress.pop();
+
+        let Some(value) = cheapest else {
+            return Err(
+                first_cycle.expect("recipes are only skipped when they loop back on themselves")
+            );
+        };
+
+        // Values that skipped a loop through an item further up the chain may be too high once that item is valued
+        if !provisional {
+            item_values.insert(item_id, value);
+        }
+        Ok(DerivedValue { value, provisional })
+    }
+}
+
+/// The value of an item, as computed by [`RecipeManifest::derive_item_value`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DerivedValue {
+    /// The value of a single one of the item.
+    value: f32,
+    /// Were any ways of making the item skipped because they looped back to an item that was still being valued?
+    ///
+    /// Provisional values are not stored, and are recomputed when next needed.
+    provisional: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::Duration;
+
+    use super::*;
+    use crate::items::recipe::RecipeData;
+
+    /// A recipe that turns `inputs` into `outputs`.
+    fn recipe(inputs: &[(&str, usize)], outputs: &[(&str, usize)]) -> RecipeData {
+        let item_counts = |items: &[(&str, usize)]| {
+            items
+                .iter()
+                .map(|&(name, count)| ItemCount::new(Id::from_name(name), count))
+                .collect()
+        };
+
+        RecipeData {
+            inputs: inputs
+                .iter()
+                .map(|&(name, count)| RecipeInput::single(Id::from_name(name), count))
+                .collect(),
+            outputs: item_counts(outputs),
+            craft_time: Duration::from_secs(1),
+            worker_scaling: Default::default(),
+            seasonal_profile: Default::default(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn net_value_is_outputs_minus_inputs() {
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert("press", recipe(&[("leaf", 3)], &[("paste", 2)]));
+
+        let item_values =
+            ItemValues::from_iter([(Id::from_name("leaf"), 1.), (Id::from_name("paste"), 4.)]);
+
+        assert_eq!(
+            recipe_manifest.net_value(Id::from_name("press"), &item_values),
+            5.
+        );
+    }
+
+    #[test]
+    fn item_values_accumulate_through_the_production_graph() {
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert("press", recipe(&[("leaf", 3)], &[("paste", 2)]));
+        recipe_manifest.insert(
+            "bake",
+            recipe(&[("paste", 2), ("water", 1)], &[("cake", 1)]),
+        );
+        // A more expensive way to make paste, which should be ignored
+        recipe_manifest.insert("grind", recipe(&[("leaf", 10)], &[("paste", 1)]));
+
+        let base_values =
+            ItemValues::from_iter([(Id::from_name("leaf"), 2.), (Id::from_name("water"), 1.)]);
+        let item_values = recipe_manifest.derive_item_values(&base_values).unwrap();
+
+        assert_eq!(item_values[&Id::from_name("paste")], 3.);
+        assert_eq!(item_values[&Id::from_name("cake")], 7.);
+        assert_eq!(item_values[&Id::from_name("leaf")], 2.);
+    }
+
+    #[test]
+    fn production_cycles_are_reported() {
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert("sprout", recipe(&[("seed", 1)], &[("plant", 1)]));
+        recipe_manifest.insert("harvest", recipe(&[("plant", 1)], &[("seed", 2)]));
+
+        let result = recipe_manifest.derive_item_values(&ItemValues::default());
+        let cycle = result.unwrap_err();
+        assert_eq!(cycle.items.len(), 2);
+        assert!(cycle.items.contains(&Id::from_name("seed")));
+        assert!(cycle.items.contains(&Id::from_name("plant")));
+
+        // Giving any item in the loop a base value breaks the cycle
+        let base_values = ItemValues::from_iter([(Id::from_name("seed"), 1.)]);
+        let item_values = recipe_manifest.derive_item_values(&base_values).unwrap();
+        assert_eq!(item_values[&Id::from_name("plant")], 1.);
+    }
+
+    #[test]
+    fn cyclic_alternatives_are_skipped_in_favor_of_acyclic_ones() {
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert("sprout", recipe(&[("seed", 1)], &[("plant", 1)]));
+        // Seeds can be harvested from plants, or gathered from the wild
+        recipe_manifest.insert("harvest", recipe(&[("plant", 1)], &[("seed", 2)]));
+        recipe_manifest.insert("gather", recipe(&[("dirt", 3)], &[("seed", 1)]));
+        // Compost accepts either plants or dirt
+        recipe_manifest.insert(
+            "compost",
+            RecipeData {
+                inputs: vec![RecipeInput::new(
+                    vec![Id::from_name("plant"), Id::from_name("dirt")],
+                    1,
+                )],
+                ..recipe(&[], &[("soil", 1)])
+            },
+        );
+
+        let base_values = ItemValues::from_iter([(Id::from_name("dirt"), 1.)]);
+        let item_values = recipe_manifest.derive_item_values(&base_values).unwrap();
+
+        assert_eq!(item_values[&Id::from_name("seed")], 3.);
+        assert_eq!(item_values[&Id::from_name("plant")], 3.);
+        assert_eq!(item_values[&Id::from_name("soil")], 1.);
+    }
+}
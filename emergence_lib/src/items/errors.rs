@@ -1,6 +1,6 @@
 //! Errors related to items and inventories.
 
-use super::ItemCount;
+use super::{fluid::FluidAmount, ItemCount};
 
 /// Failed to add items to an inventory.
 #[derive(Debug, PartialEq, Eq)]
@@ -40,3 +40,17 @@ pub struct ItemTransferError {
     /// Did this fail because the output inventory of the source was empty?
     pub empty_source: bool,
 }
+
+/// Failed to add a fluid to a [`FluidInventory`](super::fluid::FluidInventory).
+#[derive(Debug, PartialEq)]
+pub struct AddFluidError {
+    /// The amount of fluid that exceeded the capacity.
+    pub excess: FluidAmount,
+}
+
+/// Failed to remove fluids from a [`FluidInventory`](super::fluid::FluidInventory).
+#[derive(Debug, PartialEq)]
+pub struct RemoveFluidsError {
+    /// The amount of each fluid that was missing from the inventory.
+    pub missing: Vec<FluidAmount>,
+}
@@ -0,0 +1,249 @@
+//! Fluids are items that are measured continuously, rather than counted.
+//!
+//! Items flagged as [`fluid`](super::item_manifest::ItemData::fluid) are kept in a [`FluidInventory`],
+//! which stores fractional amounts alongside the integer-counted [`Inventory`](super::inventory::Inventory).
+
+use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
+
+use crate::asset_management::{localization::Localization, manifest::Id};
+
+use super::{
+    errors::{AddFluidError, RemoveFluidsError},
+    item_manifest::{Item, ItemManifest},
+};
+
+/// Differences in fluid amounts smaller than this are treated as rounding errors.
+const TOLERANCE: f32 = 1e-4;
+
+/// A specific, possibly fractional, amount of a fluid item.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FluidAmount {
+    /// The unique identifier of the fluid being measured.
+    item_id: Id<Item>,
+
+    /// The amount of fluid.
+    amount: f32,
+}
+
+impl FluidAmount {
+    /// Creates a new measure of `amount` of the fluid `item_id`.
+    pub fn new(item_id: Id<Item>, amount: f32) -> Self {
+        Self { item_id, amount }
+    }
+
+    /// The unique identifier of the fluid being measured.
+    pub fn item_id(&self) -> Id<Item> {
+        self.item_id
+    }
+
+    /// The amount of fluid.
+    pub fn amount(&self) -> f32 {
+        self.amount
+    }
+
+    /// The pretty text formatting of this type.
+    pub fn display(&self, item_manifest: &ItemManifest, localization: &Localization) -> String {
+        let name = localization.get(item_manifest.name(self.item_id));
+        format!("{}, ({:.2})", name, self.amount)
+    }
+}
+
+/// A container for a single fluid, with a capacity.
+#[derive(Debug, Clone, PartialEq)]
+struct FluidTank {
+    /// The fluid that this tank holds.
+    item_id: Id<Item>,
+    /// The amount of fluid currently stored.
+    amount: f32,
+    /// The maximum amount of fluid that fits in this tank.
+    capacity: f32,
+}
+
+/// Stores fluid items of a structure, which are kept separately from its discrete items.
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct FluidInventory {
+    /// The tanks that each hold a single fluid.
+    tanks: Vec<FluidTank>,
+}
+
+impl FluidInventory {
+    /// Adds an empty tank for `item_id`, holding up to its stack size.
+    pub(crate) fn add_empty_tank(&mut self, item_id: Id<Item>, item_manifest: &ItemManifest) {
+        let capacity = item_manifest.get(item_id).stack_size as f32;
+
+        self.tanks.push(FluidTank {
+            item_id,
+            amount: 0.,
+            capacity,
+        });
+    }
+
+    /// Does this inventory have no tanks at all?
+    pub fn is_empty(&self) -> bool {
+        self.tanks.is_empty()
+    }
+
+    /// The total amount of the fluid `item_id` stored in this inventory.
+    pub fn amount(&self, item_id: Id<Item>) -> f32 {
+        self.tanks
+            .iter()
+            .filter(|tank| tank.item_id == item_id)
+            .map(|tank| tank.amount)
+            .sum()
+    }
+
+    /// The amount of the fluid `item_id` that can still be added to this inventory.
+    pub fn free_capacity(&self, item_id: Id<Item>) -> f32 {
+        self.tanks
+            .iter()
+            .filter(|tank| tank.item_id == item_id)
+            .map(|tank| tank.capacity - tank.amount)
+            .sum()
+    }
+
+    /// The fluids stored in this inventory, one entry per tank.
+    pub fn contents(&self) -> impl Iterator<Item = FluidAmount> + '_ {
+        self.tanks
+            .iter()
+            .map(|tank| FluidAmount::new(tank.item_id, tank.amount))
+    }
+
+    /// Is there room for all of the provided `fluid_amounts` in this inventory?
+    pub fn can_fit(&self, fluid_amounts: &[FluidAmount]) -> bool {
+        fluid_amounts.iter().all(|fluid_amount| {
+            self.free_capacity(fluid_amount.item_id) + TOLERANCE >= fluid_amount.amount
+        })
+    }
+
+    /// Are all of the provided `fluid_amounts` available in this inventory?
+    pub fn has_fluids(&self, fluid_amounts: &[FluidAmount]) -> bool {
+        fluid_amounts.iter().all(|fluid_amount| {
+            self.amount(fluid_amount.item_id) + TOLERANCE >= fluid_amount.amount
+        })
+    }
+
+    /// Adds as much of the `fluid_amount` as will fit.
+    ///
+    /// Returns an error containing the excess if not all of it could be stored.
+    pub fn add_fluid(&mut self, fluid_amount: FluidAmount) -> Result<(), AddFluidError> {
+        let mut remaining = fluid_amount.amount;
+
+        for tank in self
+            .tanks
+            .iter_mut()
+            .filter(|tank| tank.item_id == fluid_amount.item_id)
+        {
+            let added = remaining.min(tank.capacity - tank.amount);
+            tank.amount += added;
+            remaining -= added;
+        }
+
+        if remaining > TOLERANCE {
+            Err(AddFluidError {
+                excess: FluidAmount::new(fluid_amount.item_id, remaining),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Removes all of the provided `fluid_amounts`.
+    ///
+    /// If any of them are missing, an error is returned and _no_ fluids are removed.
+    pub fn remove_fluids_all_or_nothing(
+        &mut self,
+        fluid_amounts: &[FluidAmount],
+    ) -> Result<(), RemoveFluidsError> {
+        let missing: Vec<FluidAmount> = fluid_amounts
+            .iter()
+            .filter_map(|fluid_amount| {
+                let missing = fluid_amount.amount - self.amount(fluid_amount.item_id);
+                (missing > TOLERANCE).then(|| FluidAmount::new(fluid_amount.item_id, missing))
+            })
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(RemoveFluidsError { missing });
+        }
+
+        for fluid_amount in fluid_amounts {
+            let mut remaining = fluid_amount.amount;
+            for tank in self
+                .tanks
+                .iter_mut()
+                .filter(|tank| tank.item_id == fluid_amount.item_id)
+            {
+                let removed = remaining.min(tank.amount);
+                // Snap away any rounding errors, so tanks can be emptied completely
+                tank.amount = (tank.amount - removed).max(0.);
+                if tank.amount < TOLERANCE {
+                    tank.amount = 0.;
+                }
+                remaining -= removed;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The pretty formatting for this type
+    pub fn display(&self, item_manifest: &ItemManifest, localization: &Localization) -> String {
+        let tank_strings: Vec<String> = self
+            .tanks
+            .iter()
+            .map(|tank| {
+                let name = localization.get(item_manifest.name(tank.item_id));
+                format!("{name} ({:.2}/{:.2})", tank.amount, tank.capacity)
+            })
+            .collect();
+
+        format!("[{}]", tank_strings.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::item_manifest::ItemData;
+
+    #[test]
+    fn fluids_are_stored_and_removed_in_fractional_amounts() {
+        let water = Id::from_name("water");
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("water", ItemData::new_fluid(2));
+
+        let mut fluid_inventory = FluidInventory::default();
+        fluid_inventory.add_empty_tank(water, &item_manifest);
+
+        assert_eq!(
+            fluid_inventory.add_fluid(FluidAmount::new(water, 0.75)),
+            Ok(())
+        );
+        assert!(fluid_inventory.has_fluids(&[FluidAmount::new(water, 0.5)]));
+        assert!(!fluid_inventory.has_fluids(&[FluidAmount::new(water, 1.)]));
+
+        // Removal is all or nothing
+        assert_eq!(
+            fluid_inventory.remove_fluids_all_or_nothing(&[FluidAmount::new(water, 1.)]),
+            Err(RemoveFluidsError {
+                missing: vec![FluidAmount::new(water, 0.25)]
+            })
+        );
+        assert_eq!(fluid_inventory.amount(water), 0.75);
+        assert_eq!(
+            fluid_inventory.remove_fluids_all_or_nothing(&[FluidAmount::new(water, 0.5)]),
+            Ok(())
+        );
+        assert_eq!(fluid_inventory.amount(water), 0.25);
+
+        // Tanks overflow once full
+        assert_eq!(
+            fluid_inventory.add_fluid(FluidAmount::new(water, 2.)),
+            Err(AddFluidError {
+                excess: FluidAmount::new(water, 0.25)
+            })
+        );
+        assert_eq!(fluid_inventory.amount(water), 2.);
+    }
+}
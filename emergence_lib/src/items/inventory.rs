@@ -1,10 +1,10 @@
 //! Storage of multiple items with a capacity.
 
-use bevy::prelude::warn;
-use itertools::rev;
+use bevy::{prelude::warn, utils::HashSet};
+use itertools::{rev, Itertools};
 use serde::{Deserialize, Serialize};
 
-use crate::asset_management::manifest::Id;
+use crate::asset_management::{localization::Localization, manifest::Id};
 
 use super::{
     errors::{
@@ -16,13 +16,98 @@ use super::{
     ItemCount,
 };
 
+/// Which items an inventory is allowed to store.
+///
+/// This is serialized in the same form as an `Option<Id<Item>>`,
+/// with sets of items written as a list.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "RawReservedFor", into = "RawReservedFor")]
+pub enum ReservedFor {
+    /// Any item can be stored.
+    #[default]
+    Any,
+    /// Only a single item type can be stored.
+    Only(Id<Item>),
+    /// Only items on this whitelist can be stored.
+    OneOf(HashSet<Id<Item>>),
+}
+
+impl ReservedFor {
+    /// Are items of the type `item_id` allowed?
+    pub fn permits(&self, item_id: Id<Item>) -> bool {
+        match self {
+            ReservedFor::Any => true,
+            ReservedFor::Only(reserved_item) => item_id == *reserved_item,
+            ReservedFor::OneOf(whitelist) => whitelist.contains(&item_id),
+        }
+    }
+
+    /// The items that have been explicitly allowed.
+    ///
+    /// This is empty for [`ReservedFor::Any`], as no particular item is singled out.
+    pub fn items(&self) -> impl Iterator<Item = Id<Item>> + '_ {
+        let (only, whitelist) = match self {
+            ReservedFor::Any => (None, None),
+            ReservedFor::Only(item_id) => (Some(*item_id), None),
+            ReservedFor::OneOf(whitelist) => (None, Some(whitelist)),
+        };
+
+        only.into_iter()
+            .chain(whitelist.into_iter().flatten().copied())
+    }
+
+    /// The pretty formatting for this type.
+    pub(crate) fn display(
+        &self,
+        item_manifest: &ItemManifest,
+        localization: &Localization,
+    ) -> String {
+        match self {
+            ReservedFor::Any => "Any item".to_string(),
+            _ => self
+                .items()
+                .map(|item_id| localization.get(item_manifest.name(item_id)))
+                .sorted()
+                .join(", "),
+        }
+    }
+}
+
+/// The serialized form of [`ReservedFor`], which is compatible with the older `Option<Id<Item>>` form.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawReservedFor {
+    /// No item, or a single item.
+    Single(Option<Id<Item>>),
+    /// A whitelist of items.
+    Many(HashSet<Id<Item>>),
+}
+
+impl From<RawReservedFor> for ReservedFor {
+    fn from(raw: RawReservedFor) -> Self {
+        match raw {
+            RawReservedFor::Single(None) => ReservedFor::Any,
+            RawReservedFor::Single(Some(item_id)) => ReservedFor::Only(item_id),
+            RawReservedFor::Many(whitelist) => ReservedFor::OneOf(whitelist),
+        }
+    }
+}
+
+impl From<ReservedFor> for RawReservedFor {
+    fn from(reserved_for: ReservedFor) -> Self {
+        match reserved_for {
+            ReservedFor::Any => RawReservedFor::Single(None),
+            ReservedFor::Only(item_id) => RawReservedFor::Single(Some(item_id)),
+            ReservedFor::OneOf(whitelist) => RawReservedFor::Many(whitelist),
+        }
+    }
+}
+
 /// An inventory to store multiple types of items.
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Inventory {
-    /// Is this inventory reserved for a single item type?
-    ///
-    /// If so, this field will be `Some(reserved_item_id)`.
-    reserved_for: Option<Id<Item>>,
+    /// Which items can be stored in this inventory?
+    reserved_for: ReservedFor,
 
     /// The item slots that are currently active.
     ///
@@ -64,7 +149,7 @@ impl InventoryState {
 #[allow(dead_code)]
 impl Inventory {
     /// Create an empty inventory with the given amount of slots.
-    pub fn new(max_slot_count: usize, reserved_for: Option<Id<Item>>) -> Self {
+    pub fn new(max_slot_count: usize, reserved_for: ReservedFor) -> Self {
         Self {
             reserved_for,
             slots: Vec::new(),
@@ -75,7 +160,7 @@ impl Inventory {
     /// Creates an inventory that can store up to `max` items of the type `item_id`.
     pub fn new_from_item(item_id: Id<Item>, max: usize) -> Self {
         Self {
-            reserved_for: Some(item_id),
+            reserved_for: ReservedFor::Only(item_id),
             slots: vec![ItemSlot::new(item_id, max)],
             max_slot_count: 1,
         }
@@ -91,17 +176,22 @@ impl Inventory {
         self.slots.iter_mut()
     }
 
-    /// Which type of item is this inventory reserved for, if any?
-    pub(crate) fn reserved_for(&self) -> Option<Id<Item>> {
-        self.reserved_for
+    /// Which items is this inventory reserved for?
+    pub(crate) fn reserved_for(&self) -> &ReservedFor {
+        &self.reserved_for
+    }
+
+    /// Changes which items this inventory is reserved for.
+    ///
+    /// Items that are already stored are kept, even if they are no longer permitted,
+    /// but no more of them can be added.
+    pub(crate) fn set_reserved_for(&mut self, reserved_for: ReservedFor) {
+        self.reserved_for = reserved_for;
     }
 
     /// Does this inventory allow storage of items of the type `item_id`?
     pub(crate) fn permits(&self, item_id: Id<Item>) -> bool {
-        match self.reserved_for {
-            None => true,
-            Some(reserved_item) => item_id == reserved_item,
-        }
+        self.reserved_for.permits(item_id)
     }
 
     /// How full is this inventory?
@@ -192,6 +282,45 @@ impl Inventory {
             + self.free_slot_count() * item_manifest.get(item_id).stack_size
     }
 
+    /// Could all of the items in `item_count` be added to this inventory at once?
+    ///
+    /// This matches the outcome of [`Inventory::add_item_all_or_nothing`],
+    /// but does not modify or clone the inventory, and stops as soon as enough space is found.
+    pub fn can_fit(&self, item_count: &ItemCount, item_manifest: &ItemManifest) -> bool {
+        if !self.permits(item_count.item_id()) {
+            return false;
+        }
+
+        let mut items_to_fit = item_count.count();
+        for slot in self
+            .slots
+            .iter()
+            .filter(|slot| slot.is_for_item(item_count.item_id()))
+        {
+            if items_to_fit <= slot.remaining_space() {
+                return true;
+            }
+            items_to_fit -= slot.remaining_space();
+        }
+
+        let stack_size = item_manifest.get(item_count.item_id()).stack_size;
+        items_to_fit <= self.free_slot_count() * stack_size
+    }
+
+    /// The fraction of the space available for `item_id` that is already filled, between 0 and 1.
+    ///
+    /// Inventories that cannot hold this item at all are considered full.
+    pub(crate) fn fullness(&self, item_id: Id<Item>, item_manifest: &ItemManifest) -> f32 {
+        let item_count = self.item_count(item_id);
+        let capacity = item_count + self.remaining_space_for_item(item_id, item_manifest);
+
+        if capacity == 0 {
+            1.0
+        } else {
+            item_count as f32 / capacity as f32
+        }
+    }
+
     /// Clears any inventory stacks with 0 items in them.
     ///
     /// This is the standard behavior for units and storages, but not for crafting.
@@ -224,20 +353,17 @@ impl Inventory {
                 "A reserved inventory was expanded to create an empty slot for {}",
                 item_manifest.name(item_id)
             );
-            self.reserved_for = None;
+            self.reserved_for = ReservedFor::Any;
         }
 
-        let n_existing_slots = self.slots.len();
-        let slot_to_use = n_existing_slots + 1;
         let stack_size = item_manifest.get(item_id).stack_size;
         let empty_stack = ItemSlot::new(item_id, stack_size);
 
-        if slot_to_use >= self.max_slot_count {
-            self.max_slot_count = slot_to_use;
-            self.slots.push(empty_stack);
-        } else {
-            self.slots[slot_to_use] = empty_stack
+        // Grow the inventory if there's no room left for another slot
+        if self.slots.len() >= self.max_slot_count {
+            self.max_slot_count = self.slots.len() + 1;
         }
+        self.slots.push(empty_stack);
     }
 
     /// Try to add as many items to the inventory as possible, up to the given count.
@@ -567,13 +693,75 @@ impl Inventory {
         }
     }
 
+    /// Moves as many items as possible out of `other` and into `self`, respecting stack sizes and reservations.
+    ///
+    /// Any items that do not fit are left in `other`.
+    /// If everything was moved, [`Ok(())`] is returned.
+    /// Otherwise, an [`AddManyItemsError`] is returned, listing the items that were left behind.
+    pub fn merge_from(
+        &mut self,
+        other: &mut Inventory,
+        item_manifest: &ItemManifest,
+    ) -> Result<(), AddManyItemsError> {
+        let item_ids: Vec<Id<Item>> = other.iter().map(|slot| slot.item_id()).unique().collect();
+        let mut excess_counts = Vec::new();
+
+        for item_id in item_ids {
+            let available = other.item_count(item_id);
+            let actual = available.min(self.remaining_space_for_item(item_id, item_manifest));
+
+            if actual > 0 {
+                let actual_count = ItemCount::new(item_id, actual);
+
+                // Unwraps are being used as assertions here: if this is panicking, this method is broken
+                other.remove_item_all_or_nothing(&actual_count).unwrap();
+                self.add_item_all_or_nothing(&actual_count, item_manifest)
+                    .unwrap();
+            }
+
+            if available > actual {
+                excess_counts.push(ItemCount::new(item_id, available - actual));
+            }
+        }
+
+        if excess_counts.is_empty() {
+            Ok(())
+        } else {
+            Err(AddManyItemsError { excess_counts })
+        }
+    }
+
+    /// Removes up to `count_per_item` of each type of item from `self`, returning them in a new inventory.
+    ///
+    /// The new inventory has the same size and reservation as `self`, so it can always hold the removed items.
+    pub fn split(&mut self, count_per_item: usize, item_manifest: &ItemManifest) -> Inventory {
+        let mut split_off = Inventory::new(self.max_slot_count, self.reserved_for.clone());
+        let item_ids: Vec<Id<Item>> = self.iter().map(|slot| slot.item_id()).unique().collect();
+
+        for item_id in item_ids {
+            let count = self.item_count(item_id).min(count_per_item);
+
+            if count > 0 {
+                let item_count = ItemCount::new(item_id, count);
+
+                // Unwraps are being used as assertions here: if this is panicking, this method is broken
+                self.remove_item_all_or_nothing(&item_count).unwrap();
+                split_off
+                    .add_item_all_or_nothing(&item_count, item_manifest)
+                    .unwrap();
+            }
+        }
+
+        split_off
+    }
+
     /// The pretty formatting for this type
-    pub fn display(&self, item_manifest: &ItemManifest) -> String {
+    pub fn display(&self, item_manifest: &ItemManifest, localization: &Localization) -> String {
         let slot_strings: Vec<String> = self
             // Filled slots
             .slots
             .iter()
-            .map(|slot| slot.display(item_manifest))
+            .map(|slot| slot.display(item_manifest, localization))
             // Empty slots
             .chain((0..self.free_slot_count()).map(|_| "_".to_string()))
             .collect();
@@ -590,14 +778,14 @@ mod tests {
     /// Create a simple item manifest for testing purposes.
     fn item_manifest() -> ItemManifest {
         let mut manifest = Manifest::new();
-        manifest.insert("acacia_leaf", ItemData { stack_size: 10 });
-        manifest.insert("test", ItemData { stack_size: 10 });
+        manifest.insert("acacia_leaf", ItemData::new(10));
+        manifest.insert("test", ItemData::new(10));
         manifest
     }
 
     fn full_inventory() -> Inventory {
         Inventory {
-            reserved_for: None,
+            reserved_for: ReservedFor::Any,
             max_slot_count: 1,
             slots: vec![ItemSlot::new_with_count(Id::from_name("test"), 10, 10)],
         }
@@ -605,7 +793,7 @@ mod tests {
 
     fn partial_inventory() -> Inventory {
         Inventory {
-            reserved_for: None,
+            reserved_for: ReservedFor::Any,
             max_slot_count: 1,
             slots: vec![ItemSlot::new_with_count(Id::from_name("test"), 10, 7)],
         }
@@ -613,7 +801,7 @@ mod tests {
 
     fn empty_inventory() -> Inventory {
         Inventory {
-            reserved_for: None,
+            reserved_for: ReservedFor::Any,
             max_slot_count: 1,
             slots: vec![],
         }
@@ -629,7 +817,7 @@ mod tests {
     #[test]
     fn should_count_item() {
         let inventory = Inventory {
-            reserved_for: None,
+            reserved_for: ReservedFor::Any,
             max_slot_count: 4,
             slots: vec![
                 ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -644,7 +832,7 @@ mod tests {
     #[test]
     fn should_determine_that_item_count_is_available() {
         let inventory = Inventory {
-            reserved_for: None,
+            reserved_for: ReservedFor::Any,
             max_slot_count: 4,
             slots: vec![
                 ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -659,7 +847,7 @@ mod tests {
     #[test]
     fn should_determine_that_item_count_is_not_available() {
         let inventory = Inventory {
-            reserved_for: None,
+            reserved_for: ReservedFor::Any,
             max_slot_count: 4,
             slots: vec![
                 ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -673,15 +861,67 @@ mod tests {
 
     #[test]
     fn should_determine_that_inventory_is_empty() {
-        let inventory = Inventory::new(4, None);
+        let inventory = Inventory::new(4, ReservedFor::Any);
 
         assert!(inventory.is_empty());
     }
 
+    #[test]
+    fn whitelisted_storage_only_accepts_listed_items() {
+        let acacia_leaf = Id::from_name("acacia_leaf");
+        let test_item = Id::from_name("test");
+        let mut item_manifest = item_manifest();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let whitelist = ReservedFor::OneOf(HashSet::from_iter([acacia_leaf, test_item]));
+        let mut inventory = Inventory::new(3, whitelist);
+
+        assert!(inventory
+            .add_item_all_or_nothing(&ItemCount::one(acacia_leaf), &item_manifest)
+            .is_ok());
+        assert!(inventory
+            .add_item_all_or_nothing(&ItemCount::one(test_item), &item_manifest)
+            .is_ok());
+
+        let leuco_chunk = Id::from_name("leuco_chunk");
+        assert_eq!(
+            inventory.remaining_space_for_item(leuco_chunk, &item_manifest),
+            0
+        );
+        assert!(inventory
+            .add_item_all_or_nothing(&ItemCount::one(leuco_chunk), &item_manifest)
+            .is_err());
+    }
+
+    #[test]
+    fn reserved_for_reads_the_older_optional_form() {
+        let acacia_leaf = Id::<Item>::from_name("acacia_leaf");
+
+        for reserved_for in [
+            ReservedFor::Any,
+            ReservedFor::Only(acacia_leaf),
+            ReservedFor::OneOf(HashSet::from_iter([acacia_leaf])),
+        ] {
+            let serialized = serde_json::to_string(&reserved_for).unwrap();
+            let deserialized: ReservedFor = serde_json::from_str(&serialized).unwrap();
+            assert_eq!(deserialized, reserved_for);
+        }
+
+        let legacy_single = serde_json::to_string(&Some(acacia_leaf)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ReservedFor>(&legacy_single).unwrap(),
+            ReservedFor::Only(acacia_leaf)
+        );
+        assert_eq!(
+            serde_json::from_str::<ReservedFor>("null").unwrap(),
+            ReservedFor::Any
+        );
+    }
+
     #[test]
     fn should_determine_that_inventory_is_not_empty() {
         let inventory = Inventory {
-            reserved_for: None,
+            reserved_for: ReservedFor::Any,
             max_slot_count: 4,
             slots: vec![
                 ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -696,7 +936,7 @@ mod tests {
     #[test]
     fn should_determine_that_inventory_is_full() {
         let inventory = Inventory {
-            reserved_for: None,
+            reserved_for: ReservedFor::Any,
             max_slot_count: 4,
             slots: vec![
                 ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -712,7 +952,7 @@ mod tests {
     #[test]
     fn should_determine_that_inventory_is_not_full() {
         let inventory = Inventory {
-            reserved_for: None,
+            reserved_for: ReservedFor::Any,
             max_slot_count: 4,
             slots: vec![
                 ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -727,7 +967,7 @@ mod tests {
     #[test]
     fn should_calculate_number_of_free_slots() {
         let inventory = Inventory {
-            reserved_for: None,
+            reserved_for: ReservedFor::Any,
             max_slot_count: 4,
             slots: vec![
                 ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -742,7 +982,7 @@ mod tests {
     #[test]
     fn should_calculate_remaining_space_for_item() {
         let inventory = Inventory {
-            reserved_for: None,
+            reserved_for: ReservedFor::Any,
             max_slot_count: 4,
             slots: vec![
                 ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -757,6 +997,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_fit_matches_adding_items() {
+        let acacia_leaf = Id::from_name("acacia_leaf");
+        let test = Id::from_name("test");
+        // Nearly full: a partial stack of leaves, and one free slot left
+        let inventory = Inventory {
+            reserved_for: ReservedFor::Any,
+            max_slot_count: 3,
+            slots: vec![
+                ItemSlot::new_with_count(acacia_leaf, 10, 8),
+                ItemSlot::new_with_count(test, 10, 3),
+            ],
+        };
+
+        for item_id in [acacia_leaf, test] {
+            for count in 0..=25 {
+                let item_count = ItemCount::new(item_id, count);
+                let added = inventory
+                    .clone()
+                    .add_item_all_or_nothing(&item_count, &item_manifest())
+                    .is_ok();
+
+                assert_eq!(
+                    inventory.can_fit(&item_count, &item_manifest()),
+                    added,
+                    "{count} items"
+                );
+            }
+        }
+
+        // Reserved inventories never fit other items
+        let reserved = Inventory::new(1, ReservedFor::Only(acacia_leaf));
+        assert!(reserved.can_fit(&ItemCount::new(acacia_leaf, 10), &item_manifest()));
+        assert!(!reserved.can_fit(&ItemCount::one(test), &item_manifest()));
+    }
+
     mod add {
         mod until_full_one_item {
             use super::super::item_manifest;
@@ -766,7 +1042,7 @@ mod tests {
             #[test]
             fn should_be_ok_when_all_fit() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -789,7 +1065,7 @@ mod tests {
             #[test]
             fn should_fill_up_when_not_all_fit() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -822,7 +1098,7 @@ mod tests {
             #[test]
             fn should_be_ok_when_all_fit() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -845,7 +1121,7 @@ mod tests {
             #[test]
             fn should_not_add_anything_if_not_enough_space() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -875,7 +1151,7 @@ mod tests {
             #[test]
             fn should_be_ok_when_all_fit() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -901,7 +1177,7 @@ mod tests {
             #[test]
             fn should_not_add_anything_if_not_enough_space() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -935,7 +1211,7 @@ mod tests {
             #[test]
             fn should_be_ok_when_all_exist() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -955,7 +1231,7 @@ mod tests {
             #[test]
             fn should_empty_when_not_all_exist() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -981,7 +1257,7 @@ mod tests {
             #[test]
             fn should_be_ok_when_all_exist() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -1004,7 +1280,7 @@ mod tests {
             #[test]
             fn should_not_remove_anything_if_not_enough_exist() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -1033,7 +1309,7 @@ mod tests {
             #[test]
             fn should_be_ok_when_all_exist() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -1056,7 +1332,7 @@ mod tests {
             #[test]
             fn should_not_remove_anything_if_not_enough_exist() {
                 let mut inventory = Inventory {
-                    reserved_for: None,
+                    reserved_for: ReservedFor::Any,
                     max_slot_count: 4,
                     slots: vec![
                         ItemSlot::new_with_count(Id::from_name("acacia_leaf"), 10, 10),
@@ -1080,6 +1356,93 @@ mod tests {
         }
     }
 
+    mod merge_from {
+        use super::*;
+
+        #[test]
+        fn everything_moves_when_it_fits() {
+            let mut destination = Inventory::new(2, ReservedFor::Any);
+            let mut source = partial_inventory();
+
+            assert_eq!(
+                destination.merge_from(&mut source, &item_manifest()),
+                Ok(())
+            );
+            assert_eq!(destination.item_count(Id::from_name("test")), 7);
+            assert!(source.is_empty());
+        }
+
+        #[test]
+        fn leftovers_stay_in_the_source_when_not_all_fit() {
+            let mut destination = partial_inventory();
+            let mut source = Inventory::new(2, ReservedFor::Any);
+            source
+                .add_items_all_or_nothing(
+                    &[
+                        ItemCount::new(Id::from_name("test"), 5),
+                        ItemCount::new(Id::from_name("acacia_leaf"), 4),
+                    ],
+                    &item_manifest(),
+                )
+                .unwrap();
+
+            // The destination has room for 3 more test items, and no free slots for the leaves
+            assert_eq!(
+                destination.merge_from(&mut source, &item_manifest()),
+                Err(AddManyItemsError {
+                    excess_counts: vec![
+                        ItemCount::new(Id::from_name("test"), 2),
+                        ItemCount::new(Id::from_name("acacia_leaf"), 4)
+                    ]
+                })
+            );
+            assert!(destination.is_full());
+            assert_eq!(destination.item_count(Id::from_name("test")), 10);
+            assert_eq!(source.item_count(Id::from_name("test")), 2);
+            assert_eq!(source.item_count(Id::from_name("acacia_leaf")), 4);
+        }
+
+        #[test]
+        fn reservations_are_respected() {
+            let mut destination =
+                Inventory::new(2, ReservedFor::Only(Id::from_name("acacia_leaf")));
+            let mut source = partial_inventory();
+
+            assert_eq!(
+                destination.merge_from(&mut source, &item_manifest()),
+                Err(AddManyItemsError {
+                    excess_counts: vec![ItemCount::new(Id::from_name("test"), 7)]
+                })
+            );
+            assert!(destination.is_empty());
+        }
+    }
+
+    mod split {
+        use super::*;
+
+        #[test]
+        fn splits_off_up_to_the_requested_count_of_each_item() {
+            let mut inventory = Inventory::new(3, ReservedFor::Any);
+            inventory
+                .add_items_all_or_nothing(
+                    &[
+                        ItemCount::new(Id::from_name("test"), 15),
+                        ItemCount::new(Id::from_name("acacia_leaf"), 2),
+                    ],
+                    &item_manifest(),
+                )
+                .unwrap();
+
+            let split_off = inventory.split(4, &item_manifest());
+
+            assert_eq!(split_off.item_count(Id::from_name("test")), 4);
+            assert_eq!(split_off.item_count(Id::from_name("acacia_leaf")), 2);
+            assert_eq!(inventory.item_count(Id::from_name("test")), 11);
+            assert_eq!(inventory.item_count(Id::from_name("acacia_leaf")), 0);
+        }
+    }
+
     mod transfer_item {
         use super::*;
 
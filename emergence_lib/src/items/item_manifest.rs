@@ -19,6 +19,31 @@ pub type ItemManifest = Manifest<Item, ItemData>;
 pub struct ItemData {
     /// The number of items that can fit in a single item slot.
     pub stack_size: usize,
+    /// Is this item a fluid, measured in fractional amounts?
+    ///
+    /// Fluids are stored in a [`FluidInventory`](super::fluid::FluidInventory),
+    /// and their stack size is the capacity of each tank.
+    /// Units never carry fluids: instead, they are piped from the structures that produce them into adjacent structures.
+    #[serde(default)]
+    pub fluid: bool,
+}
+
+impl ItemData {
+    /// Creates the data for a discrete item, with up to `stack_size` items fitting in each slot.
+    pub fn new(stack_size: usize) -> Self {
+        ItemData {
+            stack_size,
+            fluid: false,
+        }
+    }
+
+    /// Creates the data for a fluid item, with up to `capacity` units fitting in each tank.
+    pub fn new_fluid(capacity: usize) -> Self {
+        ItemData {
+            stack_size: capacity,
+            fluid: true,
+        }
+    }
 }
 
 /// The [`ItemManifest`] as seen in the manifest file.
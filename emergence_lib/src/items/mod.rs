@@ -2,15 +2,19 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::asset_management::manifest::Id;
+use crate::asset_management::{localization::Localization, manifest::Id};
 
 use self::item_manifest::{Item, ItemManifest};
 
+pub mod economy;
 pub mod errors;
+pub mod fluid;
 pub mod inventory;
 pub mod item_manifest;
+pub mod production_chain;
 pub mod recipe;
 pub mod slot;
+pub mod tech_tree;
 
 /// A specific amount of a given item.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -44,8 +48,41 @@ impl ItemCount {
     }
 
     /// The pretty text formatting of this type.
-    pub fn display(&self, item_manifest: &ItemManifest) -> String {
-        let name = item_manifest.name(self.item_id);
+    pub fn display(&self, item_manifest: &ItemManifest, localization: &Localization) -> String {
+        let name = localization.get(item_manifest.name(self.item_id));
         format!("{}, ({})", name, self.count)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asset_management::{localization::RawLocalization, manifest::Manifest},
+        items::item_manifest::ItemData,
+    };
+
+    #[test]
+    fn item_counts_display_translated_names() {
+        let mut item_manifest: ItemManifest = Manifest::new();
+        item_manifest.insert("acacia_leaf", ItemData::new(10));
+        item_manifest.insert("leuco_chunk", ItemData::new(5));
+
+        let raw_localization: RawLocalization = serde_json::from_str(
+            r#"{ "locale": "fr", "strings": { "acacia_leaf": "Feuille d'acacia" } }"#,
+        )
+        .unwrap();
+        let mut localization = Localization::new("fr");
+        localization.load(&raw_localization);
+
+        assert_eq!(
+            ItemCount::new(Id::from_name("acacia_leaf"), 3).display(&item_manifest, &localization),
+            "Feuille d'acacia, (3)"
+        );
+        // Untranslated items fall back to their raw name
+        assert_eq!(
+            ItemCount::new(Id::from_name("leuco_chunk"), 1).display(&item_manifest, &localization),
+            "leuco_chunk, (1)"
+        );
+    }
+}
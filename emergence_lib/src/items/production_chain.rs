@@ -0,0 +1,207 @@
+//! Traces how an item is made, by walking backwards through the recipes that produce it.
+//!
+//! The recipe manifest forms a dependency graph between items:
+//! each recipe links the items it consumes to the items it produces.
+//! This graph may contain cycles, such as seeds that grow into plants that produce more seeds.
+
+use bevy::utils::HashSet;
+
+use crate::asset_management::manifest::Id;
+
+use super::{
+    item_manifest::Item,
+    recipe::{Recipe, RecipeData, RecipeManifest},
+};
+
+/// A single node of a [`ProductionChain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProductionChainNode {
+    /// An item, which is made by the recipes listed beneath it.
+    ///
+    /// Items without any recipes beneath them must be gathered some other way.
+    Item {
+        /// The item being produced.
+        item_id: Id<Item>,
+        /// Was this item already expanded earlier in the chain?
+        ///
+        /// Repeated items are not expanded again, so cyclic recipes can't loop forever.
+        repeated: bool,
+    },
+    /// A recipe that produces the item above it, consuming the items listed beneath it.
+    Recipe(Id<Recipe>),
+}
+
+/// A [`ProductionChainNode`], and how deeply it is nested in the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProductionChainEntry {
+    /// The number of steps between this node and the item at the root of the chain.
+    pub depth: usize,
+    /// The item or recipe at this point of the chain.
+    pub node: ProductionChainNode,
+}
+
+/// The tree of recipes and items needed to produce an item, flattened in depth-first order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductionChain {
+    /// The nodes of the tree, each followed by its children.
+    entries: Vec<ProductionChainEntry>,
+}
+
+impl ProductionChain {
+    /// Traces the recipes that produce `item_id`, and the recipes that produce their inputs in turn.
+    ///
+    /// Each item is only expanded the first time it is encountered,
+    /// so the size of the chain is bounded by the size of the recipe manifest.
+    pub fn new(item_id: Id<Item>, recipe_manifest: &RecipeManifest) -> Self {
+        // Sort the recipes so that the chain is displayed in a stable order
+        let mut recipes: Vec<(Id<Recipe>, &RecipeData)> = recipe_manifest.iter().collect();
+        recipes.sort_by_key(|(recipe_id, _)| recipe_manifest.name(*recipe_id));
+
+        let mut chain = ProductionChain {
+            entries: Vec::new(),
+        };
+        chain.expand(item_id, 0, &recipes, &mut HashSet::new());
+        chain
+    }
+
+    /// Adds `item_id` at `depth`, followed by the recipes that produce it and their inputs.
+    fn expand(
+        &mut self,
+        item_id: Id<Item>,
+        depth: usize,
+        recipes: &[(Id<Recipe>, &RecipeData)],
+        expanded: &mut HashSet<Id<Item>>,
+    ) {
+        let repeated = !expanded.insert(item_id);
+        self.entries.push(ProductionChainEntry {
+            depth,
+            node: ProductionChainNode::Item { item_id, repeated },
+        });
+
+        if repeated {
+            return;
+        }
+
+        for (recipe_id, recipe_data) in recipes {
+            let produces_item = recipe_data
+                .outputs
+                .iter()
+                .map(|output| output.item_id())
+                .chain(
+                    recipe_data
+                        .fluid_outputs
+                        .iter()
+                        .map(|fluid_output| fluid_output.item_id()),
+                )
+                .any(|output_id| output_id == item_id);
+            if !produces_item {
+                continue;
+            }
+
+            self.entries.push(ProductionChainEntry {
+                depth: depth + 1,
+                node: ProductionChainNode::Recipe(*recipe_id),
+            });
+
+            let item_inputs = recipe_data
+                .inputs
+                .iter()
+                .flat_map(|input| input.items().iter().copied());
+            let fluid_inputs = recipe_data
+                .fluid_inputs
+                .iter()
+                .map(|fluid_input| fluid_input.item_id());
+
+            for input_id in item_inputs.chain(fluid_inputs) {
+                self.expand(input_id, depth + 2, recipes, expanded);
+            }
+        }
+    }
+
+    /// The nodes of the chain, in depth-first order.
+    pub fn entries(&self) -> &[ProductionChainEntry] {
+        &self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::Duration;
+
+    use super::*;
+    use crate::items::{recipe::RecipeInput, ItemCount};
+
+    /// A recipe that turns one of each of the `inputs` into one `output`.
+    fn recipe(inputs: &[&str], output: &str) -> RecipeData {
+        RecipeData {
+            inputs: inputs
+                .iter()
+                .map(|input| RecipeInput::one(Id::from_name(input)))
+                .collect(),
+            outputs: vec![ItemCount::one(Id::from_name(output))],
+            craft_time: Duration::from_secs(1),
+            ..Default::default()
+        }
+    }
+
+    /// Shorthand for an item node of a production chain.
+    fn item(depth: usize, name: &str, repeated: bool) -> ProductionChainEntry {
+        ProductionChainEntry {
+            depth,
+            node: ProductionChainNode::Item {
+                item_id: Id::from_name(name),
+                repeated,
+            },
+        }
+    }
+
+    /// Shorthand for a recipe node of a production chain.
+    fn recipe_node(depth: usize, name: &str) -> ProductionChainEntry {
+        ProductionChainEntry {
+            depth,
+            node: ProductionChainNode::Recipe(Id::from_name(name)),
+        }
+    }
+
+    #[test]
+    fn production_chains_trace_inputs_back_to_raw_materials() {
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert("grow_leaf", recipe(&[], "acacia_leaf"));
+        recipe_manifest.insert("digest_leaf", recipe(&["acacia_leaf"], "leuco_chunk"));
+        recipe_manifest.insert("make_paper", recipe(&["leuco_chunk", "mud"], "paper"));
+
+        let chain = ProductionChain::new(Id::from_name("paper"), &recipe_manifest);
+        assert_eq!(
+            chain.entries(),
+            &[
+                item(0, "paper", false),
+                recipe_node(1, "make_paper"),
+                item(2, "leuco_chunk", false),
+                recipe_node(3, "digest_leaf"),
+                item(4, "acacia_leaf", false),
+                recipe_node(5, "grow_leaf"),
+                // Nothing produces mud, so it must be gathered
+                item(2, "mud", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn production_chains_stop_at_cycles() {
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert("plant_seed", recipe(&["seed"], "plant"));
+        recipe_manifest.insert("harvest_plant", recipe(&["plant"], "seed"));
+
+        let chain = ProductionChain::new(Id::from_name("seed"), &recipe_manifest);
+        assert_eq!(
+            chain.entries(),
+            &[
+                item(0, "seed", false),
+                recipe_node(1, "harvest_plant"),
+                item(2, "plant", false),
+                recipe_node(3, "plant_seed"),
+                item(4, "seed", true),
+            ]
+        );
+    }
+}
@@ -1,17 +1,28 @@
 //! Instructions to craft items.
 
-use super::item_manifest::ItemManifest;
-use super::{inventory::Inventory, ItemCount};
+use super::item_manifest::{Item, ItemManifest};
+use super::{
+    fluid::{FluidAmount, FluidInventory},
+    inventory::{Inventory, ReservedFor},
+    ItemCount,
+};
+use crate::asset_management::localization::Localization;
 use crate::asset_management::manifest::loader::RawManifest;
-use crate::asset_management::manifest::Manifest;
+use crate::asset_management::manifest::{Id, Manifest};
 use crate::{
-    organisms::energy::Energy,
-    simulation::light::{Illuminance, TotalLight},
-    structures::crafting::{InputInventory, OutputInventory},
+    organisms::{energy::Energy, OrganismId},
+    simulation::{
+        geometry::Height,
+        light::{Illuminance, TotalLight},
+        time::Season,
+    },
+    structures::crafting::{InputInventory, OutputFluids, OutputInventory},
+    terrain::terrain_manifest::Terrain,
+    units::unit_manifest::Unit,
 };
 use bevy::reflect::{FromReflect, Reflect, TypeUuid};
-use bevy::utils::HashMap;
-use serde::{Deserialize, Serialize};
+use bevy::utils::{HashMap, HashSet};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::{fmt::Display, time::Duration};
 
 /// The marker type for [`Id<Recipe>`](super::Id).
@@ -25,41 +36,165 @@ pub type RecipeManifest = Manifest<Recipe, RecipeData>;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RecipeData {
     /// The inputs needed to craft the recipe.
-    pub inputs: Vec<ItemCount>,
+    pub inputs: Vec<RecipeInput>,
+
+    /// The fluids needed to craft the recipe, which may be fractional amounts.
+    #[serde(default)]
+    pub fluid_inputs: Vec<FluidAmount>,
 
     /// The outputs generated by crafting.
     pub outputs: Vec<ItemCount>,
 
+    /// The fluids generated by crafting, which may be fractional amounts.
+    ///
+    /// These are piped out of the crafter, rather than being carried away by units.
+    #[serde(default)]
+    pub fluid_outputs: Vec<FluidAmount>,
+
     /// The time needed to craft the recipe.
     pub craft_time: Duration,
 
     /// The conditions that must be met to craft the recipe.
     pub conditions: RecipeConditions,
 
+    /// How the crafting speed changes as more workers are added.
+    #[serde(default)]
+    pub worker_scaling: WorkerScaling,
+
+    /// How the crafting speed changes over the course of the year.
+    #[serde(default)]
+    pub seasonal_profile: SeasonalProfile,
+
     /// The amount of [`Energy`] produced by making this recipe, if any.
     ///
     /// This is only relevant to living structures.
     pub energy: Option<Energy>,
+
+    /// The recipes that must each be crafted at least once before this recipe is unlocked.
+    #[serde(default)]
+    pub prerequisites: Vec<Id<Recipe>>,
+
+    /// How the terrain beneath the crafting structure is reshaped each time this recipe is completed, if at all.
+    #[serde(default)]
+    pub terrain_effect: Option<TerrainEffect>,
+
+    /// The items extracted from the [`ResourceDeposit`] beneath the crafting structure each time this recipe is completed, if any.
+    ///
+    /// The recipe cannot be started unless the deposit still holds enough of the item.
+    ///
+    /// [`ResourceDeposit`]: crate::terrain::deposits::ResourceDeposit
+    #[serde(default)]
+    pub mined_deposit: Option<ItemCount>,
+
+    /// The unit or structure spawned next to the crafting structure each time this recipe is completed, if any.
+    #[serde(default)]
+    pub spawns: Option<OrganismId>,
+}
+
+impl Default for RecipeData {
+    /// An instant recipe with no inputs, outputs or conditions.
+    fn default() -> Self {
+        RecipeData {
+            inputs: Vec::new(),
+            fluid_inputs: Vec::new(),
+            outputs: Vec::new(),
+            fluid_outputs: Vec::new(),
+            craft_time: Duration::ZERO,
+            conditions: RecipeConditions::NONE,
+            worker_scaling: WorkerScaling::default(),
+            seasonal_profile: SeasonalProfile::UNIFORM,
+            energy: None,
+            prerequisites: Vec::new(),
+            terrain_effect: None,
+            mined_deposit: None,
+            spawns: None,
+        }
+    }
 }
 
 impl RecipeData {
+    /// The type of unit that is spawned when this recipe completes, if any.
+    ///
+    /// Completing these recipes is limited by the [`Population`](crate::units::population::Population) cap.
+    pub(crate) fn spawned_unit(&self) -> Option<Id<Unit>> {
+        match self.spawns {
+            Some(OrganismId::Unit(unit_id)) => Some(unit_id),
+            _ => None,
+        }
+    }
+
     /// Are the conditions to craft this recipe met?
-    pub(crate) fn satisfied(&self, workers: u8, total_light: &TotalLight) -> bool {
-        self.conditions.satisfied(workers, total_light)
+    pub(crate) fn satisfied(
+        &self,
+        workers: u8,
+        total_light: &TotalLight,
+        terrain: Option<Id<Terrain>>,
+    ) -> bool {
+        self.conditions.satisfied(workers, total_light, terrain)
     }
 
     /// An inventory with empty slots for all of the inputs of this recipe.
+    ///
+    /// Each alternative item that can be used for an input gets its own slot.
     pub(crate) fn input_inventory(&self, item_manifest: &ItemManifest) -> InputInventory {
-        let mut inventory = Inventory::new(self.inputs.len(), None);
-        for item_count in &self.inputs {
-            inventory.add_empty_slot(item_count.item_id, item_manifest);
+        let n_slots = self.inputs.iter().map(|input| input.items.len()).sum();
+        let mut inventory = Inventory::new(n_slots, ReservedFor::Any);
+        for input in &self.inputs {
+            for &item_id in &input.items {
+                inventory.add_empty_slot(item_id, item_manifest);
+            }
         }
         InputInventory { inventory }
     }
 
+    /// An inventory with empty tanks for all of the fluid inputs of this recipe.
+    pub(crate) fn fluid_inventory(&self, item_manifest: &ItemManifest) -> FluidInventory {
+        let mut fluid_inventory = FluidInventory::default();
+        for fluid_amount in &self.fluid_inputs {
+            fluid_inventory.add_empty_tank(fluid_amount.item_id(), item_manifest);
+        }
+        fluid_inventory
+    }
+
+    /// An inventory with empty tanks for all of the fluid outputs of this recipe.
+    pub(crate) fn output_fluids(&self, item_manifest: &ItemManifest) -> OutputFluids {
+        let mut tanks = FluidInventory::default();
+        for fluid_amount in &self.fluid_outputs {
+            tanks.add_empty_tank(fluid_amount.item_id(), item_manifest);
+        }
+        OutputFluids { tanks }
+    }
+
+    /// Picks the items from the `inventory` that will be consumed to craft this recipe.
+    ///
+    /// Each input uses the first of its alternatives that is fully stocked,
+    /// taking into account the items already claimed by earlier inputs.
+    /// Returns [`None`] if any of the inputs cannot be satisfied.
+    pub(crate) fn choose_inputs(&self, inventory: &Inventory) -> Option<Vec<ItemCount>> {
+        let mut chosen: Vec<ItemCount> = Vec::with_capacity(self.inputs.len());
+
+        for input in &self.inputs {
+            let claimed = |item_id: Id<Item>| -> usize {
+                chosen
+                    .iter()
+                    .filter(|item_count| item_count.item_id == item_id)
+                    .map(ItemCount::count)
+                    .sum()
+            };
+
+            let item_id =
+                input.items.iter().copied().find(|&item_id| {
+                    inventory.item_count(item_id) >= claimed(item_id) + input.count
+                })?;
+            chosen.push(ItemCount::new(item_id, input.count));
+        }
+
+        Some(chosen)
+    }
+
     /// An inventory with empty slots for all of the outputs of this recipe.
     pub(crate) fn output_inventory(&self, item_manifest: &ItemManifest) -> OutputInventory {
-        let mut inventory = Inventory::new(self.outputs.len(), None);
+        let mut inventory = Inventory::new(self.outputs.len(), ReservedFor::Any);
         for item_count in &self.outputs {
             inventory.add_empty_slot(item_count.item_id, item_manifest);
         }
@@ -76,19 +211,57 @@ impl RecipeData {
         self.conditions.workers_required > 0
     }
 
+    /// The rate at which this recipe is crafted with `workers_present`, relative to its base `craft_time`.
+    ///
+    /// Only up to `max_workers` workers contribute, as set by the structure crafting this recipe.
+    /// Recipes that do not need workers always progress at the base rate.
+    pub(crate) fn work_speed(&self, workers_present: u8, max_workers: u8) -> f32 {
+        if self.needs_workers() {
+            self.worker_scaling
+                .speed_factor(workers_present, self.workers_required(), max_workers)
+        } else {
+            1.0
+        }
+    }
+
+    /// The rate at which this recipe is crafted with `workers_present` (out of `max_workers`) during the `season`,
+    /// relative to its base `craft_time`.
+    pub(crate) fn seasonal_work_speed(
+        &self,
+        workers_present: u8,
+        max_workers: u8,
+        season: Season,
+    ) -> f32 {
+        self.work_speed(workers_present, max_workers) * self.seasonal_profile.multiplier(season)
+    }
+
     /// The pretty formatting of this type
-    pub(crate) fn display(&self, item_manifest: &ItemManifest) -> String {
+    pub(crate) fn display(
+        &self,
+        item_manifest: &ItemManifest,
+        localization: &Localization,
+    ) -> String {
         let input_strings: Vec<String> = self
             .inputs
             .iter()
-            .map(|input| input.display(item_manifest))
+            .map(|input| input.display(item_manifest, localization))
+            .chain(
+                self.fluid_inputs
+                    .iter()
+                    .map(|fluid_amount| fluid_amount.display(item_manifest, localization)),
+            )
             .collect();
         let input_str = input_strings.join(", ");
 
         let output_strings: Vec<String> = self
             .outputs
             .iter()
-            .map(|output| output.display(item_manifest))
+            .map(|output| output.display(item_manifest, localization))
+            .chain(
+                self.fluid_outputs
+                    .iter()
+                    .map(|fluid_amount| fluid_amount.display(item_manifest, localization)),
+            )
             .collect();
         let output_str = output_strings.join(", ");
 
@@ -104,6 +277,185 @@ impl RecipeData {
     }
 }
 
+/// A single ingredient of a recipe, which can be satisfied by any one of several interchangeable items.
+///
+/// For backwards compatibility, a single item can be written in place of the list of alternatives,
+/// and the old `item_id` field name is still accepted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecipeInput {
+    /// The items that can be used for this input, in order of preference.
+    #[serde(alias = "item_id", deserialize_with = "deserialize_alternatives")]
+    items: Vec<Id<Item>>,
+
+    /// The number of items needed.
+    count: usize,
+}
+
+impl RecipeInput {
+    /// Creates an input that needs `count` of any one of the provided `items`.
+    pub fn new(items: Vec<Id<Item>>, count: usize) -> Self {
+        Self { items, count }
+    }
+
+    /// Creates an input that can only be satisfied by a single item.
+    pub fn single(item_id: Id<Item>, count: usize) -> Self {
+        Self {
+            items: vec![item_id],
+            count,
+        }
+    }
+
+    /// A single one of the given item.
+    pub fn one(item_id: Id<Item>) -> Self {
+        RecipeInput::single(item_id, 1)
+    }
+
+    /// The items that can be used for this input, in order of preference.
+    pub fn items(&self) -> &[Id<Item>] {
+        &self.items
+    }
+
+    /// The number of items needed.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The pretty text formatting of this type.
+    pub fn display(&self, item_manifest: &ItemManifest, localization: &Localization) -> String {
+        let names: Vec<&str> = self
+            .items
+            .iter()
+            .map(|&item_id| localization.get(item_manifest.name(item_id)))
+            .collect();
+        format!("{}, ({})", names.join(" or "), self.count)
+    }
+}
+
+impl From<ItemCount> for RecipeInput {
+    fn from(item_count: ItemCount) -> Self {
+        RecipeInput::single(item_count.item_id(), item_count.count())
+    }
+}
+
+/// Reads the alternatives of a [`RecipeInput`], which may be either a single item or a list of items.
+fn deserialize_alternatives<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Id<Item>>, D::Error> {
+    /// The forms that the alternatives of a [`RecipeInput`] can be written in.
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Alternatives {
+        /// A bare item.
+        Single(Id<Item>),
+        /// A list of interchangeable items.
+        Many(Vec<Id<Item>>),
+    }
+
+    Ok(match Alternatives::deserialize(deserializer)? {
+        Alternatives::Single(item_id) => vec![item_id],
+        Alternatives::Many(items) => items,
+    })
+}
+
+/// Controls how crafting speed scales with the number of workers present.
+///
+/// Speed is computed relative to the number of workers required by the recipe,
+/// so having exactly the required number of workers always results in a speed of 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum WorkerScaling {
+    /// Each additional worker speeds up crafting by the same amount.
+    #[default]
+    Linear,
+    /// Each additional worker speeds up crafting by less than the previous one.
+    ///
+    /// Speed scales with the square root of the ratio of present to required workers.
+    DiminishingReturns,
+}
+
+impl WorkerScaling {
+    /// Computes the speed of crafting, relative to the base `craft_time` of the recipe.
+    ///
+    /// Workers beyond `max_workers` do not speed crafting up any further,
+    /// so the fastest possible speed is reached when the structure is fully staffed.
+    pub fn speed_factor(&self, workers_present: u8, workers_required: u8, max_workers: u8) -> f32 {
+        let workers = workers_present.min(max_workers);
+        if workers == 0 {
+            return 0.0;
+        }
+
+        let work_ratio = workers as f32 / workers_required.max(1) as f32;
+        match self {
+            WorkerScaling::Linear => work_ratio,
+            WorkerScaling::DiminishingReturns => work_ratio.sqrt(),
+        }
+    }
+}
+
+/// How quickly a recipe is crafted during each [`Season`], relative to its base `craft_time`.
+///
+/// A multiplier of zero prevents the recipe from making progress during that season.
+/// Seasons that are not specified use a multiplier of 1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeasonalProfile {
+    /// The crafting speed multiplier during [`Season::Spring`].
+    pub spring: f32,
+    /// The crafting speed multiplier during [`Season::Summer`].
+    pub summer: f32,
+    /// The crafting speed multiplier during [`Season::Autumn`].
+    pub autumn: f32,
+    /// The crafting speed multiplier during [`Season::Winter`].
+    pub winter: f32,
+}
+
+impl Default for SeasonalProfile {
+    fn default() -> Self {
+        SeasonalProfile::UNIFORM
+    }
+}
+
+impl SeasonalProfile {
+    /// Crafting speed is not affected by the seasons.
+    pub const UNIFORM: SeasonalProfile = SeasonalProfile {
+        spring: 1.,
+        summer: 1.,
+        autumn: 1.,
+        winter: 1.,
+    };
+
+    /// The crafting speed multiplier during the provided `season`.
+    pub fn multiplier(&self, season: Season) -> f32 {
+        match season {
+            Season::Spring => self.spring,
+            Season::Summer => self.summer,
+            Season::Autumn => self.autumn,
+            Season::Winter => self.winter,
+        }
+    }
+}
+
+/// A change to the terrain beneath a crafting structure, such as mining out the ground or enriching the soil.
+///
+/// The effect is applied to every tile of the structure's footprint, so the ground beneath it stays flat.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TerrainEffect {
+    /// How many steps the terrain is raised by, or lowered by if negative.
+    #[serde(default)]
+    pub height_change: i8,
+    /// The terrain type that the ground is converted into, if it should change.
+    #[serde(default)]
+    pub terrain_type: Option<Id<Terrain>>,
+}
+
+impl TerrainEffect {
+    /// The height of a tile at `height` once this effect is applied.
+    ///
+    /// Returns [`None`] if the new height would fall outside of the allowable range.
+    pub(crate) fn apply_to_height(&self, height: Height) -> Option<Height> {
+        height.0.checked_add_signed(self.height_change).map(Height)
+    }
+}
+
 /// The environmental conditions needed for work to be done on a recipe.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RecipeConditions {
@@ -111,6 +463,11 @@ pub struct RecipeConditions {
     pub workers_required: u8,
     /// The range of light levels that are acceptable for this recipe.
     pub allowable_light_range: Option<Threshold<Illuminance>>,
+    /// The types of terrain that the crafting structure must be on for this recipe to progress.
+    ///
+    /// If this is [`None`], the recipe can be crafted on any terrain.
+    #[serde(default)]
+    pub required_terrain: Option<HashSet<Id<Terrain>>>,
 }
 
 impl Display for RecipeConditions {
@@ -121,6 +478,9 @@ impl Display for RecipeConditions {
         if let Some(range) = &self.allowable_light_range {
             write!(f, "Light: {}", *range)?;
         }
+        if let Some(terrain_types) = &self.required_terrain {
+            write!(f, "Terrain: {} types", terrain_types.len())?;
+        }
         Ok(())
     }
 }
@@ -130,6 +490,7 @@ impl RecipeConditions {
     pub const NONE: RecipeConditions = RecipeConditions {
         workers_required: 0,
         allowable_light_range: None,
+        required_terrain: None,
     };
 
     /// Creates a new [`RecipeConditions`].
@@ -137,18 +498,31 @@ impl RecipeConditions {
         Self {
             workers_required,
             allowable_light_range: Some(allowable_light_range),
+            required_terrain: None,
         }
     }
 
     /// Are the conditions to craft this recipe met?
-    fn satisfied(&self, workers: u8, total_light: &TotalLight) -> bool {
+    ///
+    /// `terrain` is the type of terrain that the crafting structure is on, if any.
+    pub(crate) fn satisfied(
+        &self,
+        workers: u8,
+        total_light: &TotalLight,
+        terrain: Option<Id<Terrain>>,
+    ) -> bool {
         let work_satisfied = self.workers_required == 0 || workers >= self.workers_required;
         let light_satisfied = self
             .allowable_light_range
             .as_ref()
             .map_or(true, |range| range.contains(total_light.illuminance()));
+        let terrain_satisfied = match (&self.required_terrain, terrain) {
+            (None, _) => true,
+            (Some(required_terrain), Some(terrain)) => required_terrain.contains(&terrain),
+            (Some(_), None) => false,
+        };
 
-        work_satisfied && light_satisfied
+        work_satisfied && light_satisfied && terrain_satisfied
     }
 }
 
@@ -206,3 +580,96 @@ impl RawManifest for RawRecipeManifest {
         manifest
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The maximum number of workers allowed at the structure crafting each test recipe.
+    const MAX_WORKERS: u8 = 4;
+
+    /// Counts the number of ticks needed to complete `recipe` with a fixed number of `workers`.
+    fn ticks_to_complete(recipe: &RecipeData, workers: u8) -> usize {
+        let period = Duration::from_secs_f32(1. / 30.);
+        let mut progress = Duration::ZERO;
+        let mut ticks = 0;
+
+        while progress < recipe.craft_time {
+            progress += Duration::from_secs_f32(
+                period.as_secs_f32() * recipe.work_speed(workers, MAX_WORKERS),
+            );
+            ticks += 1;
+        }
+
+        ticks
+    }
+
+    /// A recipe that requires a single worker to craft.
+    fn recipe(worker_scaling: WorkerScaling) -> RecipeData {
+        RecipeData {
+            craft_time: Duration::from_secs(1),
+            conditions: RecipeConditions {
+                workers_required: 1,
+                allowable_light_range: None,
+                required_terrain: None,
+            },
+            worker_scaling,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn more_workers_craft_faster() {
+        let linear = recipe(WorkerScaling::Linear);
+        let one_worker = ticks_to_complete(&linear, 1);
+        let three_workers = ticks_to_complete(&linear, 3);
+        assert!(three_workers * 3 <= one_worker + 3);
+
+        let diminishing = recipe(WorkerScaling::DiminishingReturns);
+        let diminishing_three_workers = ticks_to_complete(&diminishing, 3);
+        assert_eq!(ticks_to_complete(&diminishing, 1), one_worker);
+        assert!(diminishing_three_workers < one_worker);
+        assert!(diminishing_three_workers > three_workers);
+    }
+
+    #[test]
+    fn no_workers_means_no_progress() {
+        assert_eq!(
+            recipe(WorkerScaling::Linear).work_speed(0, MAX_WORKERS),
+            0.0
+        );
+        assert_eq!(
+            recipe(WorkerScaling::DiminishingReturns).work_speed(0, MAX_WORKERS),
+            0.0
+        );
+    }
+
+    #[test]
+    fn crafting_speed_is_capped() {
+        // Workers beyond the maximum allowed by the structure don't help
+        let linear = recipe(WorkerScaling::Linear);
+        assert_eq!(
+            linear.work_speed(u8::MAX, MAX_WORKERS),
+            linear.work_speed(MAX_WORKERS, MAX_WORKERS)
+        );
+        assert_eq!(linear.work_speed(u8::MAX, MAX_WORKERS), MAX_WORKERS as f32);
+        assert!(ticks_to_complete(&linear, u8::MAX) > 1);
+    }
+
+    #[test]
+    fn recipe_inputs_accept_single_items_or_alternatives() {
+        let acacia_leaf = Id::<Item>::from_name("acacia_leaf");
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+
+        // Inputs written before alternatives were supported
+        let legacy: RecipeInput =
+            serde_json::from_value(serde_json::json!({ "item_id": acacia_leaf, "count": 2 }))
+                .unwrap();
+        assert_eq!(legacy, RecipeInput::single(acacia_leaf, 2));
+
+        let alternatives = RecipeInput::new(vec![acacia_leaf, leuco_chunk], 3);
+        let serialized = serde_json::to_string(&alternatives).unwrap();
+        let deserialized: RecipeInput = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(alternatives, deserialized);
+    }
+}
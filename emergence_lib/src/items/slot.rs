@@ -3,7 +3,7 @@
 use rand::{distributions::Uniform, prelude::Distribution, rngs::ThreadRng};
 use serde::{Deserialize, Serialize};
 
-use crate::asset_management::manifest::Id;
+use crate::asset_management::{localization::Localization, manifest::Id};
 
 use super::{
     errors::{AddOneItemError, RemoveOneItemError},
@@ -176,10 +176,10 @@ impl ItemSlot {
     }
 
     /// The pretty formatting for this type
-    pub fn display(&self, item_manifest: &ItemManifest) -> String {
+    pub fn display(&self, item_manifest: &ItemManifest, localization: &Localization) -> String {
         format!(
             "{} ({}/{})",
-            item_manifest.name(self.item_id),
+            localization.get(item_manifest.name(self.item_id)),
             self.count,
             self.max_item_count
         )
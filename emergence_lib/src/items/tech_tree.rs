@@ -0,0 +1,128 @@
+//! Recipes can be locked behind other recipes, forming a simple tech tree.
+//!
+//! A recipe is unlocked once every one of its [`prerequisites`](RecipeData::prerequisites)
+//! has been crafted at least once.
+
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{
+    asset_management::manifest::Id,
+    structures::{
+        construction::Ghost,
+        crafting::{ActiveRecipe, CraftingState},
+    },
+};
+
+use super::recipe::{Recipe, RecipeData, RecipeManifest};
+
+/// Tracks which recipes have been unlocked by the colony.
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct TechTree {
+    /// The set of recipes that can currently be crafted.
+    unlocked: HashSet<Id<Recipe>>,
+    /// The set of recipes that have been crafted at least once.
+    crafted: HashSet<Id<Recipe>>,
+}
+
+impl TechTree {
+    /// Can the recipe with the provided `recipe_id` be crafted?
+    pub fn is_unlocked(&self, recipe_id: Id<Recipe>) -> bool {
+        self.unlocked.contains(&recipe_id)
+    }
+
+    /// Records that the recipe with the provided `recipe_id` has been crafted.
+    pub(crate) fn record_crafted(&mut self, recipe_id: Id<Recipe>) {
+        self.crafted.insert(recipe_id);
+    }
+
+    /// Are all of the prerequisites of `recipe_data` met?
+    fn prerequisites_met(&self, recipe_data: &RecipeData) -> bool {
+        recipe_data
+            .prerequisites
+            .iter()
+            .all(|prerequisite| self.crafted.contains(prerequisite))
+    }
+
+    /// Unlocks all recipes whose prerequisites have been met.
+    ///
+    /// Recipes are never re-locked.
+    /// Returns `true` if any new recipes were unlocked.
+    pub(crate) fn unlock_available(&mut self, recipe_manifest: &RecipeManifest) -> bool {
+        let mut any_unlocked = false;
+        for (recipe_id, recipe_data) in recipe_manifest.iter() {
+            if !self.unlocked.contains(&recipe_id) && self.prerequisites_met(recipe_data) {
+                self.unlocked.insert(recipe_id);
+                any_unlocked = true;
+            }
+        }
+        any_unlocked
+    }
+}
+
+/// Records completed recipes and unlocks any new recipes that are now available.
+///
+/// The tech tree is only re-evaluated when a recipe is crafted for the first time, or the recipes themselves change.
+pub(crate) fn update_tech_tree(
+    // Ghosts use their crafting state to track construction, not crafting
+    crafting_query: Query<
+        (&CraftingState, &ActiveRecipe),
+        (Changed<CraftingState>, Without<Ghost>),
+    >,
+    recipe_manifest: Res<RecipeManifest>,
+    mut tech_tree: ResMut<TechTree>,
+) {
+    // Avoid triggering change detection unless something was actually unlocked
+    let tech_tree_mut = tech_tree.bypass_change_detection();
+    let mut newly_crafted = false;
+
+    for (crafting_state, active_recipe) in crafting_query.iter() {
+        if let (CraftingState::RecipeComplete, Some(recipe_id)) =
+            (crafting_state, active_recipe.recipe_id())
+        {
+            if !tech_tree_mut.crafted.contains(recipe_id) {
+                tech_tree_mut.record_crafted(*recipe_id);
+                newly_crafted = true;
+            }
+        }
+    }
+
+    if (newly_crafted || recipe_manifest.is_changed())
+        && tech_tree_mut.unlock_available(&recipe_manifest)
+    {
+        tech_tree.set_changed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// A recipe that requires the provided recipes to be crafted first.
+    fn recipe(prerequisites: Vec<Id<Recipe>>) -> RecipeData {
+        RecipeData {
+            craft_time: Duration::from_secs(1),
+            prerequisites,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn recipes_unlock_after_prerequisites_are_crafted() {
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert("basic", recipe(Vec::new()));
+        recipe_manifest.insert("advanced", recipe(vec![Id::from_name("basic")]));
+
+        let mut tech_tree = TechTree::default();
+        tech_tree.unlock_available(&recipe_manifest);
+
+        assert!(tech_tree.is_unlocked(Id::from_name("basic")));
+        assert!(!tech_tree.is_unlocked(Id::from_name("advanced")));
+
+        tech_tree.record_crafted(Id::from_name("basic"));
+        tech_tree.unlock_available(&recipe_manifest);
+
+        assert!(tech_tree.is_unlocked(Id::from_name("advanced")));
+    }
+}
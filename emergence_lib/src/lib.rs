@@ -9,6 +9,8 @@
 #![allow(clippy::too_many_arguments)]
 
 pub mod asset_management;
+#[cfg(feature = "audio")]
+pub mod audio;
 pub mod curves;
 pub mod enum_iter;
 pub mod filtered_array_iter;
@@ -56,4 +58,165 @@ pub mod testing {
             .add_plugin(crate::player_interaction::InteractionPlugin);
         app
     }
+
+    /// A static colony of units crowded around storage structures, used to benchmark how units choose their actions.
+    ///
+    /// Every unit finishes its current action and chooses a new one on each update.
+    pub fn unit_colony_app(map_radius: u32, n_units: usize, n_structures: usize) -> App {
+        use crate::{
+            asset_management::manifest::Id,
+            items::{
+                inventory::ReservedFor,
+                item_manifest::{ItemData, ItemManifest},
+                tech_tree::TechTree,
+                ItemCount,
+            },
+            player_interaction::contracts::Contracts,
+            signals::Signals,
+            simulation::geometry::{Facing, MapGeometry, TilePos},
+            structures::{
+                colony_center::ColonyCenter, construction::Footprint, crafting::StorageInventory,
+                structure_manifest::StructureManifest,
+            },
+            terrain::terrain_manifest::TerrainManifest,
+            units::{
+                actions::{advance_action_timer, choose_actions, CurrentAction},
+                collision::UnitCollision,
+                goals::Goal,
+                item_interaction::{PickupReservations, UnitInventory},
+                unit_manifest::{Unit, UnitData, UnitManifest},
+            },
+        };
+        use rand::thread_rng;
+
+        let leuco_chunk = Id::from_name("leuco_chunk");
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let mut unit_manifest = UnitManifest::new();
+        unit_manifest.insert("ant", UnitData::default());
+
+        let mut app = minimal_app();
+        // Each update is long enough for every action to finish
+        app.insert_resource(FixedTime::new_from_secs(60.))
+            .insert_resource(unit_manifest)
+            .insert_resource(StructureManifest::new())
+            .insert_resource(TerrainManifest::new())
+            .init_resource::<Signals>()
+            .init_resource::<UnitCollision>()
+            .init_resource::<PickupReservations>()
+            .init_resource::<TechTree>()
+            .init_resource::<ColonyCenter>()
+            .init_resource::<Contracts>()
+            .add_systems((advance_action_timer, choose_actions).chain());
+
+        let mut map_geometry = MapGeometry::new(map_radius);
+        let rng = &mut thread_rng();
+
+        for _ in 0..n_structures {
+            let tile_pos = TilePos::random(&map_geometry, rng);
+            if map_geometry.get_structure(tile_pos).is_some() {
+                continue;
+            }
+
+            let mut storage_inventory = StorageInventory::new(1, ReservedFor::Any);
+            storage_inventory
+                .add_item_all_or_nothing(&ItemCount::new(leuco_chunk, 5), &item_manifest)
+                .unwrap();
+            let structure_entity = app.world.spawn((storage_inventory, tile_pos)).id();
+            map_geometry.add_structure(
+                tile_pos,
+                &Footprint::single(),
+                Id::from_name("storage"),
+                structure_entity,
+            );
+        }
+
+        for i in 0..n_units {
+            // Half of the units are looking for items, and the other half are looking for somewhere to put them
+            let (goal, held_item) = match i % 2 {
+                0 => (Goal::Pickup(leuco_chunk), None),
+                _ => (Goal::Store(leuco_chunk), Some(leuco_chunk)),
+            };
+
+            app.world.spawn((
+                Id::<Unit>::from_name("ant"),
+                TilePos::random(&map_geometry, rng),
+                Facing::default(),
+                goal,
+                CurrentAction::default(),
+                UnitInventory { held_item },
+            ));
+        }
+
+        app.insert_resource(map_geometry)
+            .insert_resource(item_manifest);
+        app
+    }
+
+    /// A field of busy crafting structures, used to benchmark how recipes progress.
+    ///
+    /// Each crafter completes its recipe every few updates, and immediately starts the next one.
+    /// If `parallel` is `false`, crafters are advanced one at a time to provide a baseline.
+    #[cfg(feature = "bench")]
+    pub fn crafting_app(n_structures: usize, parallel: bool) -> App {
+        use crate::{
+            asset_management::manifest::Id,
+            items::{
+                item_manifest::ItemManifest,
+                recipe::{RecipeData, RecipeManifest},
+                tech_tree::TechTree,
+            },
+            simulation::{
+                event_log::EventLog,
+                geometry::{MapGeometry, TilePos},
+                light::TotalLight,
+                time::Season,
+            },
+            structures::crafting::{
+                progress_crafting, progress_crafting_serially, ActiveRecipe, CraftingState,
+                InputInventory, OutputInventory, WorkersPresent,
+            },
+            units::population::Population,
+        };
+        use std::time::Duration;
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "busywork",
+            RecipeData {
+                craft_time: Duration::from_secs(2),
+                ..Default::default()
+            },
+        );
+
+        let mut app = minimal_app();
+        app.insert_resource(recipe_manifest)
+            .insert_resource(ItemManifest::new())
+            .insert_resource(FixedTime::new_from_secs(1.))
+            .init_resource::<TotalLight>()
+            .init_resource::<TechTree>()
+            .init_resource::<Season>()
+            .init_resource::<EventLog>()
+            .init_resource::<Population>()
+            .insert_resource(MapGeometry::new(1));
+
+        match parallel {
+            true => app.add_system(progress_crafting),
+            false => app.add_system(progress_crafting_serially),
+        };
+
+        for _ in 0..n_structures {
+            app.world.spawn((
+                ActiveRecipe::new(Id::from_name("busywork")),
+                CraftingState::NeedsInput,
+                InputInventory::default(),
+                OutputInventory::default(),
+                WorkersPresent::new(1),
+                TilePos::ZERO,
+            ));
+        }
+
+        app
+    }
 }
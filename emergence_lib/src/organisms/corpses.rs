@@ -0,0 +1,209 @@
+//! Units leave behind corpses when they die, which can be scavenged for items.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_management::manifest::Id,
+    items::{
+        inventory::{Inventory, ReservedFor},
+        item_manifest::ItemManifest,
+        ItemCount,
+    },
+    signals::{Emitter, SignalStrength, SignalType},
+    simulation::geometry::{MapGeometry, TilePos},
+    structures::crafting::OutputInventory,
+};
+
+use super::energy::UnitDied;
+
+/// The remains of a dead unit.
+///
+/// Corpses hold the item the unit was carrying, along with some biomass.
+/// They occupy their tile until they have been emptied by other units,
+/// and are tracked in their own index of the [`MapGeometry`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Corpse;
+
+impl Corpse {
+    /// The number of slots in a corpse's inventory: one for the held item, and one for biomass.
+    const INVENTORY_SLOTS: usize = 2;
+
+    /// The amount of biomass left behind by each unit.
+    const BIOMASS_PER_CORPSE: usize = 1;
+
+    /// The name of the item that dead units decompose into.
+    const BIOMASS: &'static str = "biomass";
+
+    /// The strength of the [`SignalType::Push`] signal emitted for each item in the corpse.
+    const SIGNAL_STRENGTH: f32 = 10.;
+}
+
+/// Spawns a [`Corpse`] wherever a unit has died.
+pub(super) fn spawn_corpses(
+    mut death_events: EventReader<UnitDied>,
+    item_manifest: Res<ItemManifest>,
+    mut map_geometry: ResMut<MapGeometry>,
+    mut commands: Commands,
+) {
+    let biomass = Id::from_name(Corpse::BIOMASS);
+
+    for death in death_events.iter() {
+        // Units can die on bridges, which are already occupied by a structure
+        if map_geometry
+            .get_ghost_or_structure(death.tile_pos)
+            .is_some()
+            || map_geometry.is_obstructed(death.tile_pos)
+        {
+            continue;
+        }
+
+        let mut output_inventory = OutputInventory {
+            inventory: Inventory::new(Corpse::INVENTORY_SLOTS, ReservedFor::Any),
+        };
+
+        let mut remains = Vec::with_capacity(Corpse::INVENTORY_SLOTS);
+        remains.extend(death.held_item.map(|item_id| ItemCount::new(item_id, 1)));
+        // Not every item manifest defines biomass
+        if item_manifest.contains(biomass) {
+            remains.push(ItemCount::new(biomass, Corpse::BIOMASS_PER_CORPSE));
+        }
+
+        for item_count in remains {
+            // This should never fail, as the inventory has a slot for each item
+            if output_inventory
+                .add_item_all_or_nothing(&item_count, &item_manifest)
+                .is_err()
+            {
+                error!("Could not add {item_count:?} to a corpse.");
+            }
+        }
+
+        if output_inventory.is_empty() {
+            continue;
+        }
+
+        let corpse_entity = commands
+            .spawn((
+                Corpse,
+                death.tile_pos,
+                output_inventory,
+                Emitter::default(),
+                SpatialBundle::from_transform(Transform::from_translation(
+                    death.tile_pos.top_of_tile(&map_geometry),
+                )),
+            ))
+            .id();
+
+        map_geometry.add_corpse(death.tile_pos, corpse_entity);
+    }
+}
+
+/// Causes corpses to ask for their contents to be carried away.
+pub(super) fn set_corpse_emitter(
+    mut corpse_query: Query<(&mut Emitter, &OutputInventory), With<Corpse>>,
+) {
+    for (mut emitter, output_inventory) in corpse_query.iter_mut() {
+        emitter.signals.clear();
+
+        for item_slot in output_inventory.iter() {
+            if !item_slot.is_empty() {
+                let signal_type = SignalType::Push(item_slot.item_id());
+                let signal_strength = SignalStrength::new(Corpse::SIGNAL_STRENGTH);
+                emitter.signals.push((signal_type, signal_strength));
+            }
+        }
+    }
+}
+
+/// Removes corpses once everything has been taken from them.
+pub(super) fn remove_empty_corpses(
+    corpse_query: Query<(Entity, &TilePos, &OutputInventory), With<Corpse>>,
+    mut map_geometry: ResMut<MapGeometry>,
+    mut commands: Commands,
+) {
+    for (corpse_entity, &tile_pos, output_inventory) in corpse_query.iter() {
+        if output_inventory.is_empty() {
+            map_geometry.remove_corpse(tile_pos);
+            commands.entity(corpse_entity).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        items::item_manifest::{Item, ItemData},
+        organisms::energy::{kill_organisms_when_out_of_energy, EnergyPool},
+        simulation::geometry::Height,
+        units::{
+            goals::Goal,
+            item_interaction::UnitInventory,
+            test_fixture::{spawn_unit, UnitTestApp},
+        },
+    };
+
+    #[test]
+    fn dead_units_drop_their_items_in_a_corpse() {
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+        let biomass = Id::<Item>::from_name(Corpse::BIOMASS);
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(5));
+        item_manifest.insert(Corpse::BIOMASS, ItemData::new(10));
+
+        let mut app = UnitTestApp::new(1).with_items(item_manifest).build();
+        app.add_event::<UnitDied>().add_systems(
+            (
+                kill_organisms_when_out_of_energy,
+                spawn_corpses,
+                remove_empty_corpses,
+            )
+                .chain(),
+        );
+        app.world
+            .resource_mut::<MapGeometry>()
+            .update_height(TilePos::ZERO, Height(1));
+
+        let unit_entity = spawn_unit(
+            &mut app,
+            TilePos::ZERO,
+            Id::from_name("ant"),
+            Goal::default(),
+        );
+        app.world.entity_mut(unit_entity).insert((
+            EnergyPool::simple(10.),
+            UnitInventory {
+                held_item: Some(leuco_chunk),
+            },
+        ));
+        app.update();
+
+        assert!(app.world.get_entity(unit_entity).is_none());
+        let map_geometry = app.world.resource::<MapGeometry>();
+        assert_eq!(map_geometry.get_structure(TilePos::ZERO), None);
+        assert!(!map_geometry.is_passable(TilePos::ZERO));
+        let corpse_entity = map_geometry.get_corpse(TilePos::ZERO).unwrap();
+        let output_inventory = app.world.get::<OutputInventory>(corpse_entity).unwrap();
+        assert_eq!(output_inventory.item_count(leuco_chunk), 1);
+        assert_eq!(output_inventory.item_count(biomass), 1);
+
+        // Scavenging everything removes the corpse
+        let mut output_inventory = app.world.get_mut::<OutputInventory>(corpse_entity).unwrap();
+        output_inventory
+            .remove_item_all_or_nothing(&ItemCount::new(leuco_chunk, 1))
+            .unwrap();
+        output_inventory
+            .remove_item_all_or_nothing(&ItemCount::new(biomass, 1))
+            .unwrap();
+        app.update();
+
+        assert!(app.world.get_entity(corpse_entity).is_none());
+        assert_eq!(
+            app.world
+                .resource::<MapGeometry>()
+                .get_corpse(TilePos::ZERO),
+            None
+        );
+    }
+}
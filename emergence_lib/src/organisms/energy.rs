@@ -8,7 +8,10 @@ use leafwing_abilities::{pool::MaxPoolLessThanZero, prelude::Pool};
 use serde::{Deserialize, Serialize};
 
 use crate::asset_management::manifest::Id;
+use crate::items::item_manifest::Item;
 use crate::structures::structure_manifest::Structure;
+use crate::units::commands::UnitCommandsExt;
+use crate::units::item_interaction::UnitInventory;
 use crate::{simulation::geometry::TilePos, structures::commands::StructureCommandsExt};
 
 /// The amount of energy available to an organism.
@@ -161,16 +164,41 @@ impl Pool for EnergyPool {
     }
 }
 
+/// An event sent whenever a unit dies from running out of energy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct UnitDied {
+    /// The tile that the unit died on.
+    pub(crate) tile_pos: TilePos,
+    /// The item that the unit was carrying when it died, if any.
+    pub(crate) held_item: Option<Id<Item>>,
+}
+
 /// Despawns organisms when they run out of energy
 pub(super) fn kill_organisms_when_out_of_energy(
-    organism_query: Query<(Entity, &EnergyPool, &TilePos, Option<&Id<Structure>>)>,
+    organism_query: Query<(
+        Entity,
+        &EnergyPool,
+        &TilePos,
+        Option<&Id<Structure>>,
+        Option<&UnitInventory>,
+    )>,
     mut commands: Commands,
+    mut death_events: EventWriter<UnitDied>,
 ) {
-    for (entity, energy_pool, tile_pos, maybe_structure) in organism_query.iter() {
+    for (entity, energy_pool, tile_pos, maybe_structure, maybe_unit_inventory) in
+        organism_query.iter()
+    {
         if energy_pool.is_empty() {
             match maybe_structure {
                 Some(_) => commands.despawn_structure(*tile_pos),
-                None => commands.entity(entity).despawn_recursive(),
+                None => {
+                    commands.despawn_unit(entity);
+                    death_events.send(UnitDied {
+                        tile_pos: *tile_pos,
+                        held_item: maybe_unit_inventory
+                            .and_then(|unit_inventory| unit_inventory.held_item),
+                    });
+                }
             }
         }
     }
@@ -5,18 +5,19 @@ use leafwing_abilities::prelude::Pool;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    asset_management::manifest::Id,
+    asset_management::{localization::Localization, manifest::Id},
     player_interaction::clipboard::ClipboardData,
     simulation::{
+        factions::Faction,
         geometry::{Facing, MapGeometry, TilePos},
         time::{Days, TimePool},
     },
     structures::{commands::StructureCommandsExt, structure_manifest::StructureManifest},
     terrain::terrain_manifest::Terrain,
     units::{
-        unit_assets::UnitHandles,
+        commands::UnitCommandsExt,
+        goals::Goal,
         unit_manifest::{Unit, UnitManifest},
-        UnitBundle,
     },
 };
 
@@ -85,10 +86,14 @@ impl Lifecycle {
         &self,
         structure_manifest: &StructureManifest,
         unit_manifest: &UnitManifest,
+        localization: &Localization,
     ) -> String {
         let mut string = String::new();
         for life_path in &self.life_paths {
-            string += &format!("\n{}", life_path.display(structure_manifest, unit_manifest));
+            string += &format!(
+                "\n{}",
+                life_path.display(structure_manifest, unit_manifest, localization)
+            );
         }
         string
     }
@@ -103,6 +108,8 @@ impl Default for Lifecycle {
 /// A path from one organism to another form.
 ///
 /// Units will transform once all of their non-`None` conditions are met.
+/// Paths that require both energy and time will only transform once both have been accumulated,
+/// regardless of the order in which they are reached.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct LifePath {
     /// The form that this organism will take once all of the conditions are met.
@@ -129,42 +136,89 @@ impl LifePath {
         ready
     }
 
+    /// How close this life path is to being complete, from 0 to 1.
+    ///
+    /// When several conditions are required, progress is limited by the condition that is furthest from being met.
+    /// Paths without any conditions are always complete.
+    pub fn progress(&self) -> f32 {
+        /// The fraction of `max` that has been reached.
+        fn fraction(current: f32, max: f32) -> f32 {
+            if max <= 0. {
+                1.
+            } else {
+                (current / max).clamp(0., 1.)
+            }
+        }
+
+        let energy_progress = self
+            .energy_required
+            .as_ref()
+            .map(|energy_pool| fraction(energy_pool.current().0, energy_pool.max().0));
+        let time_progress = self
+            .time_required
+            .as_ref()
+            .map(|time_pool| fraction(time_pool.current().0, time_pool.max().0));
+
+        [energy_progress, time_progress]
+            .into_iter()
+            .flatten()
+            .fold(1., f32::min)
+    }
+
     /// Pretty formatting for this type
     pub(crate) fn display(
         &self,
         structure_manifest: &StructureManifest,
         unit_manifest: &UnitManifest,
+        localization: &Localization,
     ) -> String {
-        let mut string = String::new();
+        let mut conditions = Vec::new();
 
         if let Some(energy_pool) = &self.energy_required {
-            string += &format!("{}/{} energy", energy_pool.current(), energy_pool.max());
+            conditions.push(format!(
+                "{}/{} energy",
+                energy_pool.current(),
+                energy_pool.max()
+            ));
         }
 
         if let Some(time_pool) = &self.time_required {
-            string += &format!("{:.2}/{:.2} days", time_pool.current().0, time_pool.max().0);
+            conditions.push(format!(
+                "{:.2}/{:.2} days",
+                time_pool.current().0,
+                time_pool.max().0
+            ));
         }
 
-        string += &format!(
-            "-> {}",
-            self.new_form.display(structure_manifest, unit_manifest)
-        );
-
-        string
+        format!(
+            "{} ({:.0}%) -> {}",
+            conditions.join(" and "),
+            self.progress() * 100.,
+            self.new_form
+                .display(structure_manifest, unit_manifest, localization)
+        )
     }
 }
 
 /// Checks if lifecycles are complete, and transitions the organism to that form.
 pub(super) fn transform_when_lifecycle_complete(
-    query: Query<(Entity, &Lifecycle, &TilePos, &Facing, Option<&Id<Unit>>)>,
+    query: Query<(
+        Entity,
+        &Lifecycle,
+        &TilePos,
+        &Facing,
+        Option<&Id<Unit>>,
+        Option<&Faction>,
+    )>,
     structure_manifest: Res<StructureManifest>,
-    unit_manifest: Res<UnitManifest>,
-    unit_handles: Res<UnitHandles>,
     map_geometry: Res<MapGeometry>,
     terrain_query: Query<&Id<Terrain>>,
     mut commands: Commands,
 ) {
-    for (entity, lifecycle, &tile_pos, &facing, maybe_unit) in query.iter() {
+    for (entity, lifecycle, &tile_pos, &facing, maybe_unit, maybe_faction) in query.iter() {
+        // The new form stays in the same faction
+        let faction = maybe_faction.copied().unwrap_or_default();
+
         for new_form in lifecycle.new_forms() {
             // Make sure that there's a valid place to spawn the new form.
             if let OrganismId::Structure(structure_id) = new_form {
@@ -177,6 +231,7 @@ pub(super) fn transform_when_lifecycle_complete(
                     footprint,
                     &terrain_query,
                     allowed_terrain_types,
+                    variety.required_adjacency(),
                 ) {
                     // Look for another viable form to transform into.
                     continue;
@@ -185,7 +240,7 @@ pub(super) fn transform_when_lifecycle_complete(
 
             // Cleanup is handled on the basis of what this organism *currently* is.
             if maybe_unit.is_some() {
-                commands.entity(entity).despawn_recursive();
+                commands.despawn_unit(entity);
             } else {
                 commands.despawn_structure(tile_pos);
             }
@@ -193,25 +248,13 @@ pub(super) fn transform_when_lifecycle_complete(
             match new_form {
                 OrganismId::Structure(structure_id) => {
                     let data = ClipboardData {
-                        structure_id,
                         facing,
-                        active_recipe: structure_manifest
-                            .get(structure_id)
-                            .starting_recipe()
-                            .clone(),
+                        ..ClipboardData::new(structure_id, &structure_manifest)
                     };
-                    commands.spawn_structure(tile_pos, data);
+                    commands.spawn_structure(tile_pos, data, faction);
                 }
                 OrganismId::Unit(unit_id) => {
-                    let unit_data = unit_manifest.get(unit_id).clone();
-
-                    commands.spawn(UnitBundle::new(
-                        unit_id,
-                        tile_pos,
-                        unit_data,
-                        &unit_handles,
-                        &map_geometry,
-                    ));
+                    commands.spawn_unit(tile_pos, unit_id, Goal::default(), faction);
                 }
             }
 
@@ -220,3 +263,31 @@ pub(super) fn transform_when_lifecycle_complete(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_requiring_energy_and_time_need_both() {
+        let sprout = OrganismId::Structure(Id::from_name("acacia_sprout"));
+        let mut lifecycle = Lifecycle::new(vec![LifePath {
+            new_form: sprout,
+            energy_required: Some(EnergyPool::simple(10.)),
+            time_required: Some(TimePool::simple(2.)),
+        }]);
+        let progress = |lifecycle: &Lifecycle| lifecycle.life_paths[0].progress();
+
+        assert_eq!(progress(&lifecycle), 0.);
+
+        // Plenty of energy, but not enough time
+        lifecycle.record_energy_gained(Energy(20.));
+        lifecycle.record_elapsed_time(Days(1.));
+        assert!(lifecycle.new_forms().is_empty());
+        assert_eq!(progress(&lifecycle), 0.5);
+
+        lifecycle.record_elapsed_time(Days(1.));
+        assert_eq!(lifecycle.new_forms(), vec![sprout]);
+        assert_eq!(progress(&lifecycle), 1.);
+    }
+}
@@ -5,17 +5,19 @@ use leafwing_abilities::systems::regenerate_resource_pool;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    asset_management::manifest::Id,
+    asset_management::{localization::Localization, manifest::Id},
     simulation::SimulationSet,
     structures::structure_manifest::{Structure, StructureManifest},
     units::unit_manifest::{Unit, UnitManifest},
 };
 
 use self::{
-    energy::{kill_organisms_when_out_of_energy, EnergyPool},
+    corpses::{remove_empty_corpses, set_corpse_emitter, spawn_corpses},
+    energy::{kill_organisms_when_out_of_energy, EnergyPool, UnitDied},
     lifecycle::{transform_when_lifecycle_complete, Lifecycle},
 };
 
+pub(crate) mod corpses;
 pub mod energy;
 pub mod lifecycle;
 
@@ -34,12 +36,18 @@ impl OrganismId {
         &self,
         structure_manifest: &StructureManifest,
         unit_manifest: &UnitManifest,
+        localization: &Localization,
     ) -> String {
         match self {
             OrganismId::Structure(structure_id) => {
-                format!("{} (S)", structure_manifest.name(*structure_id))
+                format!(
+                    "{} (S)",
+                    localization.get(structure_manifest.name(*structure_id))
+                )
+            }
+            OrganismId::Unit(unit_id) => {
+                format!("{} (U)", localization.get(unit_manifest.name(*unit_id)))
             }
-            OrganismId::Unit(unit_id) => format!("{} (U)", unit_manifest.name(*unit_id)),
         }
     }
 }
@@ -86,10 +94,13 @@ pub struct OrganismPlugin;
 
 impl Plugin for OrganismPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_event::<UnitDied>().add_systems(
             (
                 regenerate_resource_pool::<EnergyPool>,
                 kill_organisms_when_out_of_energy,
+                spawn_corpses.after(kill_organisms_when_out_of_energy),
+                set_corpse_emitter,
+                remove_empty_corpses,
                 transform_when_lifecycle_complete,
             )
                 .in_set(SimulationSet)
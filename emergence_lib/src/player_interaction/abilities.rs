@@ -3,8 +3,12 @@
 use super::cursor::CursorPos;
 use super::intent::{Intent, IntentPool};
 use super::InteractionSystem;
+use crate::asset_management::manifest::Id;
+use crate::simulation::geometry::TilePos;
+use crate::units::status_effects::StatusEffectCommandsExt;
+use crate::units::unit_manifest::Unit;
 use bevy::prelude::*;
-use leafwing_abilities::prelude::Pool;
+use bevy::utils::Duration;
 use leafwing_input_manager::prelude::*;
 
 /// Controls, interface and effects of intent-spending abilities.
@@ -15,6 +19,7 @@ impl Plugin for AbilitiesPlugin {
         app.add_plugin(InputManagerPlugin::<IntentAbility>::default())
             .init_resource::<ActionState<IntentAbility>>()
             .insert_resource(IntentAbility::default_input_map())
+            .init_resource::<RallySettings>()
             .add_system(
                 use_ability
                     .in_set(InteractionSystem::UseAbilities)
@@ -32,6 +37,8 @@ pub(crate) enum IntentAbility {
     Lure,
     /// Repel allied units.
     Warning,
+    /// Temporarily speed up allied units.
+    Rally,
 }
 
 impl IntentAbility {
@@ -40,33 +47,193 @@ impl IntentAbility {
         InputMap::new([
             (KeyCode::F, IntentAbility::Lure),
             (KeyCode::V, IntentAbility::Warning),
+            (KeyCode::G, IntentAbility::Rally),
         ])
     }
 
     /// The cost of each ability
-    fn cost(&self) -> Intent {
+    pub(crate) fn cost(&self, rally_settings: &RallySettings) -> Intent {
         match self {
             IntentAbility::Lure => Intent(10.),
             IntentAbility::Warning => Intent(20.),
+            IntentAbility::Rally => rally_settings.cost,
+        }
+    }
+
+    /// Is this ability being used this frame?
+    ///
+    /// Most abilities are used continuously while held,
+    /// but [`IntentAbility::Rally`] lasts a while, and so is only used once per press.
+    fn is_used(&self, ability_state: &ActionState<IntentAbility>) -> bool {
+        match self {
+            IntentAbility::Rally => ability_state.just_pressed(*self),
+            _ => ability_state.pressed(*self),
+        }
+    }
+}
+
+/// Controls the strength and cost of [`IntentAbility::Rally`].
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub(crate) struct RallySettings {
+    /// The intent spent on each use.
+    pub(crate) cost: Intent,
+    /// How far from the cursor units are affected, in tiles.
+    pub(crate) radius: u32,
+    /// How much faster rallied units act.
+    pub(crate) speed_multiplier: f32,
+    /// How long the speed boost lasts.
+    pub(crate) duration: Duration,
+}
+
+impl RallySettings {
+    /// The name of the [`StatusEffect`](crate::units::status_effects::StatusEffect) applied to rallied units.
+    pub(crate) const STATUS_EFFECT_NAME: &'static str = "rallied";
+}
+
+impl Default for RallySettings {
+    fn default() -> Self {
+        RallySettings {
+            cost: Intent(30.),
+            radius: 3,
+            speed_multiplier: 1.5,
+            duration: Duration::from_secs(10),
         }
     }
 }
 
 /// Uses abilities when pressed at the cursor's location.
+///
+/// Abilities that cannot be afforded are skipped, without spending any intent.
 fn use_ability(
     cursor_tile_pos: Res<CursorPos>,
     ability_state: Res<ActionState<IntentAbility>>,
     mut intent_pool: ResMut<IntentPool>,
+    rally_settings: Res<RallySettings>,
+    unit_query: Query<(Entity, &TilePos), With<Id<Unit>>>,
+    mut commands: Commands,
 ) {
-    if let Some(_pos) = cursor_tile_pos.maybe_tile_pos() {
+    if let Some(pos) = cursor_tile_pos.maybe_tile_pos() {
         for variant in IntentAbility::variants() {
-            if ability_state.pressed(variant) {
-                #[allow(clippy::collapsible_if)]
-                // The expend method has side effects, and needs to be guarded
-                if intent_pool.expend(variant.cost()).is_ok() {
+            let cost = variant.cost(&rally_settings);
+            if variant.is_used(&ability_state) && intent_pool.can_afford(cost) {
+                intent_pool
+                    .spend(cost)
+                    .expect("The cost of this ability was checked to be affordable.");
+
+                match variant {
+                    IntentAbility::Rally => {
+                        for (unit_entity, unit_tile_pos) in unit_query.iter() {
+                            if unit_tile_pos.unsigned_distance_to(pos.hex) <= rally_settings.radius
+                            {
+                                commands.apply_status_effect(
+                                    unit_entity,
+                                    RallySettings::STATUS_EFFECT_NAME,
+                                    rally_settings.speed_multiplier,
+                                    rally_settings.duration,
+                                );
+                            }
+                        }
+                    }
                     // TODO: actually take effect
-                };
+                    IntentAbility::Lure | IntentAbility::Warning => (),
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        player_interaction::intent::{regenerate_intent, IntentSettings},
+        units::status_effects::StatusEffects,
+    };
+
+    #[test]
+    fn abilities_wait_for_intent_to_regenerate() {
+        let mut app = App::new();
+        let mut ability_state = ActionState::<IntentAbility>::default();
+        ability_state.press(IntentAbility::Warning);
+
+        app.insert_resource(FixedTime::new_from_secs(1.))
+            .insert_resource(CursorPos::new(TilePos::new(0, 0)))
+            .insert_resource(ability_state)
+            .insert_resource(IntentSettings {
+                regen_per_second: Intent(10.),
+                paused: true,
+            })
+            .init_resource::<IntentPool>()
+            .init_resource::<RallySettings>()
+            .add_systems((regenerate_intent, use_ability).chain());
+
+        let warning_cost = IntentAbility::Warning.cost(&RallySettings::default());
+        let mut intent_pool = app.world.resource_mut::<IntentPool>();
+        let spare_intent = intent_pool.remaining() - warning_cost * 0.5;
+        intent_pool.spend(spare_intent).unwrap();
+        assert!(!intent_pool.can_afford(warning_cost));
+
+        // Regeneration is paused, so the ability stays blocked
+        app.update();
+        let intent_pool = app.world.resource::<IntentPool>();
+        assert_eq!(intent_pool.remaining(), warning_cost * 0.5);
+
+        // Once enough intent has regenerated, the ability is used
+        app.world.resource_mut::<IntentSettings>().paused = false;
+        app.update();
+        let intent_pool = app.world.resource::<IntentPool>();
+        assert_eq!(intent_pool.remaining(), Intent(0.));
+    }
+
+    #[test]
+    fn rallying_speeds_up_nearby_units() {
+        let mut app = App::new();
+        let mut ability_state = ActionState::<IntentAbility>::default();
+        ability_state.press(IntentAbility::Rally);
+
+        let rally_settings = RallySettings::default();
+        app.insert_resource(CursorPos::new(TilePos::ZERO))
+            .insert_resource(ability_state)
+            .insert_resource(rally_settings.clone())
+            .init_resource::<IntentPool>()
+            .add_system(use_ability);
+
+        let radius = rally_settings.radius as i32;
+        let mut spawn_unit = |tile_pos: TilePos| {
+            app.world
+                .spawn((Id::<Unit>::from_name("ant"), tile_pos))
+                .id()
+        };
+        let nearby_unit = spawn_unit(TilePos::ZERO);
+        let edge_unit = spawn_unit(TilePos::new(radius, 0));
+        let distant_unit = spawn_unit(TilePos::new(radius + 1, 0));
+
+        // Leave only enough intent for a single rally
+        let mut intent_pool = app.world.resource_mut::<IntentPool>();
+        let spare_intent = intent_pool.remaining() - rally_settings.cost * 1.5;
+        intent_pool.spend(spare_intent).unwrap();
+
+        app.update();
+        for unit in [nearby_unit, edge_unit] {
+            let status_effects = app.world.get::<StatusEffects>(unit).unwrap();
+            assert_eq!(
+                status_effects.speed_multiplier(),
+                rally_settings.speed_multiplier
+            );
+        }
+        assert!(app.world.get::<StatusEffects>(distant_unit).is_none());
+        assert_eq!(
+            app.world.resource::<IntentPool>().remaining(),
+            rally_settings.cost * 0.5
+        );
+
+        // Without enough intent, rallying does nothing
+        app.update();
+        let status_effects = app.world.get::<StatusEffects>(nearby_unit).unwrap();
+        assert_eq!(status_effects.iter().count(), 1);
+        assert_eq!(
+            app.world.resource::<IntentPool>().remaining(),
+            rally_settings.cost * 0.5
+        );
+    }
+}
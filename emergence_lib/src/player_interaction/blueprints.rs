@@ -0,0 +1,427 @@
+//! Blueprints are layouts of structures that are saved to disk, to be built again elsewhere.
+//!
+//! Blueprints are created from the contents of the [`Clipboard`],
+//! and are placed by zoning each of their tiles, which in turn spawns ghosts.
+//! Each blueprint is stored under its own name in the [`BlueprintLibrary`],
+//! and the selected one is placed.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    ops::Bound,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset_management::AssetState,
+    simulation::geometry::{MapGeometry, TilePos},
+};
+
+use super::{
+    clipboard::{Clipboard, ClipboardData},
+    cursor::CursorPos,
+    zoning::Zoning,
+    InteractionSystem, PlayerAction,
+};
+
+/// Saves and places blueprints.
+pub(super) struct BlueprintPlugin;
+
+impl Plugin for BlueprintPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BlueprintLibrary::load(BlueprintLibrary::default_directory()))
+            .add_system(save_blueprint.after(InteractionSystem::SetClipboard))
+            .add_system(cycle_blueprint.after(save_blueprint))
+            .add_system(
+                place_blueprint
+                    .in_set(InteractionSystem::ApplyZoning)
+                    .after(InteractionSystem::ComputeCursorPos)
+                    .run_if(in_state(AssetState::Ready)),
+            );
+    }
+}
+
+/// A reusable layout of structures.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Blueprint {
+    /// The structures to build, relative to the tile that the blueprint is placed at.
+    structures: Vec<(TilePos, ClipboardData)>,
+}
+
+impl Blueprint {
+    /// Creates a new blueprint from the structures stored in the `clipboard`.
+    ///
+    /// Returns [`None`] if the clipboard does not contain any structures.
+    pub(crate) fn from_clipboard(clipboard: &Clipboard) -> Option<Blueprint> {
+        let Clipboard::Structures(map) = clipboard else {
+            return None;
+        };
+
+        if map.is_empty() {
+            return None;
+        }
+
+        let mut structures: Vec<(TilePos, ClipboardData)> = map
+            .iter()
+            .map(|(&tile_pos, clipboard_data)| (tile_pos, clipboard_data.clone()))
+            .collect();
+        // Sort the structures so that saving the same layout always produces the same file
+        structures.sort_by_key(|(tile_pos, _)| (tile_pos.x, tile_pos.y));
+
+        Some(Blueprint { structures })
+    }
+
+    /// The structures in this blueprint, positioned relative to the `anchor` tile.
+    pub(crate) fn placements(
+        &self,
+        anchor: TilePos,
+    ) -> impl Iterator<Item = (TilePos, ClipboardData)> + '_ {
+        self.structures
+            .iter()
+            .map(move |(offset, clipboard_data)| (*offset + anchor, clipboard_data.clone()))
+    }
+
+    /// Writes this blueprint to the file at `path`, creating any missing directories.
+    pub(crate) fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+
+        let serialized = serde_json::to_string_pretty(self)?;
+        fs::write(path, serialized)?;
+        Ok(())
+    }
+
+    /// Reads a blueprint from the file at `path`.
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Blueprint> {
+        let bytes = fs::read(path)?;
+        let blueprint = serde_json::from_slice(&bytes)?;
+        Ok(blueprint)
+    }
+}
+
+/// The blueprints that the player has saved, each stored in its own file in the `directory`.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BlueprintLibrary {
+    /// The directory that blueprints are saved to and loaded from.
+    directory: PathBuf,
+    /// The saved blueprints, keyed by name.
+    blueprints: BTreeMap<String, Blueprint>,
+    /// The name of the blueprint placed by [`PlayerAction::PlaceBlueprint`], if any.
+    selected: Option<String>,
+}
+
+impl BlueprintLibrary {
+    /// The file extension used for saved blueprints.
+    const EXTENSION: &'static str = "blueprint.json";
+
+    /// The directory that blueprints are stored in by default.
+    ///
+    /// This sits next to the game's executable, so that it does not depend on where the game was launched from.
+    pub(crate) fn default_directory() -> PathBuf {
+        std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf))
+            .unwrap_or_default()
+            .join("blueprints")
+    }
+
+    /// Loads every blueprint saved in the `directory`.
+    ///
+    /// Files that can't be read are skipped with a warning, and nothing is selected to start with.
+    pub(crate) fn load(directory: PathBuf) -> BlueprintLibrary {
+        let mut blueprints = BTreeMap::new();
+
+        if let Ok(entries) = fs::read_dir(&directory) {
+            for path in entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+            {
+                let Some(name) = path
+                    .file_name()
+                    .and_then(|file_name| file_name.to_str())
+                    .and_then(|file_name| file_name.strip_suffix(&format!(".{}", Self::EXTENSION)))
+                else {
+                    continue;
+                };
+
+                match Blueprint::load(&path) {
+                    Ok(blueprint) => {
+                        blueprints.insert(name.to_string(), blueprint);
+                    }
+                    Err(error) => {
+                        warn!("Could not load blueprint from {}: {error}", path.display())
+                    }
+                }
+            }
+        }
+
+        BlueprintLibrary {
+            directory,
+            blueprints,
+            selected: None,
+        }
+    }
+
+    /// The file that the blueprint called `name` is stored in.
+    fn path(&self, name: &str) -> PathBuf {
+        self.directory.join(format!("{name}.{}", Self::EXTENSION))
+    }
+
+    /// Saves the `blueprint` to disk under a new name, and selects it.
+    ///
+    /// Existing blueprints are never overwritten.
+    /// Returns the name that the blueprint was saved under.
+    pub(crate) fn save(&mut self, blueprint: Blueprint) -> anyhow::Result<String> {
+        let name = (1..)
+            .map(|i| format!("blueprint_{i}"))
+            .find(|name| !self.blueprints.contains_key(name) && !self.path(name).exists())
+            .unwrap();
+
+        blueprint.save(&self.path(&name))?;
+        self.blueprints.insert(name.clone(), blueprint);
+        self.selected = Some(name.clone());
+        Ok(name)
+    }
+
+    /// Selects the blueprint called `name` to be placed.
+    ///
+    /// Returns `false`, leaving the selection unchanged, if there is no such blueprint.
+    pub(crate) fn select(&mut self, name: &str) -> bool {
+        let exists = self.blueprints.contains_key(name);
+        if exists {
+            self.selected = Some(name.to_string());
+        }
+        exists
+    }
+
+    /// Selects the blueprint after the current one in alphabetical order, wrapping around to the first.
+    ///
+    /// Returns the name of the newly selected blueprint, or [`None`] if no blueprints have been saved.
+    pub(crate) fn select_next(&mut self) -> Option<&str> {
+        let next = self
+            .selected
+            .as_deref()
+            .and_then(|current| {
+                self.blueprints
+                    .range::<str, _>((Bound::Excluded(current), Bound::Unbounded))
+                    .next()
+            })
+            .or_else(|| self.blueprints.iter().next())
+            .map(|(name, _)| name.clone())?;
+
+        self.select(&next);
+        self.selected_name()
+    }
+
+    /// The name of the currently selected blueprint, if any.
+    pub(crate) fn selected_name(&self) -> Option<&str> {
+        self.selected.as_deref()
+    }
+
+    /// The currently selected blueprint, if any.
+    pub(crate) fn selected(&self) -> Option<&Blueprint> {
+        self.blueprints.get(self.selected.as_ref()?)
+    }
+
+    /// The names of every saved blueprint, in alphabetical order.
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        self.blueprints.keys().map(String::as_str)
+    }
+}
+
+/// Saves the contents of the clipboard as a blueprint.
+fn save_blueprint(
+    actions: Res<ActionState<PlayerAction>>,
+    clipboard: Res<Clipboard>,
+    mut blueprint_library: ResMut<BlueprintLibrary>,
+) {
+    if !actions.just_pressed(PlayerAction::SaveBlueprint) {
+        return;
+    }
+
+    let Some(blueprint) = Blueprint::from_clipboard(&clipboard) else {
+        info!("Copy some structures to the clipboard before saving a blueprint.");
+        return;
+    };
+
+    match blueprint_library.save(blueprint) {
+        Ok(name) => info!("Saved blueprint {name}."),
+        Err(error) => warn!("Could not save blueprint: {error}"),
+    }
+}
+
+/// Selects the next saved blueprint.
+fn cycle_blueprint(
+    actions: Res<ActionState<PlayerAction>>,
+    mut blueprint_library: ResMut<BlueprintLibrary>,
+) {
+    if !actions.just_pressed(PlayerAction::CycleBlueprint) {
+        return;
+    }
+
+    match blueprint_library.select_next() {
+        Some(name) => info!("Selected blueprint {name}."),
+        None => info!("Save a blueprint before selecting one."),
+    }
+}
+
+/// Zones the tiles around the cursor to match the selected blueprint.
+fn place_blueprint(
+    actions: Res<ActionState<PlayerAction>>,
+    cursor_pos: Res<CursorPos>,
+    blueprint_library: Res<BlueprintLibrary>,
+    mut zoning_query: Query<&mut Zoning>,
+    map_geometry: Res<MapGeometry>,
+) {
+    if !actions.just_pressed(PlayerAction::PlaceBlueprint) {
+        return;
+    }
+
+    let Some(anchor) = cursor_pos.maybe_tile_pos() else {
+        return;
+    };
+
+    let Some(blueprint) = blueprint_library.selected() else {
+        info!("Save or select a blueprint before placing it.");
+        return;
+    };
+
+    for (tile_pos, clipboard_data) in blueprint.placements(anchor) {
+        // Parts of the blueprint that fall outside of the map are skipped
+        let Some(terrain_entity) = map_geometry.get_terrain(tile_pos) else {
+            continue;
+        };
+
+        if let Ok(mut zoning) = zoning_query.get_mut(terrain_entity) {
+            *zoning = Zoning::Structure(clipboard_data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::HashMap;
+
+    use super::*;
+    use crate::{
+        asset_management::manifest::Id, items::inventory::ReservedFor,
+        simulation::geometry::Facing, structures::crafting::ActiveRecipe,
+    };
+
+    /// A clipboard containing a small, asymmetric layout of structures.
+    fn warehouse_clipboard() -> Clipboard {
+        let mut facing = Facing::default();
+        facing.rotate_left();
+
+        let storage = ClipboardData {
+            structure_id: Id::from_name("storage"),
+            facing,
+            active_recipe: ActiveRecipe::NONE,
+            reserved_for: ReservedFor::Only(Id::from_name("leuco_chunk")),
+        };
+        let leuco = ClipboardData {
+            structure_id: Id::from_name("leuco"),
+            facing: Facing::default(),
+            active_recipe: ActiveRecipe::new(Id::from_name("leuco_chunk_production")),
+            reserved_for: ReservedFor::Any,
+        };
+
+        Clipboard::Structures(HashMap::from_iter([
+            (TilePos::new(0, 0), storage.clone()),
+            (TilePos::new(1, 0), storage),
+            (TilePos::new(-1, 2), leuco),
+        ]))
+    }
+
+    #[test]
+    fn blueprints_survive_a_serde_round_trip() {
+        let blueprint = Blueprint::from_clipboard(&warehouse_clipboard()).unwrap();
+
+        let serialized = serde_json::to_string(&blueprint).unwrap();
+        let deserialized: Blueprint = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(blueprint, deserialized);
+    }
+
+    #[test]
+    fn placing_blueprints_reproduces_relative_layout() {
+        let clipboard = warehouse_clipboard();
+        let Clipboard::Structures(original) = &clipboard else {
+            unreachable!()
+        };
+        let blueprint = Blueprint::from_clipboard(&clipboard).unwrap();
+
+        let anchor = TilePos::new(5, -3);
+        let placed: HashMap<TilePos, ClipboardData> = blueprint.placements(anchor).collect();
+
+        assert_eq!(placed.len(), original.len());
+        for (offset, clipboard_data) in original.iter() {
+            assert_eq!(placed.get(&(*offset + anchor)), Some(clipboard_data));
+        }
+    }
+
+    #[test]
+    fn empty_clipboards_cannot_become_blueprints() {
+        assert_eq!(Blueprint::from_clipboard(&Clipboard::Empty), None);
+        assert_eq!(
+            Blueprint::from_clipboard(&Clipboard::Structures(HashMap::new())),
+            None
+        );
+    }
+
+    #[test]
+    fn saved_blueprints_are_kept_under_their_own_names() {
+        let directory = std::env::temp_dir().join("emergence_blueprints_test");
+        let _ = fs::remove_dir_all(&directory);
+
+        let warehouse = Blueprint::from_clipboard(&warehouse_clipboard()).unwrap();
+        let Clipboard::Structures(mut map) = warehouse_clipboard() else {
+            unreachable!()
+        };
+        map.remove(&TilePos::new(-1, 2));
+        let storage = Blueprint::from_clipboard(&Clipboard::Structures(map)).unwrap();
+
+        let mut library = BlueprintLibrary::load(directory.clone());
+        assert_eq!(library.selected(), None);
+        let warehouse_name = library.save(warehouse.clone()).unwrap();
+        let storage_name = library.save(storage.clone()).unwrap();
+        assert_ne!(warehouse_name, storage_name);
+        assert_eq!(library.selected(), Some(&storage));
+
+        // Both blueprints are still available when the game is next started
+        let mut reloaded = BlueprintLibrary::load(directory.clone());
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(
+            reloaded.names().collect::<Vec<_>>(),
+            vec![warehouse_name.as_str(), storage_name.as_str()]
+        );
+        assert!(reloaded.select(&warehouse_name));
+        assert_eq!(reloaded.selected(), Some(&warehouse));
+        assert!(!reloaded.select("missing"));
+        assert_eq!(reloaded.selected(), Some(&warehouse));
+    }
+
+    #[test]
+    fn cycling_blueprints_wraps_around() {
+        let directory = std::env::temp_dir().join("emergence_blueprints_cycle_test");
+        let _ = fs::remove_dir_all(&directory);
+
+        let mut library = BlueprintLibrary::load(directory.clone());
+        assert_eq!(library.select_next(), None);
+
+        let blueprint = Blueprint::from_clipboard(&warehouse_clipboard()).unwrap();
+        let first = library.save(blueprint.clone()).unwrap();
+        let second = library.save(blueprint).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        // Saving a blueprint selects it
+        assert_eq!(library.selected_name(), Some(second.as_str()));
+        assert_eq!(library.select_next(), Some(first.as_str()));
+        assert_eq!(library.select_next(), Some(second.as_str()));
+    }
+}
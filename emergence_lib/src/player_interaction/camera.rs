@@ -8,6 +8,7 @@ use bevy::core_pipeline::tonemapping::Tonemapping;
 use bevy::input::mouse::MouseMotion;
 use bevy::input::mouse::MouseWheel;
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 use bevy_mod_raycast::RaycastSource;
 use leafwing_input_manager::orientation::Rotation;
 use leafwing_input_manager::prelude::ActionState;
@@ -15,6 +16,7 @@ use leafwing_input_manager::prelude::ActionState;
 use crate::asset_management::manifest::Id;
 use crate::simulation::geometry::MapGeometry;
 use crate::simulation::geometry::TilePos;
+use crate::structures::colony_center::ColonyCenter;
 use crate::structures::construction::Ghost;
 use crate::structures::structure_manifest::Structure;
 use crate::terrain::terrain_manifest::Terrain;
@@ -31,7 +33,9 @@ pub(super) struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(setup_camera)
+        app.add_event::<FocusCameraOnTile>()
+            .init_resource::<PanSettings>()
+            .add_startup_system(setup_camera)
             .add_system(mousewheel_zoom.before(zoom))
             .add_system(zoom)
             .add_system(
@@ -96,6 +100,10 @@ struct CameraFocus {
     translation: Vec3,
     /// The distance from the camera to the target
     distance: f32,
+    /// How fast the focus is drifting across the map, in world units per second.
+    ///
+    /// This is only non-zero while coasting to a stop after the player stops panning.
+    pan_velocity: Vec3,
 }
 
 impl Default for CameraFocus {
@@ -103,10 +111,17 @@ impl Default for CameraFocus {
         CameraFocus {
             translation: Vec3::ZERO,
             distance: STARTING_DISTANCE_FROM_ORIGIN,
+            pan_velocity: Vec3::ZERO,
         }
     }
 }
 
+/// An event that moves the camera to look at the provided tile.
+///
+/// This is used by UI elements like the minimap, which do not have access to the camera directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FocusCameraOnTile(pub(crate) TilePos);
+
 /// Configure how the camera moves and feels.
 #[derive(Component)]
 pub(crate) struct CameraSettings {
@@ -160,6 +175,80 @@ impl Default for CameraSettings {
     }
 }
 
+/// Player preferences for how the camera pans.
+///
+/// These apply on top of the [`PlayerAction::Pan`] action, which always takes priority.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub(crate) struct PanSettings {
+    /// Should the camera pan when the cursor is near the edge of the window?
+    pub(crate) edge_scrolling: bool,
+    /// How close the cursor must be to the edge of the window to begin edge scrolling, in logical pixels.
+    pub(crate) border_thickness: f32,
+    /// How fast edge scrolling pans the camera, relative to panning with the [`PlayerAction::Pan`] action.
+    pub(crate) edge_scroll_speed: f32,
+    /// Should the camera keep drifting for a moment after the player stops panning?
+    pub(crate) momentum: bool,
+    /// The fraction of the remaining momentum that is lost each second, between 0 and 1.
+    pub(crate) momentum_decay: f32,
+}
+
+impl Default for PanSettings {
+    fn default() -> Self {
+        PanSettings {
+            edge_scrolling: true,
+            border_thickness: 20.,
+            edge_scroll_speed: 1.,
+            momentum: true,
+            momentum_decay: 0.99,
+        }
+    }
+}
+
+impl PanSettings {
+    /// The panning input caused by the cursor being at `cursor_position` in a window of `window_size`.
+    ///
+    /// Both are measured in logical pixels, with the origin at the bottom left of the window.
+    /// Each axis ramps up from 0 at the inside of the border to 1 at the edge of the window,
+    /// using the same orientation as [`PlayerAction::Pan`]: `x` to the right and `y` upwards.
+    fn edge_scroll_input(&self, cursor_position: Vec2, window_size: Vec2) -> Vec2 {
+        if !self.edge_scrolling || self.border_thickness <= 0. {
+            return Vec2::ZERO;
+        }
+
+        let axis_input = |position: f32, size: f32| {
+            let position = position.clamp(0., size);
+            if position < self.border_thickness {
+                -(1. - position / self.border_thickness)
+            } else if position > size - self.border_thickness {
+                1. - (size - position) / self.border_thickness
+            } else {
+                0.
+            }
+        };
+
+        Vec2::new(
+            axis_input(cursor_position.x, window_size.x),
+            axis_input(cursor_position.y, window_size.y),
+        ) * self.edge_scroll_speed
+    }
+
+    /// The velocity that remains after coasting with `velocity` for `delta_seconds`.
+    ///
+    /// Momentum decays exponentially, and is dropped entirely once it becomes imperceptible.
+    fn decay_momentum(&self, velocity: Vec3, delta_seconds: f32) -> Vec3 {
+        if !self.momentum {
+            return Vec3::ZERO;
+        }
+
+        let remaining = (1. - self.momentum_decay.clamp(0., 1.)).powf(delta_seconds);
+        let decayed = velocity * remaining;
+        match decayed.length_squared() < 0.01 {
+            true => Vec3::ZERO,
+            false => decayed,
+        }
+    }
+}
+
 /// Controls how the camera moves.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum CameraMode {
@@ -312,9 +401,14 @@ fn zoom(
 }
 
 /// Sets the tile that the camera is  camera's focus.
+///
+/// If nothing is selected, the camera snaps back to the [`ColonyCenter`].
+/// Any [`FocusCameraOnTile`] events are handled here too.
 fn set_camera_focus(
     actions: Res<ActionState<PlayerAction>>,
+    mut focus_events: EventReader<FocusCameraOnTile>,
     selection: Res<CurrentSelection>,
+    colony_center: Res<ColonyCenter>,
     tile_pos_query: Query<&TilePos>,
     map_geometry: Res<MapGeometry>,
     unit_query: Query<&Transform>,
@@ -331,14 +425,23 @@ fn set_camera_focus(
             | CurrentSelection::Unit(entity)
             | CurrentSelection::Structure(entity) => Some(*tile_pos_query.get(*entity).unwrap()),
             CurrentSelection::Terrain(selected_tiles) => Some(selected_tiles.center()),
-            CurrentSelection::None => None,
+            CurrentSelection::None => match settings.camera_mode {
+                CameraMode::Free => Some(colony_center.tile_pos()),
+                CameraMode::FollowUnit => None,
+            },
         };
 
         if let Some(target) = tile_to_snap_to {
             focus.translation = target.top_of_tile(&map_geometry);
+            focus.pan_velocity = Vec3::ZERO;
         }
     }
 
+    if let Some(FocusCameraOnTile(target)) = focus_events.iter().last() {
+        focus.translation = target.top_of_tile(&map_geometry);
+        focus.pan_velocity = Vec3::ZERO;
+    }
+
     // Also rotate the camera to match the orientation of the unit we're following
     if settings.camera_mode == CameraMode::FollowUnit {
         if let CurrentSelection::Unit(entity) = &*selection {
@@ -355,21 +458,40 @@ fn set_camera_focus(
 }
 
 /// Pan the camera
+///
+/// The [`PlayerAction::Pan`] action takes priority over edge scrolling.
+/// Once neither is active, any remaining momentum carries the camera onwards.
 fn pan_camera(
     mut camera_query: Query<(&Transform, &mut CameraFocus, &mut CameraSettings), With<Camera3d>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
     time: Res<Time>,
     actions: Res<ActionState<PlayerAction>>,
+    pan_settings: Res<PanSettings>,
     map_geometry: Res<MapGeometry>,
 ) {
     let (transform, mut focus, mut settings) = camera_query.single_mut();
 
-    // Pan
-    if actions.pressed(PlayerAction::Pan) {
+    let edge_scroll_input = match window_query.get_single() {
+        Ok(window) => window
+            .cursor_position()
+            .map(|cursor_position| {
+                let window_size = Vec2::new(window.width(), window.height());
+                pan_settings.edge_scroll_input(cursor_position, window_size)
+            })
+            .unwrap_or_default(),
+        Err(_) => Vec2::ZERO,
+    };
+
+    let pan_input = if actions.pressed(PlayerAction::Pan) {
+        actions.axis_pair(PlayerAction::Pan).unwrap().xy()
+    } else {
+        edge_scroll_input
+    };
+
+    if pan_input != Vec2::ZERO {
         settings.camera_mode = CameraMode::Free;
 
-        let dual_axis_data = actions.axis_pair(PlayerAction::Pan).unwrap();
-        let base_xy = dual_axis_data.xy();
-        let scaled_xy = base_xy
+        let scaled_xy = pan_input
             * time.delta_seconds()
             * settings.pan_speed.delta(time.delta())
             * focus.distance;
@@ -385,12 +507,28 @@ fn pan_camera(
         let oriented_translation = rotation.mul_vec3(unoriented_translation);
 
         focus.translation += oriented_translation;
-
-        let nearest_tile_pos = TilePos::from_world_pos(transform.translation, &map_geometry);
-        focus.translation.y = map_geometry.average_height(nearest_tile_pos, settings.float_radius);
+        if time.delta_seconds() > 0. {
+            focus.pan_velocity = oriented_translation / time.delta_seconds();
+        }
     } else {
         settings.pan_speed.reset_speed();
+
+        if settings.camera_mode == CameraMode::FollowUnit {
+            focus.pan_velocity = Vec3::ZERO;
+            return;
+        }
+
+        focus.pan_velocity = pan_settings.decay_momentum(focus.pan_velocity, time.delta_seconds());
+        if focus.pan_velocity == Vec3::ZERO {
+            return;
+        }
+
+        let coasting_translation = focus.pan_velocity * time.delta_seconds();
+        focus.translation += coasting_translation;
     }
+
+    let nearest_tile_pos = TilePos::from_world_pos(transform.translation, &map_geometry);
+    focus.translation.y = map_geometry.average_height(nearest_tile_pos, settings.float_radius);
 }
 
 /// Rotates the camera around the [`CameraFocus`].
@@ -452,3 +590,70 @@ fn compute_camera_transform(
 
     transform
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_scrolling_ramps_up_towards_the_border() {
+        let pan_settings = PanSettings::default();
+        let window_size = Vec2::new(800., 600.);
+
+        // The middle of the screen never scrolls
+        assert_eq!(
+            pan_settings.edge_scroll_input(Vec2::new(400., 300.), window_size),
+            Vec2::ZERO
+        );
+
+        // Halfway into the left border
+        assert_eq!(
+            pan_settings.edge_scroll_input(Vec2::new(10., 300.), window_size),
+            Vec2::new(-0.5, 0.)
+        );
+
+        // Pressed into the top right corner
+        assert_eq!(
+            pan_settings.edge_scroll_input(Vec2::new(800., 600.), window_size),
+            Vec2::new(1., 1.)
+        );
+
+        let disabled = PanSettings {
+            edge_scrolling: false,
+            ..default()
+        };
+        assert_eq!(
+            disabled.edge_scroll_input(Vec2::new(800., 600.), window_size),
+            Vec2::ZERO
+        );
+    }
+
+    #[test]
+    fn momentum_decays_to_a_stop() {
+        let pan_settings = PanSettings {
+            momentum_decay: 0.5,
+            ..default()
+        };
+
+        let velocity = Vec3::new(8., 0., 0.);
+        assert_eq!(
+            pan_settings.decay_momentum(velocity, 1.),
+            Vec3::new(4., 0., 0.)
+        );
+
+        let mut velocity = velocity;
+        for _ in 0..20 {
+            velocity = pan_settings.decay_momentum(velocity, 1.);
+        }
+        assert_eq!(velocity, Vec3::ZERO);
+
+        let without_momentum = PanSettings {
+            momentum: false,
+            ..default()
+        };
+        assert_eq!(
+            without_momentum.decay_momentum(Vec3::new(8., 0., 0.), 0.1),
+            Vec3::ZERO
+        );
+    }
+}
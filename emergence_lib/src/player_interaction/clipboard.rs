@@ -1,17 +1,31 @@
 //! The clipboard stores selected structures, to later be placed via zoning.
 
 use bevy::{ecs::query::WorldQuery, prelude::*, utils::HashMap};
-use hexx::{Hex, HexIterExt};
+use hexx::HexIterExt;
+use itertools::Itertools;
 use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     asset_management::manifest::Id,
+    items::{
+        inventory::{Inventory, ReservedFor},
+        item_manifest::{Item, ItemManifest},
+        ItemCount,
+    },
+    organisms::OrganismId,
     simulation::geometry::{Facing, MapGeometry, TilePos},
-    structures::{construction::Preview, crafting::ActiveRecipe, structure_manifest::Structure},
+    structures::{
+        construction::{GhostStorageFilter, Preview},
+        crafting::{ActiveRecipe, StorageInventory},
+        structure_manifest::{Structure, StructureManifest},
+    },
 };
 
 use super::{
-    cursor::CursorPos, selection::CurrentSelection, terraform::TerraformingChoice,
+    cursor::CursorPos,
+    selection::{CurrentSelection, SelectedTiles},
+    terraform::TerraformingChoice,
     InteractionSystem, PlayerAction,
 };
 
@@ -74,7 +88,7 @@ impl Clipboard {
 }
 
 /// The data copied via the clipboard for a single structure.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub(crate) struct ClipboardData {
     /// The identity of the structure.
     pub(crate) structure_id: Id<Structure>,
@@ -82,6 +96,61 @@ pub(crate) struct ClipboardData {
     pub(crate) facing: Facing,
     /// The recipe that this structure makes, if any
     pub(crate) active_recipe: ActiveRecipe,
+    /// The items that this structure can store.
+    ///
+    /// This only has an effect on storage structures.
+    pub(crate) reserved_for: ReservedFor,
+}
+
+impl ClipboardData {
+    /// Creates a new [`ClipboardData`] for `structure_id`, using the starting settings from its manifest entry.
+    pub(crate) fn new(structure_id: Id<Structure>, structure_manifest: &StructureManifest) -> Self {
+        let structure_data = structure_manifest.get(structure_id);
+
+        ClipboardData {
+            structure_id,
+            facing: Facing::default(),
+            active_recipe: structure_data.starting_recipe().clone(),
+            reserved_for: structure_data.starting_reserved_for(),
+        }
+    }
+
+    /// Copies the current configuration of an existing structure or ghost.
+    ///
+    /// Juvenile organisms are copied as their prototypical form, using its starting recipe,
+    /// so that stamping them down builds the full structure.
+    fn from_query_item(value: ClipboardQueryItem, structure_manifest: &StructureManifest) -> Self {
+        let structure_id = *value.structure_id;
+
+        if let Some(organism_variety) = &structure_manifest.get(structure_id).organism_variety {
+            if let OrganismId::Structure(prototype_id) = organism_variety.prototypical_form {
+                if prototype_id != structure_id {
+                    return ClipboardData {
+                        facing: *value.facing,
+                        ..ClipboardData::new(prototype_id, structure_manifest)
+                    };
+                }
+            }
+        }
+
+        let active_recipe = match value.active_recipe {
+            Some(recipe) => recipe.clone(),
+            None => ActiveRecipe::default(),
+        };
+
+        let reserved_for = match (value.storage_inventory, value.ghost_storage_filter) {
+            (Some(storage_inventory), _) => storage_inventory.reserved_for().clone(),
+            (None, Some(ghost_storage_filter)) => ghost_storage_filter.reserved_for.clone(),
+            (None, None) => ReservedFor::Any,
+        };
+
+        ClipboardData {
+            structure_id,
+            facing: *value.facing,
+            active_recipe,
+            reserved_for,
+        }
+    }
 }
 
 impl Clipboard {
@@ -120,23 +189,63 @@ impl Clipboard {
         }
     }
 
-    /// Rotates the contents of the clipboard around the `center`.
+    /// The structures that zoning with this clipboard would place, and where they would go.
+    ///
+    /// A single structure is placed on every one of the `relevant_tiles`,
+    /// while larger arrangements are stamped down once around the cursor.
+    pub(crate) fn planned_placements(
+        &self,
+        relevant_tiles: &SelectedTiles,
+        cursor_pos: &CursorPos,
+    ) -> Vec<(TilePos, ClipboardData)> {
+        match self {
+            Clipboard::Structures(map) if map.len() == 1 => {
+                let clipboard_data = map.values().next().unwrap();
+                relevant_tiles
+                    .selection()
+                    .iter()
+                    .map(|&tile_pos| (tile_pos, clipboard_data.clone()))
+                    .collect()
+            }
+            Clipboard::Structures(_) => match cursor_pos.maybe_tile_pos() {
+                Some(cursor_tile_pos) => self.offset_positions(cursor_tile_pos),
+                None => Vec::new(),
+            },
+            Clipboard::Terraform(_) | Clipboard::Empty => Vec::new(),
+        }
+    }
+
+    /// Rotates the whole arrangement on the clipboard by 60 degrees around its pivot at [`TilePos::ZERO`].
+    ///
+    /// Each structure's [`Facing`] is rotated by one step, and its offset from the pivot is rotated
+    /// by the same number of steps that [`Footprint::rotated`](crate::structures::construction::Footprint::rotated) would apply,
+    /// so the arrangement turns as if it were a single large structure.
     ///
     /// You must ensure that the contents are normalized first.
     fn rotate_around(&mut self, clockwise: bool) {
         if let Clipboard::Structures(map) = self {
+            let original_facing = Facing::default();
+            let mut new_facing = original_facing;
+            match clockwise {
+                true => new_facing.rotate_right(),
+                false => new_facing.rotate_left(),
+            }
+            // The number of steps that footprints are rotated by when their facing changes in this way
+            let n_rotations =
+                (new_facing.rotation_count() + 6 - original_facing.rotation_count()) % 6;
+
             let mut new_map = HashMap::with_capacity(map.capacity());
 
-            for (&original_pos, item) in map.iter_mut() {
-                let new_pos = if clockwise {
-                    item.facing.rotate_right();
-                    original_pos.right_around(Hex::ZERO)
-                } else {
-                    item.facing.rotate_left();
-                    original_pos.left_around(Hex::ZERO)
-                };
+            for (original_pos, mut item) in map.drain() {
+                match clockwise {
+                    true => item.facing.rotate_right(),
+                    false => item.facing.rotate_left(),
+                }
 
-                new_map.insert(TilePos { hex: new_pos }, item.clone());
+                let new_pos = TilePos {
+                    hex: original_pos.hex.rotate_right(n_rotations),
+                };
+                new_map.insert(new_pos, item);
             }
 
             *map = new_map;
@@ -162,21 +271,10 @@ struct ClipboardQuery {
     facing: &'static Facing,
     /// The recipe that the structure is crafting, if any
     active_recipe: Option<&'static ActiveRecipe>,
-}
-
-impl From<ClipboardQueryItem<'_>> for ClipboardData {
-    fn from(value: ClipboardQueryItem) -> ClipboardData {
-        let active_recipe = match value.active_recipe {
-            Some(recipe) => recipe.clone(),
-            None => ActiveRecipe::default(),
-        };
-
-        ClipboardData {
-            structure_id: *value.structure_id,
-            facing: *value.facing,
-            active_recipe,
-        }
-    }
+    /// The inventory of storage structures, used to copy their item filter
+    storage_inventory: Option<&'static StorageInventory>,
+    /// The item filter of ghosts that will become storage structures
+    ghost_storage_filter: Option<&'static GhostStorageFilter>,
 }
 
 /// Copies the selected structure(s) to the clipboard, to be placed later.
@@ -189,6 +287,7 @@ fn copy_selection(
     current_selection: Res<CurrentSelection>,
     structure_query: Query<ClipboardQuery, Without<Preview>>,
     map_geometry: Res<MapGeometry>,
+    structure_manifest: Res<StructureManifest>,
 ) {
     if actions.just_pressed(PlayerAction::Copy) {
         // We want to replace our selection, rather than add to it
@@ -196,9 +295,13 @@ fn copy_selection(
 
         match &*current_selection {
             CurrentSelection::Structure(entity) | CurrentSelection::Ghost(entity) => {
-                let query_item = structure_query.get(*entity).unwrap();
+                // The selected entity may have been despawned, or may not be a structure at all
+                let Ok(query_item) = structure_query.get(*entity) else {
+                    return;
+                };
                 let tile_pos = query_item.tile_pos;
-                let clipboard_data = query_item.into();
+                let clipboard_data =
+                    ClipboardData::from_query_item(query_item, &structure_manifest);
                 map.insert(*tile_pos, clipboard_data);
                 *clipboard = Clipboard::Structures(map);
                 clipboard.normalize_positions();
@@ -208,7 +311,10 @@ fn copy_selection(
                 if selected_tiles.is_empty() {
                     if let Some(hovered_tile) = cursor_pos.maybe_tile_pos() {
                         if let Some(entity) = map_geometry.get_ghost_or_structure(hovered_tile) {
-                            let clipboard_data = structure_query.get(entity).unwrap().into();
+                            let clipboard_data = ClipboardData::from_query_item(
+                                structure_query.get(entity).unwrap(),
+                                &structure_manifest,
+                            );
                             map.insert(TilePos::default(), clipboard_data);
                         }
                     }
@@ -216,7 +322,10 @@ fn copy_selection(
                     for &selected_tile_pos in selected_tiles.selection().iter() {
                         if let Some(entity) = map_geometry.get_ghost_or_structure(selected_tile_pos)
                         {
-                            let clipboard_data = structure_query.get(entity).unwrap().into();
+                            let clipboard_data = ClipboardData::from_query_item(
+                                structure_query.get(entity).unwrap(),
+                                &structure_manifest,
+                            );
                             map.insert(selected_tile_pos, clipboard_data);
                         }
                     }
@@ -228,7 +337,10 @@ fn copy_selection(
             CurrentSelection::None | CurrentSelection::Unit(_) => {
                 if let Some(cursor_tile_pos) = cursor_pos.maybe_tile_pos() {
                     if let Some(structure_entity) = map_geometry.get_structure(cursor_tile_pos) {
-                        let clipboard_data = structure_query.get(structure_entity).unwrap().into();
+                        let clipboard_data = ClipboardData::from_query_item(
+                            structure_query.get(structure_entity).unwrap(),
+                            &structure_manifest,
+                        );
                         map.insert(TilePos::default(), clipboard_data);
                         *clipboard = Clipboard::Structures(map);
                     }
@@ -254,3 +366,274 @@ fn rotate_selection(actions: Res<ActionState<PlayerAction>>, mut clipboard: ResM
         clipboard.rotate_around(true);
     }
 }
+
+/// Sums the construction materials needed to build every structure in `placements`.
+///
+/// The total is returned as an [`Inventory`] that holds exactly the required items, respecting their stack sizes.
+/// Every item is assumed to have a stack size of at least one, as checked when the manifests are validated.
+pub(crate) fn estimate_materials(
+    placements: &[(TilePos, ClipboardData)],
+    structure_manifest: &StructureManifest,
+    item_manifest: &ItemManifest,
+) -> Inventory {
+    let mut totals: HashMap<Id<Item>, usize> = HashMap::new();
+
+    for (_tile_pos, clipboard_data) in placements {
+        let materials = &structure_manifest
+            .get(clipboard_data.structure_id)
+            .construction_strategy
+            .materials;
+
+        // Construction materials are stored as empty slots, sized to the number of items required
+        for item_slot in materials.iter() {
+            *totals.entry(item_slot.item_id()).or_default() += item_slot.max_item_count();
+        }
+    }
+
+    let item_counts: Vec<ItemCount> = totals
+        .into_iter()
+        .sorted()
+        .map(|(item_id, count)| ItemCount::new(item_id, count))
+        .collect();
+
+    let slot_count = item_counts
+        .iter()
+        .map(|item_count| {
+            item_count
+                .count()
+                .div_ceil(item_manifest.get(item_count.item_id()).stack_size)
+        })
+        .sum();
+
+    let mut estimate = Inventory::new(slot_count, ReservedFor::Any);
+    // This cannot fail, as the inventory was sized to fit exactly these items
+    estimate
+        .add_items_all_or_nothing(&item_counts, item_manifest)
+        .unwrap();
+    estimate
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::{Duration, HashSet};
+
+    use super::*;
+    use crate::{
+        items::item_manifest::ItemData,
+        organisms::{energy::EnergyPool, lifecycle::Lifecycle, OrganismVariety},
+        structures::{
+            construction::Footprint,
+            crafting::InputInventory,
+            structure_manifest::{ConstructionStrategy, StructureData, StructureKind},
+        },
+    };
+
+    /// A structure of the provided `kind`, which is an organism if `prototypical_form` is provided.
+    fn structure_data(kind: StructureKind, prototypical_form: Option<&str>) -> StructureData {
+        StructureData {
+            organism_variety: prototypical_form.map(|name| OrganismVariety {
+                prototypical_form: OrganismId::Structure(Id::from_name(name)),
+                lifecycle: Lifecycle::STATIC,
+                energy_pool: EnergyPool::simple(100.),
+            }),
+            kind,
+            construction_strategy: ConstructionStrategy {
+                seedling: None,
+                work: Duration::ZERO,
+                materials: InputInventory::default(),
+                allowed_terrain_types: HashSet::new(),
+                required_adjacency: HashSet::new(),
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Copies the structure `entity` in `world`.
+    fn copy(world: &mut World, entity: Entity) -> ClipboardData {
+        let mut query = world.query::<ClipboardQuery>();
+        let query_item = query.get(world, entity).unwrap();
+        ClipboardData::from_query_item(query_item, world.resource::<StructureManifest>())
+    }
+
+    #[test]
+    fn copying_captures_structure_configuration() {
+        let acacia_recipe = ActiveRecipe::new(Id::from_name("acacia_leaf_production"));
+        let sprout_recipe = ActiveRecipe::new(Id::from_name("acacia_sprout_growth"));
+        let leuco_chunk = Id::from_name("leuco_chunk");
+
+        let mut structure_manifest = StructureManifest::new();
+        structure_manifest.insert(
+            "acacia",
+            structure_data(
+                StructureKind::Crafting {
+                    starting_recipe: acacia_recipe.clone(),
+                    output_direction: None,
+                },
+                Some("acacia"),
+            ),
+        );
+        structure_manifest.insert(
+            "acacia_sprout",
+            structure_data(
+                StructureKind::Crafting {
+                    starting_recipe: sprout_recipe.clone(),
+                    output_direction: None,
+                },
+                Some("acacia"),
+            ),
+        );
+        structure_manifest.insert(
+            "storage",
+            structure_data(
+                StructureKind::Storage {
+                    max_slot_count: 1,
+                    reserved_for: ReservedFor::Any,
+                },
+                None,
+            ),
+        );
+
+        let mut world = World::new();
+        world.insert_resource(structure_manifest);
+
+        let mut facing = Facing::default();
+        facing.rotate_right();
+
+        // Juveniles are copied as their prototypical form
+        let sprout = world
+            .spawn((
+                TilePos::ZERO,
+                Id::<Structure>::from_name("acacia_sprout"),
+                facing,
+                sprout_recipe,
+            ))
+            .id();
+        let copied_sprout = copy(&mut world, sprout);
+        assert_eq!(copied_sprout.structure_id, Id::from_name("acacia"));
+        assert_eq!(copied_sprout.active_recipe, acacia_recipe);
+        assert_eq!(copied_sprout.facing, facing);
+
+        // Storage filters are copied from the live inventory
+        let storage = world
+            .spawn((
+                TilePos::ZERO,
+                Id::<Structure>::from_name("storage"),
+                facing,
+                StorageInventory::new(1, ReservedFor::Only(leuco_chunk)),
+            ))
+            .id();
+        let copied_storage = copy(&mut world, storage);
+        assert_eq!(copied_storage.structure_id, Id::from_name("storage"));
+        assert_eq!(copied_storage.reserved_for, ReservedFor::Only(leuco_chunk));
+        assert_eq!(copied_storage.facing, facing);
+    }
+
+    /// The tiles covered by `clipboard`, if each structure had the provided `footprint`.
+    fn covered_tiles(clipboard: &Clipboard, footprint: &Footprint) -> HashSet<TilePos> {
+        clipboard
+            .offset_positions(TilePos::ZERO)
+            .into_iter()
+            .flat_map(|(tile_pos, data)| footprint.rotated(data.facing).in_world_space(tile_pos))
+            .collect()
+    }
+
+    #[test]
+    fn rotating_blueprints_turns_the_whole_arrangement() {
+        let clipboard_data = |name: &str| ClipboardData {
+            structure_id: Id::from_name(name),
+            facing: Facing::default(),
+            active_recipe: ActiveRecipe::default(),
+            reserved_for: ReservedFor::Any,
+        };
+
+        let mut clipboard = Clipboard::Structures(HashMap::from_iter([
+            (TilePos::ZERO, clipboard_data("warehouse")),
+            (TilePos::new(0, 2), clipboard_data("loom")),
+        ]));
+        let Clipboard::Structures(original) = &clipboard else {
+            unreachable!()
+        };
+        let original = original.clone();
+
+        // A non-symmetric footprint, so that any mismatch between facings and offsets shows up
+        let footprint = Footprint {
+            set: HashSet::from_iter([TilePos::ZERO, TilePos::new(1, 0)]),
+        };
+        let arrangement = Footprint {
+            set: covered_tiles(&clipboard, &footprint),
+        };
+
+        clipboard.rotate_around(true);
+        let Clipboard::Structures(rotated) = &clipboard else {
+            unreachable!()
+        };
+        assert_ne!(*rotated, original);
+        assert_eq!(rotated.len(), 2);
+        let new_facing = rotated.values().next().unwrap().facing;
+        assert!(rotated.values().all(|data| data.facing == new_facing));
+
+        // The arrangement turns exactly as a single structure with the same overall footprint would
+        assert_eq!(
+            covered_tiles(&clipboard, &footprint),
+            arrangement.rotated(new_facing).set
+        );
+
+        // Rotating back restores the original layout
+        clipboard.rotate_around(false);
+        let Clipboard::Structures(restored) = &clipboard else {
+            unreachable!()
+        };
+        assert_eq!(*restored, original);
+
+        // As does a full turn of six 60 degree steps, in either direction
+        for clockwise in [true, false] {
+            for _ in 0..6 {
+                clipboard.rotate_around(clockwise);
+            }
+            let Clipboard::Structures(turned) = &clipboard else {
+                unreachable!()
+            };
+            assert_eq!(*turned, original);
+        }
+    }
+
+    #[test]
+    fn material_estimates_sum_over_every_placement() {
+        let leuco_chunk = Id::from_name("leuco_chunk");
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let mut ant_hive = structure_data(
+            StructureKind::Storage {
+                max_slot_count: 1,
+                reserved_for: ReservedFor::Any,
+            },
+            None,
+        );
+        ant_hive.construction_strategy.materials = InputInventory {
+            inventory: Inventory::new_from_item(leuco_chunk, 4),
+        };
+        let mut structure_manifest = StructureManifest::new();
+        structure_manifest.insert("ant_hive", ant_hive);
+
+        let mut clipboard = Clipboard::Empty;
+        clipboard.set_to_structure(Some(ClipboardData::new(
+            Id::from_name("ant_hive"),
+            &structure_manifest,
+        )));
+
+        let mut line = SelectedTiles::default();
+        for x in 0..3 {
+            line.add_tile(TilePos::new(x, 0));
+        }
+
+        let placements = clipboard.planned_placements(&line, &CursorPos::default());
+        assert_eq!(placements.len(), 3);
+
+        let estimate = estimate_materials(&placements, &structure_manifest, &item_manifest);
+        assert_eq!(estimate.item_count(leuco_chunk), 3 * 4);
+        // Twelve chunks take up two stacks, and no more slots are allocated than that
+        assert_eq!(estimate.iter().count(), 2);
+        assert_eq!(estimate.free_slot_count(), 0);
+    }
+}
@@ -0,0 +1,637 @@
+//! A developer console for spawning and inspecting entities by typing text commands.
+//!
+//! Press the backquote key to open or close the console.
+//! While it is open, all other player actions and abilities are disabled so that typing does not move the camera or spend intent.
+
+use std::fmt::Display;
+
+use bevy::{prelude::*, window::ReceivedCharacter};
+use leafwing_input_manager::plugin::ToggleActions;
+
+use crate::{
+    asset_management::manifest::Id,
+    infovis::{OverlayType, TileOverlay},
+    items::{
+        item_manifest::{Item, ItemManifest},
+        ItemCount,
+    },
+    signals::SignalType,
+    simulation::{factions::Faction, geometry::TilePos},
+    structures::{
+        commands::StructureCommandsExt,
+        crafting::{InputInventory, StorageInventory},
+        planner::{ProductionTarget, ProductionTargets},
+        structure_manifest::{Structure, StructureManifest},
+    },
+    units::{
+        commands::UnitCommandsExt,
+        goals::Goal,
+        unit_manifest::{Unit, UnitManifest},
+    },
+};
+
+use super::{
+    abilities::IntentAbility, blueprints::BlueprintLibrary, camera::FocusCameraOnTile,
+    clipboard::ClipboardData, selection::CurrentSelection, PlayerAction,
+};
+
+/// Reads, parses and runs developer console commands.
+pub(super) struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .add_system(toggle_console)
+            .add_system(edit_console_input.after(toggle_console))
+            .add_system(run_console_commands.after(edit_console_input));
+    }
+}
+
+/// The key used to open and close the console.
+const TOGGLE_CONSOLE_KEY: KeyCode = KeyCode::Grave;
+
+/// The state of the developer console.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Console {
+    /// Is the console currently accepting input?
+    pub(crate) open: bool,
+    /// The text typed so far.
+    pub(crate) input: String,
+    /// The commands entered, and their responses, oldest first.
+    pub(crate) history: Vec<String>,
+    /// Lines that have been submitted but not yet run.
+    pending: Vec<String>,
+}
+
+impl Console {
+    /// The maximum number of lines of history that are kept.
+    pub(crate) const MAX_HISTORY: usize = 20;
+
+    /// Records a `line` of output, discarding the oldest lines if needed.
+    fn log(&mut self, line: impl Into<String>) {
+        self.history.push(line.into());
+        let n_excess = self.history.len().saturating_sub(Console::MAX_HISTORY);
+        self.history.drain(..n_excess);
+    }
+}
+
+/// A command that can be typed into the [`Console`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ConsoleCommand {
+    /// Spawns a structure, using the starting settings from its manifest entry.
+    ///
+    /// Written as `spawn structure <name> <x>,<y>`.
+    SpawnStructure {
+        /// The type of structure to spawn.
+        structure_id: Id<Structure>,
+        /// Where to spawn it.
+        tile_pos: TilePos,
+    },
+    /// Spawns a unit, which starts out wandering.
+    ///
+    /// Written as `spawn unit <name> <x>,<y>`.
+    SpawnUnit {
+        /// The type of unit to spawn.
+        unit_id: Id<Unit>,
+        /// Where to spawn it.
+        tile_pos: TilePos,
+    },
+    /// Adds items to the inventory of the selected structure.
+    ///
+    /// Written as `give item <name> <count>`.
+    GiveItem(ItemCount),
+    /// Moves the camera to look at a tile.
+    ///
+    /// Written as `tp camera <x>,<y>`.
+    TeleportCamera(TilePos),
+    /// Colors each tile by the strength of a single signal type, or hides the overlay.
+    ///
+    /// Written as `overlay <kind> <name>`, such as `overlay pull acacia_leaf`, or `overlay off`.
+    SetOverlay(OverlayType),
+    /// Sets or clears the [`ProductionTarget`] of an item, which the production planner builds new producers to meet.
+    ///
+    /// Written as `target <name> <stock> <items_per_minute>`, such as `target leuco_chunk 50 10`, or `target <name> off`.
+    SetProductionTarget {
+        /// The item to plan production for.
+        item_id: Id<Item>,
+        /// The new target, or [`None`] to stop planning production of this item.
+        target: Option<ProductionTarget>,
+    },
+    /// Selects which saved blueprint is placed.
+    ///
+    /// Written as `blueprint <name>`.
+    SelectBlueprint(String),
+    /// Lists the names of the saved blueprints.
+    ///
+    /// Written as `blueprint list`.
+    ListBlueprints,
+    /// Lists the available commands.
+    ///
+    /// Written as `help`.
+    Help,
+}
+
+/// The reasons why text could not be parsed into a [`ConsoleCommand`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ConsoleParseError {
+    /// No command was entered.
+    Empty,
+    /// The command was not recognized.
+    UnknownCommand(String),
+    /// A required argument was not provided.
+    MissingArgument(&'static str),
+    /// More arguments were provided than the command accepts.
+    UnexpectedArgument(String),
+    /// A tile position was not written as `x,y`.
+    InvalidTilePos(String),
+    /// An item count was not a non-negative integer.
+    InvalidCount(String),
+    /// A production rate was not a non-negative number.
+    InvalidRate(String),
+}
+
+impl Display for ConsoleParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConsoleParseError::Empty => write!(f, "No command entered"),
+            ConsoleParseError::UnknownCommand(command) => {
+                write!(f, "Unknown command '{command}', try 'help'")
+            }
+            ConsoleParseError::MissingArgument(argument) => write!(f, "Missing {argument}"),
+            ConsoleParseError::UnexpectedArgument(argument) => {
+                write!(f, "Unexpected argument '{argument}'")
+            }
+            ConsoleParseError::InvalidTilePos(text) => {
+                write!(f, "'{text}' is not a tile position, expected x,y")
+            }
+            ConsoleParseError::InvalidCount(text) => write!(f, "'{text}' is not a count"),
+            ConsoleParseError::InvalidRate(text) => write!(f, "'{text}' is not a rate"),
+        }
+    }
+}
+
+impl ConsoleCommand {
+    /// A summary of the available commands, shown by [`ConsoleCommand::Help`].
+    const HELP: &'static str = "spawn structure <name> <x>,<y> | spawn unit <name> <x>,<y> | give item <name> <count> | tp camera <x>,<y> | overlay <kind> <name> | overlay off | target <name> <stock> <items_per_minute> | target <name> off | blueprint <name> | blueprint list";
+
+    /// Parses a line of console input.
+    ///
+    /// Names are converted to ids using [`Id::from_name`], and are not checked against the manifests here.
+    pub(crate) fn parse(text: &str) -> Result<ConsoleCommand, ConsoleParseError> {
+        let mut words = text.split_whitespace();
+        let mut next = |argument: &'static str| {
+            words
+                .next()
+                .ok_or(ConsoleParseError::MissingArgument(argument))
+        };
+
+        let command = match next("command") {
+            Ok(verb) => verb,
+            Err(_) => return Err(ConsoleParseError::Empty),
+        };
+
+        let parsed = match command {
+            "spawn" => match next("entity kind")? {
+                "structure" => ConsoleCommand::SpawnStructure {
+                    structure_id: Id::from_name(next("structure name")?),
+                    tile_pos: parse_tile_pos(next("tile position")?)?,
+                },
+                "unit" => ConsoleCommand::SpawnUnit {
+                    unit_id: Id::from_name(next("unit name")?),
+                    tile_pos: parse_tile_pos(next("tile position")?)?,
+                },
+                other => return Err(ConsoleParseError::UnknownCommand(format!("spawn {other}"))),
+            },
+            "give" => match next("'item'")? {
+                "item" => {
+                    let item_id: Id<Item> = Id::from_name(next("item name")?);
+                    let count_text = next("count")?;
+                    let count = count_text
+                        .parse()
+                        .map_err(|_| ConsoleParseError::InvalidCount(count_text.to_string()))?;
+                    ConsoleCommand::GiveItem(ItemCount::new(item_id, count))
+                }
+                other => return Err(ConsoleParseError::UnknownCommand(format!("give {other}"))),
+            },
+            "tp" => match next("'camera'")? {
+                "camera" => ConsoleCommand::TeleportCamera(parse_tile_pos(next("tile position")?)?),
+                other => return Err(ConsoleParseError::UnknownCommand(format!("tp {other}"))),
+            },
+            "overlay" => match next("signal kind or 'off'")? {
+                "off" => ConsoleCommand::SetOverlay(OverlayType::None),
+                kind => {
+                    let signal_type = match kind {
+                        "push" => SignalType::Push(Id::from_name(next("item name")?)),
+                        "pull" => SignalType::Pull(Id::from_name(next("item name")?)),
+                        "contains" => SignalType::Contains(Id::from_name(next("item name")?)),
+                        "stores" => SignalType::Stores(Id::from_name(next("item name")?)),
+                        "contained" => SignalType::Contained(Id::from_name(next("item name")?)),
+                        "work" => SignalType::Work(Id::from_name(next("structure name")?)),
+                        "demolish" => SignalType::Demolish(Id::from_name(next("structure name")?)),
+                        "repair" => SignalType::Repair(Id::from_name(next("structure name")?)),
+                        "upgrade" => SignalType::Upgrade(Id::from_name(next("structure name")?)),
+                        "unit" => SignalType::Unit(Id::from_name(next("unit name")?)),
+                        "terraform" => SignalType::Terraform,
+                        "stuck" => SignalType::Stuck,
+                        other => {
+                            return Err(ConsoleParseError::UnknownCommand(format!(
+                                "overlay {other}"
+                            )))
+                        }
+                    };
+                    ConsoleCommand::SetOverlay(OverlayType::Single(signal_type))
+                }
+            },
+            "target" => {
+                let item_id: Id<Item> = Id::from_name(next("item name")?);
+                let target = match next("desired stock or 'off'")? {
+                    "off" => None,
+                    stock_text => {
+                        let desired_stock = stock_text
+                            .parse()
+                            .map_err(|_| ConsoleParseError::InvalidCount(stock_text.to_string()))?;
+                        let rate_text = next("items per minute")?;
+                        let items_per_minute = rate_text
+                            .parse::<f32>()
+                            .ok()
+                            .filter(|rate| rate.is_finite() && *rate >= 0.)
+                            .ok_or_else(|| ConsoleParseError::InvalidRate(rate_text.to_string()))?;
+                        Some(ProductionTarget {
+                            desired_stock,
+                            items_per_minute,
+                        })
+                    }
+                };
+                ConsoleCommand::SetProductionTarget { item_id, target }
+            }
+            "blueprint" => match next("blueprint name or 'list'")? {
+                "list" => ConsoleCommand::ListBlueprints,
+                name => ConsoleCommand::SelectBlueprint(name.to_string()),
+            },
+            "help" => ConsoleCommand::Help,
+            other => return Err(ConsoleParseError::UnknownCommand(other.to_string())),
+        };
+
+        match words.next() {
+            Some(extra) => Err(ConsoleParseError::UnexpectedArgument(extra.to_string())),
+            None => Ok(parsed),
+        }
+    }
+}
+
+/// Parses a tile position written as `x,y`.
+fn parse_tile_pos(text: &str) -> Result<TilePos, ConsoleParseError> {
+    let invalid = || ConsoleParseError::InvalidTilePos(text.to_string());
+
+    let (x, y) = text.split_once(',').ok_or_else(invalid)?;
+    let x = x.trim().parse().map_err(|_| invalid())?;
+    let y = y.trim().parse().map_err(|_| invalid())?;
+
+    Ok(TilePos::new(x, y))
+}
+
+/// Opens and closes the console, disabling other player actions and abilities while it is open.
+fn toggle_console(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut console: ResMut<Console>,
+    mut toggle_actions: ResMut<ToggleActions<PlayerAction>>,
+    mut toggle_abilities: ResMut<ToggleActions<IntentAbility>>,
+) {
+    if keyboard_input.just_pressed(TOGGLE_CONSOLE_KEY) {
+        console.open = !console.open;
+        console.input.clear();
+        toggle_actions.enabled = !console.open;
+        toggle_abilities.enabled = !console.open;
+    }
+}
+
+/// Types characters into the open console, submitting the line when enter is pressed.
+fn edit_console_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut character_events: EventReader<ReceivedCharacter>,
+    mut console: ResMut<Console>,
+) {
+    if !console.open {
+        character_events.clear();
+        return;
+    }
+
+    for event in character_events.iter() {
+        // The key used to open the console should not end up in the input
+        if !event.char.is_control() && event.char != '`' {
+            console.input.push(event.char);
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        console.input.pop();
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        let line = std::mem::take(&mut console.input);
+        console.pending.push(line);
+    }
+}
+
+/// Runs any console commands that have been submitted.
+fn run_console_commands(
+    mut console: ResMut<Console>,
+    mut commands: Commands,
+    structure_manifest: Res<StructureManifest>,
+    unit_manifest: Res<UnitManifest>,
+    item_manifest: Res<ItemManifest>,
+    current_selection: Res<CurrentSelection>,
+    mut inventory_query: Query<(Option<&mut StorageInventory>, Option<&mut InputInventory>)>,
+    mut focus_events: EventWriter<FocusCameraOnTile>,
+    mut tile_overlay: ResMut<TileOverlay>,
+    mut production_targets: ResMut<ProductionTargets>,
+    mut blueprint_library: ResMut<BlueprintLibrary>,
+) {
+    if console.pending.is_empty() {
+        return;
+    }
+
+    for line in std::mem::take(&mut console.pending) {
+        console.log(format!("> {line}"));
+
+        let response = match ConsoleCommand::parse(&line) {
+            Err(error) => error.to_string(),
+            Ok(ConsoleCommand::SpawnStructure {
+                structure_id,
+                tile_pos,
+            }) => {
+                if structure_manifest.contains(structure_id) {
+                    let data = ClipboardData::new(structure_id, &structure_manifest);
+                    commands.spawn_structure(tile_pos, data, Faction::COLONY);
+                    format!(
+                        "Spawning {} at {tile_pos}",
+                        structure_manifest.name(structure_id)
+                    )
+                } else {
+                    format!("Unknown structure {structure_id:?}")
+                }
+            }
+            Ok(ConsoleCommand::SpawnUnit { unit_id, tile_pos }) => {
+                if unit_manifest.contains(unit_id) {
+                    commands.spawn_unit(tile_pos, unit_id, Goal::default(), Faction::COLONY);
+                    format!("Spawning {} at {tile_pos}", unit_manifest.name(unit_id))
+                } else {
+                    format!("Unknown unit {unit_id:?}")
+                }
+            }
+            Ok(ConsoleCommand::GiveItem(item_count)) => give_item(
+                &item_count,
+                &current_selection,
+                &mut inventory_query,
+                &item_manifest,
+            ),
+            Ok(ConsoleCommand::TeleportCamera(tile_pos)) => {
+                focus_events.send(FocusCameraOnTile(tile_pos));
+                format!("Moving the camera to {tile_pos}")
+            }
+            Ok(ConsoleCommand::SetOverlay(overlay_type)) => {
+                let known = match overlay_type {
+                    OverlayType::Single(signal_type) => signal_type_is_known(
+                        signal_type,
+                        &item_manifest,
+                        &structure_manifest,
+                        &unit_manifest,
+                    ),
+                    _ => true,
+                };
+
+                if known {
+                    tile_overlay.overlay_type = overlay_type;
+                    match overlay_type {
+                        OverlayType::None => "Hiding the overlay".to_string(),
+                        _ => format!("Showing {}", line.trim_start_matches("overlay").trim()),
+                    }
+                } else {
+                    format!("Unknown signal type {overlay_type:?}")
+                }
+            }
+            Ok(ConsoleCommand::SetProductionTarget { item_id, target }) => {
+                if item_manifest.contains(item_id) {
+                    let item_name = item_manifest.name(item_id);
+                    match target {
+                        Some(target) => {
+                            production_targets.set(item_id, target);
+                            format!(
+                                "Keeping {} {item_name} in stock, making {} per minute",
+                                target.desired_stock, target.items_per_minute
+                            )
+                        }
+                        None => {
+                            production_targets.clear(item_id);
+                            format!("No longer planning production of {item_name}")
+                        }
+                    }
+                } else {
+                    format!("Unknown item {item_id:?}")
+                }
+            }
+            Ok(ConsoleCommand::ListBlueprints) => {
+                let names: Vec<&str> = blueprint_library.names().collect();
+                match names.is_empty() {
+                    true => "No blueprints have been saved".to_string(),
+                    false => format!("Saved blueprints: {}", names.join(", ")),
+                }
+            }
+            Ok(ConsoleCommand::SelectBlueprint(name)) => {
+                if blueprint_library.select(&name) {
+                    format!("Placing blueprint {name}")
+                } else {
+                    format!("Unknown blueprint {name}, try 'blueprint list'")
+                }
+            }
+            Ok(ConsoleCommand::Help) => ConsoleCommand::HELP.to_string(),
+        };
+
+        console.log(response);
+    }
+}
+
+/// Are the items, structures or units referred to by `signal_type` in their manifests?
+fn signal_type_is_known(
+    signal_type: SignalType,
+    item_manifest: &ItemManifest,
+    structure_manifest: &StructureManifest,
+    unit_manifest: &UnitManifest,
+) -> bool {
+    match signal_type {
+        SignalType::Push(item_id)
+        | SignalType::Pull(item_id)
+        | SignalType::Contains(item_id)
+        | SignalType::Stores(item_id)
+        | SignalType::Contained(item_id) => item_manifest.contains(item_id),
+        SignalType::Work(structure_id)
+        | SignalType::Demolish(structure_id)
+        | SignalType::Repair(structure_id)
+        | SignalType::Upgrade(structure_id) => structure_manifest.contains(structure_id),
+        SignalType::Unit(unit_id) => unit_manifest.contains(unit_id),
+        SignalType::Terraform | SignalType::Stuck => true,
+    }
+}
+
+/// Adds the `item_count` to the storage or input inventory of the selected structure.
+///
+/// Returns a description of the result, to be shown in the console.
+fn give_item(
+    item_count: &ItemCount,
+    current_selection: &CurrentSelection,
+    inventory_query: &mut Query<(Option<&mut StorageInventory>, Option<&mut InputInventory>)>,
+    item_manifest: &ItemManifest,
+) -> String {
+    let item_id = item_count.item_id();
+    if !item_manifest.contains(item_id) {
+        return format!("Unknown item {item_id:?}");
+    }
+
+    let CurrentSelection::Structure(structure_entity) = current_selection else {
+        return "Select a structure to give items to".to_string();
+    };
+
+    let inventory = match inventory_query.get_mut(*structure_entity) {
+        Ok((Some(storage_inventory), _)) => &mut storage_inventory.into_inner().inventory,
+        Ok((None, Some(input_inventory))) => &mut input_inventory.into_inner().inventory,
+        _ => return "The selected structure has no inventory to give items to".to_string(),
+    };
+
+    let item_name = item_manifest.name(item_id);
+    match inventory.try_add_item(item_count, item_manifest) {
+        Ok(()) => format!("Gave {} {item_name}", item_count.count()),
+        Err(error) => format!(
+            "Gave {} {item_name}, {} did not fit",
+            item_count.count() - error.excess_count.count(),
+            error.excess_count.count()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn console_text_is_parsed_into_commands() {
+        assert_eq!(
+            ConsoleCommand::parse("spawn structure leuco 0,0"),
+            Ok(ConsoleCommand::SpawnStructure {
+                structure_id: Id::from_name("leuco"),
+                tile_pos: TilePos::new(0, 0),
+            })
+        );
+        assert_eq!(
+            ConsoleCommand::parse("  spawn   unit ant -2,3 "),
+            Ok(ConsoleCommand::SpawnUnit {
+                unit_id: Id::from_name("ant"),
+                tile_pos: TilePos::new(-2, 3),
+            })
+        );
+        assert_eq!(
+            ConsoleCommand::parse("give item acacia_leaf 10"),
+            Ok(ConsoleCommand::GiveItem(ItemCount::new(
+                Id::from_name("acacia_leaf"),
+                10
+            )))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("tp camera 5,5"),
+            Ok(ConsoleCommand::TeleportCamera(TilePos::new(5, 5)))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("overlay pull acacia_leaf"),
+            Ok(ConsoleCommand::SetOverlay(OverlayType::Single(
+                SignalType::Pull(Id::from_name("acacia_leaf"))
+            )))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("overlay terraform"),
+            Ok(ConsoleCommand::SetOverlay(OverlayType::Single(
+                SignalType::Terraform
+            )))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("overlay off"),
+            Ok(ConsoleCommand::SetOverlay(OverlayType::None))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("target leuco_chunk 50 7.5"),
+            Ok(ConsoleCommand::SetProductionTarget {
+                item_id: Id::from_name("leuco_chunk"),
+                target: Some(ProductionTarget {
+                    desired_stock: 50,
+                    items_per_minute: 7.5,
+                }),
+            })
+        );
+        assert_eq!(
+            ConsoleCommand::parse("target leuco_chunk off"),
+            Ok(ConsoleCommand::SetProductionTarget {
+                item_id: Id::from_name("leuco_chunk"),
+                target: None,
+            })
+        );
+        assert_eq!(
+            ConsoleCommand::parse("blueprint blueprint_2"),
+            Ok(ConsoleCommand::SelectBlueprint("blueprint_2".to_string()))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("blueprint list"),
+            Ok(ConsoleCommand::ListBlueprints)
+        );
+        assert_eq!(ConsoleCommand::parse("help"), Ok(ConsoleCommand::Help));
+    }
+
+    #[test]
+    fn malformed_console_text_is_rejected() {
+        assert_eq!(ConsoleCommand::parse("   "), Err(ConsoleParseError::Empty));
+        assert_eq!(
+            ConsoleCommand::parse("dance"),
+            Err(ConsoleParseError::UnknownCommand("dance".to_string()))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("spawn building leuco 0,0"),
+            Err(ConsoleParseError::UnknownCommand(
+                "spawn building".to_string()
+            ))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("spawn structure leuco"),
+            Err(ConsoleParseError::MissingArgument("tile position"))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("spawn structure leuco 0;0"),
+            Err(ConsoleParseError::InvalidTilePos("0;0".to_string()))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("give item acacia_leaf lots"),
+            Err(ConsoleParseError::InvalidCount("lots".to_string()))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("overlay smell leuco"),
+            Err(ConsoleParseError::UnknownCommand(
+                "overlay smell".to_string()
+            ))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("target leuco_chunk 50 -1"),
+            Err(ConsoleParseError::InvalidRate("-1".to_string()))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("tp camera 1,1 now"),
+            Err(ConsoleParseError::UnexpectedArgument("now".to_string()))
+        );
+    }
+
+    #[test]
+    fn console_history_is_capped() {
+        let mut console = Console::default();
+        for i in 0..Console::MAX_HISTORY + 5 {
+            console.log(i.to_string());
+        }
+
+        assert_eq!(console.history.len(), Console::MAX_HISTORY);
+        assert_eq!(console.history[0], "5");
+    }
+}
@@ -0,0 +1,390 @@
+//! Delivery contracts, which guarantee a route for an item between a specific source and sink structure.
+//!
+//! Contracts are layered on top of the ordinary signals:
+//! each one emits a strong [`SignalType::Push`] at its source and a [`SignalType::Pull`] at its sink,
+//! biasing units to carry the item along that exact route.
+//! Units delivering the item will drop it off at the sink even if it is a storage structure,
+//! which ordinary deliveries avoid.
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+    asset_management::{manifest::Id, AssetState},
+    items::{
+        inventory::Inventory,
+        item_manifest::{Item, ItemManifest},
+    },
+    signals::{SignalStrength, SignalType, Signals},
+    simulation::{geometry::TilePos, SimulationSet},
+    structures::crafting::{InputInventory, OutputInventory, StorageInventory},
+    units::item_interaction::UnitInventory,
+};
+
+use super::{selection::CurrentSelection, InteractionSystem, PlayerAction};
+
+/// Creates, cancels and fulfills delivery contracts.
+pub(super) struct ContractsPlugin;
+
+impl Plugin for ContractsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Contracts>()
+            .add_system(
+                designate_contracts
+                    .after(InteractionSystem::SelectTiles)
+                    .run_if(in_state(AssetState::Ready)),
+            )
+            .add_system(
+                emit_contract_signals
+                    .in_set(SimulationSet)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+    }
+}
+
+/// An agreement to move `item_id` from the `source` structure to the `sink` structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Contract {
+    /// The type of item to move.
+    pub(crate) item_id: Id<Item>,
+    /// The structure that the item is taken from.
+    pub(crate) source: Entity,
+    /// The structure that the item is brought to.
+    pub(crate) sink: Entity,
+}
+
+/// All of the delivery contracts that the player has set up.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct Contracts {
+    /// The active contracts, oldest first.
+    contracts: Vec<Contract>,
+    /// The source of the contract that is currently being designated, if any.
+    ///
+    /// The contract is created once the player picks a sink.
+    pending_source: Option<Entity>,
+}
+
+impl Contracts {
+    /// The strength of the signals emitted at each end of a contract.
+    ///
+    /// This is stronger than the signals emitted by ordinary structures, so contracted routes are preferred.
+    pub(crate) const SIGNAL_STRENGTH: f32 = 20.;
+
+    /// Adds a new contract, returning `false` if it was a duplicate or its source and sink were the same.
+    pub(crate) fn add(&mut self, contract: Contract) -> bool {
+        if contract.source == contract.sink || self.contracts.contains(&contract) {
+            return false;
+        }
+
+        self.contracts.push(contract);
+        true
+    }
+
+    /// Cancels every contract that `structure_entity` is the source or sink of, returning the number cancelled.
+    pub(crate) fn cancel_involving(&mut self, structure_entity: Entity) -> usize {
+        let n_contracts = self.contracts.len();
+        self.contracts.retain(|contract| {
+            contract.source != structure_entity && contract.sink != structure_entity
+        });
+
+        if self.pending_source == Some(structure_entity) {
+            self.pending_source = None;
+        }
+
+        n_contracts - self.contracts.len()
+    }
+
+    /// The active contracts, oldest first.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Contract> {
+        self.contracts.iter()
+    }
+
+    /// Is `structure_entity` the sink of a contract for `item_id`?
+    pub(crate) fn is_sink_for(&self, structure_entity: Entity, item_id: Id<Item>) -> bool {
+        self.contracts
+            .iter()
+            .any(|contract| contract.sink == structure_entity && contract.item_id == item_id)
+    }
+
+    /// The source of the contract that is currently being designated, if any.
+    pub(crate) fn pending_source(&self) -> Option<Entity> {
+        self.pending_source
+    }
+}
+
+/// The inventory that items can be taken out of, for structures that can act as a contract source.
+fn source_inventory<'a>(
+    (_, maybe_output, maybe_storage): (
+        Option<&'a InputInventory>,
+        Option<&'a OutputInventory>,
+        Option<&'a StorageInventory>,
+    ),
+) -> Option<&'a Inventory> {
+    match (maybe_output, maybe_storage) {
+        (Some(output_inventory), _) => Some(&output_inventory.inventory),
+        (None, Some(storage_inventory)) => Some(&storage_inventory.inventory),
+        (None, None) => None,
+    }
+}
+
+/// The inventory that items can be put into, for structures that can act as a contract sink.
+fn sink_inventory<'a>(
+    (maybe_input, _, maybe_storage): (
+        Option<&'a InputInventory>,
+        Option<&'a OutputInventory>,
+        Option<&'a StorageInventory>,
+    ),
+) -> Option<&'a Inventory> {
+    match (maybe_input, maybe_storage) {
+        (Some(input_inventory), _) => Some(&input_inventory.inventory),
+        (None, Some(storage_inventory)) => Some(&storage_inventory.inventory),
+        (None, None) => None,
+    }
+}
+
+/// Picks the item that a contract between the `source` and `sink` inventories should move.
+///
+/// This is the first item held by the source that the sink has room for a unit's load of.
+fn contract_item(
+    source: &Inventory,
+    sink: &Inventory,
+    item_manifest: &ItemManifest,
+) -> Option<Id<Item>> {
+    source
+        .iter()
+        .map(|item_slot| item_slot.item_id())
+        .find(|&item_id| sink.can_fit(&UnitInventory::load(item_id), item_manifest))
+}
+
+/// Designates contracts between the selected structures, or cancels the contracts of the selected structure.
+///
+/// The first structure selected when designating becomes the source, and the second becomes the sink.
+fn designate_contracts(
+    actions: Res<ActionState<PlayerAction>>,
+    current_selection: Res<CurrentSelection>,
+    inventory_query: Query<AnyOf<(&InputInventory, &OutputInventory, &StorageInventory)>>,
+    item_manifest: Res<ItemManifest>,
+    mut contracts: ResMut<Contracts>,
+) {
+    let designate = actions.just_pressed(PlayerAction::DesignateContract);
+    let cancel = actions.just_pressed(PlayerAction::CancelContracts);
+    if !designate && !cancel {
+        return;
+    }
+
+    let CurrentSelection::Structure(structure_entity) = *current_selection else {
+        return;
+    };
+
+    if cancel {
+        contracts.cancel_involving(structure_entity);
+        return;
+    }
+
+    let Ok(inventories) = inventory_query.get(structure_entity) else {
+        return;
+    };
+
+    match contracts.pending_source.take() {
+        None => {
+            if source_inventory(inventories).is_some() {
+                contracts.pending_source = Some(structure_entity);
+            }
+        }
+        // Designating the same structure twice backs out of the contract
+        Some(source) if source == structure_entity => (),
+        Some(source) => {
+            let Ok(source_inventories) = inventory_query.get(source) else {
+                return;
+            };
+
+            let (Some(source_inventory), Some(sink_inventory)) = (
+                source_inventory(source_inventories),
+                sink_inventory(inventories),
+            ) else {
+                return;
+            };
+
+            if let Some(item_id) = contract_item(source_inventory, sink_inventory, &item_manifest) {
+                contracts.add(Contract {
+                    item_id,
+                    source,
+                    sink: structure_entity,
+                });
+            }
+        }
+    }
+}
+
+/// Emits signals at both ends of each contract, and drops contracts whose structures no longer exist.
+fn emit_contract_signals(
+    mut contracts: ResMut<Contracts>,
+    structure_query: Query<(
+        &TilePos,
+        AnyOf<(&InputInventory, &OutputInventory, &StorageInventory)>,
+    )>,
+    item_manifest: Res<ItemManifest>,
+    mut signals: ResMut<Signals>,
+) {
+    // Avoid triggering change detection unless a contract is actually broken
+    let broken = contracts.iter().any(|contract| {
+        !structure_query.contains(contract.source) || !structure_query.contains(contract.sink)
+    });
+    if broken {
+        contracts.contracts.retain(|contract| {
+            structure_query.contains(contract.source) && structure_query.contains(contract.sink)
+        });
+    }
+
+    let signal_strength = SignalStrength::new(Contracts::SIGNAL_STRENGTH);
+
+    for contract in contracts.iter() {
+        let item_id = contract.item_id;
+
+        if let Ok((&tile_pos, inventories)) = structure_query.get(contract.source) {
+            if let Some(inventory) = source_inventory(inventories) {
+                if inventory.item_count(item_id) > 0 {
+                    signals.add_signal(SignalType::Push(item_id), tile_pos, signal_strength);
+                }
+            }
+        }
+
+        if let Ok((&tile_pos, inventories)) = structure_query.get(contract.sink) {
+            if let Some(inventory) = sink_inventory(inventories) {
+                if inventory.can_fit(&UnitInventory::load(item_id), &item_manifest) {
+                    signals.add_signal(SignalType::Pull(item_id), tile_pos, signal_strength);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        items::{inventory::ReservedFor, item_manifest::ItemData, ItemCount},
+        signals::SignalPropagationConfig,
+        simulation::geometry::MapGeometry,
+        structures::construction::Footprint,
+        units::{
+            actions::{advance_action_timer, choose_actions, finish_actions, start_actions},
+            goals::Goal,
+            test_fixture::{spawn_unit, UnitTestApp},
+            unit_manifest::Unit,
+        },
+    };
+
+    fn leuco_chunk() -> Id<Item> {
+        Id::from_name("leuco_chunk")
+    }
+
+    fn item_manifest() -> ItemManifest {
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+        item_manifest
+    }
+
+    /// Spreads signals out, so that gradients form around their sources.
+    fn diffuse(mut signals: ResMut<Signals>, map_geometry: Res<MapGeometry>) {
+        signals.diffuse(&map_geometry, &SignalPropagationConfig::default());
+    }
+
+    #[test]
+    fn contracts_reject_duplicates_and_are_cancelled_with_their_structures() {
+        let mut world = World::new();
+        let source = world.spawn_empty().id();
+        let sink = world.spawn_empty().id();
+        let bystander = world.spawn_empty().id();
+
+        let contract = Contract {
+            item_id: leuco_chunk(),
+            source,
+            sink,
+        };
+
+        let mut contracts = Contracts::default();
+        assert!(contracts.add(contract));
+        assert!(!contracts.add(contract));
+        assert!(!contracts.add(Contract {
+            sink: source,
+            ..contract
+        }));
+
+        assert_eq!(contracts.cancel_involving(bystander), 0);
+        assert_eq!(contracts.cancel_involving(sink), 1);
+        assert_eq!(contracts.iter().count(), 0);
+    }
+
+    #[test]
+    fn contracts_draw_items_from_their_source_to_their_sink() {
+        let ant = Id::<Unit>::from_name("ant");
+        let mut app = UnitTestApp::new(3).with_items(item_manifest()).build();
+        app.add_systems(
+            (
+                emit_contract_signals,
+                diffuse,
+                advance_action_timer,
+                start_actions,
+                finish_actions,
+                choose_actions,
+            )
+                .chain(),
+        );
+
+        let source_tile = TilePos::new(-2, 0);
+        let sink_tile = TilePos::new(2, 0);
+
+        let mut spawn_storage = |tile_pos: TilePos, n_items: usize| {
+            let mut storage_inventory = StorageInventory::new(1, ReservedFor::Any);
+            storage_inventory
+                .add_item_all_or_nothing(
+                    &ItemCount::new(leuco_chunk(), n_items),
+                    app.world.resource::<ItemManifest>(),
+                )
+                .unwrap();
+            let storage_entity = app.world.spawn((storage_inventory, tile_pos)).id();
+            app.world.resource_mut::<MapGeometry>().add_structure(
+                tile_pos,
+                &Footprint::single(),
+                Id::from_name("storage"),
+                storage_entity,
+            );
+            storage_entity
+        };
+
+        let source = spawn_storage(source_tile, 5);
+        let sink = spawn_storage(sink_tile, 0);
+
+        app.world.resource_mut::<Contracts>().add(Contract {
+            item_id: leuco_chunk(),
+            source,
+            sink,
+        });
+
+        let item_count = |app: &App, storage_entity| {
+            app.world
+                .get::<StorageInventory>(storage_entity)
+                .unwrap()
+                .item_count(leuco_chunk())
+        };
+
+        spawn_unit(&mut app, TilePos::ZERO, ant, Goal::Pickup(leuco_chunk()));
+
+        for _ in 0..100 {
+            app.update();
+
+            if item_count(&app, sink) > 0 {
+                break;
+            }
+        }
+
+        assert_eq!(item_count(&app, source), 4);
+        assert_eq!(item_count(&app, sink), 1);
+
+        // Demolishing either end of the contract cancels it
+        app.world.despawn(sink);
+        app.update();
+        assert_eq!(app.world.resource::<Contracts>().iter().count(), 0);
+    }
+}
@@ -2,12 +2,13 @@
 //! available.
 use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_mod_raycast::{DefaultRaycastingPlugin, RaycastMethod, RaycastSource, RaycastSystem};
+use hexx::{Direction, Hex, HexLayout};
 use leafwing_input_manager::prelude::ActionState;
 
 use super::{InteractionSystem, PlayerAction};
 use crate::{
     asset_management::manifest::Id,
-    simulation::geometry::TilePos,
+    simulation::geometry::{MapGeometry, TilePos},
     structures::{construction::Ghost, structure_manifest::Structure},
     terrain::terrain_manifest::Terrain,
     units::unit_manifest::Unit,
@@ -19,6 +20,8 @@ pub(super) struct CursorPlugin;
 impl Plugin for CursorPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<CursorPos>()
+            .init_resource::<CursorMode>()
+            .init_resource::<GamepadCursorSettings>()
             .add_plugin(DefaultRaycastingPlugin::<Terrain>::default())
             .add_plugin(DefaultRaycastingPlugin::<Id<Structure>>::default())
             .add_plugin(DefaultRaycastingPlugin::<Id<Unit>>::default())
@@ -28,7 +31,19 @@ impl Plugin for CursorPlugin {
                     .before(RaycastSystem::BuildRays::<Terrain>)
                     .in_base_set(CoreSet::First),
             )
-            .add_system(move_cursor_manually.in_base_set(CoreSet::PreUpdate))
+            .add_system(toggle_cursor_mode.in_base_set(CoreSet::PreUpdate))
+            .add_system(
+                move_cursor_manually
+                    .run_if(resource_equals(CursorMode::Free))
+                    .after(toggle_cursor_mode)
+                    .in_base_set(CoreSet::PreUpdate),
+            )
+            .add_system(
+                step_cursor_between_tiles
+                    .run_if(resource_equals(CursorMode::TileStepping))
+                    .after(toggle_cursor_mode)
+                    .in_base_set(CoreSet::PreUpdate),
+            )
             .add_system(
                 update_cursor_pos
                     .in_set(InteractionSystem::ComputeCursorPos)
@@ -37,6 +52,62 @@ impl Plugin for CursorPlugin {
     }
 }
 
+/// How [`PlayerAction::MoveCursor`] moves the cursor.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CursorMode {
+    /// The cursor moves smoothly across the screen.
+    #[default]
+    Free,
+    /// The cursor snaps to the center of tiles, stepping to a neighboring tile with each input.
+    ///
+    /// This is much more precise for keyboard-only players.
+    TileStepping,
+}
+
+/// Controls how [`PlayerAction::MoveCursor`] stick deflection is converted into cursor movement.
+///
+/// Small deflections move the cursor slowly for fine selection, while large ones move it quickly.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct GamepadCursorSettings {
+    /// Deflections with a magnitude at or below this value are ignored, to filter out stick drift.
+    ///
+    /// Should be between 0 and 1.
+    pub(crate) dead_zone: f32,
+    /// The power that the deflection beyond the dead zone is raised to.
+    ///
+    /// Values above 1 make small deflections slower, while 1 gives a linear response.
+    pub(crate) acceleration_exponent: f32,
+    /// How far the cursor moves per frame at full deflection, in logical pixels.
+    pub(crate) max_speed: f32,
+}
+
+impl Default for GamepadCursorSettings {
+    fn default() -> Self {
+        GamepadCursorSettings {
+            dead_zone: 0.15,
+            acceleration_exponent: 2.0,
+            max_speed: 8.0,
+        }
+    }
+}
+
+impl GamepadCursorSettings {
+    /// Converts a raw stick deflection into the distance the cursor should move this frame.
+    ///
+    /// The dead zone is radial, so the direction of the input is preserved.
+    pub(crate) fn cursor_delta(&self, raw_deflection: Vec2) -> Vec2 {
+        let magnitude = raw_deflection.length().min(1.0);
+        if magnitude <= self.dead_zone {
+            return Vec2::ZERO;
+        }
+
+        let rescaled = (magnitude - self.dead_zone) / (1.0 - self.dead_zone);
+        let speed = rescaled.powf(self.acceleration_exponent) * self.max_speed;
+
+        raw_deflection.normalize() * speed
+    }
+}
+
 /// The position of the mouse cursor and what it is hovering over.
 #[derive(Resource, Default, Debug, Clone, Copy)]
 pub(crate) struct CursorPos {
@@ -90,6 +161,25 @@ impl CursorPos {
     pub(crate) fn maybe_ghost(&self) -> Option<Entity> {
         self.hovered_ghost
     }
+
+    /// Resolves a hovered tile covered by a structure to that structure's anchor tile.
+    ///
+    /// Any tile of a multi-tile structure can be hit by the raycast,
+    /// so this ensures that selection and tooltips always refer to the whole building.
+    /// If no structure mesh was hit directly, the structure covering the tile is treated as hovered.
+    fn snap_to_structures(&mut self, map_geometry: &MapGeometry) {
+        let Some(tile_pos) = self.tile_pos else {
+            return;
+        };
+
+        if let Some(anchor) = map_geometry.get_structure_anchor(tile_pos) {
+            self.tile_pos = Some(anchor);
+        }
+
+        if self.hovered_structure.is_none() {
+            self.hovered_structure = map_geometry.get_structure(tile_pos);
+        }
+    }
 }
 
 /// Updates the raycast with the cursor position
@@ -140,6 +230,7 @@ fn update_cursor_pos(
     structure_query: Query<Entity, With<Id<Structure>>>,
     unit_query: Query<Entity, With<Id<Unit>>>,
     ghost_query: Query<Entity, With<Ghost>>,
+    map_geometry: Res<MapGeometry>,
     mut cursor_moved_events: EventReader<CursorMoved>,
 ) {
     let (terrain_raycast, structure_raycast, unit_raycast, ghost_raycast) = camera_query.single();
@@ -175,6 +266,8 @@ fn update_cursor_pos(
         None
     };
 
+    cursor_pos.snap_to_structures(&map_geometry);
+
     if let Some(last_mouse_position) = cursor_moved_events.iter().last() {
         cursor_pos.screen_pos = Some(last_mouse_position.position);
     }
@@ -185,16 +278,14 @@ fn move_cursor_manually(
     actions: Res<ActionState<PlayerAction>>,
     mut window_query: Query<(Entity, &mut Window), With<PrimaryWindow>>,
     mut cursor_moved_events: EventWriter<CursorMoved>,
+    gamepad_cursor_settings: Res<GamepadCursorSettings>,
 ) {
-    /// Controls the sensitivity of cursor movement
-    const CURSOR_SPEED: f32 = 2.0;
-
     if let Ok((primary_window_entity, mut primary_window)) = window_query.get_single_mut() {
         let maybe_cursor_pos = primary_window.cursor_position();
 
         if let Some(old_cursor_pos) = maybe_cursor_pos {
             if let Some(raw_delta) = actions.axis_pair(PlayerAction::MoveCursor) {
-                let delta = raw_delta.xy() * CURSOR_SPEED;
+                let delta = gamepad_cursor_settings.cursor_delta(raw_delta.xy());
 
                 if delta != Vec2::ZERO {
                     let new_cursor_pos = old_cursor_pos + delta;
@@ -208,3 +299,184 @@ fn move_cursor_manually(
         }
     }
 }
+
+/// Switches between [`CursorMode`]s.
+fn toggle_cursor_mode(
+    actions: Res<ActionState<PlayerAction>>,
+    mut cursor_mode: ResMut<CursorMode>,
+) {
+    if actions.just_pressed(PlayerAction::ToggleCursorMode) {
+        *cursor_mode = match *cursor_mode {
+            CursorMode::Free => CursorMode::TileStepping,
+            CursorMode::TileStepping => CursorMode::Free,
+        };
+    }
+}
+
+/// Moves the cursor to the neighboring tile in the direction pressed.
+///
+/// Directions are relative to the screen, so "up" steps away from the camera.
+fn step_cursor_between_tiles(
+    actions: Res<ActionState<PlayerAction>>,
+    cursor_pos: Res<CursorPos>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    mut window_query: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+    map_geometry: Res<MapGeometry>,
+    mut cursor_moved_events: EventWriter<CursorMoved>,
+) {
+    if !actions.just_pressed(PlayerAction::MoveCursor) {
+        return;
+    }
+
+    let Some(screen_delta) = actions
+        .axis_pair(PlayerAction::MoveCursor)
+        .map(|axis_pair| axis_pair.xy())
+    else {
+        return;
+    };
+
+    if screen_delta == Vec2::ZERO {
+        return;
+    }
+
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    let Ok((primary_window_entity, mut primary_window)) = window_query.get_single_mut() else {
+        return;
+    };
+
+    // Convert the input into a direction on the XZ plane of the map
+    let forward = camera_transform.forward();
+    let right = camera_transform.right();
+    let world_delta = Vec2::new(forward.x, forward.z).normalize_or_zero() * screen_delta.y
+        + Vec2::new(right.x, right.z).normalize_or_zero() * screen_delta.x;
+
+    let Some(direction) = closest_direction(world_delta, &map_geometry.layout) else {
+        return;
+    };
+
+    let current_tile_pos = cursor_pos.maybe_tile_pos().unwrap_or_default();
+    let target_tile_pos = current_tile_pos.neighbor(direction);
+    if !map_geometry.is_valid(target_tile_pos) {
+        return;
+    }
+
+    let world_pos = target_tile_pos.into_world_pos(&map_geometry);
+    let Some(new_cursor_pos) = camera.world_to_viewport(camera_transform, world_pos) else {
+        return;
+    };
+
+    primary_window.set_cursor_position(Some(new_cursor_pos));
+    cursor_moved_events.send(CursorMoved {
+        window: primary_window_entity,
+        position: new_cursor_pos,
+    });
+}
+
+/// The hex [`Direction`] that most closely matches the provided direction on the XZ plane.
+///
+/// Returns [`None`] if `world_delta` is zero.
+fn closest_direction(world_delta: Vec2, layout: &HexLayout) -> Option<Direction> {
+    if world_delta == Vec2::ZERO {
+        return None;
+    }
+
+    let origin = layout.hex_to_world_pos(Hex::ZERO);
+
+    Direction::ALL_DIRECTIONS.into_iter().max_by(|a, b| {
+        let a_offset = layout.hex_to_world_pos(Hex::ZERO.neighbor(*a)) - origin;
+        let b_offset = layout.hex_to_world_pos(Hex::ZERO.neighbor(*b)) - origin;
+
+        a_offset
+            .normalize()
+            .dot(world_delta)
+            .total_cmp(&b_offset.normalize().dot(world_delta))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::construction::Footprint;
+
+    #[test]
+    fn closest_direction_matches_neighbor_offsets() {
+        let layout = HexLayout::default();
+
+        assert_eq!(closest_direction(Vec2::ZERO, &layout), None);
+
+        for direction in Direction::ALL_DIRECTIONS {
+            let offset = layout.hex_to_world_pos(Hex::ZERO.neighbor(direction));
+            // Slightly off-axis inputs should still snap to the nearest neighbor
+            let nudged = offset + offset.perp() * 0.2;
+
+            assert_eq!(closest_direction(offset, &layout), Some(direction));
+            assert_eq!(closest_direction(nudged, &layout), Some(direction));
+        }
+    }
+
+    #[test]
+    fn gamepad_cursor_curve_accelerates_past_the_dead_zone() {
+        let settings = GamepadCursorSettings {
+            dead_zone: 0.2,
+            acceleration_exponent: 2.0,
+            max_speed: 10.0,
+        };
+
+        // Stick drift is ignored
+        assert_eq!(settings.cursor_delta(Vec2::ZERO), Vec2::ZERO);
+        assert_eq!(settings.cursor_delta(Vec2::new(0.1, -0.1)), Vec2::ZERO);
+        assert_eq!(settings.cursor_delta(Vec2::X * 0.2), Vec2::ZERO);
+
+        // Full deflection moves at top speed, and inputs beyond the unit circle are clamped
+        assert!((settings.cursor_delta(Vec2::X) - Vec2::X * 10.0).length() < 1e-4);
+        assert!((settings.cursor_delta(Vec2::ONE) - Vec2::ONE.normalize() * 10.0).length() < 1e-4);
+
+        // Halfway between the dead zone and full deflection moves at a quarter of top speed
+        let half = settings.cursor_delta(Vec2::NEG_Y * 0.6);
+        assert!((half - Vec2::NEG_Y * 2.5).length() < 1e-4);
+
+        // Larger deflections always move faster
+        let speeds: Vec<f32> = [0.3, 0.5, 0.7, 0.9]
+            .into_iter()
+            .map(|deflection| settings.cursor_delta(Vec2::Y * deflection).length())
+            .collect();
+        assert!(speeds.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn hovering_any_footprint_tile_identifies_the_whole_structure() {
+        let mut map_geometry = MapGeometry::new(3);
+        let anchor = TilePos::new(1, 0);
+        let structure_entity = Entity::from_raw(42);
+        map_geometry.add_structure(
+            anchor,
+            &Footprint::hexagon(1),
+            Id::from_name("hatchery"),
+            structure_entity,
+        );
+
+        // A tile at the edge of the footprint, rather than its anchor
+        let edge_tile = TilePos::new(2, 0);
+        let mut cursor_pos = CursorPos::new(edge_tile);
+        cursor_pos.snap_to_structures(&map_geometry);
+        assert_eq!(cursor_pos.maybe_tile_pos(), Some(anchor));
+        assert_eq!(cursor_pos.maybe_structure(), Some(structure_entity));
+
+        // Tiles without a structure are left alone
+        let empty_tile = TilePos::new(-2, 0);
+        let mut cursor_pos = CursorPos::new(empty_tile);
+        cursor_pos.snap_to_structures(&map_geometry);
+        assert_eq!(cursor_pos.maybe_tile_pos(), Some(empty_tile));
+        assert_eq!(cursor_pos.maybe_structure(), None);
+
+        // Once removed, the structure no longer captures the cursor
+        map_geometry.remove_structure(edge_tile);
+        let mut cursor_pos = CursorPos::new(edge_tile);
+        cursor_pos.snap_to_structures(&map_geometry);
+        assert_eq!(cursor_pos.maybe_tile_pos(), Some(edge_tile));
+        assert_eq!(cursor_pos.maybe_structure(), None);
+    }
+}
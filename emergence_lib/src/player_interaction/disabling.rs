@@ -0,0 +1,49 @@
+//! Pauses and resumes individual structures, without demolishing them.
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+    asset_management::AssetState,
+    structures::crafting::{CraftingState, Disabled},
+};
+
+use super::{selection::CurrentSelection, InteractionSystem, PlayerAction};
+
+/// Lets players temporarily switch off crafting structures.
+pub(super) struct DisablingPlugin;
+
+impl Plugin for DisablingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            toggle_disabled
+                .after(InteractionSystem::SelectTiles)
+                .run_if(in_state(AssetState::Ready)),
+        );
+    }
+}
+
+/// Disables the selected crafting structure, or re-enables it if it was already disabled.
+fn toggle_disabled(
+    actions: Res<ActionState<PlayerAction>>,
+    current_selection: Res<CurrentSelection>,
+    crafting_query: Query<Option<&Disabled>, With<CraftingState>>,
+    mut commands: Commands,
+) {
+    if !actions.just_pressed(PlayerAction::ToggleDisabled) {
+        return;
+    }
+
+    let CurrentSelection::Structure(structure_entity) = *current_selection else {
+        return;
+    };
+
+    let Ok(maybe_disabled) = crafting_query.get(structure_entity) else {
+        return;
+    };
+
+    match maybe_disabled {
+        Some(_) => commands.entity(structure_entity).remove::<Disabled>(),
+        None => commands.entity(structure_entity).insert(Disabled),
+    };
+}
@@ -0,0 +1,248 @@
+//! Gamepad rumble, used to let players feel important events in the world.
+//!
+//! Bevy does not yet expose force feedback, so rumble is played directly through [`Gilrs`].
+//! When no gamepad that supports force feedback is connected, all of this does nothing.
+
+use bevy::{prelude::*, utils::Duration};
+use gilrs::{
+    ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Repeat, Replay, Ticks},
+    GamepadId, Gilrs,
+};
+
+use crate::{organisms::energy::UnitDied, structures::construction::ConstructionCompleted};
+
+use super::zoning::PlacementRejected;
+
+/// Rumbles the player's gamepad in response to game events.
+pub(super) struct HapticsPlugin;
+
+impl Plugin for HapticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HapticsSettings>()
+            .add_system(play_rumble);
+    }
+}
+
+/// Player preferences for gamepad rumble.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub(crate) struct HapticsSettings {
+    /// Should the gamepad rumble at all?
+    pub(crate) enabled: bool,
+    /// A multiplier applied to the strength of every rumble, between 0 and 1.
+    pub(crate) strength: f32,
+}
+
+impl Default for HapticsSettings {
+    fn default() -> Self {
+        HapticsSettings {
+            enabled: true,
+            strength: 1.,
+        }
+    }
+}
+
+impl HapticsSettings {
+    /// The rumble that should be played in response to all of the `feedback` that occurred this frame.
+    ///
+    /// Only the strongest rumble is played, to avoid overwhelming the player when many events happen at once.
+    fn rumble_for(&self, feedback: impl IntoIterator<Item = HapticFeedback>) -> Option<Rumble> {
+        if !self.enabled {
+            return None;
+        }
+
+        let strongest = feedback
+            .into_iter()
+            .map(|feedback| feedback.rumble())
+            .max_by(|a, b| a.strength.total_cmp(&b.strength))?;
+
+        let strength = (strongest.strength * self.strength).clamp(0., 1.);
+        match strength > 0. {
+            true => Some(Rumble {
+                strength,
+                duration: strongest.duration,
+            }),
+            false => None,
+        }
+    }
+}
+
+/// The game events that can be felt through the gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HapticFeedback {
+    /// A ghost has finished being built.
+    StructureCompleted,
+    /// A structure could not be placed where the player asked.
+    PlacementRejected,
+    /// A unit has died.
+    UnitDied,
+}
+
+impl HapticFeedback {
+    /// How the gamepad should rumble in response to this event.
+    pub(crate) fn rumble(&self) -> Rumble {
+        match self {
+            HapticFeedback::StructureCompleted => Rumble {
+                strength: 0.3,
+                duration: Duration::from_millis(150),
+            },
+            HapticFeedback::PlacementRejected => Rumble {
+                strength: 0.5,
+                duration: Duration::from_millis(100),
+            },
+            HapticFeedback::UnitDied => Rumble {
+                strength: 0.8,
+                duration: Duration::from_millis(400),
+            },
+        }
+    }
+}
+
+/// A single burst of gamepad vibration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Rumble {
+    /// How strongly the gamepad vibrates, between 0 and 1.
+    pub(crate) strength: f32,
+    /// How long the gamepad vibrates for.
+    pub(crate) duration: Duration,
+}
+
+impl Rumble {
+    /// Starts playing this rumble on the provided gamepads.
+    ///
+    /// The effect stops as soon as it is dropped, so it must be kept around until it has finished.
+    fn play(&self, gamepads: &[GamepadId], gilrs: &mut Gilrs) -> Result<Effect, gilrs::ff::Error> {
+        let ticks = Ticks::from(self.duration);
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong {
+                    magnitude: (self.strength * u16::MAX as f32) as u16,
+                },
+                scheduling: Replay {
+                    play_for: ticks,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .gamepads(gamepads)
+            .repeat(Repeat::For(ticks))
+            .finish(gilrs)?;
+
+        effect.play()?;
+        Ok(effect)
+    }
+}
+
+/// Rumbles every connected gamepad that supports force feedback in response to this frame's events.
+fn play_rumble(
+    mut construction_events: EventReader<ConstructionCompleted>,
+    mut rejection_events: EventReader<PlacementRejected>,
+    mut death_events: EventReader<UnitDied>,
+    settings: Res<HapticsSettings>,
+    maybe_gilrs: Option<NonSendMut<Gilrs>>,
+    time: Res<Time>,
+    mut active_effects: Local<Vec<(Effect, Duration)>>,
+) {
+    // Drop finished effects, which stops them
+    let delta = time.delta();
+    active_effects.retain_mut(|(_, remaining)| {
+        *remaining = remaining.saturating_sub(delta);
+        !remaining.is_zero()
+    });
+
+    let feedback = construction_events
+        .iter()
+        .map(|_| HapticFeedback::StructureCompleted)
+        .chain(
+            rejection_events
+                .iter()
+                .map(|_| HapticFeedback::PlacementRejected),
+        )
+        .chain(death_events.iter().map(|_| HapticFeedback::UnitDied));
+
+    let Some(rumble) = settings.rumble_for(feedback) else {
+        return;
+    };
+
+    let Some(mut gilrs) = maybe_gilrs else {
+        return;
+    };
+
+    let gamepads: Vec<GamepadId> = gilrs
+        .gamepads()
+        .filter(|(_, gamepad)| gamepad.is_ff_supported())
+        .map(|(id, _)| id)
+        .collect();
+
+    if gamepads.is_empty() {
+        return;
+    }
+
+    match rumble.play(&gamepads, &mut gilrs) {
+        Ok(effect) => active_effects.push((effect, rumble.duration)),
+        Err(error) => warn!("Could not rumble gamepad: {error}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::geometry::TilePos;
+
+    #[test]
+    fn strongest_feedback_wins() {
+        let settings = HapticsSettings::default();
+
+        let rumble = settings
+            .rumble_for([
+                HapticFeedback::StructureCompleted,
+                HapticFeedback::UnitDied,
+                HapticFeedback::PlacementRejected,
+            ])
+            .unwrap();
+        assert_eq!(rumble, HapticFeedback::UnitDied.rumble());
+
+        assert_eq!(settings.rumble_for([]), None);
+    }
+
+    #[test]
+    fn settings_scale_and_disable_rumble() {
+        let quiet = HapticsSettings {
+            enabled: true,
+            strength: 0.5,
+        };
+        let rumble = quiet
+            .rumble_for([HapticFeedback::PlacementRejected])
+            .unwrap();
+        assert_eq!(rumble.strength, 0.25);
+
+        let muted = HapticsSettings {
+            enabled: true,
+            strength: 0.,
+        };
+        assert_eq!(muted.rumble_for([HapticFeedback::UnitDied]), None);
+
+        let disabled = HapticsSettings {
+            enabled: false,
+            strength: 1.,
+        };
+        assert_eq!(disabled.rumble_for([HapticFeedback::UnitDied]), None);
+    }
+    #[test]
+    fn rumble_does_nothing_without_gamepads() {
+        let mut app = App::new();
+        // These events are owned by the plugins that send them
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(HapticsPlugin)
+            .add_event::<ConstructionCompleted>()
+            .add_event::<PlacementRejected>()
+            .add_event::<UnitDied>();
+
+        app.world.send_event(UnitDied {
+            tile_pos: TilePos::new(0, 0),
+            held_item: None,
+        });
+
+        // No gamepad backend exists, so this should quietly do nothing
+        app.update();
+    }
+}
@@ -8,7 +8,10 @@ use std::ops::{Div, Mul};
 
 use bevy::prelude::*;
 use derive_more::{Add, AddAssign, Sub, SubAssign};
-use leafwing_abilities::{pool::MaxPoolLessThanZero, prelude::Pool};
+use leafwing_abilities::{
+    pool::MaxPoolLessThanZero,
+    prelude::{CannotUseAbility, Pool},
+};
 
 use super::InteractionSystem;
 
@@ -18,6 +21,7 @@ pub(super) struct IntentPlugin;
 impl Plugin for IntentPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<IntentPool>()
+            .init_resource::<IntentSettings>()
             .add_system(
                 regenerate_intent
                     .in_set(InteractionSystem::ReplenishIntent)
@@ -62,6 +66,43 @@ impl Default for IntentPool {
     }
 }
 
+impl IntentPool {
+    /// The amount of intent that is currently available to spend.
+    pub(crate) fn remaining(&self) -> Intent {
+        self.current
+    }
+
+    /// Is there enough intent available to pay the provided `cost`?
+    pub(crate) fn can_afford(&self, cost: Intent) -> bool {
+        self.available(cost).is_ok()
+    }
+
+    /// Spends `cost` intent, if enough is available.
+    ///
+    /// If there is not enough intent, nothing is spent and an error is returned.
+    pub(crate) fn spend(&mut self, cost: Intent) -> Result<(), CannotUseAbility> {
+        self.expend(cost)
+    }
+}
+
+/// Controls how the [`IntentPool`] regenerates.
+#[derive(Debug, Clone, PartialEq, Resource)]
+pub(crate) struct IntentSettings {
+    /// The amount of intent regenerated per second.
+    pub(crate) regen_per_second: Intent,
+    /// When paused, no intent is regenerated.
+    pub(crate) paused: bool,
+}
+
+impl Default for IntentSettings {
+    fn default() -> Self {
+        IntentSettings {
+            regen_per_second: INTENT_REGEN,
+            paused: false,
+        }
+    }
+}
+
 /// A quantity of Intent, used to modify an [`IntentPool`].
 ///
 /// This is used to measure the amount of Intent that must be spent to perform various actions.
@@ -131,8 +172,22 @@ impl Pool for IntentPool {
 
 /// Regenerates the [`Intent`] of the hive mind.
 ///
+/// The rate of regeneration is controlled by the [`IntentSettings`].
+///
 /// Note that we cannot use the built-in system for this, as our pool is stored somewhat unusually as a resource.
-fn regenerate_intent(mut intent_pool: ResMut<IntentPool>, time: Res<FixedTime>) {
+pub(super) fn regenerate_intent(
+    mut intent_pool: ResMut<IntentPool>,
+    intent_settings: Res<IntentSettings>,
+    time: Res<FixedTime>,
+) {
+    if intent_settings.is_changed() {
+        intent_pool.set_regen_per_second(intent_settings.regen_per_second);
+    }
+
+    if intent_settings.paused {
+        return;
+    }
+
     if intent_pool.current() != intent_pool.max() {
         intent_pool.regenerate(time.period);
     }
@@ -141,7 +196,7 @@ fn regenerate_intent(mut intent_pool: ResMut<IntentPool>, time: Res<FixedTime>)
 /// Displays the current quantity of intent stored in the [`IntentPool`].
 fn display_intent(intent_pool: Res<IntentPool>) {
     if intent_pool.is_changed() {
-        let current = intent_pool.current().0;
+        let current = intent_pool.remaining().0;
         let max = intent_pool.max().0;
         info!("{current} Intent / {max} Intent");
     }
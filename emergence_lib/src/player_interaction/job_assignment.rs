@@ -0,0 +1,52 @@
+//! Assigns [`Job`]s to the selected units, restricting which goals they will pursue.
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{asset_management::AssetState, simulation::geometry::TilePos, units::jobs::Job};
+
+use super::{selection::CurrentSelection, InteractionSystem, PlayerAction};
+
+/// Lets players change the job of the selected units.
+pub(super) struct JobAssignmentPlugin;
+
+impl Plugin for JobAssignmentPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            cycle_jobs
+                .after(InteractionSystem::SelectTiles)
+                .run_if(in_state(AssetState::Ready)),
+        );
+    }
+}
+
+/// Cycles the job of the selected unit, or of every unit standing on the selected tiles.
+///
+/// When a group of units is selected, they are all given the job that follows the job of the first unit,
+/// so that mixed groups quickly converge on a single job.
+fn cycle_jobs(
+    actions: Res<ActionState<PlayerAction>>,
+    current_selection: Res<CurrentSelection>,
+    mut unit_query: Query<(Entity, &TilePos, &mut Job)>,
+) {
+    if !actions.just_pressed(PlayerAction::CycleJob) {
+        return;
+    }
+
+    match &*current_selection {
+        CurrentSelection::Unit(unit_entity) => {
+            if let Ok((_, _, mut job)) = unit_query.get_mut(*unit_entity) {
+                *job = job.next();
+            }
+        }
+        CurrentSelection::Terrain(selected_tiles) => {
+            let mut new_job = None;
+            for (_, tile_pos, mut job) in unit_query.iter_mut() {
+                if selected_tiles.contains_tile(*tile_pos) {
+                    *job = *new_job.get_or_insert_with(|| job.next());
+                }
+            }
+        }
+        _ => (),
+    }
+}
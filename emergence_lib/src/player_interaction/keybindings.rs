@@ -0,0 +1,279 @@
+//! Players can change which inputs trigger each [`PlayerAction`].
+//!
+//! Keybindings are saved to disk whenever they are changed, and loaded again on startup.
+
+use std::{fs, path::Path};
+
+use bevy::{prelude::*, utils::HashMap};
+use leafwing_input_manager::{
+    axislike::AxisType,
+    prelude::InputMap,
+    user_input::{Modifier, UserInput},
+    Actionlike,
+};
+use serde::Deserialize;
+
+use super::PlayerAction;
+
+/// Loads, remaps and saves the player's keybindings.
+pub(super) struct KeybindingsPlugin;
+
+impl Plugin for KeybindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_keybindings(Path::new(KEYBINDINGS_PATH)))
+            .init_resource::<Remapping>()
+            .add_system(capture_new_binding);
+    }
+}
+
+/// The file that keybindings are saved to and loaded from.
+pub(crate) const KEYBINDINGS_PATH: &str = "settings/keybindings.json";
+
+/// Tracks the progress of changing the binding of a single action.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Remapping {
+    /// The action whose binding will be replaced by the next key or mouse button pressed, if any.
+    pub(crate) awaiting: Option<PlayerAction>,
+    /// The actions that lost their binding to the most recently changed action.
+    pub(crate) conflicts: Vec<PlayerAction>,
+}
+
+/// Writes the `input_map` to the file at `path`, creating any missing directories.
+pub(crate) fn save_keybindings(
+    input_map: &InputMap<PlayerAction>,
+    path: &Path,
+) -> anyhow::Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+
+    let serialized = serde_json::to_string_pretty(input_map)?;
+    fs::write(path, serialized)?;
+    Ok(())
+}
+
+/// The keybindings as stored on disk.
+///
+/// This matches the serialized form of an [`InputMap`],
+/// but records which actions were saved at all.
+#[derive(Deserialize)]
+struct SavedKeybindings {
+    /// The inputs bound to each action.
+    map: HashMap<PlayerAction, Vec<UserInput>>,
+}
+
+/// Reads the keybindings from the file at `path`.
+///
+/// Any actions missing from the file use their default bindings,
+/// so that newly added actions are still usable.
+pub(crate) fn read_keybindings(path: &Path) -> anyhow::Result<InputMap<PlayerAction>> {
+    let bytes = fs::read(path)?;
+    let saved_keybindings: SavedKeybindings = serde_json::from_slice(&bytes)?;
+
+    let default_input_map = PlayerAction::default_input_map();
+    let mut input_map = InputMap::default();
+    for action in PlayerAction::variants() {
+        match saved_keybindings.map.get(&action) {
+            Some(inputs) => {
+                for input in inputs {
+                    input_map.insert(input.clone(), action.clone());
+                }
+            }
+            None => {
+                for input in default_input_map.get(action.clone()).iter() {
+                    input_map.insert(input.clone(), action.clone());
+                }
+            }
+        }
+    }
+
+    Ok(input_map)
+}
+
+/// Loads the keybindings saved at `path`, falling back to the defaults if they can't be read.
+fn load_keybindings(path: &Path) -> InputMap<PlayerAction> {
+    if !path.exists() {
+        return PlayerAction::default_input_map();
+    }
+
+    match read_keybindings(path) {
+        Ok(input_map) => input_map,
+        Err(error) => {
+            warn!(
+                "Could not load keybindings from {}, using the defaults: {error}",
+                path.display()
+            );
+            PlayerAction::default_input_map()
+        }
+    }
+}
+
+/// Is this input performed using a gamepad?
+fn is_gamepad_input(input: &UserInput) -> bool {
+    let raw_inputs = input.raw_inputs();
+
+    !raw_inputs.gamepad_buttons.is_empty()
+        || raw_inputs
+            .axis_data
+            .iter()
+            .any(|(axis_type, _)| matches!(axis_type, AxisType::Gamepad(_)))
+}
+
+/// Replaces the mouse and keyboard bindings of `action` with `input`.
+///
+/// Gamepad bindings are left untouched.
+/// Each input can only trigger a single action, so `input` is removed from any other action that used it.
+/// Returns the actions that lost their binding in this way.
+pub(crate) fn rebind(
+    input_map: &mut InputMap<PlayerAction>,
+    action: PlayerAction,
+    input: UserInput,
+) -> Vec<PlayerAction> {
+    let mut conflicts = Vec::new();
+    for other_action in PlayerAction::variants() {
+        if other_action != action
+            && input_map
+                .remove(other_action.clone(), input.clone())
+                .is_some()
+        {
+            conflicts.push(other_action);
+        }
+    }
+
+    let replaced_inputs: Vec<UserInput> = input_map
+        .get(action.clone())
+        .iter()
+        .filter(|existing_input| !is_gamepad_input(existing_input))
+        .cloned()
+        .collect();
+    for replaced_input in replaced_inputs {
+        input_map.remove(action.clone(), replaced_input);
+    }
+
+    input_map.insert(input, action);
+    conflicts
+}
+
+/// The modifier keys that can be combined with another key in a binding.
+const MODIFIERS: [Modifier; 3] = [Modifier::Control, Modifier::Shift, Modifier::Alt];
+
+/// Binds the next key or mouse button pressed to the action that is awaiting a new binding.
+///
+/// Pressing escape cancels the remapping.
+fn capture_new_binding(
+    mut remapping: ResMut<Remapping>,
+    mut input_map: ResMut<InputMap<PlayerAction>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+) {
+    // The click that started the remapping should not be captured as the new binding
+    if remapping.is_changed() {
+        return;
+    }
+
+    let Some(action) = remapping.awaiting.clone() else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        remapping.awaiting = None;
+        return;
+    }
+
+    let is_modifier = |key_code: &KeyCode| {
+        MODIFIERS
+            .iter()
+            .any(|modifier| modifier.key_codes().contains(key_code))
+    };
+    let held_modifier = MODIFIERS
+        .into_iter()
+        .find(|modifier| keyboard_input.any_pressed(modifier.key_codes()));
+
+    let maybe_key = keyboard_input
+        .get_just_pressed()
+        .find(|key_code| !is_modifier(key_code));
+    let new_input: UserInput = match (maybe_key, held_modifier) {
+        (Some(&key_code), Some(modifier)) => UserInput::modified(modifier, key_code),
+        (Some(&key_code), None) => key_code.into(),
+        (None, _) => match mouse_input.get_just_pressed().next() {
+            Some(&mouse_button) => mouse_button.into(),
+            None => return,
+        },
+    };
+
+    remapping.conflicts = rebind(&mut input_map, action.clone(), new_input);
+    remapping.awaiting = None;
+
+    for conflict in &remapping.conflicts {
+        info!("{conflict:?} was unbound, as its input is now used by {action:?}.");
+    }
+
+    if let Err(error) = save_keybindings(&input_map, Path::new(KEYBINDINGS_PATH)) {
+        warn!("Could not save keybindings to {KEYBINDINGS_PATH}: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_removes_conflicting_bindings() {
+        let mut input_map = PlayerAction::default_input_map();
+        let gamepad_bindings: Vec<UserInput> = input_map
+            .get(PlayerAction::TogglePause)
+            .iter()
+            .filter(|input| is_gamepad_input(input))
+            .cloned()
+            .collect();
+
+        // Copy is bound to Ctrl + C by default
+        let conflicts = rebind(
+            &mut input_map,
+            PlayerAction::TogglePause,
+            UserInput::modified(Modifier::Control, KeyCode::C),
+        );
+
+        assert_eq!(conflicts, vec![PlayerAction::Copy]);
+        assert!(input_map
+            .get(PlayerAction::Copy)
+            .iter()
+            .all(is_gamepad_input));
+
+        let pause_bindings = input_map.get(PlayerAction::TogglePause);
+        assert!(pause_bindings.contains(&UserInput::modified(Modifier::Control, KeyCode::C)));
+        assert!(!pause_bindings.contains(&KeyCode::Space.into()));
+        for gamepad_binding in gamepad_bindings {
+            assert!(pause_bindings.contains(&gamepad_binding));
+        }
+    }
+
+    #[test]
+    fn keybindings_round_trip_through_disk() {
+        let path = std::env::temp_dir()
+            .join("emergence_keybindings_test")
+            .join("keybindings.json");
+
+        let mut input_map = PlayerAction::default_input_map();
+        rebind(&mut input_map, PlayerAction::ZoomIn, KeyCode::PageUp.into());
+        rebind(
+            &mut input_map,
+            PlayerAction::ZoomOut,
+            MouseButton::Other(4).into(),
+        );
+
+        save_keybindings(&input_map, &path).unwrap();
+        let loaded_input_map = read_keybindings(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        for action in PlayerAction::variants() {
+            let original = input_map.get(action.clone());
+            let loaded = loaded_input_map.get(action.clone());
+
+            assert_eq!(original.len(), loaded.len(), "{action:?}");
+            for input in original.iter() {
+                assert!(loaded.contains(input), "{action:?}");
+            }
+        }
+    }
+}
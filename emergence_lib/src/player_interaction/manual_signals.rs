@@ -0,0 +1,322 @@
+//! Manually placed signals, used to request or send away items without building any structures.
+//!
+//! Each manual signal is a standalone [`Emitter`] entity, positioned at a single [`TilePos`].
+
+use bevy::{prelude::*, utils::HashMap};
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+    asset_management::{manifest::Id, AssetState},
+    items::item_manifest::{Item, ItemManifest},
+    signals::{Emitter, SignalKind, SignalStrength, SignalType},
+    simulation::geometry::{MapGeometry, TilePos},
+};
+
+use super::{cursor::CursorPos, selection::CurrentSelection, InteractionSystem, PlayerAction};
+
+/// Places and removes manual signals.
+pub(super) struct ManualSignalsPlugin;
+
+impl Plugin for ManualSignalsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ManualSignalSettings>()
+            .init_resource::<ManualSignals>()
+            .init_resource::<ManualSignalHandles>()
+            .add_system(configure_manual_signals.run_if(in_state(AssetState::Ready)))
+            .add_system(
+                place_manual_signals
+                    .after(InteractionSystem::SelectTiles)
+                    .after(configure_manual_signals),
+            );
+    }
+}
+
+/// Whether a manual signal requests or sends away its item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ManualSignalKind {
+    /// Emits a [`SignalType::Pull`], asking for items to be brought here.
+    Pull,
+    /// Emits a [`SignalType::Push`], asking for items to be taken away from here.
+    Push,
+}
+
+/// The signal that will be emitted by the next manual signal that the player places.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub(crate) struct ManualSignalSettings {
+    /// The item to request or send away.
+    ///
+    /// If this is [`None`], no manual signals can be placed.
+    pub(crate) item_id: Option<Id<Item>>,
+    /// Whether the item should be pulled or pushed.
+    pub(crate) kind: ManualSignalKind,
+    /// The strength of the emitted signal.
+    pub(crate) strength: SignalStrength,
+}
+
+impl Default for ManualSignalSettings {
+    fn default() -> Self {
+        ManualSignalSettings {
+            item_id: None,
+            kind: ManualSignalKind::Pull,
+            strength: SignalStrength::new(ManualSignalSettings::STRENGTHS[1]),
+        }
+    }
+}
+
+impl ManualSignalSettings {
+    /// The signal strengths that the player can choose between.
+    const STRENGTHS: [f32; 3] = [1., 10., 100.];
+
+    /// The signal that will be emitted, if an item has been chosen.
+    pub(crate) fn signal_type(&self) -> Option<SignalType> {
+        let item_id = self.item_id?;
+
+        Some(match self.kind {
+            ManualSignalKind::Pull => SignalType::Pull(item_id),
+            ManualSignalKind::Push => SignalType::Push(item_id),
+        })
+    }
+
+    /// Advances to the next signal type.
+    ///
+    /// For each item in `item_ids` in turn, a [`ManualSignalKind::Pull`] is followed by a [`ManualSignalKind::Push`].
+    fn cycle_signal_type(&mut self, item_ids: &[Id<Item>]) {
+        let Some(item_id) = self.item_id else {
+            self.item_id = item_ids.first().copied();
+            self.kind = ManualSignalKind::Pull;
+            return;
+        };
+
+        match self.kind {
+            ManualSignalKind::Pull => self.kind = ManualSignalKind::Push,
+            ManualSignalKind::Push => {
+                let next_index = item_ids
+                    .iter()
+                    .position(|&id| id == item_id)
+                    .map(|index| (index + 1) % item_ids.len())
+                    .unwrap_or_default();
+
+                self.item_id = item_ids.get(next_index).copied();
+                self.kind = ManualSignalKind::Pull;
+            }
+        }
+    }
+
+    /// Advances to the next signal strength, wrapping back around to the weakest.
+    fn cycle_strength(&mut self) {
+        let current_index = ManualSignalSettings::STRENGTHS
+            .iter()
+            .position(|&strength| strength == self.strength.value())
+            .unwrap_or_default();
+        let next_index = (current_index + 1) % ManualSignalSettings::STRENGTHS.len();
+
+        self.strength = SignalStrength::new(ManualSignalSettings::STRENGTHS[next_index]);
+    }
+}
+
+/// A marker component for [`Emitter`] entities that were placed by the player.
+#[derive(Component, Debug)]
+pub(crate) struct ManualSignal;
+
+/// The manual signal entity at each tile.
+///
+/// Each tile can have at most one manual signal.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct ManualSignals {
+    /// The entity of the manual signal at each tile.
+    index: HashMap<TilePos, Entity>,
+}
+
+impl ManualSignals {
+    /// Records that `entity` is the manual signal at `tile_pos`, returning the entity that it replaced.
+    fn insert(&mut self, tile_pos: TilePos, entity: Entity) -> Option<Entity> {
+        self.index.insert(tile_pos, entity)
+    }
+
+    /// Removes the manual signal at `tile_pos`, returning its entity if there was one.
+    fn remove(&mut self, tile_pos: TilePos) -> Option<Entity> {
+        self.index.remove(&tile_pos)
+    }
+}
+
+/// The mesh and materials used to mark the location of manual signals.
+#[derive(Resource, Debug)]
+struct ManualSignalHandles {
+    /// A thin ring that floats above the tile.
+    mesh: Handle<Mesh>,
+    /// The material used for pull signals.
+    pull_material: Handle<StandardMaterial>,
+    /// The material used for push signals.
+    push_material: Handle<StandardMaterial>,
+}
+
+impl ManualSignalHandles {
+    /// How far above the top of the tile the marker floats.
+    const HOVER_HEIGHT: f32 = 0.2;
+
+    /// The material used to display manual signals of the provided `kind`.
+    fn material(&self, kind: ManualSignalKind) -> Handle<StandardMaterial> {
+        match kind {
+            ManualSignalKind::Pull => self.pull_material.clone_weak(),
+            ManualSignalKind::Push => self.push_material.clone_weak(),
+        }
+    }
+}
+
+impl FromWorld for ManualSignalHandles {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let mesh = meshes.add(Mesh::from(shape::Torus {
+            radius: 0.6,
+            ring_radius: 0.06,
+            ..Default::default()
+        }));
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let mut marker_material = |signal_kind: SignalKind| {
+            materials.add(StandardMaterial {
+                base_color: signal_kind.color(),
+                unlit: true,
+                ..Default::default()
+            })
+        };
+
+        ManualSignalHandles {
+            mesh,
+            pull_material: marker_material(SignalKind::Pull),
+            push_material: marker_material(SignalKind::Push),
+        }
+    }
+}
+
+/// Changes the item, direction and strength of the manual signals that the player will place.
+fn configure_manual_signals(
+    actions: Res<ActionState<PlayerAction>>,
+    mut settings: ResMut<ManualSignalSettings>,
+    item_manifest: Res<ItemManifest>,
+) {
+    if actions.just_pressed(PlayerAction::CycleManualSignalType) {
+        // Manifests are sorted by name, so the cycling order is stable
+        let item_ids: Vec<Id<Item>> = item_manifest.variants().into_iter().collect();
+
+        settings.cycle_signal_type(&item_ids);
+    }
+
+    if actions.just_pressed(PlayerAction::CycleManualSignalStrength) {
+        settings.cycle_strength();
+    }
+}
+
+/// Places or removes manual signals on the selected tiles.
+#[allow(clippy::too_many_arguments)]
+fn place_manual_signals(
+    actions: Res<ActionState<PlayerAction>>,
+    cursor_pos: Res<CursorPos>,
+    current_selection: Res<CurrentSelection>,
+    settings: Res<ManualSignalSettings>,
+    mut manual_signals: ResMut<ManualSignals>,
+    handles: Res<ManualSignalHandles>,
+    map_geometry: Res<MapGeometry>,
+    mut commands: Commands,
+) {
+    let remove = actions.just_pressed(PlayerAction::RemoveManualSignal);
+    let place = actions.just_pressed(PlayerAction::PlaceManualSignal);
+    if !remove && !place {
+        return;
+    }
+
+    let relevant_tiles = current_selection.relevant_tiles(&cursor_pos);
+
+    for &tile_pos in relevant_tiles.selection().iter() {
+        // Each tile can only have one manual signal, so placing a new one replaces the old one
+        if let Some(old_entity) = manual_signals.remove(tile_pos) {
+            commands.entity(old_entity).despawn_recursive();
+        }
+
+        if remove {
+            continue;
+        }
+
+        let Some(signal_type) = settings.signal_type() else {
+            continue;
+        };
+
+        let entity = commands
+            .spawn((
+                ManualSignal,
+                tile_pos,
+                Emitter {
+                    signals: vec![(signal_type, settings.strength)],
+                },
+                PbrBundle {
+                    mesh: handles.mesh.clone_weak(),
+                    material: handles.material(settings.kind),
+                    transform: Transform::from_translation(
+                        tile_pos.top_of_tile(&map_geometry)
+                            + Vec3::Y * ManualSignalHandles::HOVER_HEIGHT,
+                    ),
+                    ..Default::default()
+                },
+            ))
+            .id();
+
+        manual_signals.insert(tile_pos, entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycling_alternates_pull_and_push_for_each_item() {
+        let acacia_leaf = Id::from_name("acacia_leaf");
+        let leuco_chunk = Id::from_name("leuco_chunk");
+        let item_ids = [acacia_leaf, leuco_chunk];
+
+        let mut settings = ManualSignalSettings::default();
+        assert_eq!(settings.signal_type(), None);
+
+        let mut signal_types = Vec::new();
+        for _ in 0..5 {
+            settings.cycle_signal_type(&item_ids);
+            signal_types.push(settings.signal_type().unwrap());
+        }
+
+        assert_eq!(
+            signal_types,
+            vec![
+                SignalType::Pull(acacia_leaf),
+                SignalType::Push(acacia_leaf),
+                SignalType::Pull(leuco_chunk),
+                SignalType::Push(leuco_chunk),
+                SignalType::Pull(acacia_leaf),
+            ]
+        );
+    }
+
+    #[test]
+    fn cycling_strength_wraps_around() {
+        let mut settings = ManualSignalSettings::default();
+        let starting_strength = settings.strength;
+
+        for _ in 0..ManualSignalSettings::STRENGTHS.len() {
+            settings.cycle_strength();
+        }
+
+        assert_eq!(settings.strength, starting_strength);
+    }
+
+    #[test]
+    fn manual_signals_are_indexed_by_tile() {
+        let mut manual_signals = ManualSignals::default();
+        let mut world = World::new();
+        let first = world.spawn_empty().id();
+        let second = world.spawn_empty().id();
+
+        assert_eq!(manual_signals.insert(TilePos::ZERO, first), None);
+        assert_eq!(manual_signals.insert(TilePos::ZERO, second), Some(first));
+        assert_eq!(manual_signals.remove(TilePos::ZERO), Some(second));
+        assert_eq!(manual_signals.remove(TilePos::ZERO), None);
+    }
+}
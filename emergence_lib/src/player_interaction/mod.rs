@@ -6,14 +6,27 @@ use leafwing_input_manager::{
     user_input::{Modifier, UserInput},
     Actionlike,
 };
+use serde::{Deserialize, Serialize};
 
 pub(crate) mod abilities;
+pub(crate) mod blueprints;
 pub(crate) mod camera;
 pub(crate) mod clipboard;
+#[cfg(feature = "debug_tools")]
+pub(crate) mod console;
+pub(crate) mod contracts;
 pub(crate) mod cursor;
+pub(crate) mod disabling;
+pub(crate) mod haptics;
 pub(crate) mod intent;
+pub(crate) mod job_assignment;
+pub(crate) mod keybindings;
+pub(crate) mod manual_signals;
+pub(crate) mod production_targets;
 pub(crate) mod selection;
+pub(crate) mod storage_filter;
 pub(crate) mod terraform;
+pub(crate) mod tutorial;
 pub(crate) mod zoning;
 
 /// All of the code needed for users to interact with the simulation.
@@ -23,7 +36,7 @@ impl Plugin for InteractionPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(InputManagerPlugin::<PlayerAction>::default())
             .init_resource::<ActionState<PlayerAction>>()
-            .insert_resource(PlayerAction::default_input_map())
+            .add_plugin(keybindings::KeybindingsPlugin)
             .add_plugin(camera::CameraPlugin)
             .add_plugin(abilities::AbilitiesPlugin)
             .add_plugin(cursor::CursorPlugin)
@@ -31,10 +44,20 @@ impl Plugin for InteractionPlugin {
             .add_plugin(selection::SelectionPlugin)
             .add_plugin(terraform::TerraformingPlugin)
             .add_plugin(clipboard::ClipboardPlugin)
-            .add_plugin(zoning::ZoningPlugin);
+            .add_plugin(blueprints::BlueprintPlugin)
+            .add_plugin(zoning::ZoningPlugin)
+            .add_plugin(manual_signals::ManualSignalsPlugin)
+            .add_plugin(storage_filter::StorageFilterPlugin)
+            .add_plugin(job_assignment::JobAssignmentPlugin)
+            .add_plugin(disabling::DisablingPlugin)
+            .add_plugin(contracts::ContractsPlugin)
+            .add_plugin(production_targets::ProductionTargetsPlugin)
+            .add_plugin(haptics::HapticsPlugin)
+            .add_plugin(tutorial::TutorialPlugin);
 
         #[cfg(feature = "debug_tools")]
-        app.add_plugin(debug_tools::DebugToolsPlugin);
+        app.add_plugin(debug_tools::DebugToolsPlugin)
+            .add_plugin(console::ConsolePlugin);
     }
 }
 
@@ -64,10 +87,18 @@ pub(crate) enum InteractionSystem {
 /// Actions that the player can take to modify the game world or their view of it.
 ///
 /// This should only store actions that need a dedicated keybinding.
-#[derive(Actionlike, Clone, Debug)]
+#[derive(
+    Actionlike, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
 pub(crate) enum PlayerAction {
     /// Pause or unpause the game.
     TogglePause,
+    /// Makes the simulation run faster.
+    IncreaseSimulationSpeed,
+    /// Makes the simulation run slower, without pausing it.
+    DecreaseSimulationSpeed,
+    /// Advances the simulation by a single step while the game is paused.
+    StepSimulation,
     /// When the clipboard is full, places the clipboard contents on the map.
     ///
     /// When the clipboard is empty, selects a tile or group of tiles.
@@ -86,8 +117,14 @@ pub(crate) enum PlayerAction {
     Area,
     /// Modifies the selection to cover a line between the start and end of the selection.
     Line,
+    /// Modifies the selection to cover a parallelogram, with the start and end of the selection at opposite corners.
+    Rectangle,
     /// Selects a structure from a wheel menu.
     SelectStructure,
+    /// Shows the next page of choices in an open wheel menu.
+    NextMenuPage,
+    /// Shows the previous page of choices in an open wheel menu.
+    PreviousMenuPage,
     /// Set the height of a tile.
     SelectTerraform,
     /// Selects the structure on the tile under the player's cursor.
@@ -98,6 +135,12 @@ pub(crate) enum PlayerAction {
     ///
     /// If no structure is selected to build, zoning will be set to [`Zoning::None`](zoning::Zoning::None).
     Paste,
+    /// Saves the contents of the clipboard to disk as a blueprint.
+    SaveBlueprint,
+    /// Places the selected blueprint as ghosts, centered on the cursor.
+    PlaceBlueprint,
+    /// Selects the next saved blueprint to place.
+    CycleBlueprint,
     /// Sets the zoning of all currently selected tiles to [`Zoning::None`](zoning::Zoning::None).
     ClearZoning,
     /// Sets the zoning of all currently selected tiles to [`Zoning::KeepClear`](zoning::Zoning::KeepClear).
@@ -106,7 +149,7 @@ pub(crate) enum PlayerAction {
     RotateClipboardLeft,
     /// Rotates the contents of the clipboard clockwise.
     RotateClipboardRight,
-    /// Snaps the camera to the selected object
+    /// Snaps the camera to the selected object, or to the colony center if nothing is selected
     CenterCameraOnSelection,
     /// Drag the camera with the cursor
     DragCamera,
@@ -114,6 +157,8 @@ pub(crate) enum PlayerAction {
     Pan,
     /// Move the cursor around the screen
     MoveCursor,
+    /// Switches between moving the cursor freely and stepping it from tile to tile
+    ToggleCursorMode,
     /// Reveal less of the map by moving the camera closer
     ZoomIn,
     /// Reveal more of the map by pulling the camera away
@@ -132,6 +177,35 @@ pub(crate) enum PlayerAction {
     ToggleSignalOverlay,
     /// Show / hide the strongest signal overlay
     ToggleStrongestSignalOverlay,
+    /// Show / hide the overlay of how many items have recently been moved at each tile
+    ToggleThroughputOverlay,
+    /// Places a manual signal on all currently selected tiles.
+    PlaceManualSignal,
+    /// Removes any manual signals from all currently selected tiles.
+    RemoveManualSignal,
+    /// Cycles the item and direction of the manual signal to place.
+    CycleManualSignalType,
+    /// Cycles the strength of the manual signal to place.
+    CycleManualSignalStrength,
+    /// Cycles which item the selected storage structure is reserved for.
+    CycleStorageFilter,
+    /// Cycles the job of the selected units.
+    CycleJob,
+    /// Pauses or resumes the selected structure.
+    ToggleDisabled,
+    /// Marks the selected structure as the source of a new delivery contract,
+    /// or as its sink if a source has already been chosen.
+    DesignateContract,
+    /// Cancels all delivery contracts involving the selected structure.
+    CancelContracts,
+    /// Asks the production planner for one more producer of the items made by the selected structure.
+    PlanProduction,
+    /// Stops planning production of the items made by the selected structure.
+    StopPlanningProduction,
+    /// Shows / hides the menu used to change keybindings.
+    ToggleKeybindingsMenu,
+    /// Shows / hides the panel that explains how each item is produced.
+    ToggleProductionChains,
 }
 
 impl PlayerAction {
@@ -140,6 +214,9 @@ impl PlayerAction {
         use PlayerAction::*;
         match self {
             TogglePause => KeyCode::Space.into(),
+            IncreaseSimulationSpeed => KeyCode::RBracket.into(),
+            DecreaseSimulationSpeed => KeyCode::LBracket.into(),
+            StepSimulation => KeyCode::Period.into(),
             Select => MouseButton::Left.into(),
             Deselect => MouseButton::Right.into(),
             // Plus and Equals are swapped. See: https://github.com/rust-windowing/winit/issues/2682
@@ -148,10 +225,16 @@ impl PlayerAction {
             Multiple => Modifier::Shift.into(),
             Area => Modifier::Control.into(),
             Line => Modifier::Alt.into(),
+            Rectangle => UserInput::chord([Modifier::Control, Modifier::Alt]),
             SelectStructure => KeyCode::Key1.into(),
+            NextMenuPage => KeyCode::Tab.into(),
+            PreviousMenuPage => UserInput::modified(Modifier::Shift, KeyCode::Tab),
             SelectTerraform => KeyCode::Key2.into(),
             Copy => UserInput::modified(Modifier::Control, KeyCode::C),
             Paste => UserInput::modified(Modifier::Control, KeyCode::V),
+            SaveBlueprint => UserInput::modified(Modifier::Control, KeyCode::B),
+            PlaceBlueprint => KeyCode::B.into(),
+            CycleBlueprint => UserInput::modified(Modifier::Shift, KeyCode::B),
             ClearZoning => KeyCode::Back.into(),
             KeepClear => KeyCode::Delete.into(),
             RotateClipboardLeft => UserInput::modified(Modifier::Shift, KeyCode::R),
@@ -160,6 +243,7 @@ impl PlayerAction {
             DragCamera => MouseButton::Middle.into(),
             Pan => VirtualDPad::wasd().into(),
             MoveCursor => VirtualDPad::arrow_keys().into(),
+            ToggleCursorMode => KeyCode::T.into(),
             // Plus and Equals are swapped. See: https://github.com/rust-windowing/winit/issues/2682
             ZoomIn => KeyCode::Equals.into(),
             ZoomOut => KeyCode::Minus.into(),
@@ -171,6 +255,20 @@ impl PlayerAction {
             ToggleStatusInfo => KeyCode::F1.into(),
             ToggleSignalOverlay => KeyCode::F2.into(),
             ToggleStrongestSignalOverlay => KeyCode::F3.into(),
+            ToggleThroughputOverlay => KeyCode::F5.into(),
+            PlaceManualSignal => KeyCode::P.into(),
+            RemoveManualSignal => UserInput::modified(Modifier::Shift, KeyCode::P),
+            CycleManualSignalType => KeyCode::O.into(),
+            CycleManualSignalStrength => UserInput::modified(Modifier::Shift, KeyCode::O),
+            CycleStorageFilter => KeyCode::I.into(),
+            CycleJob => KeyCode::J.into(),
+            ToggleDisabled => KeyCode::X.into(),
+            DesignateContract => KeyCode::K.into(),
+            CancelContracts => UserInput::modified(Modifier::Shift, KeyCode::K),
+            PlanProduction => KeyCode::G.into(),
+            StopPlanningProduction => UserInput::modified(Modifier::Shift, KeyCode::G),
+            ToggleKeybindingsMenu => KeyCode::F4.into(),
+            ToggleProductionChains => KeyCode::F6.into(),
         }
     }
 
@@ -185,6 +283,9 @@ impl PlayerAction {
 
         match self {
             TogglePause => GamepadButtonType::Select.into(),
+            IncreaseSimulationSpeed => UserInput::chord([camera_modifier, East]),
+            DecreaseSimulationSpeed => UserInput::chord([camera_modifier, West]),
+            StepSimulation => UserInput::chord([camera_modifier, South]),
             PlayerAction::Select => South.into(),
             Deselect => East.into(),
             Multiple => RightTrigger.into(),
@@ -192,9 +293,15 @@ impl PlayerAction {
             DecreaseSelectionRadius => UserInput::chord([radius_modifier, DPadDown]),
             Area => LeftTrigger.into(),
             Line => LeftTrigger2.into(),
+            Rectangle => UserInput::chord([LeftTrigger, LeftTrigger2]),
             SelectStructure => RightThumb.into(),
+            NextMenuPage => UserInput::chord([camera_modifier, RightTrigger]),
+            PreviousMenuPage => UserInput::chord([camera_modifier, LeftTrigger]),
             Copy => West.into(),
             Paste => North.into(),
+            SaveBlueprint => UserInput::chord([infovis_modifier, West]),
+            PlaceBlueprint => UserInput::chord([infovis_modifier, North]),
+            CycleBlueprint => UserInput::chord([radius_modifier, RightThumb]),
             ClearZoning => DPadUp.into(),
             KeepClear => DPadDown.into(),
             SelectTerraform => UserInput::chord([radius_modifier, North]),
@@ -204,6 +311,7 @@ impl PlayerAction {
             DragCamera => GamepadButtonType::RightThumb.into(),
             Pan => DualAxis::left_stick().into(),
             MoveCursor => DualAxis::right_stick().into(),
+            ToggleCursorMode => UserInput::chord([radius_modifier, DPadRight]),
             ZoomIn => UserInput::chord([camera_modifier, DPadUp]),
             ZoomOut => UserInput::chord([camera_modifier, DPadDown]),
             TiltCameraUp => UserInput::chord([RightTrigger, DPadDown]),
@@ -213,11 +321,27 @@ impl PlayerAction {
             ToggleStatusInfo => UserInput::chord([infovis_modifier, DPadLeft]),
             ToggleSignalOverlay => UserInput::chord([infovis_modifier, DPadUp]),
             ToggleStrongestSignalOverlay => UserInput::chord([infovis_modifier, DPadRight]),
+            ToggleThroughputOverlay => UserInput::chord([camera_modifier, North]),
+            PlaceManualSignal => UserInput::chord([radius_modifier, South]),
+            RemoveManualSignal => UserInput::chord([radius_modifier, East]),
+            CycleManualSignalType => UserInput::chord([radius_modifier, West]),
+            CycleManualSignalStrength => UserInput::chord([radius_modifier, DPadLeft]),
+            CycleStorageFilter => UserInput::chord([infovis_modifier, South]),
+            CycleJob => UserInput::chord([infovis_modifier, RightThumb]),
+            ToggleDisabled => UserInput::chord([infovis_modifier, LeftThumb]),
+            DesignateContract => UserInput::chord([camera_modifier, LeftThumb]),
+            CancelContracts => UserInput::chord([camera_modifier, RightThumb]),
+            PlanProduction => UserInput::chord([radius_modifier, LeftThumb]),
+            StopPlanningProduction => {
+                UserInput::chord([radius_modifier, infovis_modifier, LeftThumb])
+            }
+            ToggleKeybindingsMenu => UserInput::chord([infovis_modifier, East]),
+            ToggleProductionChains => UserInput::chord([infovis_modifier, DPadDown]),
         }
     }
 
     /// The default key bindings
-    fn default_input_map() -> InputMap<PlayerAction> {
+    pub(crate) fn default_input_map() -> InputMap<PlayerAction> {
         let mut input_map = InputMap::default();
 
         for variant in PlayerAction::variants() {
@@ -0,0 +1,159 @@
+//! Lets the player set [`ProductionTargets`] for the items made by the selected structure.
+//!
+//! Each press of [`PlayerAction::PlanProduction`] asks the planner for one more producer than the colony has now,
+//! while [`PlayerAction::StopPlanningProduction`] hands production of those items back to the player.
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+    asset_management::AssetState,
+    items::{item_manifest::ItemManifest, recipe::RecipeManifest},
+    simulation::factions::Faction,
+    structures::{
+        crafting::ActiveRecipe,
+        planner::{colony_capacity, items_per_minute, ProductionTarget, ProductionTargets},
+    },
+};
+
+use super::{selection::CurrentSelection, InteractionSystem, PlayerAction};
+
+/// Raises and clears production targets based on the selected structure.
+pub(super) struct ProductionTargetsPlugin;
+
+impl Plugin for ProductionTargetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            adjust_production_targets
+                .after(InteractionSystem::SelectTiles)
+                .run_if(in_state(AssetState::Ready)),
+        );
+    }
+}
+
+/// Raises or clears the production targets of every item made by the selected structure's recipe.
+///
+/// A raised target keeps a stack of the item in storage,
+/// and asks for the rate of the existing producers plus one more copy of the selected structure.
+fn adjust_production_targets(
+    actions: Res<ActionState<PlayerAction>>,
+    current_selection: Res<CurrentSelection>,
+    producer_query: Query<(&ActiveRecipe, Option<&Faction>)>,
+    item_manifest: Res<ItemManifest>,
+    recipe_manifest: Res<RecipeManifest>,
+    mut production_targets: ResMut<ProductionTargets>,
+) {
+    let plan = actions.just_pressed(PlayerAction::PlanProduction);
+    let stop = actions.just_pressed(PlayerAction::StopPlanningProduction);
+    if !plan && !stop {
+        return;
+    }
+
+    let CurrentSelection::Structure(structure_entity) = *current_selection else {
+        return;
+    };
+
+    let Ok((active_recipe, _)) = producer_query.get(structure_entity) else {
+        return;
+    };
+
+    let Some(recipe_id) = *active_recipe.recipe_id() else {
+        return;
+    };
+
+    for item_count in &recipe_manifest.get(recipe_id).outputs {
+        let item_id = item_count.item_id();
+
+        if stop {
+            production_targets.clear(item_id);
+            continue;
+        }
+
+        let capacity = colony_capacity(item_id, &producer_query, &recipe_manifest);
+        let desired_stock = production_targets
+            .get(item_id)
+            .map(|target| target.desired_stock)
+            .unwrap_or(item_manifest.get(item_id).stack_size);
+
+        production_targets.set(
+            item_id,
+            ProductionTarget {
+                desired_stock,
+                items_per_minute: capacity + items_per_minute(item_id, recipe_id, &recipe_manifest),
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::{Duration, Instant};
+
+    use super::*;
+    use crate::{
+        asset_management::manifest::Id,
+        items::{
+            item_manifest::{Item, ItemData},
+            recipe::RecipeData,
+            ItemCount,
+        },
+    };
+
+    #[test]
+    fn planning_production_asks_for_one_more_producer_than_the_colony_has() {
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        // Each producer makes 10 chunks a minute
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "leuco_chunk_production",
+            RecipeData {
+                outputs: vec![ItemCount::one(leuco_chunk)],
+                craft_time: Duration::from_secs(6),
+                ..Default::default()
+            },
+        );
+
+        let mut app = App::new();
+        app.insert_resource(item_manifest)
+            .insert_resource(recipe_manifest)
+            .init_resource::<ActionState<PlayerAction>>()
+            .init_resource::<ProductionTargets>()
+            .add_system(adjust_production_targets);
+
+        let active_recipe = ActiveRecipe::new(Id::from_name("leuco_chunk_production"));
+        let selected = app.world.spawn(active_recipe.clone()).id();
+        app.world.spawn(active_recipe);
+        app.insert_resource(CurrentSelection::Structure(selected));
+
+        // Input states are normally advanced each frame by the input manager
+        let press = |app: &mut App, action: PlayerAction| {
+            app.world
+                .resource_mut::<ActionState<PlayerAction>>()
+                .press(action.clone());
+            app.update();
+            let now = Instant::now();
+            let mut actions = app.world.resource_mut::<ActionState<PlayerAction>>();
+            actions.release(action);
+            actions.tick(now, now);
+        };
+
+        press(&mut app, PlayerAction::PlanProduction);
+        assert_eq!(
+            app.world.resource::<ProductionTargets>().get(leuco_chunk),
+            Some(&ProductionTarget {
+                desired_stock: 10,
+                items_per_minute: 30.,
+            })
+        );
+
+        press(&mut app, PlayerAction::StopPlanningProduction);
+        assert_eq!(
+            app.world.resource::<ProductionTargets>().get(leuco_chunk),
+            None
+        );
+    }
+}
@@ -69,7 +69,7 @@ impl SelectedTiles {
 
     /// Draws a hollow hexagonal ring of tiles.
     fn draw_ring(center: TilePos, radius: u32) -> HashSet<TilePos> {
-        let hex_coord = center.ring(radius);
+        let hex_coord = center.hex.ring(radius);
         HashSet::from_iter(hex_coord.into_iter().map(|hex| TilePos { hex }))
     }
 
@@ -93,6 +93,18 @@ impl SelectedTiles {
         tiles
     }
 
+    /// Computes the set of hexagons in the parallelogram with `start` and `end` at opposite corners.
+    ///
+    /// The sides of the parallelogram run along the axes of the hex grid.
+    fn draw_rectangle(start: TilePos, end: TilePos) -> HashSet<TilePos> {
+        let (min_x, max_x) = (start.x.min(end.x), start.x.max(end.x));
+        let (min_y, max_y) = (start.y.min(end.y), start.y.max(end.y));
+
+        (min_x..=max_x)
+            .flat_map(|x| (min_y..=max_y).map(move |y| TilePos::new(x, y)))
+            .collect()
+    }
+
     /// Clears the set of selected tiles.
     pub(super) fn clear_selection(&mut self) {
         self.selected.clear();
@@ -157,6 +169,9 @@ impl SelectedTiles {
             SelectionShape::Line { start } => {
                 SelectedTiles::draw_line(start, hovered_tile, selection_state.brush_size)
             }
+            SelectionShape::Rectangle { start } => {
+                SelectedTiles::draw_rectangle(start, hovered_tile)
+            }
         }
         // PERF: we could be faster about this by only collecting once
         .into_iter()
@@ -182,6 +197,11 @@ pub(crate) struct HoveredTiles {
 }
 
 impl HoveredTiles {
+    /// Stops hovering over any tiles.
+    fn clear(&mut self) {
+        self.hovered.clear();
+    }
+
     /// Updates the set of hovered actions based on the current cursor position and player inputs.
     fn update(&mut self, hovered_tile: TilePos, selection_state: &SelectionState) {
         self.hovered = match selection_state.shape {
@@ -197,6 +217,9 @@ impl HoveredTiles {
             SelectionShape::Line { start } => {
                 SelectedTiles::draw_line(start, hovered_tile, selection_state.brush_size)
             }
+            SelectionShape::Rectangle { start } => {
+                SelectedTiles::draw_rectangle(start, hovered_tile)
+            }
         };
     }
 }
@@ -295,7 +318,7 @@ pub(crate) enum CurrentSelection {
 
 impl CurrentSelection {
     /// Returns the set of terrain tiles that should be affected by actions.
-    pub(super) fn relevant_tiles(&self, cursor_pos: &CursorPos) -> SelectedTiles {
+    pub(crate) fn relevant_tiles(&self, cursor_pos: &CursorPos) -> SelectedTiles {
         match self {
             CurrentSelection::Terrain(selected_tiles) => match selected_tiles.is_empty() {
                 true => {
@@ -495,6 +518,11 @@ enum SelectionShape {
         /// The start of the line
         start: TilePos,
     },
+    /// A parallelogram, dragged out from one corner to the other
+    Rectangle {
+        /// The corner where the drag started
+        start: TilePos,
+    },
 }
 
 impl SelectionState {
@@ -509,7 +537,21 @@ impl SelectionState {
 
         self.multiple = actions.pressed(PlayerAction::Multiple);
 
-        self.shape = if actions.pressed(Line) {
+        // Check the rectangle first, as its keybinding overlaps with those for lines and areas
+        self.shape = if actions.pressed(Rectangle) {
+            // The starting corner is only fixed while dragging
+            let dragging = [Select, Deselect].iter().any(|action| {
+                (actions.pressed(action.clone()) && !actions.just_pressed(action.clone()))
+                    || actions.just_released(action.clone())
+            });
+
+            let start = match self.shape {
+                SelectionShape::Rectangle { start } if dragging => start,
+                _ => hovered_tile,
+            };
+
+            SelectionShape::Rectangle { start }
+        } else if actions.pressed(Line) {
             let start = if let SelectionShape::Line { start } = self.shape {
                 start
             } else {
@@ -541,7 +583,9 @@ impl SelectionState {
                     SelectionAction::Preview
                 }
             }
-            SelectionShape::Area { .. } | SelectionShape::Line { .. } => {
+            SelectionShape::Area { .. }
+            | SelectionShape::Line { .. }
+            | SelectionShape::Rectangle { .. } => {
                 // Trigger on just released in order to enable a drag-and-preview effect
                 if actions.just_released(Select) {
                     SelectionAction::Select
@@ -577,7 +621,13 @@ fn set_selection(
     let cursor_pos = &*cursor_pos;
     let map_geometry = &*map_geometry;
 
-    let Some(hovered_tile) = cursor_pos.maybe_tile_pos() else {return};
+    let Some(hovered_tile) = cursor_pos.maybe_tile_pos() else {
+        // The cursor has left the map, so nothing should remain highlighted
+        if !hovered_tiles.is_empty() {
+            hovered_tiles.clear();
+        }
+        return;
+    };
 
     // Compute how we should handle the selection based on the actions of the player
     selection_state.compute(&clipboard, actions, hovered_tile);
@@ -597,7 +647,10 @@ fn set_selection(
                 start: hovered_tile,
             };
         }
-        (SelectionAction::Select, SelectionShape::Area { .. }) => {
+        (
+            SelectionAction::Select,
+            SelectionShape::Area { .. } | SelectionShape::Rectangle { .. },
+        ) => {
             *current_selection =
                 current_selection.select_terrain(hovered_tile, &selection_state, map_geometry);
         }
@@ -627,20 +680,21 @@ fn set_selection(
                 )
             }
         }
-        (SelectionAction::Deselect, SelectionShape::Area { .. } | SelectionShape::Single) => {
-            match &mut *current_selection {
-                CurrentSelection::Terrain(ref mut selected_tiles) => {
-                    if let Some(hovered_tile) = cursor_pos.maybe_tile_pos() {
-                        selected_tiles.remove_from_selection(
-                            hovered_tile,
-                            &selection_state,
-                            map_geometry,
-                        );
-                    }
+        (
+            SelectionAction::Deselect,
+            SelectionShape::Area { .. } | SelectionShape::Rectangle { .. } | SelectionShape::Single,
+        ) => match &mut *current_selection {
+            CurrentSelection::Terrain(ref mut selected_tiles) => {
+                if let Some(hovered_tile) = cursor_pos.maybe_tile_pos() {
+                    selected_tiles.remove_from_selection(
+                        hovered_tile,
+                        &selection_state,
+                        map_geometry,
+                    );
                 }
-                _ => *current_selection = CurrentSelection::None,
             }
-        }
+            _ => *current_selection = CurrentSelection::None,
+        },
         (SelectionAction::Deselect, SelectionShape::Line { .. }) => {
             match &mut *current_selection {
                 CurrentSelection::Terrain(ref mut selected_tiles) => {
@@ -685,11 +739,9 @@ pub(super) fn set_tile_interactions(
 
 #[cfg(test)]
 mod tests {
-    use super::SelectedTiles;
-    use crate::{
-        player_interaction::{cursor::CursorPos, selection::CurrentSelection},
-        simulation::geometry::TilePos,
-    };
+    use bevy::utils::Instant;
+
+    use super::*;
 
     #[test]
     fn simple_selection() {
@@ -748,4 +800,110 @@ mod tests {
             cursor_pos_selected
         );
     }
+
+    #[test]
+    fn rectangles_are_dragged_between_opposite_corners() {
+        let mut app = App::new();
+        app.init_resource::<Clipboard>()
+            .init_resource::<CurrentSelection>()
+            .init_resource::<CursorPos>()
+            .init_resource::<ActionState<PlayerAction>>()
+            .init_resource::<HoveredTiles>()
+            .init_resource::<SelectionState>()
+            .insert_resource(MapGeometry::new(3))
+            .add_system(set_selection);
+
+        // Input states are normally advanced each frame by the input manager
+        let update = |app: &mut App| {
+            app.update();
+            let now = Instant::now();
+            app.world
+                .resource_mut::<ActionState<PlayerAction>>()
+                .tick(now, now);
+        };
+
+        let drag = |app: &mut App, start: TilePos, end: TilePos| {
+            *app.world.resource_mut::<CursorPos>() = CursorPos::new(start);
+            let mut actions = app.world.resource_mut::<ActionState<PlayerAction>>();
+            actions.press(PlayerAction::Rectangle);
+            actions.press(PlayerAction::Select);
+            update(app);
+
+            // Nothing is selected until the drag is released
+            *app.world.resource_mut::<CursorPos>() = CursorPos::new(end);
+            update(app);
+            app.world
+                .resource_mut::<ActionState<PlayerAction>>()
+                .release(PlayerAction::Select);
+            update(app);
+        };
+        let selected_tiles = |app: &App| match app.world.resource::<CurrentSelection>() {
+            CurrentSelection::Terrain(selected_tiles) => selected_tiles.selection().clone(),
+            _ => HashSet::default(),
+        };
+
+        drag(&mut app, TilePos::new(-1, 0), TilePos::new(1, 1));
+        let first_rectangle = HashSet::from_iter([
+            TilePos::new(-1, 0),
+            TilePos::new(0, 0),
+            TilePos::new(1, 0),
+            TilePos::new(-1, 1),
+            TilePos::new(0, 1),
+            TilePos::new(1, 1),
+        ]);
+        assert_eq!(selected_tiles(&app), first_rectangle);
+
+        // Tiles off of the map are never selected
+        drag(&mut app, TilePos::new(2, 0), TilePos::new(4, 0));
+        assert_eq!(
+            selected_tiles(&app),
+            HashSet::from_iter([TilePos::new(2, 0), TilePos::new(3, 0)])
+        );
+
+        // Rectangles can be combined when selecting multiple regions
+        drag(&mut app, TilePos::new(-1, 0), TilePos::new(1, 1));
+        app.world
+            .resource_mut::<ActionState<PlayerAction>>()
+            .press(PlayerAction::Multiple);
+        drag(&mut app, TilePos::new(0, -2), TilePos::new(0, -1));
+        let mut combined = first_rectangle;
+        combined.extend([TilePos::new(0, -2), TilePos::new(0, -1)]);
+        assert_eq!(selected_tiles(&app), combined);
+    }
+
+    #[test]
+    fn hover_highlight_follows_cursor_and_clears_off_map() {
+        let mut app = App::new();
+        app.init_resource::<Clipboard>()
+            .init_resource::<CurrentSelection>()
+            .init_resource::<CursorPos>()
+            .init_resource::<ActionState<PlayerAction>>()
+            .init_resource::<HoveredTiles>()
+            .init_resource::<SelectionState>()
+            .insert_resource(MapGeometry::new(1))
+            .add_systems((set_selection, set_tile_interactions).chain());
+
+        let first_tile = TilePos::new(0, 0);
+        let second_tile = TilePos::new(1, 0);
+        let first_entity = app.world.spawn((first_tile, ObjectInteraction::None)).id();
+        let second_entity = app.world.spawn((second_tile, ObjectInteraction::None)).id();
+        let interaction =
+            |app: &App, entity: Entity| app.world.get::<ObjectInteraction>(entity).unwrap().clone();
+
+        *app.world.resource_mut::<CursorPos>() = CursorPos::new(first_tile);
+        app.update();
+        assert_eq!(interaction(&app, first_entity), ObjectInteraction::Hovered);
+        assert_eq!(interaction(&app, second_entity), ObjectInteraction::None);
+
+        *app.world.resource_mut::<CursorPos>() = CursorPos::new(second_tile);
+        app.update();
+        assert_eq!(interaction(&app, first_entity), ObjectInteraction::None);
+        assert_eq!(interaction(&app, second_entity), ObjectInteraction::Hovered);
+
+        // Leaving the map
+        *app.world.resource_mut::<CursorPos>() = CursorPos::default();
+        app.update();
+        assert_eq!(interaction(&app, first_entity), ObjectInteraction::None);
+        assert_eq!(interaction(&app, second_entity), ObjectInteraction::None);
+    }
 }
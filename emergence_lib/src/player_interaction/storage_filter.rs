@@ -0,0 +1,126 @@
+//! Changes which item a storage structure is reserved for, after it has been built.
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+    asset_management::{manifest::Id, AssetState},
+    items::{
+        inventory::ReservedFor,
+        item_manifest::{Item, ItemManifest},
+    },
+    structures::crafting::StorageInventory,
+};
+
+use super::{selection::CurrentSelection, InteractionSystem, PlayerAction};
+
+/// Lets players set and clear the item filter of storage structures.
+pub(super) struct StorageFilterPlugin;
+
+impl Plugin for StorageFilterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            cycle_storage_filter
+                .after(InteractionSystem::SelectTiles)
+                .run_if(in_state(AssetState::Ready)),
+        );
+    }
+}
+
+/// The filter that follows `current` when cycling through `item_ids`.
+///
+/// Cycles from accepting any item, through each item in turn, and then back to accepting any item.
+/// Whitelists of several items restart the cycle from the first item.
+fn next_storage_filter(current: &ReservedFor, item_ids: &[Id<Item>]) -> ReservedFor {
+    let ReservedFor::Only(current_item_id) = current else {
+        return item_ids
+            .first()
+            .copied()
+            .map_or(ReservedFor::Any, ReservedFor::Only);
+    };
+
+    item_ids
+        .iter()
+        .position(|id| id == current_item_id)
+        .and_then(|current_index| item_ids.get(current_index + 1).copied())
+        .map_or(ReservedFor::Any, ReservedFor::Only)
+}
+
+/// Cycles the item filter of the selected storage structure.
+fn cycle_storage_filter(
+    actions: Res<ActionState<PlayerAction>>,
+    current_selection: Res<CurrentSelection>,
+    mut storage_query: Query<&mut StorageInventory>,
+    item_manifest: Res<ItemManifest>,
+) {
+    if !actions.just_pressed(PlayerAction::CycleStorageFilter) {
+        return;
+    }
+
+    let CurrentSelection::Structure(structure_entity) = *current_selection else {
+        return;
+    };
+
+    let Ok(mut storage_inventory) = storage_query.get_mut(structure_entity) else {
+        return;
+    };
+
+    // Manifests are sorted by name, so the cycling order is stable
+    let item_ids: Vec<Id<Item>> = item_manifest.variants().into_iter().collect();
+
+    let reserved_for = next_storage_filter(storage_inventory.reserved_for(), &item_ids);
+    storage_inventory.set_reserved_for(reserved_for);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::{item_manifest::ItemData, ItemCount};
+
+    #[test]
+    fn storage_filters_cycle_through_items_then_clear() {
+        let acacia_leaf = Id::from_name("acacia_leaf");
+        let leuco_chunk = Id::from_name("leuco_chunk");
+        let item_ids = [acacia_leaf, leuco_chunk];
+
+        let mut filter = ReservedFor::Any;
+        let mut filters = Vec::new();
+        for _ in 0..3 {
+            filter = next_storage_filter(&filter, &item_ids);
+            filters.push(filter.clone());
+        }
+
+        assert_eq!(
+            filters,
+            vec![
+                ReservedFor::Only(acacia_leaf),
+                ReservedFor::Only(leuco_chunk),
+                ReservedFor::Any
+            ]
+        );
+    }
+
+    #[test]
+    fn changing_filter_keeps_existing_items_but_refuses_new_ones() {
+        let acacia_leaf = Id::from_name("acacia_leaf");
+        let leuco_chunk = Id::from_name("leuco_chunk");
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("acacia_leaf", ItemData::new(10));
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let mut storage_inventory = StorageInventory::new(2, ReservedFor::Any);
+        storage_inventory
+            .add_item_all_or_nothing(&ItemCount::one(acacia_leaf), &item_manifest)
+            .unwrap();
+
+        storage_inventory.set_reserved_for(ReservedFor::Only(leuco_chunk));
+
+        assert_eq!(storage_inventory.item_count(acacia_leaf), 1);
+        assert!(storage_inventory
+            .add_item_all_or_nothing(&ItemCount::one(acacia_leaf), &item_manifest)
+            .is_err());
+        assert!(storage_inventory
+            .add_item_all_or_nothing(&ItemCount::one(leuco_chunk), &item_manifest)
+            .is_ok());
+    }
+}
@@ -3,16 +3,10 @@
 use bevy::prelude::*;
 
 use crate::{
-    asset_management::manifest::Id,
-    simulation::{
-        geometry::{Height, TilePos},
-        SimulationSet,
-    },
-    structures::commands::StructureCommandsExt,
-    terrain::{
-        terrain_assets::TerrainHandles,
-        terrain_manifest::{Terrain, TerrainManifest},
-    },
+    asset_management::{localization::Localization, manifest::Id},
+    signals::Emitter,
+    simulation::{geometry::Height, SimulationSet},
+    terrain::terrain_manifest::{Terrain, TerrainManifest},
 };
 
 use super::{zoning::Zoning, InteractionSystem};
@@ -23,7 +17,7 @@ pub(super) struct TerraformingPlugin;
 impl Plugin for TerraformingPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            (apply_terraforming,)
+            (complete_terraforming,)
                 .in_set(InteractionSystem::ApplyTerraforming)
                 .in_set(SimulationSet)
                 .in_schedule(CoreSchedule::FixedUpdate),
@@ -66,7 +60,7 @@ impl TerraformingChoice {
     }
 }
 
-/// When `Zoning` is set, this is added  as a component added to terrain entities that marks them to be manipulated by units.
+/// When `Zoning` is set, this is added as a component to terrain entities, marking them to be reshaped by units.
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) struct MarkedForTerraforming {
     /// The desired height of this tile
@@ -76,59 +70,137 @@ pub(crate) struct MarkedForTerraforming {
 }
 
 impl MarkedForTerraforming {
+    /// Does the terrain already match the target?
+    pub(crate) fn is_complete(&self, height: Height, material: Id<Terrain>) -> bool {
+        height == self.target_height && material == self.target_material
+    }
+
+    /// Performs a single step of terraforming work, moving the terrain one step closer to the target.
+    ///
+    /// The height is changed first, one level at a time, before the terrain material is swapped out.
+    pub(crate) fn apply_work(&self, height: &mut Height, material: &mut Id<Terrain>) {
+        if *height < self.target_height {
+            *height += Height(1);
+        } else if *height > self.target_height {
+            *height -= Height(1);
+        } else {
+            *material = self.target_material;
+        }
+    }
+
     /// Pretty formatting for this type
-    pub(crate) fn display(&self, terrain_manifest: &TerrainManifest) -> String {
+    pub(crate) fn display(
+        &self,
+        terrain_manifest: &TerrainManifest,
+        localization: &Localization,
+    ) -> String {
         format!(
             "Terraform: Height {}, Terrain Type {}",
             self.target_height,
-            terrain_manifest.name(self.target_material)
+            localization.get(terrain_manifest.name(self.target_material))
         )
     }
 }
 
-/// Changes the terrain to match the [`MarkedForTerraforming`] component
-fn apply_terraforming(
+/// Cleans up terrain that has reached its [`MarkedForTerraforming`] target.
+///
+/// The actual reshaping is done by units, one step at a time.
+fn complete_terraforming(
     mut query: Query<(
         Entity,
         &MarkedForTerraforming,
-        &TilePos,
+        &Height,
+        &Id<Terrain>,
         &mut Zoning,
-        &mut Id<Terrain>,
-        &mut Height,
-        &mut Handle<Scene>,
     )>,
-    terrain_handles: Res<TerrainHandles>,
     mut commands: Commands,
 ) {
-    for (
-        entity,
-        marked_for_terraforming,
-        tile_pos,
-        mut zoning,
-        mut terrain,
-        mut height,
-        mut scene_handle,
-    ) in query.iter_mut()
-    {
-        // TODO: this should take work.
-        *height = marked_for_terraforming.target_height;
-        *terrain = marked_for_terraforming.target_material;
-        *scene_handle = terrain_handles
-            .scenes
-            .get(&marked_for_terraforming.target_material)
-            .unwrap()
-            .clone_weak();
-
-        if *height == marked_for_terraforming.target_height
-            && *terrain == marked_for_terraforming.target_material
-        {
+    for (entity, marked_for_terraforming, &height, &terrain, mut zoning) in query.iter_mut() {
+        if marked_for_terraforming.is_complete(height, terrain) {
             // Don't keep the components around once we've completed our action
-            commands.entity(entity).remove::<MarkedForTerraforming>();
+            commands
+                .entity(entity)
+                .remove::<(MarkedForTerraforming, Emitter)>();
             // Reset the zoning when we're done
             *zoning = Zoning::None;
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        player_interaction::zoning::{mark_based_on_zoning, PlacementRejected},
+        simulation::geometry::{Facing, MapGeometry, TilePos},
+        units::{
+            actions::{advance_action_timer, choose_actions, finish_actions, start_actions},
+            goals::Goal,
+            test_fixture::{spawn_unit, UnitTestApp},
+        },
+    };
+
+    #[test]
+    fn units_raise_terrain_zoned_for_terraforming() {
+        let loam = Id::<Terrain>::from_name("loam");
+
+        let mut app = UnitTestApp::new(1).build();
+        app.add_event::<PlacementRejected>().add_systems(
+            (
+                mark_based_on_zoning,
+                advance_action_timer,
+                start_actions,
+                finish_actions,
+                choose_actions,
+                complete_terraforming,
+            )
+                .chain(),
+        );
+
+        let mut terrain_query = app.world.query_filtered::<Entity, With<Id<Terrain>>>();
+        let terrain_entities: Vec<Entity> = terrain_query.iter(&app.world).collect();
+        for terrain_entity in terrain_entities {
+            app.world.entity_mut(terrain_entity).insert(Zoning::None);
+        }
+
+        let unit_tile_pos = TilePos::ZERO;
+        let facing = Facing::default();
+        let target_tile_pos = unit_tile_pos.neighbor(facing.direction);
+        let target_entity = app
+            .world
+            .resource::<MapGeometry>()
+            .get_terrain(target_tile_pos)
+            .unwrap();
+
+        spawn_unit(
+            &mut app,
+            unit_tile_pos,
+            Id::from_name("ant"),
+            Goal::Terraform,
+        );
+
+        *app.world.get_mut::<Zoning>(target_entity).unwrap() =
+            Zoning::Terraform(TerraformingChoice::Raise.into_mark(Height(0), loam));
 
-        // Despawn any structures here; terraforming can't be done with roots growing into stuff!
-        commands.despawn_structure(*tile_pos);
+        for _ in 0..20 {
+            app.update();
+
+            if *app.world.get::<Height>(target_entity).unwrap() == Height(1) {
+                break;
+            }
+        }
+
+        assert_eq!(*app.world.get::<Height>(target_entity).unwrap(), Height(1));
+
+        // Once the work is done, the tile should no longer be zoned for terraforming
+        app.update();
+        assert_eq!(
+            *app.world.get::<Zoning>(target_entity).unwrap(),
+            Zoning::None
+        );
+        assert!(app
+            .world
+            .get::<MarkedForTerraforming>(target_entity)
+            .is_none());
     }
 }
@@ -0,0 +1,327 @@
+//! A scripted sequence of objectives that walks new players through the basics.
+//!
+//! Each [`TutorialStep`] has a message for the player, and an [`Objective`] that is checked against the state of the world.
+//! Once the objective of the current step is met, the [`Tutorial`] automatically moves on to the next one.
+
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+
+use crate::{
+    asset_management::manifest::Id,
+    items::item_manifest::Item,
+    simulation::SimulationSet,
+    structures::{
+        construction::{ConstructionCompleted, Ghost, Preview},
+        crafting::{InputInventory, OutputInventory, StorageInventory},
+        structure_manifest::Structure,
+    },
+    units::{item_interaction::UnitInventory, unit_manifest::Unit},
+};
+
+/// Advances the [`Tutorial`] as the player completes each objective.
+pub(super) struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Tutorial::base_game()).add_system(
+            advance_tutorial
+                .in_set(SimulationSet)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// Something the player must accomplish to move on to the next step of the tutorial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Objective {
+    /// At least `count` structures of this type must be constructed while the tutorial is running.
+    ///
+    /// Only structures built from ghosts count: those placed by world generation do not.
+    BuildStructure {
+        /// The type of structure to build.
+        structure_id: Id<Structure>,
+        /// How many must be built.
+        count: usize,
+    },
+    /// At least `count` items of this type must be held by the colony, in structures or carried by units.
+    CollectItems {
+        /// The type of item to collect.
+        item_id: Id<Item>,
+        /// How many must be collected.
+        count: usize,
+    },
+    /// At least `count` units of this type must be alive.
+    ReachPopulation {
+        /// The type of unit to count.
+        unit_id: Id<Unit>,
+        /// How many must be alive.
+        count: usize,
+    },
+}
+
+/// A single step of the [`Tutorial`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TutorialStep {
+    /// The instructions shown to the player while this step is active.
+    pub(crate) message: String,
+    /// What must be done to complete this step.
+    pub(crate) objective: Objective,
+}
+
+impl TutorialStep {
+    /// Creates a new [`TutorialStep`].
+    pub(crate) fn new(message: &str, objective: Objective) -> Self {
+        TutorialStep {
+            message: message.to_string(),
+            objective,
+        }
+    }
+}
+
+/// An ordered list of [`TutorialStep`]s, and how far the player has made it through them.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Tutorial {
+    /// The steps of the tutorial, in order.
+    steps: Vec<TutorialStep>,
+    /// The index of the step that the player is currently working on.
+    current: usize,
+    /// The number of structures of each type that have finished construction.
+    constructed: HashMap<Id<Structure>, usize>,
+}
+
+impl Tutorial {
+    /// Creates a new tutorial, starting at the first of the provided `steps`.
+    pub(crate) fn new(steps: Vec<TutorialStep>) -> Self {
+        Tutorial {
+            steps,
+            current: 0,
+            constructed: HashMap::default(),
+        }
+    }
+
+    /// The tutorial for the base game, introducing players to the core production loop.
+    pub(crate) fn base_game() -> Self {
+        Tutorial::new(vec![
+            TutorialStep::new(
+                "Zone a leuco so that your ants can build it.",
+                Objective::BuildStructure {
+                    structure_id: Id::from_name("leuco"),
+                    count: 1,
+                },
+            ),
+            TutorialStep::new(
+                "Leucos turn acacia leaves into food. Produce 5 leuco chunks.",
+                Objective::CollectItems {
+                    item_id: Id::from_name("leuco_chunk"),
+                    count: 5,
+                },
+            ),
+            TutorialStep::new(
+                "Build storage to keep your surplus safe.",
+                Objective::BuildStructure {
+                    structure_id: Id::from_name("storage"),
+                    count: 1,
+                },
+            ),
+            TutorialStep::new(
+                "Grow your colony to 10 ants.",
+                Objective::ReachPopulation {
+                    unit_id: Id::from_name("ant"),
+                    count: 10,
+                },
+            ),
+        ])
+    }
+
+    /// The step that the player is currently working on, if the tutorial is not yet complete.
+    pub(crate) fn current_step(&self) -> Option<&TutorialStep> {
+        self.steps.get(self.current)
+    }
+
+    /// Has every step of the tutorial been completed?
+    pub(crate) fn is_complete(&self) -> bool {
+        self.current >= self.steps.len()
+    }
+
+    /// Records that a structure of type `structure_id` has finished construction.
+    fn record_construction(&mut self, structure_id: Id<Structure>) {
+        *self.constructed.entry(structure_id).or_default() += 1;
+    }
+
+    /// The number of structures of type `structure_id` that have finished construction.
+    fn n_constructed(&self, structure_id: Id<Structure>) -> usize {
+        self.constructed
+            .get(&structure_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Moves on to the next step.
+    fn advance(&mut self) {
+        if !self.is_complete() {
+            self.current += 1;
+        }
+    }
+}
+
+/// The world state needed to check whether an [`Objective`] has been met.
+#[derive(SystemParam)]
+pub(crate) struct ObjectiveQuery<'w, 's> {
+    /// Every inventory that can hold items
+    inventory_query: Query<
+        'w,
+        's,
+        AnyOf<(
+            &'static InputInventory,
+            &'static OutputInventory,
+            &'static StorageInventory,
+        )>,
+        (Without<Ghost>, Without<Preview>),
+    >,
+    /// The units, and what they're carrying
+    unit_query: Query<'w, 's, (&'static Id<Unit>, &'static UnitInventory)>,
+}
+
+impl<'w, 's> ObjectiveQuery<'w, 's> {
+    /// Has the provided `objective` of the `tutorial` been met?
+    pub(crate) fn is_met(&self, objective: &Objective, tutorial: &Tutorial) -> bool {
+        match *objective {
+            Objective::BuildStructure {
+                structure_id,
+                count,
+            } => tutorial.n_constructed(structure_id) >= count,
+            Objective::CollectItems { item_id, count } => {
+                let stored: usize = self
+                    .inventory_query
+                    .iter()
+                    .map(|(maybe_input, maybe_output, maybe_storage)| {
+                        maybe_input.map_or(0, |inventory| inventory.item_count(item_id))
+                            + maybe_output.map_or(0, |inventory| inventory.item_count(item_id))
+                            + maybe_storage.map_or(0, |inventory| inventory.item_count(item_id))
+                    })
+                    .sum();
+                let carried = self
+                    .unit_query
+                    .iter()
+                    .filter(|(_, unit_inventory)| unit_inventory.held_item == Some(item_id))
+                    .count();
+                stored + carried >= count
+            }
+            Objective::ReachPopulation { unit_id, count } => {
+                let alive = self
+                    .unit_query
+                    .iter()
+                    .filter(|(&id, _)| id == unit_id)
+                    .count();
+                alive >= count
+            }
+        }
+    }
+}
+
+/// Moves the [`Tutorial`] on to the next step once the current objective has been met.
+fn advance_tutorial(
+    mut tutorial: ResMut<Tutorial>,
+    mut construction_events: EventReader<ConstructionCompleted>,
+    objective_query: ObjectiveQuery,
+) {
+    for event in construction_events.iter() {
+        tutorial.record_construction(event.structure_id);
+    }
+
+    let Some(step) = tutorial.current_step() else {
+        return;
+    };
+
+    if objective_query.is_met(&step.objective, &tutorial) {
+        tutorial.advance();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        items::{
+            inventory::{Inventory, ReservedFor},
+            item_manifest::{ItemData, ItemManifest},
+            ItemCount,
+        },
+        simulation::geometry::TilePos,
+    };
+
+    #[test]
+    fn tutorial_advances_as_objectives_are_met() {
+        let leuco = Id::<Structure>::from_name("leuco");
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+
+        let mut app = App::new();
+        app.insert_resource(Tutorial::new(vec![
+            TutorialStep::new(
+                "Build a leuco",
+                Objective::BuildStructure {
+                    structure_id: leuco,
+                    count: 1,
+                },
+            ),
+            TutorialStep::new(
+                "Produce 5 leuco chunks",
+                Objective::CollectItems {
+                    item_id: leuco_chunk,
+                    count: 5,
+                },
+            ),
+        ]))
+        .add_event::<ConstructionCompleted>()
+        .add_system(advance_tutorial);
+
+        // Ghosts don't count as built, and neither do structures that were already there
+        app.world.spawn((leuco, Ghost));
+        app.world.spawn(leuco);
+        app.update();
+        assert_eq!(
+            app.world
+                .resource::<Tutorial>()
+                .current_step()
+                .unwrap()
+                .message,
+            "Build a leuco"
+        );
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+        let mut output_inventory = OutputInventory {
+            inventory: Inventory::new(1, ReservedFor::Any),
+        };
+        output_inventory
+            .add_item_all_or_nothing(&ItemCount::new(leuco_chunk, 4), &item_manifest)
+            .unwrap();
+        let leuco_entity = app.world.spawn((leuco, output_inventory)).id();
+        app.world.send_event(ConstructionCompleted {
+            tile_pos: TilePos::ZERO,
+            structure_id: leuco,
+        });
+        app.update();
+        assert_eq!(
+            app.world
+                .resource::<Tutorial>()
+                .current_step()
+                .unwrap()
+                .message,
+            "Produce 5 leuco chunks"
+        );
+
+        // Not quite enough chunks yet
+        app.update();
+        assert!(!app.world.resource::<Tutorial>().is_complete());
+
+        app.world
+            .get_mut::<OutputInventory>(leuco_entity)
+            .unwrap()
+            .add_item_all_or_nothing(&ItemCount::new(leuco_chunk, 1), &item_manifest)
+            .unwrap();
+        app.update();
+        let tutorial = app.world.resource::<Tutorial>();
+        assert!(tutorial.is_complete());
+        assert_eq!(tutorial.current_step(), None);
+    }
+}
@@ -1,16 +1,20 @@
 //! Zoning is used to indicate that a tile should contain the specified structure.
 
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashSet};
 use leafwing_input_manager::prelude::ActionState;
 
 use crate::{
-    asset_management::{manifest::Id, AssetState},
+    asset_management::{localization::Localization, manifest::Id, AssetState},
     signals::{Emitter, SignalStrength, SignalType},
-    simulation::geometry::{Height, MapGeometry, TilePos},
+    simulation::{
+        factions::Faction,
+        geometry::{Height, MapGeometry, TilePos},
+    },
     structures::{
         commands::StructureCommandsExt,
         construction::{MarkedForDemolition, Preview},
         structure_manifest::{Structure, StructureManifest},
+        upgrades::MarkedForUpgrade,
     },
     terrain::terrain_manifest::{Terrain, TerrainManifest},
 };
@@ -28,24 +32,34 @@ pub(super) struct ZoningPlugin;
 
 impl Plugin for ZoningPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            (mark_for_demolition, set_zoning)
-                .in_set(InteractionSystem::ApplyZoning)
-                .after(InteractionSystem::SelectTiles)
-                .after(InteractionSystem::SetClipboard),
-        )
-        .add_system(cleanup_previews.after(set_zoning))
-        .add_system(
-            mark_based_on_zoning
-                .in_set(InteractionSystem::ManagePreviews)
-                .run_if(in_state(AssetState::Ready))
-                .after(InteractionSystem::ApplyZoning),
-        )
-        // Must run after crafting emitters in order to wipe out their signals
-        .add_system(keep_tiles_clear.after(crate::structures::crafting::set_crafting_emitter));
+        app.add_event::<PlacementRejected>()
+            .add_systems(
+                (mark_for_demolition, set_zoning)
+                    .in_set(InteractionSystem::ApplyZoning)
+                    .after(InteractionSystem::SelectTiles)
+                    .after(InteractionSystem::SetClipboard),
+            )
+            .add_system(cleanup_previews.after(set_zoning))
+            .add_system(
+                mark_based_on_zoning
+                    .in_set(InteractionSystem::ManagePreviews)
+                    .run_if(in_state(AssetState::Ready))
+                    .after(InteractionSystem::ApplyZoning),
+            )
+            // Must run after crafting emitters in order to wipe out their signals
+            .add_system(keep_tiles_clear.after(crate::structures::crafting::set_crafting_emitter));
     }
 }
 
+/// An event sent whenever a structure cannot be zoned on a tile, because the terrain there is unsuitable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PlacementRejected {
+    /// The tile that the structure was meant to be placed on.
+    pub(crate) tile_pos: TilePos,
+    /// The variety of structure that was rejected.
+    pub(crate) structure_id: Id<Structure>,
+}
+
 /// The zoning of a given tile, which specifies which structure *should* be built there.
 #[derive(Component, PartialEq, Eq, Clone, Debug)]
 pub(crate) enum Zoning {
@@ -65,12 +79,13 @@ impl Zoning {
         &self,
         structure_manifest: &StructureManifest,
         terrain_manifest: &TerrainManifest,
+        localization: &Localization,
     ) -> String {
         match self {
-            Zoning::Structure(clipboard_data) => structure_manifest
-                .name(clipboard_data.structure_id)
+            Zoning::Structure(clipboard_data) => localization
+                .get(structure_manifest.name(clipboard_data.structure_id))
                 .to_string(),
-            Zoning::Terraform(mark) => mark.display(terrain_manifest),
+            Zoning::Terraform(mark) => mark.display(terrain_manifest, localization),
             Zoning::None => "None".to_string(),
             Zoning::KeepClear => "Keep Clear".to_string(),
         }
@@ -203,18 +218,43 @@ fn set_zoning(
     }
 }
 
-/// Mark the selected structure for deletion.
+/// Mark the selected structures for deletion.
+///
+/// If an area of tiles is selected, [`PlayerAction::KeepClear`] marks every structure that overlaps it.
+/// [`PlayerAction::ClearZoning`] only ever resets the zoning of selected tiles, leaving their structures alone.
+/// Structures that units cannot tear down are skipped:
+/// those that never emit [`SignalType::Demolish`] signals, and those owned by other factions.
 ///
 /// Note that this is distinct from setting the tile to [`Zoning::KeepClear`], as it does not persist.
 fn mark_for_demolition(
     player_actions: Res<ActionState<PlayerAction>>,
     current_selection: Res<CurrentSelection>,
+    demolishable_query: Query<Option<&Faction>, (With<Id<Structure>>, With<Emitter>)>,
+    map_geometry: Res<MapGeometry>,
     mut commands: Commands,
 ) {
-    if player_actions.just_pressed(PlayerAction::KeepClear)
-        || player_actions.just_pressed(PlayerAction::ClearZoning)
-    {
-        if let CurrentSelection::Structure(structure_entity) = *current_selection {
+    let keep_clear = player_actions.just_pressed(PlayerAction::KeepClear);
+    let clear_zoning = player_actions.just_pressed(PlayerAction::ClearZoning);
+
+    // Multi-tile structures may overlap the selection several times
+    let structure_entities: HashSet<Entity> = match &*current_selection {
+        CurrentSelection::Structure(structure_entity) if keep_clear || clear_zoning => {
+            HashSet::from_iter([*structure_entity])
+        }
+        CurrentSelection::Terrain(selected_tiles) if keep_clear => selected_tiles
+            .selection()
+            .iter()
+            .filter_map(|&tile_pos| map_geometry.get_structure(tile_pos))
+            .collect(),
+        _ => HashSet::new(),
+    };
+
+    for structure_entity in structure_entities {
+        let Ok(maybe_faction) = demolishable_query.get(structure_entity) else {
+            continue;
+        };
+
+        if maybe_faction.copied().unwrap_or_default() == Faction::COLONY {
             commands
                 .entity(structure_entity)
                 .insert(MarkedForDemolition);
@@ -223,27 +263,82 @@ fn mark_for_demolition(
 }
 
 /// Spawn and despawn ghosts and apply other markings based on zoning.
-fn mark_based_on_zoning(
+pub(super) fn mark_based_on_zoning(
     mut terrain_query: Query<(Entity, &mut Zoning, &TilePos, &Id<Terrain>), Changed<Zoning>>,
+    structure_query: Query<(&Id<Structure>, Option<&MarkedForUpgrade>)>,
     structure_manifest: Res<StructureManifest>,
     mut commands: Commands,
     map_geometry: Res<MapGeometry>,
+    mut rejection_events: EventWriter<PlacementRejected>,
 ) {
     for (terrain_entity, mut zoning, &tile_pos, &terrain) in terrain_query.iter_mut() {
+        // Any previous terraforming order is overridden by the new zoning
+        if !matches!(*zoning, Zoning::Terraform(..)) {
+            commands
+                .entity(terrain_entity)
+                .remove::<(MarkedForTerraforming, Emitter)>();
+        }
+
+        let maybe_structure_entity = map_geometry.get_structure(tile_pos);
+        let maybe_upgrade = maybe_structure_entity.and_then(|structure_entity| {
+            let (&structure_id, maybe_mark) = structure_query.get(structure_entity).ok()?;
+            let upgrades_to = structure_manifest.get(structure_id).upgrades_to?;
+            Some((structure_entity, structure_id, upgrades_to, maybe_mark))
+        });
+
+        // Any previous upgrade order is overridden by the new zoning
+        if let Some((structure_entity, _, _, Some(mark))) = maybe_upgrade {
+            let still_wanted = match &*zoning {
+                Zoning::Structure(clipboard_data) => {
+                    clipboard_data.structure_id == mark.upgrades_to()
+                }
+                _ => false,
+            };
+            if !still_wanted {
+                commands
+                    .entity(structure_entity)
+                    .remove::<MarkedForUpgrade>();
+            }
+        }
+
         // Reborrowing here would trigger change detection, causing this system to constantly check
         match zoning.bypass_change_detection() {
             Zoning::Structure(clipboard_data) => {
+                // Zoning an existing structure as its upgraded form upgrades it in place
+                if let Some((structure_entity, structure_id, upgrades_to, maybe_mark)) =
+                    maybe_upgrade
+                {
+                    if upgrades_to == clipboard_data.structure_id {
+                        if maybe_mark.is_none() {
+                            let upgrade_cost = &structure_manifest.get(structure_id).upgrade_cost;
+                            commands
+                                .entity(structure_entity)
+                                .insert(MarkedForUpgrade::new(upgrades_to, upgrade_cost));
+                        }
+                        continue;
+                    }
+                }
+
                 let structure_data = structure_manifest.get(clipboard_data.structure_id);
                 if structure_data.allowed_terrain_types().contains(&terrain) {
-                    commands.spawn_ghost(tile_pos, clipboard_data.clone())
+                    commands.spawn_ghost(tile_pos, clipboard_data.clone(), Faction::COLONY)
                 } else {
+                    rejection_events.send(PlacementRejected {
+                        tile_pos,
+                        structure_id: clipboard_data.structure_id,
+                    });
                     *zoning = Zoning::None;
                     // We bypassed change detection above, so need to manually trigger it here.
                     zoning.set_changed();
                 }
             }
             Zoning::Terraform(mark) => {
-                commands.entity(terrain_entity).insert(*mark);
+                commands.entity(terrain_entity).insert((
+                    *mark,
+                    Emitter {
+                        signals: vec![(SignalType::Terraform, SignalStrength::new(100.))],
+                    },
+                ));
             }
             Zoning::None => commands.despawn_ghost(tile_pos),
             Zoning::KeepClear => {
@@ -269,3 +364,124 @@ fn keep_tiles_clear(
         )];
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        player_interaction::selection::SelectedTiles, structures::construction::Footprint,
+    };
+
+    /// The structures spawned by [`demolition_app`].
+    struct DemolitionTargets {
+        /// Colony structures inside the selected area.
+        inside: [Entity; 3],
+        /// A colony structure outside of the selected area.
+        outside: Entity,
+        /// A structure inside the area that belongs to another faction.
+        rival: Entity,
+        /// A structure inside the area that units cannot tear down.
+        undemolishable: Entity,
+    }
+
+    /// Selects a hexagonal area of tiles, containing a variety of structures, and presses `action`.
+    fn demolition_app(action: PlayerAction) -> (App, DemolitionTargets) {
+        let storage = Id::<Structure>::from_name("storage");
+        let bridge = Id::<Structure>::from_name("bridge");
+
+        let mut app = App::new();
+        let mut map_geometry = MapGeometry::new(2);
+        let mut selected_tiles = SelectedTiles::default();
+        for tile_pos in TilePos::ZERO.spiral(1, &map_geometry).collect::<Vec<_>>() {
+            selected_tiles.add_tile(tile_pos);
+        }
+
+        let mut spawn_structure = |tile_pos: TilePos, structure_id, bundle| {
+            let structure_entity = app.world.spawn((structure_id, bundle)).id();
+            map_geometry.add_structure(
+                tile_pos,
+                &Footprint::single(),
+                structure_id,
+                structure_entity,
+            );
+            structure_entity
+        };
+
+        let inside = [TilePos::ZERO, TilePos::new(1, 0), TilePos::new(0, -1)].map(|tile_pos| {
+            spawn_structure(tile_pos, storage, (Emitter::default(), Faction::COLONY))
+        });
+        let outside = spawn_structure(
+            TilePos::new(2, 0),
+            storage,
+            (Emitter::default(), Faction::COLONY),
+        );
+        let rival = spawn_structure(
+            TilePos::new(-1, 0),
+            storage,
+            (Emitter::default(), Faction(1)),
+        );
+        // Bridges never emit demolition signals, so units could never tear them down
+        let undemolishable = app.world.spawn(bridge).id();
+        map_geometry.add_structure(
+            TilePos::new(0, 1),
+            &Footprint::single(),
+            bridge,
+            undemolishable,
+        );
+
+        let mut player_actions = ActionState::<PlayerAction>::default();
+        player_actions.press(action);
+
+        app.insert_resource(map_geometry)
+            .insert_resource(player_actions)
+            .insert_resource(CurrentSelection::Terrain(selected_tiles))
+            .add_system(mark_for_demolition);
+        app.update();
+
+        let targets = DemolitionTargets {
+            inside,
+            outside,
+            rival,
+            undemolishable,
+        };
+        (app, targets)
+    }
+
+    #[test]
+    fn demolishing_an_area_marks_every_demolishable_structure() {
+        let (app, targets) = demolition_app(PlayerAction::KeepClear);
+        let DemolitionTargets {
+            inside,
+            outside,
+            rival,
+            undemolishable,
+        } = targets;
+
+        for structure_entity in inside {
+            assert!(app
+                .world
+                .get::<MarkedForDemolition>(structure_entity)
+                .is_some());
+        }
+        assert!(app.world.get::<MarkedForDemolition>(outside).is_none());
+        assert!(app.world.get::<MarkedForDemolition>(rival).is_none());
+        assert!(app
+            .world
+            .get::<MarkedForDemolition>(undemolishable)
+            .is_none());
+    }
+
+    #[test]
+    fn clearing_zoning_over_an_area_demolishes_nothing() {
+        let (mut app, targets) = demolition_app(PlayerAction::ClearZoning);
+
+        let mut marked_query = app
+            .world
+            .query_filtered::<Entity, With<MarkedForDemolition>>();
+        assert_eq!(marked_query.iter(&app.world).count(), 0);
+        assert!(app
+            .world
+            .get::<MarkedForDemolition>(targets.inside[0])
+            .is_none());
+    }
+}
@@ -7,13 +7,16 @@ use crate as emergence_lib;
 use crate::items::item_manifest::{Item, ItemManifest};
 use crate::structures::structure_manifest::{Structure, StructureManifest};
 use crate::units::unit_manifest::{Unit, UnitManifest};
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use core::ops::{Add, AddAssign, Mul, Sub, SubAssign};
 use emergence_macros::IterableEnum;
+use hexx::shapes::hexagon;
 use itertools::Itertools;
-use rand::seq::SliceRandom;
 
-use crate::asset_management::manifest::Id;
+use crate::asset_management::{localization::Localization, manifest::Id};
 use crate::simulation::geometry::{MapGeometry, TilePos};
 use crate::simulation::SimulationSet;
 use crate::units::goals::Goal;
@@ -27,17 +30,25 @@ use crate::units::goals::Goal;
 /// and probably should be below 1/7 to avoid weirdness.
 pub const DIFFUSION_FRACTION: f32 = 0.1;
 
+/// The fraction of signal that will decay at each step.
+///
+/// Higher values lead to faster decay and improved signal responsiveness.
+/// This must always be between 0 and 1.
+pub const DEGRADATION_FRACTION: f32 = 0.01;
+
 /// The resources and systems need to work with signals
 pub(crate) struct SignalsPlugin;
 
 impl Plugin for SignalsPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Signals>().add_systems(
-            (emit_signals, diffuse_signals, degrade_signals)
-                .chain()
-                .in_set(SimulationSet)
-                .in_schedule(CoreSchedule::FixedUpdate),
-        );
+        app.init_resource::<Signals>()
+            .init_resource::<SignalPropagationConfig>()
+            .add_systems(
+                (emit_signals, diffuse_signals, degrade_signals)
+                    .chain()
+                    .in_set(SimulationSet)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
     }
 }
 
@@ -49,6 +60,37 @@ pub struct Signals {
 }
 
 impl Signals {
+    /// Adds `signal_strength` of `signal_type` to every tile within `work_radius` of the `footprint`.
+    ///
+    /// The signal is strongest on the footprint itself, and weakens linearly with distance,
+    /// so units that pick it up far away can follow it back to the workplace.
+    fn add_work_signal(
+        &mut self,
+        signal_type: SignalType,
+        footprint: &HashSet<TilePos>,
+        signal_strength: SignalStrength,
+        work_radius: u32,
+        map_geometry: &MapGeometry,
+    ) {
+        let mut distances: HashMap<TilePos, u32> = HashMap::new();
+        for footprint_tile in footprint {
+            for hex in hexagon(footprint_tile.hex, work_radius) {
+                let distance = footprint_tile.unsigned_distance_to(hex);
+                distances
+                    .entry(TilePos { hex })
+                    .and_modify(|shortest| *shortest = (*shortest).min(distance))
+                    .or_insert(distance);
+            }
+        }
+
+        for (tile_pos, distance) in distances {
+            if map_geometry.is_valid(tile_pos) {
+                let falloff = (work_radius + 1 - distance) as f32 / (work_radius + 1) as f32;
+                self.add_signal(signal_type, tile_pos, signal_strength * falloff);
+            }
+        }
+    }
+
     /// Returns the signal strength of `signal_type` at the given `tile_pos`.
     ///
     /// Missing values will be filled with [`SignalStrength::ZERO`].
@@ -114,6 +156,7 @@ impl Signals {
 
     /// Returns the adjacent, empty tile position that contains the highest sum signal strength that can be used to meet the provided `goal`.
     ///
+    /// Hazardous neighbors are never chosen, so units will route around them.
     /// If no suitable tile exists, [`None`] will be returned instead.
     pub(crate) fn upstream(
         &self,
@@ -125,7 +168,7 @@ impl Signals {
         let mut best_score = SignalStrength::ZERO;
 
         let neighboring_signals = match goal {
-            Goal::Wander { .. } => return None,
+            Goal::Wander { .. } | Goal::Explore { .. } => return None,
             Goal::Pickup(item_id) | Goal::Eat(item_id) => {
                 let push_signals =
                     self.neighboring_signals(SignalType::Push(*item_id), tile_pos, map_geometry);
@@ -161,10 +204,14 @@ impl Signals {
                     }
                 }
 
+                self.apply_backpressure(&mut total_signals, *item_id, tile_pos, map_geometry);
                 total_signals
             }
             Goal::Deliver(item_id) => {
-                self.neighboring_signals(SignalType::Pull(*item_id), tile_pos, map_geometry)
+                let mut total_signals =
+                    self.neighboring_signals(SignalType::Pull(*item_id), tile_pos, map_geometry);
+                self.apply_backpressure(&mut total_signals, *item_id, tile_pos, map_geometry);
+                total_signals
             }
             Goal::Work(structure_id) => {
                 self.neighboring_signals(SignalType::Work(*structure_id), tile_pos, map_geometry)
@@ -174,9 +221,23 @@ impl Signals {
                 tile_pos,
                 map_geometry,
             ),
+            Goal::Repair(structure_id) => {
+                self.neighboring_signals(SignalType::Repair(*structure_id), tile_pos, map_geometry)
+            }
+            Goal::Upgrade(structure_id) => {
+                self.neighboring_signals(SignalType::Upgrade(*structure_id), tile_pos, map_geometry)
+            }
+            Goal::Terraform => {
+                self.neighboring_signals(SignalType::Terraform, tile_pos, map_geometry)
+            }
         };
 
         for (possible_tile, current_score) in neighboring_signals {
+            // Route around hazards, rather than walking straight into them
+            if possible_tile != tile_pos && map_geometry.is_hazardous(possible_tile) {
+                continue;
+            }
+
             if current_score > best_score {
                 best_score = current_score;
                 best_choice = Some(possible_tile);
@@ -194,6 +255,26 @@ impl Signals {
         }
     }
 
+    /// Reduces the strength of `signals` near inventories that are already full of `item_id`.
+    ///
+    /// This stops units from converging on saturated destinations.
+    fn apply_backpressure(
+        &self,
+        signals: &mut HashMap<TilePos, SignalStrength>,
+        item_id: Id<Item>,
+        tile_pos: TilePos,
+        map_geometry: &MapGeometry,
+    ) {
+        let contained_signals =
+            self.neighboring_signals(SignalType::Contained(item_id), tile_pos, map_geometry);
+
+        for (tile_pos, signal_strength) in contained_signals {
+            if let Some(existing_signal_strength) = signals.get_mut(&tile_pos) {
+                *existing_signal_strength -= signal_strength;
+            }
+        }
+    }
+
     /// Returns the signal strength of the type `signal_type` in `tile_pos` and its 6 surrounding neighbors.
     fn neighboring_signals(
         &self,
@@ -212,48 +293,50 @@ impl Signals {
     }
 
     /// Diffuses signals from one cell into the next
-    pub fn diffuse(&mut self, map_geometry: &MapGeometry, diffusion_fraction: f32) {
-        for original_map in self.maps.values_mut() {
-            let num_elements = original_map.map.len();
-            let size_hint = num_elements * 6;
-            let mut addition_map = Vec::with_capacity(size_hint);
-            let mut removal_map = Vec::with_capacity(size_hint);
-
-            for (&occupied_tile, original_strength) in original_map
-                .map
-                .iter()
-                .filter(|(_, signal_strength)| SignalStrength::ZERO.ne(signal_strength))
-            {
-                let amount_to_send_to_each_neighbor = *original_strength * diffusion_fraction;
-
-                let mut num_neighbors = 0.0;
-                for neighboring_tile in occupied_tile.empty_neighbors(map_geometry) {
-                    num_neighbors += 1.0;
-                    addition_map.push((neighboring_tile, amount_to_send_to_each_neighbor));
-                }
-                removal_map.push((
-                    occupied_tile,
-                    amount_to_send_to_each_neighbor * num_neighbors,
-                ));
-            }
-
-            // We cannot do this in one step, as we need to avoid bizarre iteration order dependencies
-            for (removal_pos, removal_strength) in removal_map.into_iter() {
-                original_map.subtract_signal(removal_pos, removal_strength)
-            }
+    ///
+    /// The fraction of each signal that spreads is looked up by signal type in the `propagation_config`.
+    pub fn diffuse(
+        &mut self,
+        map_geometry: &MapGeometry,
+        propagation_config: &SignalPropagationConfig,
+    ) {
+        for (signal_type, signal_map) in self.maps.iter_mut() {
+            let diffusion_fraction = propagation_config.get(*signal_type).diffusion_fraction();
+            signal_map.diffuse(diffusion_fraction, map_geometry);
+        }
+    }
 
-            for (addition_pos, addition_strength) in addition_map.into_iter() {
-                original_map.add_signal(addition_pos, addition_strength)
-            }
+    /// Degrades signals, allowing them to approach an asymptotically constant level.
+    ///
+    /// The fraction of each signal that decays is looked up by signal type in the `propagation_config`.
+    pub fn degrade(&mut self, propagation_config: &SignalPropagationConfig) {
+        /// The value below which decayed signals are eliminated completely
+        ///
+        /// Increasing this value will:
+        ///  - increase computational costs
+        ///  - increase the range at which tasks can be detected
+        ///  - increase the amount of time units will wait around for more production
+        const EPSILON_STRENGTH: SignalStrength = SignalStrength(1e-8);
+
+        for (signal_type, signal_map) in self.maps.iter_mut() {
+            let degradation_fraction = propagation_config.get(*signal_type).degradation_fraction();
+            signal_map.degrade(degradation_fraction, EPSILON_STRENGTH);
         }
     }
 
-    /// Returns a random signal type present in the map.
-    pub(crate) fn random_signal_type(&self) -> Option<SignalType> {
-        let mut rng = rand::thread_rng();
-        let mut keys: Vec<&SignalType> = self.maps.keys().collect();
-        keys.shuffle(&mut rng);
-        keys.pop().copied()
+    /// Returns the signal type present in the map that comes after `current`, in sorted order.
+    ///
+    /// If `current` is `None`, the first signal type is returned.
+    /// Returns `None` once every signal type has been cycled through.
+    pub(crate) fn next_signal_type(&self, current: Option<SignalType>) -> Option<SignalType> {
+        self.maps
+            .keys()
+            .filter(|&&signal_type| match current {
+                Some(current) => signal_type > current,
+                None => true,
+            })
+            .min()
+            .copied()
     }
 }
 
@@ -280,6 +363,7 @@ impl LocalSignals {
         item_manifest: &ItemManifest,
         structure_manifest: &StructureManifest,
         unit_manifest: &UnitManifest,
+        localization: &Localization,
     ) -> String {
         let mut string = String::default();
 
@@ -288,7 +372,12 @@ impl LocalSignals {
 
             let substring = format!(
                 "{}: {signal_strength:.3}\n",
-                signal_type.display(item_manifest, structure_manifest, unit_manifest)
+                signal_type.display(
+                    item_manifest,
+                    structure_manifest,
+                    unit_manifest,
+                    localization
+                )
             );
 
             string += &substring;
@@ -299,37 +388,286 @@ impl LocalSignals {
 }
 
 /// Stores the [`SignalStrength`] of the given [`SignalType`] at each [`TilePos`].
-#[derive(Debug, Default)]
-struct SignalMap {
-    /// The lookup data structure
-    map: HashMap<TilePos, SignalStrength>,
+///
+/// Most signal types only cover a small part of the map, so they start out stored sparsely,
+/// and diffusing them only visits the tiles that have a signal.
+/// Once a signal has spread over enough of the map, it is moved into a dense [`SignalGrid`],
+/// and moved back out again if it fades away.
+#[derive(Debug)]
+enum SignalMap {
+    /// Only the tiles with a signal are stored.
+    Sparse(HashMap<TilePos, SignalStrength>),
+    /// Every tile in the map is stored.
+    Dense(SignalGrid),
+}
+
+impl Default for SignalMap {
+    fn default() -> Self {
+        SignalMap::Sparse(HashMap::default())
+    }
 }
 
 impl SignalMap {
+    /// Sparse maps that cover more than this fraction of a dense grid for the map are made dense.
+    const DENSE_FRACTION: usize = 8;
+
+    /// Dense maps that cover less than this fraction of their grid are made sparse again.
+    ///
+    /// This is well below [`SignalMap::DENSE_FRACTION`], so maps near the threshold don't flip back and forth.
+    const SPARSE_FRACTION: usize = 32;
+
     /// Returns the signal strenth at the given [`TilePos`].
     ///
     /// Missing values will be filled with [`SignalStrength::ZERO`].
     fn get(&self, tile_pos: TilePos) -> SignalStrength {
-        *self.map.get(&tile_pos).unwrap_or(&SignalStrength::ZERO)
+        match self {
+            SignalMap::Sparse(map) => *map.get(&tile_pos).unwrap_or(&SignalStrength::ZERO),
+            SignalMap::Dense(grid) => grid.get(tile_pos),
+        }
+    }
+
+    /// Adds the `signal_strength` to the signal at `tile_pos`.
+    fn add_signal(&mut self, tile_pos: TilePos, signal_strength: SignalStrength) {
+        match self {
+            SignalMap::Sparse(map) => {
+                *map.entry(tile_pos).or_insert(SignalStrength::ZERO) += signal_strength
+            }
+            SignalMap::Dense(grid) => *grid.get_mut(tile_pos) += signal_strength,
+        }
+    }
+
+    /// Spreads `diffusion_fraction` of the signal on each tile to each of its empty neighbors.
+    fn diffuse(&mut self, diffusion_fraction: f32, map_geometry: &MapGeometry) {
+        // Every tile in the map must fit in the grid, so that signals can spread into it
+        let map_radius = map_geometry.radius as i32;
+        let grid_len = SignalGrid::len(map_radius);
+
+        if let SignalMap::Sparse(map) = self {
+            if map.len() > grid_len / SignalMap::DENSE_FRACTION {
+                let mut grid = SignalGrid::default();
+                grid.grow_to_radius(map_radius);
+                for (&tile_pos, &signal_strength) in map.iter() {
+                    *grid.get_mut(tile_pos) += signal_strength;
+                }
+                *self = SignalMap::Dense(grid);
+            }
+        }
+
+        match self {
+            SignalMap::Sparse(map) => {
+                let mut addition_map = Vec::with_capacity(map.len() * 6);
+                let mut removal_map = Vec::with_capacity(map.len());
+
+                for (&occupied_tile, &original_strength) in map.iter() {
+                    let amount_to_send_to_each_neighbor = original_strength * diffusion_fraction;
+
+                    let mut num_neighbors = 0.0;
+                    for neighboring_tile in occupied_tile.empty_neighbors(map_geometry) {
+                        num_neighbors += 1.0;
+                        addition_map.push((neighboring_tile, amount_to_send_to_each_neighbor));
+                    }
+                    removal_map.push((
+                        occupied_tile,
+                        amount_to_send_to_each_neighbor * num_neighbors,
+                    ));
+                }
+
+                // We cannot do this in one step, as we need to avoid bizarre iteration order dependencies
+                for (removal_pos, removal_strength) in removal_map {
+                    *map.get_mut(&removal_pos).unwrap() -= removal_strength;
+                }
+
+                for (addition_pos, addition_strength) in addition_map {
+                    *map.entry(addition_pos).or_insert(SignalStrength::ZERO) += addition_strength;
+                }
+            }
+            SignalMap::Dense(grid) => {
+                grid.grow_to_radius(map_radius);
+                grid.diffuse(diffusion_fraction, map_geometry);
+            }
+        }
+    }
+
+    /// Reduces every signal by `degradation_fraction`, removing any that fall to `epsilon` or below.
+    fn degrade(&mut self, degradation_fraction: f32, epsilon: SignalStrength) {
+        match self {
+            SignalMap::Sparse(map) => {
+                map.retain(|_, signal_strength| {
+                    *signal_strength = *signal_strength * (1. - degradation_fraction);
+                    *signal_strength > epsilon
+                });
+            }
+            SignalMap::Dense(grid) => {
+                let mut n_occupied = 0;
+                for signal_strength in grid.values.iter_mut() {
+                    let new_strength = *signal_strength * (1. - degradation_fraction);
+
+                    if new_strength > epsilon {
+                        *signal_strength = new_strength;
+                        n_occupied += 1;
+                    } else {
+                        *signal_strength = SignalStrength::ZERO;
+                    }
+                }
+
+                if n_occupied < grid.values.len() / SignalMap::SPARSE_FRACTION {
+                    let map = grid
+                        .values
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, &signal_strength)| signal_strength != SignalStrength::ZERO)
+                        .map(|(index, &signal_strength)| (grid.tile_pos(index), signal_strength))
+                        .collect();
+                    *self = SignalMap::Sparse(map);
+                }
+            }
+        }
+    }
+}
+
+/// A dense grid of signal strengths, used by [`SignalMap`] once a signal has spread across the map.
+///
+/// Strengths are stored for every tile whose axial coordinates are within `radius` of the origin.
+/// This keeps diffusion cache-friendly, and makes its iteration order deterministic.
+/// Tiles outside of the grid have no signal, and the grid grows to fit any tile that a signal is added to.
+#[derive(Debug, Default)]
+struct SignalGrid {
+    /// The largest absolute value of the axial coordinates of the tiles covered by the grid.
+    radius: i32,
+    /// The signal strength at each tile, laid out as described in [`SignalGrid::index`].
+    ///
+    /// This is empty until the grid is first grown.
+    values: Vec<SignalStrength>,
+}
+
+impl SignalGrid {
+    /// The number of tiles along each side of a grid with the provided `radius`.
+    fn side_length(radius: i32) -> i32 {
+        2 * radius + 1
+    }
+
+    /// The number of tiles stored in a grid with the provided `radius`.
+    fn len(radius: i32) -> usize {
+        let side_length = SignalGrid::side_length(radius) as usize;
+        side_length * side_length
+    }
+
+    /// The index into [`SignalGrid::values`] that stores the signal at `tile_pos`.
+    ///
+    /// Tiles are stored in rows by their `x` coordinate.
+    /// Returns [`None`] if the tile is not covered by the grid.
+    fn index(&self, tile_pos: TilePos) -> Option<usize> {
+        if self.values.is_empty() {
+            return None;
+        }
+
+        let (x, y) = (tile_pos.hex.x, tile_pos.hex.y);
+        if x.abs() > self.radius || y.abs() > self.radius {
+            return None;
+        }
+
+        let side_length = SignalGrid::side_length(self.radius);
+        Some(((x + self.radius) * side_length + (y + self.radius)) as usize)
+    }
+
+    /// The tile whose signal is stored at `index`.
+    ///
+    /// This is the inverse of [`SignalGrid::index`].
+    fn tile_pos(&self, index: usize) -> TilePos {
+        let side_length = SignalGrid::side_length(self.radius);
+        let index = index as i32;
+
+        TilePos::new(
+            index / side_length - self.radius,
+            index % side_length - self.radius,
+        )
+    }
+
+    /// Grows the grid so that it covers every tile with axial coordinates within `radius` of the origin.
+    ///
+    /// Existing signals are preserved. Grids are never shrunk.
+    fn grow_to_radius(&mut self, radius: i32) {
+        if !self.values.is_empty() && radius <= self.radius {
+            return;
+        }
+
+        let mut grown = SignalGrid {
+            radius,
+            values: vec![SignalStrength::ZERO; SignalGrid::len(radius)],
+        };
+
+        for (index, &signal_strength) in self.values.iter().enumerate() {
+            if signal_strength != SignalStrength::ZERO {
+                let grown_index = grown.index(self.tile_pos(index)).unwrap();
+                grown.values[grown_index] = signal_strength;
+            }
+        }
+
+        *self = grown;
+    }
+
+    /// Returns the signal strenth at the given [`TilePos`].
+    ///
+    /// Tiles outside of the grid have a strength of [`SignalStrength::ZERO`].
+    fn get(&self, tile_pos: TilePos) -> SignalStrength {
+        match self.index(tile_pos) {
+            Some(index) => self.values[index],
+            None => SignalStrength::ZERO,
+        }
     }
 
     /// Returns a mutable reference to the signal strength at the given [`TilePos`].
     ///
-    /// Missing values will be inserted with [`SignalStrength::ZERO`].
+    /// If the tile is outside of the grid, the grid is grown to fit it.
     fn get_mut(&mut self, tile_pos: TilePos) -> &mut SignalStrength {
-        self.map.entry(tile_pos).or_insert(SignalStrength::ZERO)
-    }
+        let index = match self.index(tile_pos) {
+            Some(index) => index,
+            None => {
+                // Grow geometrically, so that scattered additions don't repeatedly copy the grid
+                let required_radius = tile_pos.hex.x.abs().max(tile_pos.hex.y.abs());
+                let radius = match self.values.is_empty() {
+                    true => required_radius,
+                    false => required_radius.max(2 * self.radius),
+                };
+                self.grow_to_radius(radius);
+                self.index(tile_pos).unwrap()
+            }
+        };
 
-    /// Adds the `signal_strength` to the signal at `tile_pos`.
-    fn add_signal(&mut self, tile_pos: TilePos, signal_strength: SignalStrength) {
-        *self.get_mut(tile_pos) += signal_strength
+        &mut self.values[index]
     }
 
-    /// Subtracts the `signal_strength` to the signal at `tile_pos`.
+    /// Spreads `diffusion_fraction` of the signal on each tile to each of its empty neighbors.
     ///
-    /// The value is capped a minimum of [`SignalStrength::ZERO`].
-    fn subtract_signal(&mut self, tile_pos: TilePos, signal_strength: SignalStrength) {
-        *self.get_mut(tile_pos) -= signal_strength;
+    /// The grid must already cover the whole map.
+    fn diffuse(&mut self, diffusion_fraction: f32, map_geometry: &MapGeometry) {
+        // We cannot do this in one step, as we need to avoid bizarre iteration order dependencies
+        let mut inflow = vec![SignalStrength::ZERO; self.values.len()];
+
+        for index in 0..self.values.len() {
+            let original_strength = self.values[index];
+            if original_strength == SignalStrength::ZERO {
+                continue;
+            }
+
+            let amount_to_send_to_each_neighbor = original_strength * diffusion_fraction;
+            let occupied_tile = self.tile_pos(index);
+
+            let mut num_neighbors = 0.0;
+            for neighboring_tile in occupied_tile.empty_neighbors(map_geometry) {
+                if let Some(neighbor_index) = self.index(neighboring_tile) {
+                    num_neighbors += 1.0;
+                    inflow[neighbor_index] += amount_to_send_to_each_neighbor;
+                }
+            }
+
+            // Outflow only depends on this tile's own strength, so it can be removed in place
+            self.values[index] -= amount_to_send_to_each_neighbor * num_neighbors;
+        }
+
+        for (strength, inflow) in self.values.iter_mut().zip(inflow) {
+            *strength += inflow;
+        }
     }
 }
 
@@ -344,6 +682,12 @@ pub enum SignalType {
     Work(Id<Structure>),
     /// Destroy a structure of this type
     Demolish(Id<Structure>),
+    /// Repair a damaged structure of this type
+    Repair(Id<Structure>),
+    /// Upgrade a structure of this type into its more advanced form
+    Upgrade(Id<Structure>),
+    /// Reshape the terrain here, as set by zoning
+    Terraform,
     /// Has an item of this type, in case you were looking.
     ///
     /// The passive form of `Push`.
@@ -352,8 +696,16 @@ pub enum SignalType {
     ///
     /// The passive form of `Pull`.
     Stores(Id<Item>),
+    /// Is full of this item, so please stop bringing more.
+    ///
+    /// Suppresses `Pull` and `Stores` when units are looking for somewhere to put items.
+    Contained(Id<Item>),
     /// Has a unit of this type.
     Unit(Id<Unit>),
+    /// A unit ran out of patience here.
+    ///
+    /// This marks trouble spots for the player, and does not generate goals.
+    Stuck,
 }
 
 impl SignalType {
@@ -363,19 +715,55 @@ impl SignalType {
         item_manifest: &ItemManifest,
         structure_manifest: &StructureManifest,
         unit_manifest: &UnitManifest,
+        localization: &Localization,
     ) -> String {
         match self {
-            SignalType::Push(item_id) => format!("Push({})", item_manifest.name(*item_id)),
-            SignalType::Pull(item_id) => format!("Pull({})", item_manifest.name(*item_id)),
+            SignalType::Push(item_id) => {
+                format!("Push({})", localization.get(item_manifest.name(*item_id)))
+            }
+            SignalType::Pull(item_id) => {
+                format!("Pull({})", localization.get(item_manifest.name(*item_id)))
+            }
             SignalType::Work(structure_id) => {
-                format!("Work({})", structure_manifest.name(*structure_id))
+                format!(
+                    "Work({})",
+                    localization.get(structure_manifest.name(*structure_id))
+                )
             }
             SignalType::Demolish(structure_id) => {
-                format!("Demolish({})", structure_manifest.name(*structure_id))
+                format!(
+                    "Demolish({})",
+                    localization.get(structure_manifest.name(*structure_id))
+                )
+            }
+            SignalType::Repair(structure_id) => {
+                format!(
+                    "Repair({})",
+                    localization.get(structure_manifest.name(*structure_id))
+                )
+            }
+            SignalType::Upgrade(structure_id) => {
+                format!(
+                    "Upgrade({})",
+                    localization.get(structure_manifest.name(*structure_id))
+                )
+            }
+            SignalType::Terraform => "Terraform".to_string(),
+            SignalType::Contains(item_id) => format!(
+                "Contains({})",
+                localization.get(item_manifest.name(*item_id))
+            ),
+            SignalType::Stores(item_id) => {
+                format!("Stores({})", localization.get(item_manifest.name(*item_id)))
             }
-            SignalType::Contains(item_id) => format!("Contains({})", item_manifest.name(*item_id)),
-            SignalType::Stores(item_id) => format!("Stores({})", item_manifest.name(*item_id)),
-            SignalType::Unit(unit_id) => format!("Unit({})", unit_manifest.name(*unit_id)),
+            SignalType::Contained(item_id) => format!(
+                "Contained({})",
+                localization.get(item_manifest.name(*item_id))
+            ),
+            SignalType::Unit(unit_id) => {
+                format!("Unit({})", localization.get(unit_manifest.name(*unit_id)))
+            }
+            SignalType::Stuck => "Stuck".to_string(),
         }
     }
 }
@@ -384,7 +772,7 @@ impl SignalType {
 ///
 /// This has an infallible conversion from [`SignalType`] using the [`From`] trait.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, IterableEnum)]
-pub(crate) enum SignalKind {
+pub enum SignalKind {
     /// Take this item away from here.
     Push,
     /// Bring me an item of this type.
@@ -393,6 +781,12 @@ pub(crate) enum SignalKind {
     Work,
     /// Destroy a structure of this type
     Demolish,
+    /// Repair a damaged structure of this type
+    Repair,
+    /// Upgrade a structure of this type into its more advanced form
+    Upgrade,
+    /// Reshape the terrain here, as set by zoning
+    Terraform,
     /// Has an item of this type, in case you were looking.
     ///
     /// The passive form of `Push`.
@@ -401,8 +795,14 @@ pub(crate) enum SignalKind {
     ///
     /// The passive form of `Pull`.
     Stores,
+    /// Is full of this item, so please stop bringing more.
+    ///
+    /// Suppresses `Pull` and `Stores` when units are looking for somewhere to put items.
+    Contained,
     /// Has a unit of this type.
     Unit,
+    /// A unit ran out of patience here.
+    Stuck,
 }
 
 impl From<SignalType> for SignalKind {
@@ -412,13 +812,91 @@ impl From<SignalType> for SignalKind {
             SignalType::Pull(_) => SignalKind::Pull,
             SignalType::Work(_) => SignalKind::Work,
             SignalType::Demolish(_) => SignalKind::Demolish,
+            SignalType::Repair(_) => SignalKind::Repair,
+            SignalType::Upgrade(_) => SignalKind::Upgrade,
+            SignalType::Terraform => SignalKind::Terraform,
             SignalType::Contains(_) => SignalKind::Contains,
             SignalType::Stores(_) => SignalKind::Stores,
+            SignalType::Contained(_) => SignalKind::Contained,
             SignalType::Unit(_) => SignalKind::Unit,
+            SignalType::Stuck => SignalKind::Stuck,
         }
     }
 }
 
+/// How quickly a kind of signal spreads out and fades away.
+///
+/// Signals that diffuse quickly and degrade slowly travel further across the map.
+/// This comes at a cost: every tile with a non-negligible signal strength must be updated each step,
+/// so long-range signals are significantly more expensive to compute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalPropagation {
+    /// The fraction of the signal in each cell that will move to each of its 6 neighbors each step.
+    diffusion_fraction: f32,
+    /// The fraction of the signal that will decay at each step.
+    degradation_fraction: f32,
+}
+
+impl SignalPropagation {
+    /// Creates a new [`SignalPropagation`].
+    ///
+    /// # Panics
+    ///
+    /// `diffusion_fraction` must be between 0 and 1/6, and `degradation_fraction` must be between 0 and 1.
+    pub fn new(diffusion_fraction: f32, degradation_fraction: f32) -> Self {
+        assert!((0. ..1. / 6.).contains(&diffusion_fraction));
+        assert!((0. ..=1.).contains(&degradation_fraction));
+
+        SignalPropagation {
+            diffusion_fraction,
+            degradation_fraction,
+        }
+    }
+
+    /// The fraction of the signal in each cell that will move to each of its 6 neighbors each step.
+    pub fn diffusion_fraction(&self) -> f32 {
+        self.diffusion_fraction
+    }
+
+    /// The fraction of the signal that will decay at each step.
+    pub fn degradation_fraction(&self) -> f32 {
+        self.degradation_fraction
+    }
+}
+
+impl Default for SignalPropagation {
+    fn default() -> Self {
+        SignalPropagation {
+            diffusion_fraction: DIFFUSION_FRACTION,
+            degradation_fraction: DEGRADATION_FRACTION,
+        }
+    }
+}
+
+/// Controls how each kind of signal spreads and decays.
+///
+/// Signal kinds without an override use the [`SignalPropagation::default`] rates.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct SignalPropagationConfig {
+    /// The rates used by signals of each kind, where they differ from the default.
+    overrides: HashMap<SignalKind, SignalPropagation>,
+}
+
+impl SignalPropagationConfig {
+    /// Returns the propagation rates used by signals of the provided `signal_type`.
+    pub fn get(&self, signal_type: SignalType) -> SignalPropagation {
+        self.overrides
+            .get(&signal_type.into())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Sets the propagation rates used by all signals of the provided `signal_kind`.
+    pub fn set(&mut self, signal_kind: SignalKind, propagation: SignalPropagation) {
+        self.overrides.insert(signal_kind, propagation);
+    }
+}
+
 /// How strong a signal is.
 ///
 /// This has a minimum value of 0.
@@ -486,19 +964,34 @@ pub(crate) struct Emitter {
 }
 
 /// Emits signals from [`Emitter`] sources.
-fn emit_signals(
+pub(crate) fn emit_signals(
     mut signals: ResMut<Signals>,
     emitter_query: Query<(&TilePos, &Emitter, Option<&Id<Structure>>)>,
     structure_manifest: Res<StructureManifest>,
+    map_geometry: Res<MapGeometry>,
 ) {
     for (&center, emitter, maybe_structure_id) in emitter_query.iter() {
         match maybe_structure_id {
             // Signals should be emitted from all tiles in the footprint of a structure.
             Some(structure_id) => {
-                let footprint = &structure_manifest.get(*structure_id).footprint;
-                for tile_pos in footprint.in_world_space(center) {
-                    for (signal_type, signal_strength) in &emitter.signals {
-                        signals.add_signal(*signal_type, tile_pos, *signal_strength);
+                let structure_data = structure_manifest.get(*structure_id);
+                let footprint = structure_data.footprint.in_world_space(center);
+                for (signal_type, signal_strength) in &emitter.signals {
+                    match signal_type {
+                        SignalType::Work(_) if structure_data.work_radius > 0 => {
+                            signals.add_work_signal(
+                                *signal_type,
+                                &footprint,
+                                *signal_strength,
+                                structure_data.work_radius,
+                                &map_geometry,
+                            );
+                        }
+                        _ => {
+                            for &tile_pos in &footprint {
+                                signals.add_signal(*signal_type, tile_pos, *signal_strength);
+                            }
+                        }
                     }
                 }
             }
@@ -512,49 +1005,24 @@ fn emit_signals(
 }
 
 /// Spreads signals between tiles.
-fn diffuse_signals(mut signals: ResMut<Signals>, map_geometry: Res<MapGeometry>) {
+fn diffuse_signals(
+    mut signals: ResMut<Signals>,
+    map_geometry: Res<MapGeometry>,
+    propagation_config: Res<SignalPropagationConfig>,
+) {
     let map_geometry = &*map_geometry;
-    signals.diffuse(map_geometry, DIFFUSION_FRACTION);
+    signals.diffuse(map_geometry, &propagation_config);
 }
 
 /// Degrades signals, allowing them to approach an asymptotically constant level.
-fn degrade_signals(mut signals: ResMut<Signals>) {
-    /// The fraction of signal that will decay at each step.
-    ///
-    /// Higher values lead to faster decay and improved signal responsiveness.
-    /// This must always be between 0 and 1.
-    const DEGRADATION_FRACTION: f32 = 0.01;
-
-    /// The value below which decayed signals are eliminated completely
-    ///
-    /// Increasing this value will:
-    ///  - increase computational costs
-    ///  - increase the range at which tasks can be detected
-    ///  - increase the amount of time units will wait around for more production
-    const EPSILON_STRENGTH: SignalStrength = SignalStrength(1e-8);
-
-    for signal_map in signals.maps.values_mut() {
-        let mut tiles_to_clear: Vec<TilePos> = Vec::with_capacity(signal_map.map.len());
-
-        for (tile_pos, signal_strength) in signal_map.map.iter_mut() {
-            let new_strength = *signal_strength * (1. - DEGRADATION_FRACTION);
-
-            if new_strength > EPSILON_STRENGTH {
-                *signal_strength = new_strength;
-            } else {
-                tiles_to_clear.push(*tile_pos);
-            }
-        }
-
-        for tile_to_clear in tiles_to_clear {
-            signal_map.map.remove(&tile_to_clear);
-        }
-    }
+fn degrade_signals(mut signals: ResMut<Signals>, propagation_config: Res<SignalPropagationConfig>) {
+    signals.degrade(&propagation_config);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use hexx::Hex;
 
     fn test_item() -> Id<Item> {
         Id::from_name("12345")
@@ -589,6 +1057,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn signal_grid_grows_to_fit_new_signals() {
+        let mut signal_grid = SignalGrid::default();
+        let distant_tile = TilePos::new(-7, 3);
+        assert_eq!(signal_grid.get(TilePos::ZERO), SignalStrength::ZERO);
+
+        *signal_grid.get_mut(TilePos::ZERO) += SignalStrength(1.);
+        *signal_grid.get_mut(distant_tile) += SignalStrength(2.);
+
+        // Growing the grid preserves the existing signals
+        assert_eq!(signal_grid.get(TilePos::ZERO), SignalStrength(1.));
+        assert_eq!(signal_grid.get(distant_tile), SignalStrength(2.));
+
+        // Tiles outside of the grid have no signal
+        assert_eq!(signal_grid.get(TilePos::new(100, 0)), SignalStrength::ZERO);
+        assert_eq!(signal_grid.get(TilePos::new(0, -100)), SignalStrength::ZERO);
+
+        for index in 0..signal_grid.values.len() {
+            let tile_pos = signal_grid.tile_pos(index);
+            assert_eq!(signal_grid.index(tile_pos), Some(index));
+        }
+    }
+
+    #[test]
+    fn signal_maps_become_dense_as_they_spread_and_sparse_as_they_fade() {
+        let map_geometry = MapGeometry::new(10);
+        let mut signal_map = SignalMap::default();
+        signal_map.add_signal(TilePos::ZERO, SignalStrength(1.));
+
+        // A single signal stays sparse while it only covers a few tiles
+        signal_map.diffuse(0.1, &map_geometry);
+        assert!(matches!(signal_map, SignalMap::Sparse(..)));
+        assert!(signal_map.get(TilePos::new(1, 0)) > SignalStrength::ZERO);
+
+        for _ in 0..5 {
+            signal_map.diffuse(0.1, &map_geometry);
+        }
+        assert!(matches!(signal_map, SignalMap::Dense(..)));
+
+        // Switching representations doesn't lose any signal
+        let total_strength: f32 = hexagon(Hex::ZERO, 10)
+            .map(|hex| signal_map.get(TilePos { hex }).value())
+            .sum();
+        assert!((total_strength - 1.).abs() < 1e-4);
+
+        // Once everything but the strongest signal has faded away, the map is sparse again
+        let degraded_center_strength = signal_map.get(TilePos::ZERO).value() * 0.1;
+        signal_map.degrade(0.9, SignalStrength(degraded_center_strength * 0.999));
+        assert!(matches!(signal_map, SignalMap::Sparse(..)));
+        assert!(signal_map.get(TilePos::ZERO) > SignalStrength::ZERO);
+        assert_eq!(signal_map.get(TilePos::new(1, 0)), SignalStrength::ZERO);
+    }
+
+    #[test]
+    fn signal_types_are_cycled_in_order() {
+        let mut signals = Signals::default();
+        assert_eq!(signals.next_signal_type(None), None);
+
+        let push = SignalType::Push(test_item());
+        let work = SignalType::Work(test_structure());
+        signals.add_signal(work, TilePos::ZERO, SignalStrength(1.));
+        signals.add_signal(push, TilePos::ZERO, SignalStrength(1.));
+
+        assert_eq!(signals.next_signal_type(None), Some(push));
+        assert_eq!(signals.next_signal_type(Some(push)), Some(work));
+        assert_eq!(signals.next_signal_type(Some(work)), None);
+    }
+
     #[test]
     fn upstream_returns_none_with_no_signals() {
         let signals = Signals::default();
@@ -672,6 +1208,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn upstream_avoids_full_destinations() {
+        let mut signals = Signals::default();
+        let map_geometry = MapGeometry::new(1);
+        let neighbors: Vec<TilePos> = TilePos::ZERO
+            .all_neighbors(&map_geometry)
+            .into_iter()
+            .collect();
+        let full_tile = neighbors[0];
+        let open_tile = neighbors[1];
+
+        signals.add_signal(
+            SignalType::Stores(test_item()),
+            full_tile,
+            SignalStrength(1.),
+        );
+        signals.add_signal(
+            SignalType::Stores(test_item()),
+            open_tile,
+            SignalStrength(0.5),
+        );
+        assert_eq!(
+            signals.upstream(TilePos::ZERO, &Goal::Store(test_item()), &map_geometry),
+            Some(full_tile)
+        );
+
+        signals.add_signal(
+            SignalType::Contained(test_item()),
+            full_tile,
+            SignalStrength(1.),
+        );
+        assert_eq!(
+            signals.upstream(TilePos::ZERO, &Goal::Store(test_item()), &map_geometry),
+            Some(open_tile)
+        );
+    }
+
     #[test]
     fn upstream_returns_some_at_trivial_valley() {
         let mut signals = Signals::default();
@@ -705,4 +1278,59 @@ mod tests {
             .upstream(TilePos::ZERO, &Goal::Store(test_item()), &map_geometry)
             .is_some());
     }
+
+    #[test]
+    fn signal_kinds_propagate_at_their_own_rates() {
+        let mut propagation_config = SignalPropagationConfig::default();
+        propagation_config.set(
+            SignalKind::Pull,
+            SignalPropagation::new(DIFFUSION_FRACTION, 0.2),
+        );
+
+        let slow_decay = SignalType::Push(test_item());
+        let fast_decay = SignalType::Pull(test_item());
+        assert_eq!(
+            propagation_config.get(slow_decay),
+            SignalPropagation::default()
+        );
+
+        let mut signals = Signals::default();
+        let map_geometry = MapGeometry::new(5);
+        let distant_tile = TilePos::new(3, 0);
+
+        for _ in 0..20 {
+            signals.add_signal(slow_decay, TilePos::ZERO, SignalStrength(1.));
+            signals.add_signal(fast_decay, TilePos::ZERO, SignalStrength(1.));
+            signals.diffuse(&map_geometry, &propagation_config);
+            signals.degrade(&propagation_config);
+        }
+
+        assert!(signals.get(fast_decay, distant_tile) > SignalStrength::ZERO);
+        assert!(signals.get(slow_decay, distant_tile) > signals.get(fast_decay, distant_tile));
+    }
+
+    #[test]
+    fn upstream_routes_around_hazards() {
+        let mut signals = Signals::default();
+        let mut map_geometry = MapGeometry::new(2);
+
+        // Two paths lead towards the source, but the stronger one runs through a hazard
+        let corridor = TilePos::new(1, 0);
+        let detour = TilePos::new(0, 1);
+        signals.add_signal(SignalType::Pull(test_item()), corridor, SignalStrength(1.));
+        signals.add_signal(SignalType::Pull(test_item()), detour, SignalStrength(0.5));
+
+        let goal = Goal::Store(test_item());
+        assert_eq!(
+            signals.upstream(TilePos::ZERO, &goal, &map_geometry),
+            Some(corridor)
+        );
+
+        map_geometry.set_hazard(corridor, true);
+        assert!(!map_geometry.is_passable(corridor));
+        assert_eq!(
+            signals.upstream(TilePos::ZERO, &goal, &map_geometry),
+            Some(detour)
+        );
+    }
 }
@@ -0,0 +1,294 @@
+//! A record of notable things that have happened in the world, so players can catch up on what they missed.
+
+use std::{collections::VecDeque, fmt::Display};
+
+use bevy::{ecs::event::ManualEventReader, prelude::*};
+
+use crate::{
+    asset_management::{localization::Localization, manifest::Id},
+    items::recipe::{Recipe, RecipeManifest},
+    organisms::energy::UnitDied,
+    player_interaction::zoning::PlacementRejected,
+    structures::{
+        construction::ConstructionCompleted,
+        structure_manifest::{Structure, StructureManifest},
+    },
+};
+
+use super::{geometry::TilePos, time::InGameTime};
+
+/// Collects [`GameEvent`]s into the [`EventLog`].
+pub(super) struct EventLogPlugin;
+
+impl Plugin for EventLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EventLog>()
+            .add_system(record_game_events);
+    }
+}
+
+/// Something notable that happened in the world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GameEvent {
+    /// A ghost has been built into a structure.
+    StructureBuilt {
+        /// The variety of structure that was built.
+        structure_id: Id<Structure>,
+        /// Where the structure was built.
+        tile_pos: TilePos,
+    },
+    /// A structure has finished crafting a recipe.
+    RecipeCompleted {
+        /// The recipe that was crafted.
+        recipe_id: Id<Recipe>,
+        /// Where the crafting structure is.
+        tile_pos: TilePos,
+    },
+    /// A unit has run out of energy and died.
+    UnitStarved {
+        /// Where the unit died.
+        tile_pos: TilePos,
+    },
+    /// A structure could not be placed, as the terrain was unsuitable.
+    PlacementRejected {
+        /// The variety of structure that was rejected.
+        structure_id: Id<Structure>,
+        /// Where the structure was meant to be placed.
+        tile_pos: TilePos,
+    },
+}
+
+impl GameEvent {
+    /// The tile where this event happened, if any.
+    pub(crate) fn tile_pos(&self) -> Option<TilePos> {
+        match self {
+            GameEvent::StructureBuilt { tile_pos, .. }
+            | GameEvent::RecipeCompleted { tile_pos, .. }
+            | GameEvent::UnitStarved { tile_pos }
+            | GameEvent::PlacementRejected { tile_pos, .. } => Some(*tile_pos),
+        }
+    }
+
+    /// A pretty formatting for this event.
+    pub(crate) fn display(
+        &self,
+        structure_manifest: &StructureManifest,
+        recipe_manifest: &RecipeManifest,
+        localization: &Localization,
+    ) -> String {
+        match self {
+            GameEvent::StructureBuilt {
+                structure_id,
+                tile_pos,
+            } => format!(
+                "Built {} at {tile_pos}",
+                localization.get(structure_manifest.name(*structure_id))
+            ),
+            GameEvent::RecipeCompleted {
+                recipe_id,
+                tile_pos,
+            } => format!(
+                "Crafted {} at {tile_pos}",
+                localization.get(recipe_manifest.name(*recipe_id))
+            ),
+            GameEvent::UnitStarved { tile_pos } => format!("A unit starved at {tile_pos}"),
+            GameEvent::PlacementRejected {
+                structure_id,
+                tile_pos,
+            } => format!(
+                "Could not place {} at {tile_pos}",
+                localization.get(structure_manifest.name(*structure_id))
+            ),
+        }
+    }
+}
+
+/// The in-game time at which an event happened.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Timestamp {
+    /// The number of complete days that had elapsed.
+    pub(crate) day: u64,
+    /// The time of day, in 24 hour time.
+    pub(crate) hour: f32,
+}
+
+impl Timestamp {
+    /// The current time.
+    pub(crate) fn now(in_game_time: &InGameTime) -> Self {
+        Timestamp {
+            day: in_game_time.elapsed_days(),
+            hour: in_game_time.twenty_four_hour_time(),
+        }
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Day {}, {:05.2}h", self.day, self.hour)
+    }
+}
+
+/// A [`GameEvent`], and when it happened.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LogEntry {
+    /// What happened.
+    pub(crate) event: GameEvent,
+    /// When it happened.
+    pub(crate) timestamp: Timestamp,
+}
+
+/// The most recent [`GameEvent`]s.
+///
+/// Once the log is full, the oldest entries are discarded to make room for new ones.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct EventLog {
+    /// The stored entries, from oldest to newest.
+    entries: VecDeque<LogEntry>,
+    /// The time that newly pushed events are stamped with.
+    now: Timestamp,
+}
+
+impl EventLog {
+    /// The maximum number of entries that are kept.
+    pub(crate) const CAPACITY: usize = 50;
+
+    /// Records that `event` just happened.
+    pub(crate) fn push(&mut self, event: GameEvent) {
+        if self.entries.len() == EventLog::CAPACITY {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(LogEntry {
+            event,
+            timestamp: self.now,
+        });
+    }
+
+    /// Iterates over the stored entries, from newest to oldest.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter().rev()
+    }
+}
+
+/// Keeps the time of the [`EventLog`] up to date, and records events sent by other modules.
+fn record_game_events(
+    mut event_log: ResMut<EventLog>,
+    in_game_time: Res<InGameTime>,
+    mut construction_events: EventReader<ConstructionCompleted>,
+    // The simulation can run without the player interaction plugins that send these
+    maybe_rejection_events: Option<Res<Events<PlacementRejected>>>,
+    mut rejection_reader: Local<ManualEventReader<PlacementRejected>>,
+    mut death_events: EventReader<UnitDied>,
+) {
+    // The time changes every frame, but this should only be treated as a change when new entries are added
+    event_log.bypass_change_detection().now = Timestamp::now(&in_game_time);
+
+    for event in construction_events.iter() {
+        event_log.push(GameEvent::StructureBuilt {
+            structure_id: event.structure_id,
+            tile_pos: event.tile_pos,
+        });
+    }
+
+    if let Some(rejection_events) = maybe_rejection_events {
+        for event in rejection_reader.iter(&rejection_events) {
+            event_log.push(GameEvent::PlacementRejected {
+                structure_id: event.structure_id,
+                tile_pos: event.tile_pos,
+            });
+        }
+    }
+
+    for event in death_events.iter() {
+        event_log.push(GameEvent::UnitStarved {
+            tile_pos: event.tile_pos,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_log_discards_oldest_entries_when_full() {
+        let mut event_log = EventLog::default();
+        for x in 0..(EventLog::CAPACITY as i32 + 5) {
+            event_log.push(GameEvent::UnitStarved {
+                tile_pos: TilePos::new(x, 0),
+            });
+        }
+
+        assert_eq!(event_log.iter().count(), EventLog::CAPACITY);
+
+        let newest = event_log.iter().next().unwrap();
+        assert_eq!(
+            newest.event.tile_pos(),
+            Some(TilePos::new(EventLog::CAPACITY as i32 + 4, 0))
+        );
+        let oldest = event_log.iter().last().unwrap();
+        assert_eq!(oldest.event.tile_pos(), Some(TilePos::new(5, 0)));
+    }
+
+    #[test]
+    fn events_from_other_modules_are_recorded() {
+        let mut app = App::new();
+        // These events are owned by the plugins that send them
+        app.add_plugin(EventLogPlugin)
+            .init_resource::<InGameTime>()
+            .add_event::<ConstructionCompleted>()
+            .add_event::<UnitDied>();
+
+        let tile_pos = TilePos::new(1, -1);
+        app.world.send_event(ConstructionCompleted {
+            tile_pos,
+            structure_id: Id::from_name("leuco"),
+        });
+        app.world.send_event(UnitDied {
+            tile_pos,
+            held_item: None,
+        });
+
+        app.update();
+
+        let event_log = app.world.resource::<EventLog>();
+        let events: Vec<GameEvent> = event_log.iter().map(|entry| entry.event).collect();
+        assert_eq!(
+            events,
+            vec![
+                GameEvent::UnitStarved { tile_pos },
+                GameEvent::StructureBuilt {
+                    structure_id: Id::from_name("leuco"),
+                    tile_pos,
+                },
+            ]
+        );
+    }
+    #[test]
+    fn placement_rejections_are_recorded_when_zoning_is_present() {
+        let mut app = App::new();
+        app.add_plugin(EventLogPlugin)
+            .init_resource::<InGameTime>()
+            .add_event::<ConstructionCompleted>()
+            .add_event::<PlacementRejected>()
+            .add_event::<UnitDied>();
+
+        let tile_pos = TilePos::new(2, 0);
+        app.world.send_event(PlacementRejected {
+            tile_pos,
+            structure_id: Id::from_name("leuco"),
+        });
+
+        app.update();
+
+        let event_log = app.world.resource::<EventLog>();
+        let events: Vec<GameEvent> = event_log.iter().map(|entry| entry.event).collect();
+        assert_eq!(
+            events,
+            vec![GameEvent::PlacementRejected {
+                structure_id: Id::from_name("leuco"),
+                tile_pos,
+            }]
+        );
+    }
+}
@@ -0,0 +1,21 @@
+//! Ownership of units and structures by competing or cooperating factions.
+//!
+//! Units only work at, and haul items for, structures that belong to their own faction.
+
+use bevy::prelude::*;
+
+/// The faction that owns a unit or structure.
+///
+/// Entities without this component belong to [`Faction::COLONY`].
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Faction(pub u8);
+
+impl Faction {
+    /// The faction controlled by the player.
+    pub const COLONY: Faction = Faction(0);
+
+    /// The faction of `entity`, or [`Faction::COLONY`] if it does not have a [`Faction`].
+    pub(crate) fn of(entity: Entity, faction_query: &Query<&Faction>) -> Faction {
+        faction_query.get(entity).copied().unwrap_or_default()
+    }
+}
@@ -1,15 +1,17 @@
 //! Generating starting terrain and organisms
 use crate::asset_management::manifest::Id;
 use crate::asset_management::AssetState;
+use crate::items::ItemCount;
 use crate::player_interaction::clipboard::ClipboardData;
-use crate::simulation::geometry::{Facing, Height, TilePos};
+use crate::simulation::factions::Faction;
+use crate::simulation::geometry::{Height, TilePos};
 use crate::structures::commands::StructureCommandsExt;
 use crate::structures::structure_manifest::StructureManifest;
+use crate::terrain::deposits::ResourceDeposit;
 use crate::terrain::terrain_manifest::Terrain;
 use crate::terrain::SpawnTerrainCommand;
-use crate::units::unit_assets::UnitHandles;
-use crate::units::unit_manifest::UnitManifest;
-use crate::units::UnitBundle;
+use crate::units::commands::UnitCommandsExt;
+use crate::units::goals::Goal;
 use bevy::app::{App, Plugin};
 use bevy::ecs::prelude::*;
 use bevy::log::info;
@@ -19,8 +21,9 @@ use bevy::utils::HashMap;
 use hexx::shapes::hexagon;
 use hexx::Hex;
 use noisy_bevy::fbm_simplex_2d_seeded;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, SeedableRng};
 
 use super::geometry::MapGeometry;
 
@@ -39,6 +42,14 @@ pub struct GenerationConfig {
     n_hive: usize,
     /// Relative probability of generating tiles of each terrain type.
     terrain_weights: HashMap<Id<Terrain>, f32>,
+    /// Seeds the random number generation used for terrain.
+    ///
+    /// The same seed always produces the same terrain.
+    seed: u64,
+    /// Should the map start hidden under the fog of war, to be revealed by exploring units?
+    fog_of_war: bool,
+    /// The resource deposits scattered across the map, as the contents of each deposit and the number to place.
+    resource_deposits: Vec<(ItemCount, usize)>,
 }
 
 impl GenerationConfig {
@@ -60,6 +71,58 @@ impl GenerationConfig {
     const TERRAIN_WEIGHT_MUDDY: f32 = 0.3;
     /// The choice weight for impassable terrain in default generation config
     const TERRAIN_WEIGHT_ROCKY: f32 = 0.2;
+
+    /// The seed used in the default generation config
+    const SEED: u64 = 2378;
+
+    /// The number of stone deposits in the default generation config
+    const N_STONE_DEPOSITS: usize = 12;
+    /// The amount of stone held by each deposit in the default generation config
+    const STONE_DEPOSIT_SIZE: usize = 20;
+
+    /// Sets the relative probability of generating tiles of each terrain type.
+    ///
+    /// This can be used to request different biomes.
+    pub fn with_terrain_weights(mut self, terrain_weights: HashMap<Id<Terrain>, f32>) -> Self {
+        self.terrain_weights = terrain_weights;
+        self
+    }
+
+    /// Sets the radius of the map, in tiles from the center to the edge.
+    ///
+    /// Only tiles within this radius are generated, and all other tiles are treated as off the map.
+    pub fn with_map_radius(mut self, map_radius: u32) -> Self {
+        self.map_radius = map_radius;
+        self
+    }
+
+    /// Sets the seed used to generate the terrain.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets whether the map starts hidden under the fog of war.
+    ///
+    /// Idle units will explore the map to reveal it.
+    pub fn with_fog_of_war(mut self, fog_of_war: bool) -> Self {
+        self.fog_of_war = fog_of_war;
+        self
+    }
+
+    /// Scatters `n_deposits` resource deposits across the map, each initially holding `contents`.
+    ///
+    /// Each tile holds at most one deposit.
+    pub fn with_resource_deposits(mut self, contents: ItemCount, n_deposits: usize) -> Self {
+        self.resource_deposits.push((contents, n_deposits));
+        self
+    }
+
+    /// Removes all resource deposits, including the ones placed by default.
+    pub fn without_resource_deposits(mut self) -> Self {
+        self.resource_deposits.clear();
+        self
+    }
 }
 
 impl Default for GenerationConfig {
@@ -83,6 +146,12 @@ impl Default for GenerationConfig {
             n_fungi: GenerationConfig::N_FUNGI,
             n_hive: GenerationConfig::N_HIVE,
             terrain_weights,
+            seed: GenerationConfig::SEED,
+            fog_of_war: false,
+            resource_deposits: vec![(
+                ItemCount::new(Id::from_name("stone"), GenerationConfig::STONE_DEPOSIT_SIZE),
+                GenerationConfig::N_STONE_DEPOSITS,
+            )],
         }
     }
 }
@@ -118,41 +187,94 @@ const OCTAVES: usize = 4;
 const LACUNARITY: f32 = 2.3;
 /// Scale the output of the fbm function
 const GAIN: f32 = 0.5;
-/// Seed that determines the noise function output
-const SEED: f32 = 2378.0;
+/// The range of seeds passed to the noise function.
+///
+/// Noise seeds are floats, so very large seeds would lose precision.
+const NOISE_SEED_RANGE: u64 = 1 << 16;
+
+/// Selects the terrain type and height of each tile within `radius` of the center of the map.
+///
+/// The output is fully determined by the provided `config`.
+fn generate_tiles(config: &GenerationConfig, radius: u32) -> Vec<(TilePos, Id<Terrain>, Height)> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let noise_seed = (config.seed % NOISE_SEED_RANGE) as f32;
+
+    let terrain_weights = &config.terrain_weights;
+    let mut terrain_variants: Vec<Id<Terrain>> = terrain_weights.keys().copied().collect();
+    // Sort the variants so that the choices made are independent of the hash map's iteration order
+    terrain_variants.sort();
+
+    hexagon(Hex::ZERO, radius)
+        .map(|hex| {
+            // FIXME: can we not just sample from our terrain_weights directly?
+            let &terrain_id = terrain_variants
+                .choose_weighted(&mut rng, |terrain_type| {
+                    terrain_weights.get(terrain_type).unwrap()
+                })
+                .unwrap();
+
+            let tile_pos = TilePos { hex };
+            let pos = vec2(tile_pos.x as f32, tile_pos.y as f32);
+
+            let hex_height = MIN_HEIGHT
+                + (fbm_simplex_2d_seeded(
+                    pos * FREQUENCY_SCALE,
+                    OCTAVES,
+                    LACUNARITY,
+                    GAIN,
+                    noise_seed,
+                ) * AMPLITUDE_SCALE)
+                    .abs();
+
+            (tile_pos, terrain_id, Height::from_world_pos(hex_height))
+        })
+        .collect()
+}
+
+/// Chooses which of the `tiles` hold a [`ResourceDeposit`].
+///
+/// The output is fully determined by the provided `config` and `tiles`.
+fn place_resource_deposits(
+    config: &GenerationConfig,
+    tiles: &[TilePos],
+) -> HashMap<TilePos, ResourceDeposit> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut available_tiles = tiles.to_vec();
+    let mut deposits = HashMap::new();
+
+    for (contents, n_deposits) in &config.resource_deposits {
+        available_tiles.shuffle(&mut rng);
+        let n_deposits = (*n_deposits).min(available_tiles.len());
+        for tile_pos in available_tiles.split_off(available_tiles.len() - n_deposits) {
+            deposits.insert(tile_pos, ResourceDeposit::new(contents));
+        }
+    }
+
+    deposits
+}
 
 /// Creates the world according to [`GenerationConfig`].
 pub(crate) fn generate_terrain(
     mut commands: Commands,
     config: Res<GenerationConfig>,
-    map_geometry: Res<MapGeometry>,
+    mut map_geometry: ResMut<MapGeometry>,
 ) {
     info!("Generating terrain...");
-    let mut rng = thread_rng();
-
-    let terrain_weights = &config.terrain_weights;
-    let terrain_variants: Vec<Id<Terrain>> = terrain_weights.keys().copied().collect();
-
-    for hex in hexagon(Hex::ZERO, map_geometry.radius) {
-        // FIXME: can we not just sample from our terrain_weights directly?
-        let &terrain_id = terrain_variants
-            .choose_weighted(&mut rng, |terrain_type| {
-                terrain_weights.get(terrain_type).unwrap()
-            })
-            .unwrap();
 
-        let tile_pos = TilePos { hex };
-        let pos = vec2(tile_pos.x as f32, tile_pos.y as f32);
+    if config.fog_of_war {
+        map_geometry.hide_all_tiles();
+    }
 
-        let hex_height = MIN_HEIGHT
-            + (fbm_simplex_2d_seeded(pos * FREQUENCY_SCALE, OCTAVES, LACUNARITY, GAIN, SEED)
-                * AMPLITUDE_SCALE)
-                .abs();
+    let tiles = generate_tiles(&config, map_geometry.radius);
+    let tile_positions: Vec<TilePos> = tiles.iter().map(|&(tile_pos, _, _)| tile_pos).collect();
+    let mut deposits = place_resource_deposits(&config, &tile_positions);
 
+    for (tile_pos, terrain_id, height) in tiles {
         commands.add(SpawnTerrainCommand {
             tile_pos,
-            height: Height::from_world_pos(hex_height),
+            height,
             terrain_id,
+            deposit: deposits.remove(&tile_pos),
         })
     }
 }
@@ -163,10 +285,7 @@ fn generate_organisms(
     mut commands: Commands,
     config: Res<GenerationConfig>,
     tile_query: Query<&TilePos, With<Id<Terrain>>>,
-    unit_handles: Res<UnitHandles>,
-    unit_manifest: Res<UnitManifest>,
     structure_manifest: Res<StructureManifest>,
-    map_geometry: Res<MapGeometry>,
 ) {
     info!("Generating organisms...");
     let n_ant = config.n_ant;
@@ -190,13 +309,12 @@ fn generate_organisms(
     // Ant
     let ant_positions = entity_positions.split_off(entity_positions.len() - n_ant);
     for ant_position in ant_positions {
-        commands.spawn(UnitBundle::new(
-            Id::from_name("ant"),
+        commands.spawn_unit(
             ant_position,
-            unit_manifest.get(Id::from_name("ant")).clone(),
-            &unit_handles,
-            &map_geometry,
-        ));
+            Id::from_name("ant"),
+            Goal::default(),
+            Faction::COLONY,
+        );
     }
 
     // Plant
@@ -204,14 +322,7 @@ fn generate_organisms(
     for position in plant_positions {
         let structure_id = Id::from_name("acacia");
 
-        let item = ClipboardData {
-            structure_id,
-            facing: Facing::default(),
-            active_recipe: structure_manifest
-                .get(structure_id)
-                .starting_recipe()
-                .clone(),
-        };
+        let item = ClipboardData::new(structure_id, &structure_manifest);
 
         commands.spawn_randomized_structure(position, item, rng);
     }
@@ -221,14 +332,7 @@ fn generate_organisms(
     for position in fungus_positions {
         let structure_id = Id::from_name("leuco");
 
-        let item = ClipboardData {
-            structure_id,
-            facing: Facing::default(),
-            active_recipe: structure_manifest
-                .get(structure_id)
-                .starting_recipe()
-                .clone(),
-        };
+        let item = ClipboardData::new(structure_id, &structure_manifest);
 
         commands.spawn_randomized_structure(position, item, rng);
     }
@@ -238,15 +342,71 @@ fn generate_organisms(
     for position in hive_positions {
         let structure_id = Id::from_name("ant_hive");
 
-        let item = ClipboardData {
-            structure_id,
-            facing: Facing::default(),
-            active_recipe: structure_manifest
-                .get(structure_id)
-                .starting_recipe()
-                .clone(),
-        };
+        let item = ClipboardData::new(structure_id, &structure_manifest);
 
         commands.spawn_randomized_structure(position, item, rng);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_generates_same_terrain() {
+        let config = GenerationConfig::default().with_seed(42);
+
+        assert_eq!(generate_tiles(&config, 10), generate_tiles(&config, 10));
+    }
+
+    #[test]
+    fn terrain_respects_weights() {
+        let mut terrain_weights = HashMap::new();
+        terrain_weights.insert(Id::from_name("loam"), 1.0);
+        terrain_weights.insert(Id::from_name("rocky"), 0.0);
+        let config = GenerationConfig::default().with_terrain_weights(terrain_weights);
+
+        let tiles = generate_tiles(&config, 10);
+        assert!(tiles
+            .iter()
+            .all(|&(_, terrain_id, _)| terrain_id == Id::from_name("loam")));
+    }
+
+    #[test]
+    fn terrain_fills_exactly_the_map() {
+        let config = GenerationConfig::default().with_map_radius(3);
+        let map_geometry = MapGeometry::new(config.map_radius);
+
+        let tiles = generate_tiles(&config, map_geometry.radius);
+        assert_eq!(tiles.len(), Hex::range_count(3));
+        assert!(tiles
+            .iter()
+            .all(|&(tile_pos, _, _)| map_geometry.is_valid(tile_pos)));
+    }
+
+    #[test]
+    fn resource_deposits_are_placed_on_distinct_tiles() {
+        let stone = ItemCount::new(Id::from_name("stone"), 10);
+        let ore = ItemCount::new(Id::from_name("ore"), 5);
+        let config = GenerationConfig::default()
+            .with_seed(42)
+            .without_resource_deposits()
+            .with_resource_deposits(stone.clone(), 3)
+            .with_resource_deposits(ore.clone(), 2);
+        let tiles: Vec<TilePos> = hexagon(Hex::ZERO, 3).map(|hex| TilePos { hex }).collect();
+
+        let deposits = place_resource_deposits(&config, &tiles);
+        assert_eq!(deposits.len(), 5);
+        assert!(deposits.keys().all(|tile_pos| tiles.contains(tile_pos)));
+        let count = |contents: &ItemCount| {
+            deposits
+                .values()
+                .filter(|&deposit| *deposit == ResourceDeposit::new(contents))
+                .count()
+        };
+        assert_eq!(count(&stone), 3);
+        assert_eq!(count(&ore), 2);
+
+        assert_eq!(deposits, place_resource_deposits(&config, &tiles));
+    }
+}
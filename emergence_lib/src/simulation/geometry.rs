@@ -10,14 +10,24 @@ use derive_more::{Add, AddAssign, Display, Sub, SubAssign};
 use hexx::{shapes::hexagon, Direction, Hex, HexLayout, MeshInfo};
 use rand::{rngs::ThreadRng, Rng};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::{
     f32::consts::PI,
     ops::{Add, AddAssign, Sub, SubAssign},
 };
 
 use crate::{
-    asset_management::manifest::Id, filtered_array_iter::FilteredArrayIter,
-    structures::construction::Footprint, terrain::terrain_manifest::Terrain,
+    asset_management::manifest::Id,
+    filtered_array_iter::FilteredArrayIter,
+    structures::{
+        construction::Footprint,
+        structure_manifest::{AdjacencyRequirement, Structure},
+    },
+    terrain::terrain_manifest::Terrain,
+};
+
+use super::map_events::{
+    GhostAdded, GhostRemoved, HeightChanged, MapGeometryEvent, StructureAdded, StructureRemoved,
 };
 
 /// A hex-based coordinate, that represents exactly one tile.
@@ -71,7 +81,7 @@ impl TilePos {
     /// Generates a random [`TilePos`], sampled uniformly from the valid positions in `map_geometry`
     #[inline]
     pub fn random(map_geometry: &MapGeometry, rng: &mut ThreadRng) -> TilePos {
-        let range = -(map_geometry.radius as i32)..(map_geometry.radius as i32);
+        let range = -(map_geometry.radius as i32)..=(map_geometry.radius as i32);
 
         // Just use rejection sampling: easy to get right
         let mut chosen_tile: Option<TilePos> = None;
@@ -137,6 +147,20 @@ impl TilePos {
         }
     }
 
+    /// Returns the adjacent [`TilePos`] in the provided `direction`, measured relative to `facing`.
+    ///
+    /// This is used to find the tile in front of a side of a rotated structure.
+    pub(crate) fn neighbor_facing(&self, direction: Direction, facing: Facing) -> Self {
+        let offset = TilePos {
+            hex: Hex::ZERO.neighbor(direction),
+        }
+        .rotated(facing);
+
+        TilePos {
+            hex: self.hex + offset.hex,
+        }
+    }
+
     /// All adjacent tiles that are on the map.
     pub(crate) fn all_neighbors(
         &self,
@@ -148,19 +172,47 @@ impl TilePos {
         iter
     }
 
-    /// All adjacent tiles that are on the map and free of structures.
+    /// All adjacent tiles that are on the map and free of structures, corpses and item piles.
     pub(crate) fn empty_neighbors(
         &self,
         map_geometry: &MapGeometry,
     ) -> impl IntoIterator<Item = TilePos> {
         let neighbors = self.hex.all_neighbors().map(|hex| TilePos { hex });
         let mut iter = FilteredArrayIter::from(neighbors);
-        iter.filter(|&pos| {
-            map_geometry.is_valid(pos) && !map_geometry.structure_index.contains_key(&pos)
-        });
+        iter.filter(|&pos| map_geometry.is_valid(pos) && !map_geometry.is_obstructed(pos));
         iter
     }
 
+    /// The tiles on the map that are exactly `radius` tiles away from `self`.
+    ///
+    /// Before tiles off the map are removed, each ring contains `6 * radius` tiles.
+    /// A `radius` of 0 yields only `self`.
+    pub fn ring<'a>(
+        &self,
+        radius: u32,
+        map_geometry: &'a MapGeometry,
+    ) -> impl Iterator<Item = TilePos> + 'a {
+        self.hex
+            .ring(radius)
+            .into_iter()
+            .map(|hex| TilePos { hex })
+            .filter(|&tile_pos| map_geometry.is_valid(tile_pos))
+    }
+
+    /// All tiles on the map within `radius` of `self`, ordered by increasing distance.
+    ///
+    /// This starts with `self`, followed by each successive [`ring`](Self::ring).
+    pub fn spiral<'a>(
+        &self,
+        radius: u32,
+        map_geometry: &'a MapGeometry,
+    ) -> impl Iterator<Item = TilePos> + 'a {
+        self.hex
+            .spiral_range(0..=radius)
+            .map(|hex| TilePos { hex })
+            .filter(|&tile_pos| map_geometry.is_valid(tile_pos))
+    }
+
     /// Returns the [`TilePos`] rotated to match the `facing` around the origin.
     pub(crate) fn rotated(&self, facing: Facing) -> Self {
         let n_rotations = facing.rotation_count();
@@ -193,6 +245,11 @@ impl Height {
     /// The height of each step up, in world coordinates.
     pub(crate) const STEP_HEIGHT: f32 = 1.0;
 
+    /// The largest difference in height between adjacent tiles that units can walk across.
+    ///
+    /// Steeper changes in height are cliffs, which can only be crossed using a bridge.
+    pub(crate) const MAX_STEP: Height = Height(1);
+
     /// Computes the `y` coordinate of a `Transform` that corresponds to this height.
     pub(crate) fn into_world_pos(self) -> f32 {
         self.0 as f32 * Self::STEP_HEIGHT
@@ -281,10 +338,44 @@ pub struct MapGeometry {
     terrain_index: HashMap<TilePos, Entity>,
     /// Which [`Id<Structure>`](crate::asset_management::manifest::Id) entity is stored at each tile position
     structure_index: HashMap<TilePos, Entity>,
+    /// The anchor tile of each structure entity, which its footprint is centered around
+    structure_anchor_index: HashMap<Entity, TilePos>,
+    /// Which structure entities exist of each structure type
+    structure_type_index: HashMap<Id<Structure>, HashSet<Entity>>,
     /// Which [`Ghost`](crate::structures::construction::Ghost) entity is stored at each tile position
     ghost_index: HashMap<TilePos, Entity>,
+    /// Which [`Corpse`](crate::organisms::corpses::Corpse) entity is stored at each tile position
+    ///
+    /// Corpses occupy their tile like a structure, but are not structures.
+    corpse_index: HashMap<TilePos, Entity>,
+    /// Which [`ItemPile`](crate::structures::item_pile::ItemPile) entity is stored at each tile position
+    ///
+    /// Like corpses, item piles occupy their tile but are not structures.
+    item_pile_index: HashMap<TilePos, Entity>,
     /// The height of the terrain at each tile position
     height_index: HashMap<TilePos, Height>,
+    /// The tiles covered by bridges, which can be walked across regardless of changes in height
+    bridge_index: HashSet<TilePos>,
+    /// The tiles that are currently dangerous to stand on, such as flooded or burning tiles
+    hazard_index: HashSet<TilePos>,
+    /// The tiles that are still hidden by the fog of war
+    ///
+    /// This is empty unless the map was generated with fog of war, in which case it shrinks as units explore.
+    unexplored_index: HashSet<TilePos>,
+    /// The adjacent tiles that contain a ghost, structure, corpse or item pile, for each tile that has any
+    ///
+    /// Units check their neighbors for something to interact with every time they choose an action,
+    /// so this is cached and only refreshed when any of these are added or removed.
+    interesting_neighbor_index: HashMap<TilePos, Vec<TilePos>>,
+    /// Which unit entity is standing on, or about to move into, each tile position
+    ///
+    /// This is only populated when [`UnitCollision`](crate::units::collision::UnitCollision) is enabled.
+    unit_index: HashMap<TilePos, Entity>,
+    /// The changes made to the map that have not yet been sent as events
+    ///
+    /// These are drained each frame by the [`MapEventsPlugin`](super::map_events::MapEventsPlugin).
+    /// When nothing drains them, only the most recent [`MapGeometry::MAX_PENDING_EVENTS`] are kept.
+    pending_events: VecDeque<MapGeometryEvent>,
 }
 
 /// A [`MapGeometry`] index was missing an entry.
@@ -295,6 +386,16 @@ pub struct IndexError {
 }
 
 impl MapGeometry {
+    /// The furthest number of steps that units will search for unexplored tiles.
+    ///
+    /// Units with nothing to explore nearby wander instead, which eventually brings them closer to the frontier.
+    pub(crate) const MAX_EXPLORATION_DISTANCE: u32 = 10;
+
+    /// The maximum number of changes that are held onto while waiting to be sent as events.
+    ///
+    /// Once this is exceeded, the oldest changes are discarded.
+    pub(crate) const MAX_PENDING_EVENTS: usize = 4096;
+
     /// Creates a new [`MapGeometry`] of the provided raidus.
     ///
     /// All indexes will be empty.
@@ -304,9 +405,32 @@ impl MapGeometry {
             radius,
             terrain_index: HashMap::default(),
             structure_index: HashMap::default(),
+            structure_anchor_index: HashMap::default(),
+            structure_type_index: HashMap::default(),
             ghost_index: HashMap::default(),
+            corpse_index: HashMap::default(),
+            item_pile_index: HashMap::default(),
             height_index: HashMap::default(),
+            bridge_index: HashSet::default(),
+            hazard_index: HashSet::default(),
+            unexplored_index: HashSet::default(),
+            interesting_neighbor_index: HashMap::default(),
+            unit_index: HashMap::default(),
+            pending_events: VecDeque::new(),
+        }
+    }
+
+    /// Removes and returns the changes made to the map since this was last called, in the order they were made.
+    pub(crate) fn take_events(&mut self) -> Vec<MapGeometryEvent> {
+        std::mem::take(&mut self.pending_events).into()
+    }
+
+    /// Records a change made to the map, discarding the oldest change if too many are waiting to be sent.
+    fn record_event(&mut self, event: MapGeometryEvent) {
+        if self.pending_events.len() >= Self::MAX_PENDING_EVENTS {
+            self.pending_events.pop_front();
         }
+        self.pending_events.push_back(event);
     }
 
     /// Is the provided `tile_pos` in the map?
@@ -325,9 +449,183 @@ impl MapGeometry {
 
     /// Is the provided `tile_pos` passable?
     ///
-    /// Tiles that are not part of the map will return `false`
+    /// Tiles that are not part of the map will return `false`.
+    /// Structures block movement, unless they are bridges. Corpses and item piles always block movement.
+    /// Tiles that are occupied or reserved by a unit also block movement.
+    /// Hazardous tiles are never entered.
     pub(crate) fn is_passable(&self, tile_pos: TilePos) -> bool {
-        self.is_valid(tile_pos) && !self.structure_index.contains_key(&tile_pos)
+        self.is_valid(tile_pos)
+            && (!self.structure_index.contains_key(&tile_pos) || self.is_bridge(tile_pos))
+            && !self.is_cluttered(tile_pos)
+            && !self.unit_index.contains_key(&tile_pos)
+            && !self.is_hazardous(tile_pos)
+    }
+
+    /// Is the tile at `tile_pos` currently dangerous to stand on?
+    ///
+    /// Units will not walk onto hazardous tiles, and will try to leave them as soon as possible.
+    pub fn is_hazardous(&self, tile_pos: TilePos) -> bool {
+        self.hazard_index.contains(&tile_pos)
+    }
+
+    /// Marks the tile at `tile_pos` as hazardous (or safe again), such as when it floods or catches fire.
+    pub fn set_hazard(&mut self, tile_pos: TilePos, hazardous: bool) {
+        if hazardous {
+            self.hazard_index.insert(tile_pos);
+        } else {
+            self.hazard_index.remove(&tile_pos);
+        }
+    }
+
+    /// Hides every tile on the map under the fog of war.
+    pub(crate) fn hide_all_tiles(&mut self) {
+        self.unexplored_index = hexagon(Hex::ZERO, self.radius)
+            .map(|hex| TilePos { hex })
+            .collect();
+    }
+
+    /// Has the tile at `tile_pos` been revealed?
+    ///
+    /// Tiles are always explored when the map has no fog of war.
+    pub fn is_explored(&self, tile_pos: TilePos) -> bool {
+        !self.unexplored_index.contains(&tile_pos)
+    }
+
+    /// Has every tile on the map been revealed?
+    pub fn is_fully_explored(&self) -> bool {
+        self.unexplored_index.is_empty()
+    }
+
+    /// Reveals all tiles within `radius` of `center`.
+    pub(crate) fn reveal(&mut self, center: TilePos, radius: u32) {
+        if self.unexplored_index.is_empty() {
+            return;
+        }
+
+        for hex in hexagon(center.hex, radius) {
+            self.unexplored_index.remove(&TilePos { hex });
+        }
+    }
+
+    /// Returns the closest unexplored tile that a unit standing on `start` can walk to.
+    ///
+    /// The search spreads outwards through tiles that can be walked between,
+    /// so the unexplored tile must be passable and reachable.
+    /// To keep this cheap, the search gives up after [`MapGeometry::MAX_EXPLORATION_DISTANCE`] steps.
+    /// Returns [`None`] if there is no such tile.
+    pub(crate) fn nearest_unexplored(&self, start: TilePos) -> Option<TilePos> {
+        if self.unexplored_index.is_empty() {
+            return None;
+        }
+
+        // The number of steps needed to reach each tile that has been found so far
+        let mut distances: HashMap<TilePos, u32> = HashMap::default();
+        let mut frontier = VecDeque::new();
+
+        for neighbor in start.all_neighbors(self) {
+            if self.can_walk_between(start, neighbor) {
+                distances.insert(neighbor, 1);
+                frontier.push_back(neighbor);
+            }
+        }
+
+        while let Some(tile_pos) = frontier.pop_front() {
+            if !self.is_explored(tile_pos) {
+                return Some(tile_pos);
+            }
+
+            let distance = distances[&tile_pos];
+            if distance >= Self::MAX_EXPLORATION_DISTANCE {
+                continue;
+            }
+
+            for neighbor in tile_pos.all_neighbors(self) {
+                if neighbor != start
+                    && !distances.contains_key(&neighbor)
+                    && self.can_walk_between(tile_pos, neighbor)
+                {
+                    distances.insert(neighbor, distance + 1);
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Is the tile at `tile_pos` covered by a bridge?
+    pub(crate) fn is_bridge(&self, tile_pos: TilePos) -> bool {
+        self.bridge_index.contains(&tile_pos)
+    }
+
+    /// The difference in height that must be climbed (or descended) to walk from `from` to the adjacent `to` tile.
+    ///
+    /// Bridges smooth out any change in height, so walking onto or off of them is always level.
+    pub(crate) fn walking_height_difference(&self, from: TilePos, to: TilePos) -> u8 {
+        if self.is_bridge(from) || self.is_bridge(to) {
+            return 0;
+        }
+
+        match (self.get_height(from), self.get_height(to)) {
+            (Ok(from_height), Ok(to_height)) => from_height.0.abs_diff(to_height.0),
+            _ => 0,
+        }
+    }
+
+    /// Can units walk from `from` to the adjacent `to` tile?
+    ///
+    /// The destination must be passable, and must not be separated from the origin by a cliff.
+    pub(crate) fn can_walk_between(&self, from: TilePos, to: TilePos) -> bool {
+        self.is_passable(to) && self.walking_height_difference(from, to) <= Height::MAX_STEP.0
+    }
+
+    /// Returns the connected region of tiles around `start` for which `predicate` returns `true`.
+    ///
+    /// The region grows outwards from `start` through neighboring tiles, and only contains tiles that are on the map.
+    /// If `start` itself does not match the `predicate`, the region will be empty.
+    pub fn flood_fill(
+        &self,
+        start: TilePos,
+        predicate: impl Fn(TilePos) -> bool,
+    ) -> HashSet<TilePos> {
+        if !self.is_valid(start) || !predicate(start) {
+            return HashSet::new();
+        }
+
+        self.connected_region(start, |_from, to| predicate(to))
+    }
+
+    /// Returns the set of tiles that units standing on `start` can walk to.
+    ///
+    /// This includes the `start` tile itself, if it is on the map.
+    pub fn walkable_region(&self, start: TilePos) -> HashSet<TilePos> {
+        if !self.is_valid(start) {
+            return HashSet::new();
+        }
+
+        self.connected_region(start, |from, to| self.can_walk_between(from, to))
+    }
+
+    /// Grows a region outwards from `start`, stepping onto each neighbor for which `can_step(from, to)` returns `true`.
+    fn connected_region(
+        &self,
+        start: TilePos,
+        can_step: impl Fn(TilePos, TilePos) -> bool,
+    ) -> HashSet<TilePos> {
+        // This is done breadth-first with a queue, rather than recursively, to avoid overflowing the stack on large maps
+        let mut frontier = VecDeque::from([start]);
+        let mut region = HashSet::from_iter([start]);
+
+        while let Some(tile_pos) = frontier.pop_front() {
+            for neighbor in tile_pos.all_neighbors(self) {
+                if !region.contains(&neighbor) && can_step(tile_pos, neighbor) {
+                    region.insert(neighbor);
+                    frontier.push_back(neighbor);
+                }
+            }
+        }
+
+        region
     }
 
     /// Is there enough space for a structure with the provided `footprint` located at the `center` tile?
@@ -335,7 +633,7 @@ impl MapGeometry {
         footprint
             .in_world_space(center)
             .iter()
-            .all(|tile_pos| self.get_structure(*tile_pos).is_none())
+            .all(|tile_pos| !self.is_obstructed(*tile_pos))
     }
 
     /// Are all of the terrain tiles in the provided `footprint` appropriate?
@@ -353,6 +651,43 @@ impl MapGeometry {
         })
     }
 
+    /// Is each of the `required_adjacency` requirements met by a tile bordering the provided `footprint`?
+    fn is_adjacency_satisfied(
+        &self,
+        center: TilePos,
+        footprint: &Footprint,
+        terrain_query: &Query<&Id<Terrain>>,
+        required_adjacency: &HashSet<AdjacencyRequirement>,
+    ) -> bool {
+        if required_adjacency.is_empty() {
+            return true;
+        }
+
+        let footprint_tiles = footprint.in_world_space(center);
+        let bordering_tiles: HashSet<TilePos> = footprint_tiles
+            .iter()
+            .flat_map(|tile_pos| tile_pos.all_neighbors(self))
+            .filter(|tile_pos| !footprint_tiles.contains(tile_pos))
+            .collect();
+
+        required_adjacency.iter().all(|requirement| {
+            bordering_tiles.iter().any(|&tile_pos| match requirement {
+                AdjacencyRequirement::Terrain(terrain_id) => {
+                    self.get_terrain(tile_pos)
+                        .and_then(|terrain_entity| terrain_query.get(terrain_entity).ok())
+                        == Some(terrain_id)
+                }
+                AdjacencyRequirement::Structure(structure_id) => self
+                    .get_structure(tile_pos)
+                    .is_some_and(|structure_entity| {
+                        self.structure_type_index
+                            .get(structure_id)
+                            .is_some_and(|entities| entities.contains(&structure_entity))
+                    }),
+            })
+        })
+    }
+
     /// Are all of the terrain tiles in the provided `footprint` flat?
     fn is_terrain_flat(&self, center: TilePos, footprint: &Footprint) -> bool {
         let height = self.get_height(center).unwrap();
@@ -371,24 +706,92 @@ impl MapGeometry {
     /// This checks that:
     /// - the area is in the map
     /// - the area is flat
-    /// - the area is free of structures
+    /// - the area is free of structures, corpses and item piles
     /// - all tiles match the provided allowable terrain list
+    /// - every required neighbor is found next to the area
     pub(crate) fn can_build(
         &self,
         center: TilePos,
         footprint: Footprint,
         terrain_query: &Query<&Id<Terrain>>,
         allowed_terrain_types: &HashSet<Id<Terrain>>,
+        required_adjacency: &HashSet<AdjacencyRequirement>,
     ) -> bool {
         self.is_footprint_valid(center, &footprint)
             && self.is_terrain_flat(center, &footprint)
             && self.is_space_available(center, &footprint)
             && self.is_terrain_valid(center, &footprint, terrain_query, allowed_terrain_types)
+            && self.is_adjacency_satisfied(center, &footprint, terrain_query, required_adjacency)
+    }
+
+    /// Can a ghost of a structure with the provided `footprint` be placed at the `center` tile?
+    ///
+    /// In addition to the checks performed by [`MapGeometry::can_build`],
+    /// the ghost must not overlap any other ghost.
+    /// Any ghost found at the `center` tile is ignored, as it will be replaced by the new ghost.
+    pub(crate) fn can_build_ghost(
+        &self,
+        center: TilePos,
+        footprint: Footprint,
+        terrain_query: &Query<&Id<Terrain>>,
+        allowed_terrain_types: &HashSet<Id<Terrain>>,
+        required_adjacency: &HashSet<AdjacencyRequirement>,
+    ) -> bool {
+        let replaced_ghost = self.get_ghost(center);
+        let overlaps_other_ghosts = footprint.in_world_space(center).iter().any(|tile_pos| {
+            let ghost = self.get_ghost(*tile_pos);
+            ghost.is_some() && ghost != replaced_ghost
+        });
+
+        !overlaps_other_ghosts
+            && self.can_build(
+                center,
+                footprint,
+                terrain_query,
+                allowed_terrain_types,
+                required_adjacency,
+            )
+    }
+
+    /// Can a structure with the provided `footprint` replace the `replaced_entity` structure at the `center` tile?
+    ///
+    /// This performs the same checks as [`MapGeometry::can_build`],
+    /// except that tiles covered by the `replaced_entity` count as free.
+    pub(crate) fn can_replace_structure(
+        &self,
+        center: TilePos,
+        footprint: Footprint,
+        replaced_entity: Entity,
+        terrain_query: &Query<&Id<Terrain>>,
+        allowed_terrain_types: &HashSet<Id<Terrain>>,
+        required_adjacency: &HashSet<AdjacencyRequirement>,
+    ) -> bool {
+        let is_space_available = footprint.in_world_space(center).iter().all(|tile_pos| {
+            let structure = self.get_structure(*tile_pos);
+            (structure.is_none() || structure == Some(replaced_entity))
+                && !self.is_cluttered(*tile_pos)
+        });
+
+        self.is_footprint_valid(center, &footprint)
+            && self.is_terrain_flat(center, &footprint)
+            && is_space_available
+            && self.is_terrain_valid(center, &footprint, terrain_query, allowed_terrain_types)
+            && self.is_adjacency_satisfied(center, &footprint, terrain_query, required_adjacency)
     }
 
     /// Updates the height of the tile at `tile_pos`
+    ///
+    /// Sends a [`HeightChanged`] event if the height is different.
     pub(crate) fn update_height(&mut self, tile_pos: TilePos, height: Height) {
-        self.height_index.insert(tile_pos, height);
+        let old_height = self.height_index.insert(tile_pos, height);
+
+        if old_height != Some(height) {
+            self.record_event(MapGeometryEvent::HeightChanged(HeightChanged {
+                tile_pos,
+                old_height,
+                new_height: height,
+            }));
+        }
     }
 
     /// Returns the height of the tile at `tile_pos`, if available.
@@ -443,28 +846,110 @@ impl MapGeometry {
         self.structure_index.get(&tile_pos).copied()
     }
 
+    /// Is the provided `tile_pos` covered by a structure, a corpse or an item pile?
+    pub(crate) fn is_obstructed(&self, tile_pos: TilePos) -> bool {
+        self.structure_index.contains_key(&tile_pos) || self.is_cluttered(tile_pos)
+    }
+
+    /// Is the provided `tile_pos` covered by a corpse or an item pile?
+    fn is_cluttered(&self, tile_pos: TilePos) -> bool {
+        self.corpse_index.contains_key(&tile_pos) || self.item_pile_index.contains_key(&tile_pos)
+    }
+
+    /// Gets the anchor tile of the structure covering the provided `tile_pos`, if any.
+    ///
+    /// Every tile in a structure's footprint resolves to the same anchor, which the footprint is centered around.
+    pub(crate) fn get_structure_anchor(&self, tile_pos: TilePos) -> Option<TilePos> {
+        let structure_entity = self.structure_index.get(&tile_pos)?;
+        self.structure_anchor_index.get(structure_entity).copied()
+    }
+
+    /// Returns all structure entities of the provided `structure_id`.
+    ///
+    /// The order of the returned entities is arbitrary.
+    pub fn structures_of_type(
+        &self,
+        structure_id: Id<Structure>,
+    ) -> impl Iterator<Item = Entity> + '_ {
+        self.structure_type_index
+            .get(&structure_id)
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
     /// Adds the provided `structure_entity` to the structure index at the provided `center`.
+    ///
+    /// Sends a [`StructureAdded`] event.
     pub(crate) fn add_structure(
         &mut self,
         center: TilePos,
         footprint: &Footprint,
+        structure_id: Id<Structure>,
         structure_entity: Entity,
     ) {
-        for tile_pos in footprint.in_world_space(center) {
+        let footprint_tiles = footprint.in_world_space(center);
+        for &tile_pos in &footprint_tiles {
             self.structure_index.insert(tile_pos, structure_entity);
         }
+        self.structure_anchor_index.insert(structure_entity, center);
+
+        self.structure_type_index
+            .entry(structure_id)
+            .or_default()
+            .insert(structure_entity);
+
+        self.refresh_interesting_neighbors(footprint_tiles);
+        self.record_event(MapGeometryEvent::StructureAdded(StructureAdded {
+            structure_entity,
+            structure_id,
+            center,
+        }));
+    }
+
+    /// Marks all tiles in the `footprint` centered around `center` as being covered by a bridge.
+    pub(crate) fn add_bridge(&mut self, center: TilePos, footprint: &Footprint) {
+        for tile_pos in footprint.in_world_space(center) {
+            self.bridge_index.insert(tile_pos);
+        }
     }
 
     /// Removes any structure entity found at the provided `tile_pos` from the structure index.
     ///
+    /// Any bridges on the tiles it covered are removed too.
+    /// Sends a [`StructureRemoved`] event if a structure was found.
+    ///
     /// Returns the removed entity, if any.
     pub(crate) fn remove_structure(&mut self, tile_pos: TilePos) -> Option<Entity> {
         let removed = self.structure_index.remove(&tile_pos);
+        self.bridge_index.remove(&tile_pos);
 
         // Iterate through all of the entries, removing any other entries that point to the same entity
         // PERF: this could be faster, but would require a different data structure.
         if let Some(removed_entity) = removed {
-            self.structure_index.retain(|_k, v| *v != removed_entity);
+            self.structure_anchor_index.remove(&removed_entity);
+            let mut removed_tiles = vec![tile_pos];
+            let bridge_index = &mut self.bridge_index;
+            self.structure_index.retain(|k, v| {
+                let keep = *v != removed_entity;
+                if !keep {
+                    bridge_index.remove(k);
+                    removed_tiles.push(*k);
+                }
+                keep
+            });
+
+            // The structure type isn't known here, so check each type
+            self.structure_type_index.retain(|_k, entities| {
+                entities.remove(&removed_entity);
+                !entities.is_empty()
+            });
+
+            self.refresh_interesting_neighbors(removed_tiles);
+            self.record_event(MapGeometryEvent::StructureRemoved(StructureRemoved {
+                structure_entity: removed_entity,
+                tile_pos,
+            }));
         };
 
         removed
@@ -476,18 +961,28 @@ impl MapGeometry {
     }
 
     /// Adds the provided `ghost_entity` to the structure index at the provided `center`.
+    ///
+    /// Sends a [`GhostAdded`] event.
     pub(crate) fn add_ghost(
         &mut self,
         center: TilePos,
         footprint: &Footprint,
         ghost_entity: Entity,
     ) {
-        for tile_pos in footprint.in_world_space(center) {
+        let footprint_tiles = footprint.in_world_space(center);
+        for &tile_pos in &footprint_tiles {
             self.ghost_index.insert(tile_pos, ghost_entity);
         }
+
+        self.refresh_interesting_neighbors(footprint_tiles);
+        self.record_event(MapGeometryEvent::GhostAdded(GhostAdded {
+            ghost_entity,
+            center,
+        }));
     }
 
     /// Removes any ghost entity found at the provided `tile_pos` from the ghost index.
+    /// Sends a [`GhostRemoved`] event if a ghost was found.
     ///
     /// Returns the removed entity, if any.
     pub(crate) fn remove_ghost(&mut self, tile_pos: TilePos) -> Option<Entity> {
@@ -496,17 +991,165 @@ impl MapGeometry {
         // Iterate through all of the entries, removing any other entries that point to the same entity
         // PERF: this could be faster, but would require a different data structure.
         if let Some(removed_entity) = removed {
-            self.ghost_index.retain(|_k, v| *v != removed_entity);
+            let mut removed_tiles = vec![tile_pos];
+            self.ghost_index.retain(|k, v| {
+                let keep = *v != removed_entity;
+                if !keep {
+                    removed_tiles.push(*k);
+                }
+                keep
+            });
+
+            self.refresh_interesting_neighbors(removed_tiles);
+            self.record_event(MapGeometryEvent::GhostRemoved(GhostRemoved {
+                ghost_entity: removed_entity,
+                tile_pos,
+            }));
         };
 
         removed
     }
+
+    /// Gets the corpse [`Entity`] at the provided `tile_pos`, if any.
+    pub(crate) fn get_corpse(&self, tile_pos: TilePos) -> Option<Entity> {
+        self.corpse_index.get(&tile_pos).copied()
+    }
+
+    /// Adds the provided `corpse_entity` to the corpse index at the provided `tile_pos`.
+    pub(crate) fn add_corpse(&mut self, tile_pos: TilePos, corpse_entity: Entity) {
+        self.corpse_index.insert(tile_pos, corpse_entity);
+        self.refresh_interesting_neighbors([tile_pos]);
+    }
+
+    /// Removes any corpse entity found at the provided `tile_pos` from the corpse index.
+    ///
+    /// Returns the removed entity, if any.
+    pub(crate) fn remove_corpse(&mut self, tile_pos: TilePos) -> Option<Entity> {
+        let removed = self.corpse_index.remove(&tile_pos);
+        if removed.is_some() {
+            self.refresh_interesting_neighbors([tile_pos]);
+        }
+
+        removed
+    }
+
+    /// Gets the item pile [`Entity`] at the provided `tile_pos`, if any.
+    pub(crate) fn get_item_pile(&self, tile_pos: TilePos) -> Option<Entity> {
+        self.item_pile_index.get(&tile_pos).copied()
+    }
+
+    /// Adds the provided `item_pile_entity` to the item pile index at the provided `tile_pos`.
+    pub(crate) fn add_item_pile(&mut self, tile_pos: TilePos, item_pile_entity: Entity) {
+        self.item_pile_index.insert(tile_pos, item_pile_entity);
+        self.refresh_interesting_neighbors([tile_pos]);
+    }
+
+    /// Removes any item pile entity found at the provided `tile_pos` from the item pile index.
+    ///
+    /// Returns the removed entity, if any.
+    pub(crate) fn remove_item_pile(&mut self, tile_pos: TilePos) -> Option<Entity> {
+        let removed = self.item_pile_index.remove(&tile_pos);
+        if removed.is_some() {
+            self.refresh_interesting_neighbors([tile_pos]);
+        }
+
+        removed
+    }
+
+    /// Is the provided `tile_pos` occupied or reserved by a unit?
+    pub(crate) fn is_occupied_by_unit(&self, tile_pos: TilePos) -> bool {
+        self.unit_index.contains_key(&tile_pos)
+    }
+
+    /// Claims the provided `tile_pos` for the `unit_entity`, blocking other units from entering it.
+    pub(crate) fn reserve_tile(&mut self, tile_pos: TilePos, unit_entity: Entity) {
+        self.unit_index.insert(tile_pos, unit_entity);
+    }
+
+    /// Releases the claim that `unit_entity` has on the provided `tile_pos`, if any.
+    ///
+    /// Claims made by other units are left alone.
+    pub(crate) fn vacate_tile(&mut self, tile_pos: TilePos, unit_entity: Entity) {
+        if self.unit_index.get(&tile_pos) == Some(&unit_entity) {
+            self.unit_index.remove(&tile_pos);
+        }
+    }
+
+    /// Releases every tile claimed by units.
+    pub(crate) fn clear_units(&mut self) {
+        self.unit_index.clear();
+    }
+
+    /// The tiles adjacent to `tile_pos` that contain a ghost, structure, corpse or item pile.
+    ///
+    /// Tiles are returned in the same order as [`TilePos::all_neighbors`].
+    pub fn interesting_neighbors(&self, tile_pos: TilePos) -> impl Iterator<Item = TilePos> + '_ {
+        self.interesting_neighbor_index
+            .get(&tile_pos)
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
+    /// The tiles within `radius` of `tile_pos` that contain a ghost, structure, corpse or item pile, excluding `tile_pos` itself.
+    ///
+    /// Tiles are returned in order of increasing distance from `tile_pos`.
+    pub(crate) fn interesting_tiles_within(&self, tile_pos: TilePos, radius: u32) -> Vec<TilePos> {
+        // The cached neighbors are much cheaper to look up than scanning the whole area
+        if radius == 1 {
+            return self.interesting_neighbors(tile_pos).collect();
+        }
+
+        let mut interesting_tiles: Vec<TilePos> = hexagon(tile_pos.hex, radius)
+            .map(|hex| TilePos { hex })
+            .filter(|&nearby| {
+                nearby != tile_pos
+                    && self.is_valid(nearby)
+                    && (self.ghost_index.contains_key(&nearby) || self.is_obstructed(nearby))
+            })
+            .collect();
+        interesting_tiles.sort_by_key(|nearby| tile_pos.unsigned_distance_to(nearby.hex));
+        interesting_tiles
+    }
+
+    /// The tiles next to `tile_pos` that a unit could stand on to interact with whatever is there.
+    pub(crate) fn access_tiles(&self, tile_pos: TilePos) -> impl Iterator<Item = TilePos> + '_ {
+        tile_pos
+            .all_neighbors(self)
+            .into_iter()
+            .filter(|&neighbor| {
+                (!self.structure_index.contains_key(&neighbor) || self.is_bridge(neighbor))
+                    && !self.is_cluttered(neighbor)
+            })
+    }
+
+    /// Recomputes the cached [`MapGeometry::interesting_neighbors`] of every tile next to the `changed_tiles`.
+    fn refresh_interesting_neighbors(&mut self, changed_tiles: impl IntoIterator<Item = TilePos>) {
+        for changed_tile in changed_tiles {
+            for tile_pos in changed_tile.all_neighbors(self) {
+                let interesting_neighbors: Vec<TilePos> = tile_pos
+                    .all_neighbors(self)
+                    .into_iter()
+                    .filter(|neighbor| {
+                        self.ghost_index.contains_key(neighbor) || self.is_obstructed(*neighbor)
+                    })
+                    .collect();
+
+                if interesting_neighbors.is_empty() {
+                    self.interesting_neighbor_index.remove(&tile_pos);
+                } else {
+                    self.interesting_neighbor_index
+                        .insert(tile_pos, interesting_neighbors);
+                }
+            }
+        }
+    }
 }
 
 /// The hex direction that this entity is facing.
 ///
 /// Stored as a component on each entity with a grid-aligned rotation.
-#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Deref, DerefMut)]
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Deref, DerefMut, Serialize, Deserialize)]
 pub(crate) struct Facing {
     /// The desired direction.
     ///
@@ -515,6 +1158,14 @@ pub(crate) struct Facing {
 }
 
 impl Facing {
+    /// The rotation needed for an object to face this direction.
+    ///
+    /// We want to be aligned with the faces of the hexes, not their points.
+    pub(crate) fn rotation(&self, map_geometry: &MapGeometry) -> Quat {
+        let angle = self.direction.angle(&map_geometry.layout.orientation) + PI / 6.;
+        Quat::from_axis_angle(Vec3::Y, angle)
+    }
+
     /// Rotates this facing one 60 degree step clockwise.
     pub(crate) fn rotate_left(&mut self) {
         self.direction = self.direction.left();
@@ -594,19 +1245,15 @@ pub(crate) fn hexagonal_column(hex_layout: &HexLayout, hex_height: f32) -> Mesh
 }
 
 /// Rotates objects so they are facing the correct direction.
-pub(super) fn sync_rotation_to_facing(
+pub(crate) fn sync_rotation_to_facing(
     // Camera requires different logic, it rotates "around" a central point
     // PERF: re-enable change detection. For some reason this wasn't working on structures,
     // but was on ghosts.
     mut query: Query<(&mut Transform, &Facing), Without<Camera3d>>,
     map_geometry: Res<MapGeometry>,
 ) {
-    for (mut transform, &facing) in query.iter_mut() {
-        // Rotate the object in the correct direction
-        // We want to be aligned with the faces of the hexes, not their points
-        let angle = facing.direction.angle(&map_geometry.layout.orientation) + PI / 6.;
-        let target = Quat::from_axis_angle(Vec3::Y, angle);
-        transform.rotation = target;
+    for (mut transform, facing) in query.iter_mut() {
+        transform.rotation = facing.rotation(&map_geometry);
     }
 }
 
@@ -625,6 +1272,76 @@ mod tests {
         }
     }
 
+    #[test]
+    fn tiles_beyond_the_radius_are_off_the_map() {
+        let map_geometry = MapGeometry::new(2);
+
+        assert!(map_geometry.is_valid(TilePos::ZERO));
+        assert!(map_geometry.is_valid(TilePos::new(2, 0)));
+        assert!(map_geometry.is_valid(TilePos::new(-2, 2)));
+        assert!(!map_geometry.is_valid(TilePos::new(3, 0)));
+        assert!(!map_geometry.is_valid(TilePos::new(2, 1)));
+        assert!(!map_geometry.is_valid(TilePos::new(-3, 3)));
+
+        let edge_neighbors: Vec<TilePos> = TilePos::new(2, 0)
+            .all_neighbors(&map_geometry)
+            .into_iter()
+            .collect();
+        assert_eq!(edge_neighbors.len(), 3);
+        assert!(edge_neighbors
+            .iter()
+            .all(|&tile_pos| map_geometry.is_valid(tile_pos)));
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            assert!(map_geometry.is_valid(TilePos::random(&map_geometry, &mut rng)));
+        }
+    }
+
+    #[test]
+    fn rings_and_spirals_cover_the_expected_tiles() {
+        let map_geometry = MapGeometry::new(10);
+
+        assert_eq!(
+            TilePos::ZERO.ring(0, &map_geometry).collect::<Vec<_>>(),
+            vec![TilePos::ZERO]
+        );
+
+        for radius in 1..=5 {
+            let ring: Vec<TilePos> = TilePos::ZERO.ring(radius, &map_geometry).collect();
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert!(ring
+                .iter()
+                .all(|tile_pos| TilePos::ZERO.unsigned_distance_to(tile_pos.hex) == radius));
+        }
+
+        for radius in 0..=5 {
+            let spiral: Vec<TilePos> = TilePos::ZERO.spiral(radius, &map_geometry).collect();
+            assert_eq!(
+                spiral.len(),
+                3 * radius as usize * (radius as usize + 1) + 1
+            );
+
+            let distances: Vec<u32> = spiral
+                .iter()
+                .map(|tile_pos| TilePos::ZERO.unsigned_distance_to(tile_pos.hex))
+                .collect();
+            assert!(distances.windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+    }
+
+    #[test]
+    fn rings_and_spirals_are_clipped_to_the_map() {
+        let map_geometry = MapGeometry::new(2);
+        let edge = TilePos::new(2, 0);
+
+        assert_eq!(edge.ring(1, &map_geometry).count(), 3);
+        assert!(edge
+            .ring(2, &map_geometry)
+            .all(|tile_pos| map_geometry.is_valid(tile_pos)));
+        assert_eq!(TilePos::ZERO.spiral(5, &map_geometry).count(), 19);
+    }
+
     #[test]
     fn height_clamps() {
         assert_eq!(Height::MIN, Height::from_world_pos(0.));
@@ -657,7 +1374,7 @@ mod tests {
         let footprint = Footprint::hexagon(1);
         let structure_entity = Entity::from_bits(42);
         let center = TilePos::new(17, -2);
-        map_geometry.add_structure(center, &footprint, structure_entity);
+        map_geometry.add_structure(center, &footprint, Id::from_name("test"), structure_entity);
 
         // Check that the structure index was updated correctly
         for tile_pos in footprint.in_world_space(center) {
@@ -672,7 +1389,7 @@ mod tests {
         let footprint = Footprint::hexagon(1);
         let structure_entity = Entity::from_bits(42);
         let center = TilePos::new(17, -2);
-        map_geometry.add_structure(center, &footprint, structure_entity);
+        map_geometry.add_structure(center, &footprint, Id::from_name("test"), structure_entity);
         map_geometry.remove_structure(center);
 
         // Check that the structure index was updated correctly
@@ -681,4 +1398,289 @@ mod tests {
             assert_eq!(None, map_geometry.get_structure(tile_pos));
         }
     }
+
+    #[test]
+    fn structures_of_type_tracks_additions_and_removals() {
+        let mut map_geometry = MapGeometry::new(10);
+
+        let acacia = Id::from_name("acacia");
+        let leuco = Id::from_name("leuco");
+        let first_acacia = Entity::from_bits(1);
+        let second_acacia = Entity::from_bits(2);
+        let first_leuco = Entity::from_bits(3);
+
+        map_geometry.add_structure(
+            TilePos::new(0, 0),
+            &Footprint::single(),
+            acacia,
+            first_acacia,
+        );
+        map_geometry.add_structure(
+            TilePos::new(1, 0),
+            &Footprint::single(),
+            acacia,
+            second_acacia,
+        );
+        map_geometry.add_structure(TilePos::new(2, 0), &Footprint::single(), leuco, first_leuco);
+
+        let acacias: HashSet<Entity> = map_geometry.structures_of_type(acacia).collect();
+        assert_eq!(acacias, HashSet::from_iter([first_acacia, second_acacia]));
+
+        map_geometry.remove_structure(TilePos::new(0, 0));
+        let acacias: HashSet<Entity> = map_geometry.structures_of_type(acacia).collect();
+        assert_eq!(acacias, HashSet::from_iter([second_acacia]));
+
+        let leucos: Vec<Entity> = map_geometry.structures_of_type(leuco).collect();
+        assert_eq!(leucos, vec![first_leuco]);
+
+        map_geometry.remove_structure(TilePos::new(2, 0));
+        assert_eq!(map_geometry.structures_of_type(leuco).count(), 0);
+    }
+
+    #[test]
+    fn flood_fill_stays_on_one_side_of_a_wall() {
+        let radius = 3;
+        let mut map_geometry = MapGeometry::new(radius);
+
+        // Build a wall straight through the center of the map
+        for y in -(radius as i32)..=radius as i32 {
+            let tile_pos = TilePos::new(0, y);
+            if map_geometry.is_valid(tile_pos) {
+                map_geometry.add_structure(
+                    tile_pos,
+                    &Footprint::single(),
+                    Id::from_name("wall"),
+                    Entity::from_bits((y + 10) as u64),
+                );
+            }
+        }
+
+        let region = map_geometry.flood_fill(TilePos::new(-1, 0), |tile_pos| {
+            map_geometry.is_passable(tile_pos)
+        });
+
+        // Half of the 37 tiles, after removing the 7 tiles used for the wall
+        assert_eq!(region.len(), 15);
+        assert!(region.iter().all(|tile_pos| tile_pos.x < 0));
+
+        let blocked = map_geometry.flood_fill(TilePos::new(0, 0), |tile_pos| {
+            map_geometry.is_passable(tile_pos)
+        });
+        assert!(blocked.is_empty());
+    }
+
+    #[test]
+    fn bridges_let_units_cross_cliffs() {
+        let radius = 3;
+        let mut map_geometry = MapGeometry::new(radius);
+
+        // A cliff runs through the center of the map, with high ground to the east
+        for hex in hexagon(Hex::ZERO, radius) {
+            let tile_pos = TilePos { hex };
+            let height = if tile_pos.x > 0 { Height(5) } else { Height(1) };
+            map_geometry.update_height(tile_pos, height);
+        }
+
+        let lowland = TilePos::new(-2, 0);
+        let highland = TilePos::new(2, 0);
+        assert!(!map_geometry.walkable_region(lowland).contains(&highland));
+
+        // Build a bridge on the edge of the cliff
+        let bridge_pos = TilePos::new(1, 0);
+        map_geometry.add_structure(
+            bridge_pos,
+            &Footprint::single(),
+            Id::from_name("bridge"),
+            Entity::from_bits(1),
+        );
+        map_geometry.add_bridge(bridge_pos, &Footprint::single());
+
+        assert!(map_geometry.walkable_region(lowland).contains(&highland));
+        // Walking onto the bridge does not require any climbing
+        assert_eq!(
+            map_geometry.walking_height_difference(TilePos::new(0, 0), bridge_pos),
+            0
+        );
+
+        // Once the bridge is removed, the cliff blocks movement again
+        map_geometry.remove_structure(bridge_pos);
+        assert!(!map_geometry.is_bridge(bridge_pos));
+        assert!(!map_geometry.walkable_region(lowland).contains(&highland));
+    }
+
+    #[test]
+    fn interesting_neighbors_track_structures_and_ghosts() {
+        let mut map_geometry = MapGeometry::new(3);
+        let structure_pos = TilePos::new(1, 0);
+        let ghost_pos = TilePos::new(-1, 0);
+
+        assert_eq!(map_geometry.interesting_neighbors(TilePos::ZERO).count(), 0);
+
+        map_geometry.add_structure(
+            structure_pos,
+            &Footprint::single(),
+            Id::from_name("storage"),
+            Entity::from_bits(1),
+        );
+        map_geometry.add_ghost(ghost_pos, &Footprint::single(), Entity::from_bits(2));
+
+        let neighbors: HashSet<TilePos> =
+            map_geometry.interesting_neighbors(TilePos::ZERO).collect();
+        assert_eq!(neighbors, HashSet::from_iter([structure_pos, ghost_pos]));
+        // Structures are not their own neighbors
+        assert_eq!(map_geometry.interesting_neighbors(structure_pos).count(), 0);
+
+        map_geometry.remove_structure(structure_pos);
+        map_geometry.remove_ghost(ghost_pos);
+        assert_eq!(map_geometry.interesting_neighbors(TilePos::ZERO).count(), 0);
+    }
+
+    #[test]
+    fn pending_events_are_bounded_without_a_reader() {
+        let mut map_geometry = MapGeometry::new(1);
+
+        for i in 0..MapGeometry::MAX_PENDING_EVENTS + 1 {
+            map_geometry.update_height(TilePos::ZERO, Height(i as u8 % 2));
+        }
+
+        let events = map_geometry.take_events();
+        assert_eq!(events.len(), MapGeometry::MAX_PENDING_EVENTS);
+        // The oldest change was the one that was dropped
+        assert_eq!(
+            events[0],
+            MapGeometryEvent::HeightChanged(HeightChanged {
+                tile_pos: TilePos::ZERO,
+                old_height: Some(Height(0)),
+                new_height: Height(1),
+            })
+        );
+        assert!(map_geometry.take_events().is_empty());
+    }
+
+    #[test]
+    fn overlapping_ghosts_are_rejected() {
+        use bevy::ecs::system::SystemState;
+
+        let loam = Id::<Terrain>::from_name("loam");
+        let allowed_terrain_types = HashSet::from_iter([loam]);
+
+        let mut world = World::new();
+        let mut map_geometry = MapGeometry::new(4);
+        for hex in hexagon(Hex::ZERO, 4) {
+            let tile_pos = TilePos { hex };
+            let terrain_entity = world.spawn(loam).id();
+            map_geometry.add_terrain(tile_pos, terrain_entity);
+            map_geometry.update_height(tile_pos, Height(1));
+        }
+
+        let mut system_state: SystemState<Query<&Id<Terrain>>> = SystemState::new(&mut world);
+        let terrain_query = system_state.get(&world);
+
+        let large_footprint = Footprint::hexagon(1);
+        let existing_ghost = Entity::from_bits(1);
+        map_geometry.add_ghost(TilePos::ZERO, &large_footprint, existing_ghost);
+
+        // A second large ghost whose footprint overlaps the first
+        assert!(!map_geometry.can_build_ghost(
+            TilePos::new(2, 0),
+            large_footprint.clone(),
+            &terrain_query,
+            &allowed_terrain_types,
+            &HashSet::new()
+        ));
+        // Structures themselves don't care about ghosts
+        assert!(map_geometry.can_build(
+            TilePos::new(2, 0),
+            large_footprint.clone(),
+            &terrain_query,
+            &allowed_terrain_types,
+            &HashSet::new()
+        ));
+        // Placing a ghost on top of an existing ghost replaces it
+        assert!(map_geometry.can_build_ghost(
+            TilePos::ZERO,
+            large_footprint.clone(),
+            &terrain_query,
+            &allowed_terrain_types,
+            &HashSet::new()
+        ));
+        // Ghosts that don't overlap are fine
+        assert!(map_geometry.can_build_ghost(
+            TilePos::new(3, 0),
+            large_footprint,
+            &terrain_query,
+            &allowed_terrain_types,
+            &HashSet::new()
+        ));
+    }
+
+    #[test]
+    fn adjacency_gated_structures_need_their_neighbors() {
+        use bevy::ecs::system::SystemState;
+
+        let loam = Id::<Terrain>::from_name("loam");
+        let water = Id::<Terrain>::from_name("water");
+        let farm = Id::<Structure>::from_name("farm");
+        let allowed_terrain_types = HashSet::from_iter([loam]);
+        let water_pos = TilePos::new(3, 0);
+
+        let mut world = World::new();
+        let mut map_geometry = MapGeometry::new(4);
+        for hex in hexagon(Hex::ZERO, 4) {
+            let tile_pos = TilePos { hex };
+            let terrain_id = if tile_pos == water_pos { water } else { loam };
+            let terrain_entity = world.spawn(terrain_id).id();
+            map_geometry.add_terrain(tile_pos, terrain_entity);
+            map_geometry.update_height(tile_pos, Height(1));
+        }
+
+        let mut system_state: SystemState<Query<&Id<Terrain>>> = SystemState::new(&mut world);
+        let terrain_query = system_state.get(&world);
+
+        // A dock must be next to water
+        let dock_adjacency = HashSet::from_iter([AdjacencyRequirement::Terrain(water)]);
+        let can_build_dock = |tile_pos: TilePos| {
+            map_geometry.can_build(
+                tile_pos,
+                Footprint::single(),
+                &terrain_query,
+                &allowed_terrain_types,
+                &dock_adjacency,
+            )
+        };
+        assert!(can_build_dock(TilePos::new(2, 0)));
+        assert!(!can_build_dock(TilePos::ZERO));
+
+        // A mill must be next to a farm
+        let mill_adjacency = HashSet::from_iter([AdjacencyRequirement::Structure(farm)]);
+        let mill_pos = TilePos::new(-2, 0);
+        assert!(!map_geometry.can_build_ghost(
+            mill_pos,
+            Footprint::single(),
+            &terrain_query,
+            &allowed_terrain_types,
+            &mill_adjacency
+        ));
+
+        // Ghosts of the required structure don't count
+        let farm_pos = TilePos::new(-3, 0);
+        map_geometry.add_ghost(farm_pos, &Footprint::single(), Entity::from_bits(1));
+        assert!(!map_geometry.can_build_ghost(
+            mill_pos,
+            Footprint::single(),
+            &terrain_query,
+            &allowed_terrain_types,
+            &mill_adjacency
+        ));
+
+        map_geometry.remove_ghost(farm_pos);
+        map_geometry.add_structure(farm_pos, &Footprint::single(), farm, Entity::from_bits(2));
+        assert!(map_geometry.can_build_ghost(
+            mill_pos,
+            Footprint::single(),
+            &terrain_query,
+            &allowed_terrain_types,
+            &mill_adjacency
+        ));
+    }
 }
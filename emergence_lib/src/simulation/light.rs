@@ -5,7 +5,7 @@ use core::fmt::Display;
 use derive_more::{Add, AddAssign};
 use serde::{Deserialize, Serialize};
 
-use super::SimulationSet;
+use super::{time::move_celestial_bodies, SimulationSet};
 use crate::graphics::lighting::CelestialBody;
 
 /// Systems and resources for computing light (in in-game quantities).
@@ -14,7 +14,7 @@ pub(super) struct LightPlugin;
 impl Plugin for LightPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            (compute_light,)
+            (compute_light.after(move_celestial_bodies),)
                 .in_set(SimulationSet)
                 .in_schedule(CoreSchedule::FixedUpdate),
         )
@@ -58,7 +58,7 @@ impl Display for Illuminance {
 }
 
 /// Computes the amount of light available from each celestial body based on its position in the sky and luminous intensity.
-fn compute_light(mut query: Query<&CelestialBody>, mut total_light: ResMut<TotalLight>) {
+pub(super) fn compute_light(mut query: Query<&CelestialBody>, mut total_light: ResMut<TotalLight>) {
     let mut sum = Illuminance(0.0);
     for body in query.iter_mut() {
         let light = body.compute_light();
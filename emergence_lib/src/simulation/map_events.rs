@@ -0,0 +1,210 @@
+//! Events sent whenever the [`MapGeometry`] changes.
+//!
+//! Systems that only care about changes to the map (like overlays or caches) can read these,
+//! rather than polling the [`MapGeometry`] every tick.
+
+use bevy::prelude::*;
+
+use crate::{asset_management::manifest::Id, structures::structure_manifest::Structure};
+
+use super::geometry::{Height, MapGeometry, TilePos};
+
+/// Publishes the changes recorded by the [`MapGeometry`] as events.
+pub(super) struct MapEventsPlugin;
+
+impl Plugin for MapEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<StructureAdded>()
+            .add_event::<StructureRemoved>()
+            .add_event::<GhostAdded>()
+            .add_event::<GhostRemoved>()
+            .add_event::<HeightChanged>()
+            // Changes can be made at any point in the frame, so wait until they're all done
+            .add_system(send_map_geometry_events.in_base_set(CoreSet::Last));
+    }
+}
+
+/// A structure was added to the [`MapGeometry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct StructureAdded {
+    /// The structure's entity.
+    pub(crate) structure_entity: Entity,
+    /// The variety of structure that was added.
+    pub(crate) structure_id: Id<Structure>,
+    /// The center of the structure's footprint.
+    pub(crate) center: TilePos,
+}
+
+/// A structure was removed from the [`MapGeometry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct StructureRemoved {
+    /// The structure's entity.
+    pub(crate) structure_entity: Entity,
+    /// The tile that the structure was removed from.
+    ///
+    /// For structures with a larger footprint, this may not be the center.
+    pub(crate) tile_pos: TilePos,
+}
+
+/// A ghost was added to the [`MapGeometry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct GhostAdded {
+    /// The ghost's entity.
+    pub(crate) ghost_entity: Entity,
+    /// The center of the ghost's footprint.
+    pub(crate) center: TilePos,
+}
+
+/// A ghost was removed from the [`MapGeometry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct GhostRemoved {
+    /// The ghost's entity.
+    pub(crate) ghost_entity: Entity,
+    /// The tile that the ghost was removed from.
+    ///
+    /// For ghosts with a larger footprint, this may not be the center.
+    pub(crate) tile_pos: TilePos,
+}
+
+/// The height of a tile in the [`MapGeometry`] was changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HeightChanged {
+    /// The tile whose height changed.
+    pub(crate) tile_pos: TilePos,
+    /// The previous height of the tile, if it had one.
+    pub(crate) old_height: Option<Height>,
+    /// The new height of the tile.
+    pub(crate) new_height: Height,
+}
+
+/// A change made to the [`MapGeometry`], which has not been sent as an event yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MapGeometryEvent {
+    /// See [`StructureAdded`].
+    StructureAdded(StructureAdded),
+    /// See [`StructureRemoved`].
+    StructureRemoved(StructureRemoved),
+    /// See [`GhostAdded`].
+    GhostAdded(GhostAdded),
+    /// See [`GhostRemoved`].
+    GhostRemoved(GhostRemoved),
+    /// See [`HeightChanged`].
+    HeightChanged(HeightChanged),
+}
+
+/// Sends an event for each change recorded by the [`MapGeometry`] since this system last ran.
+fn send_map_geometry_events(
+    mut map_geometry: ResMut<MapGeometry>,
+    mut structure_added: EventWriter<StructureAdded>,
+    mut structure_removed: EventWriter<StructureRemoved>,
+    mut ghost_added: EventWriter<GhostAdded>,
+    mut ghost_removed: EventWriter<GhostRemoved>,
+    mut height_changed: EventWriter<HeightChanged>,
+) {
+    // Publishing the changes doesn't change the map itself
+    for event in map_geometry.bypass_change_detection().take_events() {
+        match event {
+            MapGeometryEvent::StructureAdded(event) => structure_added.send(event),
+            MapGeometryEvent::StructureRemoved(event) => structure_removed.send(event),
+            MapGeometryEvent::GhostAdded(event) => ghost_added.send(event),
+            MapGeometryEvent::GhostRemoved(event) => ghost_removed.send(event),
+            MapGeometryEvent::HeightChanged(event) => height_changed.send(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structures::construction::Footprint;
+
+    fn read_events<E: Event + Clone>(app: &mut App) -> Vec<E> {
+        let mut reader = app.world.resource::<Events<E>>().get_reader();
+        reader
+            .iter(app.world.resource::<Events<E>>())
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn mutating_the_map_sends_events() {
+        let mut app = App::new();
+        app.insert_resource(MapGeometry::new(2))
+            .add_plugin(MapEventsPlugin);
+
+        let structure_entity = app.world.spawn_empty().id();
+        let ghost_entity = app.world.spawn_empty().id();
+        let structure_id = Id::from_name("hatchery");
+        let tile_pos = TilePos::new(1, 0);
+
+        let mut map_geometry = app.world.resource_mut::<MapGeometry>();
+        map_geometry.add_structure(
+            TilePos::ZERO,
+            &Footprint::single(),
+            structure_id,
+            structure_entity,
+        );
+        map_geometry.add_ghost(tile_pos, &Footprint::single(), ghost_entity);
+        map_geometry.update_height(tile_pos, Height(1));
+        // Heights that don't change aren't reported
+        map_geometry.update_height(tile_pos, Height(1));
+        map_geometry.update_height(tile_pos, Height(2));
+
+        // Nothing is sent until the events are published
+        assert!(read_events::<StructureAdded>(&mut app).is_empty());
+        app.update();
+
+        assert_eq!(
+            read_events::<StructureAdded>(&mut app),
+            vec![StructureAdded {
+                structure_entity,
+                structure_id,
+                center: TilePos::ZERO,
+            }]
+        );
+        assert_eq!(
+            read_events::<GhostAdded>(&mut app),
+            vec![GhostAdded {
+                ghost_entity,
+                center: tile_pos,
+            }]
+        );
+        assert_eq!(
+            read_events::<HeightChanged>(&mut app),
+            vec![
+                HeightChanged {
+                    tile_pos,
+                    old_height: None,
+                    new_height: Height(1),
+                },
+                HeightChanged {
+                    tile_pos,
+                    old_height: Some(Height(1)),
+                    new_height: Height(2),
+                }
+            ]
+        );
+
+        let mut map_geometry = app.world.resource_mut::<MapGeometry>();
+        map_geometry.remove_structure(TilePos::ZERO);
+        map_geometry.remove_ghost(tile_pos);
+        // Removing from an empty tile does nothing
+        map_geometry.remove_structure(tile_pos);
+        app.update();
+
+        assert_eq!(
+            read_events::<StructureRemoved>(&mut app),
+            vec![StructureRemoved {
+                structure_entity,
+                tile_pos: TilePos::ZERO,
+            }]
+        );
+        assert_eq!(
+            read_events::<GhostRemoved>(&mut app),
+            vec![GhostRemoved {
+                ghost_entity,
+                tile_pos,
+            }]
+        );
+    }
+}
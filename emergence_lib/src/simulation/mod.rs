@@ -5,18 +5,25 @@
 use crate::asset_management::AssetState;
 use crate::organisms::OrganismPlugin;
 use crate::signals::SignalsPlugin;
+use crate::simulation::event_log::EventLogPlugin;
 use crate::simulation::generation::{GenerationConfig, GenerationPlugin};
 use crate::simulation::geometry::{sync_rotation_to_facing, MapGeometry};
 use crate::simulation::light::LightPlugin;
-use crate::simulation::time::TemporalPlugin;
+use crate::simulation::map_events::MapEventsPlugin;
+use crate::simulation::naming::NamingPlugin;
+use crate::simulation::time::{simulation_is_running, TemporalPlugin};
 use crate::structures::StructuresPlugin;
 use crate::terrain::TerrainPlugin;
 use crate::units::UnitsPlugin;
 use bevy::prelude::*;
 
+pub mod event_log;
+pub mod factions;
 pub mod generation;
 pub mod geometry;
 pub mod light;
+pub mod map_events;
+pub mod naming;
 pub mod time;
 
 /// Sets up world geometry
@@ -27,7 +34,8 @@ pub struct GeometryPlugin {
 
 impl Plugin for GeometryPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(MapGeometry::new(self.gen_config.map_radius));
+        app.insert_resource(MapGeometry::new(self.gen_config.map_radius))
+            .add_plugin(MapEventsPlugin);
     }
 }
 
@@ -41,12 +49,11 @@ impl Plugin for SimulationPlugin {
     fn build(&self, app: &mut App) {
         info!("Building simulation plugin...");
         app.add_system(sync_rotation_to_facing)
-            .add_state::<PauseState>()
             .insert_resource(FixedTime::new_from_secs(1.0 / 30.))
             .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
                 schedule.configure_set(
                     SimulationSet
-                        .run_if(in_state(PauseState::Playing))
+                        .run_if(simulation_is_running)
                         .run_if(in_state(AssetState::Ready)),
                 );
             })
@@ -59,25 +66,17 @@ impl Plugin for SimulationPlugin {
             .add_plugin(UnitsPlugin)
             .add_plugin(SignalsPlugin)
             .add_plugin(TemporalPlugin)
-            .add_plugin(LightPlugin);
+            .add_plugin(LightPlugin)
+            .add_plugin(NamingPlugin)
+            .add_plugin(EventLogPlugin);
     }
 }
 
-/// Controls whether or not the game is paused.
-#[derive(States, Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
-enum PauseState {
-    /// Game logic is running.
-    #[default]
-    Playing,
-    /// Game logic is stopped.
-    Paused,
-}
-
 /// Simulation systems.
 ///
 /// These:
 /// - are run in [`CoreSchedule::FixedUpdate`]
-/// - only run in [`PauseState::Playing`]
+/// - only run when the simulation is not paused, as controlled by [`SimulationSpeed`](time::SimulationSpeed)
 /// - only run in [`AssetState::Ready`]
 #[derive(SystemSet, PartialEq, Eq, Hash, Debug, Clone)]
 pub(crate) struct SimulationSet;
@@ -0,0 +1,192 @@
+//! Stable, human-readable names for specific units and structures.
+//!
+//! These are intended for scenarios and scripting, where a particular entity (like `main_hive`)
+//! needs to be referenced reliably across ticks.
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+/// Maintains the [`NameIndex`].
+pub(super) struct NamingPlugin;
+
+impl Plugin for NamingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NameIndex>()
+            .add_system(update_name_index.in_base_set(CoreSet::PostUpdate));
+    }
+}
+
+/// A stable name or tag attached to a specific entity.
+///
+/// Look up entities by name using [`find_by_name`].
+#[derive(Component, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Named(pub String);
+
+/// A lookup table from [`Named`] to the corresponding entities.
+///
+/// Names are not required to be unique.
+/// When multiple entities share a name, they are stored in the order that they were named.
+#[derive(Resource, Debug, Default)]
+pub struct NameIndex {
+    /// The entities with each name, in the order that they were named.
+    entities: HashMap<String, Vec<Entity>>,
+    /// The current name of each named entity.
+    ///
+    /// This is needed to clean up the index when the [`Named`] component is removed.
+    names: HashMap<Entity, String>,
+}
+
+impl NameIndex {
+    /// Returns the first entity that was given the name `name`, if any.
+    pub fn get(&self, name: &str) -> Option<Entity> {
+        self.entities.get(name)?.first().copied()
+    }
+
+    /// Returns all entities with the name `name`, in the order that they were named.
+    pub fn get_all(&self, name: &str) -> &[Entity] {
+        match self.entities.get(name) {
+            Some(entities) => entities,
+            None => &[],
+        }
+    }
+
+    /// Records that `entity` is now called `name`, replacing any previous name.
+    ///
+    /// Has no effect if `entity` already has this name, so it keeps its place among entities that share it.
+    fn insert(&mut self, entity: Entity, name: &str) {
+        if self.names.get(&entity).map(String::as_str) == Some(name) {
+            return;
+        }
+
+        self.remove(entity);
+        self.entities
+            .entry(name.to_string())
+            .or_default()
+            .push(entity);
+        self.names.insert(entity, name.to_string());
+    }
+
+    /// Removes `entity` from the index.
+    fn remove(&mut self, entity: Entity) {
+        let Some(old_name) = self.names.remove(&entity) else {
+            return;
+        };
+
+        if let Some(entities) = self.entities.get_mut(&old_name) {
+            entities.retain(|&e| e != entity);
+            if entities.is_empty() {
+                self.entities.remove(&old_name);
+            }
+        }
+    }
+}
+
+/// Finds the entity with the provided `name`.
+///
+/// If multiple entities share the same name, the entity that was named first is returned.
+/// Use [`NameIndex::get_all`] to access all of them.
+///
+/// Entities are added to the index in [`CoreSet::PostUpdate`],
+/// so newly named entities cannot be found until the app has been updated.
+pub fn find_by_name(world: &World, name: &str) -> Option<Entity> {
+    world.get_resource::<NameIndex>()?.get(name)
+}
+
+/// Keeps the [`NameIndex`] in sync with the [`Named`] components in the world.
+fn update_name_index(
+    mut name_index: ResMut<NameIndex>,
+    named_query: Query<(Entity, &Named), Changed<Named>>,
+    mut removed_names: RemovedComponents<Named>,
+) {
+    for entity in removed_names.iter() {
+        name_index.remove(entity);
+    }
+
+    for (entity, named) in named_query.iter() {
+        name_index.insert(entity, &named.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asset_management::manifest::Id, simulation::geometry::TilePos,
+        structures::structure_manifest::Structure,
+    };
+
+    /// An app containing only the [`NamingPlugin`].
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugin(NamingPlugin);
+        app
+    }
+
+    #[test]
+    fn can_find_named_structure() {
+        let mut app = app();
+        let hive = app
+            .world
+            .spawn((
+                TilePos::new(1, 2),
+                Id::<Structure>::from_name("ant_hive"),
+                Named("main_hive".to_string()),
+            ))
+            .id();
+        app.update();
+
+        assert_eq!(find_by_name(&app.world, "main_hive"), Some(hive));
+        assert_eq!(find_by_name(&app.world, "tutorial_ant"), None);
+    }
+
+    #[test]
+    fn duplicate_names_return_first() {
+        let mut app = app();
+        let first = app.world.spawn(Named("ant".to_string())).id();
+        app.update();
+        let second = app.world.spawn(Named("ant".to_string())).id();
+        app.update();
+
+        assert_eq!(find_by_name(&app.world, "ant"), Some(first));
+        assert_eq!(
+            app.world.resource::<NameIndex>().get_all("ant"),
+            &[first, second]
+        );
+
+        app.world.despawn(first);
+        app.update();
+        assert_eq!(find_by_name(&app.world, "ant"), Some(second));
+    }
+
+    #[test]
+    fn renaming_updates_index() {
+        let mut app = app();
+        let entity = app.world.spawn(Named("old".to_string())).id();
+        app.update();
+
+        app.world.get_mut::<Named>(entity).unwrap().0 = "new".to_string();
+        app.update();
+
+        assert_eq!(find_by_name(&app.world, "old"), None);
+        assert_eq!(find_by_name(&app.world, "new"), Some(entity));
+    }
+
+    #[test]
+    fn touching_a_name_keeps_its_place() {
+        let mut app = app();
+        let first = app.world.spawn(Named("ant".to_string())).id();
+        app.update();
+        let second = app.world.spawn(Named("ant".to_string())).id();
+        app.update();
+
+        // Mutable access marks the component as changed, even though the name is the same
+        app.world.get_mut::<Named>(first).unwrap().0 = "ant".to_string();
+        app.update();
+
+        assert_eq!(find_by_name(&app.world, "ant"), Some(first));
+        assert_eq!(
+            app.world.resource::<NameIndex>().get_all("ant"),
+            &[first, second]
+        );
+    }
+}
@@ -15,7 +15,7 @@ use crate::graphics::lighting::CelestialBody;
 use crate::organisms::lifecycle::Lifecycle;
 use crate::player_interaction::PlayerAction;
 
-use super::{PauseState, SimulationSet};
+use super::SimulationSet;
 
 /// Introduces temporal variation into the environment.
 pub(super) struct TemporalPlugin;
@@ -25,6 +25,7 @@ impl Plugin for TemporalPlugin {
         app.add_systems(
             (
                 advance_in_game_time,
+                update_season,
                 move_celestial_bodies,
                 record_elapsed_time_for_lifecycles,
             )
@@ -32,8 +33,18 @@ impl Plugin for TemporalPlugin {
                 .in_set(SimulationSet)
                 .in_schedule(CoreSchedule::FixedUpdate),
         )
-        .add_system(pause_game)
-        .init_resource::<InGameTime>();
+        .add_system(
+            consume_simulation_steps
+                .after(SimulationSet)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(control_simulation_speed)
+        .add_system(fast_forward.in_base_set(CoreSet::PreUpdate))
+        .init_resource::<SimulationSpeed>()
+        .init_resource::<QueuedSteps>()
+        .init_resource::<InGameTime>()
+        .init_resource::<TimeOfDay>()
+        .init_resource::<Season>();
     }
 }
 
@@ -42,8 +53,64 @@ impl Plugin for TemporalPlugin {
 pub struct InGameTime {
     /// How much time has elapsed, in units of in-game days.
     elapsed_time: Days,
-    /// The number of wall-clock seconds that should elapse per complete in-game day.
-    seconds_per_day: f32,
+    /// The number of in-game days that each [`Season`] lasts.
+    days_per_season: f32,
+}
+
+/// How far through the current in-game day we are, and how long each day lasts.
+///
+/// Insert this resource before adding the [`SimulationPlugin`](super::SimulationPlugin) to change the length of each day.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct TimeOfDay {
+    /// The fraction of the current day that has elapsed, starting from 0.0 at dawn.
+    fraction_of_day: f32,
+    /// The number of seconds of simulated time that make up each in-game day.
+    day_length: f32,
+}
+
+impl TimeOfDay {
+    /// The number of seconds of simulated time in each in-game day, unless otherwise specified.
+    pub const DEFAULT_DAY_LENGTH: f32 = 60.;
+
+    /// Creates a new [`TimeOfDay`], starting at dawn.
+    ///
+    /// Each in-game day will take `day_length` seconds of simulated time.
+    pub fn new(day_length: f32) -> Self {
+        assert!(day_length > 0.);
+
+        TimeOfDay {
+            fraction_of_day: 0.,
+            day_length,
+        }
+    }
+
+    /// The number of seconds of simulated time that make up each in-game day.
+    pub fn day_length(&self) -> f32 {
+        self.day_length
+    }
+
+    /// How far are we through the day?
+    ///
+    /// See [`InGameTime::fraction_of_day`] for how to interpret this value.
+    pub fn fraction_of_day(&self) -> f32 {
+        self.fraction_of_day
+    }
+
+    /// Is the sun up?
+    pub fn is_daytime(&self) -> bool {
+        self.fraction_of_day < 0.5
+    }
+
+    /// Converts a duration of simulated time into in-game days.
+    fn to_days(self, duration: std::time::Duration) -> Days {
+        Days(duration.as_secs_f32() / self.day_length)
+    }
+}
+
+impl Default for TimeOfDay {
+    fn default() -> Self {
+        TimeOfDay::new(TimeOfDay::DEFAULT_DAY_LENGTH)
+    }
 }
 
 /// A duration of time, in in-game days.
@@ -79,6 +146,27 @@ impl Mul<f32> for Days {
 }
 
 impl InGameTime {
+    /// Creates a new [`InGameTime`], starting at dawn on the first day.
+    ///
+    /// The length of each day is controlled by the [`TimeOfDay`].
+    pub fn new() -> Self {
+        InGameTime {
+            elapsed_time: Days(0.0),
+            days_per_season: InGameTime::DEFAULT_DAYS_PER_SEASON,
+        }
+    }
+
+    /// The number of in-game days that each [`Season`] lasts, unless otherwise specified.
+    pub const DEFAULT_DAYS_PER_SEASON: f32 = 7.;
+
+    /// Sets the number of in-game days that each [`Season`] lasts.
+    pub fn with_days_per_season(mut self, days_per_season: f32) -> Self {
+        assert!(days_per_season > 0.);
+
+        self.days_per_season = days_per_season;
+        self
+    }
+
     /// How many days have elapsed total?
     pub fn elapsed_days(&self) -> u64 {
         self.elapsed_time.0.floor() as u64
@@ -95,6 +183,14 @@ impl InGameTime {
         self.elapsed_time.0 % 1.0
     }
 
+    /// Which season is it?
+    ///
+    /// The year starts with [`Season::Spring`], and each season lasts for the same number of days.
+    pub fn season(&self) -> Season {
+        let seasons_elapsed = (self.elapsed_time.0 / self.days_per_season).floor() as usize;
+        Season::CYCLE[seasons_elapsed % Season::CYCLE.len()]
+    }
+
     /// What time is it, in 24 hour time?
     pub fn twenty_four_hour_time(&self) -> f32 {
         // Correct for different time systems: fraction of day begins at dawn,
@@ -107,30 +203,85 @@ impl Display for InGameTime {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{} days elapsed\n{:.2}h",
+            "{} days elapsed\n{:.2}h\n{}",
             self.elapsed_days(),
-            self.twenty_four_hour_time()
+            self.twenty_four_hour_time(),
+            self.season()
         )
     }
 }
 
 impl Default for InGameTime {
     fn default() -> Self {
-        InGameTime {
-            elapsed_time: Days(0.0),
-            seconds_per_day: 60.,
-        }
+        InGameTime::new()
+    }
+}
+
+/// The long-term cycle of the in-game year, which modulates how quickly some recipes are crafted.
+///
+/// This resource is kept in sync with [`InGameTime::season`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Season {
+    /// The first season of the year.
+    #[default]
+    Spring,
+    /// The warmest season of the year.
+    Summer,
+    /// The season between summer and winter.
+    Autumn,
+    /// The coldest season of the year.
+    Winter,
+}
+
+impl Season {
+    /// Each of the seasons, in the order that they occur.
+    pub const CYCLE: [Season; 4] = [
+        Season::Spring,
+        Season::Summer,
+        Season::Autumn,
+        Season::Winter,
+    ];
+}
+
+impl Display for Season {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Season::Spring => "Spring",
+            Season::Summer => "Summer",
+            Season::Autumn => "Autumn",
+            Season::Winter => "Winter",
+        };
+
+        write!(f, "{name}")
     }
 }
 
 /// Advances the in game time based on elapsed clock time when the game is not paused.
-fn advance_in_game_time(time: Res<FixedTime>, mut in_game_time: ResMut<InGameTime>) {
-    let delta = Days(time.period.as_secs_f32() / in_game_time.seconds_per_day);
-    in_game_time.elapsed_time += delta;
+///
+/// The [`TimeOfDay`] is kept in sync with the [`InGameTime`].
+fn advance_in_game_time(
+    time: Res<FixedTime>,
+    mut in_game_time: ResMut<InGameTime>,
+    mut time_of_day: ResMut<TimeOfDay>,
+) {
+    in_game_time.elapsed_time += time_of_day.to_days(time.period);
+    time_of_day.fraction_of_day = in_game_time.fraction_of_day();
+}
+
+/// Changes the [`Season`] once enough in-game time has elapsed.
+fn update_season(in_game_time: Res<InGameTime>, mut season: ResMut<Season>) {
+    let current_season = in_game_time.season();
+    // Avoid triggering change detection every tick
+    if *season != current_season {
+        *season = current_season;
+    }
 }
 
 /// Moves the sun and moon based on the in-game time
-fn move_celestial_bodies(mut query: Query<&mut CelestialBody>, in_game_time: Res<InGameTime>) {
+pub(super) fn move_celestial_bodies(
+    mut query: Query<&mut CelestialBody>,
+    in_game_time: Res<InGameTime>,
+) {
     for mut celestial_body in query.iter_mut() {
         // Take the modulo with respect to the period to get the revolution period correct
         let cycle_normalized_time = (in_game_time.elapsed_time.0 % celestial_body.days_per_cycle)
@@ -142,17 +293,123 @@ fn move_celestial_bodies(mut query: Query<&mut CelestialBody>, in_game_time: Res
     }
 }
 
-/// Pauses and unpauses the game when prompted by player input
-fn pause_game(
-    current_pause_state: Res<State<PauseState>>,
-    mut next_pause_state: ResMut<NextState<PauseState>>,
+/// How quickly the simulation runs, relative to wall-clock time.
+///
+/// Speeding up the simulation runs more fixed steps each frame, rather than lengthening each step,
+/// so that timers and rates behave identically at every speed.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum SimulationSpeed {
+    /// The simulation is stopped, but the camera and UI are still responsive.
+    Paused,
+    /// The simulation runs in real time.
+    #[default]
+    Normal,
+    /// The simulation runs twice as fast as normal.
+    Double,
+    /// The simulation runs four times as fast as normal.
+    Quadruple,
+}
+
+impl SimulationSpeed {
+    /// The number of simulated seconds that elapse per second of wall-clock time.
+    pub(crate) fn multiplier(&self) -> u32 {
+        match self {
+            SimulationSpeed::Paused => 0,
+            SimulationSpeed::Normal => 1,
+            SimulationSpeed::Double => 2,
+            SimulationSpeed::Quadruple => 4,
+        }
+    }
+
+    /// The next faster speed, if any.
+    ///
+    /// Speeding up a paused simulation resumes it at normal speed.
+    fn faster(&self) -> Self {
+        match self {
+            SimulationSpeed::Paused => SimulationSpeed::Normal,
+            SimulationSpeed::Normal => SimulationSpeed::Double,
+            SimulationSpeed::Double | SimulationSpeed::Quadruple => SimulationSpeed::Quadruple,
+        }
+    }
+
+    /// The next slower speed, without pausing the simulation.
+    fn slower(&self) -> Self {
+        match self {
+            SimulationSpeed::Paused => SimulationSpeed::Paused,
+            SimulationSpeed::Normal | SimulationSpeed::Double => SimulationSpeed::Normal,
+            SimulationSpeed::Quadruple => SimulationSpeed::Double,
+        }
+    }
+}
+
+/// The number of single simulation steps that the player has requested while the game is paused.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct QueuedSteps(pub(crate) u32);
+
+/// Should the systems in [`SimulationSet`] run during this fixed step?
+pub(crate) fn simulation_is_running(
+    simulation_speed: Res<SimulationSpeed>,
+    queued_steps: Res<QueuedSteps>,
+) -> bool {
+    *simulation_speed != SimulationSpeed::Paused || queued_steps.0 > 0
+}
+
+/// Pauses, unpauses, steps and changes the speed of the game when prompted by player input.
+///
+/// Unpausing resumes the simulation at the speed it was running at before it was paused.
+fn control_simulation_speed(
+    mut simulation_speed: ResMut<SimulationSpeed>,
+    mut queued_steps: ResMut<QueuedSteps>,
+    mut speed_before_pause: Local<SimulationSpeed>,
     player_actions: Res<ActionState<PlayerAction>>,
 ) {
     if player_actions.just_pressed(PlayerAction::TogglePause) {
-        next_pause_state.set(match current_pause_state.0 {
-            PauseState::Paused => PauseState::Playing,
-            PauseState::Playing => PauseState::Paused,
-        });
+        *simulation_speed = match *simulation_speed {
+            SimulationSpeed::Paused => *speed_before_pause,
+            unpaused_speed => {
+                *speed_before_pause = unpaused_speed;
+                SimulationSpeed::Paused
+            }
+        };
+    }
+
+    if player_actions.just_pressed(PlayerAction::IncreaseSimulationSpeed) {
+        *simulation_speed = simulation_speed.faster();
+    }
+
+    if player_actions.just_pressed(PlayerAction::DecreaseSimulationSpeed) {
+        *simulation_speed = simulation_speed.slower();
+    }
+
+    if player_actions.just_pressed(PlayerAction::StepSimulation)
+        && *simulation_speed == SimulationSpeed::Paused
+    {
+        queued_steps.0 += 1;
+    }
+}
+
+/// Feeds extra time into the fixed timestep when the simulation is sped up,
+/// causing [`CoreSchedule::FixedUpdate`] to run several times per frame.
+fn fast_forward(
+    simulation_speed: Res<SimulationSpeed>,
+    time: Res<Time>,
+    mut fixed_time: ResMut<FixedTime>,
+) {
+    let extra_steps = simulation_speed.multiplier().saturating_sub(1);
+    if extra_steps > 0 {
+        fixed_time.tick(time.delta() * extra_steps);
+    }
+}
+
+/// Uses up one of the [`QueuedSteps`] each time the simulation is stepped while paused.
+pub(crate) fn consume_simulation_steps(
+    simulation_speed: Res<SimulationSpeed>,
+    mut queued_steps: ResMut<QueuedSteps>,
+) {
+    if *simulation_speed == SimulationSpeed::Paused {
+        queued_steps.0 = queued_steps.0.saturating_sub(1);
+    } else {
+        queued_steps.0 = 0;
     }
 }
 
@@ -232,11 +489,107 @@ impl Pool for TimePool {
 /// Advances life cycles accorded to elapsed in-game time
 fn record_elapsed_time_for_lifecycles(
     mut query: Query<&mut Lifecycle>,
-    in_game_time: Res<InGameTime>,
+    time_of_day: Res<TimeOfDay>,
     fixed_time: Res<FixedTime>,
 ) {
+    let delta_days = time_of_day.to_days(fixed_time.period);
     for mut lifecycle in query.iter_mut() {
-        let delta_days = Days(fixed_time.period.as_secs_f32() / in_game_time.seconds_per_day);
         lifecycle.record_elapsed_time(delta_days);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        items::recipe::{RecipeConditions, Threshold},
+        simulation::light::{compute_light, Illuminance, TotalLight},
+    };
+
+    #[test]
+    fn light_gated_recipes_only_run_during_the_day() {
+        /// The number of simulation steps in each in-game day.
+        const STEPS_PER_DAY: usize = 100;
+
+        let mut app = App::new();
+        app.insert_resource(FixedTime::new_from_secs(1.))
+            .init_resource::<InGameTime>()
+            .insert_resource(TimeOfDay::new(STEPS_PER_DAY as f32))
+            .init_resource::<TotalLight>()
+            .add_systems((advance_in_game_time, move_celestial_bodies, compute_light).chain());
+        app.world.spawn(CelestialBody::sun());
+
+        let photosynthesis = RecipeConditions::new(
+            0,
+            Threshold::new(Illuminance(1e4), Illuminance(f32::INFINITY)),
+        );
+
+        let mut daytime_steps = 0;
+        for _ in 0..STEPS_PER_DAY {
+            app.update();
+
+            let time_of_day = app.world.resource::<TimeOfDay>();
+            let total_light = app.world.resource::<TotalLight>();
+
+            if photosynthesis.satisfied(0, total_light, None) {
+                daytime_steps += 1;
+                // The sun rises at 0.0 and sets at 0.5
+                assert!(time_of_day.is_daytime());
+                assert!(time_of_day.fraction_of_day() > 0.);
+            }
+        }
+
+        // The recipe should run for some, but not all, of the day
+        assert!(daytime_steps > 0);
+        assert!(daytime_steps < STEPS_PER_DAY / 2);
+    }
+
+    #[test]
+    fn seasonal_recipes_change_speed_over_the_year() {
+        use crate::items::recipe::{RecipeData, SeasonalProfile};
+        use std::time::Duration;
+
+        let mut app = App::new();
+        // Each update is one in-game day, and each season lasts two days
+        app.insert_resource(FixedTime::new_from_secs(1.))
+            .insert_resource(InGameTime::new().with_days_per_season(2.))
+            .insert_resource(TimeOfDay::new(1.))
+            .init_resource::<Season>()
+            .add_systems((advance_in_game_time, update_season).chain());
+
+        let plant_growth = RecipeData {
+            craft_time: Duration::from_secs(1),
+            seasonal_profile: SeasonalProfile {
+                summer: 2.,
+                winter: 0.,
+                ..SeasonalProfile::UNIFORM
+            },
+            ..Default::default()
+        };
+
+        let mut observed = Vec::new();
+        for _ in 0..8 {
+            let season = *app.world.resource::<Season>();
+            observed.push((season, plant_growth.seasonal_work_speed(0, 0, season)));
+            app.update();
+        }
+
+        assert_eq!(
+            observed,
+            vec![
+                (Season::Spring, 1.),
+                (Season::Spring, 1.),
+                (Season::Summer, 2.),
+                (Season::Summer, 2.),
+                (Season::Autumn, 1.),
+                (Season::Autumn, 1.),
+                (Season::Winter, 0.),
+                (Season::Winter, 0.),
+            ]
+        );
+
+        // The year starts over after winter
+        app.update();
+        assert_eq!(*app.world.resource::<Season>(), Season::Spring);
+    }
+}
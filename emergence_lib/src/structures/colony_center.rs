@@ -0,0 +1,327 @@
+//! The colony center is the notional "home" of the hive mind, used by distance-based mechanics.
+
+use bevy::prelude::*;
+use hexx::{Direction, HexIterExt};
+use leafwing_abilities::prelude::Pool;
+use rand::rngs::ThreadRng;
+
+use crate::{
+    asset_management::manifest::Id,
+    organisms::energy::{Energy, EnergyPool},
+    simulation::{
+        geometry::{MapGeometry, TilePos},
+        SimulationSet,
+    },
+    units::{unit_manifest::Unit, WanderingBehavior},
+};
+
+use super::{
+    construction::{Ghost, Preview},
+    structure_manifest::Structure,
+};
+
+/// Tracks the [`ColonyCenter`] as the world changes.
+pub(super) struct ColonyCenterPlugin;
+
+impl Plugin for ColonyCenterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ColonyCenter>().add_systems(
+            (update_colony_center, tire_far_flung_units)
+                .chain()
+                .in_set(SimulationSet)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// The center of the colony, used by mechanics that care about how far something is from home.
+///
+/// By default, this tracks the designated hive structure.
+/// If that hive is destroyed, the next available hive is chosen.
+/// If there are no hives at all, this falls back to the centroid of all structures.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct ColonyCenter {
+    /// A position chosen by the player, which takes priority over any hive.
+    pub(crate) manual_center: Option<TilePos>,
+    /// The hive structure that the colony is centered on, if any.
+    designated_hive: Option<Entity>,
+    /// The current location of the colony center.
+    tile_pos: TilePos,
+}
+
+impl ColonyCenter {
+    /// The structure type that is treated as the hive.
+    const HIVE_STRUCTURE: &'static str = "ant_hive";
+
+    /// Idle units within this many tiles of the colony center try to spread out, rather than crowding around it.
+    const DISPERSAL_RADIUS: u32 = 4;
+
+    /// The probability that each wandering step of an idle unit near the colony center heads away from it.
+    const DISPERSAL_STRENGTH: f32 = 0.5;
+
+    /// Units can stray this many tiles from the colony center before they start to tire.
+    const HOME_RANGE: u32 = 15;
+
+    /// The energy lost per second, for each tile that a unit is beyond the [`ColonyCenter::HOME_RANGE`].
+    const FAR_FROM_HOME_ENERGY_COST: f32 = 0.1;
+
+    /// The current location of the colony center.
+    pub(crate) fn tile_pos(&self) -> TilePos {
+        self.tile_pos
+    }
+
+    /// The number of tiles between the colony center and `tile_pos`.
+    pub(crate) fn distance_to(&self, tile_pos: TilePos) -> u32 {
+        self.tile_pos.unsigned_distance_to(tile_pos.hex)
+    }
+
+    /// The direction that an idle unit at `tile_pos` should wander in to spread out from the colony center.
+    ///
+    /// Returns [`None`] if the unit is far enough away already, its way outward is blocked,
+    /// or it should wander randomly this step.
+    pub(crate) fn dispersal_direction(
+        &self,
+        tile_pos: TilePos,
+        map_geometry: &MapGeometry,
+        rng: &mut ThreadRng,
+    ) -> Option<Direction> {
+        if self.distance_to(tile_pos) > ColonyCenter::DISPERSAL_RADIUS {
+            return None;
+        }
+
+        // Dispersal is just wandering with an outward bias from the colony center
+        let outward = WanderingBehavior::from_iter([])
+            .with_bias(self.tile_pos, ColonyCenter::DISPERSAL_STRENGTH)
+            .preferred_direction(tile_pos, rng)?;
+        map_geometry
+            .can_walk_between(tile_pos, tile_pos.neighbor(outward))
+            .then_some(outward)
+    }
+
+    /// The energy per second lost by a unit at `tile_pos`, due to how far it has strayed from home.
+    pub(crate) fn far_from_home_energy_cost(&self, tile_pos: TilePos) -> Energy {
+        let excess_distance = self
+            .distance_to(tile_pos)
+            .saturating_sub(ColonyCenter::HOME_RANGE);
+        Energy(excess_distance as f32 * ColonyCenter::FAR_FROM_HOME_ENERGY_COST)
+    }
+}
+
+/// Keeps the [`ColonyCenter`] in sync with the location of the designated hive and other structures.
+fn update_colony_center(
+    mut colony_center: ResMut<ColonyCenter>,
+    structure_query: Query<(Entity, &TilePos, &Id<Structure>), (Without<Ghost>, Without<Preview>)>,
+) {
+    let hive_id: Id<Structure> = Id::from_name(ColonyCenter::HIVE_STRUCTURE);
+
+    // Drop the designation if the hive no longer exists
+    if let Some(hive_entity) = colony_center.designated_hive {
+        if structure_query.get(hive_entity).is_err() {
+            colony_center.designated_hive = None;
+        }
+    }
+
+    if colony_center.designated_hive.is_none() {
+        colony_center.designated_hive = structure_query
+            .iter()
+            .find(|(.., &structure_id)| structure_id == hive_id)
+            .map(|(entity, ..)| entity);
+    }
+
+    let new_tile_pos = if let Some(manual_center) = colony_center.manual_center {
+        manual_center
+    } else if let Some(hive_entity) = colony_center.designated_hive {
+        *structure_query.get(hive_entity).unwrap().1
+    } else if structure_query.is_empty() {
+        TilePos::default()
+    } else {
+        TilePos {
+            hex: structure_query
+                .iter()
+                .map(|(_, tile_pos, _)| tile_pos.hex)
+                .center(),
+        }
+    };
+
+    // Avoid triggering change detection every tick
+    if colony_center.tile_pos != new_tile_pos {
+        colony_center.tile_pos = new_tile_pos;
+    }
+}
+
+/// Drains the energy of units that have strayed too far from the [`ColonyCenter`].
+fn tire_far_flung_units(
+    mut unit_query: Query<(&TilePos, &mut EnergyPool), With<Id<Unit>>>,
+    colony_center: Res<ColonyCenter>,
+    fixed_time: Res<FixedTime>,
+) {
+    let delta = fixed_time.period.as_secs_f32();
+
+    for (&tile_pos, mut energy_pool) in unit_query.iter_mut() {
+        let cost = colony_center.far_from_home_energy_cost(tile_pos) * delta;
+        if cost > Energy(0.) {
+            let current = energy_pool.current();
+            energy_pool.set_current(current - cost);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs [`update_colony_center`] once in a bare app.
+    fn update(app: &mut App) {
+        app.add_system(update_colony_center);
+        app.update();
+    }
+
+    #[test]
+    fn colony_center_tracks_hive() {
+        let mut app = App::new();
+        app.init_resource::<ColonyCenter>();
+        app.world
+            .spawn((TilePos::new(0, 0), Id::<Structure>::from_name("acacia")));
+        let hive_pos = TilePos::new(3, -1);
+        let hive = app
+            .world
+            .spawn((hive_pos, Id::<Structure>::from_name("ant_hive")))
+            .id();
+
+        update(&mut app);
+
+        let colony_center = app.world.resource::<ColonyCenter>();
+        assert_eq!(colony_center.designated_hive, Some(hive));
+        assert_eq!(colony_center.tile_pos(), hive_pos);
+    }
+
+    #[test]
+    fn destroying_hive_falls_back_to_structure_centroid() {
+        let mut app = App::new();
+        app.init_resource::<ColonyCenter>();
+        app.add_system(update_colony_center);
+        app.world
+            .spawn((TilePos::new(0, 0), Id::<Structure>::from_name("acacia")));
+        app.world
+            .spawn((TilePos::new(4, 0), Id::<Structure>::from_name("leuco")));
+        let hive = app
+            .world
+            .spawn((TilePos::new(-5, 5), Id::<Structure>::from_name("ant_hive")))
+            .id();
+
+        app.update();
+        assert_eq!(
+            app.world.resource::<ColonyCenter>().tile_pos(),
+            TilePos::new(-5, 5)
+        );
+
+        app.world.despawn(hive);
+        app.update();
+
+        let colony_center = app.world.resource::<ColonyCenter>();
+        assert_eq!(colony_center.designated_hive, None);
+        assert_eq!(colony_center.tile_pos(), TilePos::new(2, 0));
+    }
+
+    #[test]
+    fn idle_units_disperse_away_from_the_colony_center() {
+        use hexx::{shapes::hexagon, Hex};
+
+        use crate::simulation::geometry::Height;
+
+        let rng = &mut rand::thread_rng();
+        let colony_center = ColonyCenter::default();
+        let mut map_geometry = MapGeometry::new(ColonyCenter::DISPERSAL_RADIUS + 2);
+        let mut world = World::new();
+        for hex in hexagon(Hex::ZERO, map_geometry.radius) {
+            let terrain_entity = world.spawn_empty().id();
+            map_geometry.add_terrain(TilePos { hex }, terrain_entity);
+            map_geometry.update_height(TilePos { hex }, Height(1));
+        }
+
+        let nearby = TilePos::new(2, 0);
+        let outward = colony_center.tile_pos().direction_to(nearby.hex);
+
+        let directions: Vec<Option<Direction>> = (0..100)
+            .map(|_| colony_center.dispersal_direction(nearby, &map_geometry, rng))
+            .collect();
+        assert!(directions.contains(&Some(outward)));
+        assert!(directions
+            .iter()
+            .all(|&d| d.is_none() || d == Some(outward)));
+
+        // Units on the center or far from it wander freely
+        let far_away = TilePos::new(ColonyCenter::DISPERSAL_RADIUS as i32 + 1, 0);
+        for _ in 0..100 {
+            assert_eq!(
+                colony_center.dispersal_direction(TilePos::ZERO, &map_geometry, rng),
+                None
+            );
+            assert_eq!(
+                colony_center.dispersal_direction(far_away, &map_geometry, rng),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn units_far_from_home_lose_energy() {
+        use crate::units::{
+            goals::Goal,
+            test_fixture::{spawn_unit, UnitTestApp},
+        };
+
+        let ant = Id::<Unit>::from_name("ant");
+        let mut app = UnitTestApp::new(ColonyCenter::HOME_RANGE + 10)
+            .with_timestep(1.)
+            .build();
+        app.add_system(tire_far_flung_units);
+
+        let near = spawn_unit(
+            &mut app,
+            TilePos::new(ColonyCenter::HOME_RANGE as i32, 0),
+            ant,
+            Goal::default(),
+        );
+        let far = spawn_unit(
+            &mut app,
+            TilePos::new(ColonyCenter::HOME_RANGE as i32 + 10, 0),
+            ant,
+            Goal::default(),
+        );
+        for unit_entity in [near, far] {
+            app.world
+                .entity_mut(unit_entity)
+                .insert(EnergyPool::new_full(Energy(100.), Energy(0.)));
+        }
+
+        app.update();
+
+        assert_eq!(
+            app.world.get::<EnergyPool>(near).unwrap().current(),
+            Energy(100.)
+        );
+        assert_eq!(
+            app.world.get::<EnergyPool>(far).unwrap().current(),
+            Energy(100. - 10. * ColonyCenter::FAR_FROM_HOME_ENERGY_COST)
+        );
+    }
+
+    #[test]
+    fn manual_center_takes_priority() {
+        let mut app = App::new();
+        app.insert_resource(ColonyCenter {
+            manual_center: Some(TilePos::new(1, 2)),
+            ..Default::default()
+        });
+        app.world
+            .spawn((TilePos::new(-5, 5), Id::<Structure>::from_name("ant_hive")));
+
+        update(&mut app);
+
+        assert_eq!(
+            app.world.resource::<ColonyCenter>().tile_pos(),
+            TilePos::new(1, 2)
+        );
+    }
+}
@@ -10,28 +10,40 @@ use rand::{rngs::ThreadRng, seq::SliceRandom, thread_rng};
 use crate::{
     asset_management::manifest::Id,
     graphics::InheritedMaterial,
-    items::{item_manifest::ItemManifest, recipe::RecipeManifest},
+    items::{
+        errors::AddManyItemsError, inventory::ReservedFor, item_manifest::ItemManifest,
+        recipe::RecipeManifest, ItemCount,
+    },
     organisms::OrganismBundle,
     player_interaction::clipboard::ClipboardData,
     signals::Emitter,
-    simulation::geometry::{Facing, MapGeometry, TilePos},
+    simulation::{
+        factions::Faction,
+        geometry::{Facing, MapGeometry, TilePos},
+    },
     terrain::terrain_manifest::Terrain,
 };
 
 use super::{
     construction::{GhostBundle, GhostKind, PreviewBundle},
-    crafting::{CraftingBundle, StorageInventory},
+    conveyor::Conveyor,
+    crafting::{
+        CraftingBundle, InputInventory, OutputDirection, OutputInventory, StorageInventory,
+    },
+    item_pile::ItemPile,
+    power::{PowerConsumer, PowerGenerator},
     structure_assets::StructureHandles,
-    structure_manifest::{StructureKind, StructureManifest},
+    structure_manifest::{Structure, StructureKind, StructureManifest},
+    upgrades::MarkedForUpgrade,
     StructureBundle,
 };
 
 /// An extension trait for [`Commands`] for working with structures.
 pub(crate) trait StructureCommandsExt {
-    /// Spawns a structure defined by `data` at `tile_pos`.
+    /// Spawns a structure defined by `data` at `tile_pos` that belongs to `faction`.
     ///
     /// Has no effect if the tile position is already occupied by an existing structure.
-    fn spawn_structure(&mut self, tile_pos: TilePos, data: ClipboardData);
+    fn spawn_structure(&mut self, tile_pos: TilePos, data: ClipboardData, faction: Faction);
 
     /// Spawns a structure with randomized `data` at `tile_pos`.
     ///
@@ -49,16 +61,30 @@ pub(crate) trait StructureCommandsExt {
     /// Has no effect if the tile position is already empty.
     fn despawn_structure(&mut self, tile_pos: TilePos);
 
-    /// Spawns a ghost with data defined by `data` at `tile_pos`.
+    /// Replaces the structure at `tile_pos` with a new structure of type `upgrades_to`.
+    ///
+    /// The new structure keeps the facing of the original, and the items in its inventory.
+    /// Has no effect if the tile position is empty.
+    /// If the new structure would not fit, the original is kept and is no longer marked for upgrade.
+    fn upgrade_structure(&mut self, tile_pos: TilePos, upgrades_to: Id<Structure>);
+
+    /// Spawns a ghost with data defined by `data` at `tile_pos` that belongs to `faction`.
     ///
     /// Replaces any existing ghost.
-    fn spawn_ghost(&mut self, tile_pos: TilePos, data: ClipboardData);
+    /// The structure that the ghost becomes once built also belongs to `faction`.
+    fn spawn_ghost(&mut self, tile_pos: TilePos, data: ClipboardData, faction: Faction);
 
-    /// Despawns any ghost at the provided `tile_pos`.
+    /// Despawns any ghost at the provided `tile_pos`, cancelling its construction.
     ///
+    /// Any materials that were already delivered to the ghost are dropped in an [`ItemPile`] nearby.
     /// Has no effect if the tile position is already empty.
     fn despawn_ghost(&mut self, tile_pos: TilePos);
 
+    /// Despawns any ghost at the provided `tile_pos` once it has been built, consuming its materials.
+    ///
+    /// Has no effect if the tile position is already empty.
+    fn consume_ghost(&mut self, tile_pos: TilePos);
+
     /// Spawns a preview with data defined by `item` at `tile_pos`.
     ///
     /// Replaces any existing preview.
@@ -66,11 +92,12 @@ pub(crate) trait StructureCommandsExt {
 }
 
 impl<'w, 's> StructureCommandsExt for Commands<'w, 's> {
-    fn spawn_structure(&mut self, tile_pos: TilePos, data: ClipboardData) {
+    fn spawn_structure(&mut self, tile_pos: TilePos, data: ClipboardData, faction: Faction) {
         self.add(SpawnStructureCommand {
             tile_pos,
             data,
             randomized: false,
+            faction,
         });
     }
 
@@ -87,6 +114,7 @@ impl<'w, 's> StructureCommandsExt for Commands<'w, 's> {
             tile_pos,
             data,
             randomized: true,
+            faction: Faction::COLONY,
         });
     }
 
@@ -94,12 +122,33 @@ impl<'w, 's> StructureCommandsExt for Commands<'w, 's> {
         self.add(DespawnStructureCommand { tile_pos });
     }
 
-    fn spawn_ghost(&mut self, tile_pos: TilePos, data: ClipboardData) {
-        self.add(SpawnGhostCommand { tile_pos, data });
+    fn upgrade_structure(&mut self, tile_pos: TilePos, upgrades_to: Id<Structure>) {
+        self.add(UpgradeStructureCommand {
+            tile_pos,
+            upgrades_to,
+        });
+    }
+
+    fn spawn_ghost(&mut self, tile_pos: TilePos, data: ClipboardData, faction: Faction) {
+        self.add(SpawnGhostCommand {
+            tile_pos,
+            data,
+            faction,
+        });
     }
 
     fn despawn_ghost(&mut self, tile_pos: TilePos) {
-        self.add(DespawnGhostCommand { tile_pos });
+        self.add(DespawnGhostCommand {
+            tile_pos,
+            refund: true,
+        });
+    }
+
+    fn consume_ghost(&mut self, tile_pos: TilePos) {
+        self.add(DespawnGhostCommand {
+            tile_pos,
+            refund: false,
+        });
     }
 
     fn spawn_preview(&mut self, tile_pos: TilePos, data: ClipboardData) {
@@ -115,6 +164,8 @@ struct SpawnStructureCommand {
     data: ClipboardData,
     /// Should the generated structure be randomized
     randomized: bool,
+    /// The faction that the structure belongs to.
+    faction: Faction,
 }
 
 impl Command for SpawnStructureCommand {
@@ -126,6 +177,7 @@ impl Command for SpawnStructureCommand {
         }
 
         let structure_id = self.data.structure_id;
+        let reserved_for = self.data.reserved_for.clone();
 
         let mut system_state: SystemState<(
             Query<&Id<Terrain>>,
@@ -142,6 +194,7 @@ impl Command for SpawnStructureCommand {
             structure_variety.footprint.rotated(self.data.facing),
             &terrain_query,
             structure_variety.allowed_terrain_types(),
+            structure_variety.required_adjacency(),
         ) {
             return;
         }
@@ -163,6 +216,7 @@ impl Command for SpawnStructureCommand {
                 picking_mesh,
                 scene_handle,
                 world_pos,
+                self.faction,
             ))
             .id();
 
@@ -177,16 +231,32 @@ impl Command for SpawnStructureCommand {
         };
 
         match structure_variety.kind {
-            StructureKind::Storage {
+            StructureKind::Storage { max_slot_count, .. } => {
+                world
+                    .entity_mut(structure_entity)
+                    .insert(StorageInventory::new(max_slot_count, reserved_for))
+                    .insert(Emitter::default());
+            }
+            StructureKind::Conveyor {
+                direction,
                 max_slot_count,
-                reserved_for,
             } => {
                 world
                     .entity_mut(structure_entity)
-                    .insert(StorageInventory::new(max_slot_count, reserved_for))
+                    .insert(Conveyor::new(direction))
+                    .insert(StorageInventory::new(max_slot_count, ReservedFor::Any))
                     .insert(Emitter::default());
             }
-            StructureKind::Crafting { starting_recipe } => {
+            StructureKind::Crafting {
+                starting_recipe,
+                output_direction,
+            } => {
+                if let Some(direction) = output_direction {
+                    world
+                        .entity_mut(structure_entity)
+                        .insert(OutputDirection::new(direction));
+                }
+
                 world.resource_scope(|world, recipe_manifest: Mut<RecipeManifest>| {
                     world.resource_scope(|world, item_manifest: Mut<ItemManifest>| {
                         world.resource_scope(|world, structure_manifest: Mut<StructureManifest>| {
@@ -216,12 +286,34 @@ impl Command for SpawnStructureCommand {
                     })
                 })
             }
+            StructureKind::Bridge => {
+                world
+                    .resource_mut::<MapGeometry>()
+                    .add_bridge(self.tile_pos, &structure_variety.footprint);
+            }
+        }
+
+        world
+            .entity_mut(structure_entity)
+            .insert(structure_variety.health);
+
+        if structure_variety.power_supply > 0. {
+            world
+                .entity_mut(structure_entity)
+                .insert(PowerGenerator::new(structure_variety.power_supply));
+        }
+
+        if structure_variety.power_draw > 0. {
+            world
+                .entity_mut(structure_entity)
+                .insert(PowerConsumer::new(structure_variety.power_draw));
         }
 
         let mut geometry = world.resource_mut::<MapGeometry>();
         geometry.add_structure(
             self.tile_pos,
             &structure_variety.footprint,
+            structure_id,
             structure_entity,
         );
     }
@@ -249,12 +341,132 @@ impl Command for DespawnStructureCommand {
     }
 }
 
+/// A [`Command`] used to upgrade a structure via [`StructureCommandsExt`].
+struct UpgradeStructureCommand {
+    /// The tile position at which the structure to be upgraded is found.
+    tile_pos: TilePos,
+    /// The type of structure to replace it with.
+    upgrades_to: Id<Structure>,
+}
+
+impl Command for UpgradeStructureCommand {
+    fn write(self, world: &mut World) {
+        let geometry = world.resource::<MapGeometry>();
+        let Some(structure_entity) = geometry.get_structure(self.tile_pos) else {
+            return;
+        };
+
+        let structure = world.entity(structure_entity);
+        let Some(&facing) = structure.get::<Facing>() else {
+            return;
+        };
+        let faction = structure.get::<Faction>().copied().unwrap_or_default();
+
+        // Carry over everything that was stored or produced here
+        let maybe_storage_inventory = structure.get::<StorageInventory>();
+        let items: Vec<ItemCount> = maybe_storage_inventory
+            .into_iter()
+            .flat_map(|storage_inventory| storage_inventory.iter())
+            .chain(
+                structure
+                    .get::<OutputInventory>()
+                    .into_iter()
+                    .flat_map(|output_inventory| output_inventory.iter()),
+            )
+            .filter(|item_slot| !item_slot.is_empty())
+            .map(|item_slot| ItemCount::new(item_slot.item_id(), item_slot.count()))
+            .collect();
+
+        let mut data = ClipboardData::new(self.upgrades_to, world.resource::<StructureManifest>());
+        data.facing = facing;
+        if let Some(storage_inventory) = maybe_storage_inventory {
+            if *storage_inventory.reserved_for() != ReservedFor::Any {
+                data.reserved_for = storage_inventory.reserved_for().clone();
+            }
+        }
+
+        let mut system_state: SystemState<(
+            Query<&Id<Terrain>>,
+            Res<MapGeometry>,
+            Res<StructureManifest>,
+        )> = SystemState::new(world);
+
+        let (terrain_query, geometry, manifest) = system_state.get(world);
+        let upgraded_variety = manifest.get(self.upgrades_to);
+
+        // Check that the upgraded structure fits before the original is removed, so nothing is lost
+        if !geometry.can_replace_structure(
+            self.tile_pos,
+            upgraded_variety.footprint.rotated(facing),
+            structure_entity,
+            &terrain_query,
+            upgraded_variety.allowed_terrain_types(),
+            upgraded_variety.required_adjacency(),
+        ) {
+            warn!(
+                "Could not upgrade the structure at {:?}: the upgraded structure does not fit.",
+                self.tile_pos
+            );
+            // Stop retrying, so that the player can decide what to do instead
+            world
+                .entity_mut(structure_entity)
+                .remove::<MarkedForUpgrade>();
+            return;
+        }
+
+        DespawnStructureCommand {
+            tile_pos: self.tile_pos,
+        }
+        .write(world);
+        SpawnStructureCommand {
+            tile_pos: self.tile_pos,
+            data,
+            randomized: false,
+            faction,
+        }
+        .write(world);
+
+        let geometry = world.resource::<MapGeometry>();
+        let Some(upgraded_entity) = geometry.get_structure(self.tile_pos) else {
+            return;
+        };
+
+        world.resource_scope(|world, item_manifest: Mut<ItemManifest>| {
+            let mut upgraded_structure = world.entity_mut(upgraded_entity);
+            let result = if let Some(mut storage_inventory) =
+                upgraded_structure.get_mut::<StorageInventory>()
+            {
+                storage_inventory.try_add_items(&items, &item_manifest)
+            } else if let Some(mut output_inventory) =
+                upgraded_structure.get_mut::<OutputInventory>()
+            {
+                output_inventory.try_add_items(&items, &item_manifest)
+            } else {
+                Err(AddManyItemsError {
+                    excess_counts: items,
+                })
+            };
+
+            if let Err(AddManyItemsError { excess_counts }) = result {
+                if !excess_counts.is_empty() {
+                    warn!(
+                        "{excess_counts:?} were lost when upgrading the structure at {:?}.",
+                        self.tile_pos
+                    );
+                }
+            }
+        });
+    }
+}
+
 /// A [`Command`] used to spawn a ghost via [`StructureCommandsExt`].
 struct SpawnGhostCommand {
     /// The tile position at which to spawn the structure.
     tile_pos: TilePos,
     /// Data about the structure to spawn.
     data: ClipboardData,
+    /// The faction that the ghost belongs to.
+    faction: Faction,
 }
 
 impl Command for SpawnGhostCommand {
@@ -276,17 +488,18 @@ impl Command for SpawnGhostCommand {
         let (terrain_query, geometry, manifest) = system_state.get(world);
         let structure_variety = manifest.get(structure_id).clone();
 
-        // Check that the tiles needed are appropriate.
-        if !geometry.can_build(
+        // Check that the tiles needed are appropriate, and not already claimed by another ghost.
+        if !geometry.can_build_ghost(
             self.tile_pos,
             structure_variety.footprint.rotated(self.data.facing),
             &terrain_query,
             structure_variety.allowed_terrain_types(),
+            structure_variety.required_adjacency(),
         ) {
             return;
         }
 
-        // Remove any existing ghosts
+        // Remove any existing ghost at this tile, which is replaced by the new one
         let mut geometry = world.resource_mut::<MapGeometry>();
         let maybe_existing_ghost = geometry.remove_ghost(self.tile_pos);
 
@@ -322,6 +535,7 @@ impl Command for SpawnGhostCommand {
                 scene_handle,
                 inherited_material,
                 world_pos,
+                self.faction,
             ))
             .id();
 
@@ -340,6 +554,8 @@ impl Command for SpawnGhostCommand {
 struct DespawnGhostCommand {
     /// The tile position at which the structure to be despawned is found.
     tile_pos: TilePos,
+    /// Should any materials delivered to the ghost be dropped, rather than destroyed?
+    refund: bool,
 }
 
 impl Command for DespawnGhostCommand {
@@ -353,8 +569,20 @@ impl Command for DespawnGhostCommand {
         }
 
         let ghost_entity = maybe_entity.unwrap();
+        let maybe_materials = match self.refund {
+            true => world
+                .get::<InputInventory>(ghost_entity)
+                .map(|input_inventory| input_inventory.inventory.clone()),
+            false => None,
+        };
+
         // Make sure to despawn all children, which represent the meshes stored in the loaded gltf scene.
         world.entity_mut(ghost_entity).despawn_recursive();
+
+        // Materials that were partially delivered are dropped, rather than lost
+        if let Some(mut materials) = maybe_materials {
+            ItemPile::spawn(world, self.tile_pos, &mut materials);
+        }
     }
 }
 
@@ -389,12 +617,13 @@ impl Command for SpawnPreviewCommand {
         let (terrain_query, geometry, manifest) = system_state.get(world);
         let structure_variety = manifest.get(structure_id).clone();
 
-        // Check that the tiles needed are appropriate.
-        let forbidden = !geometry.can_build(
+        // Check that the tiles needed are appropriate, and not already claimed by another ghost.
+        let forbidden = !geometry.can_build_ghost(
             self.tile_pos,
             structure_variety.footprint.rotated(self.data.facing),
             &terrain_query,
             structure_variety.allowed_terrain_types(),
+            structure_variety.required_adjacency(),
         );
 
         // Fetch the scene and material to use
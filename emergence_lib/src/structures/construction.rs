@@ -17,12 +17,17 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     asset_management::manifest::Id,
+    items::inventory::ReservedFor,
     player_interaction::clipboard::ClipboardData,
     signals::{Emitter, SignalStrength, SignalType},
-    simulation::geometry::{Facing, TilePos},
+    simulation::{
+        factions::Faction,
+        geometry::{Facing, TilePos},
+    },
 };
 
 use super::crafting::WorkersPresent;
+use super::structure_assets::StructureHandles;
 use super::structure_manifest::{Structure, StructureManifest};
 use super::{
     commands::StructureCommandsExt,
@@ -33,6 +38,22 @@ use super::{
 #[derive(Reflect, FromReflect, Component, Clone, Copy, Debug)]
 pub(crate) struct Ghost;
 
+/// The items that a storage structure built from this ghost will be reserved for.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct GhostStorageFilter {
+    /// The items that the finished structure will be able to store.
+    pub(crate) reserved_for: ReservedFor,
+}
+
+/// An event sent whenever a ghost has been fully built and replaced by a structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ConstructionCompleted {
+    /// The tile that the structure was built on.
+    pub(crate) tile_pos: TilePos,
+    /// The variety of structure that was built.
+    pub(crate) structure_id: Id<Structure>,
+}
+
 /// A marker component indicating that this structure should be rendered in a transparent style.
 #[derive(Component, Clone, Copy, Debug)]
 pub(crate) struct Ghostly;
@@ -56,6 +77,8 @@ pub(super) struct GhostBundle {
     crafting_state: CraftingState,
     /// What should the structure craft when it is first built?
     active_recipe: ActiveRecipe,
+    /// What should the structure store when it is first built?
+    storage_filter: GhostStorageFilter,
     /// Makes structures pickable
     raycast_mesh: RaycastMesh<Ghost>,
     /// The mesh used for raycasting
@@ -66,6 +89,8 @@ pub(super) struct GhostBundle {
     scene_bundle: SceneBundle,
     /// Emits signals, drawing units towards this ghost to build it
     emitter: Emitter,
+    /// The faction that the ghost, and the structure it becomes, belongs to
+    faction: Faction,
 }
 
 impl GhostBundle {
@@ -78,6 +103,7 @@ impl GhostBundle {
         scene_handle: Handle<Scene>,
         inherited_material: InheritedMaterial,
         world_pos: Vec3,
+        faction: Faction,
     ) -> Self {
         let structure_id = clipboard_data.structure_id;
         let structure_data = structure_manifest.get(structure_id);
@@ -91,6 +117,9 @@ impl GhostBundle {
             workers_present: WorkersPresent::new(6),
             crafting_state: CraftingState::NeedsInput,
             active_recipe: clipboard_data.active_recipe,
+            storage_filter: GhostStorageFilter {
+                reserved_for: clipboard_data.reserved_for,
+            },
             raycast_mesh: RaycastMesh::default(),
             picking_mesh,
             inherited_material,
@@ -100,6 +129,7 @@ impl GhostBundle {
                 ..default()
             },
             emitter: Emitter::default(),
+            faction,
         }
     }
 }
@@ -184,6 +214,53 @@ impl PreviewBundle {
     }
 }
 
+/// The number of distinct appearances used to show how many construction materials a ghost has received.
+pub(crate) const GHOST_PROGRESS_STEPS: usize = 5;
+
+/// The material used for ghosts that have reached `step` out of [`GHOST_PROGRESS_STEPS`] steps of material delivery.
+///
+/// Ghosts become more opaque as their materials are delivered, making starved build sites easy to spot.
+pub(crate) fn ghost_progress_material(step: usize) -> StandardMaterial {
+    use crate::graphics::palette::infovis::{GHOST_ALPHA, GHOST_COLOR, STARVED_GHOST_ALPHA};
+
+    let fraction = step as f32 / (GHOST_PROGRESS_STEPS - 1) as f32;
+    let alpha = STARVED_GHOST_ALPHA + (GHOST_ALPHA - STARVED_GHOST_ALPHA) * fraction;
+
+    StandardMaterial {
+        base_color: GHOST_COLOR.with_a(alpha),
+        alpha_mode: AlphaMode::Blend,
+        ..Default::default()
+    }
+}
+
+/// The step of [`ghost_progress_material`] that best represents `delivered_fraction`.
+///
+/// Only ghosts with every material delivered reach the final step.
+fn ghost_progress_step(delivered_fraction: f32) -> usize {
+    let last_step = GHOST_PROGRESS_STEPS - 1;
+    if delivered_fraction >= 1.0 {
+        last_step
+    } else {
+        ((delivered_fraction.max(0.0) * last_step as f32) as usize).min(last_step - 1)
+    }
+}
+
+/// Changes the appearance of ghosts to reflect how many of their construction materials have been delivered.
+pub(super) fn show_ghost_progress(
+    mut ghost_query: Query<
+        (&InputInventory, &mut InheritedMaterial),
+        (With<Ghost>, Changed<InputInventory>),
+    >,
+    structure_handles: Res<StructureHandles>,
+) {
+    for (input_inventory, mut inherited_material) in ghost_query.iter_mut() {
+        let step = ghost_progress_step(input_inventory.delivered_fraction());
+        if let Some(material) = structure_handles.ghost_progress_materials.get(step) {
+            inherited_material.0 = material.clone_weak();
+        }
+    }
+}
+
 /// Marker component for structures that are intended to be deconstructed
 #[derive(Component, Debug)]
 pub(crate) struct MarkedForDemolition;
@@ -247,13 +324,16 @@ pub(super) fn ghost_lifecycle(
             &Id<Structure>,
             &Facing,
             &ActiveRecipe,
+            &GhostStorageFilter,
             &WorkersPresent,
+            Option<&Faction>,
         ),
         With<Ghost>,
     >,
     structure_manifest: Res<StructureManifest>,
     time: Res<FixedTime>,
     mut commands: Commands,
+    mut construction_events: EventWriter<ConstructionCompleted>,
 ) {
     for (
         mut crafting_state,
@@ -262,7 +342,9 @@ pub(super) fn ghost_lifecycle(
         &structure_id,
         &facing,
         active_recipe,
+        storage_filter,
         workers_present,
+        maybe_faction,
     ) in ghost_query.iter_mut()
     {
         match *crafting_state {
@@ -295,32 +377,29 @@ pub(super) fn ghost_lifecycle(
                 }
             }
             CraftingState::RecipeComplete => {
-                commands.despawn_ghost(tile_pos);
+                commands.consume_ghost(tile_pos);
+                construction_events.send(ConstructionCompleted {
+                    tile_pos,
+                    structure_id,
+                });
 
                 // Spawn the seedling form of a structure if any
-                if let Some(seedling) = structure_manifest
+                let built_structure_id = structure_manifest
                     .get(structure_id)
                     .construction_strategy
                     .seedling
-                {
-                    commands.spawn_structure(
-                        tile_pos,
-                        ClipboardData {
-                            structure_id: seedling,
-                            facing,
-                            active_recipe: active_recipe.clone(),
-                        },
-                    );
-                } else {
-                    commands.spawn_structure(
-                        tile_pos,
-                        ClipboardData {
-                            structure_id,
-                            facing,
-                            active_recipe: active_recipe.clone(),
-                        },
-                    );
-                }
+                    .unwrap_or(structure_id);
+
+                commands.spawn_structure(
+                    tile_pos,
+                    ClipboardData {
+                        structure_id: built_structure_id,
+                        facing,
+                        active_recipe: active_recipe.clone(),
+                        reserved_for: storage_filter.reserved_for.clone(),
+                    },
+                    maybe_faction.copied().unwrap_or_default(),
+                );
             }
             _ => unreachable!(),
         }
@@ -417,9 +496,28 @@ pub(super) fn validate_ghosts(
     for (&tile_pos, &structure_id, &facing) in ghost_query.iter() {
         let structure_details = structure_manifest.get(structure_id);
         let footprint = structure_details.footprint.rotated(facing);
-        let allowed_terrain_types = structure_details.allowed_terrain_types();
-        if !map_geometry.can_build(tile_pos, footprint, &terrain_query, allowed_terrain_types) {
+        if !map_geometry.can_build(
+            tile_pos,
+            footprint,
+            &terrain_query,
+            structure_details.allowed_terrain_types(),
+            structure_details.required_adjacency(),
+        ) {
             commands.despawn_ghost(tile_pos);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ghosts_only_look_complete_once_every_material_arrives() {
+        assert_eq!(ghost_progress_step(0.0), 0);
+        assert_eq!(ghost_progress_step(0.4), 1);
+        assert_eq!(ghost_progress_step(0.5), 2);
+        assert_eq!(ghost_progress_step(0.99), GHOST_PROGRESS_STEPS - 2);
+        assert_eq!(ghost_progress_step(1.0), GHOST_PROGRESS_STEPS - 1);
+    }
+}
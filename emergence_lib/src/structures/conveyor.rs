@@ -0,0 +1,310 @@
+//! Conveyors move items from their buffer into the structure they are pointing at.
+//!
+//! Chains of conveyors form belts, carrying items across the map without the help of units.
+//! Units can drop items onto, and pick items off of, conveyors just like any other storage.
+//! Crafting structures with an [`OutputDirection`] feed their outputs onto a conveyor placed in front of their output.
+
+use bevy::{prelude::*, utils::HashMap};
+use hexx::Direction;
+
+use crate::{
+    items::{item_manifest::ItemManifest, ItemCount},
+    simulation::{
+        geometry::{Facing, MapGeometry, TilePos},
+        SimulationSet,
+    },
+};
+
+use super::crafting::{InputInventory, OutputDirection, OutputInventory, StorageInventory};
+
+/// Moves items along conveyor belts.
+pub(super) struct ConveyorPlugin;
+
+impl Plugin for ConveyorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                feed_conveyors_from_outputs.before(move_items_on_conveyors),
+                move_items_on_conveyors,
+            )
+                .in_set(SimulationSet)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// A structure that moves items from its [`StorageInventory`] to the structure in front of it.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Conveyor {
+    /// The direction that items are moved in, relative to the structure's [`Facing`].
+    direction: Direction,
+}
+
+impl Conveyor {
+    /// Creates a new [`Conveyor`] that moves items in the provided `direction`.
+    pub(crate) fn new(direction: Direction) -> Self {
+        Conveyor { direction }
+    }
+
+    /// The tile that items are moved to, for a conveyor at `tile_pos` with the provided `facing`.
+    pub(crate) fn output_tile(&self, tile_pos: TilePos, facing: Facing) -> TilePos {
+        tile_pos.neighbor_facing(self.direction, facing)
+    }
+}
+
+/// Moves a single item from each directional crafting structure onto the conveyor in front of its output, if there is space.
+fn feed_conveyors_from_outputs(
+    mut crafter_query: Query<(&TilePos, &Facing, &OutputDirection, &mut OutputInventory)>,
+    mut conveyor_query: Query<&mut StorageInventory, With<Conveyor>>,
+    map_geometry: Res<MapGeometry>,
+    item_manifest: Res<ItemManifest>,
+) {
+    for (&tile_pos, &facing, output_direction, mut output_inventory) in crafter_query.iter_mut() {
+        let output_tile = output_direction.output_tile(tile_pos, facing);
+        let Some(conveyor_entity) = map_geometry.get_structure(output_tile) else {
+            continue;
+        };
+
+        let Ok(mut conveyor_inventory) = conveyor_query.get_mut(conveyor_entity) else {
+            continue;
+        };
+
+        let Some(item_slot) = output_inventory.iter().find(|slot| !slot.is_empty()) else {
+            continue;
+        };
+
+        let item_count = ItemCount::one(item_slot.item_id());
+        if conveyor_inventory
+            .add_item_all_or_nothing(&item_count, &item_manifest)
+            .is_ok()
+        {
+            // The item was just found in the output inventory
+            output_inventory
+                .remove_item_all_or_nothing(&item_count)
+                .unwrap();
+        }
+    }
+}
+
+/// Moves a single item from each conveyor into the structure in front of it, if there is space.
+///
+/// Conveyors are processed from the end of each belt back towards its start,
+/// so space freed up downstream can be used in the same tick, but no item moves more than one tile per tick.
+fn move_items_on_conveyors(
+    conveyor_query: Query<(Entity, &TilePos, &Facing, &Conveyor)>,
+    mut inventory_query: Query<AnyOf<(&mut InputInventory, &mut StorageInventory)>>,
+    map_geometry: Res<MapGeometry>,
+    item_manifest: Res<ItemManifest>,
+) {
+    // Find the structure that each conveyor feeds into
+    let mut downstream: HashMap<Entity, Entity> = HashMap::default();
+    for (conveyor_entity, &tile_pos, &facing, conveyor) in conveyor_query.iter() {
+        let output_tile = conveyor.output_tile(tile_pos, facing);
+        if let Some(target_entity) = map_geometry.get_structure(output_tile) {
+            if target_entity != conveyor_entity {
+                downstream.insert(conveyor_entity, target_entity);
+            }
+        }
+    }
+
+    // Decide which item each conveyor will move before anything moves,
+    // so items that arrive this tick are never moved again, even in loops.
+    let mut planned_moves: Vec<(usize, Entity, Entity, ItemCount)> = Vec::new();
+    for (&conveyor_entity, &target_entity) in downstream.iter() {
+        let Ok((_, Some(storage_inventory))) = inventory_query.get(conveyor_entity) else {
+            continue;
+        };
+
+        let Some(item_slot) = storage_inventory.iter().find(|slot| !slot.is_empty()) else {
+            continue;
+        };
+
+        // The number of conveyors between this conveyor and the end of its belt.
+        // Loops have no end, so the distance is capped at the number of conveyors.
+        let mut distance_to_end = 0;
+        let mut current_entity = target_entity;
+        while let Some(&next_entity) = downstream.get(&current_entity) {
+            distance_to_end += 1;
+            current_entity = next_entity;
+            if distance_to_end >= downstream.len() {
+                break;
+            }
+        }
+
+        planned_moves.push((
+            distance_to_end,
+            conveyor_entity,
+            target_entity,
+            ItemCount::one(item_slot.item_id()),
+        ));
+    }
+
+    planned_moves.sort_by_key(|(distance_to_end, ..)| *distance_to_end);
+
+    for (_, conveyor_entity, target_entity, item_count) in planned_moves {
+        let Ok((input_inventory, storage_inventory)) = inventory_query.get_mut(target_entity)
+        else {
+            continue;
+        };
+
+        let added = match (input_inventory, storage_inventory) {
+            (Some(mut input_inventory), _) => input_inventory
+                .add_item_all_or_nothing(&item_count, &item_manifest)
+                .is_ok(),
+            (None, Some(mut storage_inventory)) => storage_inventory
+                .add_item_all_or_nothing(&item_count, &item_manifest)
+                .is_ok(),
+            (None, None) => false,
+        };
+
+        if added {
+            let (_, storage_inventory) = inventory_query.get_mut(conveyor_entity).unwrap();
+            // The item was present when the move was planned, and conveyors never lose items before moving
+            storage_inventory
+                .unwrap()
+                .remove_item_all_or_nothing(&item_count)
+                .unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asset_management::manifest::Id,
+        items::{
+            inventory::{Inventory, ReservedFor},
+            item_manifest::{Item, ItemData},
+        },
+        structures::{construction::Footprint, structure_manifest::Structure},
+    };
+
+    /// The number of items stored on each conveyor, from the start of the belt to the end.
+    fn items_on_belt(app: &App, belt: &[Entity], item_id: Id<Item>) -> Vec<usize> {
+        belt.iter()
+            .map(|&entity| {
+                app.world
+                    .get::<StorageInventory>(entity)
+                    .unwrap()
+                    .item_count(item_id)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn items_move_one_tile_per_tick_along_belt() {
+        let mut app = App::new();
+        let item_id = Id::from_name("leuco_chunk");
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        // A single item starts at the beginning of the belt
+        let mut first_inventory = StorageInventory::new(1, ReservedFor::Any);
+        first_inventory
+            .add_item_all_or_nothing(&ItemCount::one(item_id), &item_manifest)
+            .unwrap();
+        let inventories = vec![
+            first_inventory,
+            StorageInventory::new(1, ReservedFor::Any),
+            StorageInventory::new(1, ReservedFor::Any),
+        ];
+
+        app.insert_resource(item_manifest);
+        app.insert_resource(MapGeometry::new(5));
+        app.add_system(move_items_on_conveyors);
+
+        let facing = Facing::default();
+        let conveyor = Conveyor::new(Direction::Top);
+        let structure_id = Id::<Structure>::from_name("conveyor");
+
+        let mut tile_pos = TilePos::ZERO;
+        let mut belt = Vec::new();
+        for inventory in inventories {
+            let entity = app
+                .world
+                .spawn((tile_pos, facing, conveyor, inventory))
+                .id();
+            app.world.resource_mut::<MapGeometry>().add_structure(
+                tile_pos,
+                &Footprint::single(),
+                structure_id,
+                entity,
+            );
+            belt.push(entity);
+            tile_pos = conveyor.output_tile(tile_pos, facing);
+        }
+
+        app.update();
+        assert_eq!(items_on_belt(&app, &belt, item_id), vec![0, 1, 0]);
+
+        app.update();
+        assert_eq!(items_on_belt(&app, &belt, item_id), vec![0, 0, 1]);
+
+        // Nothing is in front of the last conveyor, so the item stays put
+        app.update();
+        assert_eq!(items_on_belt(&app, &belt, item_id), vec![0, 0, 1]);
+    }
+
+    #[test]
+    fn directional_crafters_feed_conveyors_in_front_of_their_output() {
+        let mut app = App::new();
+        let item_id = Id::from_name("leuco_chunk");
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let mut output_inventory = OutputInventory {
+            inventory: Inventory::new(1, ReservedFor::Any),
+        };
+        output_inventory
+            .add_item_all_or_nothing(&ItemCount::new(item_id, 2), &item_manifest)
+            .unwrap();
+
+        app.insert_resource(item_manifest);
+        app.insert_resource(MapGeometry::new(5));
+        app.add_system(feed_conveyors_from_outputs);
+
+        let facing = Facing::default();
+        let output_direction = OutputDirection::new(Direction::Top);
+        let crafter_tile_pos = TilePos::ZERO;
+        let crafter = app
+            .world
+            .spawn((crafter_tile_pos, facing, output_direction, output_inventory))
+            .id();
+
+        let mut spawn_conveyor = |tile_pos: TilePos| {
+            let entity = app
+                .world
+                .spawn((
+                    tile_pos,
+                    facing,
+                    Conveyor::new(Direction::Top),
+                    StorageInventory::new(1, ReservedFor::Any),
+                ))
+                .id();
+            app.world.resource_mut::<MapGeometry>().add_structure(
+                tile_pos,
+                &Footprint::single(),
+                Id::<Structure>::from_name("conveyor"),
+                entity,
+            );
+            entity
+        };
+        let front_conveyor = spawn_conveyor(output_direction.output_tile(crafter_tile_pos, facing));
+        let side_conveyor = spawn_conveyor(crafter_tile_pos.neighbor(Direction::Bottom));
+
+        app.update();
+
+        assert_eq!(
+            app.world
+                .get::<OutputInventory>(crafter)
+                .unwrap()
+                .item_count(item_id),
+            1
+        );
+        assert_eq!(
+            items_on_belt(&app, &[front_conveyor, side_conveyor], item_id),
+            vec![1, 0]
+        );
+    }
+}
@@ -1,32 +1,54 @@
 //! Everything needed to make structures able to craft things.
 
-use std::{fmt::Display, time::Duration};
+use std::{fmt::Display, sync::Mutex, time::Duration};
 
 use bevy::{
     ecs::{query::WorldQuery, system::SystemParam},
     prelude::*,
 };
+use hexx::Direction;
 use leafwing_abilities::prelude::Pool;
 use rand::{distributions::Uniform, prelude::Distribution, rngs::ThreadRng};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    asset_management::manifest::{plugin::ManifestPlugin, Id},
+    asset_management::{
+        localization::Localization,
+        manifest::{plugin::ManifestPlugin, Id},
+    },
     items::{
-        inventory::Inventory,
+        fluid::{FluidAmount, FluidInventory},
+        inventory::{Inventory, ReservedFor},
         item_manifest::{Item, ItemManifest, RawItemManifest},
-        recipe::{RawRecipeManifest, Recipe, RecipeManifest},
+        recipe::{RawRecipeManifest, Recipe, RecipeData, RecipeManifest},
+        tech_tree::{update_tech_tree, TechTree},
+        ItemCount,
     },
-    organisms::{energy::EnergyPool, lifecycle::Lifecycle, Organism},
+    organisms::{energy::EnergyPool, lifecycle::Lifecycle, Organism, OrganismId},
+    player_interaction::clipboard::ClipboardData,
     signals::{Emitter, SignalStrength, SignalType},
     simulation::{
-        geometry::{MapGeometry, TilePos},
+        event_log::{EventLog, GameEvent},
+        factions::Faction,
+        geometry::{Facing, Height, MapGeometry, TilePos},
         light::TotalLight,
+        time::Season,
         SimulationSet,
     },
+    terrain::{deposits::ResourceDeposit, terrain_manifest::Terrain, ChangeTerrainCommand},
+    units::{
+        commands::UnitCommandsExt, goals::Goal, item_interaction::UnitInventory,
+        population::Population,
+    },
 };
 
-use super::structure_manifest::{Structure, StructureManifest};
+use super::{
+    colony_center::ColonyCenter,
+    commands::StructureCommandsExt,
+    construction::Ghost,
+    power::{distribute_power, PowerConsumer},
+    structure_manifest::{Structure, StructureManifest},
+};
 
 /// The current state in the crafting progress.
 #[derive(Component, Debug, Default, Clone, PartialEq)]
@@ -41,8 +63,18 @@ pub(crate) enum CraftingState {
         /// How long does this recipe take to complete in full?
         required: Duration,
     },
-    /// Resources need to be claimed before more crafting can continue.
+    /// The recipe is complete, but there is no room in the output inventory for the finished goods.
+    ///
+    /// The finished goods are held until space frees up, and no inputs are consumed in the meantime.
     FullAndBlocked,
+    /// The recipe would spawn a unit, but the population is at its cap.
+    ///
+    /// The recipe completes as soon as there is room for another unit.
+    PopulationCapped,
+    /// The recipe mines a resource deposit, but the deposit beneath the structure is exhausted.
+    ///
+    /// The recipe cannot be started again unless there is enough left to mine.
+    DepositExhausted,
     /// The recipe is complete.
     RecipeComplete,
     /// The output is full but production is continuing.
@@ -61,7 +93,9 @@ impl Display for CraftingState {
                 format!("In progress ({progress_in_seconds:.1} / {required_in_seconds:.1})")
             }
             CraftingState::RecipeComplete => "Recipe complete".to_string(),
-            CraftingState::FullAndBlocked => "Blocked".to_string(),
+            CraftingState::FullAndBlocked => "Output full".to_string(),
+            CraftingState::PopulationCapped => "Population cap reached".to_string(),
+            CraftingState::DepositExhausted => "Deposit exhausted".to_string(),
             CraftingState::Overproduction => "Overproduction".to_string(),
             CraftingState::NoRecipe => "No recipe set".to_string(),
         };
@@ -78,6 +112,23 @@ pub struct InputInventory {
 }
 
 impl InputInventory {
+    /// The fraction of the total item capacity of this inventory that has been filled, between 0 and 1.
+    ///
+    /// Ghosts start with an empty copy of their construction materials,
+    /// so this measures how much of the required materials have been delivered.
+    /// Inventories with no capacity are considered complete.
+    pub(crate) fn delivered_fraction(&self) -> f32 {
+        let (delivered, required) = self.iter().fold((0, 0), |(delivered, required), slot| {
+            (delivered + slot.count(), required + slot.max_item_count())
+        });
+
+        if required == 0 {
+            1.0
+        } else {
+            delivered as f32 / required as f32
+        }
+    }
+
     /// Randomizes the contents of this inventory so that each slot is somewhere between empty and full.
     pub(super) fn randomize(&mut self, rng: &mut ThreadRng) {
         for item_slot in self.iter_mut() {
@@ -102,6 +153,22 @@ impl OutputInventory {
     }
 }
 
+/// The fluids produced by a structure, waiting to be piped into the structures next to it.
+///
+/// The fluids that a structure consumes are stored in its [`FluidInventory`] instead.
+#[derive(Component, Debug, Clone, Default, PartialEq, Deref, DerefMut)]
+pub(crate) struct OutputFluids {
+    /// Inner storage
+    pub(crate) tanks: FluidInventory,
+}
+
+/// Marker component for structures that the player has paused.
+///
+/// Disabled structures make no crafting progress, attract no workers and emit no signals,
+/// but keep their inventories and recipe so they can resume where they left off.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Disabled;
+
 /// An inventory that simply stores items
 #[derive(Component, Clone, Debug, Default, Deref, DerefMut)]
 pub(crate) struct StorageInventory {
@@ -112,14 +179,33 @@ pub(crate) struct StorageInventory {
 impl StorageInventory {
     /// Creates a new [`StorageInventory`] with the provided number of slots.
     ///
-    /// If `reserved_for` is `Some`, only one item variety will be able to be stored here.
-    pub(crate) fn new(max_slot_count: usize, reserved_for: Option<Id<Item>>) -> Self {
+    /// Only items permitted by `reserved_for` will be able to be stored here.
+    pub(crate) fn new(max_slot_count: usize, reserved_for: ReservedFor) -> Self {
         StorageInventory {
             inventory: Inventory::new(max_slot_count, reserved_for),
         }
     }
 }
 
+/// The side of a directional crafting structure that its outputs are handed off from.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OutputDirection {
+    /// The direction that outputs are handed off in, relative to the structure's [`Facing`].
+    direction: Direction,
+}
+
+impl OutputDirection {
+    /// Creates a new [`OutputDirection`] that hands off outputs in the provided `direction`.
+    pub(crate) fn new(direction: Direction) -> Self {
+        OutputDirection { direction }
+    }
+
+    /// The tile that outputs are handed off to, for a structure at `tile_pos` with the provided `facing`.
+    pub(crate) fn output_tile(&self, tile_pos: TilePos, facing: Facing) -> TilePos {
+        tile_pos.neighbor_facing(self.direction, facing)
+    }
+}
+
 /// The recipe that is currently being crafted, if any.
 #[derive(Component, Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ActiveRecipe(Option<Id<Recipe>>);
@@ -139,14 +225,88 @@ impl ActiveRecipe {
     }
 
     /// The pretty formatting for this type
-    pub(crate) fn display(&self, recipe_manifest: &RecipeManifest) -> String {
+    pub(crate) fn display(
+        &self,
+        recipe_manifest: &RecipeManifest,
+        localization: &Localization,
+    ) -> String {
         match self.0 {
-            Some(recipe_id) => recipe_manifest.name(recipe_id).to_string(),
+            Some(recipe_id) => localization
+                .get(recipe_manifest.name(recipe_id))
+                .to_string(),
             None => "None".to_string(),
         }
     }
 }
 
+/// Lifetime statistics about how productive a crafting structure has been.
+#[derive(Component, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductionStats {
+    /// The total number of items produced.
+    items_produced: u64,
+    /// The total time spent actively crafting.
+    time_working: Duration,
+    /// The total time spent waiting, for inputs, workers, power or space to put the outputs.
+    time_idle: Duration,
+}
+
+impl ProductionStats {
+    /// The total number of items produced.
+    pub fn items_produced(&self) -> u64 {
+        self.items_produced
+    }
+
+    /// Records that `delta` time has passed, during which the structure was either working or idle.
+    pub(crate) fn record_time(&mut self, delta: Duration, working: bool) {
+        match working {
+            true => self.time_working += delta,
+            false => self.time_idle += delta,
+        }
+    }
+
+    /// Records that the provided `outputs` were produced.
+    pub(crate) fn record_outputs(&mut self, outputs: &[ItemCount]) {
+        self.items_produced += outputs
+            .iter()
+            .map(|item_count| item_count.count() as u64)
+            .sum::<u64>();
+    }
+
+    /// The average number of items produced per minute over the lifetime of this structure.
+    pub fn throughput(&self) -> f32 {
+        let minutes = (self.time_working + self.time_idle).as_secs_f32() / 60.;
+
+        if minutes > 0. {
+            self.items_produced as f32 / minutes
+        } else {
+            0.
+        }
+    }
+
+    /// The fraction of time that this structure has spent working, between 0 and 1.
+    pub fn uptime(&self) -> f32 {
+        let total = self.time_working + self.time_idle;
+
+        if total > Duration::ZERO {
+            self.time_working.as_secs_f32() / total.as_secs_f32()
+        } else {
+            0.
+        }
+    }
+}
+
+impl Display for ProductionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Produced: {} ({:.1} / min)\nUptime: {:.0}%",
+            self.items_produced,
+            self.throughput(),
+            self.uptime() * 100.
+        )
+    }
+}
+
 /// All components needed to craft stuff.
 #[derive(Debug, Bundle)]
 pub(crate) struct CraftingBundle {
@@ -156,6 +316,12 @@ pub(crate) struct CraftingBundle {
     /// The output inventory for the crafted items.
     output_inventory: OutputInventory,
 
+    /// The tanks for any fluids needed for crafting.
+    fluid_inventory: FluidInventory,
+
+    /// The tanks for any fluids produced by crafting.
+    output_fluids: OutputFluids,
+
     /// The recipe that is currently being crafted.
     active_recipe: ActiveRecipe,
 
@@ -167,6 +333,9 @@ pub(crate) struct CraftingBundle {
 
     /// The number of workers present / allowed at this structure
     workers_present: WorkersPresent,
+
+    /// How productive this structure has been over its lifetime
+    production_stats: ProductionStats,
 }
 
 /// The number of workers present / allowed at this structure.
@@ -197,6 +366,11 @@ impl WorkersPresent {
         self.present
     }
 
+    /// The maximum number of workers allowed.
+    pub(crate) fn allowed(&self) -> u8 {
+        self.allowed
+    }
+
     /// Adds a worker to this structure if there is room.
     pub(crate) fn add_worker(&mut self) -> Result<(), ()> {
         if self.needs_more() {
@@ -241,23 +415,29 @@ impl CraftingBundle {
             Self {
                 input_inventory: recipe.input_inventory(item_manifest),
                 output_inventory: recipe.output_inventory(item_manifest),
+                fluid_inventory: recipe.fluid_inventory(item_manifest),
+                output_fluids: recipe.output_fluids(item_manifest),
                 active_recipe: ActiveRecipe(Some(recipe_id)),
                 craft_state: CraftingState::NeedsInput,
                 emitter: Emitter::default(),
                 workers_present: WorkersPresent::new(max_workers),
+                production_stats: ProductionStats::default(),
             }
         } else {
             Self {
                 input_inventory: InputInventory {
-                    inventory: Inventory::new(0, None),
+                    inventory: Inventory::new(0, ReservedFor::Any),
                 },
                 output_inventory: OutputInventory {
-                    inventory: Inventory::new(1, None),
+                    inventory: Inventory::new(1, ReservedFor::Any),
                 },
+                fluid_inventory: FluidInventory::default(),
+                output_fluids: OutputFluids::default(),
                 active_recipe: ActiveRecipe(None),
                 craft_state: CraftingState::NeedsInput,
                 emitter: Emitter::default(),
                 workers_present: WorkersPresent::new(max_workers),
+                production_stats: ProductionStats::default(),
             }
         }
     }
@@ -286,6 +466,8 @@ impl CraftingBundle {
             Self {
                 input_inventory,
                 output_inventory,
+                fluid_inventory: recipe.fluid_inventory(item_manifest),
+                output_fluids: recipe.output_fluids(item_manifest),
                 active_recipe: ActiveRecipe(Some(recipe_id)),
                 craft_state: CraftingState::InProgress {
                     progress,
@@ -293,6 +475,7 @@ impl CraftingBundle {
                 },
                 emitter: Emitter::default(),
                 workers_present: WorkersPresent::new(max_workers),
+                production_stats: ProductionStats::default(),
             }
         } else {
             CraftingBundle::new(
@@ -309,7 +492,9 @@ impl CraftingBundle {
 /// Data needed for [`progress_crafting`].
 #[derive(WorldQuery)]
 #[world_query(mutable)]
-struct CraftingQuery {
+pub(crate) struct CraftingQuery {
+    /// The crafting entity
+    entity: Entity,
     /// The recipe of the crafter
     active_recipe: &'static ActiveRecipe,
     /// The status of crafting
@@ -318,107 +503,367 @@ struct CraftingQuery {
     input: &'static mut InputInventory,
     /// The outputs
     output: &'static mut OutputInventory,
+    /// The fluids used as inputs, if any
+    maybe_fluids: Option<&'static mut FluidInventory>,
+    /// The fluids produced, if any
+    maybe_output_fluids: Option<&'static mut OutputFluids>,
     /// The number of workers present
     workers_present: &'static WorkersPresent,
     /// Is this an organism?
     maybe_organism: Option<&'static Organism>,
+    /// Does this structure need power to craft?
+    maybe_power_consumer: Option<&'static PowerConsumer>,
+    /// The tile that the crafter is on
+    tile_pos: &'static TilePos,
+    /// How productive this crafter has been
+    maybe_production_stats: Option<&'static mut ProductionStats>,
 }
 
-/// Progress the state of recipes that are being crafted.
-fn progress_crafting(
-    time: Res<FixedTime>,
-    recipe_manifest: Res<RecipeManifest>,
-    item_manifest: Res<ItemManifest>,
-    total_light: Res<TotalLight>,
-    mut crafting_query: Query<CraftingQuery>,
-) {
-    for mut crafter in crafting_query.iter_mut() {
-        *crafter.state = match *crafter.state {
-            CraftingState::NoRecipe => match crafter.active_recipe.recipe_id() {
-                Some(_) => CraftingState::NeedsInput,
-                None => CraftingState::NoRecipe,
-            },
-            CraftingState::NeedsInput | CraftingState::Overproduction => {
-                if let Some(recipe_id) = crafter.active_recipe.recipe_id() {
-                    let recipe = recipe_manifest.get(*recipe_id);
-                    match crafter.input.remove_items_all_or_nothing(&recipe.inputs) {
-                        Ok(()) => CraftingState::InProgress {
-                            progress: Duration::ZERO,
-                            required: recipe.craft_time,
-                        },
-                        Err(_) => CraftingState::NeedsInput,
-                    }
+/// Finishes crafting `recipe`, unless it would spawn a unit that the [`Population`] has no room for.
+fn complete_recipe(recipe: &RecipeData, population: &mut Population) -> CraftingState {
+    match recipe.spawned_unit() {
+        Some(unit_id) if !population.try_reserve(unit_id) => CraftingState::PopulationCapped,
+        _ => CraftingState::RecipeComplete,
+    }
+}
+
+/// Finishes crafting `recipe` at `crafter_entity`, without touching the shared [`Population`].
+///
+/// Recipes that spawn units wait as [`CraftingState::PopulationCapped`] until [`apply_crafting_effects`] reserves room for them.
+fn finish_recipe(
+    recipe: &RecipeData,
+    crafter_entity: Entity,
+    effect: &mut Option<CraftingEffect>,
+) -> CraftingState {
+    match recipe.spawned_unit() {
+        Some(_) => {
+            *effect = Some(CraftingEffect::AwaitingPopulation(crafter_entity));
+            CraftingState::PopulationCapped
+        }
+        None => CraftingState::RecipeComplete,
+    }
+}
+
+/// The read-only data needed to advance each crafter.
+///
+/// This is shared between threads by [`progress_crafting`].
+#[derive(SystemParam)]
+pub(crate) struct CraftingContext<'w, 's> {
+    /// The length of each simulation tick
+    time: Res<'w, FixedTime>,
+    /// The recipes that can be crafted
+    recipe_manifest: Res<'w, RecipeManifest>,
+    /// The items that recipes produce
+    item_manifest: Res<'w, ItemManifest>,
+    /// The light available to recipes that need it
+    total_light: Res<'w, TotalLight>,
+    /// Which recipes have been unlocked
+    tech_tree: Res<'w, TechTree>,
+    /// The current season
+    season: Res<'w, Season>,
+    /// Used to look up the terrain beneath each crafter
+    map_geometry: Res<'w, MapGeometry>,
+    /// The type of each terrain tile
+    terrain_query: Query<'w, 's, &'static Id<Terrain>>,
+    /// The resources left beneath each terrain tile
+    deposit_query: Query<'w, 's, &'static ResourceDeposit>,
+}
+
+/// A change to shared resources requested by a single crafter.
+///
+/// These are applied once every crafter has been advanced.
+#[derive(Debug)]
+enum CraftingEffect {
+    /// A building completed a recipe, which should be recorded in the [`EventLog`].
+    Completed(Entity, GameEvent),
+    /// The crafter finished a recipe that hatches a unit, and needs room in the [`Population`].
+    AwaitingPopulation(Entity),
+}
+
+/// Advances the state of a single crafter by one tick.
+///
+/// Any changes to shared resources are returned rather than applied, so crafters can be advanced in parallel.
+fn advance_crafter(
+    crafter: &mut CraftingQueryItem,
+    context: &CraftingContext,
+) -> Option<CraftingEffect> {
+    // Only time spent making progress on a recipe counts as work
+    let mut working = false;
+    let mut produced: Option<&[ItemCount]> = None;
+    let mut effect = None;
+
+    *crafter.state = match *crafter.state {
+        CraftingState::NoRecipe => match crafter.active_recipe.recipe_id() {
+            Some(_) => CraftingState::NeedsInput,
+            None => CraftingState::NoRecipe,
+        },
+        CraftingState::NeedsInput
+        | CraftingState::Overproduction
+        | CraftingState::DepositExhausted => {
+            if let Some(recipe_id) = crafter.active_recipe.recipe_id() {
+                let recipe = context.recipe_manifest.get(*recipe_id);
+                // Mining can't produce anything from nothing, so check the deposit before consuming any inputs
+                let deposit_available = match &recipe.mined_deposit {
+                    Some(mined) => context
+                        .map_geometry
+                        .get_terrain(*crafter.tile_pos)
+                        .and_then(|terrain_entity| context.deposit_query.get(terrain_entity).ok())
+                        .is_some_and(|deposit| deposit.can_mine(mined)),
+                    None => true,
+                };
+
+                // Locked recipes can't be started, so hang on to the inputs until they're unlocked
+                if !context.tech_tree.is_unlocked(*recipe_id) {
+                    CraftingState::NeedsInput
+                } else if !deposit_available {
+                    CraftingState::DepositExhausted
                 } else {
-                    CraftingState::NoRecipe
-                }
-            }
-            CraftingState::InProgress { progress, required } => {
-                let mut updated_progress = progress;
-                if let Some(recipe_id) = crafter.active_recipe.recipe_id() {
-                    let recipe = recipe_manifest.get(*recipe_id);
-                    if recipe.satisfied(crafter.workers_present.current(), &total_light) {
-                        // Many hands make light work!
-                        if recipe.workers_required() > 0 {
-                            let work_ratio = crafter.workers_present.current() as f32
-                                / recipe.workers_required() as f32;
-                            updated_progress +=
-                                Duration::from_secs_f32(time.period.as_secs_f32() * work_ratio);
-                        } else {
-                            updated_progress += time.period;
-                        }
+                    // Both the discrete items and the fluids must be present before either is consumed
+                    let fluids_available = match &crafter.maybe_fluids {
+                        Some(fluids) => fluids.has_fluids(&recipe.fluid_inputs),
+                        None => recipe.fluid_inputs.is_empty(),
+                    };
+
+                    // Use whichever of the alternative ingredients are available
+                    let chosen_inputs = recipe
+                        .choose_inputs(&crafter.input)
+                        .filter(|_| fluids_available);
+
+                    match chosen_inputs {
+                        Some(chosen_inputs) => {
+                            crafter
+                                .input
+                                .remove_items_all_or_nothing(&chosen_inputs)
+                                .unwrap();
+                            if let Some(fluids) = &mut crafter.maybe_fluids {
+                                fluids
+                                    .remove_fluids_all_or_nothing(&recipe.fluid_inputs)
+                                    .unwrap();
+                            }
 
-                        if updated_progress >= required {
-                            CraftingState::RecipeComplete
-                        } else {
                             CraftingState::InProgress {
-                                progress: updated_progress,
-                                required,
+                                progress: Duration::ZERO,
+                                required: recipe.craft_time,
                             }
                         }
+                        None => CraftingState::NeedsInput,
+                    }
+                }
+            } else {
+                CraftingState::NoRecipe
+            }
+        }
+        CraftingState::InProgress { progress, required } => {
+            let mut updated_progress = progress;
+            if let Some(recipe_id) = crafter.active_recipe.recipe_id() {
+                let recipe = context.recipe_manifest.get(*recipe_id);
+                let powered = match crafter.maybe_power_consumer {
+                    Some(power_consumer) => power_consumer.is_powered(),
+                    None => true,
+                };
+
+                let terrain = context
+                    .map_geometry
+                    .get_terrain(*crafter.tile_pos)
+                    .and_then(|terrain_entity| context.terrain_query.get(terrain_entity).ok())
+                    .copied();
+
+                if powered
+                    && recipe.satisfied(
+                        crafter.workers_present.current(),
+                        &context.total_light,
+                        terrain,
+                    )
+                {
+                    working = true;
+                    // Many hands make light work!
+                    let work_speed = recipe.seasonal_work_speed(
+                        crafter.workers_present.current(),
+                        crafter.workers_present.allowed(),
+                        *context.season,
+                    );
+                    updated_progress +=
+                        Duration::from_secs_f32(context.time.period.as_secs_f32() * work_speed);
+
+                    if updated_progress >= required {
+                        finish_recipe(recipe, crafter.entity, &mut effect)
                     } else {
-                        CraftingState::InProgress { progress, required }
+                        CraftingState::InProgress {
+                            progress: updated_progress,
+                            required,
+                        }
                     }
                 } else {
-                    CraftingState::NoRecipe
+                    CraftingState::InProgress { progress, required }
                 }
+            } else {
+                CraftingState::NoRecipe
             }
-            CraftingState::RecipeComplete => {
-                if let Some(recipe_id) = crafter.active_recipe.recipe_id() {
-                    let recipe = recipe_manifest.get(*recipe_id);
-                    match crafter.maybe_organism {
-                        Some(_) => {
-                            match crafter
-                                .output
-                                .try_add_items(&recipe.outputs, &item_manifest)
-                            {
-                                Ok(_) => CraftingState::NeedsInput,
-                                // TODO: handle the waste products somehow
-                                Err(_) => CraftingState::Overproduction,
-                            }
-                        }
-                        None => match crafter
+        }
+        CraftingState::PopulationCapped => match crafter.active_recipe.recipe_id() {
+            Some(recipe_id) => finish_recipe(
+                context.recipe_manifest.get(*recipe_id),
+                crafter.entity,
+                &mut effect,
+            ),
+            None => CraftingState::NoRecipe,
+        },
+        // Blocked crafts keep trying to hand off their finished goods
+        CraftingState::RecipeComplete | CraftingState::FullAndBlocked => {
+            if let Some(recipe_id) = crafter.active_recipe.recipe_id() {
+                let recipe = context.recipe_manifest.get(*recipe_id);
+                // Fluids can't be spilled, so the tanks must have room for all of them before anything is handed off
+                let fluids_fit = match &crafter.maybe_output_fluids {
+                    Some(output_fluids) => output_fluids.can_fit(&recipe.fluid_outputs),
+                    None => recipe.fluid_outputs.is_empty(),
+                };
+
+                match crafter.maybe_organism {
+                    Some(_) if !fluids_fit => CraftingState::Overproduction,
+                    Some(_) => {
+                        match crafter
                             .output
-                            .add_items_all_or_nothing(&recipe.outputs, &item_manifest)
+                            .try_add_items(&recipe.outputs, &context.item_manifest)
                         {
-                            Ok(()) => CraftingState::NeedsInput,
-                            Err(_) => CraftingState::FullAndBlocked,
-                        },
+                            Ok(_) => {
+                                store_output_fluids(crafter, recipe);
+                                produced = Some(&recipe.outputs);
+                                CraftingState::NeedsInput
+                            }
+                            // TODO: handle the waste products somehow
+                            Err(_) => CraftingState::Overproduction,
+                        }
                     }
-                } else {
-                    CraftingState::NoRecipe
+                    None if !fluids_fit => CraftingState::FullAndBlocked,
+                    // Organisms craft constantly, so only the work of buildings is worth logging
+                    None => match crafter
+                        .output
+                        .add_items_all_or_nothing(&recipe.outputs, &context.item_manifest)
+                    {
+                        Ok(()) => {
+                            store_output_fluids(crafter, recipe);
+                            effect = Some(CraftingEffect::Completed(
+                                crafter.entity,
+                                GameEvent::RecipeCompleted {
+                                    recipe_id: *recipe_id,
+                                    tile_pos: *crafter.tile_pos,
+                                },
+                            ));
+                            produced = Some(&recipe.outputs);
+                            CraftingState::NeedsInput
+                        }
+                        Err(_) => CraftingState::FullAndBlocked,
+                    },
                 }
+            } else {
+                CraftingState::NoRecipe
             }
-            CraftingState::FullAndBlocked => {
-                let mut item_slots = crafter.output.iter();
-                match item_slots.any(|slot| slot.is_full()) {
-                    true => CraftingState::FullAndBlocked,
-                    false => CraftingState::NeedsInput,
+        }
+    };
+
+    if let Some(production_stats) = &mut crafter.maybe_production_stats {
+        production_stats.record_time(context.time.period, working);
+
+        if let Some(outputs) = produced {
+            production_stats.record_outputs(outputs);
+        }
+    }
+
+    effect
+}
+
+/// Stores the fluid outputs of the completed `recipe` in the crafter's [`OutputFluids`].
+///
+/// The caller must have already checked that they fit.
+fn store_output_fluids(crafter: &mut CraftingQueryItem, recipe: &RecipeData) {
+    if let Some(output_fluids) = &mut crafter.maybe_output_fluids {
+        for &fluid_amount in &recipe.fluid_outputs {
+            output_fluids.add_fluid(fluid_amount).unwrap();
+        }
+    }
+}
+
+/// Applies the `effects` requested by crafters this tick.
+fn apply_crafting_effects(
+    mut effects: Vec<CraftingEffect>,
+    crafting_query: &mut Query<CraftingQuery, Without<Disabled>>,
+    recipe_manifest: &RecipeManifest,
+    event_log: &mut EventLog,
+    population: &mut Population,
+) {
+    // Crafters are advanced in an arbitrary order, so sort them to keep the event log and population reservations deterministic
+    effects.sort_by_key(|effect| match effect {
+        CraftingEffect::Completed(crafter_entity, _) => *crafter_entity,
+        CraftingEffect::AwaitingPopulation(crafter_entity) => *crafter_entity,
+    });
+
+    for effect in effects {
+        match effect {
+            CraftingEffect::Completed(_, event) => event_log.push(event),
+            CraftingEffect::AwaitingPopulation(crafter_entity) => {
+                let Ok(mut crafter) = crafting_query.get_mut(crafter_entity) else {
+                    continue;
+                };
+
+                if let Some(recipe_id) = crafter.active_recipe.recipe_id() {
+                    *crafter.state = complete_recipe(recipe_manifest.get(*recipe_id), population);
                 }
             }
-        };
+        }
     }
 }
 
+/// Progress the state of recipes that are being crafted.
+///
+/// Crafters are advanced in parallel, as the manifests and other shared data are read-only during the tick.
+/// Changes to the [`EventLog`] and [`Population`] are collected and applied afterwards.
+pub(crate) fn progress_crafting(
+    context: CraftingContext,
+    mut crafting_query: Query<CraftingQuery, Without<Disabled>>,
+    mut event_log: ResMut<EventLog>,
+    mut population: ResMut<Population>,
+) {
+    let effects = Mutex::new(Vec::new());
+
+    crafting_query.par_iter_mut().for_each_mut(|mut crafter| {
+        if let Some(effect) = advance_crafter(&mut crafter, &context) {
+            effects.lock().unwrap().push(effect);
+        }
+    });
+
+    apply_crafting_effects(
+        effects.into_inner().unwrap(),
+        &mut crafting_query,
+        &context.recipe_manifest,
+        &mut event_log,
+        &mut population,
+    );
+}
+
+/// Progress the state of recipes that are being crafted, one crafter at a time.
+///
+/// This behaves exactly like [`progress_crafting`], and is kept as a baseline to benchmark it against.
+#[cfg(feature = "bench")]
+pub(crate) fn progress_crafting_serially(
+    context: CraftingContext,
+    mut crafting_query: Query<CraftingQuery, Without<Disabled>>,
+    mut event_log: ResMut<EventLog>,
+    mut population: ResMut<Population>,
+) {
+    let effects = crafting_query
+        .iter_mut()
+        .filter_map(|mut crafter| advance_crafter(&mut crafter, &context))
+        .collect();
+
+    apply_crafting_effects(
+        effects,
+        &mut crafting_query,
+        &context.recipe_manifest,
+        &mut event_log,
+        &mut population,
+    );
+}
+
 /// Sessile organisms gain energy when they finish crafting recipes.
 fn gain_energy_when_crafting_completes(
     mut sessile_query: Query<(
@@ -444,6 +889,188 @@ fn gain_energy_when_crafting_completes(
     }
 }
 
+/// Spawns the units and structures produced by recipes that have just completed.
+///
+/// These are placed on the free tile next to the crafter that is closest to the [`ColonyCenter`],
+/// and belong to the same [`Faction`] as the crafter.
+/// Ghosts that have just finished construction are skipped, as they have not crafted anything.
+fn spawn_recipe_organisms(
+    crafter_query: Query<
+        (&TilePos, &CraftingState, &ActiveRecipe, Option<&Faction>),
+        Without<Ghost>,
+    >,
+    recipe_manifest: Res<RecipeManifest>,
+    structure_manifest: Res<StructureManifest>,
+    map_geometry: Res<MapGeometry>,
+    colony_center: Res<ColonyCenter>,
+    mut commands: Commands,
+) {
+    for (tile_pos, crafting_state, active_recipe, maybe_faction) in crafter_query.iter() {
+        if !matches!(crafting_state, CraftingState::RecipeComplete) {
+            continue;
+        }
+
+        let Some(recipe_id) = active_recipe.recipe_id() else {
+            continue;
+        };
+
+        let Some(organism_id) = recipe_manifest.get(*recipe_id).spawns else {
+            continue;
+        };
+
+        let Some(pos_to_spawn) = tile_pos
+            .empty_neighbors(&map_geometry)
+            .into_iter()
+            .min_by_key(|&tile_pos| colony_center.distance_to(tile_pos))
+        else {
+            continue;
+        };
+
+        let faction = maybe_faction.copied().unwrap_or_default();
+        match organism_id {
+            OrganismId::Unit(unit_id) => {
+                commands.spawn_unit(pos_to_spawn, unit_id, Goal::default(), faction);
+            }
+            OrganismId::Structure(structure_id) => {
+                let data = ClipboardData::new(structure_id, &structure_manifest);
+                commands.spawn_structure(pos_to_spawn, data, faction);
+            }
+        }
+    }
+}
+
+/// Removes the mined items from the [`ResourceDeposit`] beneath structures that have just completed a mining recipe.
+///
+/// [`progress_crafting`] only starts these recipes when the deposit holds enough,
+/// so the crafter will stop with [`CraftingState::DepositExhausted`] once it runs dry.
+/// If the deposit was drained while the recipe was in progress, the recipe produces nothing.
+///
+/// Ghosts store the recipe that the finished structure will start with, so they are skipped:
+/// completing their construction is not a completed recipe.
+fn mine_resource_deposits(
+    mut crafter_query: Query<(&TilePos, &mut CraftingState, &ActiveRecipe), Without<Ghost>>,
+    mut deposit_query: Query<&mut ResourceDeposit>,
+    recipe_manifest: Res<RecipeManifest>,
+    map_geometry: Res<MapGeometry>,
+) {
+    for (&tile_pos, mut crafting_state, active_recipe) in crafter_query.iter_mut() {
+        if !matches!(*crafting_state, CraftingState::RecipeComplete) {
+            continue;
+        }
+
+        let Some(recipe_id) = active_recipe.recipe_id() else {
+            continue;
+        };
+
+        let Some(mined) = &recipe_manifest.get(*recipe_id).mined_deposit else {
+            continue;
+        };
+
+        let mined_successfully = map_geometry
+            .get_terrain(tile_pos)
+            .and_then(|terrain_entity| deposit_query.get_mut(terrain_entity).ok())
+            .is_some_and(|mut deposit| deposit.mine(mined));
+
+        // The finished goods are discarded before they are ever handed off
+        if !mined_successfully {
+            *crafting_state = CraftingState::DepositExhausted;
+        }
+    }
+}
+
+/// Reshapes the terrain beneath structures that have just completed a recipe with a [`TerrainEffect`].
+///
+/// The changed [`Height`] and [`Id<Terrain>`] components are picked up by the terrain systems,
+/// which keep the [`MapGeometry`] and the terrain's appearance in sync.
+///
+/// Effects that would leave the structure standing on terrain it could not be built on are skipped,
+/// as are ghosts that have just finished construction.
+///
+/// [`TerrainEffect`]: crate::items::recipe::TerrainEffect
+fn apply_terrain_effects(
+    crafter_query: Query<
+        (
+            &Id<Structure>,
+            &TilePos,
+            &Facing,
+            &CraftingState,
+            &ActiveRecipe,
+        ),
+        Without<Ghost>,
+    >,
+    mut terrain_query: Query<(&mut Height, &Id<Terrain>)>,
+    recipe_manifest: Res<RecipeManifest>,
+    structure_manifest: Res<StructureManifest>,
+    map_geometry: Res<MapGeometry>,
+    mut commands: Commands,
+) {
+    for (&structure_id, &center, &facing, crafting_state, active_recipe) in crafter_query.iter() {
+        if !matches!(crafting_state, CraftingState::RecipeComplete) {
+            continue;
+        }
+
+        let Some(recipe_id) = active_recipe.recipe_id() else {
+            continue;
+        };
+
+        let Some(terrain_effect) = &recipe_manifest.get(*recipe_id).terrain_effect else {
+            continue;
+        };
+
+        let structure_data = structure_manifest.get(structure_id);
+        if let Some(terrain_type) = terrain_effect.terrain_type {
+            if !structure_data
+                .allowed_terrain_types()
+                .contains(&terrain_type)
+            {
+                warn!(
+                    "Skipped the terrain effect of {}: {} cannot be built on the resulting terrain.",
+                    recipe_manifest.name(*recipe_id),
+                    structure_manifest.name(structure_id)
+                );
+                continue;
+            }
+        }
+
+        let footprint = structure_data.footprint.rotated(facing);
+        let changes: Option<Vec<(TilePos, Entity, Height)>> = footprint
+            .in_world_space(center)
+            .into_iter()
+            .map(|tile_pos| {
+                let terrain_entity = map_geometry.get_terrain(tile_pos)?;
+                let (height, _) = terrain_query.get(terrain_entity).ok()?;
+                let new_height = terrain_effect.apply_to_height(*height)?;
+                Some((tile_pos, terrain_entity, new_height))
+            })
+            .collect();
+
+        let Some(changes) = changes else {
+            warn!(
+                "Skipped the terrain effect of {}: the terrain beneath {} cannot be reshaped any further.",
+                recipe_manifest.name(*recipe_id),
+                structure_manifest.name(structure_id)
+            );
+            continue;
+        };
+
+        for (tile_pos, terrain_entity, new_height) in changes {
+            let (mut height, &terrain_id) = terrain_query.get_mut(terrain_entity).unwrap();
+            if *height != new_height {
+                *height = new_height;
+            }
+
+            if let Some(terrain_type) = terrain_effect.terrain_type {
+                if terrain_id != terrain_type {
+                    commands.add(ChangeTerrainCommand {
+                        tile_pos,
+                        terrain_id: terrain_type,
+                    });
+                }
+            }
+        }
+    }
+}
+
 /// Causes crafting structures to emit signals based on the items they have and need.
 pub(crate) fn set_crafting_emitter(
     mut crafting_query: Query<(
@@ -454,6 +1081,7 @@ pub(crate) fn set_crafting_emitter(
         &Id<Structure>,
         &WorkersPresent,
         &ActiveRecipe,
+        Option<&Disabled>,
     )>,
     recipe_manifest: Res<RecipeManifest>,
 ) {
@@ -465,14 +1093,24 @@ pub(crate) fn set_crafting_emitter(
         &structure_id,
         workers_present,
         active_recipe,
+        maybe_disabled,
     ) in crafting_query.iter_mut()
     {
         // Reset and recompute all signals
         emitter.signals.clear();
 
+        // Paused structures should be ignored by units entirely
+        if maybe_disabled.is_some() {
+            continue;
+        }
+
         // Input signals
         for item_slot in input_inventory.iter() {
-            if !item_slot.is_full() {
+            if item_slot.is_full() {
+                let signal_type = SignalType::Contained(item_slot.item_id());
+                let signal_strength = SignalStrength::new(10.);
+                emitter.signals.push((signal_type, signal_strength));
+            } else {
                 let signal_type = SignalType::Pull(item_slot.item_id());
                 let signal_strength = SignalStrength::new(10.);
                 emitter.signals.push((signal_type, signal_strength));
@@ -516,41 +1154,99 @@ pub(crate) fn set_storage_emitter(
         // Reset and recompute all signals
         emitter.signals.clear();
 
-        match storage_inventory.reserved_for() {
-            // Item-specific storage
-            Some(item_id) => {
-                // If there's space, signal that
-                if storage_inventory.remaining_space_for_item(item_id, &item_manifest) > 0 {
-                    let signal_type = SignalType::Stores(item_id);
-                    let signal_strength = SignalStrength::new(10.);
-                    emitter.signals.push((signal_type, signal_strength));
+        // Junk drawers could hold anything, while reserved storage only advertises the items it accepts
+        let item_ids: Vec<Id<Item>> = match storage_inventory.reserved_for() {
+            ReservedFor::Any => item_manifest.variants().into_iter().collect(),
+            reserved_for => reserved_for.items().collect(),
+        };
+
+        for item_id in item_ids {
+            // If there's space for a unit to drop off its load, signal that
+            if storage_inventory.can_fit(&UnitInventory::load(item_id), &item_manifest) {
+                let signal_type = SignalType::Stores(item_id);
+                let signal_strength = SignalStrength::new(10.);
+                emitter.signals.push((signal_type, signal_strength));
+            }
+
+            // If there's any inventory, signal that
+            if storage_inventory.item_count(item_id) > 0 {
+                let signal_type = SignalType::Contains(item_id);
+                let signal_strength = SignalStrength::new(10.);
+                emitter.signals.push((signal_type, signal_strength));
+
+                // The fuller we are, the less attractive we should be to units with items to store
+                let signal_type = SignalType::Contained(item_id);
+                let signal_strength =
+                    SignalStrength::new(10. * storage_inventory.fullness(item_id, &item_manifest));
+                emitter.signals.push((signal_type, signal_strength));
+            }
+        }
+
+        // The filter may have changed after other items were stored: get rid of them
+        for item_slot in storage_inventory.iter() {
+            if !storage_inventory.permits(item_slot.item_id()) && !item_slot.is_empty() {
+                let signal_type = SignalType::Push(item_slot.item_id());
+                let signal_strength = SignalStrength::new(10.);
+                emitter.signals.push((signal_type, signal_strength));
+            }
+        }
+    }
+}
+
+/// The amount of fluid that can flow out of a structure and into each of its neighbors per second.
+const PIPE_FLOW_RATE: f32 = 1.;
+
+/// Pipes fluids out of the [`OutputFluids`] of each structure, and into the tanks of the structures next to it.
+///
+/// Units never carry fluids, so this is the only way for a [`FluidInventory`] to be filled.
+/// Fluids flow continuously: each neighbor can receive up to [`PIPE_FLOW_RATE`] per second, including fractional amounts.
+fn pipe_fluids(
+    mut source_query: Query<(Entity, &TilePos, &mut OutputFluids)>,
+    mut tank_query: Query<&mut FluidInventory>,
+    map_geometry: Res<MapGeometry>,
+    fixed_time: Res<FixedTime>,
+) {
+    let max_flow = PIPE_FLOW_RATE * fixed_time.period.as_secs_f32();
+
+    for (source_entity, &tile_pos, mut output_fluids) in source_query.iter_mut() {
+        let fluids: Vec<FluidAmount> = output_fluids
+            .contents()
+            .filter(|fluid_amount| fluid_amount.amount() > 0.)
+            .collect();
+
+        for fluid_amount in fluids {
+            let item_id = fluid_amount.item_id();
+            let mut remaining = fluid_amount.amount();
+
+            for neighbor in tile_pos.all_neighbors(&map_geometry) {
+                if remaining <= 0. {
+                    break;
                 }
 
-                // If there's any inventory, signal that
-                if storage_inventory.item_count(item_id) > 0 {
-                    let signal_type = SignalType::Contains(item_id);
-                    let signal_strength = SignalStrength::new(10.);
-                    emitter.signals.push((signal_type, signal_strength));
+                let Some(neighbor_entity) = map_geometry.get_structure(neighbor) else {
+                    continue;
+                };
+                if neighbor_entity == source_entity {
+                    continue;
                 }
-            }
-            // Junk drawer
-            None => {
-                // You could put anything in here!
-                for item_id in item_manifest.variants() {
-                    // If there's space, signal that
-                    if storage_inventory.remaining_space_for_item(item_id, &item_manifest) > 0 {
-                        let signal_type = SignalType::Stores(item_id);
-                        let signal_strength = SignalStrength::new(10.);
-                        emitter.signals.push((signal_type, signal_strength));
-                    }
+                let Ok(mut fluid_inventory) = tank_query.get_mut(neighbor_entity) else {
+                    continue;
+                };
 
-                    // If there's any inventory, signal that
-                    if storage_inventory.item_count(item_id) > 0 {
-                        let signal_type = SignalType::Contains(item_id);
-                        let signal_strength = SignalStrength::new(10.);
-                        emitter.signals.push((signal_type, signal_strength));
-                    }
+                let piped = remaining
+                    .min(max_flow)
+                    .min(fluid_inventory.free_capacity(item_id));
+                if piped <= 0. {
+                    continue;
                 }
+
+                let piped_amount = FluidAmount::new(item_id, piped);
+                // We just checked the free capacity and the amount available, so neither of these can fail
+                fluid_inventory.add_fluid(piped_amount).unwrap();
+                output_fluids
+                    .remove_fluids_all_or_nothing(&[piped_amount])
+                    .unwrap();
+                remaining -= piped;
             }
         }
     }
@@ -574,8 +1270,12 @@ pub(crate) struct WorkplaceQuery<'w, 's> {
             &'static CraftingState,
             &'static Id<Structure>,
             &'static WorkersPresent,
+            Option<&'static ActiveRecipe>,
         ),
+        Without<Disabled>,
     >,
+    /// Units won't work on recipes that the colony hasn't unlocked yet.
+    tech_tree: Res<'w, TechTree>,
 }
 
 impl<'w, 's> WorkplaceQuery<'w, 's> {
@@ -589,19 +1289,30 @@ impl<'w, 's> WorkplaceQuery<'w, 's> {
         map_geometry: &MapGeometry,
     ) -> Option<Entity> {
         // Prioritize ghosts over structures to allow for replacing structures by building
-        let entity = if let Some(ghost_entity) = map_geometry.get_ghost(structure_pos) {
+        let maybe_ghost = map_geometry.get_ghost(structure_pos);
+        let entity = if let Some(ghost_entity) = maybe_ghost {
             ghost_entity
         } else {
             map_geometry.get_structure(structure_pos)?
         };
 
-        let (found_crafting_state, found_structure_id, workers_present) =
+        let (found_crafting_state, found_structure_id, workers_present, maybe_active_recipe) =
             self.query.get(entity).ok()?;
 
         if *found_structure_id != structure_id {
             return None;
         }
 
+        // Ghosts store the recipe that the finished structure will start with, but constructing them is always allowed
+        if let (None, Some(Some(recipe_id))) = (
+            maybe_ghost,
+            maybe_active_recipe.map(ActiveRecipe::recipe_id),
+        ) {
+            if !self.tech_tree.is_unlocked(*recipe_id) {
+                return None;
+            }
+        }
+
         if let CraftingState::InProgress { .. } = found_crafting_state {
             if workers_present.needs_more() {
                 Some(entity)
@@ -621,16 +1332,1367 @@ impl Plugin for CraftingPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(ManifestPlugin::<RawItemManifest>::new())
             .add_plugin(ManifestPlugin::<RawRecipeManifest>::new())
+            .init_resource::<TechTree>()
             .add_systems(
                 (
-                    progress_crafting,
-                    gain_energy_when_crafting_completes.after(progress_crafting),
+                    update_tech_tree.before(progress_crafting),
+                    progress_crafting.after(distribute_power),
+                    // Failed mining cancels the completed recipe, so it must be checked before anything else reacts to it
+                    mine_resource_deposits.after(progress_crafting),
+                    gain_energy_when_crafting_completes.after(mine_resource_deposits),
+                    apply_terrain_effects.after(mine_resource_deposits),
+                    spawn_recipe_organisms.after(mine_resource_deposits),
                     set_crafting_emitter.after(progress_crafting),
                     set_storage_emitter,
                     clear_empty_storage_slots,
+                    pipe_fluids.after(progress_crafting),
                 )
                     .in_set(SimulationSet)
                     .in_schedule(CoreSchedule::FixedUpdate),
             );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        items::{
+            item_manifest::ItemData,
+            recipe::{RecipeConditions, RecipeData, RecipeInput, TerrainEffect},
+        },
+        structures::{
+            construction::Footprint,
+            power::{EnergyGrid, PowerGenerator},
+            structure_manifest::{ConstructionStrategy, StructureData, StructureKind},
+        },
+        units::{
+            population::count_population,
+            test_fixture::UnitTestApp,
+            unit_manifest::{Unit, UnitManifest},
+        },
+    };
+    use bevy::utils::HashSet;
+
+    #[test]
+    fn unpowered_consumers_do_not_craft() {
+        let recipe_id = Id::from_name("powered_recipe");
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "powered_recipe",
+            RecipeData {
+                craft_time: Duration::from_secs(10),
+                ..Default::default()
+            },
+        );
+
+        let mut app = parallel_crafting_app(recipe_manifest, Population::default());
+        app.init_resource::<EnergyGrid>()
+            .add_system(distribute_power.before(progress_crafting));
+
+        app.world.spawn(PowerGenerator::new(10.));
+        let consumers: Vec<Entity> = (0..2)
+            .map(|_| {
+                app.world
+                    .spawn((
+                        PowerConsumer::new(6.),
+                        ActiveRecipe::new(recipe_id),
+                        CraftingState::InProgress {
+                            progress: Duration::ZERO,
+                            required: Duration::from_secs(10),
+                        },
+                        InputInventory::default(),
+                        OutputInventory::default(),
+                        WorkersPresent::new(0),
+                        TilePos::new(0, 0),
+                    ))
+                    .id()
+            })
+            .collect();
+
+        app.update();
+
+        let crafting_count = consumers
+            .iter()
+            .filter(|&&entity| {
+                matches!(
+                    app.world.get::<CraftingState>(entity).unwrap(),
+                    CraftingState::InProgress { progress, .. } if *progress > Duration::ZERO
+                )
+            })
+            .count();
+        assert_eq!(crafting_count, 1);
+    }
+
+    #[test]
+    fn delivered_fraction_counts_partial_deliveries() {
+        let leuco_chunk = Id::from_name("leuco_chunk");
+        let acacia_leaf = Id::from_name("acacia_leaf");
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(3));
+        item_manifest.insert("acacia_leaf", ItemData::new(2));
+
+        // Construction materials: 3 leuco chunks and 2 acacia leaves
+        let mut materials = InputInventory {
+            inventory: Inventory::new(2, ReservedFor::Any),
+        };
+        materials.add_empty_slot(leuco_chunk, &item_manifest);
+        materials.add_empty_slot(acacia_leaf, &item_manifest);
+        assert_eq!(materials.delivered_fraction(), 0.0);
+
+        materials
+            .try_add_item(&ItemCount::new(leuco_chunk, 1), &item_manifest)
+            .unwrap();
+        materials
+            .try_add_item(&ItemCount::new(acacia_leaf, 1), &item_manifest)
+            .unwrap();
+        assert_eq!(materials.delivered_fraction(), 0.4);
+
+        materials
+            .try_add_item(&ItemCount::new(leuco_chunk, 2), &item_manifest)
+            .unwrap();
+        materials
+            .try_add_item(&ItemCount::new(acacia_leaf, 1), &item_manifest)
+            .unwrap();
+        assert_eq!(materials.delivered_fraction(), 1.0);
+
+        // Ghosts that need no materials are immediately ready to build
+        assert_eq!(InputInventory::default().delivered_fraction(), 1.0);
+    }
+
+    #[test]
+    fn disabled_structures_pause_crafting() {
+        let mut app = App::new();
+        let recipe_id = Id::from_name("slow_recipe");
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "slow_recipe",
+            RecipeData {
+                craft_time: Duration::from_secs(10),
+                ..Default::default()
+            },
+        );
+
+        app.insert_resource(recipe_manifest)
+            .insert_resource(ItemManifest::new())
+            .insert_resource(FixedTime::new_from_secs(1.))
+            .init_resource::<TotalLight>()
+            .init_resource::<TechTree>()
+            .init_resource::<Season>()
+            .init_resource::<EventLog>()
+            .init_resource::<Population>()
+            .insert_resource(MapGeometry::new(1))
+            .add_system(progress_crafting);
+
+        let paused_progress = Duration::from_secs(3);
+        let crafter = app
+            .world
+            .spawn((
+                ActiveRecipe::new(recipe_id),
+                CraftingState::InProgress {
+                    progress: paused_progress,
+                    required: Duration::from_secs(10),
+                },
+                InputInventory::default(),
+                OutputInventory::default(),
+                WorkersPresent::new(0),
+                TilePos::new(0, 0),
+                Disabled,
+            ))
+            .id();
+
+        for _ in 0..100 {
+            app.update();
+        }
+
+        assert_eq!(
+            app.world.get::<CraftingState>(crafter).unwrap(),
+            &CraftingState::InProgress {
+                progress: paused_progress,
+                required: Duration::from_secs(10),
+            }
+        );
+
+        app.world.entity_mut(crafter).remove::<Disabled>();
+        app.update();
+
+        assert_eq!(
+            app.world.get::<CraftingState>(crafter).unwrap(),
+            &CraftingState::InProgress {
+                progress: paused_progress + Duration::from_secs(1),
+                required: Duration::from_secs(10),
+            }
+        );
+    }
+
+    #[test]
+    fn hatching_stalls_while_the_population_is_capped() {
+        let mut app = App::new();
+        let recipe_id = Id::from_name("hatch_ants");
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "hatch_ants",
+            RecipeData {
+                craft_time: Duration::from_secs(1),
+                spawns: Some(OrganismId::Unit(Id::from_name("ant"))),
+                ..Default::default()
+            },
+        );
+
+        app.insert_resource(recipe_manifest)
+            .insert_resource(ItemManifest::new())
+            .insert_resource(UnitManifest::new())
+            .insert_resource(FixedTime::new_from_secs(1.))
+            .init_resource::<TotalLight>()
+            .init_resource::<TechTree>()
+            .init_resource::<Season>()
+            .init_resource::<EventLog>()
+            .insert_resource(Population::new(1))
+            .insert_resource(MapGeometry::new(1))
+            .add_systems((count_population, progress_crafting).chain());
+
+        let ant = app.world.spawn(Id::<Unit>::from_name("ant")).id();
+        let hatchery = app
+            .world
+            .spawn((
+                ActiveRecipe::new(recipe_id),
+                CraftingState::InProgress {
+                    progress: Duration::ZERO,
+                    required: Duration::from_secs(1),
+                },
+                InputInventory::default(),
+                OutputInventory::default(),
+                WorkersPresent::new(1),
+                TilePos::new(0, 0),
+            ))
+            .id();
+
+        for _ in 0..10 {
+            app.update();
+        }
+
+        assert_eq!(
+            app.world.get::<CraftingState>(hatchery).unwrap(),
+            &CraftingState::PopulationCapped
+        );
+
+        app.world.despawn(ant);
+        app.update();
+
+        assert_eq!(
+            app.world.get::<CraftingState>(hatchery).unwrap(),
+            &CraftingState::RecipeComplete
+        );
+    }
+
+    /// Creates an app that advances crafters with [`progress_crafting`], without any of them to start with.
+    fn parallel_crafting_app(recipe_manifest: RecipeManifest, population: Population) -> App {
+        let mut app = App::new();
+        app.insert_resource(recipe_manifest)
+            .insert_resource(ItemManifest::new())
+            .insert_resource(FixedTime::new_from_secs(1.))
+            .init_resource::<TotalLight>()
+            .init_resource::<TechTree>()
+            .init_resource::<Season>()
+            .init_resource::<EventLog>()
+            .insert_resource(population)
+            .insert_resource(MapGeometry::new(1))
+            .add_system(progress_crafting);
+        app
+    }
+
+    #[test]
+    fn parallel_crafting_reserves_population_in_crafter_order() {
+        let recipe_id = Id::from_name("hatch_ants");
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "hatch_ants",
+            RecipeData {
+                craft_time: Duration::from_secs(1),
+                spawns: Some(OrganismId::Unit(Id::from_name("ant"))),
+                ..Default::default()
+            },
+        );
+        let mut app = parallel_crafting_app(recipe_manifest, Population::new(5));
+
+        let hatcheries: Vec<Entity> = (0..100)
+            .map(|_| {
+                app.world
+                    .spawn((
+                        ActiveRecipe::new(recipe_id),
+                        CraftingState::InProgress {
+                            progress: Duration::ZERO,
+                            required: Duration::from_secs(1),
+                        },
+                        InputInventory::default(),
+                        OutputInventory::default(),
+                        WorkersPresent::new(1),
+                        TilePos::ZERO,
+                    ))
+                    .id()
+            })
+            .collect();
+
+        app.update();
+
+        // Whichever thread gets there first, the first crafters claim the free population slots
+        for (i, &entity) in hatcheries.iter().enumerate() {
+            let expected = match i < 5 {
+                true => CraftingState::RecipeComplete,
+                false => CraftingState::PopulationCapped,
+            };
+            assert_eq!(*app.world.get::<CraftingState>(entity).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn parallel_crafting_logs_completions_in_crafter_order() {
+        let recipe_id = Id::from_name("busywork");
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "busywork",
+            RecipeData {
+                craft_time: Duration::from_secs(1),
+                ..Default::default()
+            },
+        );
+        let mut app = parallel_crafting_app(recipe_manifest, Population::default());
+
+        // Each crafter sits on its own tile, so its log entries can be told apart
+        let tiles: Vec<TilePos> = hexx::shapes::hexagon(hexx::Hex::ZERO, 1)
+            .map(|hex| TilePos { hex })
+            .collect();
+        for &tile_pos in &tiles {
+            app.world.spawn((
+                ActiveRecipe::new(recipe_id),
+                CraftingState::RecipeComplete,
+                InputInventory::default(),
+                OutputInventory::default(),
+                WorkersPresent::new(1),
+                tile_pos,
+            ));
+        }
+
+        app.update();
+
+        // The log is read from newest to oldest
+        let mut logged_tiles: Vec<TilePos> = app
+            .world
+            .resource::<EventLog>()
+            .iter()
+            .filter_map(|entry| entry.event.tile_pos())
+            .collect();
+        logged_tiles.reverse();
+        assert_eq!(logged_tiles, tiles);
+    }
+
+    #[test]
+    fn recipes_only_progress_on_required_terrain() {
+        let mut app = App::new();
+        let recipe_id = Id::from_name("well_water");
+        let muddy = Id::from_name("muddy");
+        let rocky = Id::from_name("rocky");
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "well_water",
+            RecipeData {
+                craft_time: Duration::from_secs(10),
+                conditions: RecipeConditions {
+                    required_terrain: Some(HashSet::from_iter([muddy])),
+                    ..RecipeConditions::NONE
+                },
+                ..Default::default()
+            },
+        );
+
+        let mut map_geometry = MapGeometry::new(1);
+        let muddy_tile = TilePos::new(0, 0);
+        let rocky_tile = TilePos::new(1, 0);
+        for (tile_pos, terrain_id) in [(muddy_tile, muddy), (rocky_tile, rocky)] {
+            let terrain_entity = app.world.spawn(terrain_id).id();
+            map_geometry.add_terrain(tile_pos, terrain_entity);
+        }
+
+        app.insert_resource(recipe_manifest)
+            .insert_resource(ItemManifest::new())
+            .insert_resource(FixedTime::new_from_secs(1.))
+            .init_resource::<TotalLight>()
+            .init_resource::<TechTree>()
+            .init_resource::<Season>()
+            .init_resource::<EventLog>()
+            .init_resource::<Population>()
+            .insert_resource(map_geometry)
+            .add_system(progress_crafting);
+
+        let mut spawn_crafter = |tile_pos: TilePos| {
+            app.world
+                .spawn((
+                    ActiveRecipe::new(recipe_id),
+                    CraftingState::InProgress {
+                        progress: Duration::ZERO,
+                        required: Duration::from_secs(10),
+                    },
+                    InputInventory::default(),
+                    OutputInventory::default(),
+                    WorkersPresent::new(0),
+                    tile_pos,
+                ))
+                .id()
+        };
+        let valid_crafter = spawn_crafter(muddy_tile);
+        let invalid_crafter = spawn_crafter(rocky_tile);
+
+        app.update();
+
+        let progress = |entity: Entity| match app.world.get::<CraftingState>(entity).unwrap() {
+            CraftingState::InProgress { progress, .. } => *progress,
+            other => panic!("Unexpected crafting state {other:?}"),
+        };
+        assert!(progress(valid_crafter) > Duration::ZERO);
+        assert_eq!(progress(invalid_crafter), Duration::ZERO);
+    }
+
+    #[test]
+    fn production_stats_count_crafted_items() {
+        let mut app = App::new();
+        let recipe_id = Id::from_name("leaf_press");
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "leaf_press",
+            RecipeData {
+                outputs: vec![ItemCount::new(Id::from_name("leaf"), 2)],
+                craft_time: Duration::from_secs(1),
+                ..Default::default()
+            },
+        );
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leaf", ItemData::new(100));
+        let output_inventory = recipe_manifest
+            .get(recipe_id)
+            .output_inventory(&item_manifest);
+
+        app.insert_resource(recipe_manifest)
+            .insert_resource(item_manifest)
+            .insert_resource(FixedTime::new_from_secs(1.))
+            .init_resource::<TotalLight>()
+            .init_resource::<TechTree>()
+            .init_resource::<Season>()
+            .init_resource::<EventLog>()
+            .init_resource::<Population>()
+            .insert_resource(MapGeometry::new(1))
+            .add_systems((update_tech_tree, progress_crafting).chain());
+
+        let crafter = app
+            .world
+            .spawn((
+                ActiveRecipe::new(recipe_id),
+                CraftingState::NeedsInput,
+                InputInventory::default(),
+                output_inventory,
+                WorkersPresent::new(0),
+                TilePos::new(0, 0),
+                ProductionStats::default(),
+            ))
+            .id();
+
+        // Starting, crafting and emptying out each recipe takes one tick each
+        for _ in 0..9 {
+            app.update();
+        }
+
+        let production_stats = app.world.get::<ProductionStats>(crafter).unwrap();
+        assert_eq!(production_stats.items_produced(), 6);
+        assert_eq!(
+            app.world
+                .get::<OutputInventory>(crafter)
+                .unwrap()
+                .item_count(Id::from_name("leaf")),
+            6
+        );
+        assert!((production_stats.uptime() - 1. / 3.).abs() < 1e-4);
+        assert!((production_stats.throughput() - 40.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn full_storage_suppresses_deliveries() {
+        let leaf = Id::from_name("leaf");
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leaf", ItemData::new(10));
+
+        let mut app = App::new();
+        app.insert_resource(item_manifest)
+            .add_system(set_storage_emitter);
+
+        let storage_entity = app
+            .world
+            .spawn((
+                Emitter::default(),
+                StorageInventory::new(1, ReservedFor::Only(leaf)),
+            ))
+            .id();
+
+        let signal_strength = |app: &App, signal_type: SignalType| {
+            app.world
+                .get::<Emitter>(storage_entity)
+                .unwrap()
+                .signals
+                .iter()
+                .find(|(emitted_type, _)| *emitted_type == signal_type)
+                .map(|(_, signal_strength)| *signal_strength)
+        };
+
+        app.update();
+        assert_eq!(
+            signal_strength(&app, SignalType::Stores(leaf)),
+            Some(SignalStrength::new(10.))
+        );
+        assert_eq!(signal_strength(&app, SignalType::Contained(leaf)), None);
+
+        app.world
+            .resource_scope(|world, item_manifest: Mut<ItemManifest>| {
+                world
+                    .get_mut::<StorageInventory>(storage_entity)
+                    .unwrap()
+                    .add_item_all_or_nothing(&ItemCount::new(leaf, 10), &item_manifest)
+            })
+            .unwrap();
+        app.update();
+        assert_eq!(signal_strength(&app, SignalType::Stores(leaf)), None);
+        assert_eq!(
+            signal_strength(&app, SignalType::Contained(leaf)),
+            Some(SignalStrength::new(10.))
+        );
+    }
+
+    #[test]
+    fn recipes_use_whichever_alternative_input_is_stocked() {
+        let recipe_id = Id::from_name("leaf_mulch");
+        let acacia_leaf = Id::from_name("acacia_leaf");
+        let fern_leaf = Id::from_name("fern_leaf");
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "leaf_mulch",
+            RecipeData {
+                inputs: vec![RecipeInput::new(vec![acacia_leaf, fern_leaf], 2)],
+                craft_time: Duration::from_secs(10),
+                ..Default::default()
+            },
+        );
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("acacia_leaf", ItemData::new(5));
+        item_manifest.insert("fern_leaf", ItemData::new(5));
+
+        // Only the second alternative is available
+        let mut input_inventory = recipe_manifest
+            .get(recipe_id)
+            .input_inventory(&item_manifest);
+        input_inventory
+            .add_item_all_or_nothing(&ItemCount::new(fern_leaf, 3), &item_manifest)
+            .unwrap();
+
+        let mut app = App::new();
+        app.insert_resource(recipe_manifest)
+            .insert_resource(item_manifest)
+            .insert_resource(FixedTime::new_from_secs(1.))
+            .init_resource::<TotalLight>()
+            .init_resource::<TechTree>()
+            .init_resource::<Season>()
+            .init_resource::<EventLog>()
+            .init_resource::<Population>()
+            .insert_resource(MapGeometry::new(1))
+            .add_systems((update_tech_tree, progress_crafting).chain());
+
+        let crafter = app
+            .world
+            .spawn((
+                ActiveRecipe::new(recipe_id),
+                CraftingState::NeedsInput,
+                input_inventory,
+                OutputInventory::default(),
+                WorkersPresent::new(0),
+                TilePos::new(0, 0),
+            ))
+            .id();
+
+        app.update();
+
+        assert!(matches!(
+            app.world.get::<CraftingState>(crafter).unwrap(),
+            CraftingState::InProgress { .. }
+        ));
+        let input_inventory = app.world.get::<InputInventory>(crafter).unwrap();
+        assert_eq!(input_inventory.item_count(fern_leaf), 1);
+        assert_eq!(input_inventory.item_count(acacia_leaf), 0);
+    }
+
+    #[test]
+    fn recipes_consume_fractional_fluid_inputs() {
+        let recipe_id = Id::from_name("leaf_soup");
+        let acacia_leaf = Id::from_name("acacia_leaf");
+        let water = Id::from_name("water");
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "leaf_soup",
+            RecipeData {
+                inputs: vec![RecipeInput::one(acacia_leaf)],
+                fluid_inputs: vec![FluidAmount::new(water, 0.5)],
+                craft_time: Duration::from_secs(10),
+                ..Default::default()
+            },
+        );
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("acacia_leaf", ItemData::new(5));
+        item_manifest.insert("water", ItemData::new_fluid(2));
+
+        let recipe = recipe_manifest.get(recipe_id);
+        let mut input_inventory = recipe.input_inventory(&item_manifest);
+        input_inventory
+            .add_item_all_or_nothing(&ItemCount::new(acacia_leaf, 2), &item_manifest)
+            .unwrap();
+        let mut fluid_inventory = recipe.fluid_inventory(&item_manifest);
+        fluid_inventory
+            .add_fluid(FluidAmount::new(water, 0.75))
+            .unwrap();
+
+        let mut app = App::new();
+        app.insert_resource(recipe_manifest)
+            .insert_resource(item_manifest)
+            .insert_resource(FixedTime::new_from_secs(10.))
+            .init_resource::<TotalLight>()
+            .init_resource::<TechTree>()
+            .init_resource::<Season>()
+            .init_resource::<EventLog>()
+            .init_resource::<Population>()
+            .insert_resource(MapGeometry::new(1))
+            .add_systems((update_tech_tree, progress_crafting).chain());
+
+        let crafter = app
+            .world
+            .spawn((
+                ActiveRecipe::new(recipe_id),
+                CraftingState::NeedsInput,
+                input_inventory,
+                fluid_inventory,
+                OutputInventory::default(),
+                WorkersPresent::new(0),
+                TilePos::new(0, 0),
+            ))
+            .id();
+
+        // The first batch uses half of the water
+        app.update();
+        assert!(matches!(
+            app.world.get::<CraftingState>(crafter).unwrap(),
+            CraftingState::InProgress { .. }
+        ));
+        assert_eq!(
+            app.world
+                .get::<FluidInventory>(crafter)
+                .unwrap()
+                .amount(water),
+            0.25
+        );
+        assert_eq!(
+            app.world
+                .get::<InputInventory>(crafter)
+                .unwrap()
+                .item_count(acacia_leaf),
+            1
+        );
+
+        // Finish crafting, then try again
+        app.update();
+        app.update();
+        app.update();
+
+        // There are still leaves, but not enough water, so nothing is consumed
+        assert_eq!(
+            *app.world.get::<CraftingState>(crafter).unwrap(),
+            CraftingState::NeedsInput
+        );
+        assert_eq!(
+            app.world
+                .get::<FluidInventory>(crafter)
+                .unwrap()
+                .amount(water),
+            0.25
+        );
+        assert_eq!(
+            app.world
+                .get::<InputInventory>(crafter)
+                .unwrap()
+                .item_count(acacia_leaf),
+            1
+        );
+    }
+
+    #[test]
+    fn units_only_work_on_unlocked_recipes() {
+        use bevy::ecs::system::SystemState;
+
+        let structure_id = Id::<Structure>::from_name("leuco");
+        let recipe_id = Id::<Recipe>::from_name("leuco_chunk_production");
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "leuco_chunk_production",
+            RecipeData {
+                craft_time: Duration::from_secs(1),
+                ..Default::default()
+            },
+        );
+
+        let mut world = World::new();
+        let structure_entity = world
+            .spawn((
+                structure_id,
+                ActiveRecipe::new(recipe_id),
+                CraftingState::InProgress {
+                    progress: Duration::ZERO,
+                    required: Duration::from_secs(1),
+                },
+                WorkersPresent::new(1),
+            ))
+            .id();
+        let mut map_geometry = MapGeometry::new(1);
+        map_geometry.add_structure(
+            TilePos::ZERO,
+            &Footprint::single(),
+            structure_id,
+            structure_entity,
+        );
+        world.insert_resource(map_geometry);
+        world.init_resource::<TechTree>();
+
+        let mut system_state: SystemState<(WorkplaceQuery, Res<MapGeometry>)> =
+            SystemState::new(&mut world);
+        let (workplace_query, map_geometry) = system_state.get(&world);
+        assert_eq!(
+            workplace_query.needs_work(TilePos::ZERO, structure_id, &map_geometry),
+            None
+        );
+
+        world
+            .resource_mut::<TechTree>()
+            .unlock_available(&recipe_manifest);
+        let (workplace_query, map_geometry) = system_state.get(&world);
+        assert_eq!(
+            workplace_query.needs_work(TilePos::ZERO, structure_id, &map_geometry),
+            Some(structure_entity)
+        );
+    }
+
+    #[test]
+    fn recipes_produce_fractional_fluid_outputs() {
+        let recipe_id = Id::from_name("draw_water");
+        let water = Id::from_name("water");
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "draw_water",
+            RecipeData {
+                fluid_outputs: vec![FluidAmount::new(water, 0.75)],
+                craft_time: Duration::from_secs(1),
+                ..Default::default()
+            },
+        );
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("water", ItemData::new_fluid(2));
+        let output_fluids = recipe_manifest.get(recipe_id).output_fluids(&item_manifest);
+
+        let mut app = parallel_crafting_app(recipe_manifest, Population::default());
+        app.insert_resource(item_manifest)
+            .add_system(update_tech_tree.before(progress_crafting));
+
+        let well = app
+            .world
+            .spawn((
+                ActiveRecipe::new(recipe_id),
+                CraftingState::NeedsInput,
+                InputInventory::default(),
+                OutputInventory::default(),
+                output_fluids,
+                WorkersPresent::new(0),
+                TilePos::new(0, 0),
+            ))
+            .id();
+
+        // Starting, finishing and handing off each batch takes three ticks
+        for expected in [0.75, 1.5] {
+            for _ in 0..3 {
+                app.update();
+            }
+            assert_eq!(
+                app.world.get::<OutputFluids>(well).unwrap().amount(water),
+                expected
+            );
+        }
+
+        // The tank can't hold another batch, so the well waits for it to be drained
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(
+            *app.world.get::<CraftingState>(well).unwrap(),
+            CraftingState::FullAndBlocked
+        );
+        assert_eq!(
+            app.world.get::<OutputFluids>(well).unwrap().amount(water),
+            1.5
+        );
+    }
+
+    #[test]
+    fn fluids_are_piped_into_adjacent_tanks() {
+        let water = Id::from_name("water");
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("water", ItemData::new_fluid(2));
+
+        let mut output_fluids = OutputFluids::default();
+        output_fluids.add_empty_tank(water, &item_manifest);
+        output_fluids
+            .add_fluid(FluidAmount::new(water, 0.75))
+            .unwrap();
+        let mut fluid_inventory = FluidInventory::default();
+        fluid_inventory.add_empty_tank(water, &item_manifest);
+        fluid_inventory
+            .add_fluid(FluidAmount::new(water, 0.5))
+            .unwrap();
+
+        let mut app = App::new();
+        let well_pos = TilePos::ZERO;
+        let tank_pos = TilePos::new(1, 0);
+        let well = app.world.spawn((well_pos, output_fluids)).id();
+        let tank = app.world.spawn((tank_pos, fluid_inventory)).id();
+
+        let mut map_geometry = MapGeometry::new(2);
+        map_geometry.add_structure(well_pos, &Footprint::single(), Id::from_name("well"), well);
+        map_geometry.add_structure(tank_pos, &Footprint::single(), Id::from_name("tank"), tank);
+
+        // Half a unit of fluid can flow through each pipe every tick
+        app.insert_resource(map_geometry)
+            .insert_resource(FixedTime::new_from_secs(0.5 / PIPE_FLOW_RATE))
+            .add_system(pipe_fluids);
+
+        app.update();
+        assert_eq!(
+            app.world.get::<FluidInventory>(tank).unwrap().amount(water),
+            1.
+        );
+        assert_eq!(
+            app.world.get::<OutputFluids>(well).unwrap().amount(water),
+            0.25
+        );
+
+        // The remaining fraction of a unit is piped as well
+        app.update();
+        assert_eq!(
+            app.world.get::<FluidInventory>(tank).unwrap().amount(water),
+            1.25
+        );
+        assert_eq!(
+            app.world.get::<OutputFluids>(well).unwrap().amount(water),
+            0.
+        );
+    }
+
+    #[test]
+    fn mining_recipes_lower_the_terrain() {
+        let mut app = App::new();
+        let quarry = Id::<Structure>::from_name("quarry");
+        let rocky = Id::<Terrain>::from_name("rocky");
+        let recipe_id = Id::from_name("mine_stone");
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "mine_stone",
+            RecipeData {
+                craft_time: Duration::from_secs(1),
+                terrain_effect: Some(TerrainEffect {
+                    height_change: -1,
+                    terrain_type: None,
+                }),
+                ..Default::default()
+            },
+        );
+
+        let mut structure_manifest = StructureManifest::new();
+        structure_manifest.insert(
+            "quarry",
+            StructureData {
+                kind: StructureKind::Crafting {
+                    starting_recipe: ActiveRecipe::new(recipe_id),
+                    output_direction: None,
+                },
+                construction_strategy: ConstructionStrategy {
+                    seedling: None,
+                    work: Duration::ZERO,
+                    materials: InputInventory::default(),
+                    allowed_terrain_types: HashSet::from_iter([rocky]),
+                    required_adjacency: HashSet::new(),
+                },
+                ..Default::default()
+            },
+        );
+
+        let tile_pos = TilePos::ZERO;
+        let terrain_entity = app.world.spawn((Height(2), rocky)).id();
+        let mut map_geometry = MapGeometry::new(1);
+        map_geometry.add_terrain(tile_pos, terrain_entity);
+
+        app.insert_resource(recipe_manifest)
+            .insert_resource(structure_manifest)
+            .insert_resource(map_geometry)
+            .add_system(apply_terrain_effects);
+
+        let crafter = app
+            .world
+            .spawn((
+                quarry,
+                tile_pos,
+                Facing::default(),
+                CraftingState::RecipeComplete,
+                ActiveRecipe::new(recipe_id),
+            ))
+            .id();
+
+        app.update();
+        assert_eq!(*app.world.get::<Height>(terrain_entity).unwrap(), Height(1));
+
+        // Effects are only applied when a recipe completes
+        *app.world.get_mut::<CraftingState>(crafter).unwrap() = CraftingState::NeedsInput;
+        app.update();
+        assert_eq!(*app.world.get::<Height>(terrain_entity).unwrap(), Height(1));
+
+        // The terrain can't be dug out any further than the minimum height
+        for _ in 0..2 {
+            *app.world.get_mut::<CraftingState>(crafter).unwrap() = CraftingState::RecipeComplete;
+            app.update();
+        }
+        assert_eq!(
+            *app.world.get::<Height>(terrain_entity).unwrap(),
+            Height::MIN
+        );
+    }
+
+    #[test]
+    fn full_outputs_block_crafting_without_losing_goods() {
+        let acacia_leaf = Id::from_name("acacia_leaf");
+        let leuco_chunk = Id::from_name("leuco_chunk");
+        let recipe_id = Id::from_name("leuco_digestion");
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "leuco_digestion",
+            RecipeData {
+                inputs: vec![RecipeInput::single(acacia_leaf, 1)],
+                outputs: vec![ItemCount::new(leuco_chunk, 2)],
+                craft_time: Duration::from_secs(1),
+                ..Default::default()
+            },
+        );
+
+        let mut item_manifest = ItemManifest::new();
+        for name in ["acacia_leaf", "leuco_chunk"] {
+            item_manifest.insert(name, ItemData::new(2));
+        }
+
+        let recipe = recipe_manifest.get(recipe_id);
+        let mut input_inventory = recipe.input_inventory(&item_manifest);
+        input_inventory
+            .add_item_all_or_nothing(&ItemCount::new(acacia_leaf, 1), &item_manifest)
+            .unwrap();
+        // The outputs of the previous craft are still waiting to be collected
+        let mut output_inventory = recipe.output_inventory(&item_manifest);
+        output_inventory
+            .add_item_all_or_nothing(&ItemCount::new(leuco_chunk, 2), &item_manifest)
+            .unwrap();
+
+        let mut app = App::new();
+        app.insert_resource(recipe_manifest)
+            .insert_resource(item_manifest)
+            .insert_resource(FixedTime::new_from_secs(1.))
+            .init_resource::<TotalLight>()
+            .init_resource::<TechTree>()
+            .init_resource::<Season>()
+            .init_resource::<EventLog>()
+            .init_resource::<Population>()
+            .insert_resource(MapGeometry::new(1))
+            .add_systems((update_tech_tree, progress_crafting).chain());
+
+        let crafter = app
+            .world
+            .spawn((
+                ActiveRecipe::new(recipe_id),
+                CraftingState::RecipeComplete,
+                input_inventory,
+                output_inventory,
+                WorkersPresent::new(0),
+                TilePos::new(0, 0),
+            ))
+            .id();
+
+        for _ in 0..3 {
+            app.update();
+
+            let crafting_state = app.world.get::<CraftingState>(crafter).unwrap();
+            assert_eq!(*crafting_state, CraftingState::FullAndBlocked);
+            assert_eq!(crafting_state.to_string(), "Output full");
+            // No inputs are consumed while blocked
+            let input_inventory = app.world.get::<InputInventory>(crafter).unwrap();
+            assert_eq!(input_inventory.item_count(acacia_leaf), 1);
+        }
+
+        // Once the outputs are collected, the finished goods take their place
+        app.world
+            .get_mut::<OutputInventory>(crafter)
+            .unwrap()
+            .remove_item_all_or_nothing(&ItemCount::new(leuco_chunk, 2))
+            .unwrap();
+        app.update();
+
+        assert_eq!(
+            *app.world.get::<CraftingState>(crafter).unwrap(),
+            CraftingState::NeedsInput
+        );
+        assert_eq!(
+            app.world
+                .get::<OutputInventory>(crafter)
+                .unwrap()
+                .item_count(leuco_chunk),
+            2
+        );
+    }
+
+    #[test]
+    fn mining_stops_once_the_deposit_is_exhausted() {
+        let stone = Id::from_name("stone");
+        let recipe_id = Id::from_name("mine_stone");
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "mine_stone",
+            RecipeData {
+                outputs: vec![ItemCount::one(stone)],
+                craft_time: Duration::from_secs(1),
+                mined_deposit: Some(ItemCount::one(stone)),
+                ..Default::default()
+            },
+        );
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("stone", ItemData::new(10));
+
+        let recipe = recipe_manifest.get(recipe_id);
+        let input_inventory = recipe.input_inventory(&item_manifest);
+        let output_inventory = recipe.output_inventory(&item_manifest);
+
+        let mut app = App::new();
+        let tile_pos = TilePos::ZERO;
+        let terrain_entity = app
+            .world
+            .spawn(ResourceDeposit::new(&ItemCount::new(stone, 2)))
+            .id();
+        let mut map_geometry = MapGeometry::new(1);
+        map_geometry.add_terrain(tile_pos, terrain_entity);
+
+        app.insert_resource(recipe_manifest)
+            .insert_resource(item_manifest)
+            .insert_resource(FixedTime::new_from_secs(1.))
+            .init_resource::<TotalLight>()
+            .init_resource::<TechTree>()
+            .init_resource::<Season>()
+            .init_resource::<EventLog>()
+            .init_resource::<Population>()
+            .insert_resource(map_geometry)
+            .add_systems((update_tech_tree, progress_crafting, mine_resource_deposits).chain());
+
+        let crafter = app
+            .world
+            .spawn((
+                ActiveRecipe::new(recipe_id),
+                CraftingState::NeedsInput,
+                input_inventory,
+                output_inventory,
+                WorkersPresent::new(0),
+                tile_pos,
+            ))
+            .id();
+
+        for _ in 0..20 {
+            app.update();
+        }
+
+        let crafting_state = app.world.get::<CraftingState>(crafter).unwrap();
+        assert_eq!(*crafting_state, CraftingState::DepositExhausted);
+        assert_eq!(crafting_state.to_string(), "Deposit exhausted");
+        assert!(app
+            .world
+            .get::<ResourceDeposit>(terrain_entity)
+            .unwrap()
+            .is_exhausted());
+        // Each item in the deposit was mined exactly once
+        let output_count = |app: &App| {
+            app.world
+                .get::<OutputInventory>(crafter)
+                .unwrap()
+                .item_count(stone)
+        };
+        assert_eq!(output_count(&app), 2);
+
+        // Nothing more is produced from the empty deposit
+        for _ in 0..5 {
+            app.update();
+        }
+        assert_eq!(
+            *app.world.get::<CraftingState>(crafter).unwrap(),
+            CraftingState::DepositExhausted
+        );
+        assert_eq!(output_count(&app), 2);
+    }
+
+    #[test]
+    fn recipes_that_find_their_deposit_drained_produce_nothing() {
+        let stone = Id::from_name("stone");
+        let recipe_id = Id::from_name("mine_stone");
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "mine_stone",
+            RecipeData {
+                outputs: vec![ItemCount::one(stone)],
+                craft_time: Duration::from_secs(1),
+                mined_deposit: Some(ItemCount::one(stone)),
+                ..Default::default()
+            },
+        );
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("stone", ItemData::new(10));
+
+        let mut app = App::new();
+        let tile_pos = TilePos::ZERO;
+        let terrain_entity = app
+            .world
+            .spawn(ResourceDeposit::new(&ItemCount::one(stone)))
+            .id();
+        let mut map_geometry = MapGeometry::new(1);
+        map_geometry.add_terrain(tile_pos, terrain_entity);
+
+        app.insert_resource(recipe_manifest)
+            .insert_resource(item_manifest)
+            .insert_resource(FixedTime::new_from_secs(1.))
+            .init_resource::<TotalLight>()
+            .init_resource::<TechTree>()
+            .init_resource::<Season>()
+            .init_resource::<EventLog>()
+            .init_resource::<Population>()
+            .insert_resource(map_geometry)
+            .add_systems((update_tech_tree, progress_crafting, mine_resource_deposits).chain());
+
+        // Both crafters started while the deposit still held a single stone
+        let crafters: Vec<Entity> = (0..2)
+            .map(|_| {
+                let output_inventory = app
+                    .world
+                    .resource::<RecipeManifest>()
+                    .get(recipe_id)
+                    .output_inventory(app.world.resource::<ItemManifest>());
+                app.world
+                    .spawn((
+                        ActiveRecipe::new(recipe_id),
+                        CraftingState::InProgress {
+                            progress: Duration::ZERO,
+                            required: Duration::from_secs(1),
+                        },
+                        InputInventory::default(),
+                        output_inventory,
+                        WorkersPresent::new(0),
+                        tile_pos,
+                    ))
+                    .id()
+            })
+            .collect();
+
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let produced: usize = crafters
+            .iter()
+            .map(|&crafter| {
+                app.world
+                    .get::<OutputInventory>(crafter)
+                    .unwrap()
+                    .item_count(stone)
+            })
+            .sum();
+        assert_eq!(produced, 1);
+        for crafter in crafters {
+            assert_eq!(
+                *app.world.get::<CraftingState>(crafter).unwrap(),
+                CraftingState::DepositExhausted
+            );
+        }
+    }
+
+    #[test]
+    fn quarry_ghosts_do_not_mine() {
+        let stone = Id::from_name("stone");
+        let recipe_id = Id::from_name("mine_stone");
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "mine_stone",
+            RecipeData {
+                outputs: vec![ItemCount::one(stone)],
+                craft_time: Duration::from_secs(1),
+                mined_deposit: Some(ItemCount::one(stone)),
+                ..Default::default()
+            },
+        );
+
+        let mut app = App::new();
+        let deposit_tile = TilePos::ZERO;
+        let bare_tile = TilePos::new(1, 0);
+        let deposit_entity = app
+            .world
+            .spawn(ResourceDeposit::new(&ItemCount::one(stone)))
+            .id();
+        let bare_entity = app.world.spawn_empty().id();
+        let mut map_geometry = MapGeometry::new(1);
+        map_geometry.add_terrain(deposit_tile, deposit_entity);
+        map_geometry.add_terrain(bare_tile, bare_entity);
+
+        app.insert_resource(recipe_manifest)
+            .insert_resource(map_geometry)
+            .add_system(mine_resource_deposits);
+
+        // Both ghosts have just finished construction, and store the recipe that the quarry will start with
+        let ghosts: Vec<Entity> = [deposit_tile, bare_tile]
+            .into_iter()
+            .map(|tile_pos| {
+                app.world
+                    .spawn((
+                        Ghost,
+                        tile_pos,
+                        CraftingState::RecipeComplete,
+                        ActiveRecipe::new(recipe_id),
+                    ))
+                    .id()
+            })
+            .collect();
+
+        app.update();
+
+        // Construction is left to finish as usual
+        for ghost in ghosts {
+            assert_eq!(
+                *app.world.get::<CraftingState>(ghost).unwrap(),
+                CraftingState::RecipeComplete
+            );
+        }
+        assert!(!app
+            .world
+            .get::<ResourceDeposit>(deposit_entity)
+            .unwrap()
+            .is_exhausted());
+    }
+
+    #[test]
+    fn completed_recipes_spawn_units_next_to_the_crafter() {
+        let ant = Id::<Unit>::from_name("ant");
+        let recipe_id = Id::from_name("hatch_ants");
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "hatch_ants",
+            RecipeData {
+                craft_time: Duration::from_secs(1),
+                spawns: Some(OrganismId::Unit(ant)),
+                ..Default::default()
+            },
+        );
+
+        let mut app = UnitTestApp::new(1).build();
+        app.insert_resource(recipe_manifest)
+            .add_system(spawn_recipe_organisms);
+
+        let crafter = app
+            .world
+            .spawn((
+                TilePos::ZERO,
+                CraftingState::RecipeComplete,
+                ActiveRecipe::new(recipe_id),
+            ))
+            .id();
+
+        app.update();
+
+        let mut unit_query = app.world.query::<(&Id<Unit>, &TilePos)>();
+        let units: Vec<(Id<Unit>, TilePos)> = unit_query
+            .iter(&app.world)
+            .map(|(&unit_id, &tile_pos)| (unit_id, tile_pos))
+            .collect();
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].0, ant);
+        assert_eq!(units[0].1.unsigned_distance_to(TilePos::ZERO.hex), 1);
+
+        // Units are only spawned when the recipe completes
+        *app.world.get_mut::<CraftingState>(crafter).unwrap() = CraftingState::NeedsInput;
+        app.update();
+        assert_eq!(unit_query.iter(&app.world).len(), 1);
+    }
+
+    #[test]
+    fn spawned_units_belong_to_the_crafters_faction() {
+        let ant = Id::<Unit>::from_name("ant");
+        let recipe_id = Id::from_name("hatch_ants");
+        let rival = Faction(1);
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "hatch_ants",
+            RecipeData {
+                craft_time: Duration::from_secs(1),
+                spawns: Some(OrganismId::Unit(ant)),
+                ..Default::default()
+            },
+        );
+
+        let mut app = UnitTestApp::new(3).build();
+        app.insert_resource(recipe_manifest)
+            .add_system(spawn_recipe_organisms);
+
+        app.world.spawn((
+            TilePos::ZERO,
+            CraftingState::RecipeComplete,
+            ActiveRecipe::new(recipe_id),
+            rival,
+        ));
+        // Crafters without a faction belong to the colony
+        app.world.spawn((
+            TilePos::new(3, 0),
+            CraftingState::RecipeComplete,
+            ActiveRecipe::new(recipe_id),
+        ));
+
+        app.update();
+
+        let mut unit_query = app
+            .world
+            .query_filtered::<(&TilePos, &Faction), With<Id<Unit>>>();
+        let mut factions: Vec<(u32, Faction)> = unit_query
+            .iter(&app.world)
+            .map(|(tile_pos, &faction)| (tile_pos.unsigned_distance_to(hexx::Hex::ZERO), faction))
+            .collect();
+        factions.sort_by_key(|&(distance, _)| distance);
+        assert_eq!(factions, vec![(1, rival), (2, Faction::COLONY)]);
+    }
+}
@@ -0,0 +1,295 @@
+//! Structures can wear down over time, and must be repaired by units to avoid falling apart.
+//!
+//! Decay is opt-in: only structures whose [`Health`] sets a `decay_rate` wear down.
+//! The base game sets one for each of its buildings in the structure manifest, but not for organisms.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset_management::manifest::Id,
+    items::item_manifest::Item,
+    signals::{Emitter, SignalStrength, SignalType},
+    simulation::{
+        geometry::{MapGeometry, TilePos},
+        SimulationSet,
+    },
+};
+
+use super::{
+    commands::StructureCommandsExt,
+    construction::MarkedForDemolition,
+    crafting::{set_crafting_emitter, set_storage_emitter, InputInventory},
+    structure_manifest::Structure,
+};
+
+/// Wears down structures, asks for them to be repaired, and removes them once they fall apart.
+pub(super) struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                decay_structures,
+                emit_repair_signals
+                    .after(decay_structures)
+                    .after(set_crafting_emitter)
+                    .after(set_storage_emitter),
+                demolish_destroyed_structures.after(decay_structures),
+            )
+                .in_set(SimulationSet)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// How intact a structure is.
+///
+/// Structures are demolished once their health reaches zero.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Health {
+    /// The current amount of health.
+    current: f32,
+    /// The maximum amount of health.
+    max: f32,
+    /// The amount of health lost per second, as a fraction of the maximum health.
+    ///
+    /// Structures with a decay rate of zero never wear down on their own.
+    #[serde(default)]
+    decay_rate: f32,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Health::full(100.)
+    }
+}
+
+impl Health {
+    /// Structures below this fraction of their maximum health will ask to be repaired.
+    const REPAIR_THRESHOLD: f32 = 0.5;
+
+    /// Creates a new [`Health`] with `max` health, which starts completely intact and never decays.
+    pub fn full(max: f32) -> Self {
+        Health {
+            current: max,
+            max,
+            decay_rate: 0.,
+        }
+    }
+
+    /// Sets the fraction of the maximum health that is lost each second.
+    pub fn with_decay_rate(mut self, decay_rate: f32) -> Self {
+        self.decay_rate = decay_rate;
+        self
+    }
+
+    /// The fraction of the maximum health that is lost each second.
+    pub fn decay_rate(&self) -> f32 {
+        self.decay_rate
+    }
+
+    /// The current amount of health.
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    /// The maximum amount of health.
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    /// Reduces the current health by `amount`, down to a minimum of zero.
+    pub fn damage(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.);
+    }
+
+    /// Increases the current health by `amount`, up to the maximum health.
+    pub fn heal(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    /// Has this structure fallen apart?
+    pub fn is_destroyed(&self) -> bool {
+        self.current <= 0.
+    }
+
+    /// Is this structure damaged enough that it should ask to be repaired?
+    pub(crate) fn needs_repair(&self) -> bool {
+        self.current < self.max * Health::REPAIR_THRESHOLD
+    }
+
+    /// Repairs this structure using one of the `held_item` that the unit is carrying.
+    ///
+    /// Each item from the structure's construction `materials` restores an equal share of its maximum health,
+    /// so that a full set of materials restores it completely.
+    /// Structures that need no materials are fully restored without consuming anything.
+    ///
+    /// Returns `true` if the held item was consumed.
+    pub(crate) fn repair(
+        &mut self,
+        held_item: Option<Id<Item>>,
+        materials: &InputInventory,
+    ) -> Result<bool, RepairError> {
+        let total_materials: usize = materials
+            .iter()
+            .map(|item_slot| item_slot.max_item_count())
+            .sum();
+
+        if total_materials == 0 {
+            self.heal(self.max);
+            return Ok(false);
+        }
+
+        let Some(item_id) = held_item else {
+            return Err(RepairError::NoMaterials);
+        };
+
+        if !materials
+            .iter()
+            .any(|item_slot| item_slot.is_for_item(item_id))
+        {
+            return Err(RepairError::WrongMaterial);
+        }
+
+        self.heal(self.max / total_materials as f32);
+        Ok(true)
+    }
+}
+
+/// The reasons why a unit may fail to repair a structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RepairError {
+    /// The unit is not carrying anything.
+    NoMaterials,
+    /// The unit is carrying an item that is not used to build this structure.
+    WrongMaterial,
+}
+
+/// A query for structures that need to be repaired.
+#[derive(SystemParam)]
+pub(crate) struct RepairQuery<'w, 's> {
+    /// The contained query type.
+    query: Query<'w, 's, (&'static Id<Structure>, &'static Health), Without<MarkedForDemolition>>,
+}
+
+impl<'w, 's> RepairQuery<'w, 's> {
+    /// Is there a structure of type `structure_id` at `structure_pos` that needs to be repaired?
+    ///
+    /// If so, returns `Some(matching_structure_entity_that_needs_to_be_repaired)`.
+    pub(crate) fn needs_repair(
+        &self,
+        structure_pos: TilePos,
+        structure_id: Id<Structure>,
+        map_geometry: &MapGeometry,
+    ) -> Option<Entity> {
+        let entity = map_geometry.get_structure(structure_pos)?;
+
+        let (&found_structure_id, health) = self.query.get(entity).ok()?;
+
+        match found_structure_id == structure_id && health.needs_repair() {
+            true => Some(entity),
+            false => None,
+        }
+    }
+}
+
+/// Slowly wears down structures that decay.
+fn decay_structures(mut health_query: Query<&mut Health>, fixed_time: Res<FixedTime>) {
+    let delta = fixed_time.period.as_secs_f32();
+
+    for mut health in health_query.iter_mut() {
+        if health.decay_rate <= 0. {
+            continue;
+        }
+
+        let amount = health.max * health.decay_rate * delta;
+        health.damage(amount);
+    }
+}
+
+/// Damaged structures ask units to come and repair them.
+fn emit_repair_signals(
+    mut structure_query: Query<
+        (&mut Emitter, &Health, &Id<Structure>),
+        Without<MarkedForDemolition>,
+    >,
+) {
+    for (mut emitter, health, &structure_id) in structure_query.iter_mut() {
+        if health.needs_repair() {
+            emitter
+                .signals
+                .push((SignalType::Repair(structure_id), SignalStrength::new(100.)));
+        }
+    }
+}
+
+/// Structures that have fallen apart are removed from the map.
+fn demolish_destroyed_structures(
+    structure_query: Query<(&Health, &TilePos), With<Id<Structure>>>,
+    mut commands: Commands,
+) {
+    for (health, &tile_pos) in structure_query.iter() {
+        if health.is_destroyed() {
+            commands.despawn_structure(tile_pos);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::inventory::Inventory;
+
+    #[test]
+    fn structures_can_be_damaged_then_repaired() {
+        let mut app = App::new();
+        // Decay most of the structure's health in a single tick
+        app.insert_resource(FixedTime::new_from_secs(600.))
+            .add_system(decay_structures);
+
+        let acacia_leaf = Id::from_name("acacia_leaf");
+        let materials = InputInventory {
+            inventory: Inventory::new_from_item(acacia_leaf, 2),
+        };
+        let structure_entity = app
+            .world
+            .spawn(Health::full(100.).with_decay_rate(0.001))
+            .id();
+
+        app.update();
+
+        let mut health = *app.world.get::<Health>(structure_entity).unwrap();
+        assert!((health.current() - 40.).abs() < 0.01);
+        assert!(health.needs_repair());
+
+        assert_eq!(
+            health.repair(None, &materials),
+            Err(RepairError::NoMaterials)
+        );
+        assert_eq!(
+            health.repair(Some(Id::from_name("leuco_chunk")), &materials),
+            Err(RepairError::WrongMaterial)
+        );
+        assert_eq!(health.repair(Some(acacia_leaf), &materials), Ok(true));
+        assert!((health.current() - 90.).abs() < 0.01);
+        assert!(!health.needs_repair());
+
+        // Repairs can never exceed the maximum health
+        assert_eq!(health.repair(Some(acacia_leaf), &materials), Ok(true));
+        assert_eq!(health.current(), health.max());
+    }
+
+    #[test]
+    fn structures_only_decay_if_they_opt_in() {
+        let mut app = App::new();
+        app.insert_resource(FixedTime::new_from_secs(600.))
+            .add_system(decay_structures);
+
+        let sturdy_entity = app.world.spawn(Health::default()).id();
+        app.update();
+
+        let health = app.world.get::<Health>(sturdy_entity).unwrap();
+        assert_eq!(health.current(), health.max());
+    }
+}
@@ -0,0 +1,225 @@
+//! Piles of items left lying on the ground, such as the materials of cancelled construction.
+
+use bevy::prelude::*;
+
+use crate::{
+    items::{
+        inventory::{Inventory, ReservedFor},
+        item_manifest::ItemManifest,
+    },
+    signals::{Emitter, SignalStrength, SignalType},
+    simulation::geometry::{MapGeometry, TilePos},
+};
+
+use super::crafting::OutputInventory;
+
+/// Items lying on the ground, waiting to be carried away.
+///
+/// Piles occupy their tile until they have been emptied by units,
+/// and are tracked in their own index of the [`MapGeometry`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ItemPile;
+
+impl ItemPile {
+    /// The strength of the [`SignalType::Push`] signal emitted for each item in the pile.
+    pub(crate) const SIGNAL_STRENGTH: f32 = 10.;
+
+    /// Finds a tile at or next to `tile_pos` that isn't occupied by a ghost, structure, corpse or item pile.
+    ///
+    /// The tile itself is preferred, but its neighbors are checked if it is occupied.
+    pub(crate) fn free_tile(tile_pos: TilePos, map_geometry: &MapGeometry) -> Option<TilePos> {
+        std::iter::once(tile_pos)
+            .chain(tile_pos.all_neighbors(map_geometry))
+            .find(|&candidate| {
+                map_geometry.is_valid(candidate)
+                    && map_geometry.get_ghost(candidate).is_none()
+                    && !map_geometry.is_obstructed(candidate)
+            })
+    }
+
+    /// Drops the contents of `inventory` in a new pile on or next to `tile_pos`.
+    ///
+    /// Returns the pile entity, or [`None`] if there was nothing to drop or no free tile to drop it on.
+    pub(crate) fn spawn(
+        world: &mut World,
+        tile_pos: TilePos,
+        inventory: &mut Inventory,
+    ) -> Option<Entity> {
+        if inventory.is_empty() {
+            return None;
+        }
+
+        let map_geometry = world.resource::<MapGeometry>();
+        let Some(pile_tile_pos) = ItemPile::free_tile(tile_pos, map_geometry) else {
+            warn!("Could not find a free tile to drop {inventory:?} on near {tile_pos}.");
+            return None;
+        };
+        let translation = pile_tile_pos.top_of_tile(map_geometry);
+
+        let item_manifest = world.resource::<ItemManifest>();
+        let n_slots = inventory.iter().filter(|slot| !slot.is_empty()).count();
+        let mut pile_inventory = Inventory::new(n_slots, ReservedFor::Any);
+        // Every non-empty slot gets its own slot in the pile, so everything always fits
+        if let Err(error) = pile_inventory.merge_from(inventory, item_manifest) {
+            error!("Could not drop {error:?} in an item pile.");
+        }
+
+        let pile_entity = world
+            .spawn((
+                ItemPile,
+                pile_tile_pos,
+                OutputInventory {
+                    inventory: pile_inventory,
+                },
+                Emitter::default(),
+                SpatialBundle::from_transform(Transform::from_translation(translation)),
+            ))
+            .id();
+
+        world
+            .resource_mut::<MapGeometry>()
+            .add_item_pile(pile_tile_pos, pile_entity);
+
+        Some(pile_entity)
+    }
+}
+
+/// Causes item piles to ask for their contents to be carried away.
+pub(crate) fn set_item_pile_emitter(
+    mut item_pile_query: Query<(&mut Emitter, &OutputInventory), With<ItemPile>>,
+) {
+    for (mut emitter, output_inventory) in item_pile_query.iter_mut() {
+        emitter.signals.clear();
+
+        for item_slot in output_inventory.iter() {
+            if !item_slot.is_empty() {
+                let signal_type = SignalType::Push(item_slot.item_id());
+                let signal_strength = SignalStrength::new(ItemPile::SIGNAL_STRENGTH);
+                emitter.signals.push((signal_type, signal_strength));
+            }
+        }
+    }
+}
+
+/// Removes item piles once everything has been taken from them.
+pub(crate) fn remove_empty_item_piles(
+    item_pile_query: Query<(Entity, &TilePos, &OutputInventory), With<ItemPile>>,
+    mut map_geometry: ResMut<MapGeometry>,
+    mut commands: Commands,
+) {
+    for (item_pile_entity, &tile_pos, output_inventory) in item_pile_query.iter() {
+        if output_inventory.is_empty() {
+            map_geometry.remove_item_pile(tile_pos);
+            commands.entity(item_pile_entity).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::CommandQueue;
+
+    use super::*;
+    use crate::{
+        asset_management::manifest::Id,
+        items::{
+            item_manifest::{Item, ItemData},
+            ItemCount,
+        },
+        simulation::geometry::Height,
+        structures::{
+            commands::StructureCommandsExt, construction::Footprint, crafting::InputInventory,
+        },
+    };
+
+    fn leuco_chunk() -> Id<Item> {
+        Id::from_name("leuco_chunk")
+    }
+
+    /// Spawns a ghost at the origin, which has had 3 of the 5 items it needs delivered.
+    fn spawn_partially_supplied_ghost(world: &mut World) -> Entity {
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let mut materials = InputInventory {
+            inventory: Inventory::new_from_item(leuco_chunk(), 5),
+        };
+        materials
+            .add_item_all_or_nothing(&ItemCount::new(leuco_chunk(), 3), &item_manifest)
+            .unwrap();
+
+        let ghost_entity = world.spawn((TilePos::ZERO, materials)).id();
+
+        let mut map_geometry = MapGeometry::new(1);
+        map_geometry.update_height(TilePos::ZERO, Height(0));
+        map_geometry.add_ghost(TilePos::ZERO, &Footprint::single(), ghost_entity);
+
+        world.insert_resource(map_geometry);
+        world.insert_resource(item_manifest);
+        ghost_entity
+    }
+
+    /// Runs `f` on a fresh [`Commands`], then applies the commands to `world`.
+    fn apply_commands(world: &mut World, f: impl FnOnce(&mut Commands)) {
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, world);
+        f(&mut commands);
+        queue.apply(world);
+    }
+
+    #[test]
+    fn cancelled_ghosts_drop_their_delivered_materials() {
+        let mut app = App::new();
+        app.add_systems((set_item_pile_emitter, remove_empty_item_piles).chain());
+        let ghost_entity = spawn_partially_supplied_ghost(&mut app.world);
+        apply_commands(&mut app.world, |commands| {
+            commands.despawn_ghost(TilePos::ZERO)
+        });
+
+        assert!(app.world.get_entity(ghost_entity).is_none());
+        let pile_entity = app
+            .world
+            .resource::<MapGeometry>()
+            .get_item_pile(TilePos::ZERO)
+            .unwrap();
+        assert!(app.world.get::<ItemPile>(pile_entity).is_some());
+        let output_inventory = app.world.get::<OutputInventory>(pile_entity).unwrap();
+        assert_eq!(output_inventory.item_count(leuco_chunk()), 3);
+
+        // The pile asks for its contents to be carried away, and disappears once it has been emptied
+        app.update();
+
+        let emitter = app.world.get::<Emitter>(pile_entity).unwrap();
+        assert_eq!(
+            emitter.signals,
+            vec![(
+                SignalType::Push(leuco_chunk()),
+                SignalStrength::new(ItemPile::SIGNAL_STRENGTH)
+            )]
+        );
+
+        app.world
+            .get_mut::<OutputInventory>(pile_entity)
+            .unwrap()
+            .remove_item_all_or_nothing(&ItemCount::new(leuco_chunk(), 3))
+            .unwrap();
+        app.update();
+        assert!(app.world.get_entity(pile_entity).is_none());
+        let map_geometry = app.world.resource::<MapGeometry>();
+        assert_eq!(map_geometry.get_item_pile(TilePos::ZERO), None);
+        assert_eq!(map_geometry.get_structure(TilePos::ZERO), None);
+    }
+
+    #[test]
+    fn completed_ghosts_consume_their_materials() {
+        let mut world = World::new();
+        let ghost_entity = spawn_partially_supplied_ghost(&mut world);
+        apply_commands(&mut world, |commands| commands.consume_ghost(TilePos::ZERO));
+
+        assert!(world.get_entity(ghost_entity).is_none());
+        let map_geometry = world.resource::<MapGeometry>();
+        assert_eq!(map_geometry.get_ghost_or_structure(TilePos::ZERO), None);
+        assert_eq!(map_geometry.get_item_pile(TilePos::ZERO), None);
+        assert_eq!(world.query::<&ItemPile>().iter(&world).count(), 0);
+    }
+}
@@ -9,27 +9,44 @@ use bevy_mod_raycast::RaycastMesh;
 use crate::{
     asset_management::{
         manifest::{plugin::ManifestPlugin, Id},
-        AssetCollectionExt,
+        AssetCollectionExt, AssetState,
     },
     player_interaction::{clipboard::ClipboardData, selection::ObjectInteraction},
     simulation::{
+        factions::Faction,
         geometry::{Facing, TilePos},
         SimulationSet,
     },
 };
 
 use self::{
-    construction::{ghost_lifecycle, ghost_signals, validate_ghosts},
+    colony_center::ColonyCenterPlugin,
+    construction::{
+        ghost_lifecycle, ghost_signals, show_ghost_progress, validate_ghosts, ConstructionCompleted,
+    },
+    conveyor::ConveyorPlugin,
     crafting::CraftingPlugin,
+    health::HealthPlugin,
+    item_pile::{remove_empty_item_piles, set_item_pile_emitter},
+    planner::ProductionPlannerPlugin,
+    power::PowerPlugin,
     structure_assets::StructureHandles,
     structure_manifest::{RawStructureManifest, Structure},
+    upgrades::UpgradePlugin,
 };
 
+pub(crate) mod colony_center;
 pub(crate) mod commands;
 pub mod construction;
+pub(crate) mod conveyor;
 pub mod crafting;
+pub mod health;
+pub(crate) mod item_pile;
+pub mod planner;
+pub(crate) mod power;
 mod structure_assets;
 pub mod structure_manifest;
+pub(crate) mod upgrades;
 
 /// The systems that make structures tick.
 pub(super) struct StructuresPlugin;
@@ -39,15 +56,25 @@ impl Plugin for StructuresPlugin {
         app.add_plugin(ManifestPlugin::<RawStructureManifest>::new())
             .add_asset_collection::<StructureHandles>()
             .add_plugin(CraftingPlugin)
+            .add_plugin(ColonyCenterPlugin)
+            .add_plugin(ConveyorPlugin)
+            .add_plugin(PowerPlugin)
+            .add_plugin(HealthPlugin)
+            .add_plugin(UpgradePlugin)
+            .add_plugin(ProductionPlannerPlugin)
+            .add_event::<ConstructionCompleted>()
             .add_systems(
                 (
                     validate_ghosts,
                     ghost_signals.after(validate_ghosts),
                     ghost_lifecycle.after(validate_ghosts),
+                    set_item_pile_emitter,
+                    remove_empty_item_piles,
                 )
                     .in_set(SimulationSet)
                     .in_schedule(CoreSchedule::FixedUpdate),
-            );
+            )
+            .add_system(show_ghost_progress.run_if(in_state(AssetState::Ready)));
     }
 }
 
@@ -68,6 +95,8 @@ struct StructureBundle {
     picking_mesh: Handle<Mesh>,
     /// The child scene that contains the gltF model used
     scene_bundle: SceneBundle,
+    /// The faction that owns this structure
+    faction: Faction,
 }
 
 impl StructureBundle {
@@ -78,6 +107,7 @@ impl StructureBundle {
         picking_mesh: Handle<Mesh>,
         scene_handle: Handle<Scene>,
         world_pos: Vec3,
+        faction: Faction,
     ) -> Self {
         StructureBundle {
             structure: data.structure_id,
@@ -91,6 +121,7 @@ impl StructureBundle {
                 transform: Transform::from_translation(world_pos),
                 ..Default::default()
             },
+            faction,
         }
     }
 }
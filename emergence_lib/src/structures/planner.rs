@@ -0,0 +1,518 @@
+//! Automatically queues new producer structures to meet the player's production targets.
+//!
+//! The player sets a [`ProductionTarget`] for an item: the stock they want to keep on hand,
+//! and how quickly that item should be made.
+//! Whenever the stock falls short and the existing producers can't keep up,
+//! ghosts of new producers are placed near the [`ColonyCenter`].
+//!
+//! The planner is deliberately conservative: it only ever adds ghosts,
+//! and never changes or demolishes existing structures.
+//! It re-checks the targets as soon as they change, and otherwise only every [`PLANNING_INTERVAL`].
+//!
+//! Players raise the targets of the items made by the selected structure with [`PlayerAction::PlanProduction`](crate::player_interaction::PlayerAction::PlanProduction),
+//! and clear them with [`PlayerAction::StopPlanningProduction`](crate::player_interaction::PlayerAction::StopPlanningProduction).
+//! Targets can also be set with the developer console, using `target <item> <stock> <items_per_minute>`.
+
+use bevy::{
+    prelude::*,
+    utils::{Duration, HashMap, HashSet},
+};
+
+use crate::{
+    asset_management::manifest::Id,
+    items::{
+        item_manifest::Item,
+        recipe::{Recipe, RecipeManifest},
+    },
+    player_interaction::clipboard::ClipboardData,
+    simulation::{
+        factions::Faction,
+        geometry::{Facing, MapGeometry, TilePos},
+        SimulationSet,
+    },
+    terrain::terrain_manifest::Terrain,
+};
+
+use super::{
+    colony_center::ColonyCenter,
+    commands::StructureCommandsExt,
+    crafting::{ActiveRecipe, StorageInventory},
+    structure_manifest::{Structure, StructureManifest},
+};
+
+/// Places ghosts of producer structures to meet the [`ProductionTargets`].
+pub(super) struct ProductionPlannerPlugin;
+
+impl Plugin for ProductionPlannerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProductionTargets>().add_system(
+            plan_production
+                .in_set(SimulationSet)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// The amount of simulated time between planning passes, while the [`ProductionTargets`] are unchanged.
+///
+/// Planning searches the whole map for free tiles, so it is not worth redoing every tick.
+pub(crate) const PLANNING_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How much of a single item the colony should have, and how quickly it should be made.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProductionTarget {
+    /// The number of items that should be kept in storage.
+    ///
+    /// New producers are only planned while the stock is below this level.
+    pub desired_stock: usize,
+    /// The number of items that the colony's producers should be able to make each minute.
+    pub items_per_minute: f32,
+}
+
+/// The [`ProductionTarget`] of each item that the planner is responsible for.
+///
+/// This is empty by default, so the planner does nothing until the player opts in.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct ProductionTargets {
+    /// The target for each item.
+    targets: HashMap<Id<Item>, ProductionTarget>,
+}
+
+impl ProductionTargets {
+    /// Sets the production target for `item_id`, replacing any existing target.
+    pub fn set(&mut self, item_id: Id<Item>, target: ProductionTarget) {
+        self.targets.insert(item_id, target);
+    }
+
+    /// Stops planning production for `item_id`.
+    ///
+    /// Producers that were already placed are left alone.
+    pub fn clear(&mut self, item_id: Id<Item>) {
+        self.targets.remove(&item_id);
+    }
+
+    /// The production target for `item_id`, if any.
+    pub fn get(&self, item_id: Id<Item>) -> Option<&ProductionTarget> {
+        self.targets.get(&item_id)
+    }
+
+    /// Iterates over all items with a production target.
+    pub fn iter(&self) -> impl Iterator<Item = (Id<Item>, &ProductionTarget)> {
+        self.targets
+            .iter()
+            .map(|(&item_id, target)| (item_id, target))
+    }
+}
+
+/// A structure and recipe that can be used to make an item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Producer {
+    /// The structure to build.
+    structure_id: Id<Structure>,
+    /// The recipe that the structure should craft.
+    recipe_id: Id<Recipe>,
+    /// The number of items made each minute by a single copy of this structure.
+    items_per_minute: f32,
+}
+
+/// The number of `item_id` made each minute by a single structure crafting `recipe_id`.
+pub(crate) fn items_per_minute(
+    item_id: Id<Item>,
+    recipe_id: Id<Recipe>,
+    recipe_manifest: &RecipeManifest,
+) -> f32 {
+    let recipe_data = recipe_manifest.get(recipe_id);
+    let craft_secs = recipe_data.craft_time.as_secs_f32();
+    if craft_secs <= 0. {
+        return 0.;
+    }
+
+    let items_per_craft: usize = recipe_data
+        .outputs
+        .iter()
+        .filter(|output| output.item_id() == item_id)
+        .map(|output| output.count())
+        .sum();
+
+    items_per_craft as f32 * 60. / craft_secs
+}
+
+/// The number of `item_id` that the colony's producers can make each minute.
+///
+/// Ghosts of producers count as well, since they will be built soon enough.
+pub(crate) fn colony_capacity(
+    item_id: Id<Item>,
+    producer_query: &Query<(&ActiveRecipe, Option<&Faction>)>,
+    recipe_manifest: &RecipeManifest,
+) -> f32 {
+    producer_query
+        .iter()
+        .filter(|(_, maybe_faction)| maybe_faction.copied().unwrap_or_default() == Faction::COLONY)
+        .filter_map(|(active_recipe, _)| *active_recipe.recipe_id())
+        .map(|recipe_id| items_per_minute(item_id, recipe_id, recipe_manifest))
+        .sum()
+}
+
+/// Finds the structure that should be built to make more of `item_id`.
+///
+/// Only buildable structures whose starting recipe produces the item are considered.
+/// Ties are broken by name, so the choice is stable.
+fn choose_producer(
+    item_id: Id<Item>,
+    structure_manifest: &StructureManifest,
+    recipe_manifest: &RecipeManifest,
+) -> Option<Producer> {
+    let mut candidates: Vec<Producer> = structure_manifest
+        .prototypes()
+        .into_iter()
+        .filter_map(|structure_id| {
+            let recipe_id = (*structure_manifest
+                .get(structure_id)
+                .starting_recipe()
+                .recipe_id())?;
+            let items_per_minute = items_per_minute(item_id, recipe_id, recipe_manifest);
+
+            (items_per_minute > 0.).then_some(Producer {
+                structure_id,
+                recipe_id,
+                items_per_minute,
+            })
+        })
+        .collect();
+
+    candidates.sort_by_key(|producer| structure_manifest.name(producer.structure_id));
+    candidates.into_iter().next()
+}
+
+/// The number of new producers needed to meet the `target`.
+///
+/// No producers are needed while the `stock` is at or above the desired level,
+/// or while the existing `capacity` already meets the desired rate.
+fn producers_needed(
+    target: &ProductionTarget,
+    stock: usize,
+    capacity: f32,
+    items_per_producer: f32,
+) -> usize {
+    if stock >= target.desired_stock || capacity >= target.items_per_minute {
+        return 0;
+    }
+
+    if items_per_producer <= 0. {
+        return 0;
+    }
+
+    ((target.items_per_minute - capacity) / items_per_producer).ceil() as usize
+}
+
+/// Places ghosts of producer structures for each item that is short of its [`ProductionTarget`].
+///
+/// Both built structures and ghosts count towards the existing capacity,
+/// so producers that are still under construction are not planned twice.
+///
+/// This runs whenever the targets change, and every [`PLANNING_INTERVAL`] otherwise.
+fn plan_production(
+    production_targets: Res<ProductionTargets>,
+    fixed_time: Res<FixedTime>,
+    mut time_since_planning: Local<Duration>,
+    storage_query: Query<(&StorageInventory, Option<&Faction>)>,
+    producer_query: Query<(&ActiveRecipe, Option<&Faction>)>,
+    terrain_query: Query<&Id<Terrain>>,
+    structure_manifest: Res<StructureManifest>,
+    recipe_manifest: Res<RecipeManifest>,
+    map_geometry: Res<MapGeometry>,
+    colony_center: Res<ColonyCenter>,
+    mut commands: Commands,
+) {
+    *time_since_planning += fixed_time.period;
+    if !production_targets.is_changed() && *time_since_planning < PLANNING_INTERVAL {
+        return;
+    }
+    *time_since_planning = Duration::ZERO;
+
+    // Tiles claimed by ghosts placed during this pass, which are not yet in the map geometry
+    let mut claimed_tiles: HashSet<TilePos> = HashSet::new();
+    // Tiles that a new producer could be centered on, nearest to the colony center first.
+    // These are only gathered once some item is short, and shrink as ghosts are placed.
+    let mut candidate_tiles: Option<Vec<TilePos>> = None;
+
+    for (item_id, target) in production_targets.iter() {
+        let stock: usize = storage_query
+            .iter()
+            .filter(|(_, maybe_faction)| {
+                maybe_faction.copied().unwrap_or_default() == Faction::COLONY
+            })
+            .map(|(storage_inventory, _)| storage_inventory.item_count(item_id))
+            .sum();
+
+        let capacity = colony_capacity(item_id, &producer_query, &recipe_manifest);
+
+        let Some(producer) = choose_producer(item_id, &structure_manifest, &recipe_manifest) else {
+            continue;
+        };
+
+        let mut remaining = producers_needed(target, stock, capacity, producer.items_per_minute);
+        if remaining == 0 {
+            continue;
+        }
+
+        let candidate_tiles = candidate_tiles.get_or_insert_with(|| {
+            colony_center
+                .tile_pos()
+                .spiral(map_geometry.radius * 2, &map_geometry)
+                // Never replace an existing ghost
+                .filter(|&tile_pos| map_geometry.get_ghost(tile_pos).is_none())
+                .collect()
+        });
+
+        // The map is full, so there's no point checking the remaining items
+        if candidate_tiles.is_empty() {
+            break;
+        }
+
+        let structure_data = structure_manifest.get(producer.structure_id);
+        let footprint = structure_data.footprint.rotated(Facing::default());
+
+        for &tile_pos in candidate_tiles.iter() {
+            if remaining == 0 {
+                break;
+            }
+
+            let footprint_tiles = footprint.in_world_space(tile_pos);
+            if !footprint_tiles.is_disjoint(&claimed_tiles)
+                || !map_geometry.can_build_ghost(
+                    tile_pos,
+                    footprint.clone(),
+                    &terrain_query,
+                    structure_data.allowed_terrain_types(),
+                    structure_data.required_adjacency(),
+                )
+            {
+                continue;
+            }
+
+            let mut data = ClipboardData::new(producer.structure_id, &structure_manifest);
+            data.active_recipe = ActiveRecipe::new(producer.recipe_id);
+            commands.spawn_ghost(tile_pos, data, Faction::COLONY);
+
+            claimed_tiles.extend(footprint_tiles);
+            remaining -= 1;
+        }
+
+        candidate_tiles.retain(|tile_pos| !claimed_tiles.contains(tile_pos));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::Duration;
+
+    use super::*;
+    use crate::{
+        items::{
+            inventory::ReservedFor,
+            item_manifest::{ItemData, ItemManifest},
+            recipe::{RecipeData, RecipeInput},
+            ItemCount,
+        },
+        simulation::geometry::Height,
+        structures::{
+            construction::{Ghost, GhostKind},
+            crafting::InputInventory,
+            structure_assets::StructureHandles,
+            structure_manifest::{ConstructionStrategy, StructureData, StructureKind},
+        },
+    };
+
+    #[test]
+    fn producers_are_only_needed_when_stock_and_capacity_are_short() {
+        let target = ProductionTarget {
+            desired_stock: 10,
+            items_per_minute: 25.,
+        };
+
+        assert_eq!(producers_needed(&target, 0, 0., 10.), 3);
+        assert_eq!(producers_needed(&target, 0, 10., 10.), 2);
+        // Enough stock on hand
+        assert_eq!(producers_needed(&target, 10, 0., 10.), 0);
+        // Enough producers already
+        assert_eq!(producers_needed(&target, 0, 25., 10.), 0);
+    }
+
+    /// An app whose only producer is `leuco`, which needs two more copies to meet its target.
+    fn planner_app() -> App {
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+        let leuco = Id::<Structure>::from_name("leuco");
+        let leuco_recipe = Id::<Recipe>::from_name("leuco_chunk_production");
+        let loam = Id::<Terrain>::from_name("loam");
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let mut recipe_manifest = RecipeManifest::new();
+        recipe_manifest.insert(
+            "leuco_chunk_production",
+            RecipeData {
+                inputs: Vec::<RecipeInput>::new(),
+                outputs: vec![ItemCount::one(leuco_chunk)],
+                craft_time: Duration::from_secs(6),
+                ..Default::default()
+            },
+        );
+
+        let mut structure_manifest = StructureManifest::new();
+        structure_manifest.insert(
+            "leuco",
+            StructureData {
+                kind: StructureKind::Crafting {
+                    starting_recipe: ActiveRecipe::new(leuco_recipe),
+                    output_direction: None,
+                },
+                construction_strategy: ConstructionStrategy {
+                    seedling: None,
+                    work: Duration::ZERO,
+                    materials: InputInventory::default(),
+                    allowed_terrain_types: HashSet::from_iter([loam]),
+                    required_adjacency: HashSet::new(),
+                },
+                upgrade_cost: Default::default(),
+                ..Default::default()
+            },
+        );
+
+        let mut app = App::new();
+        let mut map_geometry = MapGeometry::new(1);
+        for tile_pos in TilePos::ZERO.spiral(1, &map_geometry).collect::<Vec<_>>() {
+            let terrain_entity = app.world.spawn(loam).id();
+            map_geometry.update_height(tile_pos, Height(1));
+            map_geometry.add_terrain(tile_pos, terrain_entity);
+        }
+
+        // Each leuco makes 10 chunks a minute, so two are needed to make 15 chunks a minute
+        let mut production_targets = ProductionTargets::default();
+        production_targets.set(
+            leuco_chunk,
+            ProductionTarget {
+                desired_stock: 20,
+                items_per_minute: 15.,
+            },
+        );
+
+        app.insert_resource(item_manifest)
+            .insert_resource(recipe_manifest)
+            .insert_resource(structure_manifest)
+            .insert_resource(map_geometry)
+            .insert_resource(ColonyCenter::default())
+            .insert_resource(production_targets)
+            .insert_resource(StructureHandles {
+                scenes: HashMap::from_iter([(leuco, Handle::default())]),
+                ghost_materials: HashMap::from_iter([(GhostKind::Ghost, Handle::default())]),
+                ghost_progress_materials: Vec::new(),
+                picking_mesh: Handle::default(),
+            })
+            .add_system(plan_production);
+
+        // A nearly empty storage, which is well short of the desired stock
+        let mut storage_inventory = StorageInventory::new(1, ReservedFor::Any);
+        storage_inventory
+            .add_item_all_or_nothing(
+                &ItemCount::new(leuco_chunk, 2),
+                app.world.resource::<ItemManifest>(),
+            )
+            .unwrap();
+        app.world.spawn(storage_inventory);
+
+        app.insert_resource(FixedTime::new_from_secs(1.));
+        app
+    }
+
+    #[test]
+    fn planner_queues_a_producer_ghost_when_stock_is_low() {
+        let leuco = Id::<Structure>::from_name("leuco");
+        let leuco_recipe = Id::<Recipe>::from_name("leuco_chunk_production");
+        let mut app = planner_app();
+
+        app.update();
+
+        let mut ghost_query = app
+            .world
+            .query_filtered::<(&Id<Structure>, &ActiveRecipe), With<Ghost>>();
+        let ghosts: Vec<_> = ghost_query.iter(&app.world).collect();
+        assert_eq!(ghosts.len(), 2);
+        for (&structure_id, active_recipe) in ghosts {
+            assert_eq!(structure_id, leuco);
+            assert_eq!(*active_recipe, ActiveRecipe::new(leuco_recipe));
+        }
+
+        // The planned ghosts count as capacity, so nothing more is queued
+        for _ in 0..PLANNING_INTERVAL.as_secs() {
+            app.update();
+        }
+        let mut ghost_query = app.world.query_filtered::<Entity, With<Ghost>>();
+        assert_eq!(ghost_query.iter(&app.world).count(), 2);
+    }
+
+    #[test]
+    fn planner_only_runs_when_targets_change_or_the_interval_passes() {
+        let mut app = planner_app();
+        app.update();
+
+        // Cancel the planned ghosts, leaving the colony short of its target again
+        let mut ghost_query = app.world.query_filtered::<&TilePos, With<Ghost>>();
+        let ghost_tiles: Vec<TilePos> = ghost_query.iter(&app.world).copied().collect();
+        for tile_pos in ghost_tiles {
+            let ghost_entity = app
+                .world
+                .resource_mut::<MapGeometry>()
+                .remove_ghost(tile_pos)
+                .unwrap();
+            app.world.despawn(ghost_entity);
+        }
+
+        let count_ghosts = |app: &mut App| {
+            app.world
+                .query_filtered::<Entity, With<Ghost>>()
+                .iter(&app.world)
+                .count()
+        };
+
+        // Nothing is planned until the interval has passed
+        for _ in 1..PLANNING_INTERVAL.as_secs() {
+            app.update();
+            assert_eq!(count_ghosts(&mut app), 0);
+        }
+        app.update();
+        assert_eq!(count_ghosts(&mut app), 2);
+
+        // Changing the targets triggers a new pass right away
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+        app.world.resource_mut::<ProductionTargets>().set(
+            leuco_chunk,
+            ProductionTarget {
+                desired_stock: 20,
+                items_per_minute: 25.,
+            },
+        );
+        app.update();
+        assert_eq!(count_ghosts(&mut app), 3);
+    }
+
+    #[test]
+    fn planner_stops_once_the_map_is_full() {
+        let mut app = planner_app();
+
+        // Far more producers than there are tiles
+        app.world.resource_mut::<ProductionTargets>().set(
+            Id::from_name("leuco_chunk"),
+            ProductionTarget {
+                desired_stock: 20,
+                items_per_minute: 1000.,
+            },
+        );
+        app.update();
+
+        // Every tile of the radius 1 map is covered, and the rest of the producers are skipped
+        let mut ghost_query = app.world.query_filtered::<Entity, With<Ghost>>();
+        assert_eq!(ghost_query.iter(&app.world).count(), 7);
+    }
+}
@@ -0,0 +1,143 @@
+//! Structures that generate power, and structures that need power in order to craft.
+//!
+//! For now, every structure on the map is connected to a single, global [`EnergyGrid`].
+
+use bevy::prelude::*;
+
+use crate::simulation::SimulationSet;
+
+/// Balances the supply and demand of power across the map.
+pub(super) struct PowerPlugin;
+
+impl Plugin for PowerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EnergyGrid>().add_system(
+            distribute_power
+                .in_set(SimulationSet)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// The total supply and demand of power, recomputed each tick.
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub(crate) struct EnergyGrid {
+    /// The total power provided by all [`PowerGenerator`]s.
+    pub(crate) supply: f32,
+    /// The total power requested by all [`PowerConsumer`]s.
+    pub(crate) demand: f32,
+}
+
+impl EnergyGrid {
+    /// Is more power being requested than is being supplied?
+    pub(crate) fn is_in_deficit(&self) -> bool {
+        self.demand > self.supply
+    }
+}
+
+/// A structure that adds power to the [`EnergyGrid`].
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PowerGenerator {
+    /// The amount of power supplied each tick.
+    supply: f32,
+}
+
+impl PowerGenerator {
+    /// Creates a new [`PowerGenerator`] that supplies the provided amount of power.
+    pub(crate) fn new(supply: f32) -> Self {
+        PowerGenerator { supply }
+    }
+}
+
+/// A structure that cannot craft without drawing power from the [`EnergyGrid`].
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PowerConsumer {
+    /// The amount of power needed each tick.
+    draw: f32,
+    /// Did this structure receive the power it needed this tick?
+    powered: bool,
+}
+
+impl PowerConsumer {
+    /// Creates a new [`PowerConsumer`] that needs the provided amount of power.
+    ///
+    /// Consumers start unpowered, until power is next distributed.
+    pub(crate) fn new(draw: f32) -> Self {
+        PowerConsumer {
+            draw,
+            powered: false,
+        }
+    }
+
+    /// Did this structure receive the power it needed this tick?
+    pub(crate) fn is_powered(&self) -> bool {
+        self.powered
+    }
+}
+
+/// Recomputes the [`EnergyGrid`], and decides which [`PowerConsumer`]s receive power.
+///
+/// When there is not enough power to go around, consumers are powered one at a time until the supply runs out.
+/// Partially powered consumers are not allowed to run.
+pub(super) fn distribute_power(
+    generator_query: Query<&PowerGenerator>,
+    mut consumer_query: Query<(Entity, &mut PowerConsumer)>,
+    mut energy_grid: ResMut<EnergyGrid>,
+) {
+    let supply: f32 = generator_query
+        .iter()
+        .map(|generator| generator.supply)
+        .sum();
+
+    let demand: f32 = consumer_query
+        .iter()
+        .map(|(_, consumer)| consumer.draw)
+        .sum();
+    *energy_grid = EnergyGrid { supply, demand };
+
+    let mut consumers: Vec<_> = consumer_query.iter_mut().collect();
+    // Sort to ensure that the same consumers are powered each tick
+    consumers.sort_by_key(|(entity, _)| *entity);
+
+    let mut remaining_supply = supply;
+    for (_, consumer) in consumers.iter_mut() {
+        let powered = !energy_grid.is_in_deficit() || consumer.draw <= remaining_supply;
+        if powered {
+            remaining_supply -= consumer.draw;
+        }
+
+        // Avoid triggering change detection when nothing has changed
+        if consumer.powered != powered {
+            consumer.powered = powered;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consumers_are_powered_until_supply_runs_out() {
+        let mut app = App::new();
+        app.init_resource::<EnergyGrid>()
+            .add_system(distribute_power);
+
+        app.world.spawn(PowerGenerator::new(10.));
+        let first_consumer = app.world.spawn(PowerConsumer::new(6.)).id();
+        let second_consumer = app.world.spawn(PowerConsumer::new(6.)).id();
+
+        app.update();
+
+        let energy_grid = app.world.resource::<EnergyGrid>();
+        assert_eq!(energy_grid.supply, 10.);
+        assert_eq!(energy_grid.demand, 12.);
+        assert!(energy_grid.is_in_deficit());
+
+        let powered_count = [first_consumer, second_consumer]
+            .iter()
+            .filter(|&&entity| app.world.get::<PowerConsumer>(entity).unwrap().is_powered())
+            .count();
+        assert_eq!(powered_count, 1);
+    }
+}
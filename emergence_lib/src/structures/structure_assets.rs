@@ -6,7 +6,7 @@ use crate::{
     player_interaction::selection::ObjectInteraction,
     simulation::geometry::{hexagonal_column, MapGeometry},
     structures::{
-        construction::GhostKind,
+        construction::{ghost_progress_material, GhostKind, GHOST_PROGRESS_STEPS},
         structure_manifest::{Structure, StructureManifest},
     },
 };
@@ -19,6 +19,8 @@ pub(crate) struct StructureHandles {
     pub(crate) scenes: HashMap<Id<Structure>, Handle<Scene>>,
     /// The materials used for tiles when they are selected or otherwise interacted with
     pub(crate) ghost_materials: HashMap<GhostKind, Handle<StandardMaterial>>,
+    /// The materials used for ghosts, indexed by how many of their construction materials have been delivered
+    pub(crate) ghost_progress_materials: Vec<Handle<StandardMaterial>>,
     /// The raycasting mesh used to select structures
     pub(crate) picking_mesh: Handle<Mesh>,
 }
@@ -53,9 +55,14 @@ impl Loadable for StructureHandles {
             ghost_materials.insert(variant, material_handle);
         }
 
+        let ghost_progress_materials = (0..GHOST_PROGRESS_STEPS)
+            .map(|step| material_assets.add(ghost_progress_material(step)))
+            .collect();
+
         let mut handles = StructureHandles {
             scenes: HashMap::default(),
             ghost_materials,
+            ghost_progress_materials,
             picking_mesh,
         };
 
@@ -2,11 +2,12 @@
 
 use crate::{
     asset_management::manifest::{loader::RawManifest, Id, Manifest},
-    items::item_manifest::Item,
+    items::inventory::ReservedFor,
     organisms::{OrganismId, OrganismVariety},
     structures::{
         construction::Footprint,
         crafting::{ActiveRecipe, InputInventory},
+        health::Health,
     },
     terrain::terrain_manifest::Terrain,
 };
@@ -14,6 +15,7 @@ use bevy::{
     reflect::{FromReflect, Reflect, TypeUuid},
     utils::{Duration, HashMap, HashSet},
 };
+use hexx::Direction;
 
 use serde::{Deserialize, Serialize};
 
@@ -34,16 +36,61 @@ pub struct StructureData {
     pub kind: StructureKind,
     /// How new copies of this structure can be built
     pub construction_strategy: ConstructionStrategy,
+    /// The structure that this structure can be upgraded into in place, if any.
+    #[serde(default)]
+    pub upgrades_to: Option<Id<Structure>>,
+    /// The work and materials needed to upgrade this structure into its `upgrades_to` form.
+    #[serde(default)]
+    pub upgrade_cost: UpgradeCost,
     /// The maximum number of workers that can work at this structure at once.
     pub max_workers: u8,
+    /// How far from this structure, in tiles, its [`SignalType::Work`](crate::signals::SignalType::Work) signals are emitted.
+    ///
+    /// Larger values recruit idle units from further away.
+    /// At 0, work signals are only emitted from the structure's own footprint.
+    #[serde(default)]
+    pub work_radius: u32,
+    /// The amount of power this structure needs from the [`EnergyGrid`](super::power::EnergyGrid) to craft.
+    #[serde(default)]
+    pub power_draw: f32,
+    /// The amount of power this structure provides to the [`EnergyGrid`](super::power::EnergyGrid).
+    #[serde(default)]
+    pub power_supply: f32,
+    /// The health of this structure when it is first built.
+    ///
+    /// Structures start at full health unless otherwise specified.
+    #[serde(default)]
+    pub health: Health,
     /// The tiles taken up by this building.
     pub footprint: Footprint,
 }
 
+impl Default for StructureData {
+    /// A single-tile crafting structure with no recipe, which can be built instantly and for free.
+    fn default() -> Self {
+        StructureData {
+            organism_variety: None,
+            kind: StructureKind::Crafting {
+                starting_recipe: ActiveRecipe::NONE,
+                output_direction: None,
+            },
+            construction_strategy: ConstructionStrategy::default(),
+            upgrades_to: None,
+            upgrade_cost: UpgradeCost::default(),
+            max_workers: 1,
+            work_radius: 0,
+            power_draw: 0.,
+            power_supply: 0.,
+            health: Health::default(),
+            footprint: Footprint::single(),
+        }
+    }
+}
+
 /// How new structures of this sort can be built.
 ///
 /// For structures that are part of a `Lifecycle`, this should generally be the same for all of them.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ConstructionStrategy {
     /// The "seedling" or "baby" form of this structure that should be built when we attempt to build a structure of this type.
     ///
@@ -57,6 +104,33 @@ pub struct ConstructionStrategy {
     pub materials: InputInventory,
     /// The set of terrain types that this structure can be built on
     pub allowed_terrain_types: HashSet<Id<Terrain>>,
+    /// The terrain or structures that must be found next to this structure for it to be built.
+    ///
+    /// Each requirement must be met by at least one tile bordering the structure's footprint.
+    #[serde(default)]
+    pub required_adjacency: HashSet<AdjacencyRequirement>,
+}
+
+/// Something that must be found next to a structure for it to be built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AdjacencyRequirement {
+    /// A tile of this terrain type.
+    Terrain(Id<Terrain>),
+    /// A structure of this type.
+    ///
+    /// Ghosts and previews do not count.
+    Structure(Id<Structure>),
+}
+
+/// The price paid by units to upgrade a structure into its more advanced form.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpgradeCost {
+    /// The amount of work by units required once all materials have been delivered.
+    ///
+    /// If this is [`Duration::ZERO`], the upgrade completes as soon as the materials arrive.
+    pub work: Duration,
+    /// The set of items consumed by the upgrade
+    pub materials: InputInventory,
 }
 
 /// What set of components should this structure have?
@@ -66,14 +140,31 @@ pub enum StructureKind {
     Storage {
         /// The number of slots in the inventory, controlling how large it is.
         max_slot_count: usize,
-        /// Is any item allowed here, or just one?
-        reserved_for: Option<Id<Item>>,
+        /// Which items are allowed here?
+        ///
+        /// This may be a single item, a list of items, or `null` to allow any item.
+        #[serde(default)]
+        reserved_for: ReservedFor,
     },
     /// Crafts items, turning inputs into outputs.
     Crafting {
         /// Does this structure start with a recipe pre-selected?
         starting_recipe: ActiveRecipe,
+        /// Which neighboring tile should outputs be handed off to, relative to the structure's [`Facing`](crate::simulation::geometry::Facing)?
+        ///
+        /// Units prefer to pick up items from this side, and conveyors placed there will be fed automatically.
+        #[serde(default)]
+        output_direction: Option<Direction>,
+    },
+    /// Moves items to the structure next to it, forming belts when chained together.
+    Conveyor {
+        /// The direction that items are moved in, relative to the structure's [`Facing`](crate::simulation::geometry::Facing).
+        direction: Direction,
+        /// The number of slots in the conveyor's buffer.
+        max_slot_count: usize,
     },
+    /// Lets units walk across its tiles, no matter how steep the surrounding terrain is.
+    Bridge,
 }
 
 impl StructureData {
@@ -81,17 +172,34 @@ impl StructureData {
     ///
     /// If no starting recipe is set, [`ActiveRecipe::NONE`] will be returned.
     pub fn starting_recipe(&self) -> &ActiveRecipe {
-        if let StructureKind::Crafting { starting_recipe } = &self.kind {
+        if let StructureKind::Crafting {
+            starting_recipe, ..
+        } = &self.kind
+        {
             starting_recipe
         } else {
             &ActiveRecipe::NONE
         }
     }
 
+    /// Returns the items that this structure's storage is reserved for when first built.
+    pub fn starting_reserved_for(&self) -> ReservedFor {
+        if let StructureKind::Storage { reserved_for, .. } = &self.kind {
+            reserved_for.clone()
+        } else {
+            ReservedFor::Any
+        }
+    }
+
     /// Returns the set of terrain types that this structure can be built on
     pub fn allowed_terrain_types(&self) -> &HashSet<Id<Terrain>> {
         &self.construction_strategy.allowed_terrain_types
     }
+
+    /// Returns the set of terrain or structures that must be next to this structure for it to be built
+    pub fn required_adjacency(&self) -> &HashSet<AdjacencyRequirement> {
+        &self.construction_strategy.required_adjacency
+    }
 }
 
 impl StructureManifest {
@@ -99,13 +207,12 @@ impl StructureManifest {
     ///
     /// These should be used to populate menus and other player-facing tools.
     pub(crate) fn prototypes(&self) -> impl IntoIterator<Item = Id<Structure>> + '_ {
-        self.data_map()
-            .iter()
+        self.iter()
             .filter(|(id, v)| match &v.organism_variety {
                 None => true,
-                Some(variety) => variety.prototypical_form == OrganismId::Structure(**id),
+                Some(variety) => variety.prototypical_form == OrganismId::Structure(*id),
             })
-            .map(|(id, _v)| *id)
+            .map(|(id, _v)| id)
     }
 
     /// Returns the names of all structures where [`StructureData`]'s `prototypical` field is `true`.
@@ -142,3 +249,50 @@ impl RawManifest for RawStructureManifest {
         manifest
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A plain storage structure, which is its own prototype.
+    fn storage_data() -> StructureData {
+        StructureData {
+            kind: StructureKind::Storage {
+                max_slot_count: 1,
+                reserved_for: ReservedFor::Any,
+            },
+            construction_strategy: ConstructionStrategy {
+                seedling: None,
+                work: Duration::ZERO,
+                materials: InputInventory::default(),
+                allowed_terrain_types: HashSet::from_iter([Id::<Terrain>::from_name("loam")]),
+                required_adjacency: HashSet::new(),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn prototype_names_are_sorted_regardless_of_insertion_order() {
+        let names = ["storage", "ladder", "path", "hatchery", "vat"];
+
+        let mut forwards = StructureManifest::new();
+        for name in names {
+            forwards.insert(name, storage_data());
+        }
+
+        let mut backwards = StructureManifest::new();
+        for name in names.iter().rev() {
+            backwards.insert(name, storage_data());
+        }
+
+        let forwards_names: Vec<&str> = forwards.prototype_names().into_iter().collect();
+        let backwards_names: Vec<&str> = backwards.prototype_names().into_iter().collect();
+
+        assert_eq!(
+            forwards_names,
+            vec!["hatchery", "ladder", "path", "storage", "vat"]
+        );
+        assert_eq!(forwards_names, backwards_names);
+    }
+}
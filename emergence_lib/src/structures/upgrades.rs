@@ -0,0 +1,455 @@
+//! Player-built structures can be upgraded in place into a more advanced form.
+//!
+//! Units bring the materials listed in the [`UpgradeCost`] of the structure, then perform the required work.
+//! Once complete, the structure is swapped for its upgraded form, keeping its position and contents.
+
+use bevy::{ecs::system::SystemParam, prelude::*, utils::Duration};
+
+use crate::{
+    asset_management::manifest::Id,
+    items::item_manifest::Item,
+    signals::{Emitter, SignalStrength, SignalType},
+    simulation::{
+        geometry::{MapGeometry, TilePos},
+        SimulationSet,
+    },
+};
+
+use super::{
+    commands::StructureCommandsExt,
+    construction::MarkedForDemolition,
+    crafting::{set_crafting_emitter, set_storage_emitter, InputInventory},
+    structure_manifest::{Structure, UpgradeCost},
+};
+
+/// Asks units to upgrade marked structures, and swaps them for their upgraded form once done.
+pub(super) struct UpgradePlugin;
+
+impl Plugin for UpgradePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                emit_upgrade_signals
+                    .after(set_crafting_emitter)
+                    .after(set_storage_emitter),
+                complete_upgrades,
+            )
+                .in_set(SimulationSet)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
+
+/// A structure that should be upgraded into the `upgrades_to` structure.
+///
+/// This tracks the progress made by units towards the [`UpgradeCost`] of the structure.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub(crate) struct MarkedForUpgrade {
+    /// The structure that this structure will become.
+    upgrades_to: Id<Structure>,
+    /// The materials that have been delivered so far.
+    materials: InputInventory,
+    /// The amount of work that must still be done once all materials have been delivered.
+    work_remaining: Duration,
+}
+
+impl MarkedForUpgrade {
+    /// The amount of work performed by a single upgrade action.
+    pub(crate) const WORK_PER_ACTION: Duration = Duration::from_secs(1);
+
+    /// Creates a new [`MarkedForUpgrade`], where none of the `upgrade_cost` has been paid yet.
+    pub(crate) fn new(upgrades_to: Id<Structure>, upgrade_cost: &UpgradeCost) -> Self {
+        MarkedForUpgrade {
+            upgrades_to,
+            materials: upgrade_cost.materials.clone(),
+            work_remaining: upgrade_cost.work,
+        }
+    }
+
+    /// The structure that this structure will become.
+    pub(crate) fn upgrades_to(&self) -> Id<Structure> {
+        self.upgrades_to
+    }
+
+    /// Have all of the materials needed been delivered?
+    pub(crate) fn has_materials(&self) -> bool {
+        self.materials.iter().all(|item_slot| item_slot.is_full())
+    }
+
+    /// Has the upgrade been fully paid for?
+    pub(crate) fn is_complete(&self) -> bool {
+        self.has_materials() && self.work_remaining.is_zero()
+    }
+
+    /// Makes progress on the upgrade, using the `held_item` if it is still needed.
+    ///
+    /// Once all of the materials have been delivered, each call performs `work` instead.
+    /// Returns `Ok(true)` if the held item was consumed.
+    pub(crate) fn contribute(
+        &mut self,
+        held_item: Option<Id<Item>>,
+        work: Duration,
+    ) -> Result<bool, UpgradeError> {
+        if let Some(item_id) = held_item {
+            if let Some(item_slot) = self
+                .materials
+                .iter_mut()
+                .find(|item_slot| item_slot.is_for_item(item_id) && !item_slot.is_full())
+            {
+                // We just checked that there's room for this item
+                let _ = item_slot.add_all_or_nothing(1);
+                return Ok(true);
+            }
+        }
+
+        if !self.has_materials() {
+            return match held_item {
+                Some(_) => Err(UpgradeError::WrongMaterial),
+                None => Err(UpgradeError::NoMaterials),
+            };
+        }
+
+        self.work_remaining = self.work_remaining.saturating_sub(work);
+        Ok(false)
+    }
+}
+
+/// The reasons why a unit may fail to upgrade a structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UpgradeError {
+    /// The unit is not carrying anything, but materials are still needed.
+    NoMaterials,
+    /// The unit is carrying an item that is not needed for this upgrade.
+    WrongMaterial,
+}
+
+/// A query for structures that are waiting to be upgraded.
+#[derive(SystemParam)]
+pub(crate) struct UpgradeQuery<'w, 's> {
+    /// The contained query type.
+    query: Query<
+        'w,
+        's,
+        (&'static Id<Structure>, &'static MarkedForUpgrade),
+        Without<MarkedForDemolition>,
+    >,
+}
+
+impl<'w, 's> UpgradeQuery<'w, 's> {
+    /// Is there a structure of type `structure_id` at `structure_pos` that needs to be upgraded?
+    ///
+    /// If so, returns `Some(matching_structure_entity_that_needs_to_be_upgraded)`.
+    pub(crate) fn needs_upgrade(
+        &self,
+        structure_pos: TilePos,
+        structure_id: Id<Structure>,
+        map_geometry: &MapGeometry,
+    ) -> Option<Entity> {
+        let entity = map_geometry.get_structure(structure_pos)?;
+
+        let (&found_structure_id, mark) = self.query.get(entity).ok()?;
+
+        match found_structure_id == structure_id && !mark.is_complete() {
+            true => Some(entity),
+            false => None,
+        }
+    }
+}
+
+/// Structures that are marked for upgrade ask units to come and upgrade them.
+fn emit_upgrade_signals(
+    mut structure_query: Query<
+        (&mut Emitter, &MarkedForUpgrade, &Id<Structure>),
+        Without<MarkedForDemolition>,
+    >,
+) {
+    for (mut emitter, mark, &structure_id) in structure_query.iter_mut() {
+        if !mark.is_complete() {
+            emitter
+                .signals
+                .push((SignalType::Upgrade(structure_id), SignalStrength::new(100.)));
+        }
+    }
+}
+
+/// Swaps structures whose upgrade has been paid for with their upgraded form.
+fn complete_upgrades(
+    structure_query: Query<(&TilePos, &MarkedForUpgrade)>,
+    mut commands: Commands,
+) {
+    for (&tile_pos, mark) in structure_query.iter() {
+        if mark.is_complete() {
+            commands.upgrade_structure(tile_pos, mark.upgrades_to);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::CommandQueue,
+        utils::{HashMap, HashSet},
+    };
+
+    use super::*;
+    use crate::{
+        items::{
+            inventory::{Inventory, ReservedFor},
+            item_manifest::{ItemData, ItemManifest},
+            ItemCount,
+        },
+        player_interaction::clipboard::ClipboardData,
+        simulation::{factions::Faction, geometry::Height},
+        structures::{
+            construction::Footprint,
+            crafting::StorageInventory,
+            structure_assets::StructureHandles,
+            structure_manifest::{
+                ConstructionStrategy, StructureData, StructureKind, StructureManifest,
+            },
+        },
+        terrain::terrain_manifest::Terrain,
+    };
+
+    /// A storage structure with `max_slot_count` slots, which can be built on `terrain_id`.
+    fn storage_data(
+        max_slot_count: usize,
+        upgrades_to: Option<Id<Structure>>,
+        upgrade_cost: UpgradeCost,
+        terrain_id: Id<Terrain>,
+    ) -> StructureData {
+        StructureData {
+            kind: StructureKind::Storage {
+                max_slot_count,
+                reserved_for: ReservedFor::Any,
+            },
+            construction_strategy: ConstructionStrategy {
+                seedling: None,
+                work: Duration::ZERO,
+                materials: InputInventory::default(),
+                allowed_terrain_types: HashSet::from_iter([terrain_id]),
+                required_adjacency: HashSet::new(),
+            },
+            upgrades_to,
+            upgrade_cost,
+            ..Default::default()
+        }
+    }
+
+    /// Creates an app that completes upgrades on a single tile of `terrain_id`, without any structures to start with.
+    fn upgrade_app(
+        item_manifest: ItemManifest,
+        structure_manifest: StructureManifest,
+        terrain_id: Id<Terrain>,
+    ) -> App {
+        let scenes = HashMap::from_iter(
+            structure_manifest
+                .variants()
+                .into_iter()
+                .map(|structure_id| (structure_id, Handle::default())),
+        );
+
+        let mut app = App::new();
+        let terrain_entity = app.world.spawn(terrain_id).id();
+        let mut map_geometry = MapGeometry::new(0);
+        map_geometry.update_height(TilePos::ZERO, Height(1));
+        map_geometry.add_terrain(TilePos::ZERO, terrain_entity);
+
+        app.insert_resource(item_manifest)
+            .insert_resource(structure_manifest)
+            .insert_resource(map_geometry)
+            .insert_resource(StructureHandles {
+                scenes,
+                ghost_materials: HashMap::default(),
+                ghost_progress_materials: Vec::new(),
+                picking_mesh: Handle::default(),
+            })
+            .add_system(complete_upgrades);
+        app
+    }
+
+    /// Builds a structure of type `structure_id` in the middle of the `app`'s map.
+    fn spawn_structure(app: &mut App, structure_id: Id<Structure>) -> Entity {
+        let data = ClipboardData::new(structure_id, app.world.resource::<StructureManifest>());
+        let mut command_queue = CommandQueue::default();
+        Commands::new(&mut command_queue, &app.world).spawn_structure(
+            TilePos::ZERO,
+            data,
+            Faction::COLONY,
+        );
+        command_queue.apply(&mut app.world);
+
+        app.world
+            .resource::<MapGeometry>()
+            .get_structure(TilePos::ZERO)
+            .unwrap()
+    }
+
+    #[test]
+    fn upgrades_need_materials_then_work() {
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+        let acacia_leaf = Id::<Item>::from_name("acacia_leaf");
+        let upgrade_cost = UpgradeCost {
+            work: Duration::from_secs(2),
+            materials: InputInventory {
+                inventory: Inventory::new_from_item(leuco_chunk, 1),
+            },
+        };
+        let mut mark = MarkedForUpgrade::new(Id::from_name("large_storage"), &upgrade_cost);
+
+        assert_eq!(
+            mark.contribute(None, MarkedForUpgrade::WORK_PER_ACTION),
+            Err(UpgradeError::NoMaterials)
+        );
+        assert_eq!(
+            mark.contribute(Some(acacia_leaf), MarkedForUpgrade::WORK_PER_ACTION),
+            Err(UpgradeError::WrongMaterial)
+        );
+        assert_eq!(
+            mark.contribute(Some(leuco_chunk), MarkedForUpgrade::WORK_PER_ACTION),
+            Ok(true)
+        );
+        assert!(mark.has_materials());
+        assert!(!mark.is_complete());
+
+        // Extra materials are not consumed once everything has been delivered
+        assert_eq!(
+            mark.contribute(Some(leuco_chunk), MarkedForUpgrade::WORK_PER_ACTION),
+            Ok(false)
+        );
+        assert!(!mark.is_complete());
+        assert_eq!(
+            mark.contribute(None, MarkedForUpgrade::WORK_PER_ACTION),
+            Ok(false)
+        );
+        assert!(mark.is_complete());
+    }
+
+    #[test]
+    fn upgraded_storage_keeps_its_items() {
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+        let acacia_leaf = Id::<Item>::from_name("acacia_leaf");
+        let loam = Id::<Terrain>::from_name("loam");
+        let small_storage = Id::<Structure>::from_name("small_storage");
+        let large_storage = Id::<Structure>::from_name("large_storage");
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(5));
+        item_manifest.insert("acacia_leaf", ItemData::new(5));
+
+        let upgrade_cost = UpgradeCost {
+            work: Duration::ZERO,
+            materials: InputInventory::default(),
+        };
+        let mut structure_manifest = StructureManifest::new();
+        structure_manifest.insert(
+            "small_storage",
+            storage_data(2, Some(large_storage), upgrade_cost.clone(), loam),
+        );
+        structure_manifest.insert(
+            "large_storage",
+            storage_data(6, None, UpgradeCost::default(), loam),
+        );
+
+        let mut app = upgrade_app(item_manifest, structure_manifest, loam);
+        let small_entity = spawn_structure(&mut app, small_storage);
+        app.world
+            .resource_scope(|world, item_manifest: Mut<ItemManifest>| {
+                let mut storage_inventory =
+                    world.get_mut::<StorageInventory>(small_entity).unwrap();
+                storage_inventory
+                    .add_item_all_or_nothing(&ItemCount::new(leuco_chunk, 5), &item_manifest)
+                    .unwrap();
+                storage_inventory
+                    .add_item_all_or_nothing(&ItemCount::new(acacia_leaf, 3), &item_manifest)
+                    .unwrap();
+            });
+
+        app.world
+            .entity_mut(small_entity)
+            .insert(MarkedForUpgrade::new(large_storage, &upgrade_cost));
+        app.update();
+
+        assert!(app.world.get_entity(small_entity).is_none());
+        let large_entity = app
+            .world
+            .resource::<MapGeometry>()
+            .get_structure(TilePos::ZERO)
+            .unwrap();
+        assert_eq!(
+            *app.world.get::<Id<Structure>>(large_entity).unwrap(),
+            large_storage
+        );
+        assert_eq!(
+            *app.world.get::<TilePos>(large_entity).unwrap(),
+            TilePos::ZERO
+        );
+
+        let storage_inventory = app.world.get::<StorageInventory>(large_entity).unwrap();
+        // The larger storage has room to spare
+        assert_eq!(storage_inventory.free_slot_count(), 4);
+        assert_eq!(storage_inventory.item_count(leuco_chunk), 5);
+        assert_eq!(storage_inventory.item_count(acacia_leaf), 3);
+    }
+
+    #[test]
+    fn upgrades_that_do_not_fit_keep_the_original() {
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+        let loam = Id::<Terrain>::from_name("loam");
+        let small_storage = Id::<Structure>::from_name("small_storage");
+        let huge_storage = Id::<Structure>::from_name("huge_storage");
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(5));
+
+        let upgrade_cost = UpgradeCost {
+            work: Duration::ZERO,
+            materials: InputInventory::default(),
+        };
+        let mut structure_manifest = StructureManifest::new();
+        structure_manifest.insert(
+            "small_storage",
+            storage_data(2, Some(huge_storage), upgrade_cost.clone(), loam),
+        );
+        // The map is a single tile, so this can never fit
+        structure_manifest.insert(
+            "huge_storage",
+            StructureData {
+                footprint: Footprint::hexagon(1),
+                ..storage_data(6, None, UpgradeCost::default(), loam)
+            },
+        );
+
+        let mut app = upgrade_app(item_manifest, structure_manifest, loam);
+        let small_entity = spawn_structure(&mut app, small_storage);
+        app.world
+            .resource_scope(|world, item_manifest: Mut<ItemManifest>| {
+                world
+                    .get_mut::<StorageInventory>(small_entity)
+                    .unwrap()
+                    .add_item_all_or_nothing(&ItemCount::new(leuco_chunk, 5), &item_manifest)
+                    .unwrap();
+            });
+
+        app.world
+            .entity_mut(small_entity)
+            .insert(MarkedForUpgrade::new(huge_storage, &upgrade_cost));
+        app.update();
+
+        assert_eq!(
+            app.world
+                .resource::<MapGeometry>()
+                .get_structure(TilePos::ZERO),
+            Some(small_entity)
+        );
+        assert_eq!(
+            app.world
+                .get::<StorageInventory>(small_entity)
+                .unwrap()
+                .item_count(leuco_chunk),
+            5
+        );
+        // The upgrade is not retried every tick
+        assert!(app.world.get::<MarkedForUpgrade>(small_entity).is_none());
+    }
+}
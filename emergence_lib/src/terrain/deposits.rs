@@ -0,0 +1,96 @@
+//! Finite deposits of resources found beneath terrain tiles.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset_management::{localization::Localization, manifest::Id},
+    items::{
+        item_manifest::{Item, ItemManifest},
+        ItemCount,
+    },
+};
+
+/// A finite supply of an item stored in a terrain tile, which can be extracted by mining recipes.
+///
+/// Once the deposit is exhausted, no more items can be mined from it.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceDeposit {
+    /// The item that is stored in this deposit.
+    item_id: Id<Item>,
+    /// The number of items left in this deposit.
+    remaining: usize,
+}
+
+impl ResourceDeposit {
+    /// Creates a new deposit, filled with `contents`.
+    pub fn new(contents: &ItemCount) -> Self {
+        ResourceDeposit {
+            item_id: contents.item_id(),
+            remaining: contents.count(),
+        }
+    }
+
+    /// The item that is stored in this deposit.
+    pub fn item_id(&self) -> Id<Item> {
+        self.item_id
+    }
+
+    /// The number of items left in this deposit.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Is there nothing left to mine?
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Can `item_count` be mined from this deposit in full?
+    pub(crate) fn can_mine(&self, item_count: &ItemCount) -> bool {
+        self.item_id == item_count.item_id() && self.remaining >= item_count.count()
+    }
+
+    /// Removes `item_count` from this deposit.
+    ///
+    /// Returns `false` and leaves the deposit untouched if it does not contain enough of the item.
+    pub(crate) fn mine(&mut self, item_count: &ItemCount) -> bool {
+        if self.can_mine(item_count) {
+            self.remaining -= item_count.count();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The pretty text formatting of this type.
+    pub fn display(&self, item_manifest: &ItemManifest, localization: &Localization) -> String {
+        let name = localization.get(item_manifest.name(self.item_id));
+        format!("{name} ({} remaining)", self.remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deposits_are_mined_until_exhausted() {
+        let stone = Id::from_name("stone");
+        let mut deposit = ResourceDeposit::new(&ItemCount::new(stone, 3));
+
+        assert!(deposit.mine(&ItemCount::new(stone, 2)));
+        assert_eq!(deposit.remaining(), 1);
+
+        // Partial amounts are never mined
+        assert!(!deposit.mine(&ItemCount::new(stone, 2)));
+        assert_eq!(deposit.remaining(), 1);
+
+        // Only the stored item can be mined
+        assert!(!deposit.mine(&ItemCount::one(Id::from_name("ore"))));
+
+        assert!(deposit.mine(&ItemCount::one(stone)));
+        assert!(deposit.is_exhausted());
+        assert!(!deposit.can_mine(&ItemCount::one(stone)));
+    }
+}
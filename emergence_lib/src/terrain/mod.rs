@@ -12,9 +12,11 @@ use crate::player_interaction::zoning::Zoning;
 use crate::simulation::geometry::{Height, MapGeometry, TilePos};
 use crate::simulation::SimulationSet;
 
+use self::deposits::ResourceDeposit;
 use self::terrain_assets::TerrainHandles;
 use self::terrain_manifest::{RawTerrainManifest, Terrain};
 
+pub mod deposits;
 pub(crate) mod terrain_assets;
 pub mod terrain_manifest;
 
@@ -25,8 +27,8 @@ impl Plugin for TerrainPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(ManifestPlugin::<RawTerrainManifest>::new())
             .add_asset_collection::<TerrainHandles>()
-            .add_system(
-                respond_to_height_changes
+            .add_systems(
+                (respond_to_height_changes, respond_to_terrain_type_changes)
                     .in_set(SimulationSet)
                     .in_schedule(CoreSchedule::FixedUpdate),
             );
@@ -103,6 +105,61 @@ fn respond_to_height_changes(
     }
 }
 
+/// Swaps out the scene of each tile whose terrain type has changed, using a [`ChangeTerrainCommand`].
+fn respond_to_terrain_type_changes(
+    terrain_query: Query<(&TilePos, &Id<Terrain>), Changed<Id<Terrain>>>,
+    mut commands: Commands,
+) {
+    for (&tile_pos, &terrain_id) in terrain_query.iter() {
+        commands.add(ChangeTerrainCommand {
+            tile_pos,
+            terrain_id,
+        });
+    }
+}
+
+/// Changes the type of an existing [`Terrain`] tile in place.
+///
+/// The [`Height`] of the tile and anything built on top of it are left untouched.
+/// Only the scene root (child index 2) is respawned:
+/// the column and overlay children created by [`SpawnTerrainCommand`] are preserved.
+pub(crate) struct ChangeTerrainCommand {
+    /// The position of the tile to change
+    pub(crate) tile_pos: TilePos,
+    /// The new type of the tile
+    pub(crate) terrain_id: Id<Terrain>,
+}
+
+impl Command for ChangeTerrainCommand {
+    fn write(self, world: &mut World) {
+        let Some(terrain_entity) = world.resource::<MapGeometry>().get_terrain(self.tile_pos)
+        else {
+            warn!(
+                "Could not change the terrain at {:?}: no terrain was found.",
+                self.tile_pos
+            );
+            return;
+        };
+
+        let scene_handle = world
+            .resource::<TerrainHandles>()
+            .scenes
+            .get(&self.terrain_id)
+            .unwrap()
+            .clone_weak();
+
+        let mut terrain_entity_mut = world.entity_mut(terrain_entity);
+        // Only touch components that actually change, so this doesn't retrigger respond_to_terrain_type_changes
+        if terrain_entity_mut.get::<Id<Terrain>>() != Some(&self.terrain_id) {
+            terrain_entity_mut.insert(self.terrain_id);
+        }
+        // Bevy despawns the previous scene instance and spawns the new one whenever this handle changes
+        if terrain_entity_mut.get::<Handle<Scene>>() != Some(&scene_handle) {
+            terrain_entity_mut.insert(scene_handle);
+        }
+    }
+}
+
 /// Constructs a new [`Terrain`] entity.
 ///
 /// The order of the chidlren *must* be:
@@ -116,6 +173,8 @@ pub(crate) struct SpawnTerrainCommand {
     pub(crate) height: Height,
     /// The type of tile
     pub(crate) terrain_id: Id<Terrain>,
+    /// The resources stored beneath the tile, if any
+    pub(crate) deposit: Option<ResourceDeposit>,
 }
 
 impl Command for SpawnTerrainCommand {
@@ -151,6 +210,10 @@ impl Command for SpawnTerrainCommand {
             ..Default::default()
         };
 
+        if let Some(deposit) = self.deposit {
+            world.entity_mut(terrain_entity).insert(deposit);
+        }
+
         let hex_column = world.spawn(column_bundle).id();
         world.entity_mut(terrain_entity).add_child(hex_column);
 
@@ -178,3 +241,115 @@ impl Command for SpawnTerrainCommand {
         map_geometry.add_terrain(self.tile_pos, terrain_entity);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{asset::HandleId, utils::HashMap};
+
+    use super::*;
+
+    #[test]
+    fn changing_terrain_preserves_height() {
+        let mut world = World::new();
+        let loam = Id::<Terrain>::from_name("loam");
+        let muddy = Id::<Terrain>::from_name("muddy");
+        let loam_scene = Handle::<Scene>::weak(HandleId::random::<Scene>());
+        let muddy_scene = Handle::<Scene>::weak(HandleId::random::<Scene>());
+
+        world.insert_resource(MapGeometry::new(1));
+        world.insert_resource(TerrainHandles {
+            scenes: HashMap::from_iter([(loam, loam_scene), (muddy, muddy_scene.clone())]),
+            topper_mesh: Handle::default(),
+            column_mesh: Handle::default(),
+            column_material: Handle::default(),
+            interaction_materials: HashMap::default(),
+        });
+
+        let tile_pos = TilePos::new(1, 0);
+        SpawnTerrainCommand {
+            tile_pos,
+            height: Height(3),
+            terrain_id: loam,
+            deposit: None,
+        }
+        .write(&mut world);
+        let terrain_entity = world
+            .resource::<MapGeometry>()
+            .get_terrain(tile_pos)
+            .unwrap();
+        let children_before: Vec<Entity> = world.get::<Children>(terrain_entity).unwrap().to_vec();
+
+        ChangeTerrainCommand {
+            tile_pos,
+            terrain_id: muddy,
+        }
+        .write(&mut world);
+
+        assert_eq!(
+            world.resource::<MapGeometry>().get_terrain(tile_pos),
+            Some(terrain_entity)
+        );
+        assert_eq!(*world.get::<Id<Terrain>>(terrain_entity).unwrap(), muddy);
+        assert_eq!(
+            *world.get::<Handle<Scene>>(terrain_entity).unwrap(),
+            muddy_scene
+        );
+        assert_eq!(*world.get::<Height>(terrain_entity).unwrap(), Height(3));
+        assert_eq!(
+            world
+                .resource::<MapGeometry>()
+                .get_height(tile_pos)
+                .unwrap(),
+            Height(3)
+        );
+        // The column and overlay are kept
+        assert_eq!(
+            world.get::<Children>(terrain_entity).unwrap().to_vec(),
+            children_before
+        );
+    }
+    #[test]
+    fn editing_the_terrain_type_swaps_the_scene() {
+        let mut world = World::new();
+        let loam = Id::<Terrain>::from_name("loam");
+        let muddy = Id::<Terrain>::from_name("muddy");
+        let loam_scene = Handle::<Scene>::weak(HandleId::random::<Scene>());
+        let muddy_scene = Handle::<Scene>::weak(HandleId::random::<Scene>());
+
+        world.insert_resource(MapGeometry::new(1));
+        world.insert_resource(TerrainHandles {
+            scenes: HashMap::from_iter([(loam, loam_scene), (muddy, muddy_scene.clone())]),
+            topper_mesh: Handle::default(),
+            column_mesh: Handle::default(),
+            column_material: Handle::default(),
+            interaction_materials: HashMap::default(),
+        });
+
+        let tile_pos = TilePos::new(0, 1);
+        SpawnTerrainCommand {
+            tile_pos,
+            height: Height(1),
+            terrain_id: loam,
+            deposit: None,
+        }
+        .write(&mut world);
+        let terrain_entity = world
+            .resource::<MapGeometry>()
+            .get_terrain(tile_pos)
+            .unwrap();
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(respond_to_terrain_type_changes);
+        schedule.run(&mut world);
+
+        // Other systems can simply change the terrain type, and the scene follows
+        *world.get_mut::<Id<Terrain>>(terrain_entity).unwrap() = muddy;
+        schedule.run(&mut world);
+
+        assert_eq!(
+            *world.get::<Handle<Scene>>(terrain_entity).unwrap(),
+            muddy_scene
+        );
+        assert_eq!(*world.get::<Height>(terrain_entity).unwrap(), Height(1));
+    }
+}
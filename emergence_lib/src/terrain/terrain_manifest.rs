@@ -6,7 +6,10 @@ use bevy::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::asset_management::manifest::{loader::RawManifest, Manifest};
+use crate::{
+    asset_management::manifest::{loader::RawManifest, Id, Manifest},
+    units::unit_manifest::Unit,
+};
 
 /// The marker type for [`Id<Terrain>`](super::Id).
 #[derive(Reflect, FromReflect, Clone, Copy, PartialEq, Eq)]
@@ -15,14 +18,99 @@ pub struct Terrain;
 pub type TerrainManifest = Manifest<Terrain, TerrainData>;
 
 /// Data stored in a [`TerrainManifest`] for each [`Id<Terrain>`](super::Id).
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct TerrainData {
     /// The walking speed multiplier associated with this terrain type.
     ///
     /// These values should always be strictly positive.
     /// Higher values make units walk faster.
     /// 1.0 is "normal speed".
-    pub walking_speed: f32,
+    pub walking_speed: WalkingSpeed,
+}
+
+/// How quickly units can walk across a terrain type.
+#[derive(Debug, PartialEq, Clone)]
+pub enum WalkingSpeed {
+    /// Every unit type walks at the same speed.
+    Uniform(f32),
+    /// Some unit types walk at a different speed to the rest.
+    PerUnit {
+        /// The speed of any unit type that is not listed in `units`.
+        default: f32,
+        /// The speed of specific unit types.
+        units: HashMap<Id<Unit>, f32>,
+    },
+}
+
+impl WalkingSpeed {
+    /// The walking speed multiplier for units of type `unit_id`.
+    pub fn get(&self, unit_id: Id<Unit>) -> f32 {
+        match self {
+            WalkingSpeed::Uniform(walking_speed) => *walking_speed,
+            WalkingSpeed::PerUnit { default, units } => {
+                units.get(&unit_id).copied().unwrap_or(*default)
+            }
+        }
+    }
+
+    /// The unit types that walk at their own speed, rather than the default.
+    pub(crate) fn unit_overrides(&self) -> impl Iterator<Item = Id<Unit>> + '_ {
+        let units = match self {
+            WalkingSpeed::Uniform(_) => None,
+            WalkingSpeed::PerUnit { units, .. } => Some(units),
+        };
+
+        units.into_iter().flat_map(|units| units.keys().copied())
+    }
+}
+
+/// The [`TerrainData`] as seen in the manifest file.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct RawTerrainData {
+    /// The walking speed multiplier associated with this terrain type.
+    pub walking_speed: RawWalkingSpeed,
+}
+
+impl From<&RawTerrainData> for TerrainData {
+    fn from(raw: &RawTerrainData) -> Self {
+        TerrainData {
+            walking_speed: (&raw.walking_speed).into(),
+        }
+    }
+}
+
+/// The [`WalkingSpeed`] as seen in the manifest file.
+///
+/// This is written either as a bare number, which applies to every unit type,
+/// or as a `default` speed alongside a map of overrides for particular unit types.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum RawWalkingSpeed {
+    /// Every unit type walks at the same speed.
+    Uniform(f32),
+    /// Some unit types walk at a different speed to the rest.
+    PerUnit {
+        /// The speed of any unit type that is not listed in `units`.
+        default: f32,
+        /// The speed of specific unit types, keyed by the name of the unit type.
+        units: HashMap<String, f32>,
+    },
+}
+
+impl From<&RawWalkingSpeed> for WalkingSpeed {
+    fn from(raw: &RawWalkingSpeed) -> Self {
+        match raw {
+            RawWalkingSpeed::Uniform(walking_speed) => WalkingSpeed::Uniform(*walking_speed),
+            // Resolve the names once, so units don't need to hash them every time they move
+            RawWalkingSpeed::PerUnit { default, units } => WalkingSpeed::PerUnit {
+                default: *default,
+                units: units
+                    .iter()
+                    .map(|(name, &walking_speed)| (Id::from_name(name), walking_speed))
+                    .collect(),
+            },
+        }
+    }
 }
 
 /// The [`TerrainManifest`] as seen in the manifest file.
@@ -30,7 +118,7 @@ pub struct TerrainData {
 #[uuid = "8d6b3b65-9b11-42a9-a795-f95b06653070"]
 pub struct RawTerrainManifest {
     /// The data for each item.
-    pub terrain_types: HashMap<String, TerrainData>,
+    pub terrain_types: HashMap<String, RawTerrainData>,
 }
 
 impl RawManifest for RawTerrainManifest {
@@ -43,10 +131,40 @@ impl RawManifest for RawTerrainManifest {
         let mut manifest = Manifest::new();
 
         for (name, raw_data) in &self.terrain_types {
-            // No additional preprocessing is needed.
-            manifest.insert(name, raw_data.clone())
+            manifest.insert(name, raw_data.into())
         }
 
         manifest
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_unit_walking_speeds_are_resolved_when_processed() {
+        let raw_manifest: RawTerrainManifest = serde_json::from_str(
+            r#"{
+                "terrain_types": {
+                    "loam": { "walking_speed": 1.0 },
+                    "muddy": { "walking_speed": { "default": 0.5, "units": { "beetle": 2.0 } } }
+                }
+            }"#,
+        )
+        .unwrap();
+        let manifest = raw_manifest.process();
+
+        let ant = Id::<Unit>::from_name("ant");
+        let beetle = Id::<Unit>::from_name("beetle");
+
+        let loam = &manifest.get(Id::from_name("loam")).walking_speed;
+        assert_eq!(loam.get(ant), 1.0);
+        assert_eq!(loam.unit_overrides().count(), 0);
+
+        let muddy = &manifest.get(Id::from_name("muddy")).walking_speed;
+        assert_eq!(muddy.get(ant), 0.5);
+        assert_eq!(muddy.get(beetle), 2.0);
+        assert_eq!(muddy.unit_overrides().collect::<Vec<_>>(), vec![beetle]);
+    }
+}
@@ -0,0 +1,89 @@
+//! Displays the blueprint that the player will place next.
+
+use bevy::prelude::*;
+
+use crate::player_interaction::blueprints::BlueprintLibrary;
+
+use super::{FiraSansFontFamily, LeftPanel};
+
+/// Adds the blueprint panel to the UI.
+pub(super) struct BlueprintPanelPlugin;
+
+impl Plugin for BlueprintPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_blueprint_panel)
+            .add_system(update_blueprint_panel);
+    }
+}
+
+/// A marker component for the text that lists the saved blueprints.
+#[derive(Component, Debug)]
+struct BlueprintPanel;
+
+/// Creates the text used to display the saved blueprints.
+fn setup_blueprint_panel(
+    mut commands: Commands,
+    left_panel_query: Query<Entity, With<LeftPanel>>,
+    fonts: Res<FiraSansFontFamily>,
+) {
+    let left_panel_entity = left_panel_query.single();
+    let text_style = TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: 20.0,
+        color: Color::WHITE,
+    };
+
+    let panel_entity = commands
+        .spawn((
+            TextBundle {
+                text: Text::from_section("BLUEPRINTS".to_string(), text_style),
+                ..Default::default()
+            },
+            BlueprintPanel,
+        ))
+        .id();
+    commands.entity(left_panel_entity).add_child(panel_entity);
+}
+
+/// Updates the panel text whenever the [`BlueprintLibrary`] changes.
+///
+/// Every saved blueprint is listed, with the selected one highlighted.
+fn update_blueprint_panel(
+    mut text_query: Query<&mut Text, With<BlueprintPanel>>,
+    blueprint_library: Res<BlueprintLibrary>,
+    fonts: Res<FiraSansFontFamily>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    if !blueprint_library.is_changed() {
+        return;
+    }
+
+    let style = |color: Color| TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: 20.0,
+        color,
+    };
+
+    let mut sections = vec![TextSection {
+        value: "Blueprints:".to_string(),
+        style: style(Color::WHITE),
+    }];
+
+    for name in blueprint_library.names() {
+        let color = if Some(name) == blueprint_library.selected_name() {
+            Color::YELLOW
+        } else {
+            Color::GRAY
+        };
+
+        sections.push(TextSection {
+            value: format!("\n{name}"),
+            style: style(color),
+        });
+    }
+
+    text.sections = sections;
+}
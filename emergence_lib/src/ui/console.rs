@@ -0,0 +1,77 @@
+//! Displays the developer console's history and the line being typed.
+
+use bevy::prelude::*;
+
+use crate::player_interaction::console::Console;
+
+use super::FiraSansFontFamily;
+
+/// Shows the developer [`Console`] while it is open.
+pub(super) struct ConsoleOverlayPlugin;
+
+impl Plugin for ConsoleOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_console_overlay)
+            .add_system(update_console_overlay);
+    }
+}
+
+/// Marker component for the text of the console overlay.
+#[derive(Component)]
+struct ConsoleOverlay;
+
+/// The font size used in the console.
+const FONT_SIZE: f32 = 14.;
+
+/// Creates the console overlay, which starts hidden.
+fn spawn_console_overlay(mut commands: Commands, fonts: Res<FiraSansFontFamily>) {
+    let style = TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: FONT_SIZE,
+        color: Color::WHITE,
+    };
+
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section("", style),
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(8.),
+                    bottom: Val::Px(8.),
+                    ..Default::default()
+                },
+                padding: UiRect::all(Val::Px(4.)),
+                ..Default::default()
+            },
+            background_color: BackgroundColor(Color::BLACK.with_a(0.8)),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        ConsoleOverlay,
+    ));
+}
+
+/// Shows the history and current input of the console, hiding it when the console is closed.
+fn update_console_overlay(
+    console: Res<Console>,
+    mut overlay_query: Query<(&mut Text, &mut Visibility), With<ConsoleOverlay>>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+
+    let Ok((mut text, mut visibility)) = overlay_query.get_single_mut() else {
+        return;
+    };
+
+    if !console.open {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+
+    let mut lines = console.history.clone();
+    lines.push(format!("> {}_", console.input));
+    text.sections[0].value = lines.join("\n");
+    *visibility = Visibility::Inherited;
+}
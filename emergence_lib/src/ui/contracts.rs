@@ -0,0 +1,102 @@
+//! Lists the delivery contracts that the player has set up.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_management::{localization::Localization, manifest::Id, AssetState},
+    items::item_manifest::ItemManifest,
+    player_interaction::contracts::Contracts,
+    simulation::geometry::TilePos,
+    structures::structure_manifest::{Structure, StructureManifest},
+};
+
+use super::{FiraSansFontFamily, LeftPanel};
+
+/// Adds the contracts panel to the UI.
+pub(super) struct ContractsPanelPlugin;
+
+impl Plugin for ContractsPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_contracts_panel)
+            .add_system(update_contracts_panel.run_if(in_state(AssetState::Ready)));
+    }
+}
+
+/// A marker component for the text that lists the current [`Contracts`].
+#[derive(Component, Debug)]
+struct ContractsPanel;
+
+/// Creates the text used to list the current [`Contracts`].
+fn setup_contracts_panel(
+    mut commands: Commands,
+    left_panel_query: Query<Entity, With<LeftPanel>>,
+    fonts: Res<FiraSansFontFamily>,
+) {
+    let left_panel_entity = left_panel_query.single();
+    let text_style = TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: 20.0,
+        color: Color::WHITE,
+    };
+
+    let panel_entity = commands
+        .spawn((
+            TextBundle {
+                text: Text::from_section("CONTRACTS".to_string(), text_style),
+                ..Default::default()
+            },
+            ContractsPanel,
+        ))
+        .id();
+    commands.entity(left_panel_entity).add_child(panel_entity);
+}
+
+/// Updates the panel text whenever the [`Contracts`] change.
+fn update_contracts_panel(
+    mut text_query: Query<&mut Text, With<ContractsPanel>>,
+    contracts: Res<Contracts>,
+    structure_query: Query<(&Id<Structure>, &TilePos)>,
+    fonts: Res<FiraSansFontFamily>,
+    item_manifest: Res<ItemManifest>,
+    structure_manifest: Res<StructureManifest>,
+    localization: Res<Localization>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    if !contracts.is_changed() {
+        return;
+    }
+
+    let describe = |structure_entity: Entity| match structure_query.get(structure_entity) {
+        Ok((&structure_id, tile_pos)) => format!(
+            "{} at {tile_pos}",
+            localization.get(structure_manifest.name(structure_id))
+        ),
+        Err(_) => "Unknown".to_string(),
+    };
+
+    let mut value = "Contracts:".to_string();
+    if let Some(source) = contracts.pending_source() {
+        value += &format!("\nChoose a sink for {}", describe(source));
+    }
+
+    for contract in contracts.iter() {
+        value += &format!(
+            "\n{}: {} -> {}",
+            localization.get(item_manifest.name(contract.item_id)),
+            describe(contract.source),
+            describe(contract.sink)
+        );
+    }
+
+    text.sections = vec![TextSection {
+        value,
+        style: TextStyle {
+            font: fonts.regular.clone_weak(),
+            font_size: 20.0,
+            color: Color::WHITE,
+        },
+    }];
+}
@@ -0,0 +1,195 @@
+//! Displays the [`EventLog`] as a scrollable feed of recent events.
+//!
+//! Clicking on an entry moves the camera to where that event happened.
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    ui::FocusPolicy,
+};
+
+use crate::{
+    asset_management::{localization::Localization, AssetState},
+    items::recipe::RecipeManifest,
+    player_interaction::camera::FocusCameraOnTile,
+    simulation::{event_log::EventLog, geometry::TilePos},
+    structures::structure_manifest::StructureManifest,
+};
+
+use super::{FiraSansFontFamily, LeftPanel};
+
+/// Displays recent game events in the UI.
+pub(super) struct EventLogPanelPlugin;
+
+impl Plugin for EventLogPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_event_log_panel)
+            .add_system(update_event_log_panel.run_if(in_state(AssetState::Ready)))
+            .add_system(scroll_event_log)
+            .add_system(focus_on_clicked_entry);
+    }
+}
+
+/// Marker component for the visible window of the event log.
+#[derive(Component)]
+struct EventLogPanel;
+
+/// The list of entries in the event log, which is scrolled within the [`EventLogPanel`].
+#[derive(Component, Default)]
+struct EventLogList {
+    /// How far the list has been scrolled, in pixels.
+    ///
+    /// This is always zero or negative, as the list starts scrolled to the top.
+    scroll_position: f32,
+}
+
+/// A single clickable entry in the event log.
+#[derive(Component)]
+struct EventLogEntry {
+    /// Where the event happened, if anywhere.
+    tile_pos: Option<TilePos>,
+}
+
+/// The height of the event log panel, in pixels.
+const PANEL_HEIGHT: f32 = 200.;
+
+/// The number of pixels scrolled for each line of mouse wheel movement.
+const PIXELS_PER_LINE: f32 = 20.;
+
+/// Creates the empty event log panel.
+fn spawn_event_log_panel(mut commands: Commands, left_panel_query: Query<Entity, With<LeftPanel>>) {
+    let panel_entity = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    size: Size::new(Val::Percent(100.), Val::Px(PANEL_HEIGHT)),
+                    overflow: Overflow::Hidden,
+                    ..Default::default()
+                },
+                background_color: BackgroundColor(Color::BLACK.with_a(0.5)),
+                ..Default::default()
+            },
+            Interaction::default(),
+            EventLogPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        flex_shrink: 0.,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                EventLogList::default(),
+            ));
+        })
+        .id();
+
+    let left_panel_entity = left_panel_query.single();
+    commands.entity(left_panel_entity).add_child(panel_entity);
+}
+
+/// Rebuilds the entries in the event log panel whenever new events are logged.
+fn update_event_log_panel(
+    event_log: Res<EventLog>,
+    list_query: Query<Entity, With<EventLogList>>,
+    fonts: Res<FiraSansFontFamily>,
+    structure_manifest: Res<StructureManifest>,
+    recipe_manifest: Res<RecipeManifest>,
+    localization: Res<Localization>,
+    mut commands: Commands,
+) {
+    if !event_log.is_changed() {
+        return;
+    }
+
+    let Ok(list_entity) = list_query.get_single() else {
+        return;
+    };
+
+    let style = TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: 16.,
+        color: Color::WHITE,
+    };
+
+    commands.entity(list_entity).despawn_descendants();
+    commands.entity(list_entity).with_children(|parent| {
+        for entry in event_log.iter() {
+            let text = format!(
+                "[{}] {}",
+                entry.timestamp,
+                entry
+                    .event
+                    .display(&structure_manifest, &recipe_manifest, &localization)
+            );
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        background_color: BackgroundColor(Color::NONE),
+                        // Let the panel underneath know that it is hovered, so it can be scrolled
+                        focus_policy: FocusPolicy::Pass,
+                        ..Default::default()
+                    },
+                    EventLogEntry {
+                        tile_pos: entry.event.tile_pos(),
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(text, style.clone()));
+                });
+        }
+    });
+}
+
+/// Scrolls the event log when the mouse wheel is used while hovering over it.
+fn scroll_event_log(
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    panel_query: Query<(&Interaction, &Node), With<EventLogPanel>>,
+    mut list_query: Query<(&mut EventLogList, &mut Style, &Node)>,
+) {
+    let Ok((interaction, panel_node)) = panel_query.get_single() else {
+        return;
+    };
+
+    let Ok((mut list, mut style, list_node)) = list_query.get_single_mut() else {
+        return;
+    };
+
+    if *interaction == Interaction::None {
+        mouse_wheel_events.clear();
+        return;
+    }
+
+    let max_scroll = (list_node.size().y - panel_node.size().y).max(0.);
+    for event in mouse_wheel_events.iter() {
+        let delta = match event.unit {
+            MouseScrollUnit::Line => event.y * PIXELS_PER_LINE,
+            MouseScrollUnit::Pixel => event.y,
+        };
+
+        list.scroll_position = (list.scroll_position + delta).clamp(-max_scroll, 0.);
+    }
+
+    style.position.top = Val::Px(list.scroll_position);
+}
+
+/// Moves the camera to the location of event log entries when they are clicked.
+fn focus_on_clicked_entry(
+    entry_query: Query<(&Interaction, &EventLogEntry), Changed<Interaction>>,
+    mut focus_events: EventWriter<FocusCameraOnTile>,
+) {
+    for (interaction, entry) in entry_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        if let Some(tile_pos) = entry.tile_pos {
+            focus_events.send(FocusCameraOnTile(tile_pos));
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! A tooltip that follows the cursor, summarizing the tile underneath it.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_management::{localization::Localization, manifest::Id, AssetState},
+    items::item_manifest::ItemManifest,
+    player_interaction::{cursor::CursorPos, zoning::Zoning, InteractionSystem},
+    simulation::geometry::{Height, MapGeometry},
+    structures::structure_manifest::{Structure, StructureManifest},
+    terrain::{
+        deposits::ResourceDeposit,
+        terrain_manifest::{Terrain, TerrainManifest},
+    },
+};
+
+use super::FiraSansFontFamily;
+
+/// Shows details about the hovered tile next to the cursor.
+pub(super) struct HoverTooltipPlugin;
+
+impl Plugin for HoverTooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_hover_tooltip).add_system(
+            update_hover_tooltip
+                .after(InteractionSystem::ComputeCursorPos)
+                .run_if(in_state(AssetState::Ready)),
+        );
+    }
+}
+
+/// Marker component for the tooltip that follows the cursor.
+#[derive(Component)]
+struct HoverTooltip;
+
+/// The font size used in the tooltip.
+const FONT_SIZE: f32 = 14.;
+
+/// How far the tooltip is drawn from the cursor, in logical pixels.
+///
+/// This keeps the tooltip from covering the tile it describes.
+const CURSOR_OFFSET: Vec2 = Vec2::new(16., 16.);
+
+/// Creates the tooltip, which starts hidden.
+fn spawn_hover_tooltip(mut commands: Commands, fonts: Res<FiraSansFontFamily>) {
+    let style = TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: FONT_SIZE,
+        color: Color::WHITE,
+    };
+
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section("", style),
+            style: Style {
+                position_type: PositionType::Absolute,
+                padding: UiRect::all(Val::Px(4.)),
+                ..Default::default()
+            },
+            background_color: BackgroundColor(Color::BLACK.with_a(0.8)),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
+        HoverTooltip,
+    ));
+}
+
+/// Moves the tooltip to the cursor and fills it with the details of the hovered tile.
+///
+/// The tooltip is hidden whenever the cursor is not over the map.
+fn update_hover_tooltip(
+    cursor_pos: Res<CursorPos>,
+    map_geometry: Res<MapGeometry>,
+    terrain_query: Query<(&Id<Terrain>, &Height, &Zoning, Option<&ResourceDeposit>)>,
+    structure_query: Query<&Id<Structure>>,
+    terrain_manifest: Res<TerrainManifest>,
+    structure_manifest: Res<StructureManifest>,
+    item_manifest: Res<ItemManifest>,
+    localization: Res<Localization>,
+    mut tooltip_query: Query<(&mut Text, &mut Style, &mut Visibility), With<HoverTooltip>>,
+) {
+    let Ok((mut text, mut style, mut visibility)) = tooltip_query.get_single_mut() else {
+        return;
+    };
+
+    let hovered = cursor_pos
+        .maybe_tile_pos()
+        .zip(cursor_pos.maybe_screen_pos());
+    let Some((tile_pos, screen_pos)) = hovered else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let Some((&terrain_id, height, zoning, maybe_deposit)) = map_geometry
+        .get_terrain(tile_pos)
+        .and_then(|terrain_entity| terrain_query.get(terrain_entity).ok())
+    else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let terrain_name = localization.get(terrain_manifest.name(terrain_id));
+    let zoning = zoning.display(&structure_manifest, &terrain_manifest, &localization);
+    let mut details = format!("{terrain_name}\nHeight: {height}\nZoning: {zoning}");
+
+    if let Some(deposit) = maybe_deposit {
+        let deposit = deposit.display(&item_manifest, &localization);
+        details.push_str(&format!("\nDeposit: {deposit}"));
+    }
+
+    if let Some(&structure_id) = map_geometry
+        .get_structure(tile_pos)
+        .and_then(|structure_entity| structure_query.get(structure_entity).ok())
+    {
+        let structure_name = localization.get(structure_manifest.name(structure_id));
+        details.push_str(&format!("\nStructure: {structure_name}"));
+    }
+
+    text.sections[0].value = details;
+    style.position = UiRect {
+        left: Val::Px(screen_pos.x + CURSOR_OFFSET.x),
+        top: Val::Px(screen_pos.y + CURSOR_OFFSET.y),
+        ..Default::default()
+    };
+    *visibility = Visibility::Inherited;
+}
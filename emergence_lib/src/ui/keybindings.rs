@@ -0,0 +1,207 @@
+//! A menu for viewing and changing the keybinding of each [`PlayerAction`].
+//!
+//! Clicking on an action waits for the next key or mouse button to be pressed, which becomes its new binding.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use leafwing_input_manager::{
+    prelude::{ActionState, InputMap},
+    Actionlike,
+};
+
+use crate::player_interaction::{
+    keybindings::{save_keybindings, Remapping, KEYBINDINGS_PATH},
+    PlayerAction,
+};
+
+use super::{FiraSansFontFamily, RightPanel};
+
+/// Displays and edits the player's keybindings.
+pub(super) struct KeybindingsMenuPlugin;
+
+impl Plugin for KeybindingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_keybindings_menu)
+            .add_system(toggle_keybindings_menu)
+            .add_system(start_remapping)
+            .add_system(reset_keybindings)
+            .add_system(update_keybindings_menu);
+    }
+}
+
+/// Marker component for the root node of the keybindings menu.
+#[derive(Component)]
+struct KeybindingsMenu;
+
+/// A button that starts remapping the provided action when clicked.
+#[derive(Component)]
+struct KeybindingButton(PlayerAction);
+
+/// Marker component for the button that restores the default keybindings.
+#[derive(Component)]
+struct ResetKeybindingsButton;
+
+/// Marker component for the text that explains what is happening with the current remapping.
+#[derive(Component)]
+struct RemappingStatus;
+
+/// The font size used in the keybindings menu.
+const FONT_SIZE: f32 = 14.;
+
+/// Creates the keybindings menu, which starts hidden.
+fn spawn_keybindings_menu(
+    mut commands: Commands,
+    right_panel_query: Query<Entity, With<RightPanel>>,
+    fonts: Res<FiraSansFontFamily>,
+) {
+    let style = TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: FONT_SIZE,
+        color: Color::WHITE,
+    };
+
+    let menu_entity = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                background_color: BackgroundColor(Color::BLACK.with_a(0.8)),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            KeybindingsMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((TextBundle::from_section("", style.clone()), RemappingStatus));
+
+            for action in PlayerAction::variants() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            background_color: BackgroundColor(Color::NONE),
+                            ..Default::default()
+                        },
+                        KeybindingButton(action),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section("", style.clone()));
+                    });
+            }
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        background_color: BackgroundColor(Color::DARK_GRAY),
+                        ..Default::default()
+                    },
+                    ResetKeybindingsButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section("Reset to defaults", style));
+                });
+        })
+        .id();
+
+    let right_panel_entity = right_panel_query.single();
+    commands.entity(right_panel_entity).add_child(menu_entity);
+}
+
+/// Shows or hides the keybindings menu.
+fn toggle_keybindings_menu(
+    actions: Res<ActionState<PlayerAction>>,
+    mut menu_query: Query<&mut Visibility, With<KeybindingsMenu>>,
+    mut remapping: ResMut<Remapping>,
+) {
+    if !actions.just_pressed(PlayerAction::ToggleKeybindingsMenu) {
+        return;
+    }
+
+    let Ok(mut visibility) = menu_query.get_single_mut() else {
+        return;
+    };
+
+    *visibility = match *visibility {
+        Visibility::Hidden => Visibility::Inherited,
+        _ => {
+            // Don't keep waiting for input once the menu is closed
+            remapping.awaiting = None;
+            Visibility::Hidden
+        }
+    };
+}
+
+/// Waits for a new binding for actions that are clicked on.
+fn start_remapping(
+    button_query: Query<(&Interaction, &KeybindingButton), Changed<Interaction>>,
+    mut remapping: ResMut<Remapping>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction == Interaction::Clicked {
+            remapping.awaiting = Some(button.0.clone());
+            remapping.conflicts.clear();
+        }
+    }
+}
+
+/// Restores the default keybindings when the reset button is clicked.
+fn reset_keybindings(
+    button_query: Query<&Interaction, (Changed<Interaction>, With<ResetKeybindingsButton>)>,
+    mut input_map: ResMut<InputMap<PlayerAction>>,
+    mut remapping: ResMut<Remapping>,
+) {
+    for interaction in button_query.iter() {
+        if *interaction == Interaction::Clicked {
+            *input_map = PlayerAction::default_input_map();
+            *remapping = Remapping::default();
+
+            if let Err(error) = save_keybindings(&input_map, Path::new(KEYBINDINGS_PATH)) {
+                warn!("Could not save keybindings to {KEYBINDINGS_PATH}: {error}");
+            }
+        }
+    }
+}
+
+/// Shows the current bindings of each action, and the progress of any remapping.
+fn update_keybindings_menu(
+    input_map: Res<InputMap<PlayerAction>>,
+    remapping: Res<Remapping>,
+    button_query: Query<(&KeybindingButton, &Children)>,
+    mut text_query: Query<&mut Text, Without<RemappingStatus>>,
+    mut status_query: Query<&mut Text, With<RemappingStatus>>,
+) {
+    if !input_map.is_changed() && !remapping.is_changed() {
+        return;
+    }
+
+    for (button, children) in button_query.iter() {
+        let action = &button.0;
+        // Each button has a single child: its label
+        let Ok(mut text) = text_query.get_mut(children[0]) else {
+            continue;
+        };
+
+        text.sections[0].value = if remapping.awaiting.as_ref() == Some(action) {
+            format!("{action:?}: ...")
+        } else {
+            let bindings: Vec<String> = input_map
+                .get(action.clone())
+                .iter()
+                .map(|input| input.to_string())
+                .collect();
+            format!("{action:?}: {}", bindings.join(", "))
+        };
+    }
+
+    if let Ok(mut status_text) = status_query.get_single_mut() {
+        status_text.sections[0].value = match &remapping.awaiting {
+            Some(action) => format!("Press a new key for {action:?}, or Escape to cancel."),
+            None if !remapping.conflicts.is_empty() => {
+                format!("Unbound: {:?}", remapping.conflicts)
+            }
+            None => "Click an action to change its binding.".to_string(),
+        };
+    }
+}
@@ -0,0 +1,101 @@
+//! Displays the manual signal that the player will place next.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_management::{localization::Localization, AssetState},
+    items::item_manifest::ItemManifest,
+    player_interaction::manual_signals::ManualSignalSettings,
+    signals::SignalKind,
+    structures::structure_manifest::StructureManifest,
+    units::unit_manifest::UnitManifest,
+};
+
+use super::{FiraSansFontFamily, LeftPanel};
+
+/// Adds the manual signal panel to the UI.
+pub(super) struct ManualSignalsPanelPlugin;
+
+impl Plugin for ManualSignalsPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_manual_signals_panel)
+            .add_system(update_manual_signals_panel.run_if(in_state(AssetState::Ready)));
+    }
+}
+
+/// A marker component for the text that describes the current [`ManualSignalSettings`].
+#[derive(Component, Debug)]
+struct ManualSignalsPanel;
+
+/// Creates the text used to display the current [`ManualSignalSettings`].
+fn setup_manual_signals_panel(
+    mut commands: Commands,
+    left_panel_query: Query<Entity, With<LeftPanel>>,
+    fonts: Res<FiraSansFontFamily>,
+) {
+    let left_panel_entity = left_panel_query.single();
+    let text_style = TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: 20.0,
+        color: Color::WHITE,
+    };
+
+    let panel_entity = commands
+        .spawn((
+            TextBundle {
+                text: Text::from_section("MANUAL_SIGNAL".to_string(), text_style),
+                ..Default::default()
+            },
+            ManualSignalsPanel,
+        ))
+        .id();
+    commands.entity(left_panel_entity).add_child(panel_entity);
+}
+
+/// Updates the panel text whenever the [`ManualSignalSettings`] change.
+fn update_manual_signals_panel(
+    mut text_query: Query<&mut Text, With<ManualSignalsPanel>>,
+    settings: Res<ManualSignalSettings>,
+    fonts: Res<FiraSansFontFamily>,
+    item_manifest: Res<ItemManifest>,
+    structure_manifest: Res<StructureManifest>,
+    unit_manifest: Res<UnitManifest>,
+    localization: Res<Localization>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    if !settings.is_changed() {
+        return;
+    }
+
+    let (value, color) = match settings.signal_type() {
+        Some(signal_type) => {
+            let signal_kind: SignalKind = signal_type.into();
+            (
+                format!(
+                    "Manual signal: {} at {}",
+                    signal_type.display(
+                        &item_manifest,
+                        &structure_manifest,
+                        &unit_manifest,
+                        &localization,
+                    ),
+                    settings.strength.value()
+                ),
+                signal_kind.color(),
+            )
+        }
+        None => ("Manual signal: None".to_string(), Color::WHITE),
+    };
+
+    text.sections = vec![TextSection {
+        value,
+        style: TextStyle {
+            font: fonts.regular.clone_weak(),
+            font_size: 20.0,
+            color,
+        },
+    }];
+}
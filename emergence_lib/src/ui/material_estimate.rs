@@ -0,0 +1,85 @@
+//! Previews the materials needed to build the structures that are about to be zoned.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_management::{localization::Localization, AssetState},
+    items::item_manifest::ItemManifest,
+    player_interaction::{
+        clipboard::{estimate_materials, Clipboard},
+        cursor::CursorPos,
+        selection::CurrentSelection,
+    },
+    structures::structure_manifest::StructureManifest,
+};
+
+use super::{FiraSansFontFamily, LeftPanel};
+
+/// Adds the material estimate panel to the UI.
+pub(super) struct MaterialEstimatePlugin;
+
+impl Plugin for MaterialEstimatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_material_estimate)
+            .add_system(update_material_estimate.run_if(in_state(AssetState::Ready)));
+    }
+}
+
+/// A marker component for the text that lists the materials needed for the planned structures.
+#[derive(Component, Debug)]
+struct MaterialEstimate;
+
+/// Creates the text used to display the material estimate.
+fn setup_material_estimate(
+    mut commands: Commands,
+    left_panel_query: Query<Entity, With<LeftPanel>>,
+    fonts: Res<FiraSansFontFamily>,
+) {
+    let left_panel_entity = left_panel_query.single();
+    let text_style = TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: 20.0,
+        color: Color::WHITE,
+    };
+
+    let estimate_entity = commands
+        .spawn((
+            TextBundle {
+                text: Text::from_section(String::new(), text_style),
+                ..Default::default()
+            },
+            MaterialEstimate,
+        ))
+        .id();
+    commands
+        .entity(left_panel_entity)
+        .add_child(estimate_entity);
+}
+
+/// Sums the materials needed for the structures that would be placed by zoning, before the player confirms.
+fn update_material_estimate(
+    mut text_query: Query<&mut Text, With<MaterialEstimate>>,
+    clipboard: Res<Clipboard>,
+    current_selection: Res<CurrentSelection>,
+    cursor_pos: Res<CursorPos>,
+    structure_manifest: Res<StructureManifest>,
+    item_manifest: Res<ItemManifest>,
+    localization: Res<Localization>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let relevant_tiles = current_selection.relevant_tiles(&cursor_pos);
+    let placements = clipboard.planned_placements(&relevant_tiles, &cursor_pos);
+    let estimate = estimate_materials(&placements, &structure_manifest, &item_manifest);
+
+    text.sections[0].value = match estimate.is_empty() {
+        true => String::new(),
+        false => format!(
+            "Materials for {} structures: {}",
+            placements.len(),
+            estimate.display(&item_manifest, &localization)
+        ),
+    };
+}
@@ -0,0 +1,404 @@
+//! A small overview of the entire map, showing terrain, structures and units.
+//!
+//! Each tile is drawn as a single pixel, laid out using offset coordinates.
+//! Clicking on the minimap moves the camera to the corresponding tile.
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::ImageSampler,
+    },
+    utils::{HashMap, HashSet},
+    window::PrimaryWindow,
+};
+use hexx::{Hex, OffsetHexMode};
+
+use crate::{
+    asset_management::{manifest::Id, AssetState},
+    graphics::palette::minimap::{terrain_color, EMPTY_COLOR, STRUCTURE_COLOR, UNIT_COLOR},
+    player_interaction::camera::FocusCameraOnTile,
+    simulation::{
+        geometry::{Height, MapGeometry, TilePos},
+        map_events::{StructureAdded, StructureRemoved},
+    },
+    structures::{
+        construction::{Ghost, Preview},
+        structure_manifest::Structure,
+    },
+    terrain::terrain_manifest::Terrain,
+    units::unit_manifest::Unit,
+};
+
+use super::LeftPanel;
+
+/// Displays a minimap of the world in the UI.
+pub(super) struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_minimap)
+            .add_system(update_minimap.run_if(in_state(AssetState::Ready)))
+            .add_system(jump_to_clicked_tile);
+    }
+}
+
+/// Stores the state of the minimap, so only the pixels that changed need to be redrawn.
+#[derive(Resource, Debug)]
+struct Minimap {
+    /// The image that the minimap is drawn to.
+    image_handle: Handle<Image>,
+    /// The UI node that displays the minimap.
+    image_entity: Entity,
+    /// The radius of the map being displayed.
+    radius: u32,
+    /// The color of the terrain at each tile.
+    ///
+    /// This is used to restore the underlying terrain when a structure or unit moves off of a tile.
+    terrain_colors: HashMap<TilePos, Color>,
+    /// The center of each structure, which is drawn as a dot.
+    structure_centers: HashMap<TilePos, Entity>,
+    /// The tile that each unit is on.
+    unit_tiles: HashMap<Entity, TilePos>,
+    /// The number of units on each tile.
+    units_per_tile: HashMap<TilePos, u32>,
+}
+
+impl Minimap {
+    /// The width and height of the minimap in the UI, in pixels.
+    const DISPLAY_SIZE: f32 = 200.;
+
+    /// The width and height of the minimap texture for a map with the provided `radius`.
+    const fn texture_size(radius: u32) -> u32 {
+        2 * radius + 1
+    }
+
+    /// The pixel that `tile_pos` is drawn to, in a map with the provided `radius`.
+    fn tile_to_pixel(tile_pos: TilePos, radius: u32) -> UVec2 {
+        let [column, row] = tile_pos
+            .hex
+            .to_offset_coordinates(OffsetHexMode::OddColumns);
+        let radius = radius as i32;
+
+        UVec2::new((column + radius) as u32, (row + radius) as u32)
+    }
+
+    /// The tile that is drawn to `pixel`, in a map with the provided `radius`.
+    ///
+    /// Not all pixels correspond to a valid tile: check the result using [`MapGeometry::is_valid`].
+    fn pixel_to_tile(pixel: UVec2, radius: u32) -> TilePos {
+        let radius = radius as i32;
+        let column = pixel.x as i32 - radius;
+        let row = pixel.y as i32 - radius;
+
+        TilePos {
+            hex: Hex::from_offset_coordinates([column, row], OffsetHexMode::OddColumns),
+        }
+    }
+
+    /// The color that `tile_pos` should be drawn in.
+    ///
+    /// Units are drawn on top of structures, which are drawn on top of the terrain.
+    fn color(&self, tile_pos: TilePos) -> Color {
+        if self.units_per_tile.contains_key(&tile_pos) {
+            UNIT_COLOR
+        } else if self.structure_centers.contains_key(&tile_pos) {
+            STRUCTURE_COLOR
+        } else {
+            self.terrain_colors
+                .get(&tile_pos)
+                .copied()
+                .unwrap_or(EMPTY_COLOR)
+        }
+    }
+
+    /// Records that `unit_entity` is now on `new_tile`, or no longer exists if [`None`].
+    ///
+    /// Returns the tile that the unit was on before, if any.
+    fn move_unit(&mut self, unit_entity: Entity, new_tile: Option<TilePos>) -> Option<TilePos> {
+        let old_tile = match new_tile {
+            Some(new_tile) => self.unit_tiles.insert(unit_entity, new_tile),
+            None => self.unit_tiles.remove(&unit_entity),
+        };
+
+        if let Some(old_tile) = old_tile {
+            if let Some(count) = self.units_per_tile.get_mut(&old_tile) {
+                *count -= 1;
+                if *count == 0 {
+                    self.units_per_tile.remove(&old_tile);
+                }
+            }
+        }
+
+        if let Some(new_tile) = new_tile {
+            *self.units_per_tile.entry(new_tile).or_default() += 1;
+        }
+
+        old_tile
+    }
+
+    /// Sets the color of the pixel corresponding to `tile_pos` in `image`.
+    fn write_pixel(&self, image: &mut Image, tile_pos: TilePos, color: Color) {
+        let size = Minimap::texture_size(self.radius);
+        let pixel = Minimap::tile_to_pixel(tile_pos, self.radius);
+        if pixel.x >= size || pixel.y >= size {
+            return;
+        }
+
+        // Each pixel is represented by 4 bytes, in RGBA order
+        let pixel_start = (pixel.y * size + pixel.x) as usize * 4;
+        image.data[pixel_start..pixel_start + 4].copy_from_slice(&rgba_bytes(color));
+    }
+}
+
+/// Converts `color` into the sRGB bytes used by the minimap texture.
+fn rgba_bytes(color: Color) -> [u8; 4] {
+    [
+        (color.r() * 255.0) as u8,
+        (color.g() * 255.0) as u8,
+        (color.b() * 255.0) as u8,
+        (color.a() * 255.0) as u8,
+    ]
+}
+
+/// Creates the minimap image and adds it to the UI.
+fn setup_minimap(
+    mut commands: Commands,
+    left_panel_query: Query<Entity, With<LeftPanel>>,
+    map_geometry: Res<MapGeometry>,
+    mut image_assets: ResMut<Assets<Image>>,
+) {
+    let left_panel_entity = left_panel_query.single();
+    let size = Minimap::texture_size(map_geometry.radius);
+
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &rgba_bytes(EMPTY_COLOR),
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    // Keep each tile crisp, rather than blurring them together
+    image.sampler_descriptor = ImageSampler::nearest();
+    let image_handle = image_assets.add(image);
+
+    let image_entity = commands
+        .spawn((
+            ImageBundle {
+                style: Style {
+                    size: Size::new(
+                        Val::Px(Minimap::DISPLAY_SIZE),
+                        Val::Px(Minimap::DISPLAY_SIZE),
+                    ),
+                    ..Default::default()
+                },
+                image: UiImage {
+                    texture: image_handle.clone(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Interaction::default(),
+        ))
+        .id();
+    commands.entity(left_panel_entity).add_child(image_entity);
+
+    commands.insert_resource(Minimap {
+        image_handle,
+        image_entity,
+        radius: map_geometry.radius,
+        terrain_colors: HashMap::default(),
+        structure_centers: HashMap::default(),
+        unit_tiles: HashMap::default(),
+        units_per_tile: HashMap::default(),
+    });
+}
+
+/// Redraws the pixels of the minimap whose terrain, structures or units have changed.
+///
+/// Structures are tracked using the [`MapGeometry`] change events.
+/// Everything already on the map is picked up the first time this runs,
+/// as older events may have been discarded while the map was being generated.
+fn update_minimap(
+    mut minimap: ResMut<Minimap>,
+    mut image_assets: ResMut<Assets<Image>>,
+    terrain_query: Query<
+        (&TilePos, &Id<Terrain>, &Height),
+        Or<(Changed<Id<Terrain>>, Changed<Height>)>,
+    >,
+    (mut structure_added, mut structure_removed): (
+        EventReader<StructureAdded>,
+        EventReader<StructureRemoved>,
+    ),
+    structure_query: Query<
+        (Entity, &TilePos),
+        (With<Id<Structure>>, Without<Ghost>, Without<Preview>),
+    >,
+    unit_query: Query<(Entity, &TilePos), (With<Id<Unit>>, Changed<TilePos>)>,
+    mut removed_units: RemovedComponents<Id<Unit>>,
+) {
+    let mut changed_tiles: HashSet<TilePos> = HashSet::default();
+
+    for (&tile_pos, &terrain_id, &height) in terrain_query.iter() {
+        let color = terrain_color(terrain_id, height);
+        minimap.terrain_colors.insert(tile_pos, color);
+        changed_tiles.insert(tile_pos);
+    }
+
+    if minimap.is_added() {
+        for (structure_entity, &center) in structure_query.iter() {
+            minimap.structure_centers.insert(center, structure_entity);
+            changed_tiles.insert(center);
+        }
+    }
+
+    for event in structure_added.iter() {
+        minimap
+            .structure_centers
+            .insert(event.center, event.structure_entity);
+        changed_tiles.insert(event.center);
+    }
+
+    for event in structure_removed.iter() {
+        // The event may not record the structure's center, so look it up by entity instead
+        minimap.structure_centers.retain(|&center, &mut entity| {
+            let keep = entity != event.structure_entity;
+            if !keep {
+                changed_tiles.insert(center);
+            }
+            keep
+        });
+    }
+
+    for (unit_entity, &tile_pos) in unit_query.iter() {
+        if let Some(old_tile) = minimap.move_unit(unit_entity, Some(tile_pos)) {
+            changed_tiles.insert(old_tile);
+        }
+        changed_tiles.insert(tile_pos);
+    }
+
+    for unit_entity in removed_units.iter() {
+        if let Some(old_tile) = minimap.move_unit(unit_entity, None) {
+            changed_tiles.insert(old_tile);
+        }
+    }
+
+    // Avoid re-uploading the texture when nothing has changed
+    if changed_tiles.is_empty() {
+        return;
+    }
+
+    let Some(image) = image_assets.get_mut(&minimap.image_handle) else {
+        return;
+    };
+
+    for tile_pos in changed_tiles {
+        minimap.write_pixel(image, tile_pos, minimap.color(tile_pos));
+    }
+}
+
+/// Moves the camera to the tile under the cursor when the minimap is clicked.
+fn jump_to_clicked_tile(
+    minimap: Option<Res<Minimap>>,
+    minimap_query: Query<(&Interaction, &Node, &GlobalTransform)>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    map_geometry: Res<MapGeometry>,
+    mut focus_events: EventWriter<FocusCameraOnTile>,
+) {
+    let Some(minimap) = minimap else {
+        return;
+    };
+
+    let Ok((interaction, node, global_transform)) = minimap_query.get(minimap.image_entity) else {
+        return;
+    };
+
+    if *interaction != Interaction::Clicked {
+        return;
+    }
+
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    let Some(cursor_position) = window.cursor_position() else {
+        return;
+    };
+
+    // The cursor's origin is at the bottom left of the window, while the UI's origin is at the top left
+    let cursor_position = Vec2::new(cursor_position.x, window.height() - cursor_position.y);
+    let top_left = global_transform.translation().truncate() - node.size() / 2.;
+    let fraction = (cursor_position - top_left) / node.size();
+    if !(0.0..1.0).contains(&fraction.x) || !(0.0..1.0).contains(&fraction.y) {
+        return;
+    }
+
+    let size = Minimap::texture_size(minimap.radius) as f32;
+    let pixel = (fraction * size).floor().as_uvec2();
+    let tile_pos = Minimap::pixel_to_tile(pixel, minimap.radius);
+
+    if map_geometry.is_valid(tile_pos) {
+        focus_events.send(FocusCameraOnTile(tile_pos));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hexx::shapes::hexagon;
+
+    use super::*;
+
+    #[test]
+    fn minimap_pixels_are_invertable() {
+        let radius = 10;
+        let size = Minimap::texture_size(radius);
+
+        for hex in hexagon(Hex::ZERO, radius) {
+            let tile_pos = TilePos { hex };
+            let pixel = Minimap::tile_to_pixel(tile_pos, radius);
+
+            assert!(pixel.x < size && pixel.y < size);
+            assert_eq!(Minimap::pixel_to_tile(pixel, radius), tile_pos);
+        }
+    }
+
+    #[test]
+    fn units_are_drawn_over_structures_and_terrain() {
+        let tile_pos = TilePos::ZERO;
+        let mut minimap = Minimap {
+            image_handle: Handle::default(),
+            image_entity: Entity::PLACEHOLDER,
+            radius: 1,
+            terrain_colors: HashMap::default(),
+            structure_centers: HashMap::default(),
+            unit_tiles: HashMap::default(),
+            units_per_tile: HashMap::default(),
+        };
+        assert_eq!(minimap.color(tile_pos), EMPTY_COLOR);
+
+        minimap
+            .structure_centers
+            .insert(tile_pos, Entity::from_raw(0));
+        assert_eq!(minimap.color(tile_pos), STRUCTURE_COLOR);
+
+        // The tile stays covered until every unit has left it
+        let first_unit = Entity::from_raw(1);
+        let second_unit = Entity::from_raw(2);
+        minimap.move_unit(first_unit, Some(tile_pos));
+        minimap.move_unit(second_unit, Some(tile_pos));
+        assert_eq!(minimap.color(tile_pos), UNIT_COLOR);
+
+        let neighbor = tile_pos.neighbor(hexx::Direction::Top);
+        assert_eq!(
+            minimap.move_unit(first_unit, Some(neighbor)),
+            Some(tile_pos)
+        );
+        assert_eq!(minimap.color(tile_pos), UNIT_COLOR);
+        assert_eq!(minimap.move_unit(second_unit, None), Some(tile_pos));
+        assert_eq!(minimap.color(tile_pos), STRUCTURE_COLOR);
+        assert_eq!(minimap.color(neighbor), UNIT_COLOR);
+    }
+}
@@ -5,25 +5,49 @@ use crate::{
     player_interaction::terraform::TerraformingChoice,
     structures::structure_manifest::Structure,
     ui::{
+        blueprints::BlueprintPanelPlugin,
+        contracts::ContractsPanelPlugin,
+        event_log::EventLogPanelPlugin,
+        hover_tooltip::HoverTooltipPlugin,
+        keybindings::KeybindingsMenuPlugin,
+        manual_signals::ManualSignalsPanelPlugin,
+        material_estimate::MaterialEstimatePlugin,
+        minimap::MinimapPlugin,
         overlay::OverlayMenuPlugin,
+        production_chain::ProductionChainPanelPlugin,
         production_statistics::ProductionStatisticsPlugin,
+        production_targets::ProductionTargetsPanelPlugin,
         select_structure::SelectStructurePlugin,
         select_terraforming::SelectTerraformingPlugin,
         selection_details::SelectionDetailsPlugin,
         status::StatusPlugin,
+        tutorial::TutorialPanelPlugin,
         ui_assets::{Icons, UiElements},
     },
 };
 use bevy::prelude::*;
 use bevy_screen_diagnostics::{ScreenDiagnosticsPlugin, ScreenFrameDiagnosticsPlugin};
 
+mod blueprints;
+#[cfg(feature = "debug_tools")]
+mod console;
+mod contracts;
+mod event_log;
+mod hover_tooltip;
 mod intent;
+mod keybindings;
+mod manual_signals;
+mod material_estimate;
+mod minimap;
 mod overlay;
+mod production_chain;
 mod production_statistics;
+mod production_targets;
 mod select_structure;
 mod select_terraforming;
 mod selection_details;
 mod status;
+mod tutorial;
 mod ui_assets;
 mod wheel_menu;
 
@@ -58,8 +82,22 @@ impl Plugin for UiPlugin {
         .add_plugin(ProductionStatisticsPlugin)
         .add_plugin(StatusPlugin)
         .add_plugin(OverlayMenuPlugin)
+        .add_plugin(MinimapPlugin)
+        .add_plugin(TutorialPanelPlugin)
+        .add_plugin(EventLogPanelPlugin)
+        .add_plugin(ManualSignalsPanelPlugin)
+        .add_plugin(BlueprintPanelPlugin)
+        .add_plugin(ContractsPanelPlugin)
+        .add_plugin(ProductionTargetsPanelPlugin)
+        .add_plugin(MaterialEstimatePlugin)
         .add_plugin(SelectStructurePlugin)
-        .add_plugin(SelectTerraformingPlugin);
+        .add_plugin(SelectTerraformingPlugin)
+        .add_plugin(KeybindingsMenuPlugin)
+        .add_plugin(ProductionChainPanelPlugin)
+        .add_plugin(HoverTooltipPlugin);
+
+        #[cfg(feature = "debug_tools")]
+        app.add_plugin(console::ConsoleOverlayPlugin);
     }
 }
 
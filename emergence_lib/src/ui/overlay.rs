@@ -1,7 +1,7 @@
 //! Controls what is being visualized on the terrain by the [`TileOverlay`].
 
 use crate::{
-    asset_management::AssetState,
+    asset_management::{localization::Localization, AssetState},
     infovis::{OverlayType, TileOverlay},
     items::item_manifest::ItemManifest,
     player_interaction::PlayerAction,
@@ -49,9 +49,21 @@ fn select_overlay(
         }
     }
 
+    if player_actions.just_pressed(PlayerAction::ToggleThroughputOverlay) {
+        if tile_overlay.overlay_type != OverlayType::ItemThroughput {
+            tile_overlay.overlay_type = OverlayType::ItemThroughput;
+        } else {
+            tile_overlay.overlay_type = OverlayType::None;
+        }
+    }
+
     if player_actions.just_pressed(PlayerAction::ToggleSignalOverlay) {
-        // FIXME: this is very silly, but it's the easiest way to get and cycle signal types
-        tile_overlay.overlay_type = signals.random_signal_type().into();
+        // Step through each signal type in turn, turning the overlay off after the last one
+        let current = match tile_overlay.overlay_type {
+            OverlayType::Single(signal_type) => Some(signal_type),
+            _ => None,
+        };
+        tile_overlay.overlay_type = signals.next_signal_type(current).into();
     }
 }
 
@@ -113,6 +125,7 @@ fn update_signal_type_display(
     item_manifest: Res<ItemManifest>,
     structure_manifest: Res<StructureManifest>,
     unit_manifest: Res<UnitManifest>,
+    localization: Res<Localization>,
 ) {
     let mut text = text_query.get_mut(overlay_menu.signal_type_entity).unwrap();
     let mut legend = image_query.get_mut(overlay_menu.legend_entity).unwrap();
@@ -135,7 +148,12 @@ fn update_signal_type_display(
             let signal_kind: SignalKind = (*signal_type).into();
 
             text.sections = vec![TextSection {
-                value: signal_type.display(&item_manifest, &structure_manifest, &unit_manifest),
+                value: signal_type.display(
+                    &item_manifest,
+                    &structure_manifest,
+                    &unit_manifest,
+                    &localization,
+                ),
                 style: TextStyle {
                     font: fonts.regular.clone_weak(),
                     font_size,
@@ -172,16 +190,52 @@ fn update_signal_type_display(
                     },
                 },
                 TextSection {
-                    value: "Demolish".to_string(),
+                    value: "Demolish\n".to_string(),
                     style: TextStyle {
                         font: fonts.regular.clone_weak(),
                         font_size,
                         color: SignalKind::Demolish.color(),
                     },
                 },
+                TextSection {
+                    value: "Repair\n".to_string(),
+                    style: TextStyle {
+                        font: fonts.regular.clone_weak(),
+                        font_size,
+                        color: SignalKind::Repair.color(),
+                    },
+                },
+                TextSection {
+                    value: "Upgrade\n".to_string(),
+                    style: TextStyle {
+                        font: fonts.regular.clone_weak(),
+                        font_size,
+                        color: SignalKind::Upgrade.color(),
+                    },
+                },
+                TextSection {
+                    value: "Terraform".to_string(),
+                    style: TextStyle {
+                        font: fonts.regular.clone_weak(),
+                        font_size,
+                        color: SignalKind::Terraform.color(),
+                    },
+                },
             ];
 
             legend.texture = Handle::default();
         }
+        crate::infovis::OverlayType::ItemThroughput => {
+            text.sections = vec![TextSection {
+                value: "Item throughput".to_string(),
+                style: TextStyle {
+                    font: fonts.regular.clone_weak(),
+                    font_size,
+                    color: Color::WHITE,
+                },
+            }];
+
+            legend.texture = tile_overlay.throughput_legend_handle();
+        }
     }
 }
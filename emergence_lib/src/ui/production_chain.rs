@@ -0,0 +1,206 @@
+//! A panel that explains how each item is produced, by following the recipe manifest backwards.
+//!
+//! Clicking on an item shows the recipes that produce it and the items that they consume,
+//! which can be clicked in turn to follow the chain further.
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+    asset_management::{localization::Localization, manifest::Id, AssetState},
+    items::{
+        item_manifest::{Item, ItemManifest},
+        production_chain::{ProductionChain, ProductionChainNode},
+        recipe::RecipeManifest,
+    },
+    player_interaction::PlayerAction,
+};
+
+use super::{FiraSansFontFamily, RightPanel};
+
+/// Displays the production chain of the chosen item.
+pub(super) struct ProductionChainPanelPlugin;
+
+impl Plugin for ProductionChainPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProductionChainFocus>()
+            .add_startup_system(spawn_production_chain_panel)
+            .add_systems(
+                (
+                    toggle_production_chain_panel,
+                    focus_on_clicked_item,
+                    update_production_chain_panel.run_if(in_state(AssetState::Ready)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The item whose production chain is currently shown.
+///
+/// If this is [`None`], a list of all items is shown instead.
+#[derive(Resource, Debug, Default)]
+struct ProductionChainFocus(Option<Id<Item>>);
+
+/// Marker component for the root node of the production chain panel.
+#[derive(Component)]
+struct ProductionChainPanel;
+
+/// A button that changes the [`ProductionChainFocus`] to the provided value when clicked.
+#[derive(Component)]
+struct ProductionChainButton(Option<Id<Item>>);
+
+/// The font size used in the production chain panel.
+const FONT_SIZE: f32 = 14.;
+
+/// The number of spaces each level of the chain is indented by.
+const INDENT: usize = 2;
+
+/// Creates the production chain panel, which starts hidden.
+fn spawn_production_chain_panel(
+    mut commands: Commands,
+    right_panel_query: Query<Entity, With<RightPanel>>,
+) {
+    let panel_entity = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                background_color: BackgroundColor(Color::BLACK.with_a(0.8)),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            ProductionChainPanel,
+        ))
+        .id();
+
+    let right_panel_entity = right_panel_query.single();
+    commands.entity(right_panel_entity).add_child(panel_entity);
+}
+
+/// Shows or hides the production chain panel.
+fn toggle_production_chain_panel(
+    actions: Res<ActionState<PlayerAction>>,
+    mut panel_query: Query<&mut Visibility, With<ProductionChainPanel>>,
+) {
+    if !actions.just_pressed(PlayerAction::ToggleProductionChains) {
+        return;
+    }
+
+    let Ok(mut visibility) = panel_query.get_single_mut() else {
+        return;
+    };
+
+    *visibility = match *visibility {
+        Visibility::Hidden => Visibility::Inherited,
+        _ => Visibility::Hidden,
+    };
+}
+
+/// Follows the production chain to the item that was clicked on.
+fn focus_on_clicked_item(
+    button_query: Query<(&Interaction, &ProductionChainButton), Changed<Interaction>>,
+    mut focus: ResMut<ProductionChainFocus>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction == Interaction::Clicked {
+            focus.0 = button.0;
+        }
+    }
+}
+
+/// Rebuilds the contents of the panel whenever a new item is focused.
+fn update_production_chain_panel(
+    focus: Res<ProductionChainFocus>,
+    panel_query: Query<Entity, With<ProductionChainPanel>>,
+    fonts: Res<FiraSansFontFamily>,
+    item_manifest: Res<ItemManifest>,
+    recipe_manifest: Res<RecipeManifest>,
+    localization: Res<Localization>,
+    mut commands: Commands,
+) {
+    if !focus.is_changed() && !item_manifest.is_changed() && !recipe_manifest.is_changed() {
+        return;
+    }
+
+    let Ok(panel_entity) = panel_query.get_single() else {
+        return;
+    };
+
+    let style = TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: FONT_SIZE,
+        color: Color::WHITE,
+    };
+    let item_name = |item_id: Id<Item>| localization.get(item_manifest.name(item_id));
+
+    commands.entity(panel_entity).despawn_descendants();
+    commands.entity(panel_entity).with_children(|parent| {
+        let Some(focused_item) = focus.0 else {
+            parent.spawn(TextBundle::from_section(
+                "Click an item to see how it is made:",
+                style.clone(),
+            ));
+
+            let mut item_ids: Vec<Id<Item>> = item_manifest.variants().into_iter().collect();
+            item_ids.sort_by_key(|&item_id| item_name(item_id));
+            for item_id in item_ids {
+                spawn_button(
+                    parent,
+                    item_name(item_id).to_string(),
+                    Some(item_id),
+                    &style,
+                );
+            }
+            return;
+        };
+
+        spawn_button(parent, "< All items".to_string(), None, &style);
+
+        let production_chain = ProductionChain::new(focused_item, &recipe_manifest);
+        for entry in production_chain.entries() {
+            let indent = " ".repeat(entry.depth * INDENT);
+
+            match entry.node {
+                ProductionChainNode::Item { item_id, repeated } => {
+                    let suffix = if repeated { " (see above)" } else { "" };
+                    spawn_button(
+                        parent,
+                        format!("{indent}{}{suffix}", item_name(item_id)),
+                        Some(item_id),
+                        &style,
+                    );
+                }
+                ProductionChainNode::Recipe(recipe_id) => {
+                    let recipe_name = localization.get(recipe_manifest.name(recipe_id));
+                    parent.spawn(TextBundle::from_section(
+                        format!("{indent}<- {recipe_name}"),
+                        style.clone(),
+                    ));
+                }
+            }
+        }
+    });
+}
+
+/// Adds a clickable line of text, which focuses the `target` item when clicked.
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    text: String,
+    target: Option<Id<Item>>,
+    style: &TextStyle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                background_color: BackgroundColor(Color::NONE),
+                ..Default::default()
+            },
+            ProductionChainButton(target),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(text, style.clone()));
+        });
+}
@@ -0,0 +1,95 @@
+//! Lists the production targets that the planner is working towards.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_management::{localization::Localization, AssetState},
+    items::item_manifest::ItemManifest,
+    structures::planner::ProductionTargets,
+};
+
+use super::{FiraSansFontFamily, LeftPanel};
+
+/// Adds the production targets panel to the UI.
+pub(super) struct ProductionTargetsPanelPlugin;
+
+impl Plugin for ProductionTargetsPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_production_targets_panel)
+            .add_system(update_production_targets_panel.run_if(in_state(AssetState::Ready)));
+    }
+}
+
+/// A marker component for the text that lists the current [`ProductionTargets`].
+#[derive(Component, Debug)]
+struct ProductionTargetsPanel;
+
+/// Creates the text used to list the current [`ProductionTargets`].
+fn setup_production_targets_panel(
+    mut commands: Commands,
+    left_panel_query: Query<Entity, With<LeftPanel>>,
+    fonts: Res<FiraSansFontFamily>,
+) {
+    let left_panel_entity = left_panel_query.single();
+    let text_style = TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: 20.0,
+        color: Color::WHITE,
+    };
+
+    let panel_entity = commands
+        .spawn((
+            TextBundle {
+                text: Text::from_section("PRODUCTION TARGETS".to_string(), text_style),
+                ..Default::default()
+            },
+            ProductionTargetsPanel,
+        ))
+        .id();
+    commands.entity(left_panel_entity).add_child(panel_entity);
+}
+
+/// Updates the panel text whenever the [`ProductionTargets`] change.
+fn update_production_targets_panel(
+    mut text_query: Query<&mut Text, With<ProductionTargetsPanel>>,
+    production_targets: Res<ProductionTargets>,
+    fonts: Res<FiraSansFontFamily>,
+    item_manifest: Res<ItemManifest>,
+    localization: Res<Localization>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    if !production_targets.is_changed() {
+        return;
+    }
+
+    // Sort by name, so the list doesn't jump around as targets are added
+    let mut lines: Vec<String> = production_targets
+        .iter()
+        .map(|(item_id, target)| {
+            format!(
+                "{}: keep {}, make {:.1}/min",
+                localization.get(item_manifest.name(item_id)),
+                target.desired_stock,
+                target.items_per_minute
+            )
+        })
+        .collect();
+    lines.sort();
+
+    let mut value = "Production targets:".to_string();
+    for line in lines {
+        value += &format!("\n{line}");
+    }
+
+    text.sections = vec![TextSection {
+        value,
+        style: TextStyle {
+            font: fonts.regular.clone_weak(),
+            font_size: 20.0,
+            color: Color::WHITE,
+        },
+    }];
+}
@@ -3,12 +3,12 @@
 use crate::{
     asset_management::{manifest::Id, AssetState},
     graphics::palette::ui::{MENU_HIGHLIGHT_COLOR, MENU_NEUTRAL_COLOR},
+    items::tech_tree::TechTree,
     player_interaction::{
         clipboard::{Clipboard, ClipboardData},
         PlayerAction,
     },
-    simulation::geometry::Facing,
-    structures::structure_manifest::{Structure, StructureManifest},
+    structures::structure_manifest::{Structure, StructureData, StructureKind, StructureManifest},
 };
 
 use itertools::Itertools;
@@ -16,8 +16,8 @@ use itertools::Itertools;
 use bevy::prelude::*;
 
 use super::wheel_menu::{
-    select_hex, spawn_hex_menu, AvailableChoices, Choice, HexMenu, HexMenuArrangement,
-    HexMenuElement, HexMenuError,
+    change_hex_menu_page, select_hex, spawn_hex_menu, AvailableChoices, Choice, HexMenu,
+    HexMenuArrangement, HexMenuElement, HexMenuError,
 };
 
 /// Logic used to let users select the structure to build.
@@ -35,6 +35,10 @@ impl Plugin for SelectStructurePlugin {
                 select_hex
                     .pipe(handle_selection)
                     .run_if(resource_exists::<HexMenuArrangement<Id<Structure>>>()),
+            )
+            .add_system(
+                change_hex_menu_page::<Id<Structure>>
+                    .run_if(resource_exists::<HexMenuArrangement<Id<Structure>>>()),
             );
     }
 }
@@ -43,17 +47,64 @@ impl Choice for Id<Structure> {
     const ACTIVATION: PlayerAction = PlayerAction::SelectStructure;
 }
 
-/// Update the set of choices available to build whenever the structure manifest is updated
+/// The groups that structures are sorted into in the structure menu.
+///
+/// Structures of the same category are kept next to each other, in the order listed here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum StructureCategory {
+    /// Living structures, like plants and fungi.
+    Organism,
+    /// Non-living structures that craft items.
+    Crafting,
+    /// Structures that store items.
+    Storage,
+    /// Structures that move items or units between tiles.
+    Logistics,
+}
+
+impl From<&StructureData> for StructureCategory {
+    fn from(structure_data: &StructureData) -> Self {
+        if structure_data.organism_variety.is_some() {
+            return StructureCategory::Organism;
+        }
+
+        match structure_data.kind {
+            StructureKind::Crafting { .. } => StructureCategory::Crafting,
+            StructureKind::Storage { .. } => StructureCategory::Storage,
+            StructureKind::Conveyor { .. } | StructureKind::Bridge => StructureCategory::Logistics,
+        }
+    }
+}
+
+/// Update the set of choices available to build whenever the structure manifest or tech tree is updated
+///
+/// Structures whose starting recipe has not yet been unlocked are hidden.
+/// Choices are grouped by [`StructureCategory`], and may span several pages of the menu.
 fn update_structure_choices(
     mut available_choices: ResMut<AvailableChoices<Id<Structure>>>,
     structure_manifest: Res<StructureManifest>,
+    tech_tree: Res<TechTree>,
 ) {
-    if structure_manifest.is_changed() {
+    if structure_manifest.is_changed() || tech_tree.is_changed() {
         // Sort to ensure a stable ordering
         available_choices.choices = structure_manifest
             .prototypes()
             .into_iter()
-            .sorted()
+            .filter(|&structure_id| {
+                match structure_manifest
+                    .get(structure_id)
+                    .starting_recipe()
+                    .recipe_id()
+                {
+                    Some(recipe_id) => tech_tree.is_unlocked(*recipe_id),
+                    None => true,
+                }
+            })
+            // Prototypes are sorted by name, and this sort is stable,
+            // so structures are listed alphabetically within each category
+            .sorted_by_key(|&structure_id| {
+                StructureCategory::from(structure_manifest.get(structure_id))
+            })
             .collect();
     }
 }
@@ -80,14 +131,7 @@ fn handle_selection(
     match result {
         Ok(element) => {
             if element.is_complete() {
-                let structure_data = ClipboardData {
-                    structure_id: *element.data(),
-                    facing: Facing::default(),
-                    active_recipe: structure_manifest
-                        .get(*element.data())
-                        .starting_recipe()
-                        .clone(),
-                };
+                let structure_data = ClipboardData::new(*element.data(), &structure_manifest);
 
                 clipboard.set_to_structure(Some(structure_data));
                 cleanup(commands, menu_query);
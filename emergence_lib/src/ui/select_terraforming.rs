@@ -7,8 +7,6 @@ use crate::{
     terrain::terrain_manifest::TerrainManifest,
 };
 
-use itertools::Itertools;
-
 use bevy::prelude::*;
 
 use super::wheel_menu::{
@@ -50,13 +48,12 @@ fn update_terraforming_choices(
     if terrain_manifest.is_changed() {
         available_choices.choices = vec![TerraformingChoice::Raise, TerraformingChoice::Lower];
 
-        // Sort to ensure a stable ordering
+        // The manifest is sorted by name, so this ordering is stable
         // The lint here is just wrong
         #[allow(clippy::redundant_closure)]
         let terrain_choices = terrain_manifest
             .variants()
             .into_iter()
-            .sorted()
             .map(|terrain_id| TerraformingChoice::Change(terrain_id));
         available_choices.choices.extend(terrain_choices);
     }
@@ -3,7 +3,7 @@
 use bevy::{ecs::query::QueryEntityError, prelude::*};
 
 use crate::{
-    asset_management::AssetState,
+    asset_management::{localization::Localization, AssetState},
     items::{item_manifest::ItemManifest, recipe::RecipeManifest},
     player_interaction::{
         camera::{CameraMode, CameraSettings},
@@ -172,6 +172,7 @@ fn update_selection_details(
     terrain_manifest: Res<TerrainManifest>,
     recipe_manifest: Res<RecipeManifest>,
     item_manifest: Res<ItemManifest>,
+    localization: Res<Localization>,
 ) {
     let mut parent_visibility = selection_panel_query.single_mut();
     let (mut ghost_style, mut ghost_text) = ghost_details_query.single_mut();
@@ -216,12 +217,20 @@ fn update_selection_details(
 
     match &*selection_details {
         SelectionDetails::Ghost(details) => {
-            ghost_text.sections[0].value =
-                details.display(&item_manifest, &structure_manifest, &recipe_manifest);
+            ghost_text.sections[0].value = details.display(
+                &item_manifest,
+                &structure_manifest,
+                &recipe_manifest,
+                &localization,
+            );
         }
         SelectionDetails::Structure(details) => {
-            structure_text.sections[0].value =
-                details.display(&structure_manifest, &unit_manifest, &item_manifest);
+            structure_text.sections[0].value = details.display(
+                &structure_manifest,
+                &unit_manifest,
+                &item_manifest,
+                &localization,
+            );
         }
         SelectionDetails::Terrain(details) => {
             terrain_text.sections[0].value = details.display(
@@ -229,11 +238,16 @@ fn update_selection_details(
                 &structure_manifest,
                 &item_manifest,
                 &unit_manifest,
+                &localization,
             );
         }
         SelectionDetails::Unit(details) => {
-            unit_text.sections[0].value =
-                details.display(&unit_manifest, &item_manifest, &structure_manifest);
+            unit_text.sections[0].value = details.display(
+                &unit_manifest,
+                &item_manifest,
+                &structure_manifest,
+                &localization,
+            );
         }
         SelectionDetails::None => (),
     };
@@ -268,7 +282,9 @@ pub(crate) enum SelectionDetails {
     /// A ghost is selected
     Ghost(GhostDetails),
     /// A structure is selected
-    Structure(StructureDetails),
+    ///
+    /// Boxed, as these details are much larger than the other variants.
+    Structure(Box<StructureDetails>),
     /// A tile is selected.
     Terrain(TerrainDetails),
     /// A unit is selected
@@ -309,7 +325,7 @@ fn get_details(
             let structure_query_item = structure_query.get(*structure_entity)?;
 
             let crafting_details =
-                if let Some((input, output, active_recipe, workers_present, state)) =
+                if let Some((input, output, active_recipe, workers_present, state, stats)) =
                     structure_query_item.crafting
                 {
                     let maybe_recipe_id = *active_recipe.recipe_id();
@@ -318,10 +334,15 @@ fn get_details(
 
                     Some(CraftingDetails {
                         input_inventory: input.inventory.clone(),
+                        fluid_inventory: structure_query_item.fluid_inventory.cloned(),
+                        output_fluids: structure_query_item
+                            .output_fluids
+                            .map(|output_fluids| output_fluids.tanks.clone()),
                         output_inventory: output.inventory.clone(),
                         recipe,
                         workers_present: workers_present.clone(),
                         state: state.clone(),
+                        production_stats: stats.clone(),
                     })
                 } else {
                     None
@@ -342,15 +363,17 @@ fn get_details(
                         energy_pool: query_item.energy_pool.clone(),
                     });
 
-            SelectionDetails::Structure(StructureDetails {
+            SelectionDetails::Structure(Box::new(StructureDetails {
                 entity: structure_query_item.entity,
                 tile_pos: *structure_query_item.tile_pos,
                 structure_id: *structure_query_item.structure_id,
                 crafting_details,
                 maybe_organism_details,
                 storage_inventory: structure_query_item.storage_inventory.cloned(),
+                health: structure_query_item.health.copied(),
                 marked_for_removal: structure_query_item.marked_for_removal.is_some(),
-            })
+                disabled: structure_query_item.disabled.is_some(),
+            }))
         }
         CurrentSelection::Terrain(selected_tiles) => {
             // FIXME: display info about multiple tiles correctly
@@ -392,6 +415,7 @@ fn get_details(
                 tile_pos: *unit_query_item.tile_pos,
                 held_item: unit_query_item.held_item.clone(),
                 goal: unit_query_item.goal.clone(),
+                job: *unit_query_item.job,
                 action: unit_query_item.action.clone(),
                 impatience_pool: unit_query_item.impatience_pool.clone(),
                 organism_details,
@@ -420,7 +444,7 @@ mod ghost_details {
     use bevy::ecs::{prelude::*, query::WorldQuery};
 
     use crate::{
-        asset_management::manifest::Id,
+        asset_management::{localization::Localization, manifest::Id},
         items::{item_manifest::ItemManifest, recipe::RecipeManifest},
         signals::Emitter,
         simulation::geometry::TilePos,
@@ -473,13 +497,15 @@ mod ghost_details {
             item_manifest: &ItemManifest,
             structure_manifest: &StructureManifest,
             recipe_manifest: &RecipeManifest,
+            localization: &Localization,
         ) -> String {
             let entity = self.entity;
-            let structure_id = structure_manifest.name(self.structure_id);
+            let structure_id = localization.get(structure_manifest.name(self.structure_id));
             let tile_pos = &self.tile_pos;
             let crafting_state = &self.crafting_state;
-            let recipe = self.active_recipe.display(recipe_manifest);
-            let construction_materials = self.input_inventory.display(item_manifest);
+            let recipe = self.active_recipe.display(recipe_manifest, localization);
+            let construction_materials = self.input_inventory.display(item_manifest, localization);
+            let delivered_percent = self.input_inventory.delivered_fraction() * 100.;
 
             format!(
                 "Entity: {entity:?}
@@ -487,6 +513,7 @@ Tile: {tile_pos}
 Ghost structure type: {structure_id}
 Recipe: {recipe}
 Construction materials: {construction_materials}
+Materials delivered: {delivered_percent:.0}%
 {crafting_state}"
             )
         }
@@ -498,6 +525,7 @@ mod organism_details {
     use bevy::ecs::query::WorldQuery;
 
     use crate::{
+        asset_management::localization::Localization,
         organisms::{energy::EnergyPool, lifecycle::Lifecycle, OrganismId},
         structures::structure_manifest::StructureManifest,
         units::unit_manifest::UnitManifest,
@@ -529,11 +557,14 @@ mod organism_details {
             &self,
             structure_manifest: &StructureManifest,
             unit_manifest: &UnitManifest,
+            localization: &Localization,
         ) -> String {
-            let prototypical_form = self
-                .prototypical_form
-                .display(structure_manifest, unit_manifest);
-            let lifecycle = self.lifecycle.display(structure_manifest, unit_manifest);
+            let prototypical_form =
+                self.prototypical_form
+                    .display(structure_manifest, unit_manifest, localization);
+            let lifecycle = self
+                .lifecycle
+                .display(structure_manifest, unit_manifest, localization);
 
             let energy_pool = &self.energy_pool;
 
@@ -552,15 +583,19 @@ mod structure_details {
 
     use super::organism_details::OrganismDetails;
     use crate::{
-        asset_management::manifest::Id,
-        items::{inventory::Inventory, item_manifest::ItemManifest, recipe::RecipeData},
+        asset_management::{localization::Localization, manifest::Id},
+        items::{
+            fluid::FluidInventory, inventory::Inventory, item_manifest::ItemManifest,
+            recipe::RecipeData,
+        },
         simulation::geometry::TilePos,
         structures::{
             construction::MarkedForDemolition,
             crafting::{
-                ActiveRecipe, CraftingState, InputInventory, OutputInventory, StorageInventory,
-                WorkersPresent,
+                ActiveRecipe, CraftingState, Disabled, InputInventory, OutputFluids,
+                OutputInventory, ProductionStats, StorageInventory, WorkersPresent,
             },
+            health::Health,
             structure_manifest::{Structure, StructureManifest},
         },
         units::unit_manifest::UnitManifest,
@@ -582,11 +617,20 @@ mod structure_details {
             &'static ActiveRecipe,
             &'static WorkersPresent,
             &'static CraftingState,
+            &'static ProductionStats,
         )>,
+        /// The fluids used for crafting, if any.
+        pub(super) fluid_inventory: Option<&'static FluidInventory>,
+        /// The fluids produced by crafting, if any.
+        pub(super) output_fluids: Option<&'static OutputFluids>,
         /// If this structure stores things, its inventory.
         pub(super) storage_inventory: Option<&'static StorageInventory>,
+        /// How intact this structure is.
+        pub(super) health: Option<&'static Health>,
         /// Is this structure marked for removal?
         pub(super) marked_for_removal: Option<&'static MarkedForDemolition>,
+        /// Has this structure been paused by the player?
+        pub(super) disabled: Option<&'static Disabled>,
     }
 
     /// Detailed info about a given structure.
@@ -602,10 +646,14 @@ mod structure_details {
         pub(crate) crafting_details: Option<CraftingDetails>,
         /// If this structure stores things, its inventory.
         pub(crate) storage_inventory: Option<StorageInventory>,
+        /// How intact this structure is.
+        pub(crate) health: Option<Health>,
         /// Details about this organism, if it is one.
         pub(crate) maybe_organism_details: Option<OrganismDetails>,
         /// Is this structure slated for removal?
         pub(crate) marked_for_removal: bool,
+        /// Has this structure been paused by the player?
+        pub(crate) disabled: bool,
     }
 
     impl StructureDetails {
@@ -615,9 +663,10 @@ mod structure_details {
             structure_manifest: &StructureManifest,
             unit_manifest: &UnitManifest,
             item_manifest: &ItemManifest,
+            localization: &Localization,
         ) -> String {
             let entity = self.entity;
-            let structure_id = structure_manifest.name(self.structure_id);
+            let structure_id = localization.get(structure_manifest.name(self.structure_id));
             let tile_pos = &self.tile_pos;
 
             let mut string = format!(
@@ -626,20 +675,37 @@ Structure type: {structure_id}
 Tile: {tile_pos}"
             );
 
+            if let Some(health) = &self.health {
+                string += &format!("\nHealth: {:.0}/{:.0}", health.current(), health.max());
+            }
+
             if self.marked_for_removal {
                 string += "\nMarked for removal!";
             }
 
+            if self.disabled {
+                string += "\nDisabled";
+            }
+
             if let Some(crafting) = &self.crafting_details {
-                string += &format!("\n{}", crafting.display(item_manifest));
+                string += &format!("\n{}", crafting.display(item_manifest, localization));
             }
 
             if let Some(storage) = &self.storage_inventory {
-                string += &format!("\nStoring: {}", storage.display(item_manifest));
+                string += &format!(
+                    "\nStoring: {}",
+                    storage.display(item_manifest, localization)
+                );
+
+                let reserved_for = storage.reserved_for().display(item_manifest, localization);
+                string += &format!("\nReserved for: {reserved_for}");
             }
 
             if let Some(organism) = &self.maybe_organism_details {
-                string += &format!("\n{}", organism.display(structure_manifest, unit_manifest));
+                string += &format!(
+                    "\n{}",
+                    organism.display(structure_manifest, unit_manifest, localization)
+                );
             };
 
             string
@@ -652,6 +718,12 @@ Tile: {tile_pos}"
         /// The inventory for the input items.
         pub(crate) input_inventory: Inventory,
 
+        /// The tanks for the input fluids, if any.
+        pub(crate) fluid_inventory: Option<FluidInventory>,
+
+        /// The tanks for the output fluids, if any.
+        pub(crate) output_fluids: Option<FluidInventory>,
+
         /// The inventory for the output items.
         pub(crate) output_inventory: Inventory,
 
@@ -663,28 +735,53 @@ Tile: {tile_pos}"
 
         /// The number of workers that are presently working on this.
         pub(crate) workers_present: WorkersPresent,
+
+        /// How productive this structure has been over its lifetime.
+        pub(crate) production_stats: ProductionStats,
     }
 
     impl CraftingDetails {
         /// The pretty formatting for this type.
-        pub(crate) fn display(&self, item_manifest: &ItemManifest) -> String {
-            let input_inventory = self.input_inventory.display(item_manifest);
-            let output_inventory = self.output_inventory.display(item_manifest);
+        pub(crate) fn display(
+            &self,
+            item_manifest: &ItemManifest,
+            localization: &Localization,
+        ) -> String {
+            let mut input_inventory = self.input_inventory.display(item_manifest, localization);
+            if let Some(fluid_inventory) = &self.fluid_inventory {
+                if !fluid_inventory.is_empty() {
+                    input_inventory += &format!(
+                        "\nFluids: {}",
+                        fluid_inventory.display(item_manifest, localization)
+                    );
+                }
+            }
+            let mut output_inventory = self.output_inventory.display(item_manifest, localization);
+            if let Some(output_fluids) = &self.output_fluids {
+                if !output_fluids.is_empty() {
+                    output_inventory += &format!(
+                        "\nFluids: {}",
+                        output_fluids.display(item_manifest, localization)
+                    );
+                }
+            }
             let crafting_state = &self.state;
 
             let recipe_string = match &self.recipe {
-                Some(recipe) => recipe.display(item_manifest),
+                Some(recipe) => recipe.display(item_manifest, localization),
                 None => "None".to_string(),
             };
 
             let workers_present = &self.workers_present;
+            let production_stats = &self.production_stats;
 
             format!(
                 "Recipe: {recipe_string}
 Input: {input_inventory}
 {crafting_state}
 Workers present: {workers_present}
-Output: {output_inventory}"
+Output: {output_inventory}
+{production_stats}"
             )
         }
     }
@@ -695,7 +792,7 @@ mod terrain_details {
     use bevy::ecs::{prelude::*, query::WorldQuery};
 
     use crate::{
-        asset_management::manifest::Id,
+        asset_management::{localization::Localization, manifest::Id},
         items::item_manifest::ItemManifest,
         player_interaction::zoning::Zoning,
         signals::LocalSignals,
@@ -743,15 +840,21 @@ mod terrain_details {
             structure_manifest: &StructureManifest,
             item_manifest: &ItemManifest,
             unit_manifest: &UnitManifest,
+            localization: &Localization,
         ) -> String {
             let entity = self.entity;
-            let terrain_type = terrain_manifest.name(self.terrain_id);
+            let terrain_type = localization.get(terrain_manifest.name(self.terrain_id));
             let tile_pos = &self.tile_pos;
             let height = &self.height;
-            let signals = self
-                .signals
-                .display(item_manifest, structure_manifest, unit_manifest);
-            let zoning = self.zoning.display(structure_manifest, terrain_manifest);
+            let signals = self.signals.display(
+                item_manifest,
+                structure_manifest,
+                unit_manifest,
+                localization,
+            );
+            let zoning = self
+                .zoning
+                .display(structure_manifest, terrain_manifest, localization);
 
             format!(
                 "Entity: {entity:?}
@@ -771,7 +874,7 @@ mod unit_details {
     use bevy::ecs::{prelude::*, query::WorldQuery};
 
     use crate::{
-        asset_management::manifest::Id,
+        asset_management::{localization::Localization, manifest::Id},
         items::item_manifest::ItemManifest,
         simulation::geometry::TilePos,
         structures::structure_manifest::StructureManifest,
@@ -781,6 +884,7 @@ mod unit_details {
             hunger::Diet,
             impatience::ImpatiencePool,
             item_interaction::UnitInventory,
+            jobs::Job,
             unit_manifest::{Unit, UnitManifest},
         },
     };
@@ -800,6 +904,8 @@ mod unit_details {
         pub(super) held_item: &'static UnitInventory,
         /// What this unit is trying to achieve
         pub(super) goal: &'static Goal,
+        /// Which goals this unit is willing to pursue
+        pub(super) job: &'static Job,
         /// What is currently being done
         pub(super) action: &'static CurrentAction,
         /// How frustrated the unit is
@@ -821,6 +927,8 @@ mod unit_details {
         pub(super) held_item: UnitInventory,
         /// What this unit is trying to achieve
         pub(super) goal: Goal,
+        /// Which goals this unit is willing to pursue
+        pub(super) job: Job,
         /// What is currently being done
         pub(super) action: CurrentAction,
         /// Details about this organism, if it is one.
@@ -836,18 +944,22 @@ mod unit_details {
             unit_manifest: &UnitManifest,
             item_manifest: &ItemManifest,
             structure_manifest: &StructureManifest,
+            localization: &Localization,
         ) -> String {
             let entity = self.entity;
-            let unit_name = unit_manifest.name(self.unit_id);
-            let diet = self.diet.display(item_manifest);
+            let unit_name = localization.get(unit_manifest.name(self.unit_id));
+            let diet = self.diet.display(item_manifest, localization);
             let tile_pos = &self.tile_pos;
-            let held_item = self.held_item.display(item_manifest);
-            let goal = self.goal.display(item_manifest, structure_manifest);
-            let action = &self.action.display(item_manifest);
+            let held_item = self.held_item.display(item_manifest, localization);
+            let goal = self
+                .goal
+                .display(item_manifest, structure_manifest, localization);
+            let job = &self.job;
+            let action = &self.action.display(item_manifest, localization);
             let impatience_pool = &self.impatience_pool;
-            let organism_details = self
-                .organism_details
-                .display(structure_manifest, unit_manifest);
+            let organism_details =
+                self.organism_details
+                    .display(structure_manifest, unit_manifest, localization);
 
             format!(
                 "Entity: {entity:?}
@@ -855,6 +967,7 @@ Unit type: {unit_name}
 Tile: {tile_pos}
 Diet: {diet}
 Holding: {held_item}
+Job: {job:?}
 Goal: {goal}
 Action: {action}
 Impatience: {impatience_pool}
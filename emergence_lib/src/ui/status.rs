@@ -5,11 +5,11 @@ use bevy_mod_billboard::{prelude::BillboardPlugin, BillboardDepth, BillboardText
 use leafwing_input_manager::prelude::ActionState;
 
 use crate::{
-    asset_management::AssetState,
+    asset_management::{localization::Localization, AssetState},
     items::item_manifest::ItemManifest,
     player_interaction::PlayerAction,
     structures::{crafting::CraftingState, structure_manifest::StructureManifest},
-    units::goals::Goal,
+    units::{actions::CurrentAction, goals::Goal},
 };
 
 use super::FiraSansFontFamily;
@@ -89,12 +89,13 @@ fn cycle_status_visualization(
 /// Displays the status of each unit and crafting structure.
 fn display_status(
     status_visualization: Res<StatusVisualization>,
-    unit_query: Query<(&Transform, &Goal)>,
+    unit_query: Query<(&Transform, &Goal, &CurrentAction)>,
     crafting_query: Query<(&Transform, &CraftingState)>,
     status_display_query: Query<Entity, With<StatusDisplay>>,
     fonts: Res<FiraSansFontFamily>,
     item_manifest: Res<ItemManifest>,
     structure_manifest: Res<StructureManifest>,
+    localization: Res<Localization>,
     mut commands: Commands,
 ) {
     // PERF: immediate mode for now
@@ -134,7 +135,7 @@ fn display_status(
     }
 
     if status_visualization.units_enabled() {
-        for (unit_transform, goal) in unit_query.iter() {
+        for (unit_transform, goal, action) in unit_query.iter() {
             let transform = Transform {
                 translation: Vec3::new(
                     unit_transform.translation.x,
@@ -149,7 +150,11 @@ fn display_status(
                 .spawn(BillboardTextBundle {
                     transform,
                     text: Text::from_section(
-                        goal.display(&item_manifest, &structure_manifest),
+                        format!(
+                            "{}\n{}",
+                            goal.display(&item_manifest, &structure_manifest, &localization),
+                            action.display(&item_manifest, &localization)
+                        ),
                         TextStyle {
                             font_size: 60.0,
                             font: fonts.regular.clone_weak(),
@@ -0,0 +1,70 @@
+//! Shows the instructions for the current step of the [`Tutorial`].
+
+use bevy::prelude::*;
+
+use crate::player_interaction::tutorial::Tutorial;
+
+use super::{FiraSansFontFamily, LeftPanel};
+
+/// Displays the current tutorial step in the UI.
+pub(super) struct TutorialPanelPlugin;
+
+impl Plugin for TutorialPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_tutorial_panel)
+            .add_system(update_tutorial_panel);
+    }
+}
+
+/// Marker component for the text of the tutorial panel.
+#[derive(Component)]
+struct TutorialPanel;
+
+/// Creates the empty tutorial panel.
+fn spawn_tutorial_panel(
+    mut commands: Commands,
+    left_panel_query: Query<Entity, With<LeftPanel>>,
+    fonts: Res<FiraSansFontFamily>,
+) {
+    let style = TextStyle {
+        font: fonts.regular.clone_weak(),
+        font_size: 20.,
+        color: Color::WHITE,
+    };
+
+    let panel_entity = commands
+        .spawn((
+            TextBundle {
+                text: Text::from_section("", style),
+                background_color: BackgroundColor(Color::BLACK.with_a(0.5)),
+                ..Default::default()
+            },
+            TutorialPanel,
+        ))
+        .id();
+
+    let left_panel_entity = left_panel_query.single();
+    commands.entity(left_panel_entity).add_child(panel_entity);
+}
+
+/// Shows the message for the current tutorial step, and hides the panel once the tutorial is complete.
+fn update_tutorial_panel(
+    tutorial: Res<Tutorial>,
+    mut panel_query: Query<(&mut Text, &mut Visibility), With<TutorialPanel>>,
+) {
+    if !tutorial.is_changed() {
+        return;
+    }
+
+    let Ok((mut text, mut visibility)) = panel_query.get_single_mut() else {
+        return;
+    };
+
+    match tutorial.current_step() {
+        Some(step) => {
+            text.sections[0].value = format!("Tutorial: {}", step.message);
+            *visibility = Visibility::Inherited;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
@@ -127,21 +127,70 @@ pub(super) trait Choice: Clone + Hash + Eq + Send + Sync + 'static {
     const ACTIVATION: PlayerAction;
 }
 
+/// The number of rings of hexes in a hex menu, including the reserved center.
+///
+/// Any larger than this is quite unwieldy.
+const MENU_RANGE: u32 = 3;
+
 /// The list of choices available to use in a menu.
+///
+/// When there are more choices than fit in a single menu, they are split into pages.
 #[derive(Resource)]
 pub(super) struct AvailableChoices<D> {
     /// The backing ordered [`Vec`]
     pub(super) choices: Vec<D>,
+    /// The index of the page that is currently displayed.
+    page: usize,
 }
 
 impl<D> Default for AvailableChoices<D> {
     fn default() -> Self {
         Self {
             choices: Vec::new(),
+            page: 0,
         }
     }
 }
 
+impl<D> AvailableChoices<D> {
+    /// The number of choices that fit on a single page.
+    ///
+    /// Each ring of the menu contains 6 more hexes than the last, and the center is reserved for cancellation.
+    pub(super) const PAGE_SIZE: usize = 3 * (MENU_RANGE * (MENU_RANGE - 1)) as usize;
+
+    /// The number of pages needed to display all of the choices.
+    ///
+    /// There is always at least one page, even if it is empty.
+    pub(super) fn page_count(&self) -> usize {
+        self.choices.len().div_ceil(Self::PAGE_SIZE).max(1)
+    }
+
+    /// The index of the page that is currently displayed.
+    ///
+    /// This is clamped, in case the choices have shrunk since the page was selected.
+    pub(super) fn page(&self) -> usize {
+        self.page.min(self.page_count() - 1)
+    }
+
+    /// The choices on the current page.
+    pub(super) fn current_page(&self) -> &[D] {
+        self.choices
+            .chunks(Self::PAGE_SIZE)
+            .nth(self.page())
+            .unwrap_or(&[])
+    }
+
+    /// Advances to the next page, wrapping around at the end.
+    pub(super) fn next_page(&mut self) {
+        self.page = (self.page() + 1) % self.page_count();
+    }
+
+    /// Goes back to the previous page, wrapping around at the start.
+    pub(super) fn previous_page(&mut self) {
+        self.page = (self.page() + self.page_count() - 1) % self.page_count();
+    }
+}
+
 /// Creates a new hex menu.
 pub(super) fn spawn_hex_menu<D: Choice>(
     mut commands: Commands,
@@ -151,67 +200,114 @@ pub(super) fn spawn_hex_menu<D: Choice>(
     available_choices: Res<AvailableChoices<D>>,
     icons: Res<Icons<D>>,
 ) {
-    /// The size of the hexes used in this menu.
-    const HEX_SIZE: f32 = 64.0;
     if actions.just_pressed(D::ACTIVATION) {
         if let Some(cursor_pos) = cursor_pos.maybe_screen_pos() {
-            let mut arrangement = HexMenuArrangement {
-                content_map: HashMap::default(),
-                icon_map: HashMap::default(),
-                background_map: HashMap::default(),
-                layout: HexLayout {
-                    orientation: HexOrientation::pointy(),
-                    origin: cursor_pos,
-                    hex_size: Vec2 {
-                        x: HEX_SIZE,
-                        y: HEX_SIZE,
-                    },
-                },
-            };
-
-            // Any larger than this is quite unwieldy
-            let range = 3;
-
-            // Center is reserved for easy cancellation.
-            let mut hexes =
-                Hex::ZERO.custom_spiral_range(1..range, hexx::Direction::BottomRight, true);
-
-            let variants: Vec<_> = Vec::from_iter(available_choices.choices.iter().cloned());
-
-            for data in variants {
-                if let Some(hex) = hexes.next() {
-                    // Content
-                    arrangement.content_map.insert(hex, data.clone());
-                    // Icon
-                    let icon_entity = commands
-                        .spawn(HexMenuIconBundle::new(
-                            data,
-                            hex,
-                            &icons,
-                            &arrangement.layout,
-                        ))
-                        .id();
-                    arrangement.icon_map.insert(hex, icon_entity);
-                    // Background
-                    let background_entity = commands
-                        .spawn(HexMenuBackgroundBundle::new(
-                            hex,
-                            &arrangement.layout,
-                            &ui_elements.hex_menu_background,
-                        ))
-                        .id();
-                    arrangement.background_map.insert(hex, background_entity);
-                } else {
-                    // Just give up rather than panic if too many entries are found
-                    warn!("Too many entries in hex menu!");
-                }
-            }
-
+            let arrangement = build_hex_menu(
+                &mut commands,
+                cursor_pos,
+                &available_choices,
+                &icons,
+                &ui_elements,
+            );
             commands.insert_resource(arrangement);
         }
     }
 }
 
+/// Flips between the pages of an open hex menu using the dedicated menu page actions.
+///
+/// The menu is rebuilt in place, so the player can keep holding the activation button.
+pub(super) fn change_hex_menu_page<D: Choice>(
+    mut commands: Commands,
+    actions: Res<ActionState<PlayerAction>>,
+    arrangement: Res<HexMenuArrangement<D>>,
+    menu_query: Query<Entity, With<HexMenu>>,
+    ui_elements: Res<UiElements>,
+    mut available_choices: ResMut<AvailableChoices<D>>,
+    icons: Res<Icons<D>>,
+) {
+    if available_choices.page_count() <= 1 {
+        return;
+    }
+
+    if actions.just_pressed(PlayerAction::NextMenuPage) {
+        available_choices.next_page();
+    } else if actions.just_pressed(PlayerAction::PreviousMenuPage) {
+        available_choices.previous_page();
+    } else {
+        return;
+    }
+
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let new_arrangement = build_hex_menu(
+        &mut commands,
+        arrangement.layout.origin,
+        &available_choices,
+        &icons,
+        &ui_elements,
+    );
+    commands.insert_resource(new_arrangement);
+}
+
+/// Spawns the icons and backgrounds for the current page of `available_choices`, centered on `origin`.
+fn build_hex_menu<D: Choice>(
+    commands: &mut Commands,
+    origin: Vec2,
+    available_choices: &AvailableChoices<D>,
+    icons: &Icons<D>,
+    ui_elements: &UiElements,
+) -> HexMenuArrangement<D> {
+    /// The size of the hexes used in this menu.
+    const HEX_SIZE: f32 = 64.0;
+
+    let mut arrangement = HexMenuArrangement {
+        content_map: HashMap::default(),
+        icon_map: HashMap::default(),
+        background_map: HashMap::default(),
+        layout: HexLayout {
+            orientation: HexOrientation::pointy(),
+            origin,
+            hex_size: Vec2 {
+                x: HEX_SIZE,
+                y: HEX_SIZE,
+            },
+        },
+    };
+
+    // Center is reserved for easy cancellation.
+    let hexes = Hex::ZERO.custom_spiral_range(1..MENU_RANGE, hexx::Direction::BottomRight, true);
+
+    // Each page fits exactly within the available hexes
+    for (hex, data) in hexes.zip(available_choices.current_page().iter().cloned()) {
+        // Content
+        arrangement.content_map.insert(hex, data.clone());
+        // Icon
+        let icon_entity = commands
+            .spawn(HexMenuIconBundle::new(
+                data,
+                hex,
+                icons,
+                &arrangement.layout,
+            ))
+            .id();
+        arrangement.icon_map.insert(hex, icon_entity);
+        // Background
+        let background_entity = commands
+            .spawn(HexMenuBackgroundBundle::new(
+                hex,
+                &arrangement.layout,
+                &ui_elements.hex_menu_background,
+            ))
+            .id();
+        arrangement.background_map.insert(hex, background_entity);
+    }
+
+    arrangement
+}
+
 /// The icon stored presented in a hex menu
 #[derive(Bundle)]
 struct HexMenuIconBundle {
@@ -318,3 +414,51 @@ impl HexMenuBackgroundBundle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A set of available choices with `n` numbered entries.
+    fn choices(n: usize) -> AvailableChoices<usize> {
+        AvailableChoices {
+            choices: (0..n).collect(),
+            page: 0,
+        }
+    }
+
+    #[test]
+    fn choices_are_split_into_pages() {
+        let page_size = AvailableChoices::<usize>::PAGE_SIZE;
+        let mut available_choices = choices(page_size + 1);
+        assert_eq!(available_choices.page_count(), 2);
+        assert_eq!(available_choices.current_page().len(), page_size);
+
+        available_choices.next_page();
+        assert_eq!(available_choices.current_page(), &[page_size]);
+
+        // Pages wrap around in both directions
+        available_choices.next_page();
+        assert_eq!(available_choices.page(), 0);
+        available_choices.previous_page();
+        assert_eq!(available_choices.page(), 1);
+    }
+
+    #[test]
+    fn page_is_clamped_when_choices_shrink() {
+        let mut available_choices = choices(AvailableChoices::<usize>::PAGE_SIZE * 3);
+        available_choices.previous_page();
+        assert_eq!(available_choices.page(), 2);
+
+        available_choices.choices.truncate(1);
+        assert_eq!(available_choices.page(), 0);
+        assert_eq!(available_choices.current_page(), &[0]);
+    }
+
+    #[test]
+    fn empty_menus_have_one_page() {
+        let available_choices = choices(0);
+        assert_eq!(available_choices.page_count(), 1);
+        assert!(available_choices.current_page().is_empty());
+    }
+}
@@ -1,39 +1,47 @@
 //! What are units currently doing?
 
-use bevy::{ecs::query::WorldQuery, prelude::*};
+use bevy::{ecs::query::WorldQuery, prelude::*, utils::Duration};
 use leafwing_abilities::prelude::Pool;
-use rand::{rngs::ThreadRng, seq::SliceRandom, thread_rng};
+use rand::{rngs::ThreadRng, seq::SliceRandom, thread_rng, Rng};
 
 use crate::{
-    asset_management::manifest::Id,
-    items::{
-        item_manifest::{Item, ItemManifest},
-        ItemCount,
-    },
+    asset_management::{localization::Localization, manifest::Id},
+    items::item_manifest::{Item, ItemManifest},
     organisms::{energy::EnergyPool, lifecycle::Lifecycle},
+    player_interaction::{contracts::Contracts, terraform::MarkedForTerraforming},
     signals::{SignalStrength, SignalType, Signals},
-    simulation::geometry::{Facing, MapGeometry, RotationDirection, TilePos},
+    simulation::{
+        factions::Faction,
+        geometry::{Facing, Height, MapGeometry, RotationDirection, TilePos},
+    },
     structures::{
+        colony_center::ColonyCenter,
         commands::StructureCommandsExt,
         construction::{DemolitionQuery, MarkedForDemolition},
         crafting::{
-            CraftingState, InputInventory, OutputInventory, StorageInventory, WorkersPresent,
-            WorkplaceQuery,
+            CraftingState, InputInventory, OutputDirection, OutputInventory, StorageInventory,
+            WorkersPresent, WorkplaceQuery,
         },
-        structure_manifest::Structure,
+        health::{Health, RepairQuery},
+        structure_manifest::{Structure, StructureManifest},
+        upgrades::{MarkedForUpgrade, UpgradeQuery},
     },
     terrain::terrain_manifest::{Terrain, TerrainManifest},
 };
 
 use super::{
+    collision::UnitCollision,
     goals::Goal,
     impatience::ImpatiencePool,
-    item_interaction::UnitInventory,
+    item_interaction::{ItemTransferStarted, ItemTransferred, PickupReservations, UnitInventory},
+    jobs::Job,
+    memory::FailedTargets,
+    status_effects::StatusEffects,
     unit_manifest::{Unit, UnitManifest},
 };
 
 /// Ticks the timer for each [`CurrentAction`].
-pub(super) fn advance_action_timer(
+pub(crate) fn advance_action_timer(
     mut units_query: Query<&mut CurrentAction>,
     time: Res<FixedTime>,
 ) {
@@ -45,11 +53,20 @@ pub(super) fn advance_action_timer(
 }
 
 /// Choose the unit's action for this turn
-pub(super) fn choose_actions(
-    mut units_query: Query<
-        (&TilePos, &Facing, &Goal, &mut CurrentAction, &UnitInventory),
-        With<Id<Unit>>,
-    >,
+pub(crate) fn choose_actions(
+    mut units_query: Query<(
+        Entity,
+        &Id<Unit>,
+        &TilePos,
+        &Facing,
+        &Goal,
+        &mut CurrentAction,
+        &UnitInventory,
+        Option<&StatusEffects>,
+        Option<&mut FailedTargets>,
+        Option<&Faction>,
+        Option<&Job>,
+    )>,
     // We shouldn't be dropping off new stuff at structures that are about to be destroyed!
     input_inventory_query: Query<
         AnyOf<(&InputInventory, &StorageInventory)>,
@@ -57,31 +74,129 @@ pub(super) fn choose_actions(
     >,
     // But we can take their items away
     output_inventory_query: Query<AnyOf<(&OutputInventory, &StorageInventory)>>,
+    // Units prefer to pick up from the front of directional structures,
+    // and from sources that few other units are picking up from
+    (output_direction_query, pickup_reservations): (
+        Query<(&TilePos, &Facing, &OutputDirection), Without<Goal>>,
+        Res<PickupReservations>,
+    ),
     workplace_query: WorkplaceQuery,
     demolition_query: DemolitionQuery,
-    map_geometry: Res<MapGeometry>,
-    signals: Res<Signals>,
+    // Grouped together to stay within the limit on the number of system parameters
+    (repair_query, upgrade_query): (RepairQuery, UpgradeQuery),
+    // Grouped together to stay within the limit on the number of system parameters,
+    // and units only interact with structures that belong to their own faction
+    (terraforming_query, faction_query): (Query<&MarkedForTerraforming>, Query<&Faction>),
+    mut map_geometry: ResMut<MapGeometry>,
+    // Idle units spread out around the colony center
+    (unit_collision, colony_center): (Res<UnitCollision>, Res<ColonyCenter>),
+    // Contract sinks accept deliveries even when they are storage structures
+    (signals, contracts): (Res<Signals>, Res<Contracts>),
     terrain_query: Query<&Id<Terrain>>,
     terrain_manifest: Res<TerrainManifest>,
     item_manifest: Res<ItemManifest>,
+    structure_manifest: Res<StructureManifest>,
+    unit_manifest: Res<UnitManifest>,
 ) {
     let rng = &mut thread_rng();
-    let map_geometry = map_geometry.into_inner();
+    // Reserving tiles for units doesn't change where structures can be built, so don't trigger change detection
+    let map_geometry = map_geometry.bypass_change_detection();
+
+    for (
+        unit_entity,
+        &unit_id,
+        &unit_tile_pos,
+        facing,
+        goal,
+        mut action,
+        unit_inventory,
+        maybe_status_effects,
+        maybe_failed_targets,
+        maybe_faction,
+        maybe_job,
+    ) in units_query.iter_mut()
+    {
+        let unit_faction = maybe_faction.copied().unwrap_or_default();
+        let ghosts_only = maybe_job.copied().unwrap_or_default().only_serves_ghosts();
+
+        // Units caught on a hazardous tile get off of it as soon as they are free to act.
+        // Anything else they were doing has already been cut short by `interrupt_hazardous_actions`.
+        if map_geometry.is_hazardous(unit_tile_pos) && action.finished() {
+            if let Some(flee) = CurrentAction::flee(
+                unit_tile_pos,
+                unit_id,
+                facing,
+                &terrain_query,
+                &terrain_manifest,
+                map_geometry,
+            ) {
+                *action = flee;
+                continue;
+            }
+        }
 
-    for (&unit_tile_pos, facing, goal, mut action, unit_inventory) in units_query.iter_mut() {
         if action.finished() {
+            let interaction_radius = unit_manifest.get(unit_id).interaction_radius;
+            // Units without a memory simply forget their failures right away
+            let mut no_memory = FailedTargets::new(0);
+            let failed_targets = match maybe_failed_targets {
+                Some(failed_targets) => failed_targets.into_inner(),
+                None => &mut no_memory,
+            };
+
             *action = match goal {
-                // Alternate between spinning and moving forward.
-                Goal::Wander { .. } => match action.action() {
-                    UnitAction::Spin { .. } => CurrentAction::move_forward(
-                        unit_tile_pos,
-                        facing,
-                        map_geometry,
-                        &terrain_query,
-                        &terrain_manifest,
-                    ),
-                    _ => CurrentAction::random_spin(rng),
-                },
+                Goal::Wander { .. } => {
+                    let wandering_behavior = &unit_manifest.get(unit_id).wandering_behavior;
+                    let preferred_direction = wandering_behavior
+                        .preferred_direction(unit_tile_pos, rng)
+                        .or_else(|| {
+                            colony_center.dispersal_direction(unit_tile_pos, map_geometry, rng)
+                        });
+
+                    match preferred_direction {
+                        // Head in the direction we're biased towards
+                        Some(direction) if direction != facing.direction => {
+                            CurrentAction::spin_towards(facing, direction)
+                        }
+                        Some(_) => CurrentAction::move_forward(
+                            unit_tile_pos,
+                            unit_id,
+                            facing,
+                            map_geometry,
+                            &terrain_query,
+                            &terrain_manifest,
+                        ),
+                        // Alternate between spinning and moving forward.
+                        None => match action.action() {
+                            UnitAction::Spin { .. } => CurrentAction::move_forward(
+                                unit_tile_pos,
+                                unit_id,
+                                facing,
+                                map_geometry,
+                                &terrain_query,
+                                &terrain_manifest,
+                            ),
+                            _ => CurrentAction::random_spin(rng),
+                        },
+                    }
+                }
+                // The target was chosen by `choose_goal`, so no further searching is needed here
+                Goal::Explore { target } => {
+                    if map_geometry.is_explored(*target) {
+                        // Wait for `choose_goal` to pick a new target, or to switch back to wandering
+                        CurrentAction::idle()
+                    } else {
+                        CurrentAction::move_or_spin(
+                            unit_tile_pos,
+                            unit_id,
+                            *target,
+                            facing,
+                            &terrain_query,
+                            &terrain_manifest,
+                            map_geometry,
+                        )
+                    }
+                }
                 Goal::Pickup(item_id) => {
                     if unit_inventory.is_some() && unit_inventory.unwrap() != *item_id {
                         CurrentAction::abandon()
@@ -89,13 +204,20 @@ pub(super) fn choose_actions(
                         CurrentAction::find_item(
                             *item_id,
                             unit_tile_pos,
+                            unit_id,
+                            interaction_radius,
                             facing,
                             goal,
                             &output_inventory_query,
+                            &output_direction_query,
+                            &pickup_reservations,
                             &signals,
                             rng,
                             &terrain_query,
                             &terrain_manifest,
+                            unit_faction,
+                            &faction_query,
+                            failed_targets,
                             map_geometry,
                         )
                     }
@@ -107,6 +229,8 @@ pub(super) fn choose_actions(
                         CurrentAction::find_storage(
                             *item_id,
                             unit_tile_pos,
+                            unit_id,
+                            interaction_radius,
                             facing,
                             goal,
                             &input_inventory_query,
@@ -115,6 +239,9 @@ pub(super) fn choose_actions(
                             &terrain_query,
                             &terrain_manifest,
                             &item_manifest,
+                            unit_faction,
+                            &faction_query,
+                            failed_targets,
                             map_geometry,
                         )
                     }
@@ -126,13 +253,21 @@ pub(super) fn choose_actions(
                         CurrentAction::find_delivery(
                             *item_id,
                             unit_tile_pos,
+                            unit_id,
+                            interaction_radius,
                             facing,
                             goal,
                             &input_inventory_query,
+                            &contracts,
+                            ghosts_only,
                             &signals,
                             rng,
                             &terrain_query,
                             &terrain_manifest,
+                            &item_manifest,
+                            unit_faction,
+                            &faction_query,
+                            failed_targets,
                             map_geometry,
                         )
                     }
@@ -148,13 +283,20 @@ pub(super) fn choose_actions(
                         CurrentAction::find_item(
                             *item_id,
                             unit_tile_pos,
+                            unit_id,
+                            interaction_radius,
                             facing,
                             goal,
                             &output_inventory_query,
+                            &output_direction_query,
+                            &pickup_reservations,
                             &signals,
                             rng,
                             &terrain_query,
                             &terrain_manifest,
+                            unit_faction,
+                            &faction_query,
+                            failed_targets,
                             map_geometry,
                         )
                     }
@@ -162,36 +304,174 @@ pub(super) fn choose_actions(
                 Goal::Work(structure_id) => CurrentAction::find_workplace(
                     *structure_id,
                     unit_tile_pos,
+                    unit_id,
                     facing,
                     &workplace_query,
+                    ghosts_only,
                     &signals,
                     rng,
                     &terrain_query,
                     &terrain_manifest,
+                    unit_faction,
+                    &faction_query,
+                    failed_targets,
                     map_geometry,
                 ),
                 Goal::Demolish(structure_id) => CurrentAction::find_demolition_site(
                     *structure_id,
                     unit_tile_pos,
+                    unit_id,
                     facing,
                     &demolition_query,
                     &signals,
                     rng,
                     &terrain_query,
                     &terrain_manifest,
+                    unit_faction,
+                    &faction_query,
+                    map_geometry,
+                ),
+                Goal::Repair(structure_id) => {
+                    let materials = &structure_manifest
+                        .get(*structure_id)
+                        .construction_strategy
+                        .materials;
+                    let material_ids: Vec<Id<Item>> = materials
+                        .iter()
+                        .map(|item_slot| item_slot.item_id())
+                        .collect();
+
+                    match unit_inventory.held_item {
+                        // Grab some materials before heading over to make repairs
+                        None if !material_ids.is_empty() => {
+                            let item_id = material_ids[0];
+                            CurrentAction::find_item(
+                                item_id,
+                                unit_tile_pos,
+                                unit_id,
+                                interaction_radius,
+                                facing,
+                                &Goal::Pickup(item_id),
+                                &output_inventory_query,
+                                &output_direction_query,
+                                &pickup_reservations,
+                                &signals,
+                                rng,
+                                &terrain_query,
+                                &terrain_manifest,
+                                unit_faction,
+                                &faction_query,
+                                failed_targets,
+                                map_geometry,
+                            )
+                        }
+                        // This item can't be used to repair the structure
+                        Some(held_item)
+                            if !material_ids.is_empty() && !material_ids.contains(&held_item) =>
+                        {
+                            CurrentAction::abandon()
+                        }
+                        _ => CurrentAction::find_repair_site(
+                            *structure_id,
+                            unit_tile_pos,
+                            unit_id,
+                            facing,
+                            &repair_query,
+                            &signals,
+                            rng,
+                            &terrain_query,
+                            &terrain_manifest,
+                            unit_faction,
+                            &faction_query,
+                            map_geometry,
+                        ),
+                    }
+                }
+                Goal::Upgrade(structure_id) => {
+                    let materials = &structure_manifest.get(*structure_id).upgrade_cost.materials;
+                    let material_ids: Vec<Id<Item>> = materials
+                        .iter()
+                        .map(|item_slot| item_slot.item_id())
+                        .collect();
+
+                    match unit_inventory.held_item {
+                        // Grab some materials before heading over to upgrade the structure
+                        None if !material_ids.is_empty() => {
+                            let item_id = *material_ids.choose(rng).unwrap();
+                            CurrentAction::find_item(
+                                item_id,
+                                unit_tile_pos,
+                                unit_id,
+                                interaction_radius,
+                                facing,
+                                &Goal::Pickup(item_id),
+                                &output_inventory_query,
+                                &output_direction_query,
+                                &pickup_reservations,
+                                &signals,
+                                rng,
+                                &terrain_query,
+                                &terrain_manifest,
+                                unit_faction,
+                                &faction_query,
+                                failed_targets,
+                                map_geometry,
+                            )
+                        }
+                        // This item can't be used to upgrade the structure
+                        Some(held_item)
+                            if !material_ids.is_empty() && !material_ids.contains(&held_item) =>
+                        {
+                            CurrentAction::abandon()
+                        }
+                        _ => CurrentAction::find_upgrade_site(
+                            *structure_id,
+                            unit_tile_pos,
+                            unit_id,
+                            facing,
+                            &upgrade_query,
+                            &signals,
+                            rng,
+                            &terrain_query,
+                            &terrain_manifest,
+                            unit_faction,
+                            &faction_query,
+                            map_geometry,
+                        ),
+                    }
+                }
+                Goal::Terraform => CurrentAction::find_terraforming_site(
+                    unit_tile_pos,
+                    unit_id,
+                    facing,
+                    &terraforming_query,
+                    &signals,
+                    rng,
+                    &terrain_query,
+                    &terrain_manifest,
                     map_geometry,
                 ),
+            };
+
+            if let Some(status_effects) = maybe_status_effects {
+                action.apply_speed_multiplier(status_effects.speed_multiplier());
+            }
+
+            if unit_collision.enabled {
+                action.avoid_collisions(unit_entity, unit_tile_pos, facing, map_geometry, rng);
             }
         }
     }
 }
 
 /// Exhaustively handles the setup for each planned action
-pub(super) fn start_actions(
-    mut unit_query: Query<&mut CurrentAction>,
+pub(crate) fn start_actions(
+    mut unit_query: Query<(Entity, &mut CurrentAction)>,
     mut workplace_query: Query<&mut WorkersPresent>,
+    mut pickup_reservations: ResMut<PickupReservations>,
+    mut item_transfer_events: EventWriter<ItemTransferStarted>,
 ) {
-    for mut action in unit_query.iter_mut() {
+    for (unit_entity, mut action) in unit_query.iter_mut() {
         if action.just_started {
             if let Some(workplace_entity) = action.action().workplace() {
                 if let Ok(mut workers_present) = workplace_query.get_mut(workplace_entity) {
@@ -205,13 +485,39 @@ pub(super) fn start_actions(
                 }
             }
 
+            let duration = action.timer.duration();
+            match *action.action() {
+                UnitAction::PickUp {
+                    item_id,
+                    output_entity,
+                } => {
+                    pickup_reservations.reserve(output_entity);
+                    item_transfer_events.send(ItemTransferStarted {
+                        item_id,
+                        source: output_entity,
+                        destination: unit_entity,
+                        duration,
+                    });
+                }
+                UnitAction::DropOff {
+                    item_id,
+                    input_entity,
+                } => item_transfer_events.send(ItemTransferStarted {
+                    item_id,
+                    source: unit_entity,
+                    destination: input_entity,
+                    duration,
+                }),
+                _ => (),
+            }
+
             action.just_started = false;
         }
     }
 }
 
 /// Exhaustively handles the cleanup for each planned action
-pub(super) fn finish_actions(
+pub(crate) fn finish_actions(
     mut unit_query: Query<ActionDataQuery>,
     mut inventory_query: Query<
         AnyOf<(
@@ -223,8 +529,23 @@ pub(super) fn finish_actions(
     mut workplace_query: Query<(&CraftingState, &mut WorkersPresent)>,
     // This must be compatible with unit_query
     structure_query: Query<&TilePos, (With<Id<Structure>>, Without<Goal>)>,
-    map_geometry: Res<MapGeometry>,
+    mut health_query: Query<(&Id<Structure>, &mut Health)>,
+    mut upgrade_query: Query<&mut MarkedForUpgrade>,
+    // This must be compatible with unit_query
+    mut terraforming_query: Query<
+        (
+            &MarkedForTerraforming,
+            &TilePos,
+            &mut Height,
+            &mut Id<Terrain>,
+        ),
+        Without<Goal>,
+    >,
+    mut map_geometry: ResMut<MapGeometry>,
+    mut pickup_reservations: ResMut<PickupReservations>,
+    mut item_transferred_events: EventWriter<ItemTransferred>,
     item_manifest: Res<ItemManifest>,
+    structure_manifest: Res<StructureManifest>,
     unit_manifest: Res<UnitManifest>,
     signals: Res<Signals>,
     mut commands: Commands,
@@ -234,15 +555,14 @@ pub(super) fn finish_actions(
     for mut unit in unit_query.iter_mut() {
         if unit.action.finished() {
             // Take workers off of the job once actions complete
-            if let Some(workplace_entity) = unit.action.action().workplace() {
-                if let Ok(workplace) = workplace_query.get_mut(workplace_entity) {
-                    let (.., mut workers_present) = workplace;
-                    // FIXME: this isn't robust to units dying
-                    workers_present.remove_worker();
-                } else {
-                    warn!("Unit was working at an entity that is not a workplace!");
-                }
-            }
+            let maybe_workers_present = unit
+                .action
+                .action()
+                .workplace()
+                .and_then(|workplace_entity| workplace_query.get_mut(workplace_entity).ok())
+                .map(|(_, workers_present)| workers_present.into_inner());
+            unit.action
+                .release_claims(maybe_workers_present, &mut pickup_reservations);
 
             match unit.action.action() {
                 UnitAction::Idle => {
@@ -259,7 +579,7 @@ pub(super) fn finish_actions(
                             // We shouldn't be holding anything yet, but if we are get rid of it
                             Some(held_item_id) => Goal::Store(held_item_id),
                             None => {
-                                let item_count = ItemCount::new(*item_id, 1);
+                                let item_count = UnitInventory::load(*item_id);
                                 let transfer_result = if let Some(mut output_inventory) =
                                     maybe_output_inventory
                                 {
@@ -275,7 +595,18 @@ pub(super) fn finish_actions(
                                 match transfer_result {
                                     Ok(()) => {
                                         unit.unit_inventory.held_item = Some(*item_id);
-                                        if signals.get(SignalType::Pull(*item_id), *unit.tile_pos)
+                                        item_transferred_events.send(ItemTransferred {
+                                            item_id: *item_id,
+                                            tile_pos: *unit.tile_pos,
+                                        });
+                                        if matches!(
+                                            *unit.goal,
+                                            Goal::Repair(..) | Goal::Upgrade(..) | Goal::Eat(..)
+                                        ) {
+                                            // This item was picked up to be used right away, rather than delivered
+                                            unit.goal.clone()
+                                        } else if signals
+                                            .get(SignalType::Pull(*item_id), *unit.tile_pos)
                                             > SignalStrength::ZERO
                                         {
                                             // If we can see any `Pull` signals of the right type, deliver the item.
@@ -285,7 +616,13 @@ pub(super) fn finish_actions(
                                             Goal::Store(*item_id)
                                         }
                                     }
-                                    Err(..) => Goal::Pickup(*item_id),
+                                    Err(..) => {
+                                        // Someone else got there first, so look elsewhere
+                                        if let Some(failed_targets) = &mut unit.failed_targets {
+                                            failed_targets.remember(*output_entity);
+                                        }
+                                        Goal::Pickup(*item_id)
+                                    }
                                 }
                             }
                         }
@@ -306,7 +643,7 @@ pub(super) fn finish_actions(
                             None => Goal::default(),
                             Some(held_item_id) => {
                                 if held_item_id == *item_id {
-                                    let item_count = ItemCount::new(held_item_id, 1);
+                                    let item_count = UnitInventory::load(held_item_id);
                                     let transfer_result =
                                         if let Some(mut input_inventory) = maybe_input_inventory {
                                             input_inventory
@@ -324,9 +661,19 @@ pub(super) fn finish_actions(
                                     match transfer_result {
                                         Ok(()) => {
                                             unit.unit_inventory.held_item = None;
+                                            item_transferred_events.send(ItemTransferred {
+                                                item_id: *item_id,
+                                                tile_pos: *unit.tile_pos,
+                                            });
                                             Goal::default()
                                         }
-                                        Err(..) => Goal::Store(held_item_id),
+                                        Err(..) => {
+                                            // This receptacle filled up before we arrived
+                                            if let Some(failed_targets) = &mut unit.failed_targets {
+                                                failed_targets.remember(*input_entity);
+                                            }
+                                            Goal::Store(held_item_id)
+                                        }
                                     }
                                 } else {
                                     // Somehow we're holding the wrong thing
@@ -347,6 +694,10 @@ pub(super) fn finish_actions(
                     let direction = unit.facing.direction;
                     let target_tile = unit.tile_pos.neighbor(direction);
 
+                    // The target tile was already reserved when this action was chosen
+                    map_geometry
+                        .bypass_change_detection()
+                        .vacate_tile(*unit.tile_pos, unit.entity);
                     *unit.tile_pos = target_tile;
                     unit.transform.translation = target_tile.top_of_tile(&map_geometry);
                 }
@@ -362,6 +713,9 @@ pub(super) fn finish_actions(
                     }
 
                     if !success {
+                        if let Some(failed_targets) = &mut unit.failed_targets {
+                            failed_targets.remember(*structure_entity);
+                        }
                         *unit.goal = Goal::default();
                     }
                 }
@@ -374,6 +728,68 @@ pub(super) fn finish_actions(
                     // Whether we succeeded or failed, pick something else to do
                     *unit.goal = Goal::default();
                 }
+                UnitAction::Repair { structure_entity } => {
+                    let mut still_needs_repair = false;
+
+                    if let Ok((&structure_id, mut health)) = health_query.get_mut(*structure_entity)
+                    {
+                        let materials = &structure_manifest
+                            .get(structure_id)
+                            .construction_strategy
+                            .materials;
+
+                        if let Ok(consumed) =
+                            health.repair(unit.unit_inventory.held_item, materials)
+                        {
+                            if consumed {
+                                unit.unit_inventory.held_item = None;
+                            }
+                        }
+
+                        still_needs_repair = health.needs_repair();
+                    }
+
+                    if !still_needs_repair {
+                        *unit.goal = Goal::default();
+                    }
+                }
+                UnitAction::Upgrade { structure_entity } => {
+                    let mut still_needs_upgrade = false;
+
+                    if let Ok(mut mark) = upgrade_query.get_mut(*structure_entity) {
+                        if let Ok(consumed) = mark.contribute(
+                            unit.unit_inventory.held_item,
+                            MarkedForUpgrade::WORK_PER_ACTION,
+                        ) {
+                            if consumed {
+                                unit.unit_inventory.held_item = None;
+                            }
+                        }
+
+                        still_needs_upgrade = !mark.is_complete();
+                    }
+
+                    if !still_needs_upgrade {
+                        *unit.goal = Goal::default();
+                    }
+                }
+                UnitAction::Terraform { terrain_entity } => {
+                    let mut still_needs_terraforming = false;
+
+                    if let Ok((mark, &terrain_tile_pos, mut height, mut terrain_id)) =
+                        terraforming_query.get_mut(*terrain_entity)
+                    {
+                        // Terraforming can't be done with roots growing into stuff!
+                        commands.despawn_structure(terrain_tile_pos);
+                        mark.apply_work(&mut height, &mut terrain_id);
+
+                        still_needs_terraforming = !mark.is_complete(*height, *terrain_id);
+                    }
+
+                    if !still_needs_terraforming {
+                        *unit.goal = Goal::default();
+                    }
+                }
                 UnitAction::Eat => {
                     if let Some(held_item) = unit.unit_inventory.held_item {
                         let unit_data = unit_manifest.get(*unit.unit_id);
@@ -398,10 +814,77 @@ pub(super) fn finish_actions(
     }
 }
 
+/// Smoothly moves and turns units between tiles, based on how far along their current action is.
+///
+/// This is purely visual: the [`TilePos`] and [`Facing`] of each unit only change once the action completes.
+pub(super) fn interpolate_unit_transforms(
+    mut unit_query: Query<(&mut Transform, &TilePos, &Facing, &CurrentAction), With<Id<Unit>>>,
+    map_geometry: Res<MapGeometry>,
+) {
+    for (mut transform, &tile_pos, facing, action) in unit_query.iter_mut() {
+        let progress = action.timer.percent();
+        let start = tile_pos.top_of_tile(&map_geometry);
+        let start_rotation = facing.rotation(&map_geometry);
+
+        (transform.translation, transform.rotation) = match action.action {
+            UnitAction::MoveForward => {
+                let target_tile = tile_pos.neighbor(facing.direction);
+                let end = target_tile.top_of_tile(&map_geometry);
+                (start.lerp(end, progress), start_rotation)
+            }
+            UnitAction::Spin { rotation_direction } => {
+                let mut target_facing = *facing;
+                match rotation_direction {
+                    RotationDirection::Left => target_facing.rotate_left(),
+                    RotationDirection::Right => target_facing.rotate_right(),
+                }
+                let end_rotation = target_facing.rotation(&map_geometry);
+                (start, start_rotation.slerp(end_rotation, progress))
+            }
+            _ => (start, start_rotation),
+        };
+    }
+}
+
+/// Cuts short the actions of units standing on hazardous tiles, so they can flee right away.
+///
+/// Movement is left alone, as it is already carrying the unit off of the tile.
+/// Interrupted actions give up their workplace slots and pickup reservations, just as if they had finished.
+pub(crate) fn interrupt_hazardous_actions(
+    mut unit_query: Query<(&TilePos, &mut CurrentAction)>,
+    mut workplace_query: Query<&mut WorkersPresent>,
+    mut pickup_reservations: ResMut<PickupReservations>,
+    map_geometry: Res<MapGeometry>,
+) {
+    for (&tile_pos, mut action) in unit_query.iter_mut() {
+        let escaping = matches!(
+            action.action(),
+            UnitAction::MoveForward | UnitAction::Spin { .. }
+        );
+        if !map_geometry.is_hazardous(tile_pos) || action.finished() || escaping {
+            continue;
+        }
+
+        // Claims are only made by `start_actions`, so actions that never started have nothing to give up
+        if !action.just_started {
+            let maybe_workers_present = action
+                .action()
+                .workplace()
+                .and_then(|workplace_entity| workplace_query.get_mut(workplace_entity).ok())
+                .map(Mut::into_inner);
+            action.release_claims(maybe_workers_present, &mut pickup_reservations);
+        }
+
+        *action = CurrentAction::interrupted();
+    }
+}
+
 /// All of the data needed to handle unit actions correctly
 #[derive(WorldQuery)]
 #[world_query(mutable)]
-pub(super) struct ActionDataQuery {
+pub(crate) struct ActionDataQuery {
+    /// The unit's entity
+    entity: Entity,
     /// The [`Id`] of the unit type
     unit_id: &'static Id<Unit>,
     /// The unit's goal
@@ -420,6 +903,8 @@ pub(super) struct ActionDataQuery {
     energy_pool: &'static mut EnergyPool,
     /// How frustrated this unit is about not being able to progress towards its goal
     impatience: &'static mut ImpatiencePool,
+    /// The targets this unit recently failed to interact with
+    failed_targets: Option<&'static mut FailedTargets>,
     /// The direction this unit is facing
     facing: &'static mut Facing,
 }
@@ -454,6 +939,21 @@ pub(super) enum UnitAction {
         /// The structure to work at.
         structure_entity: Entity,
     },
+    /// Restore the health of the provided `structure_entity`, using up the held item
+    Repair {
+        /// The structure to repair.
+        structure_entity: Entity,
+    },
+    /// Bring the provided `structure_entity` one step closer to its upgraded form, using up the held item if it is needed
+    Upgrade {
+        /// The structure to upgrade.
+        structure_entity: Entity,
+    },
+    /// Reshape the provided `terrain_entity`, bringing it one step closer to its zoned target
+    Terraform {
+        /// The terrain to reshape.
+        terrain_entity: Entity,
+    },
     /// Spin left or right.
     Spin {
         /// The direction to turn in.
@@ -486,7 +986,11 @@ impl UnitAction {
     }
 
     /// Pretty formatting for this type
-    pub(crate) fn display(&self, item_manifest: &ItemManifest) -> String {
+    pub(crate) fn display(
+        &self,
+        item_manifest: &ItemManifest,
+        localization: &Localization,
+    ) -> String {
         match self {
             UnitAction::Idle => "Idling".to_string(),
             UnitAction::PickUp {
@@ -494,19 +998,28 @@ impl UnitAction {
                 output_entity,
             } => format!(
                 "Picking up {} from {output_entity:?}",
-                item_manifest.name(*item_id)
+                localization.get(item_manifest.name(*item_id))
             ),
             UnitAction::DropOff {
                 item_id,
                 input_entity,
             } => format!(
                 "Dropping off {} at {input_entity:?}",
-                item_manifest.name(*item_id)
+                localization.get(item_manifest.name(*item_id))
             ),
             UnitAction::Work { structure_entity } => format!("Working at {structure_entity:?}"),
             UnitAction::Demolish { structure_entity } => {
                 format!("Demolishing {structure_entity:?}")
             }
+            UnitAction::Repair { structure_entity } => {
+                format!("Repairing {structure_entity:?}")
+            }
+            UnitAction::Upgrade { structure_entity } => {
+                format!("Upgrading {structure_entity:?}")
+            }
+            UnitAction::Terraform { terrain_entity } => {
+                format!("Terraforming {terrain_entity:?}")
+            }
             UnitAction::Spin { rotation_direction } => format!("Spinning {rotation_direction}"),
             UnitAction::MoveForward => "Moving forward".to_string(),
             UnitAction::Eat => "Eating".to_string(),
@@ -534,13 +1047,17 @@ impl Default for CurrentAction {
 
 impl CurrentAction {
     /// Pretty formatting for this type
-    pub(crate) fn display(&self, item_manifest: &ItemManifest) -> String {
+    pub(crate) fn display(
+        &self,
+        item_manifest: &ItemManifest,
+        localization: &Localization,
+    ) -> String {
         let action = &self.action;
         let time_remaining = self.timer.remaining_secs();
 
         format!(
             "{}\nRemaining: {time_remaining:.2} s.",
-            action.display(item_manifest)
+            action.display(item_manifest, localization)
         )
     }
 
@@ -549,29 +1066,148 @@ impl CurrentAction {
         &self.action
     }
 
+    /// Gives up the workplace slot and pickup reservation claimed when this action started.
+    ///
+    /// This must happen exactly once for every started action, whether it finishes, is interrupted or its unit dies.
+    fn release_claims(
+        &self,
+        maybe_workers_present: Option<&mut WorkersPresent>,
+        pickup_reservations: &mut PickupReservations,
+    ) {
+        if self.action.workplace().is_some() {
+            match maybe_workers_present {
+                Some(workers_present) => workers_present.remove_worker(),
+                None => warn!("Unit was working at an entity that is not a workplace!"),
+            }
+        }
+
+        if let UnitAction::PickUp { output_entity, .. } = self.action {
+            pickup_reservations.release(output_entity);
+        }
+    }
+
+    /// Gives up the claims made by this action, for a unit that is about to be despawned.
+    ///
+    /// Claims are only made by `start_actions`, so actions that never started have nothing to give up.
+    pub(super) fn release_claims_before_despawn(&self, world: &mut World) {
+        if self.just_started {
+            return;
+        }
+
+        world.resource_scope(|world, mut pickup_reservations: Mut<PickupReservations>| {
+            let maybe_workers_present = self
+                .action
+                .workplace()
+                .and_then(|workplace_entity| world.get_mut::<WorkersPresent>(workplace_entity))
+                .map(Mut::into_inner);
+            self.release_claims(maybe_workers_present, &mut pickup_reservations);
+        });
+    }
+
     /// Have we waited long enough to perform this action?
     pub(super) fn finished(&self) -> bool {
         self.timer.finished()
     }
 
     /// Attempt to locate a source of the provided `item_id`.
+    ///
+    /// See [`CurrentAction::choose_item_source`] for how sources are picked between.
+    /// If the only sources are further away, the unit walks over to the nearest one.
     fn find_item(
         item_id: Id<Item>,
         unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
+        interaction_radius: u32,
         facing: &Facing,
         goal: &Goal,
         output_inventory_query: &Query<AnyOf<(&OutputInventory, &StorageInventory)>>,
+        output_direction_query: &Query<(&TilePos, &Facing, &OutputDirection), Without<Goal>>,
+        pickup_reservations: &PickupReservations,
         signals: &Signals,
         rng: &mut ThreadRng,
         terrain_query: &Query<&Id<Terrain>>,
         terrain_manifest: &TerrainManifest,
+        unit_faction: Faction,
+        faction_query: &Query<&Faction>,
+        failed_targets: &mut FailedTargets,
         map_geometry: &MapGeometry,
     ) -> CurrentAction {
-        let neighboring_tiles = unit_tile_pos.all_neighbors(map_geometry);
+        let sources: Vec<(Entity, TilePos)> = CurrentAction::item_sources(
+            item_id,
+            unit_tile_pos,
+            interaction_radius,
+            output_inventory_query,
+            map_geometry,
+        )
+        .into_iter()
+        .filter(|&(source_entity, _)| {
+            !failed_targets.is_avoiding(source_entity)
+                && Faction::of(source_entity, faction_query) == unit_faction
+        })
+        .collect();
+
+        if let Some((output_entity, output_tile_pos)) = CurrentAction::choose_item_source(
+            &sources,
+            unit_tile_pos,
+            output_direction_query,
+            pickup_reservations,
+            rng,
+        ) {
+            CurrentAction::pickup(
+                item_id,
+                output_entity,
+                facing,
+                unit_tile_pos,
+                output_tile_pos,
+            )
+        } else if let Some(approach) = CurrentAction::approach_reachable(
+            unit_tile_pos,
+            unit_id,
+            &sources,
+            failed_targets,
+            facing,
+            terrain_query,
+            terrain_manifest,
+            map_geometry,
+        ) {
+            approach
+        } else if let Some(upstream) = signals.upstream(unit_tile_pos, goal, map_geometry) {
+            CurrentAction::move_or_spin(
+                unit_tile_pos,
+                unit_id,
+                upstream,
+                facing,
+                terrain_query,
+                terrain_manifest,
+                map_geometry,
+            )
+        } else {
+            CurrentAction::idle()
+        }
+    }
+
+    /// Finds all structures, corpses and item piles within `interaction_radius` of the unit that have the provided `item_id`.
+    ///
+    /// Sources are returned in order of increasing distance from the unit,
+    /// along with the tile that they were found on.
+    fn item_sources(
+        item_id: Id<Item>,
+        unit_tile_pos: TilePos,
+        interaction_radius: u32,
+        output_inventory_query: &Query<AnyOf<(&OutputInventory, &StorageInventory)>>,
+        map_geometry: &MapGeometry,
+    ) -> Vec<(Entity, TilePos)> {
+        let nearby_tiles = map_geometry.interesting_tiles_within(unit_tile_pos, interaction_radius);
         let mut sources: Vec<(Entity, TilePos)> = Vec::new();
 
-        for tile_pos in neighboring_tiles {
-            if let Some(structure_entity) = map_geometry.get_structure(tile_pos) {
+        for tile_pos in nearby_tiles {
+            // Corpses and item piles can be emptied just like structures
+            let maybe_source = map_geometry
+                .get_structure(tile_pos)
+                .or_else(|| map_geometry.get_corpse(tile_pos))
+                .or_else(|| map_geometry.get_item_pile(tile_pos));
+
+            if let Some(structure_entity) = maybe_source {
                 if let Ok((maybe_output_inventory, maybe_storage_inventory)) =
                     output_inventory_query.get(structure_entity)
                 {
@@ -590,26 +1226,148 @@ impl CurrentAction {
             }
         }
 
-        if let Some((output_entity, output_tile_pos)) = sources.choose(rng) {
-            CurrentAction::pickup(
-                item_id,
-                *output_entity,
-                facing,
-                unit_tile_pos,
-                *output_tile_pos,
-            )
-        } else if let Some(upstream) = signals.upstream(unit_tile_pos, goal, map_geometry) {
-            CurrentAction::move_or_spin(
+        sources
+    }
+
+    /// Randomly picks one of the `sources` that is adjacent to the unit, if any.
+    ///
+    /// Structures with an [`OutputDirection`] are much more likely to be picked from when the unit is standing in front of their output.
+    /// Structures that other units are already picking up from are less likely to be picked.
+    fn choose_item_source(
+        sources: &[(Entity, TilePos)],
+        unit_tile_pos: TilePos,
+        output_direction_query: &Query<(&TilePos, &Facing, &OutputDirection), Without<Goal>>,
+        pickup_reservations: &PickupReservations,
+        rng: &mut impl Rng,
+    ) -> Option<(Entity, TilePos)> {
+        let adjacent_sources: Vec<(Entity, TilePos)> = sources
+            .iter()
+            .filter(|(_, source_tile_pos)| {
+                unit_tile_pos.unsigned_distance_to(source_tile_pos.hex) == 1
+            })
+            .copied()
+            .collect();
+
+        /// How much more likely units are to pick up from the front of a directional structure.
+        const FRONT_PICKUP_WEIGHT: f32 = 4.;
+
+        let source_weight = |&(output_entity, _): &(Entity, TilePos)| {
+            let attractiveness = pickup_reservations.attractiveness(output_entity);
+
+            let Ok((&structure_tile_pos, &structure_facing, output_direction)) =
+                output_direction_query.get(output_entity)
+            else {
+                return attractiveness;
+            };
+
+            if output_direction.output_tile(structure_tile_pos, structure_facing) == unit_tile_pos {
+                FRONT_PICKUP_WEIGHT * attractiveness
+            } else {
+                attractiveness
+            }
+        };
+
+        adjacent_sources
+            .choose_weighted(rng, source_weight)
+            .ok()
+            .copied()
+    }
+
+    /// Takes a step towards the nearest tile from which the unit could interact with one of the `target_tiles`.
+    ///
+    /// Returns [`None`] if the unit cannot get any closer.
+    fn approach(
+        unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
+        target_tiles: impl IntoIterator<Item = TilePos>,
+        facing: &Facing,
+        terrain_query: &Query<&Id<Terrain>>,
+        terrain_manifest: &TerrainManifest,
+        map_geometry: &MapGeometry,
+    ) -> Option<CurrentAction> {
+        let access_tiles: Vec<TilePos> = target_tiles
+            .into_iter()
+            .flat_map(|target_tile| map_geometry.access_tiles(target_tile))
+            .collect();
+
+        let distance_to_access = |tile_pos: TilePos| {
+            access_tiles
+                .iter()
+                .map(|access_tile| tile_pos.unsigned_distance_to(access_tile.hex))
+                .min()
+        };
+        let current_distance = distance_to_access(unit_tile_pos)?;
+
+        let (next_step, next_distance) = unit_tile_pos
+            .all_neighbors(map_geometry)
+            .into_iter()
+            .filter(|&neighbor| map_geometry.can_walk_between(unit_tile_pos, neighbor))
+            .filter_map(|neighbor| Some((neighbor, distance_to_access(neighbor)?)))
+            .min_by_key(|&(_, distance)| distance)?;
+
+        if next_distance >= current_distance {
+            return None;
+        }
+
+        Some(CurrentAction::move_or_spin(
+            unit_tile_pos,
+            unit_id,
+            next_step,
+            facing,
+            terrain_query,
+            terrain_manifest,
+            map_geometry,
+        ))
+    }
+
+    /// Takes a step towards the nearest of the `targets` that the unit can make progress towards.
+    ///
+    /// Targets that the unit cannot get any closer to are added to its [`FailedTargets`],
+    /// so that it tries the alternatives instead of getting stuck.
+    /// Returns [`None`] if the unit cannot get closer to any of the targets.
+    fn approach_reachable(
+        unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
+        targets: &[(Entity, TilePos)],
+        failed_targets: &mut FailedTargets,
+        facing: &Facing,
+        terrain_query: &Query<&Id<Terrain>>,
+        terrain_manifest: &TerrainManifest,
+        map_geometry: &MapGeometry,
+    ) -> Option<CurrentAction> {
+        let mut sorted_targets = targets.to_vec();
+        sorted_targets
+            .sort_by_key(|(_, tile_pos)| unit_tile_pos.unsigned_distance_to(tile_pos.hex));
+
+        // Large structures are found on several tiles, but should be approached as a whole
+        let mut target_entities: Vec<Entity> = Vec::new();
+        for &(target_entity, _) in &sorted_targets {
+            if !target_entities.contains(&target_entity) {
+                target_entities.push(target_entity);
+            }
+        }
+
+        for target_entity in target_entities {
+            let target_tiles = sorted_targets
+                .iter()
+                .filter(|&&(entity, _)| entity == target_entity)
+                .map(|&(_, tile_pos)| tile_pos);
+
+            match CurrentAction::approach(
                 unit_tile_pos,
-                upstream,
+                unit_id,
+                target_tiles,
                 facing,
                 terrain_query,
                 terrain_manifest,
                 map_geometry,
-            )
-        } else {
-            CurrentAction::idle()
+            ) {
+                Some(approach) => return Some(approach),
+                None => failed_targets.remember(target_entity),
+            }
         }
+
+        None
     }
 
     /// Attempt to locate a place to put an item of type `item_id`.
@@ -617,6 +1375,8 @@ impl CurrentAction {
     fn find_storage(
         item_id: Id<Item>,
         unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
+        interaction_radius: u32,
         facing: &Facing,
         goal: &Goal,
         input_inventory_query: &Query<
@@ -628,12 +1388,15 @@ impl CurrentAction {
         terrain_query: &Query<&Id<Terrain>>,
         terrain_manifest: &TerrainManifest,
         item_manifest: &ItemManifest,
+        unit_faction: Faction,
+        faction_query: &Query<&Faction>,
+        failed_targets: &mut FailedTargets,
         map_geometry: &MapGeometry,
     ) -> CurrentAction {
-        let neighboring_tiles = unit_tile_pos.all_neighbors(map_geometry);
+        let nearby_tiles = map_geometry.interesting_tiles_within(unit_tile_pos, interaction_radius);
         let mut receptacles: Vec<(Entity, TilePos)> = Vec::new();
 
-        for tile_pos in neighboring_tiles {
+        for tile_pos in nearby_tiles {
             // Ghosts
             if let Some(ghost_entity) = map_geometry.get_ghost(tile_pos) {
                 if let Ok((maybe_input_inventory, ..)) = input_inventory_query.get(ghost_entity) {
@@ -655,7 +1418,7 @@ impl CurrentAction {
                             receptacles.push((structure_entity, tile_pos));
                         }
                     } else if let Some(storage_inventory) = maybe_storage_inventory {
-                        if storage_inventory.remaining_space_for_item(item_id, item_manifest) > 0 {
+                        if storage_inventory.can_fit(&UnitInventory::load(item_id), item_manifest) {
                             receptacles.push((structure_entity, tile_pos));
                         }
                     } else {
@@ -665,7 +1428,18 @@ impl CurrentAction {
             }
         }
 
-        if let Some((input_entity, input_tile_pos)) = receptacles.choose(rng) {
+        receptacles.retain(|&(receptacle_entity, _)| {
+            !failed_targets.is_avoiding(receptacle_entity)
+                && Faction::of(receptacle_entity, faction_query) == unit_faction
+        });
+
+        let (adjacent_receptacles, distant_receptacles): (Vec<_>, Vec<_>) = receptacles
+            .into_iter()
+            .partition(|(_, receptacle_tile_pos)| {
+                unit_tile_pos.unsigned_distance_to(receptacle_tile_pos.hex) == 1
+            });
+
+        if let Some((input_entity, input_tile_pos)) = adjacent_receptacles.choose(rng) {
             CurrentAction::dropoff(
                 item_id,
                 *input_entity,
@@ -673,9 +1447,21 @@ impl CurrentAction {
                 unit_tile_pos,
                 *input_tile_pos,
             )
+        } else if let Some(approach) = CurrentAction::approach_reachable(
+            unit_tile_pos,
+            unit_id,
+            &distant_receptacles,
+            failed_targets,
+            facing,
+            terrain_query,
+            terrain_manifest,
+            map_geometry,
+        ) {
+            approach
         } else if let Some(upstream) = signals.upstream(unit_tile_pos, goal, map_geometry) {
             CurrentAction::move_or_spin(
                 unit_tile_pos,
+                unit_id,
                 upstream,
                 facing,
                 terrain_query,
@@ -692,22 +1478,30 @@ impl CurrentAction {
     fn find_delivery(
         item_id: Id<Item>,
         unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
+        interaction_radius: u32,
         facing: &Facing,
         goal: &Goal,
         input_inventory_query: &Query<
             AnyOf<(&InputInventory, &StorageInventory)>,
             Without<MarkedForDemolition>,
         >,
+        contracts: &Contracts,
+        ghosts_only: bool,
         signals: &Signals,
         rng: &mut ThreadRng,
         terrain_query: &Query<&Id<Terrain>>,
         terrain_manifest: &TerrainManifest,
+        item_manifest: &ItemManifest,
+        unit_faction: Faction,
+        faction_query: &Query<&Faction>,
+        failed_targets: &mut FailedTargets,
         map_geometry: &MapGeometry,
     ) -> CurrentAction {
-        let neighboring_tiles = unit_tile_pos.all_neighbors(map_geometry);
+        let nearby_tiles = map_geometry.interesting_tiles_within(unit_tile_pos, interaction_radius);
         let mut receptacles: Vec<(Entity, TilePos)> = Vec::new();
 
-        for tile_pos in neighboring_tiles {
+        for tile_pos in nearby_tiles {
             // Ghosts
             if let Some(ghost_entity) = map_geometry.get_ghost(tile_pos) {
                 if let Ok((maybe_input_inventory, ..)) = input_inventory_query.get(ghost_entity) {
@@ -719,22 +1513,45 @@ impl CurrentAction {
                 }
             }
 
+            if ghosts_only {
+                continue;
+            }
+
             // Structures
             if let Some(structure_entity) = map_geometry.get_structure(tile_pos) {
                 // We deliberately avoid storage locations here, our goal is to complete a delivery!
-                if let Ok((maybe_input_inventory, _maybe_storage_inventory)) =
+                // The exception is storage that the player has contracted this item to.
+                if let Ok((maybe_input_inventory, maybe_storage_inventory)) =
                     input_inventory_query.get(structure_entity)
                 {
                     if let Some(input_inventory) = maybe_input_inventory {
                         if input_inventory.remaining_reserved_space_for_item(item_id) > 0 {
                             receptacles.push((structure_entity, tile_pos));
                         }
+                    } else if let Some(storage_inventory) = maybe_storage_inventory {
+                        if contracts.is_sink_for(structure_entity, item_id)
+                            && storage_inventory
+                                .can_fit(&UnitInventory::load(item_id), item_manifest)
+                        {
+                            receptacles.push((structure_entity, tile_pos));
+                        }
                     }
                 }
             }
         }
 
-        if let Some((input_entity, input_tile_pos)) = receptacles.choose(rng) {
+        receptacles.retain(|&(receptacle_entity, _)| {
+            !failed_targets.is_avoiding(receptacle_entity)
+                && Faction::of(receptacle_entity, faction_query) == unit_faction
+        });
+
+        let (adjacent_receptacles, distant_receptacles): (Vec<_>, Vec<_>) = receptacles
+            .into_iter()
+            .partition(|(_, receptacle_tile_pos)| {
+                unit_tile_pos.unsigned_distance_to(receptacle_tile_pos.hex) == 1
+            });
+
+        if let Some((input_entity, input_tile_pos)) = adjacent_receptacles.choose(rng) {
             CurrentAction::dropoff(
                 item_id,
                 *input_entity,
@@ -742,9 +1559,21 @@ impl CurrentAction {
                 unit_tile_pos,
                 *input_tile_pos,
             )
+        } else if let Some(approach) = CurrentAction::approach_reachable(
+            unit_tile_pos,
+            unit_id,
+            &distant_receptacles,
+            failed_targets,
+            facing,
+            terrain_query,
+            terrain_manifest,
+            map_geometry,
+        ) {
+            approach
         } else if let Some(upstream) = signals.upstream(unit_tile_pos, goal, map_geometry) {
             CurrentAction::move_or_spin(
                 unit_tile_pos,
+                unit_id,
                 upstream,
                 facing,
                 terrain_query,
@@ -760,31 +1589,42 @@ impl CurrentAction {
     fn find_workplace(
         structure_id: Id<Structure>,
         unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
         facing: &Facing,
         workplace_query: &WorkplaceQuery,
+        ghosts_only: bool,
         signals: &Signals,
         rng: &mut ThreadRng,
         terrain_query: &Query<&Id<Terrain>>,
         terrain_manifest: &TerrainManifest,
+        unit_faction: Faction,
+        faction_query: &Query<&Faction>,
+        failed_targets: &FailedTargets,
         map_geometry: &MapGeometry,
     ) -> CurrentAction {
+        let needs_work = |tile_pos: TilePos| {
+            workplace_query
+                .needs_work(tile_pos, structure_id, map_geometry)
+                .filter(|&workplace| {
+                    !ghosts_only || map_geometry.get_ghost(tile_pos) == Some(workplace)
+                })
+                .filter(|&workplace| !failed_targets.is_avoiding(workplace))
+                .filter(|&workplace| Faction::of(workplace, faction_query) == unit_faction)
+        };
+
         let ahead = unit_tile_pos.neighbor(facing.direction);
-        if let Some(workplace) = workplace_query.needs_work(ahead, structure_id, map_geometry) {
+        if let Some(workplace) = needs_work(ahead) {
             CurrentAction::work(workplace)
         // Let units work even if they're standing on the structure
         // This is particularly relevant in the case of ghosts, where it's easy enough to end up on top of the structure trying to work on it
-        } else if let Some(workplace) =
-            workplace_query.needs_work(unit_tile_pos, structure_id, map_geometry)
-        {
+        } else if let Some(workplace) = needs_work(unit_tile_pos) {
             CurrentAction::work(workplace)
         } else {
-            let neighboring_tiles = unit_tile_pos.all_neighbors(map_geometry);
+            let neighboring_tiles = map_geometry.interesting_neighbors(unit_tile_pos);
             let mut workplaces: Vec<(Entity, TilePos)> = Vec::new();
 
             for neighbor in neighboring_tiles {
-                if let Some(workplace) =
-                    workplace_query.needs_work(neighbor, structure_id, map_geometry)
-                {
+                if let Some(workplace) = needs_work(neighbor) {
                     workplaces.push((workplace, neighbor));
                 }
             }
@@ -792,6 +1632,7 @@ impl CurrentAction {
             if let Some(chosen_workplace) = workplaces.choose(rng) {
                 CurrentAction::move_or_spin(
                     unit_tile_pos,
+                    unit_id,
                     chosen_workplace.1,
                     facing,
                     terrain_query,
@@ -803,6 +1644,7 @@ impl CurrentAction {
             {
                 CurrentAction::move_or_spin(
                     unit_tile_pos,
+                    unit_id,
                     upstream,
                     facing,
                     terrain_query,
@@ -819,31 +1661,34 @@ impl CurrentAction {
     fn find_demolition_site(
         structure_id: Id<Structure>,
         unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
         facing: &Facing,
         demolition_query: &DemolitionQuery,
         signals: &Signals,
         rng: &mut ThreadRng,
         terrain_query: &Query<&Id<Terrain>>,
         terrain_manifest: &TerrainManifest,
+        unit_faction: Faction,
+        faction_query: &Query<&Faction>,
         map_geometry: &MapGeometry,
     ) -> CurrentAction {
+        let needs_demolition = |tile_pos: TilePos| {
+            demolition_query
+                .needs_demolition(tile_pos, structure_id, map_geometry)
+                .filter(|&site| Faction::of(site, faction_query) == unit_faction)
+        };
+
         let ahead = unit_tile_pos.neighbor(facing.direction);
-        if let Some(workplace) =
-            demolition_query.needs_demolition(ahead, structure_id, map_geometry)
-        {
+        if let Some(workplace) = needs_demolition(ahead) {
             CurrentAction::demolish(workplace)
-        } else if let Some(workplace) =
-            demolition_query.needs_demolition(unit_tile_pos, structure_id, map_geometry)
-        {
+        } else if let Some(workplace) = needs_demolition(unit_tile_pos) {
             CurrentAction::demolish(workplace)
         } else {
-            let neighboring_tiles = unit_tile_pos.all_neighbors(map_geometry);
+            let neighboring_tiles = map_geometry.interesting_neighbors(unit_tile_pos);
             let mut demo_sites: Vec<(Entity, TilePos)> = Vec::new();
 
             for neighbor in neighboring_tiles {
-                if let Some(demo_site) =
-                    demolition_query.needs_demolition(neighbor, structure_id, map_geometry)
-                {
+                if let Some(demo_site) = needs_demolition(neighbor) {
                     demo_sites.push((demo_site, neighbor));
                 }
             }
@@ -851,6 +1696,7 @@ impl CurrentAction {
             if let Some(chosen_demo_site) = demo_sites.choose(rng) {
                 CurrentAction::move_or_spin(
                     unit_tile_pos,
+                    unit_id,
                     chosen_demo_site.1,
                     facing,
                     terrain_query,
@@ -862,6 +1708,189 @@ impl CurrentAction {
             {
                 CurrentAction::move_or_spin(
                     unit_tile_pos,
+                    unit_id,
+                    upstream,
+                    facing,
+                    terrain_query,
+                    terrain_manifest,
+                    map_geometry,
+                )
+            } else {
+                CurrentAction::idle()
+            }
+        }
+    }
+
+    /// Attempt to find a structure of type `structure_id` that needs to be repaired
+    fn find_repair_site(
+        structure_id: Id<Structure>,
+        unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
+        facing: &Facing,
+        repair_query: &RepairQuery,
+        signals: &Signals,
+        rng: &mut ThreadRng,
+        terrain_query: &Query<&Id<Terrain>>,
+        terrain_manifest: &TerrainManifest,
+        unit_faction: Faction,
+        faction_query: &Query<&Faction>,
+        map_geometry: &MapGeometry,
+    ) -> CurrentAction {
+        let needs_repair = |tile_pos: TilePos| {
+            repair_query
+                .needs_repair(tile_pos, structure_id, map_geometry)
+                .filter(|&site| Faction::of(site, faction_query) == unit_faction)
+        };
+
+        let ahead = unit_tile_pos.neighbor(facing.direction);
+        if let Some(repair_site) = needs_repair(ahead) {
+            CurrentAction::repair(repair_site)
+        } else if let Some(repair_site) = needs_repair(unit_tile_pos) {
+            CurrentAction::repair(repair_site)
+        } else {
+            let neighboring_tiles = map_geometry.interesting_neighbors(unit_tile_pos);
+            let mut repair_sites: Vec<(Entity, TilePos)> = Vec::new();
+
+            for neighbor in neighboring_tiles {
+                if let Some(repair_site) = needs_repair(neighbor) {
+                    repair_sites.push((repair_site, neighbor));
+                }
+            }
+
+            if let Some(chosen_repair_site) = repair_sites.choose(rng) {
+                CurrentAction::move_or_spin(
+                    unit_tile_pos,
+                    unit_id,
+                    chosen_repair_site.1,
+                    facing,
+                    terrain_query,
+                    terrain_manifest,
+                    map_geometry,
+                )
+            } else if let Some(upstream) =
+                signals.upstream(unit_tile_pos, &Goal::Repair(structure_id), map_geometry)
+            {
+                CurrentAction::move_or_spin(
+                    unit_tile_pos,
+                    unit_id,
+                    upstream,
+                    facing,
+                    terrain_query,
+                    terrain_manifest,
+                    map_geometry,
+                )
+            } else {
+                CurrentAction::idle()
+            }
+        }
+    }
+
+    /// Attempt to find a structure of type `structure_id` that has been marked for upgrade
+    fn find_upgrade_site(
+        structure_id: Id<Structure>,
+        unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
+        facing: &Facing,
+        upgrade_query: &UpgradeQuery,
+        signals: &Signals,
+        rng: &mut ThreadRng,
+        terrain_query: &Query<&Id<Terrain>>,
+        terrain_manifest: &TerrainManifest,
+        unit_faction: Faction,
+        faction_query: &Query<&Faction>,
+        map_geometry: &MapGeometry,
+    ) -> CurrentAction {
+        let needs_upgrade = |tile_pos: TilePos| {
+            upgrade_query
+                .needs_upgrade(tile_pos, structure_id, map_geometry)
+                .filter(|&site| Faction::of(site, faction_query) == unit_faction)
+        };
+
+        let ahead = unit_tile_pos.neighbor(facing.direction);
+        if let Some(upgrade_site) = needs_upgrade(ahead) {
+            CurrentAction::upgrade(upgrade_site)
+        } else if let Some(upgrade_site) = needs_upgrade(unit_tile_pos) {
+            CurrentAction::upgrade(upgrade_site)
+        } else {
+            let neighboring_tiles = map_geometry.interesting_neighbors(unit_tile_pos);
+            let mut upgrade_sites: Vec<(Entity, TilePos)> = Vec::new();
+
+            for neighbor in neighboring_tiles {
+                if let Some(upgrade_site) = needs_upgrade(neighbor) {
+                    upgrade_sites.push((upgrade_site, neighbor));
+                }
+            }
+
+            if let Some(chosen_upgrade_site) = upgrade_sites.choose(rng) {
+                CurrentAction::move_or_spin(
+                    unit_tile_pos,
+                    unit_id,
+                    chosen_upgrade_site.1,
+                    facing,
+                    terrain_query,
+                    terrain_manifest,
+                    map_geometry,
+                )
+            } else if let Some(upstream) =
+                signals.upstream(unit_tile_pos, &Goal::Upgrade(structure_id), map_geometry)
+            {
+                CurrentAction::move_or_spin(
+                    unit_tile_pos,
+                    unit_id,
+                    upstream,
+                    facing,
+                    terrain_query,
+                    terrain_manifest,
+                    map_geometry,
+                )
+            } else {
+                CurrentAction::idle()
+            }
+        }
+    }
+
+    /// Attempt to find a tile that has been zoned for terraforming
+    ///
+    /// Units never reshape the tile that they are standing on.
+    fn find_terraforming_site(
+        unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
+        facing: &Facing,
+        terraforming_query: &Query<&MarkedForTerraforming>,
+        signals: &Signals,
+        rng: &mut ThreadRng,
+        terrain_query: &Query<&Id<Terrain>>,
+        terrain_manifest: &TerrainManifest,
+        map_geometry: &MapGeometry,
+    ) -> CurrentAction {
+        let needs_terraforming = |tile_pos: TilePos| {
+            map_geometry
+                .get_terrain(tile_pos)
+                .filter(|&terrain_entity| terraforming_query.contains(terrain_entity))
+        };
+
+        let ahead = unit_tile_pos.neighbor(facing.direction);
+        if let Some(terrain_entity) = needs_terraforming(ahead) {
+            CurrentAction::terraform(terrain_entity)
+        } else {
+            let mut terraforming_sites: Vec<TilePos> = Vec::new();
+
+            for neighbor in unit_tile_pos.all_neighbors(map_geometry) {
+                if needs_terraforming(neighbor).is_some() {
+                    terraforming_sites.push(neighbor);
+                }
+            }
+
+            if let Some(&chosen_site) = terraforming_sites.choose(rng) {
+                // Turn to face the site, so that it can be worked on next time
+                let direction = unit_tile_pos.direction_to(chosen_site.hex);
+                CurrentAction::spin_towards(facing, direction)
+            } else if let Some(upstream) =
+                signals.upstream(unit_tile_pos, &Goal::Terraform, map_geometry)
+            {
+                CurrentAction::move_or_spin(
+                    unit_tile_pos,
+                    unit_id,
                     upstream,
                     facing,
                     terrain_query,
@@ -911,9 +1940,20 @@ impl CurrentAction {
         CurrentAction::spin(rotation_direction)
     }
 
+    /// Speeds up (or slows down) this action based on the unit's [`StatusEffects`].
+    ///
+    /// Only movement and work are affected.
+    pub(super) fn apply_speed_multiplier(&mut self, speed_multiplier: f32) {
+        if let UnitAction::MoveForward | UnitAction::Work { .. } = self.action {
+            let duration = self.timer.duration().as_secs_f32() / speed_multiplier;
+            self.timer = Timer::from_seconds(duration, TimerMode::Once);
+        }
+    }
+
     /// Move toward the tile this unit is facing if able
     pub(super) fn move_forward(
         unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
         facing: &Facing,
         map_geometry: &MapGeometry,
         terrain_query: &Query<&Id<Terrain>>,
@@ -921,14 +1961,22 @@ impl CurrentAction {
     ) -> Self {
         /// The time in seconds that it takes a standard unit to walk to an adjacent tile.
         const BASE_WALKING_DURATION: f32 = 0.5;
+        /// The additional time in seconds that it takes to climb up or down each step of height.
+        const CLIMBING_DURATION_PER_STEP: f32 = 0.25;
 
         let target_tile = unit_tile_pos.neighbor(facing.direction);
         let entity_standing_on = map_geometry.get_terrain(unit_tile_pos).unwrap();
         let terrain_standing_on = terrain_query.get(entity_standing_on).unwrap();
-        let walking_speed = terrain_manifest.get(*terrain_standing_on).walking_speed;
-        let walking_duration = BASE_WALKING_DURATION / walking_speed;
-
-        if map_geometry.is_passable(target_tile) {
+        let walking_speed = terrain_manifest
+            .get(*terrain_standing_on)
+            .walking_speed
+            .get(unit_id);
+        let height_difference = map_geometry.walking_height_difference(unit_tile_pos, target_tile);
+        let walking_duration = (BASE_WALKING_DURATION
+            + CLIMBING_DURATION_PER_STEP * height_difference as f32)
+            / walking_speed;
+
+        if map_geometry.can_walk_between(unit_tile_pos, target_tile) {
             CurrentAction {
                 action: UnitAction::MoveForward,
                 timer: Timer::from_seconds(walking_duration, TimerMode::Once),
@@ -939,9 +1987,45 @@ impl CurrentAction {
         }
     }
 
+    /// Step off of a hazardous tile onto an adjacent safe tile, preferring the tile straight ahead.
+    ///
+    /// Returns [`None`] if every neighboring tile is blocked or hazardous.
+    pub(super) fn flee(
+        unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
+        facing: &Facing,
+        terrain_query: &Query<&Id<Terrain>>,
+        terrain_manifest: &TerrainManifest,
+        map_geometry: &MapGeometry,
+    ) -> Option<Self> {
+        let ahead = unit_tile_pos.neighbor(facing.direction);
+        let safe_tiles: Vec<TilePos> = unit_tile_pos
+            .all_neighbors(map_geometry)
+            .into_iter()
+            .filter(|&neighbor| map_geometry.can_walk_between(unit_tile_pos, neighbor))
+            .collect();
+
+        let target_tile = if safe_tiles.contains(&ahead) {
+            ahead
+        } else {
+            *safe_tiles.first()?
+        };
+
+        Some(CurrentAction::move_or_spin(
+            unit_tile_pos,
+            unit_id,
+            target_tile,
+            facing,
+            terrain_query,
+            terrain_manifest,
+            map_geometry,
+        ))
+    }
+
     /// Attempt to move toward the `target_tile_pos`.
     pub(super) fn move_or_spin(
         unit_tile_pos: TilePos,
+        unit_id: Id<Unit>,
         target_tile_pos: TilePos,
         facing: &Facing,
         terrain_query: &Query<&Id<Terrain>>,
@@ -953,6 +2037,7 @@ impl CurrentAction {
         if required_direction == facing.direction {
             CurrentAction::move_forward(
                 unit_tile_pos,
+                unit_id,
                 facing,
                 map_geometry,
                 terrain_query,
@@ -963,6 +2048,35 @@ impl CurrentAction {
         }
     }
 
+    /// Stops this unit from walking into other units.
+    ///
+    /// Units that are about to move reserve their target tile, so no other unit can claim it this tick.
+    /// Units blocked by another unit will sometimes turn away instead of waiting,
+    /// which breaks up deadlocks where two units are trying to walk through each other.
+    fn avoid_collisions(
+        &mut self,
+        unit_entity: Entity,
+        unit_tile_pos: TilePos,
+        facing: &Facing,
+        map_geometry: &mut MapGeometry,
+        rng: &mut ThreadRng,
+    ) {
+        /// The chance that a blocked unit gives way, rather than waiting for the tile to clear.
+        const YIELD_PROBABILITY: f64 = 0.5;
+
+        let target_tile = unit_tile_pos.neighbor(facing.direction);
+        match self.action {
+            UnitAction::MoveForward => map_geometry.reserve_tile(target_tile, unit_entity),
+            UnitAction::Idle
+                if map_geometry.is_occupied_by_unit(target_tile)
+                    && rng.gen_bool(YIELD_PROBABILITY) =>
+            {
+                *self = CurrentAction::random_spin(rng);
+            }
+            _ => (),
+        }
+    }
+
     /// Wait, as there is nothing to be done.
     pub(super) fn idle() -> Self {
         CurrentAction {
@@ -972,6 +2086,18 @@ impl CurrentAction {
         }
     }
 
+    /// An idle action that has already finished, so that a new action is chosen right away.
+    fn interrupted() -> Self {
+        let mut timer = Timer::new(Duration::ZERO, TimerMode::Once);
+        timer.tick(Duration::ZERO);
+
+        CurrentAction {
+            action: UnitAction::Idle,
+            timer,
+            just_started: false,
+        }
+    }
+
     /// Picks up the `item_id` at the `output_entity`.
     pub(super) fn pickup(
         item_id: Id<Item>,
@@ -1047,6 +2173,33 @@ impl CurrentAction {
         }
     }
 
+    /// Repair the specified structure
+    pub(super) fn repair(structure_entity: Entity) -> Self {
+        CurrentAction {
+            action: UnitAction::Repair { structure_entity },
+            timer: Timer::from_seconds(1.0, TimerMode::Once),
+            just_started: true,
+        }
+    }
+
+    /// Upgrade the specified structure
+    pub(super) fn upgrade(structure_entity: Entity) -> Self {
+        CurrentAction {
+            action: UnitAction::Upgrade { structure_entity },
+            timer: Timer::new(MarkedForUpgrade::WORK_PER_ACTION, TimerMode::Once),
+            just_started: true,
+        }
+    }
+
+    /// Terraform the specified tile
+    pub(super) fn terraform(terrain_entity: Entity) -> Self {
+        CurrentAction {
+            action: UnitAction::Terraform { terrain_entity },
+            timer: Timer::from_seconds(1.0, TimerMode::Once),
+            just_started: true,
+        }
+    }
+
     /// Eats one of the currently held item.
     pub(super) fn abandon() -> Self {
         CurrentAction {
@@ -1056,3 +2209,870 @@ impl CurrentAction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::Duration;
+
+    use super::*;
+    use crate::{
+        items::{inventory::ReservedFor, ItemCount},
+        units::status_effects::StatusEffect,
+    };
+
+    /// A unit moving forward, which takes half a second at normal speed.
+    fn move_forward() -> CurrentAction {
+        CurrentAction {
+            action: UnitAction::MoveForward,
+            timer: Timer::from_seconds(0.5, TimerMode::Once),
+            just_started: true,
+        }
+    }
+
+    #[test]
+    fn speed_effects_shorten_movement_until_expired() {
+        let mut status_effects = StatusEffects::default();
+        status_effects.apply(StatusEffect::new("hasted", 2.0, Duration::from_secs(1)));
+
+        let mut action = move_forward();
+        action.apply_speed_multiplier(status_effects.speed_multiplier());
+        assert_eq!(action.timer.duration(), Duration::from_secs_f32(0.25));
+
+        status_effects.tick(Duration::from_secs(1));
+
+        let mut action = move_forward();
+        action.apply_speed_multiplier(status_effects.speed_multiplier());
+        assert_eq!(action.timer.duration(), Duration::from_secs_f32(0.5));
+    }
+
+    #[test]
+    fn unit_types_can_walk_at_different_speeds_on_the_same_terrain() {
+        use crate::{
+            simulation::geometry::Height,
+            terrain::terrain_manifest::{TerrainData, WalkingSpeed},
+        };
+        use bevy::{ecs::system::SystemState, utils::HashMap};
+
+        let mut terrain_manifest = TerrainManifest::new();
+        terrain_manifest.insert(
+            "muddy",
+            TerrainData {
+                walking_speed: WalkingSpeed::PerUnit {
+                    default: 0.5,
+                    units: HashMap::from_iter([(Id::from_name("beetle"), 2.0)]),
+                },
+            },
+        );
+
+        let mut world = World::new();
+        let mut map_geometry = MapGeometry::new(1);
+        for tile_pos in [TilePos::ZERO, TilePos::ZERO.neighbor(hexx::Direction::Top)] {
+            let terrain_entity = world.spawn(Id::<Terrain>::from_name("muddy")).id();
+            map_geometry.add_terrain(tile_pos, terrain_entity);
+            map_geometry.update_height(tile_pos, Height(0));
+        }
+
+        let mut system_state: SystemState<Query<&Id<Terrain>>> = SystemState::new(&mut world);
+        let terrain_query = system_state.get(&world);
+        let move_forward_as = |unit_name: &str| {
+            CurrentAction::move_forward(
+                TilePos::ZERO,
+                Id::from_name(unit_name),
+                &Facing::default(),
+                &map_geometry,
+                &terrain_query,
+                &terrain_manifest,
+            )
+        };
+
+        // Unlisted unit types fall back to the default speed
+        let ant_action = move_forward_as("ant");
+        let beetle_action = move_forward_as("beetle");
+        assert!(matches!(ant_action.action, UnitAction::MoveForward));
+        assert_eq!(ant_action.timer.duration(), Duration::from_secs_f32(1.0));
+        assert_eq!(
+            beetle_action.timer.duration(),
+            Duration::from_secs_f32(0.25)
+        );
+    }
+
+    #[test]
+    fn speed_effects_do_not_change_spinning() {
+        let mut action = CurrentAction::spin(RotationDirection::Left);
+        action.apply_speed_multiplier(2.0);
+        assert_eq!(action.timer.duration(), Duration::from_secs_f32(0.1));
+    }
+
+    #[test]
+    fn pausing_freezes_action_timers() {
+        use crate::simulation::{
+            time::{consume_simulation_steps, simulation_is_running, QueuedSteps, SimulationSpeed},
+            SimulationSet,
+        };
+
+        let period = Duration::from_millis(100);
+
+        let mut app = App::new();
+        app.insert_resource(FixedTime::new(period))
+            .init_resource::<SimulationSpeed>()
+            .init_resource::<QueuedSteps>()
+            .configure_set(SimulationSet.run_if(simulation_is_running))
+            .add_system(advance_action_timer.in_set(SimulationSet))
+            .add_system(consume_simulation_steps.after(SimulationSet));
+
+        let unit_entity = app.world.spawn(move_forward()).id();
+        let elapsed = |app: &App| {
+            app.world
+                .get::<CurrentAction>(unit_entity)
+                .unwrap()
+                .timer
+                .elapsed()
+        };
+
+        app.update();
+        assert_eq!(elapsed(&app), period);
+
+        *app.world.resource_mut::<SimulationSpeed>() = SimulationSpeed::Paused;
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(elapsed(&app), period);
+
+        // Stepping while paused advances the simulation exactly once
+        app.world.resource_mut::<QueuedSteps>().0 += 1;
+        app.update();
+        app.update();
+        assert_eq!(elapsed(&app), period * 2);
+
+        *app.world.resource_mut::<SimulationSpeed>() = SimulationSpeed::Normal;
+        app.update();
+        assert_eq!(elapsed(&app), period * 3);
+    }
+
+    #[test]
+    fn pickups_spread_out_across_equal_sources() {
+        use crate::{items::item_manifest::ItemData, structures::construction::Footprint};
+        use bevy::ecs::system::SystemState;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        /// The number of units that start picking up items.
+        const N_UNITS: u8 = 12;
+
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(100));
+
+        let mut world = World::new();
+        let mut map_geometry = MapGeometry::new(1);
+        let sources: Vec<Entity> = [TilePos::new(1, 0), TilePos::new(-1, 0)]
+            .into_iter()
+            .map(|tile_pos| {
+                let mut storage_inventory = StorageInventory::new(1, ReservedFor::Any);
+                storage_inventory
+                    .add_item_all_or_nothing(&ItemCount::new(leuco_chunk, 100), &item_manifest)
+                    .unwrap();
+                let source = world.spawn((storage_inventory, tile_pos)).id();
+                map_geometry.add_structure(
+                    tile_pos,
+                    &Footprint::single(),
+                    Id::from_name("storage"),
+                    source,
+                );
+                source
+            })
+            .collect();
+
+        let mut system_state: SystemState<(
+            Query<AnyOf<(&OutputInventory, &StorageInventory)>>,
+            Query<(&TilePos, &Facing, &OutputDirection), Without<Goal>>,
+        )> = SystemState::new(&mut world);
+        let (output_inventory_query, output_direction_query) = system_state.get(&world);
+
+        let mut pickup_reservations = PickupReservations::default();
+        let rng = &mut StdRng::seed_from_u64(0);
+
+        let item_sources = CurrentAction::item_sources(
+            leuco_chunk,
+            TilePos::ZERO,
+            1,
+            &output_inventory_query,
+            &map_geometry,
+        );
+
+        // Each unit starts picking up from its chosen source, but none of them finish
+        for _ in 0..N_UNITS {
+            let (source, _) = CurrentAction::choose_item_source(
+                &item_sources,
+                TilePos::ZERO,
+                &output_direction_query,
+                &pickup_reservations,
+                rng,
+            )
+            .unwrap();
+            pickup_reservations.reserve(source);
+        }
+
+        // Without reservations, one of the sources would get 2 units or fewer about 4% of the time
+        for &source in &sources {
+            let n_reserved = pickup_reservations.get(source);
+            assert!(
+                n_reserved >= 3,
+                "Only {n_reserved} of {N_UNITS} units chose {source:?}"
+            );
+        }
+
+        for &source in &sources {
+            for _ in 0..pickup_reservations.get(source) {
+                pickup_reservations.release(source);
+            }
+            assert_eq!(pickup_reservations.attractiveness(source), 1.);
+        }
+    }
+
+    #[test]
+    fn units_walk_over_to_pick_up_from_large_structures() {
+        use crate::{
+            items::item_manifest::ItemData,
+            structures::construction::Footprint,
+            units::{
+                test_fixture::{spawn_unit, UnitTestApp},
+                unit_manifest::UnitData,
+            },
+        };
+
+        let ant = Id::<Unit>::from_name("ant");
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let mut app = UnitTestApp::new(4)
+            .with_unit(
+                "ant",
+                UnitData {
+                    interaction_radius: 2,
+                    ..Default::default()
+                },
+            )
+            .with_items(item_manifest)
+            .build();
+        app.add_systems(
+            (
+                advance_action_timer,
+                start_actions,
+                finish_actions,
+                choose_actions,
+            )
+                .chain(),
+        );
+
+        let mut storage_inventory = StorageInventory::new(1, ReservedFor::Any);
+        storage_inventory
+            .add_item_all_or_nothing(
+                &ItemCount::new(leuco_chunk, 5),
+                app.world.resource::<ItemManifest>(),
+            )
+            .unwrap();
+        let storage_entity = app.world.spawn((storage_inventory, TilePos::ZERO)).id();
+        app.world.resource_mut::<MapGeometry>().add_structure(
+            TilePos::ZERO,
+            &Footprint::hexagon(1),
+            Id::from_name("storage"),
+            storage_entity,
+        );
+
+        // Two tiles away from the edge of the structure
+        let unit_entity = spawn_unit(&mut app, TilePos::new(3, 0), ant, Goal::Pickup(leuco_chunk));
+
+        for _ in 0..100 {
+            app.update();
+
+            if app
+                .world
+                .get::<UnitInventory>(unit_entity)
+                .unwrap()
+                .held_item
+                == Some(leuco_chunk)
+            {
+                break;
+            }
+        }
+
+        assert_eq!(
+            app.world
+                .get::<UnitInventory>(unit_entity)
+                .unwrap()
+                .held_item,
+            Some(leuco_chunk)
+        );
+        let unit_tile_pos = *app.world.get::<TilePos>(unit_entity).unwrap();
+        assert_eq!(unit_tile_pos.unsigned_distance_to(hexx::Hex::ZERO), 2);
+        assert_eq!(
+            app.world
+                .get::<StorageInventory>(storage_entity)
+                .unwrap()
+                .item_count(leuco_chunk),
+            4
+        );
+    }
+
+    #[test]
+    fn units_try_another_source_when_one_is_unreachable() {
+        use crate::{
+            items::item_manifest::ItemData,
+            simulation::geometry::Height,
+            structures::construction::Footprint,
+            units::{
+                test_fixture::{spawn_unit, UnitTestApp},
+                unit_manifest::UnitData,
+            },
+        };
+
+        let ant = Id::<Unit>::from_name("ant");
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let mut app = UnitTestApp::new(4)
+            .with_unit(
+                "ant",
+                UnitData {
+                    interaction_radius: 3,
+                    ..Default::default()
+                },
+            )
+            .with_items(item_manifest)
+            .build();
+        app.add_systems(
+            (
+                advance_action_timer,
+                start_actions,
+                finish_actions,
+                choose_actions,
+            )
+                .chain(),
+        );
+
+        // The nearest source is walled in by cliffs, while a more distant one is out in the open
+        let walled_in_tile = TilePos::new(2, 0);
+        let open_tile = TilePos::new(-3, 0);
+
+        let mut map_geometry = app.world.resource_mut::<MapGeometry>();
+        for hex in hexx::shapes::hexagon(hexx::Hex::ZERO, 4) {
+            let tile_pos = TilePos { hex };
+            if tile_pos.unsigned_distance_to(walled_in_tile.hex) == 1 {
+                map_geometry.update_height(tile_pos, Height(5));
+            }
+        }
+
+        let mut spawn_source = |tile_pos: TilePos| {
+            let mut storage_inventory = StorageInventory::new(1, ReservedFor::Any);
+            storage_inventory
+                .add_item_all_or_nothing(
+                    &ItemCount::new(leuco_chunk, 5),
+                    app.world.resource::<ItemManifest>(),
+                )
+                .unwrap();
+            let storage_entity = app.world.spawn((storage_inventory, tile_pos)).id();
+            app.world.resource_mut::<MapGeometry>().add_structure(
+                tile_pos,
+                &Footprint::single(),
+                Id::from_name("storage"),
+                storage_entity,
+            );
+            storage_entity
+        };
+
+        let walled_in_source = spawn_source(walled_in_tile);
+        let open_source = spawn_source(open_tile);
+
+        let unit_entity = spawn_unit(&mut app, TilePos::ZERO, ant, Goal::Pickup(leuco_chunk));
+
+        for _ in 0..100 {
+            app.update();
+
+            if app
+                .world
+                .get::<UnitInventory>(unit_entity)
+                .unwrap()
+                .held_item
+                .is_some()
+            {
+                break;
+            }
+        }
+
+        assert_eq!(
+            app.world
+                .get::<UnitInventory>(unit_entity)
+                .unwrap()
+                .held_item,
+            Some(leuco_chunk)
+        );
+        assert!(app
+            .world
+            .get::<FailedTargets>(unit_entity)
+            .unwrap()
+            .is_avoiding(walled_in_source));
+
+        let item_count = |app: &App, source| {
+            app.world
+                .get::<StorageInventory>(source)
+                .unwrap()
+                .item_count(leuco_chunk)
+        };
+        assert_eq!(item_count(&app, walled_in_source), 5);
+        assert_eq!(item_count(&app, open_source), 4);
+    }
+
+    #[test]
+    fn units_flee_and_avoid_hazardous_tiles() {
+        use crate::units::test_fixture::{spawn_unit, UnitTestApp};
+
+        let ant = Id::<Unit>::from_name("ant");
+
+        let mut app = UnitTestApp::new(3).build();
+        app.add_systems(
+            (
+                advance_action_timer,
+                start_actions,
+                finish_actions,
+                interrupt_hazardous_actions,
+                choose_actions,
+            )
+                .chain(),
+        );
+
+        // The unit starts on a hazard, facing another one
+        let facing = Facing::default();
+        let hazards = [TilePos::ZERO, TilePos::ZERO.neighbor(facing.direction)];
+        let mut map_geometry = app.world.resource_mut::<MapGeometry>();
+        for hazard in hazards {
+            map_geometry.set_hazard(hazard, true);
+        }
+
+        let unit_entity = spawn_unit(&mut app, TilePos::ZERO, ant, Goal::default());
+
+        let mut escaped = false;
+        for _ in 0..200 {
+            app.update();
+
+            let unit_tile_pos = *app.world.get::<TilePos>(unit_entity).unwrap();
+            if unit_tile_pos != TilePos::ZERO {
+                escaped = true;
+            }
+
+            // Once off of the hazard, the unit never wanders back onto one
+            if escaped {
+                assert!(!hazards.contains(&unit_tile_pos));
+            }
+        }
+
+        assert!(escaped);
+    }
+
+    #[test]
+    fn units_only_work_at_structures_of_their_own_faction() {
+        use crate::{
+            structures::{
+                construction::Footprint,
+                crafting::{CraftingState, WorkersPresent},
+            },
+            units::test_fixture::{spawn_unit, UnitTestApp},
+        };
+
+        let ant = Id::<Unit>::from_name("ant");
+        let hatchery = Id::<Structure>::from_name("hatchery");
+        let rival = Faction(1);
+
+        let mut app = UnitTestApp::new(2).build();
+        app.add_system(choose_actions);
+
+        // One hatchery for each faction, on either side of the unit
+        let mut spawn_hatchery = |direction: hexx::Direction, faction: Faction| {
+            let tile_pos = TilePos::ZERO.neighbor(direction);
+            let entity = app
+                .world
+                .spawn((
+                    hatchery,
+                    tile_pos,
+                    CraftingState::InProgress {
+                        progress: Duration::ZERO,
+                        required: Duration::from_secs(1),
+                    },
+                    WorkersPresent::new(1),
+                    faction,
+                ))
+                .id();
+            app.world.resource_mut::<MapGeometry>().add_structure(
+                tile_pos,
+                &Footprint::single(),
+                hatchery,
+                entity,
+            );
+            entity
+        };
+        let colony_hatchery = spawn_hatchery(hexx::Direction::Top, Faction::COLONY);
+        let rival_hatchery = spawn_hatchery(hexx::Direction::Bottom, rival);
+
+        let unit_entity = spawn_unit(&mut app, TilePos::ZERO, ant, Goal::Work(hatchery));
+
+        // Chooses a new action for the unit, while it faces towards `direction`
+        let mut choose_action = |direction: hexx::Direction, faction: Faction| {
+            let mut finished_action = CurrentAction::idle();
+            finished_action.timer.tick(Duration::from_secs(1));
+
+            let mut unit = app.world.entity_mut(unit_entity);
+            unit.insert((finished_action, Facing { direction }, faction));
+            app.update();
+            app.world
+                .get::<CurrentAction>(unit_entity)
+                .unwrap()
+                .action
+                .clone()
+        };
+
+        let works_at = |action: &UnitAction, structure_entity: Entity| matches!(action, UnitAction::Work { structure_entity: working_at } if *working_at == structure_entity);
+
+        let action = choose_action(hexx::Direction::Top, Faction::COLONY);
+        assert!(works_at(&action, colony_hatchery));
+
+        // The rival hatchery is right in front of the unit, but is ignored
+        let action = choose_action(hexx::Direction::Bottom, Faction::COLONY);
+        assert!(!works_at(&action, rival_hatchery));
+
+        // Units of the rival faction are happy to work there instead
+        let action = choose_action(hexx::Direction::Bottom, rival);
+        assert!(works_at(&action, rival_hatchery));
+        let action = choose_action(hexx::Direction::Top, rival);
+        assert!(!works_at(&action, colony_hatchery));
+    }
+
+    #[test]
+    fn builders_only_work_at_ghosts() {
+        use crate::{
+            structures::{
+                construction::Footprint,
+                crafting::{CraftingState, WorkersPresent},
+            },
+            units::test_fixture::{spawn_unit, UnitTestApp},
+        };
+
+        let ant = Id::<Unit>::from_name("ant");
+        let hatchery = Id::<Structure>::from_name("hatchery");
+
+        let mut app = UnitTestApp::new(2).build();
+        app.add_system(choose_actions);
+
+        // A finished hatchery and a ghost of one, on either side of the unit
+        let mut spawn_hatchery = |direction: hexx::Direction, ghost: bool| {
+            let tile_pos = TilePos::ZERO.neighbor(direction);
+            let entity = app
+                .world
+                .spawn((
+                    hatchery,
+                    tile_pos,
+                    CraftingState::InProgress {
+                        progress: Duration::ZERO,
+                        required: Duration::from_secs(1),
+                    },
+                    WorkersPresent::new(1),
+                ))
+                .id();
+            let mut map_geometry = app.world.resource_mut::<MapGeometry>();
+            match ghost {
+                true => map_geometry.add_ghost(tile_pos, &Footprint::single(), entity),
+                false => {
+                    map_geometry.add_structure(tile_pos, &Footprint::single(), hatchery, entity)
+                }
+            }
+            entity
+        };
+        let structure = spawn_hatchery(hexx::Direction::Top, false);
+        let ghost = spawn_hatchery(hexx::Direction::Bottom, true);
+
+        let unit_entity = spawn_unit(&mut app, TilePos::ZERO, ant, Goal::Work(hatchery));
+
+        // Chooses a new action for the unit, while it faces towards `direction`
+        let mut choose_action = |direction: hexx::Direction, job: Job| {
+            let mut finished_action = CurrentAction::idle();
+            finished_action.timer.tick(Duration::from_secs(1));
+
+            let mut unit = app.world.entity_mut(unit_entity);
+            unit.insert((finished_action, Facing { direction }, job));
+            app.update();
+            app.world
+                .get::<CurrentAction>(unit_entity)
+                .unwrap()
+                .action
+                .clone()
+        };
+
+        let works_at = |action: &UnitAction, structure_entity: Entity| matches!(action, UnitAction::Work { structure_entity: working_at } if *working_at == structure_entity);
+
+        assert!(works_at(
+            &choose_action(hexx::Direction::Top, Job::Any),
+            structure
+        ));
+        assert!(!works_at(
+            &choose_action(hexx::Direction::Top, Job::Builder),
+            structure
+        ));
+        assert!(works_at(
+            &choose_action(hexx::Direction::Bottom, Job::Builder),
+            ghost
+        ));
+    }
+
+    #[test]
+    fn units_only_pick_up_from_structures_of_their_own_faction() {
+        use crate::{
+            items::item_manifest::ItemData,
+            structures::construction::Footprint,
+            units::test_fixture::{spawn_unit, UnitTestApp},
+        };
+
+        let ant = Id::<Unit>::from_name("ant");
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+        let rival = Faction(1);
+
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let mut app = UnitTestApp::new(2).with_items(item_manifest).build();
+        app.add_system(choose_actions);
+
+        let tile_pos = TilePos::ZERO.neighbor(hexx::Direction::Top);
+        let mut storage_inventory = StorageInventory::new(1, ReservedFor::Any);
+        storage_inventory
+            .add_item_all_or_nothing(
+                &ItemCount::new(leuco_chunk, 5),
+                app.world.resource::<ItemManifest>(),
+            )
+            .unwrap();
+        let storage_entity = app.world.spawn((storage_inventory, tile_pos, rival)).id();
+        app.world.resource_mut::<MapGeometry>().add_structure(
+            tile_pos,
+            &Footprint::single(),
+            Id::from_name("storage"),
+            storage_entity,
+        );
+
+        let unit_entity = spawn_unit(&mut app, TilePos::ZERO, ant, Goal::Pickup(leuco_chunk));
+
+        // Chooses a new action for the unit, as a member of `faction`
+        let mut choose_action = |faction: Faction| {
+            let mut finished_action = CurrentAction::idle();
+            finished_action.timer.tick(Duration::from_secs(1));
+
+            let mut unit = app.world.entity_mut(unit_entity);
+            unit.insert((finished_action, faction));
+            app.update();
+            app.world
+                .get::<CurrentAction>(unit_entity)
+                .unwrap()
+                .action
+                .clone()
+        };
+
+        let picks_up_from_storage = |action: &UnitAction| matches!(action, UnitAction::PickUp { output_entity, .. } if *output_entity == storage_entity);
+
+        assert!(!picks_up_from_storage(&choose_action(Faction::COLONY)));
+        assert!(picks_up_from_storage(&choose_action(rival)));
+    }
+
+    #[test]
+    fn despawned_units_give_up_their_pickup_reservations() {
+        use crate::{
+            items::item_manifest::ItemData,
+            structures::construction::Footprint,
+            units::{
+                commands::UnitCommandsExt,
+                test_fixture::{spawn_unit, UnitTestApp},
+            },
+        };
+        use bevy::ecs::system::CommandQueue;
+
+        let ant = Id::<Unit>::from_name("ant");
+        let leuco_chunk = Id::from_name("leuco_chunk");
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let mut app = UnitTestApp::new(1).with_items(item_manifest).build();
+        app.add_systems((choose_actions, start_actions).chain());
+
+        let tile_pos = TilePos::ZERO.neighbor(Facing::default().direction);
+        let mut storage_inventory = StorageInventory::new(1, ReservedFor::Any);
+        storage_inventory
+            .add_item_all_or_nothing(
+                &ItemCount::new(leuco_chunk, 5),
+                app.world.resource::<ItemManifest>(),
+            )
+            .unwrap();
+        let storage_entity = app.world.spawn((storage_inventory, tile_pos)).id();
+        app.world.resource_mut::<MapGeometry>().add_structure(
+            tile_pos,
+            &Footprint::single(),
+            Id::from_name("storage"),
+            storage_entity,
+        );
+
+        // The unit starts picking up from the adjacent storage, but dies before it can finish
+        let unit_entity = spawn_unit(&mut app, TilePos::ZERO, ant, Goal::Pickup(leuco_chunk));
+        let mut finished_action = CurrentAction::idle();
+        finished_action.timer.tick(Duration::from_secs(1));
+        app.world.entity_mut(unit_entity).insert(finished_action);
+        app.update();
+        assert_eq!(
+            app.world
+                .resource::<PickupReservations>()
+                .get(storage_entity),
+            1
+        );
+
+        let mut command_queue = CommandQueue::default();
+        Commands::new(&mut command_queue, &app.world).despawn_unit(unit_entity);
+        command_queue.apply(&mut app.world);
+
+        assert!(app.world.get_entity(unit_entity).is_none());
+        assert_eq!(
+            app.world
+                .resource::<PickupReservations>()
+                .get(storage_entity),
+            0
+        );
+    }
+
+    #[test]
+    fn units_are_drawn_between_tiles_while_moving_and_spinning() {
+        use crate::{
+            simulation::geometry::Height,
+            units::test_fixture::{spawn_unit, UnitTestApp},
+        };
+        use bevy::ecs::system::SystemState;
+
+        let ant = Id::<Unit>::from_name("ant");
+        let facing = Facing::default();
+        let target_tile = TilePos::ZERO.neighbor(facing.direction);
+
+        let mut app = UnitTestApp::new(1).build();
+        app.add_system(interpolate_unit_transforms);
+        app.world
+            .resource_mut::<MapGeometry>()
+            .update_height(target_tile, Height(1));
+        let unit_entity = spawn_unit(&mut app, TilePos::ZERO, ant, Goal::default());
+
+        let mut system_state: SystemState<(
+            Query<&Id<Terrain>>,
+            Res<MapGeometry>,
+            Res<TerrainManifest>,
+        )> = SystemState::new(&mut app.world);
+        let (terrain_query, map_geometry, terrain_manifest) = system_state.get(&app.world);
+        let move_forward = CurrentAction::move_forward(
+            TilePos::ZERO,
+            ant,
+            &facing,
+            &map_geometry,
+            &terrain_query,
+            &terrain_manifest,
+        );
+        assert!(matches!(move_forward.action, UnitAction::MoveForward));
+        let walking_duration = move_forward.timer.duration();
+
+        let start = TilePos::ZERO.top_of_tile(&map_geometry);
+        let end = target_tile.top_of_tile(&map_geometry);
+        let start_rotation = facing.rotation(&map_geometry);
+        app.world.entity_mut(unit_entity).insert(move_forward);
+
+        let step = |app: &mut App, fraction: f32| {
+            let mut action = app.world.get_mut::<CurrentAction>(unit_entity).unwrap();
+            action.timer.tick(walking_duration.mul_f32(fraction));
+            app.update();
+            *app.world.get::<Transform>(unit_entity).unwrap()
+        };
+
+        // Movement is continuous, rather than teleporting at the end of the action
+        let transform = step(&mut app, 0.);
+        assert!(transform.translation.distance(start) < 1e-4);
+        let transform = step(&mut app, 0.5);
+        assert!(transform.translation.distance(start.lerp(end, 0.5)) < 1e-4);
+        assert!(transform.rotation.angle_between(start_rotation) < 1e-3);
+        let transform = step(&mut app, 0.5);
+        assert!(transform.translation.distance(end) < 1e-4);
+
+        // Spinning turns the unit gradually, but leaves it in place
+        app.world
+            .entity_mut(unit_entity)
+            .insert(CurrentAction::spin(RotationDirection::Left));
+        let spin_duration = app
+            .world
+            .get::<CurrentAction>(unit_entity)
+            .unwrap()
+            .timer
+            .duration();
+        let mut action = app.world.get_mut::<CurrentAction>(unit_entity).unwrap();
+        action.timer.tick(spin_duration / 2);
+        app.update();
+
+        let transform = app.world.get::<Transform>(unit_entity).unwrap();
+        assert!(transform.translation.distance(start) < 1e-4);
+        let half_turn = std::f32::consts::PI / 6.;
+        assert!((transform.rotation.angle_between(start_rotation) - half_turn).abs() < 1e-3);
+    }
+
+    #[test]
+    fn units_fleeing_mid_work_free_their_worker_slot() {
+        use crate::units::test_fixture::{spawn_unit, UnitTestApp};
+
+        let ant = Id::<Unit>::from_name("ant");
+        let mut app = UnitTestApp::new(1).build();
+        app.add_system(interrupt_hazardous_actions);
+        app.world
+            .resource_mut::<MapGeometry>()
+            .set_hazard(TilePos::ZERO, true);
+
+        let mut workers_present = WorkersPresent::new(1);
+        workers_present.add_worker().unwrap();
+        let workplace_entity = app.world.spawn(workers_present).id();
+        let source_entity = app.world.spawn_empty().id();
+        app.world
+            .resource_mut::<PickupReservations>()
+            .reserve(source_entity);
+
+        let started = |action: UnitAction| CurrentAction {
+            action,
+            timer: Timer::from_seconds(10., TimerMode::Once),
+            just_started: false,
+        };
+        let worker = spawn_unit(&mut app, TilePos::ZERO, ant, Goal::default());
+        app.world
+            .entity_mut(worker)
+            .insert(started(UnitAction::Work {
+                structure_entity: workplace_entity,
+            }));
+        let hauler = spawn_unit(&mut app, TilePos::ZERO, ant, Goal::default());
+        app.world
+            .entity_mut(hauler)
+            .insert(started(UnitAction::PickUp {
+                item_id: Id::from_name("leuco_chunk"),
+                output_entity: source_entity,
+            }));
+
+        app.update();
+
+        for unit_entity in [worker, hauler] {
+            let action = app.world.get::<CurrentAction>(unit_entity).unwrap();
+            assert!(action.finished());
+            assert!(matches!(action.action(), UnitAction::Idle));
+        }
+        let workers_present = app.world.get::<WorkersPresent>(workplace_entity).unwrap();
+        assert_eq!(workers_present.current(), 0);
+        assert_eq!(
+            app.world
+                .resource::<PickupReservations>()
+                .get(source_entity),
+            0
+        );
+    }
+}
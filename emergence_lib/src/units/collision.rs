@@ -0,0 +1,143 @@
+//! Optionally stops units from sharing tiles, forcing them to route around each other.
+//!
+//! Each unit claims the tile it is standing on, and reserves the tile it is about to move into.
+//! These claims are stored in [`MapGeometry`], which treats claimed tiles as impassable.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_management::manifest::Id,
+    simulation::geometry::{Facing, MapGeometry, TilePos},
+};
+
+use super::{
+    actions::{CurrentAction, UnitAction},
+    unit_manifest::Unit,
+};
+
+/// Controls whether or not units can share tiles.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnitCollision {
+    /// When `true`, only one unit can occupy each tile.
+    pub enabled: bool,
+}
+
+/// Rebuilds the tiles claimed by units from scratch.
+///
+/// This picks up any units that have been spawned or despawned since the last update.
+pub(super) fn sync_unit_occupancy(
+    unit_collision: Res<UnitCollision>,
+    unit_query: Query<(Entity, &TilePos, &Facing, &CurrentAction), With<Id<Unit>>>,
+    mut map_geometry: ResMut<MapGeometry>,
+) {
+    // Units moving around doesn't change where structures can be built, so don't trigger change detection
+    let map_geometry = map_geometry.bypass_change_detection();
+    map_geometry.clear_units();
+
+    if !unit_collision.enabled {
+        return;
+    }
+
+    for (unit_entity, &tile_pos, facing, action) in unit_query.iter() {
+        map_geometry.reserve_tile(tile_pos, unit_entity);
+
+        if let UnitAction::MoveForward = action.action() {
+            map_geometry.reserve_tile(tile_pos.neighbor(facing.direction), unit_entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hexx::Hex;
+
+    use super::*;
+    use crate::{
+        asset_management::manifest::Id,
+        structures::construction::Footprint,
+        units::{
+            actions::{advance_action_timer, choose_actions, finish_actions, start_actions},
+            goals::Goal,
+            test_fixture::{spawn_unit, UnitTestApp},
+            unit_manifest::Unit,
+        },
+    };
+
+    /// Creates a map where only a single, straight corridor can be walked along.
+    ///
+    /// Returns the app, and the tiles that make up the corridor.
+    fn corridor_app() -> (App, Vec<TilePos>) {
+        let radius: i32 = 3;
+        let ant = Id::from_name("ant");
+
+        let mut app = UnitTestApp::new(radius as u32).with_collision(true).build();
+        app.add_systems(
+            (
+                advance_action_timer,
+                sync_unit_occupancy,
+                start_actions,
+                finish_actions,
+                choose_actions,
+            )
+                .chain(),
+        );
+
+        let mut corridor = Vec::new();
+        for hex in hexx::shapes::hexagon(Hex::ZERO, radius as u32) {
+            let tile_pos = TilePos { hex };
+            if tile_pos.y == 0 {
+                corridor.push(tile_pos);
+            } else {
+                // Wall off everything else
+                let wall_entity = app.world.spawn_empty().id();
+                app.world.resource_mut::<MapGeometry>().add_structure(
+                    tile_pos,
+                    &Footprint::single(),
+                    Id::from_name("wall"),
+                    wall_entity,
+                );
+            }
+        }
+
+        // Two units start at opposite ends of the corridor, facing each other
+        let west_end = TilePos::new(-radius, 0);
+        let east_end = TilePos::new(radius, 0);
+        let ends = [
+            (west_end, west_end.direction_to(east_end.hex)),
+            (east_end, east_end.direction_to(west_end.hex)),
+        ];
+        for (tile_pos, direction) in ends {
+            let unit_entity = spawn_unit(&mut app, tile_pos, ant, Goal::default());
+            app.world
+                .entity_mut(unit_entity)
+                .insert(Facing { direction });
+        }
+
+        (app, corridor)
+    }
+
+    #[test]
+    fn units_in_a_corridor_never_overlap() {
+        let (mut app, corridor) = corridor_app();
+        let mut visited = Vec::new();
+
+        for _ in 0..500 {
+            app.update();
+
+            let mut unit_query = app.world.query_filtered::<&TilePos, With<Id<Unit>>>();
+            let positions: Vec<TilePos> = unit_query.iter(&app.world).copied().collect();
+            assert_eq!(positions.len(), 2);
+            assert_ne!(positions[0], positions[1]);
+
+            for tile_pos in positions {
+                assert!(corridor.contains(&tile_pos));
+                if !visited.contains(&tile_pos) {
+                    visited.push(tile_pos);
+                }
+            }
+        }
+
+        // The units should actually be moving, rather than standing still to avoid collisions
+        assert!(visited.len() > 2);
+    }
+}
@@ -0,0 +1,222 @@
+//! Methods to use [`Commands`] to manipulate units.
+
+use bevy::{
+    ecs::system::Command,
+    prelude::{warn, Commands, DespawnRecursiveExt, Entity, World},
+};
+
+use crate::{
+    asset_management::manifest::Id,
+    simulation::{
+        factions::Faction,
+        geometry::{MapGeometry, TilePos},
+    },
+};
+
+use super::{
+    actions::CurrentAction,
+    collision::UnitCollision,
+    goals::Goal,
+    unit_assets::UnitHandles,
+    unit_manifest::{Unit, UnitManifest},
+    UnitBundle,
+};
+
+/// An extension trait for [`Commands`] for working with units.
+pub(crate) trait UnitCommandsExt {
+    /// Spawns a unit of type `unit_id` at `tile_pos` that belongs to `faction`, pursuing the provided `goal`.
+    ///
+    /// The unit's energy, lifecycle and impatience are initialized from its [`UnitManifest`] entry.
+    /// Has no effect if the tile position is outside of the map.
+    fn spawn_unit(&mut self, tile_pos: TilePos, unit_id: Id<Unit>, goal: Goal, faction: Faction);
+
+    /// Despawns the unit `unit_entity`, along with its children.
+    ///
+    /// Any workplace slot or pickup reservation claimed by its current action is given up first,
+    /// so other units aren't turned away by a unit that no longer exists.
+    fn despawn_unit(&mut self, unit_entity: Entity);
+}
+
+impl<'w, 's> UnitCommandsExt for Commands<'w, 's> {
+    fn spawn_unit(&mut self, tile_pos: TilePos, unit_id: Id<Unit>, goal: Goal, faction: Faction) {
+        self.add(SpawnUnitCommand {
+            tile_pos,
+            unit_id,
+            goal,
+            faction,
+        });
+    }
+
+    fn despawn_unit(&mut self, unit_entity: Entity) {
+        self.add(DespawnUnitCommand { unit_entity });
+    }
+}
+
+/// A [`Command`] used to spawn a unit via [`UnitCommandsExt`].
+pub(super) struct SpawnUnitCommand {
+    /// The tile position at which to spawn the unit.
+    pub(super) tile_pos: TilePos,
+    /// The type of unit to spawn.
+    pub(super) unit_id: Id<Unit>,
+    /// The goal that the unit starts with.
+    pub(super) goal: Goal,
+    /// The faction that the unit belongs to.
+    pub(super) faction: Faction,
+}
+
+impl Command for SpawnUnitCommand {
+    fn write(self, world: &mut World) {
+        self.spawn(world);
+    }
+}
+
+impl SpawnUnitCommand {
+    /// Spawns the unit into the `world`, returning its entity if it could be placed.
+    pub(super) fn spawn(self, world: &mut World) -> Option<Entity> {
+        let map_geometry = world.resource::<MapGeometry>();
+        if !map_geometry.is_valid(self.tile_pos) {
+            warn!(
+                "Tried to spawn a unit at {:?}, which is outside of the map.",
+                self.tile_pos
+            );
+            return None;
+        }
+
+        let unit_data = world.resource::<UnitManifest>().get(self.unit_id).clone();
+        let mut unit_bundle = UnitBundle::new(
+            self.unit_id,
+            self.tile_pos,
+            unit_data,
+            world.resource::<UnitHandles>(),
+            map_geometry,
+        );
+        unit_bundle.current_goal = self.goal;
+        unit_bundle.faction = self.faction;
+
+        let unit_entity = world.spawn(unit_bundle).id();
+
+        // Claim the tile right away, rather than waiting for the next occupancy sync
+        let collision_enabled = world
+            .get_resource::<UnitCollision>()
+            .is_some_and(|unit_collision| unit_collision.enabled);
+        if collision_enabled {
+            world
+                .resource_mut::<MapGeometry>()
+                .reserve_tile(self.tile_pos, unit_entity);
+        }
+
+        Some(unit_entity)
+    }
+}
+
+/// A [`Command`] used to despawn a unit via [`UnitCommandsExt`].
+struct DespawnUnitCommand {
+    /// The unit to despawn.
+    unit_entity: Entity,
+}
+
+impl Command for DespawnUnitCommand {
+    fn write(self, world: &mut World) {
+        if let Some(action) = world.get::<CurrentAction>(self.unit_entity).cloned() {
+            action.release_claims_before_despawn(world);
+        }
+
+        if let Some(entity_mut) = world.get_entity_mut(self.unit_entity) {
+            entity_mut.despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::CommandQueue, prelude::Entity};
+    use leafwing_abilities::prelude::Pool;
+
+    use super::*;
+    use crate::{
+        organisms::{
+            energy::{Energy, EnergyPool},
+            lifecycle::Lifecycle,
+            OrganismVariety,
+        },
+        simulation::geometry::Facing,
+        units::{
+            actions::{CurrentAction, UnitAction},
+            impatience::ImpatiencePool,
+            item_interaction::UnitInventory,
+            test_fixture::UnitTestApp,
+            unit_manifest::UnitData,
+        },
+    };
+
+    #[test]
+    fn spawned_units_match_their_manifest() {
+        let ant = Id::<Unit>::from_name("ant");
+        let unit_data = UnitData {
+            organism_variety: OrganismVariety {
+                energy_pool: EnergyPool::new_full(Energy(42.), Energy(-1.)),
+                ..UnitData::default().organism_variety
+            },
+            max_impatience: 7,
+            ..Default::default()
+        };
+        let mut world = UnitTestApp::new(0)
+            .with_unit("ant", unit_data)
+            .with_collision(true)
+            .build()
+            .world;
+
+        let goal = Goal::Pickup(Id::from_name("leuco_chunk"));
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.spawn_unit(TilePos::ZERO, ant, goal.clone(), Faction::COLONY);
+        // Off the map, so nothing should be spawned
+        commands.spawn_unit(TilePos::new(5, 0), ant, Goal::default(), Faction::COLONY);
+        queue.apply(&mut world);
+
+        let mut query = world.query::<(
+            Entity,
+            &Id<Unit>,
+            &TilePos,
+            &Facing,
+            &Goal,
+            &ImpatiencePool,
+            &CurrentAction,
+            &UnitInventory,
+            &EnergyPool,
+            &Lifecycle,
+        )>();
+        let units: Vec<_> = query.iter(&world).collect();
+        assert_eq!(units.len(), 1);
+
+        let (
+            entity,
+            &unit_id,
+            &tile_pos,
+            &facing,
+            unit_goal,
+            impatience,
+            action,
+            inventory,
+            energy_pool,
+            lifecycle,
+        ) = units[0];
+        assert_eq!(unit_id, ant);
+        assert_eq!(tile_pos, TilePos::ZERO);
+        assert_eq!(facing, Facing::default());
+        assert_eq!(*unit_goal, goal);
+        assert_eq!(*impatience, ImpatiencePool::new(7));
+        assert!(matches!(action.action(), UnitAction::Idle));
+        assert_eq!(inventory.held_item, None);
+        assert_eq!(energy_pool.current(), Energy(42.));
+        assert_eq!(*lifecycle, Lifecycle::STATIC);
+
+        let map_geometry = world.resource::<MapGeometry>();
+        assert!(map_geometry.is_occupied_by_unit(TilePos::ZERO));
+        assert!(!map_geometry.is_occupied_by_unit(TilePos::new(1, 0)));
+        // The claim belongs to the spawned unit
+        let mut map_geometry = world.resource_mut::<MapGeometry>();
+        map_geometry.vacate_tile(TilePos::ZERO, entity);
+        assert!(!map_geometry.is_occupied_by_unit(TilePos::ZERO));
+    }
+}
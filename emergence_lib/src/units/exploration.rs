@@ -0,0 +1,178 @@
+//! Units reveal the map around them as they move, lifting the fog of war.
+//!
+//! Idle units pursue [`Goal::Explore`](super::goals::Goal::Explore) to seek out the tiles that are still hidden.
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_management::manifest::Id,
+    simulation::geometry::{MapGeometry, TilePos},
+};
+
+use super::unit_manifest::Unit;
+
+/// The number of tiles around each unit that it reveals.
+pub(crate) const SIGHT_RADIUS: u32 = 2;
+
+/// Reveals the tiles around each unit.
+pub(super) fn reveal_around_units(
+    unit_query: Query<&TilePos, With<Id<Unit>>>,
+    mut map_geometry: ResMut<MapGeometry>,
+) {
+    if map_geometry.is_fully_explored() {
+        return;
+    }
+
+    // Revealing tiles doesn't change where structures can be built, so don't trigger change detection
+    let map_geometry = map_geometry.bypass_change_detection();
+    for &tile_pos in unit_query.iter() {
+        map_geometry.reveal(tile_pos, SIGHT_RADIUS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hexx::{shapes::hexagon, Hex};
+
+    use super::*;
+    use crate::{
+        simulation::geometry::Height,
+        units::{
+            actions::{advance_action_timer, choose_actions, finish_actions, start_actions},
+            goals::{choose_goal, Goal},
+            test_fixture::{spawn_unit, UnitTestApp},
+            unit_manifest::UnitData,
+        },
+    };
+
+    /// The radius of the test map.
+    const MAP_RADIUS: u32 = 6;
+
+    /// Creates an app whose map is hidden, except for the tiles west of `frontier_x`.
+    fn partially_explored_app(frontier_x: i32) -> App {
+        let mut app = UnitTestApp::new(MAP_RADIUS)
+            .with_unit(
+                "ant",
+                UnitData {
+                    max_impatience: 100,
+                    ..Default::default()
+                },
+            )
+            .with_timestep(1.0)
+            .build();
+
+        let mut map_geometry = app.world.resource_mut::<MapGeometry>();
+        map_geometry.hide_all_tiles();
+        for hex in hexagon(Hex::ZERO, MAP_RADIUS) {
+            if hex.x < frontier_x {
+                map_geometry.reveal(TilePos { hex }, 0);
+            }
+        }
+
+        app.add_systems(
+            (
+                advance_action_timer,
+                start_actions,
+                finish_actions,
+                reveal_around_units,
+                choose_goal,
+                choose_actions,
+            )
+                .chain(),
+        );
+        app
+    }
+
+    #[test]
+    fn explorers_head_for_the_unexplored_frontier() {
+        // Everything east of the unit's sight radius is hidden
+        let frontier_x = SIGHT_RADIUS as i32 + 2;
+        let mut app = partially_explored_app(frontier_x);
+        let target = app
+            .world
+            .resource::<MapGeometry>()
+            .nearest_unexplored(TilePos::ZERO)
+            .unwrap();
+        assert!(target.x > 0);
+
+        let unit_entity = spawn_unit(
+            &mut app,
+            TilePos::ZERO,
+            Id::from_name("ant"),
+            Goal::Explore { target },
+        );
+
+        let mut furthest_x = 0;
+        for _ in 0..50 {
+            app.update();
+            let tile_pos = *app.world.get::<TilePos>(unit_entity).unwrap();
+            furthest_x = furthest_x.max(tile_pos.x);
+
+            if app.world.resource::<MapGeometry>().is_fully_explored() {
+                break;
+            }
+        }
+
+        // The unit walked towards the frontier, revealing the hidden tiles as it went
+        assert!(furthest_x > 0);
+        let map_geometry = app.world.resource::<MapGeometry>();
+        assert!(map_geometry.is_explored(TilePos::new(frontier_x, 0)));
+    }
+
+    #[test]
+    fn explorers_wander_if_the_unexplored_tiles_are_out_of_reach() {
+        let frontier_x = SIGHT_RADIUS as i32 + 2;
+        let mut app = partially_explored_app(frontier_x);
+
+        // Wall off the hidden tiles with a cliff that can't be climbed
+        let mut map_geometry = app.world.resource_mut::<MapGeometry>();
+        for hex in hexagon(Hex::ZERO, MAP_RADIUS) {
+            if hex.x == frontier_x - 1 {
+                map_geometry.update_height(TilePos { hex }, Height(5));
+            }
+        }
+
+        // Units that are done wandering look for something to explore
+        let unit_entity = spawn_unit(
+            &mut app,
+            TilePos::ZERO,
+            Id::from_name("ant"),
+            Goal::Wander {
+                remaining_actions: Some(0),
+            },
+        );
+
+        for _ in 0..20 {
+            app.update();
+
+            assert!(matches!(
+                app.world.get::<Goal>(unit_entity).unwrap(),
+                Goal::Wander { .. }
+            ));
+        }
+        assert!(!app.world.resource::<MapGeometry>().is_fully_explored());
+    }
+
+    #[test]
+    fn explorers_wander_once_the_map_is_explored() {
+        let mut app = partially_explored_app(MAP_RADIUS as i32 + 1);
+        assert!(app.world.resource::<MapGeometry>().is_fully_explored());
+
+        // This target has already been revealed, and there's nothing left to replace it with
+        let unit_entity = spawn_unit(
+            &mut app,
+            TilePos::ZERO,
+            Id::from_name("ant"),
+            Goal::Explore {
+                target: TilePos::new(1, 0),
+            },
+        );
+
+        app.update();
+
+        assert!(matches!(
+            app.world.get::<Goal>(unit_entity).unwrap(),
+            Goal::Wander { .. }
+        ));
+    }
+}
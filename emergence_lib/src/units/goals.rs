@@ -1,19 +1,23 @@
 //! What are units attempting to achieve?
 
 use bevy::prelude::*;
+use leafwing_abilities::prelude::Pool;
 use rand::distributions::WeightedIndex;
 use rand::prelude::Distribution;
 use rand::rngs::ThreadRng;
 use rand::thread_rng;
 
-use crate::asset_management::manifest::Id;
+use crate::asset_management::{localization::Localization, manifest::Id};
 use crate::items::item_manifest::{Item, ItemManifest};
-use crate::signals::{SignalType, Signals};
-use crate::simulation::geometry::TilePos;
+use crate::organisms::energy::EnergyPool;
+use crate::signals::{SignalStrength, SignalType, Signals};
+use crate::simulation::geometry::{MapGeometry, TilePos};
 use crate::structures::structure_manifest::{Structure, StructureManifest};
 
-use super::impatience::ImpatiencePool;
+use super::hunger::food_is_available;
+use super::impatience::{ImpatienceConsequence, ImpatiencePool};
 use super::item_interaction::UnitInventory;
+use super::jobs::Job;
 use super::unit_manifest::{Unit, UnitManifest};
 use super::WanderingBehavior;
 
@@ -32,6 +36,15 @@ pub(crate) enum Goal {
         /// How many actions will this unit take before picking a new goal?
         remaining_actions: Option<u16>,
     },
+    /// Heading towards the closest tile hidden by the fog of war, in order to reveal it
+    ///
+    /// Once the whole map has been explored, units go back to wandering.
+    Explore {
+        /// The unexplored tile that this unit is walking towards.
+        ///
+        /// A new target is only searched for once this tile has been revealed.
+        target: TilePos,
+    },
     /// Attempting to pick up an object
     #[allow(dead_code)]
     Pickup(Id<Item>),
@@ -50,6 +63,12 @@ pub(crate) enum Goal {
     Eat(Id<Item>),
     /// Attempting to destroy a structure
     Demolish(Id<Structure>),
+    /// Attempting to repair a damaged structure, using the materials it was built from
+    Repair(Id<Structure>),
+    /// Attempting to upgrade a structure into its more advanced form, using the materials its upgrade requires
+    Upgrade(Id<Structure>),
+    /// Attempting to reshape terrain that has been zoned for terraforming
+    Terraform,
 }
 
 impl Default for Goal {
@@ -72,9 +91,14 @@ impl TryFrom<SignalType> for Goal {
             SignalType::Pull(item_id) => Ok(Goal::Pickup(item_id)),
             SignalType::Work(structure_id) => Ok(Goal::Work(structure_id)),
             SignalType::Demolish(structure_id) => Ok(Goal::Demolish(structure_id)),
+            SignalType::Repair(structure_id) => Ok(Goal::Repair(structure_id)),
+            SignalType::Upgrade(structure_id) => Ok(Goal::Upgrade(structure_id)),
+            SignalType::Terraform => Ok(Goal::Terraform),
             SignalType::Contains(_) => Err(()),
             SignalType::Stores(_) => Err(()),
+            SignalType::Contained(_) => Err(()),
             SignalType::Unit(_) => Err(()),
+            SignalType::Stuck => Err(()),
         }
     }
 }
@@ -85,25 +109,161 @@ impl Goal {
         &self,
         item_manifest: &ItemManifest,
         structure_manifest: &StructureManifest,
+        localization: &Localization,
     ) -> String {
         match self {
             Goal::Wander { remaining_actions } => format!(
                 "Wander ({} actions remaining)",
                 remaining_actions.unwrap_or(0)
             ),
-            Goal::Pickup(item) => format!("Pickup {}", item_manifest.name(*item)),
-            Goal::Store(item) => format!("Store {}", item_manifest.name(*item)),
-            Goal::Deliver(item) => format!("Deliver {}", item_manifest.name(*item)),
-            Goal::Work(structure) => format!("Work at {}", structure_manifest.name(*structure)),
+            Goal::Pickup(item) => format!("Pickup {}", localization.get(item_manifest.name(*item))),
+            Goal::Store(item) => format!("Store {}", localization.get(item_manifest.name(*item))),
+            Goal::Deliver(item) => {
+                format!("Deliver {}", localization.get(item_manifest.name(*item)))
+            }
+            Goal::Work(structure) => format!(
+                "Work at {}",
+                localization.get(structure_manifest.name(*structure))
+            ),
             Goal::Demolish(structure) => {
-                format!("Demolish {}", structure_manifest.name(*structure))
+                format!(
+                    "Demolish {}",
+                    localization.get(structure_manifest.name(*structure))
+                )
             }
-            Goal::Eat(item) => format!("Eat {}", item_manifest.name(*item)),
+            Goal::Repair(structure) => format!(
+                "Repair {}",
+                localization.get(structure_manifest.name(*structure))
+            ),
+            Goal::Upgrade(structure) => format!(
+                "Upgrade {}",
+                localization.get(structure_manifest.name(*structure))
+            ),
+            Goal::Terraform => "Terraform".to_string(),
+            Goal::Explore { .. } => "Explore".to_string(),
+            Goal::Eat(item) => format!("Eat {}", localization.get(item_manifest.name(*item))),
+        }
+    }
+}
+
+/// The weights used to score the candidate goals of each unit.
+///
+/// Whichever candidate has the highest score becomes the unit's goal.
+/// Changing these values tunes how units trade off between their different needs.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub(crate) struct GoalWeights {
+    /// The score of eating, multiplied by how empty the unit's energy pool is.
+    pub(crate) hunger: f32,
+    /// The score of following a signal, multiplied by the logarithm of its strength.
+    pub(crate) signals: f32,
+    /// The score of continuing the current goal, multiplied by how much patience the unit has left.
+    pub(crate) persistence: f32,
+    /// The score of wandering, when there's nothing else to do.
+    pub(crate) wander: f32,
+    /// The bonus added to the score of the current goal.
+    ///
+    /// This prevents units from flip-flopping between goals with similar scores.
+    pub(crate) hysteresis: f32,
+}
+
+impl Default for GoalWeights {
+    fn default() -> Self {
+        GoalWeights {
+            hunger: 10.,
+            signals: 1.,
+            persistence: 2.,
+            wander: 0.1,
+            hysteresis: 1.,
+        }
+    }
+}
+
+impl GoalWeights {
+    /// The score of eating, when the unit's energy pool is `fraction_full`.
+    fn hunger_score(&self, fraction_full: f32) -> f32 {
+        self.hunger * (1. - fraction_full).clamp(0., 1.)
+    }
+
+    /// The score of following a signal of the provided `strength`.
+    fn signal_score(&self, strength: SignalStrength) -> f32 {
+        self.signals * strength.value().ln_1p()
+    }
+
+    /// The score of continuing the current goal, when the unit's impatience pool is `fraction_full`.
+    fn persistence_score(&self, fraction_full: f32) -> f32 {
+        self.persistence * (1. - fraction_full).clamp(0., 1.)
+    }
+}
+
+/// Scores the candidate goals of a single unit, picking the best one.
+#[derive(Debug)]
+pub(crate) struct GoalSelector<'a> {
+    /// The goal that the unit is currently pursuing.
+    current: &'a Goal,
+    /// The weights used to score each candidate.
+    weights: &'a GoalWeights,
+    /// The highest-scoring candidate seen so far, and its score.
+    best: Option<(Goal, f32)>,
+}
+
+impl<'a> GoalSelector<'a> {
+    /// Creates a new [`GoalSelector`] for a unit that is currently pursuing `current`.
+    pub(crate) fn new(current: &'a Goal, weights: &'a GoalWeights) -> Self {
+        GoalSelector {
+            current,
+            weights,
+            best: None,
+        }
+    }
+
+    /// Proposes `goal` as a candidate, with the provided `score`.
+    ///
+    /// Candidates that match the current goal get a bonus, to avoid flip-flopping.
+    /// Ties are won by the earliest candidate.
+    pub(crate) fn consider(&mut self, goal: Goal, score: f32) {
+        let score = match self.current.is_same_task(&goal) {
+            true => score + self.weights.hysteresis,
+            false => score,
+        };
+
+        let is_better = match &self.best {
+            Some((_, best_score)) => score > *best_score,
+            None => true,
+        };
+
+        if is_better {
+            self.best = Some((goal, score));
+        }
+    }
+
+    /// Returns the highest-scoring candidate.
+    ///
+    /// If no candidates were proposed, the unit will wander.
+    pub(crate) fn select(self) -> Goal {
+        match self.best {
+            Some((goal, _)) => goal,
+            None => Goal::Wander {
+                remaining_actions: None,
+            },
+        }
+    }
+}
+
+impl Goal {
+    /// Is `other` the same task as this goal?
+    ///
+    /// Wandering is treated as a single task, no matter how many actions remain.
+    fn is_same_task(&self, other: &Goal) -> bool {
+        match (self, other) {
+            (Goal::Wander { .. }, Goal::Wander { .. }) => true,
+            _ => self == other,
         }
     }
 }
 
 /// Choose this unit's new goal if needed
+///
+/// Each candidate goal is scored by a [`GoalSelector`], using the [`GoalWeights`].
 pub(super) fn choose_goal(
     mut units_query: Query<(
         &TilePos,
@@ -111,49 +271,123 @@ pub(super) fn choose_goal(
         &mut Goal,
         &mut ImpatiencePool,
         &UnitInventory,
-        &Id<Unit>,
+        &EnergyPool,
+        Option<&Job>,
     )>,
     unit_manifest: Res<UnitManifest>,
-    signals: Res<Signals>,
+    mut signals: ResMut<Signals>,
+    goal_weights: Res<GoalWeights>,
+    map_geometry: Res<MapGeometry>,
 ) {
     let rng = &mut thread_rng();
 
-    for (&tile_pos, &unit_id, mut goal, mut impatience_pool, unit_inventory, id) in
+    for (&tile_pos, &unit_id, mut goal, mut impatience_pool, unit_inventory, energy_pool, job) in
         units_query.iter_mut()
     {
+        let job = job.copied().unwrap_or_default();
+
+        let unit_data = unit_manifest.get(unit_id);
+
         // If we're out of patience, give up and choose a new goal
         if impatience_pool.is_full() {
-            // If you're holding something, try to put it away nicely
-            *goal = if let Some(held_item) = unit_inventory.held_item {
-                // Don't get stuck trying to do a hopeless storage task forever
-                if !matches!(*goal, Goal::Store(..) | Goal::Wander { .. }) {
-                    Goal::Store(held_item)
-                } else {
-                    Goal::Wander {
-                        remaining_actions: None,
+            let consequence = unit_data.impatience_consequence;
+            if consequence == ImpatienceConsequence::SignalStuck {
+                signals.add_signal(
+                    SignalType::Stuck,
+                    tile_pos,
+                    SignalStrength::new(ImpatienceConsequence::STUCK_SIGNAL_STRENGTH),
+                );
+            }
+
+            *goal = consequence.next_goal(&goal, unit_inventory.held_item);
+
+            // Reset impatience when we choose a new goal
+            impatience_pool.reset();
+        }
+
+        let mut selector = GoalSelector::new(&goal, &goal_weights);
+
+        match *goal {
+            Goal::Wander { remaining_actions } => {
+                let (new_goal, maybe_strength) = compute_new_goal(
+                    remaining_actions,
+                    unit_id,
+                    tile_pos,
+                    &unit_data.wandering_behavior,
+                    rng,
+                    &signals,
+                    job,
+                );
+
+                match maybe_strength {
+                    Some(strength) => {
+                        selector.consider(new_goal, goal_weights.signal_score(strength))
                     }
+                    // Once done wandering, reveal more of the map rather than wandering aimlessly again
+                    None if remaining_actions == Some(0) => {
+                        match map_geometry.nearest_unexplored(tile_pos) {
+                            Some(target) => {
+                                selector.consider(Goal::Explore { target }, goal_weights.wander)
+                            }
+                            None => selector.consider(new_goal, goal_weights.wander),
+                        }
+                    }
+                    None => selector.consider(new_goal, goal_weights.wander),
                 }
-            } else {
-                Goal::Wander {
-                    remaining_actions: None,
+            }
+            // Explorers still follow any signals they come across
+            Goal::Explore { target } => {
+                let (new_goal, maybe_strength) = compute_new_goal(
+                    Some(0),
+                    unit_id,
+                    tile_pos,
+                    &unit_data.wandering_behavior,
+                    rng,
+                    &signals,
+                    job,
+                );
+
+                if let Some(strength) = maybe_strength {
+                    selector.consider(new_goal, goal_weights.signal_score(strength));
                 }
-            };
 
-            // Reset impatience when we choose a new goal
-            impatience_pool.reset();
+                // Only search for a new target once the current one has been revealed,
+                // and wander instead once there's nothing left that can be reached and explored
+                let maybe_target = if map_geometry.is_explored(target) {
+                    map_geometry.nearest_unexplored(tile_pos)
+                } else {
+                    Some(target)
+                };
+
+                match maybe_target {
+                    Some(target) => {
+                        selector.consider(Goal::Explore { target }, goal_weights.wander)
+                    }
+                    None => selector.consider(Goal::default(), goal_weights.wander),
+                }
+            }
+            // Eating is only worthwhile while hungry, which is scored below
+            Goal::Eat(..) => (),
+            // Goals that clash with a newly assigned job are dropped, falling back to wandering
+            ref current_goal if !job.permits(current_goal) => (),
+            ref current_goal => selector.consider(
+                current_goal.clone(),
+                goal_weights.persistence_score(impatience_pool.fraction_full()),
+            ),
         }
 
-        if let Goal::Wander { remaining_actions } = *goal {
-            let wandering_behavior = &unit_manifest.get(*id).wandering_behavior;
-            *goal = compute_new_goal(
-                remaining_actions,
-                unit_id,
-                tile_pos,
-                wandering_behavior,
-                rng,
-                &signals,
-            );
+        // Once units start eating, they keep going until they're full
+        let wants_food =
+            energy_pool.is_hungry() || matches!(*goal, Goal::Eat(..)) && !energy_pool.is_satiated();
+        let food = unit_data.diet.item();
+        if wants_food && food_is_available(food, tile_pos, unit_inventory, &signals) {
+            let fraction_full = energy_pool.current().0 / energy_pool.max().0;
+            selector.consider(Goal::Eat(food), goal_weights.hunger_score(fraction_full));
+        }
 
+        let new_goal = selector.select();
+        if new_goal != *goal {
+            *goal = new_goal;
             // Reset impatience when we choose a new goal
             impatience_pool.reset();
         }
@@ -164,6 +398,9 @@ pub(super) fn choose_goal(
 ///
 // By default, goals are reset to wandering when completed.
 /// If anything fails, just keep wandering for now.
+///
+/// Only signals that lead to goals permitted by the unit's [`Job`] are considered.
+/// If a signal was chosen to follow, its strength is returned alongside the new goal.
 fn compute_new_goal(
     mut remaining_actions: Option<u16>,
     unit_id: Id<Unit>,
@@ -171,7 +408,8 @@ fn compute_new_goal(
     wandering_behavior: &WanderingBehavior,
     rng: &mut ThreadRng,
     signals: &Signals,
-) -> Goal {
+    job: Job,
+) -> (Goal, Option<SignalStrength>) {
     // When we first get a wandering goal, pick a number of actions to take before picking a new goal.
     if remaining_actions.is_none() {
         // Units should wander for longer when they are more densely packed in order to fight crowding.
@@ -188,28 +426,298 @@ fn compute_new_goal(
     // If we have actions left while wandering, use them up before picking a new goal.
     if let Some(n) = remaining_actions {
         if n != 0 {
-            return Goal::Wander {
-                remaining_actions: Some(n - 1),
-            };
+            return (
+                Goal::Wander {
+                    remaining_actions: Some(n - 1),
+                },
+                None,
+            );
         }
     }
 
     // Pick a new goal based on the signals at this tile
     let current_signals = signals.all_signals_at_position(tile_pos);
-    let mut goal_relevant_signals = current_signals.goal_relevant_signals();
+    let permitted_goals: Vec<(Goal, SignalStrength)> = current_signals
+        .goal_relevant_signals()
+        .map(|(&signal_type, &strength)| (signal_type.try_into().unwrap(), strength))
+        .filter(|(goal, _strength)| job.permits(goal))
+        .collect();
     if let Ok(goal_weights) = WeightedIndex::new(
-        goal_relevant_signals
-            .clone()
-            .map(|(_type, strength)| strength.value()),
+        permitted_goals
+            .iter()
+            .map(|(_goal, strength)| strength.value()),
     ) {
         let selected_goal_index = goal_weights.sample(rng);
-        if let Some(selected_signal) = goal_relevant_signals.nth(selected_goal_index) {
-            let selected_signal_type = *selected_signal.0;
-            selected_signal_type.try_into().unwrap()
+        if let Some((selected_goal, strength)) = permitted_goals.get(selected_goal_index) {
+            (selected_goal.clone(), Some(*strength))
         } else {
-            Goal::Wander { remaining_actions }
+            (Goal::Wander { remaining_actions }, None)
         }
     } else {
-        Goal::Wander { remaining_actions }
+        (Goal::Wander { remaining_actions }, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        organisms::energy::Energy,
+        signals::SignalStrength,
+        units::{
+            jobs::Job,
+            test_fixture::{spawn_unit, UnitTestApp},
+            unit_manifest::UnitData,
+        },
+    };
+
+    #[test]
+    fn current_goal_resists_similar_alternatives() {
+        let weights = GoalWeights::default();
+        let current = Goal::Work(Id::from_name("hatchery"));
+
+        let mut selector = GoalSelector::new(&current, &weights);
+        selector.consider(current.clone(), 1.);
+        selector.consider(Goal::Work(Id::from_name("ant_hive")), 1.5);
+        assert_eq!(selector.select(), current);
+
+        let mut selector = GoalSelector::new(&current, &weights);
+        selector.consider(current.clone(), 1.);
+        selector.consider(Goal::Work(Id::from_name("ant_hive")), 2.5);
+        assert_eq!(selector.select(), Goal::Work(Id::from_name("ant_hive")));
+    }
+
+    #[test]
+    fn hunger_outweighs_available_work() {
+        let ant = Id::<Unit>::from_name("ant");
+        let leuco_chunk = Id::<Item>::from_name("leuco_chunk");
+        let hatchery = Id::<Structure>::from_name("hatchery");
+
+        let mut app = UnitTestApp::new(1).build();
+        app.world.resource_mut::<Signals>().add_signal(
+            SignalType::Work(hatchery),
+            TilePos::ZERO,
+            SignalStrength::new(50.),
+        );
+        app.add_system(choose_goal);
+
+        // Both units are done wandering, and are holding some food
+        let mut spawn_ant = |energy: Energy| {
+            let goal = Goal::Wander {
+                remaining_actions: Some(0),
+            };
+            let entity = spawn_unit(&mut app, TilePos::ZERO, ant, goal);
+            app.world.entity_mut(entity).insert((
+                UnitInventory {
+                    held_item: Some(leuco_chunk),
+                },
+                EnergyPool::new(energy, Energy(100.), Energy(0.)),
+            ));
+            entity
+        };
+        let hungry_ant = spawn_ant(Energy(10.));
+        let well_fed_ant = spawn_ant(Energy(90.));
+
+        app.update();
+
+        assert_eq!(
+            *app.world.get::<Goal>(hungry_ant).unwrap(),
+            Goal::Eat(leuco_chunk)
+        );
+        assert_eq!(
+            *app.world.get::<Goal>(well_fed_ant).unwrap(),
+            Goal::Work(hatchery)
+        );
+    }
+
+    #[test]
+    fn haulers_never_take_work() {
+        let ant = Id::<Unit>::from_name("ant");
+        let hatchery = Id::<Structure>::from_name("hatchery");
+
+        let mut app = UnitTestApp::new(1)
+            .with_unit(
+                "ant",
+                UnitData {
+                    wandering_behavior: WanderingBehavior::from_iter([(0, 1.0)]),
+                    ..Default::default()
+                },
+            )
+            .build();
+        // Work is the only thing to do around here
+        app.world.resource_mut::<Signals>().add_signal(
+            SignalType::Work(hatchery),
+            TilePos::ZERO,
+            SignalStrength::new(50.),
+        );
+        app.add_system(choose_goal);
+
+        let mut spawn_ant = |job: Job| {
+            let goal = Goal::Wander {
+                remaining_actions: Some(0),
+            };
+            let entity = spawn_unit(&mut app, TilePos::ZERO, ant, goal);
+            app.world.entity_mut(entity).insert(job);
+            entity
+        };
+        let hauler = spawn_ant(Job::Hauler);
+        let generalist = spawn_ant(Job::Any);
+
+        for _ in 0..10 {
+            app.update();
+            assert!(matches!(
+                *app.world.get::<Goal>(hauler).unwrap(),
+                Goal::Wander { .. }
+            ));
+        }
+
+        assert_eq!(
+            *app.world.get::<Goal>(generalist).unwrap(),
+            Goal::Work(hatchery)
+        );
+    }
+
+    #[test]
+    fn workplaces_recruit_units_within_their_work_radius() {
+        use crate::{
+            signals::{emit_signals, Emitter},
+            structures::structure_manifest::StructureData,
+        };
+
+        let ant = Id::<Unit>::from_name("ant");
+        let hatchery = Id::<Structure>::from_name("hatchery");
+        let vat = Id::<Structure>::from_name("vat");
+
+        // The hatchery recruits from far away, while the vat only attracts units that stumble upon it
+        let workplace = |work_radius: u32| StructureData {
+            work_radius,
+            ..Default::default()
+        };
+        let mut structure_manifest = StructureManifest::new();
+        structure_manifest.insert("hatchery", workplace(4));
+        structure_manifest.insert("vat", workplace(0));
+
+        let mut app = UnitTestApp::new(6)
+            .with_unit(
+                "ant",
+                UnitData {
+                    wandering_behavior: WanderingBehavior::from_iter([(0, 1.0)]),
+                    ..Default::default()
+                },
+            )
+            .with_structures(structure_manifest)
+            .build();
+        app.add_systems((emit_signals, choose_goal).chain());
+
+        for structure_id in [hatchery, vat] {
+            app.world.spawn((
+                TilePos::ZERO,
+                structure_id,
+                Emitter {
+                    signals: vec![(SignalType::Work(structure_id), SignalStrength::new(100.))],
+                },
+            ));
+        }
+
+        let distant_tile = TilePos::new(3, 0);
+        let unit_entity = spawn_unit(
+            &mut app,
+            distant_tile,
+            ant,
+            Goal::Wander {
+                remaining_actions: Some(0),
+            },
+        );
+
+        app.update();
+
+        let goal = app.world.get::<Goal>(unit_entity).unwrap().clone();
+        assert_eq!(goal, Goal::Work(hatchery));
+
+        // The signal leads the unit back towards the hatchery
+        let signals = app.world.resource::<Signals>();
+        let map_geometry = app.world.resource::<MapGeometry>();
+        assert_eq!(
+            signals.get(SignalType::Work(vat), distant_tile),
+            SignalStrength::ZERO
+        );
+        let next_step = signals.upstream(distant_tile, &goal, map_geometry).unwrap();
+        assert!(
+            next_step.unsigned_distance_to(TilePos::ZERO.hex)
+                < distant_tile.unsigned_distance_to(TilePos::ZERO.hex)
+        );
+    }
+
+    #[test]
+    fn running_out_of_patience_triggers_the_configured_consequence() {
+        let wanderer = Id::<Unit>::from_name("wanderer");
+        let complainer = Id::<Unit>::from_name("complainer");
+        let hatchery = Id::<Structure>::from_name("hatchery");
+
+        let mut fixture = UnitTestApp::new(1);
+        for (name, impatience_consequence) in [
+            ("wanderer", ImpatienceConsequence::WanderAway { actions: 5 }),
+            ("complainer", ImpatienceConsequence::SignalStuck),
+        ] {
+            fixture = fixture.with_unit(
+                name,
+                UnitData {
+                    max_impatience: 3,
+                    impatience_consequence,
+                    ..Default::default()
+                },
+            );
+        }
+        let mut app = fixture.build();
+        app.add_system(choose_goal);
+
+        // Both units are trying to work at a hatchery that they can't find
+        let wanderer_entity = spawn_unit(&mut app, TilePos::ZERO, wanderer, Goal::Work(hatchery));
+        let complainer_entity =
+            spawn_unit(&mut app, TilePos::ZERO, complainer, Goal::Work(hatchery));
+
+        // Idle until both units run out of patience
+        for _ in 0..3 {
+            app.update();
+            for entity in [wanderer_entity, complainer_entity] {
+                assert_eq!(
+                    *app.world.get::<Goal>(entity).unwrap(),
+                    Goal::Work(hatchery)
+                );
+                app.world
+                    .get_mut::<ImpatiencePool>(entity)
+                    .unwrap()
+                    .increment();
+            }
+        }
+        assert_eq!(
+            app.world
+                .resource::<Signals>()
+                .get(SignalType::Stuck, TilePos::ZERO),
+            SignalStrength::ZERO
+        );
+
+        app.update();
+
+        // One action has already been taken this frame
+        assert_eq!(
+            *app.world.get::<Goal>(wanderer_entity).unwrap(),
+            Goal::Wander {
+                remaining_actions: Some(4)
+            }
+        );
+        assert!(matches!(
+            *app.world.get::<Goal>(complainer_entity).unwrap(),
+            Goal::Wander { .. }
+        ));
+        assert_eq!(
+            app.world
+                .resource::<Signals>()
+                .get(SignalType::Stuck, TilePos::ZERO),
+            SignalStrength::new(ImpatienceConsequence::STUCK_SIGNAL_STRENGTH)
+        );
+        for entity in [wanderer_entity, complainer_entity] {
+            assert!(!app.world.get::<ImpatiencePool>(entity).unwrap().is_full());
+        }
     }
 }
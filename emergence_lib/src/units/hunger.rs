@@ -1,18 +1,19 @@
-//! Logic for finding and eating food when the [`EnergyPool`] is low.
+//! Logic for finding and eating food when the [`EnergyPool`](crate::organisms::energy::EnergyPool) is low.
+//!
+//! Units decide when to eat in [`choose_goal`](super::goals::choose_goal).
 
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    asset_management::manifest::Id,
+    asset_management::{localization::Localization, manifest::Id},
     items::item_manifest::{Item, ItemManifest},
-    organisms::energy::{Energy, EnergyPool},
+    organisms::energy::Energy,
+    signals::{SignalStrength, SignalType, Signals},
+    simulation::geometry::TilePos,
 };
 
-use super::{
-    goals::Goal,
-    unit_manifest::{Unit, UnitManifest},
-};
+use super::item_interaction::UnitInventory;
 
 /// The item(s) that a unit must consume to gain [`Energy`].
 #[derive(Component, Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -30,7 +31,7 @@ impl Diet {
     }
 
     /// The type of item that this unit must consume.
-    pub(super) fn item(&self) -> Id<Item> {
+    pub(crate) fn item(&self) -> Id<Item> {
         self.item
     }
 
@@ -40,28 +41,141 @@ impl Diet {
     }
 
     /// Pretty formatting for this type
-    pub(crate) fn display(&self, item_manifest: &ItemManifest) -> String {
+    pub(crate) fn display(
+        &self,
+        item_manifest: &ItemManifest,
+        localization: &Localization,
+    ) -> String {
         format!(
             "{} -> {} energy",
-            item_manifest.name(self.item),
+            localization.get(item_manifest.name(self.item)),
             self.energy
         )
     }
 }
 
-/// Swaps the goal to [`Goal::Eat`] when energy is low
-pub(super) fn check_for_hunger(
-    mut unit_query: Query<(&mut Goal, &EnergyPool, &Id<Unit>)>,
-    unit_manifest: Res<UnitManifest>,
-) {
-    for (mut goal, energy_pool, unit_id) in unit_query.iter_mut() {
-        if energy_pool.is_hungry() {
-            let diet = &unit_manifest.get(*unit_id).diet;
-            *goal = Goal::Eat(diet.item);
-        } else if matches!(*goal, Goal::Eat(..)) && energy_pool.is_satiated() {
-            *goal = Goal::Wander {
-                remaining_actions: None,
+/// Can the unit at `tile_pos` find any of the `food` item to eat?
+///
+/// Food can be found if the unit is already holding it, or if any signals that lead to it have reached this tile.
+pub(super) fn food_is_available(
+    food: Id<Item>,
+    tile_pos: TilePos,
+    unit_inventory: &UnitInventory,
+    signals: &Signals,
+) -> bool {
+    unit_inventory.held_item == Some(food)
+        || signals.get(SignalType::Push(food), tile_pos) > SignalStrength::ZERO
+        || signals.get(SignalType::Contains(food), tile_pos) > SignalStrength::ZERO
+}
+
+#[cfg(test)]
+mod tests {
+    use leafwing_abilities::prelude::Pool;
+
+    use super::*;
+    use crate::{
+        items::{inventory::ReservedFor, item_manifest::ItemData, ItemCount},
+        organisms::energy::EnergyPool,
+        simulation::geometry::MapGeometry,
+        structures::{construction::Footprint, crafting::StorageInventory},
+        units::{
+            actions::{advance_action_timer, choose_actions, finish_actions, start_actions},
+            goals::{choose_goal, Goal},
+            test_fixture::{spawn_unit, UnitTestApp},
+        },
+    };
+
+    /// Creates an app containing a single hungry ant, which eats leuco chunks.
+    ///
+    /// Returns the app and the entity of the ant.
+    fn hungry_ant_app() -> (App, Entity) {
+        let mut item_manifest = ItemManifest::new();
+        item_manifest.insert("leuco_chunk", ItemData::new(10));
+
+        let mut app = UnitTestApp::new(3)
+            .with_items(item_manifest)
+            .with_timestep(1.0)
+            .build();
+        app.add_systems(
+            (
+                advance_action_timer,
+                start_actions,
+                finish_actions,
+                choose_goal,
+                choose_actions,
+            )
+                .chain(),
+        );
+
+        let ant_entity = spawn_unit(
+            &mut app,
+            TilePos::ZERO,
+            Id::from_name("ant"),
+            Goal::default(),
+        );
+        app.world.entity_mut(ant_entity).insert(EnergyPool::new(
+            Energy(10.),
+            Energy(100.),
+            Energy(0.),
+        ));
+
+        (app, ant_entity)
+    }
+
+    #[test]
+    fn hungry_units_eat_nearby_food() {
+        let (mut app, ant_entity) = hungry_ant_app();
+        let leuco_chunk = Id::from_name("leuco_chunk");
+
+        let mut storage_inventory = StorageInventory::new(1, ReservedFor::Any);
+        storage_inventory
+            .add_item_all_or_nothing(
+                &ItemCount::new(leuco_chunk, 5),
+                app.world.resource::<ItemManifest>(),
+            )
+            .unwrap();
+        let food_tile_pos = TilePos::new(1, 0);
+        let storage_entity = app.world.spawn((storage_inventory, food_tile_pos)).id();
+        app.world.resource_mut::<MapGeometry>().add_structure(
+            food_tile_pos,
+            &Footprint::single(),
+            Id::from_name("storage"),
+            storage_entity,
+        );
+        app.world.resource_mut::<Signals>().add_signal(
+            SignalType::Contains(leuco_chunk),
+            TilePos::ZERO,
+            SignalStrength::new(10.),
+        );
+
+        // Stop as soon as the ant has eaten, before it wanders off
+        for _ in 0..20 {
+            app.update();
+
+            let energy_pool = app.world.get::<EnergyPool>(ant_entity).unwrap();
+            if energy_pool.current() > Energy(10.) {
+                break;
             }
         }
+
+        let energy_pool = app.world.get::<EnergyPool>(ant_entity).unwrap();
+        assert_eq!(energy_pool.current(), Energy(60.));
+
+        let storage_inventory = app.world.get::<StorageInventory>(storage_entity).unwrap();
+        assert_eq!(storage_inventory.item_count(leuco_chunk), 4);
+    }
+
+    #[test]
+    fn starving_units_without_food_keep_wandering() {
+        let (mut app, ant_entity) = hungry_ant_app();
+        let leuco_chunk = Id::from_name("leuco_chunk");
+        app.world
+            .entity_mut(ant_entity)
+            .insert(Goal::Eat(leuco_chunk));
+
+        app.update();
+
+        let goal = app.world.get::<Goal>(ant_entity).unwrap();
+        assert!(matches!(goal, Goal::Wander { .. }));
     }
 }
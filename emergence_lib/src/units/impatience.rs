@@ -3,10 +3,15 @@
 
 use bevy::prelude::*;
 use core::fmt::Display;
+use serde::{Deserialize, Serialize};
+
+use crate::{asset_management::manifest::Id, items::item_manifest::Item};
+
+use super::goals::Goal;
 
 /// The patience of a unit.
 ///
-/// If current >= max, they will abandon their current goal.
+/// If current >= max, they suffer their [`ImpatienceConsequence`].
 #[derive(Debug, Clone, PartialEq, Component, Resource)]
 pub(crate) struct ImpatiencePool {
     /// The current impatience of this unit.
@@ -17,7 +22,7 @@ pub(crate) struct ImpatiencePool {
 
 impl ImpatiencePool {
     /// Creates a new impatience pool with the provided `max` value.
-    pub(super) fn new(max: u8) -> Self {
+    pub(crate) fn new(max: u8) -> Self {
         ImpatiencePool { current: 0, max }
     }
 
@@ -26,6 +31,14 @@ impl ImpatiencePool {
         self.current >= self.max
     }
 
+    /// How close is this unit to running out of patience, from 0 to 1?
+    pub(super) fn fraction_full(&self) -> f32 {
+        match self.max {
+            0 => 1.,
+            max => (self.current as f32 / max as f32).min(1.),
+        }
+    }
+
     /// Increase the current impatience by 1
     pub(super) fn increment(&mut self) {
         self.current += 1;
@@ -42,3 +55,48 @@ impl Display for ImpatiencePool {
         write!(f, "{}/{}", self.current, self.max)
     }
 }
+
+/// What units of a given type do once they have run out of patience.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImpatienceConsequence {
+    /// Give up on the current goal, putting away any held item before choosing something else to do.
+    #[default]
+    Abandon,
+    /// Wander away for the provided number of actions before choosing something else to do.
+    ///
+    /// Any held item is kept.
+    WanderAway {
+        /// The number of actions to wander for.
+        actions: u16,
+    },
+    /// Emit a [`SignalType::Stuck`](crate::signals::SignalType::Stuck) signal to mark the spot where the unit gave up,
+    /// then abandon the current goal.
+    SignalStuck,
+}
+
+impl ImpatienceConsequence {
+    /// The strength of the signal emitted by [`ImpatienceConsequence::SignalStuck`].
+    pub(crate) const STUCK_SIGNAL_STRENGTH: f32 = 10.;
+
+    /// The goal that a unit pursuing `current_goal` while holding `held_item` switches to once it has run out of patience.
+    pub(super) fn next_goal(&self, current_goal: &Goal, held_item: Option<Id<Item>>) -> Goal {
+        match self {
+            ImpatienceConsequence::Abandon | ImpatienceConsequence::SignalStuck => {
+                match held_item {
+                    // Don't get stuck trying to do a hopeless storage task forever
+                    Some(held_item)
+                        if !matches!(current_goal, Goal::Store(..) | Goal::Wander { .. }) =>
+                    {
+                        Goal::Store(held_item)
+                    }
+                    _ => Goal::Wander {
+                        remaining_actions: None,
+                    },
+                }
+            }
+            ImpatienceConsequence::WanderAway { actions } => Goal::Wander {
+                remaining_actions: Some(*actions),
+            },
+        }
+    }
+}
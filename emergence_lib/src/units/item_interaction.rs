@@ -1,12 +1,22 @@
 //! Holding, using and carrying items.
 
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    utils::{Duration, HashMap},
+};
 
 use crate::{
-    asset_management::manifest::Id,
-    items::item_manifest::{Item, ItemManifest},
+    asset_management::{localization::Localization, manifest::Id},
+    items::{
+        item_manifest::{Item, ItemManifest},
+        ItemCount,
+    },
+    signals::{Emitter, SignalStrength, SignalType},
+    simulation::geometry::TilePos,
 };
 
+use super::unit_manifest::Unit;
+
 /// The item(s) that a unit is carrying.
 #[derive(Component, Default, Clone, Debug, Deref, DerefMut)]
 pub(crate) struct UnitInventory {
@@ -15,12 +25,207 @@ pub(crate) struct UnitInventory {
 }
 
 impl UnitInventory {
+    /// The number of items that a unit picks up or drops off at once.
+    pub(crate) const CAPACITY: usize = 1;
+
+    /// The items that a unit holding `item_id` is carrying.
+    ///
+    /// Use this when checking whether a unit's load will fit somewhere, rather than assuming a single item.
+    pub(crate) fn load(item_id: Id<Item>) -> ItemCount {
+        ItemCount::new(item_id, UnitInventory::CAPACITY)
+    }
+
     /// Pretty foramtting for this type.
-    pub(crate) fn display(&self, item_manifest: &ItemManifest) -> String {
+    pub(crate) fn display(
+        &self,
+        item_manifest: &ItemManifest,
+        localization: &Localization,
+    ) -> String {
         if let Some(item) = self.held_item {
-            item_manifest.name(item).to_string()
+            localization.get(item_manifest.name(item)).to_string()
         } else {
             "Nothing".to_string()
         }
     }
 }
+
+/// An event sent whenever a unit starts moving an item to or from a structure.
+///
+/// This is used to animate the transfer, and has no effect on gameplay.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ItemTransferStarted {
+    /// The type of item being moved.
+    pub(crate) item_id: Id<Item>,
+    /// The entity that the item is being taken from.
+    pub(crate) source: Entity,
+    /// The entity that the item is being given to.
+    pub(crate) destination: Entity,
+    /// How long the transfer will take.
+    pub(crate) duration: Duration,
+}
+
+/// An event sent whenever a unit finishes picking up or dropping off an item.
+///
+/// This is used to visualize the flow of items, and has no effect on gameplay.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ItemTransferred {
+    /// The type of item that was moved.
+    pub(crate) item_id: Id<Item>,
+    /// The tile that the unit was standing on.
+    pub(crate) tile_pos: TilePos,
+}
+
+/// The number of units that are currently picking up items from each source.
+///
+/// Heavily reserved sources are less attractive, spreading units out across the available sources.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct PickupReservations {
+    /// The number of outstanding pickups for each source entity.
+    ///
+    /// Sources with no reservations are not stored.
+    reservations: HashMap<Entity, u8>,
+}
+
+impl PickupReservations {
+    /// Records that a unit has started picking up an item from the `source` entity.
+    pub(crate) fn reserve(&mut self, source: Entity) {
+        let count = self.reservations.entry(source).or_default();
+        *count = count.saturating_add(1);
+    }
+
+    /// Records that a unit has finished picking up an item from the `source` entity.
+    pub(crate) fn release(&mut self, source: Entity) {
+        if let Some(count) = self.reservations.get_mut(&source) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.reservations.remove(&source);
+            }
+        }
+    }
+
+    /// The number of units currently picking up items from the `source` entity.
+    pub(crate) fn get(&self, source: Entity) -> u8 {
+        self.reservations.get(&source).copied().unwrap_or_default()
+    }
+
+    /// How attractive the `source` entity is, relative to an unreserved source.
+    ///
+    /// Each additional reservation has a progressively larger effect.
+    pub(crate) fn attractiveness(&self, source: Entity) -> f32 {
+        let crowding = 1. + self.get(source) as f32;
+        crowding.powi(-2)
+    }
+}
+
+/// Controls whether or not units advertise the item they are carrying.
+///
+/// When enabled, carriers emit a faint [`SignalType::Push`] signal for their held item,
+/// making roaming units part of the logistics gradient.
+/// This adds an emitter per carrying unit, so it can be disabled for performance.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub(crate) struct CargoSignals {
+    /// When `true`, units carrying an item will emit a push signal for it.
+    pub(crate) enabled: bool,
+    /// The strength of the push signal emitted by each carrying unit.
+    pub(crate) strength: f32,
+}
+
+impl Default for CargoSignals {
+    fn default() -> Self {
+        CargoSignals {
+            enabled: false,
+            strength: 1.,
+        }
+    }
+}
+
+/// Sets the signals that each unit emits, based on its identity and what it is carrying.
+pub(super) fn set_unit_emitter(
+    cargo_signals: Res<CargoSignals>,
+    mut unit_query: Query<(&Id<Unit>, &UnitInventory, &mut Emitter)>,
+) {
+    for (&unit_id, unit_inventory, mut emitter) in unit_query.iter_mut() {
+        emitter.signals.clear();
+        emitter
+            .signals
+            .push((SignalType::Unit(unit_id), SignalStrength::new(1.)));
+
+        if !cargo_signals.enabled {
+            continue;
+        }
+
+        if let Some(item_id) = unit_inventory.held_item {
+            emitter.signals.push((
+                SignalType::Push(item_id),
+                SignalStrength::new(cargo_signals.strength),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        signals::{SignalPropagationConfig, Signals},
+        simulation::geometry::MapGeometry,
+        units::{
+            goals::Goal,
+            test_fixture::{spawn_unit, UnitTestApp},
+        },
+    };
+
+    fn emitted_signals(cargo_signals: CargoSignals, held_item: Option<Id<Item>>) -> Signals {
+        let mut app = UnitTestApp::new(1).build();
+        app.insert_resource(cargo_signals)
+            .add_system(set_unit_emitter);
+
+        let unit_entity = spawn_unit(
+            &mut app,
+            TilePos::ZERO,
+            Id::from_name("ant"),
+            Goal::default(),
+        );
+        app.world
+            .entity_mut(unit_entity)
+            .insert(UnitInventory { held_item });
+
+        app.update();
+
+        let mut signals = Signals::default();
+        let emitter = app.world.get::<Emitter>(unit_entity).unwrap();
+        for &(signal_type, signal_strength) in &emitter.signals {
+            signals.add_signal(signal_type, TilePos::ZERO, signal_strength);
+        }
+        signals.diffuse(&MapGeometry::new(1), &SignalPropagationConfig::default());
+        signals
+    }
+
+    #[test]
+    fn carrying_units_advertise_their_cargo_to_neighbors() {
+        let item_id = Id::from_name("leaf");
+        let neighbor = TilePos::ZERO.neighbor(hexx::Direction::Top);
+
+        let enabled = CargoSignals {
+            enabled: true,
+            ..Default::default()
+        };
+        let signals = emitted_signals(enabled.clone(), Some(item_id));
+        assert!(signals.get(SignalType::Push(item_id), neighbor) > SignalStrength::ZERO);
+        assert!(
+            signals.get(SignalType::Unit(Id::from_name("ant")), neighbor) > SignalStrength::ZERO
+        );
+
+        let signals = emitted_signals(enabled, None);
+        assert_eq!(
+            signals.get(SignalType::Push(item_id), neighbor),
+            SignalStrength::ZERO
+        );
+
+        let signals = emitted_signals(CargoSignals::default(), Some(item_id));
+        assert_eq!(
+            signals.get(SignalType::Push(item_id), neighbor),
+            SignalStrength::ZERO
+        );
+    }
+}
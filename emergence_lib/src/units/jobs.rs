@@ -0,0 +1,100 @@
+//! Jobs restrict which goals a unit is willing to pursue.
+//!
+//! By default, units will do whatever the signals around them suggest.
+//! Assigning a job lets the player dedicate some units to a particular kind of work,
+//! so that (for example) haulers keep items flowing even when there is plenty of crafting to do.
+
+use bevy::prelude::*;
+
+use super::goals::Goal;
+
+/// The kind of work that a unit is willing to take on.
+///
+/// Units can always wander, eat and put away the items they are holding, regardless of their job.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Job {
+    /// Pursues any goal.
+    #[default]
+    Any,
+    /// Picks up, stores and delivers items.
+    Hauler,
+    /// Builds, repairs, upgrades and demolishes structures, and terraforms the land.
+    ///
+    /// Ghost structures request construction via [`SignalType::Work`](crate::signals::SignalType::Work)
+    /// and [`SignalType::Pull`](crate::signals::SignalType::Pull), just like crafting structures do.
+    /// Builders accept those goals, but only ever work at or deliver to ghosts: see [`Job::only_serves_ghosts`].
+    Builder,
+    /// Works at structures to craft recipes.
+    Crafter,
+}
+
+impl Job {
+    /// Is a unit with this job willing to pursue `goal`?
+    pub(crate) fn permits(&self, goal: &Goal) -> bool {
+        match goal {
+            Goal::Wander { .. } | Goal::Explore { .. } | Goal::Eat(..) | Goal::Store(..) => true,
+            Goal::Pickup(..) => matches!(self, Job::Any | Job::Hauler),
+            Goal::Deliver(..) => matches!(self, Job::Any | Job::Hauler | Job::Builder),
+            Goal::Work(..) => matches!(self, Job::Any | Job::Builder | Job::Crafter),
+            Goal::Demolish(..) | Goal::Repair(..) | Goal::Upgrade(..) | Goal::Terraform => {
+                matches!(self, Job::Any | Job::Builder)
+            }
+        }
+    }
+
+    /// Does this job limit [`Goal::Work`] and [`Goal::Deliver`] to ghost structures?
+    ///
+    /// Goals don't record whether they were requested by a ghost, so this is checked when choosing a target.
+    pub(crate) fn only_serves_ghosts(&self) -> bool {
+        matches!(self, Job::Builder)
+    }
+
+    /// The job that follows this one, used when cycling through jobs.
+    pub(crate) fn next(&self) -> Job {
+        match self {
+            Job::Any => Job::Hauler,
+            Job::Hauler => Job::Builder,
+            Job::Builder => Job::Crafter,
+            Job::Crafter => Job::Any,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset_management::manifest::Id;
+
+    #[test]
+    fn jobs_restrict_goals() {
+        let work = Goal::Work(Id::from_name("hatchery"));
+        let pickup = Goal::Pickup(Id::from_name("leuco_chunk"));
+        let store = Goal::Store(Id::from_name("leuco_chunk"));
+        let deliver = Goal::Deliver(Id::from_name("leuco_chunk"));
+        let demolish = Goal::Demolish(Id::from_name("hatchery"));
+
+        assert!(!Job::Hauler.permits(&work));
+        assert!(Job::Hauler.permits(&pickup));
+        assert!(Job::Crafter.permits(&work));
+        assert!(!Job::Crafter.permits(&demolish));
+        assert!(Job::Builder.permits(&demolish));
+        assert!(!Job::Builder.permits(&pickup));
+        assert!(Job::Builder.permits(&work));
+        assert!(Job::Builder.permits(&deliver));
+        assert!(!Job::Crafter.permits(&deliver));
+
+        for job in [Job::Any, Job::Hauler, Job::Builder, Job::Crafter] {
+            assert!(job.permits(&Goal::default()));
+            assert!(job.permits(&store));
+        }
+    }
+
+    #[test]
+    fn cycling_visits_every_job() {
+        let mut job = Job::Any;
+        for expected in [Job::Hauler, Job::Builder, Job::Crafter, Job::Any] {
+            job = job.next();
+            assert_eq!(job, expected);
+        }
+    }
+}
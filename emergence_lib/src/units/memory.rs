@@ -0,0 +1,101 @@
+//! Short-term memory that stops units from repeatedly picking targets that just failed them.
+
+use bevy::{prelude::*, utils::HashMap};
+
+/// The entities that a unit recently failed to interact with, which it will avoid for a while.
+///
+/// Without this, units can get stuck in loops,
+/// repeatedly choosing the same unreachable item source or full storage.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FailedTargets {
+    /// The number of simulation ticks that each failed target is avoided for.
+    memory_duration: u16,
+    /// The remaining number of ticks that each target will be avoided for.
+    remaining: HashMap<Entity, u16>,
+}
+
+impl Default for FailedTargets {
+    fn default() -> Self {
+        FailedTargets::new(FailedTargets::DEFAULT_MEMORY_DURATION)
+    }
+}
+
+impl FailedTargets {
+    /// The default number of ticks that failed targets are avoided for.
+    pub(crate) const DEFAULT_MEMORY_DURATION: u16 = 40;
+
+    /// Creates an empty memory, which avoids failed targets for `memory_duration` ticks.
+    pub(crate) fn new(memory_duration: u16) -> Self {
+        FailedTargets {
+            memory_duration,
+            remaining: HashMap::default(),
+        }
+    }
+
+    /// Avoids `target` for the next `memory_duration` ticks.
+    ///
+    /// If the target was already being avoided, the timer is restarted.
+    pub(crate) fn remember(&mut self, target: Entity) {
+        if self.memory_duration > 0 {
+            self.remaining.insert(target, self.memory_duration);
+        }
+    }
+
+    /// Is this unit currently avoiding `target`?
+    pub(crate) fn is_avoiding(&self, target: Entity) -> bool {
+        self.remaining.contains_key(&target)
+    }
+
+    /// Counts down the memory of each failed target by a single tick, forgetting any that have expired.
+    fn tick(&mut self) {
+        self.remaining.retain(|_, remaining| {
+            *remaining -= 1;
+            *remaining > 0
+        });
+    }
+}
+
+/// Slowly forgets the targets that units have failed to interact with, so they can be tried again.
+pub(super) fn forget_failed_targets(mut unit_query: Query<&mut FailedTargets>) {
+    for mut failed_targets in unit_query.iter_mut() {
+        if !failed_targets.remaining.is_empty() {
+            failed_targets.tick();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_targets_are_forgotten_after_the_memory_duration() {
+        let mut failed_targets = FailedTargets::new(3);
+        let blocked = Entity::from_raw(0);
+        let other = Entity::from_raw(1);
+
+        failed_targets.remember(blocked);
+        assert!(failed_targets.is_avoiding(blocked));
+        assert!(!failed_targets.is_avoiding(other));
+
+        failed_targets.tick();
+        failed_targets.tick();
+        assert!(failed_targets.is_avoiding(blocked));
+
+        // Failing again restarts the timer
+        failed_targets.remember(blocked);
+        failed_targets.tick();
+        failed_targets.tick();
+        assert!(failed_targets.is_avoiding(blocked));
+
+        failed_targets.tick();
+        assert!(!failed_targets.is_avoiding(blocked));
+    }
+
+    #[test]
+    fn units_without_memory_never_avoid_targets() {
+        let mut failed_targets = FailedTargets::new(0);
+        failed_targets.remember(Entity::from_raw(0));
+        assert!(!failed_targets.is_avoiding(Entity::from_raw(0)));
+    }
+}
@@ -5,23 +5,33 @@ use crate::{
         manifest::{plugin::ManifestPlugin, Id, Manifest},
         AssetCollectionExt,
     },
-    player_interaction::InteractionSystem,
+    player_interaction::{contracts::Contracts, InteractionSystem},
     signals::{Emitter, SignalStrength, SignalType},
     simulation::{
-        geometry::{Facing, MapGeometry, TilePos},
+        factions::Faction,
+        geometry::{sync_rotation_to_facing, Facing, MapGeometry, TilePos},
         SimulationSet,
     },
+    structures::crafting::progress_crafting,
 };
 use bevy::prelude::*;
 use bevy_mod_raycast::RaycastMesh;
-use rand::{distributions::WeightedIndex, prelude::Distribution, rngs::ThreadRng};
+use hexx::Direction;
+use rand::{distributions::WeightedIndex, prelude::Distribution, rngs::ThreadRng, Rng};
 use serde::{Deserialize, Serialize};
 
 use self::{
     actions::CurrentAction,
-    goals::Goal,
+    collision::UnitCollision,
+    goals::{Goal, GoalWeights},
     impatience::ImpatiencePool,
-    item_interaction::UnitInventory,
+    item_interaction::{
+        CargoSignals, ItemTransferStarted, ItemTransferred, PickupReservations, UnitInventory,
+    },
+    jobs::Job,
+    memory::FailedTargets,
+    population::Population,
+    status_effects::StatusEffects,
     unit_assets::UnitHandles,
     unit_manifest::{RawUnitManifest, Unit, UnitData},
 };
@@ -29,11 +39,19 @@ use self::{
 use crate::organisms::OrganismBundle;
 
 pub(crate) mod actions;
+pub mod collision;
+pub(crate) mod commands;
+pub(crate) mod exploration;
 pub(crate) mod goals;
 pub mod hunger;
-pub(crate) mod impatience;
+pub mod impatience;
 pub(crate) mod item_interaction;
-mod reproduction;
+pub(crate) mod jobs;
+pub(crate) mod memory;
+pub(crate) mod population;
+pub mod status_effects;
+#[cfg(test)]
+pub(crate) mod test_fixture;
 pub(crate) mod unit_assets;
 pub mod unit_manifest;
 
@@ -44,9 +62,45 @@ pub struct WanderingBehavior {
     ///
     /// The [`f32`] represents the relative probability of each value.
     wander_durations: Vec<(u16, f32)>,
+    /// An optional home tile and strength, used to make wandering units drift away from (or towards) home.
+    ///
+    /// The strength is the probability that any given wandering step follows the bias.
+    /// Positive values push units outward from the home tile, while negative values pull them back in.
+    #[serde(default)]
+    bias: Option<(TilePos, f32)>,
 }
 
 impl WanderingBehavior {
+    /// Sets the directional bias of this wandering behavior.
+    ///
+    /// See the `bias` field for the meaning of `strength`.
+    pub fn with_bias(mut self, home: TilePos, strength: f32) -> Self {
+        self.bias = Some((home, strength));
+        self
+    }
+
+    /// Chooses the direction that a wandering unit at `tile_pos` should try to face this step.
+    ///
+    /// Returns [`None`] if the unit should wander randomly instead.
+    pub(crate) fn preferred_direction(
+        &self,
+        tile_pos: TilePos,
+        rng: &mut ThreadRng,
+    ) -> Option<Direction> {
+        let (home, strength) = self.bias?;
+
+        // There's no meaningful "outward" direction when standing on the home tile
+        if tile_pos == home || rng.gen::<f32>() >= strength.abs() {
+            return None;
+        }
+
+        let outward = home.direction_to(tile_pos.hex);
+        match strength > 0. {
+            true => Some(outward),
+            false => Some(outward.const_neg()),
+        }
+    }
+
     /// Randomly choose the number of actions to take while wandering.
     fn sample(&self, rng: &mut ThreadRng) -> u16 {
         let weights = self.wander_durations.iter().map(|(_, weight)| *weight);
@@ -60,7 +114,10 @@ impl FromIterator<(u16, f32)> for WanderingBehavior {
     fn from_iter<T: IntoIterator<Item = (u16, f32)>>(iter: T) -> Self {
         let wander_durations = Vec::from_iter(iter);
 
-        WanderingBehavior { wander_durations }
+        WanderingBehavior {
+            wander_durations,
+            bias: None,
+        }
     }
 }
 
@@ -75,6 +132,10 @@ pub(crate) struct UnitBundle {
     facing: Facing,
     /// What is the unit working towards.
     current_goal: Goal,
+    /// Which goals is the unit willing to pursue?
+    job: Job,
+    /// The targets this unit recently failed to interact with, and is avoiding.
+    failed_targets: FailedTargets,
     /// How frustrated this unit is.
     ///
     /// When full, the current goal will be abandoned.
@@ -83,6 +144,8 @@ pub(crate) struct UnitBundle {
     current_action: CurrentAction,
     /// What is the unit currently holding, if anything?
     held_item: UnitInventory,
+    /// Temporary effects that change how quickly the unit moves and works.
+    status_effects: StatusEffects,
     /// What signals is this unit emitting?
     emitter: Emitter,
     /// Organism data
@@ -93,6 +156,8 @@ pub(crate) struct UnitBundle {
     mesh: Handle<Mesh>,
     /// The child scene that contains the gltF model used
     scene_bundle: SceneBundle,
+    /// The faction that controls this unit
+    faction: Faction,
 }
 
 impl UnitBundle {
@@ -111,9 +176,12 @@ impl UnitBundle {
             tile_pos,
             facing: Facing::default(),
             current_goal: Goal::default(),
+            job: Job::default(),
+            failed_targets: FailedTargets::default(),
             impatience: ImpatiencePool::new(unit_data.max_impatience),
             current_action: CurrentAction::default(),
             held_item: UnitInventory::default(),
+            status_effects: StatusEffects::default(),
             emitter: Emitter {
                 signals: vec![(SignalType::Unit(unit_id), SignalStrength::new(1.))],
             },
@@ -128,6 +196,7 @@ impl UnitBundle {
                 transform: Transform::from_translation(tile_pos.into_world_pos(map_geometry)),
                 ..default()
             },
+            faction: Faction::default(),
         }
     }
 }
@@ -151,9 +220,23 @@ impl Plugin for UnitsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(ManifestPlugin::<RawUnitManifest>::new())
             .add_asset_collection::<UnitHandles>()
+            .init_resource::<UnitCollision>()
+            .init_resource::<CargoSignals>()
+            .init_resource::<Population>()
+            .init_resource::<GoalWeights>()
+            .init_resource::<PickupReservations>()
+            // Contracts are designated by the player, but units need them even when the game is simulated headlessly
+            .init_resource::<Contracts>()
+            .add_event::<ItemTransferStarted>()
+            .add_event::<ItemTransferred>()
             .add_systems(
                 (
                     actions::advance_action_timer.in_set(UnitSystem::AdvanceTimers),
+                    collision::sync_unit_occupancy
+                        .after(UnitSystem::AdvanceTimers)
+                        .before(UnitSystem::Act),
+                    status_effects::tick_status_effects.in_set(UnitSystem::AdvanceTimers),
+                    memory::forget_failed_targets.in_set(UnitSystem::AdvanceTimers),
                     actions::start_actions
                         .in_set(UnitSystem::Act)
                         .before(actions::finish_actions),
@@ -163,16 +246,83 @@ impl Plugin for UnitsPlugin {
                         // This must occur after MarkedForDemolition is added,
                         // or we'll get a panic due to inserting a component on a despawned entity
                         .after(InteractionSystem::ManagePreviews),
+                    actions::interrupt_hazardous_actions
+                        .after(UnitSystem::Act)
+                        .before(UnitSystem::ChooseNewAction),
+                    exploration::reveal_around_units
+                        .after(UnitSystem::Act)
+                        .before(UnitSystem::ChooseGoal),
                     goals::choose_goal.in_set(UnitSystem::ChooseGoal),
                     actions::choose_actions
                         .in_set(UnitSystem::ChooseNewAction)
                         .after(UnitSystem::Act)
                         .after(UnitSystem::ChooseGoal),
-                    reproduction::hatch_ant_eggs,
-                    hunger::check_for_hunger.before(UnitSystem::ChooseNewAction),
+                    population::count_population.before(progress_crafting),
+                    item_interaction::set_unit_emitter.after(UnitSystem::Act),
                 )
                     .in_set(SimulationSet)
                     .in_schedule(CoreSchedule::FixedUpdate),
-            );
+            )
+            // Overrides the rotation of units while they are spinning
+            .add_system(actions::interpolate_unit_transforms.after(sync_rotation_to_facing));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::simulation::geometry::RotationDirection;
+
+    /// Simulates a unit wandering across an unobstructed plane, returning its final distance from the origin.
+    fn wander(wandering_behavior: &WanderingBehavior, n_steps: usize) -> u32 {
+        let rng = &mut thread_rng();
+        let mut tile_pos = TilePos::default();
+        let mut facing = Facing::default();
+
+        for _ in 0..n_steps {
+            match wandering_behavior.preferred_direction(tile_pos, rng) {
+                Some(direction) => facing.direction = direction,
+                None => match RotationDirection::random(rng) {
+                    RotationDirection::Left => facing.rotate_left(),
+                    RotationDirection::Right => facing.rotate_right(),
+                },
+            }
+
+            tile_pos = tile_pos.neighbor(facing.direction);
+        }
+
+        tile_pos.unsigned_distance_to(TilePos::default().hex)
+    }
+
+    #[test]
+    fn directional_bias_causes_outward_drift() {
+        const N_TRIALS: usize = 100;
+        const N_STEPS: usize = 100;
+
+        let unbiased = WanderingBehavior::from_iter([(1, 1.0)]);
+        let outward = unbiased.clone().with_bias(TilePos::default(), 0.5);
+        let inward = unbiased.clone().with_bias(TilePos::default(), -0.5);
+
+        let mean_distance = |wandering_behavior: &WanderingBehavior| {
+            (0..N_TRIALS)
+                .map(|_| wander(wandering_behavior, N_STEPS))
+                .sum::<u32>() as f32
+                / N_TRIALS as f32
+        };
+
+        let unbiased_distance = mean_distance(&unbiased);
+        let outward_distance = mean_distance(&outward);
+        let inward_distance = mean_distance(&inward);
+
+        assert!(
+            outward_distance > 2. * unbiased_distance,
+            "Outward: {outward_distance}, unbiased: {unbiased_distance}"
+        );
+        assert!(
+            inward_distance < unbiased_distance,
+            "Inward: {inward_distance}, unbiased: {unbiased_distance}"
+        );
     }
 }
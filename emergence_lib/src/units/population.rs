@@ -0,0 +1,122 @@
+//! Limits on how many units can exist at once.
+//!
+//! Without a cap, hatcheries would keep producing units until the simulation slows to a crawl.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::asset_management::manifest::Id;
+
+use super::unit_manifest::{Unit, UnitManifest};
+
+/// Tracks the number of units of each type, and how many more are allowed.
+///
+/// The counts are rebuilt from scratch each tick,
+/// and are incremented whenever a recipe that spawns a unit is allowed to complete.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub(crate) struct Population {
+    /// The number of units of each type, including those that are about to hatch.
+    counts: HashMap<Id<Unit>, u32>,
+    /// The maximum number of units of each type, as set by the unit manifest.
+    ///
+    /// Unit types without a cap are not stored.
+    caps: HashMap<Id<Unit>, u32>,
+    /// The total number of units of any type, including those that are about to hatch.
+    total: u32,
+    /// The maximum number of units of any type.
+    pub(crate) global_cap: u32,
+}
+
+impl Default for Population {
+    fn default() -> Self {
+        Population::new(Population::DEFAULT_GLOBAL_CAP)
+    }
+}
+
+impl Population {
+    /// The default maximum number of units that can exist at once.
+    pub(crate) const DEFAULT_GLOBAL_CAP: u32 = 1000;
+
+    /// Creates an empty population, which allows at most `global_cap` units.
+    pub(crate) fn new(global_cap: u32) -> Self {
+        Population {
+            counts: HashMap::default(),
+            caps: HashMap::default(),
+            total: 0,
+            global_cap,
+        }
+    }
+
+    /// The number of units of type `unit_id`.
+    pub(crate) fn get(&self, unit_id: Id<Unit>) -> u32 {
+        self.counts.get(&unit_id).copied().unwrap_or_default()
+    }
+
+    /// Can another unit of type `unit_id` be spawned without exceeding either cap?
+    pub(crate) fn has_room(&self, unit_id: Id<Unit>) -> bool {
+        if self.total >= self.global_cap {
+            return false;
+        }
+
+        match self.caps.get(&unit_id) {
+            Some(&cap) => self.get(unit_id) < cap,
+            None => true,
+        }
+    }
+
+    /// Claims a slot for a new unit of type `unit_id`, returning `false` if the population is capped.
+    ///
+    /// This ensures that several hatcheries finishing at once can't overshoot the cap.
+    pub(crate) fn try_reserve(&mut self, unit_id: Id<Unit>) -> bool {
+        if !self.has_room(unit_id) {
+            return false;
+        }
+
+        *self.counts.entry(unit_id).or_default() += 1;
+        self.total += 1;
+        true
+    }
+}
+
+/// Counts the number of units of each type, and refreshes the caps from the manifest.
+pub(crate) fn count_population(
+    unit_query: Query<&Id<Unit>>,
+    unit_manifest: Res<UnitManifest>,
+    mut population: ResMut<Population>,
+) {
+    population.counts.clear();
+    population.caps.clear();
+    population.total = 0;
+
+    for (unit_id, unit_data) in unit_manifest.iter() {
+        if let Some(max_population) = unit_data.max_population {
+            population.caps.insert(unit_id, max_population);
+        }
+    }
+
+    for &unit_id in unit_query.iter() {
+        *population.counts.entry(unit_id).or_default() += 1;
+        population.total += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn population_respects_global_and_per_type_caps() {
+        let ant = Id::from_name("ant");
+        let beetle = Id::from_name("beetle");
+
+        let mut population = Population::new(3);
+        population.caps.insert(ant, 2);
+
+        assert!(population.try_reserve(ant));
+        assert!(population.try_reserve(ant));
+        assert!(!population.try_reserve(ant));
+        assert_eq!(population.get(ant), 2);
+
+        assert!(population.try_reserve(beetle));
+        assert!(!population.has_room(beetle));
+    }
+}
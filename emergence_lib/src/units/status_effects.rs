@@ -0,0 +1,185 @@
+//! Temporary buffs and debuffs that change how quickly units move and work.
+
+use bevy::{ecs::system::Command, prelude::*, utils::Duration};
+
+/// A temporary modifier to the speed of a unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusEffect {
+    /// The name of this effect, such as "tired" or "hasted".
+    pub name: String,
+    /// How much faster this effect makes the unit.
+    ///
+    /// Values greater than 1 speed the unit up, while values between 0 and 1 slow it down.
+    pub speed_multiplier: f32,
+    /// How much longer this effect will last.
+    remaining: Duration,
+}
+
+impl StatusEffect {
+    /// Creates a new [`StatusEffect`] that lasts for `duration`.
+    pub fn new(name: impl Into<String>, speed_multiplier: f32, duration: Duration) -> Self {
+        StatusEffect {
+            name: name.into(),
+            speed_multiplier,
+            remaining: duration,
+        }
+    }
+
+    /// Has this effect run out?
+    pub fn is_expired(&self) -> bool {
+        self.remaining.is_zero()
+    }
+}
+
+/// The set of [`StatusEffect`]s currently affecting a unit.
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct StatusEffects {
+    /// The active effects.
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    /// The slowest that a unit can be made by its status effects.
+    ///
+    /// This prevents timers from becoming infinitely long.
+    const MIN_SPEED_MULTIPLIER: f32 = 0.01;
+
+    /// Adds a new `status_effect`.
+    ///
+    /// Effects with the same name stack.
+    pub fn apply(&mut self, status_effect: StatusEffect) {
+        self.effects.push(status_effect);
+    }
+
+    /// The combined speed multiplier of all active effects.
+    pub fn speed_multiplier(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(|effect| effect.speed_multiplier)
+            .product::<f32>()
+            .max(StatusEffects::MIN_SPEED_MULTIPLIER)
+    }
+
+    /// Advances the time remaining on each effect by `delta`, removing any that have expired.
+    pub(super) fn tick(&mut self, delta: Duration) {
+        for effect in self.effects.iter_mut() {
+            effect.remaining = effect.remaining.saturating_sub(delta);
+        }
+
+        self.effects.retain(|effect| !effect.is_expired());
+    }
+
+    /// Iterates over the currently active effects.
+    pub fn iter(&self) -> impl Iterator<Item = &StatusEffect> {
+        self.effects.iter()
+    }
+}
+
+/// Ticks down and removes expired [`StatusEffects`].
+pub(super) fn tick_status_effects(
+    mut query: Query<&mut StatusEffects>,
+    fixed_time: Res<FixedTime>,
+) {
+    let delta = fixed_time.period;
+
+    for mut status_effects in query.iter_mut() {
+        // Avoid triggering change detection for units that aren't affected by anything
+        if status_effects.effects.is_empty() {
+            continue;
+        }
+
+        status_effects.tick(delta);
+    }
+}
+
+/// An extension trait for [`Commands`] for working with [`StatusEffects`].
+pub trait StatusEffectCommandsExt {
+    /// Applies a [`StatusEffect`] to `entity` for `duration`.
+    ///
+    /// Adds a [`StatusEffects`] component to the entity if needed.
+    fn apply_status_effect(
+        &mut self,
+        entity: Entity,
+        name: impl Into<String>,
+        speed_multiplier: f32,
+        duration: Duration,
+    );
+}
+
+impl<'w, 's> StatusEffectCommandsExt for Commands<'w, 's> {
+    fn apply_status_effect(
+        &mut self,
+        entity: Entity,
+        name: impl Into<String>,
+        speed_multiplier: f32,
+        duration: Duration,
+    ) {
+        self.add(ApplyStatusEffectCommand {
+            entity,
+            status_effect: StatusEffect::new(name, speed_multiplier, duration),
+        });
+    }
+}
+
+/// A [`Command`] used to apply a status effect via [`StatusEffectCommandsExt`].
+struct ApplyStatusEffectCommand {
+    /// The entity to apply the effect to.
+    entity: Entity,
+    /// The effect to apply.
+    status_effect: StatusEffect,
+}
+
+impl Command for ApplyStatusEffectCommand {
+    fn write(self, world: &mut World) {
+        let Some(mut entity_mut) = world.get_entity_mut(self.entity) else {
+            warn!(
+                "Tried to apply a status effect to {:?}, which does not exist.",
+                self.entity
+            );
+            return;
+        };
+
+        match entity_mut.get_mut::<StatusEffects>() {
+            Some(mut status_effects) => status_effects.apply(self.status_effect),
+            None => {
+                let mut status_effects = StatusEffects::default();
+                status_effects.apply(self.status_effect);
+                entity_mut.insert(status_effects);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effects_stack_and_expire() {
+        let mut status_effects = StatusEffects::default();
+        assert_eq!(status_effects.speed_multiplier(), 1.0);
+
+        status_effects.apply(StatusEffect::new("hasted", 2.0, Duration::from_secs(2)));
+        status_effects.apply(StatusEffect::new("tired", 0.5, Duration::from_secs(1)));
+        assert_eq!(status_effects.speed_multiplier(), 1.0);
+
+        status_effects.tick(Duration::from_secs(1));
+        assert_eq!(status_effects.speed_multiplier(), 2.0);
+
+        status_effects.tick(Duration::from_secs(1));
+        assert_eq!(status_effects.iter().count(), 0);
+    }
+
+    #[test]
+    fn commands_add_status_effects() {
+        let mut app = App::new();
+        let entity = app.world.spawn_empty().id();
+        app.add_system(move |mut commands: Commands| {
+            commands.apply_status_effect(entity, "hasted", 2.0, Duration::from_secs(1));
+        });
+        app.update();
+
+        let status_effects = app.world.get::<StatusEffects>(entity).unwrap();
+        assert_eq!(status_effects.speed_multiplier(), 2.0);
+    }
+}
@@ -0,0 +1,170 @@
+//! A shared fixture for testing how units behave.
+
+use bevy::{
+    prelude::{App, Entity, FixedTime, Handle, Mesh},
+    utils::HashMap,
+};
+use hexx::{shapes::hexagon, Hex};
+
+use crate::{
+    asset_management::manifest::Id,
+    items::{item_manifest::ItemManifest, tech_tree::TechTree},
+    player_interaction::contracts::Contracts,
+    signals::Signals,
+    simulation::{
+        factions::Faction,
+        geometry::{Height, MapGeometry, TilePos},
+    },
+    structures::{colony_center::ColonyCenter, structure_manifest::StructureManifest},
+    terrain::terrain_manifest::{Terrain, TerrainData, TerrainManifest, WalkingSpeed},
+};
+
+use super::{
+    collision::UnitCollision,
+    commands::SpawnUnitCommand,
+    goals::{Goal, GoalWeights},
+    item_interaction::{ItemTransferStarted, ItemTransferred, PickupReservations},
+    unit_assets::UnitHandles,
+    unit_manifest::{Unit, UnitData, UnitManifest},
+};
+
+/// Builds an [`App`] containing every resource and event that the unit behavior systems rely on.
+///
+/// The map is a flat hexagon of `loam`, and an `ant` with [`UnitData::default`] is always available.
+/// No systems are added: each test schedules the ones it cares about.
+pub(crate) struct UnitTestApp {
+    /// The radius of the map.
+    map_radius: u32,
+    /// The length of each fixed timestep, in seconds.
+    timestep: f32,
+    /// Are units prevented from sharing tiles?
+    collision: bool,
+    /// The types of units that can be spawned.
+    unit_manifest: UnitManifest,
+    /// The items known to the app.
+    item_manifest: ItemManifest,
+    /// The structures known to the app.
+    structure_manifest: StructureManifest,
+}
+
+impl UnitTestApp {
+    /// Creates a new builder for a flat map with the provided `map_radius`.
+    pub(crate) fn new(map_radius: u32) -> Self {
+        let mut unit_manifest = UnitManifest::new();
+        unit_manifest.insert("ant", UnitData::default());
+
+        UnitTestApp {
+            map_radius,
+            timestep: 0.25,
+            collision: false,
+            unit_manifest,
+            item_manifest: ItemManifest::new(),
+            structure_manifest: StructureManifest::new(),
+        }
+    }
+
+    /// Registers a type of unit, replacing any existing unit with the same `name`.
+    pub(crate) fn with_unit(mut self, name: &str, unit_data: UnitData) -> Self {
+        self.unit_manifest.insert(name, unit_data);
+        self
+    }
+
+    /// Uses the provided `item_manifest`.
+    pub(crate) fn with_items(mut self, item_manifest: ItemManifest) -> Self {
+        self.item_manifest = item_manifest;
+        self
+    }
+
+    /// Uses the provided `structure_manifest`.
+    pub(crate) fn with_structures(mut self, structure_manifest: StructureManifest) -> Self {
+        self.structure_manifest = structure_manifest;
+        self
+    }
+
+    /// Sets the length of each fixed timestep, in seconds.
+    pub(crate) fn with_timestep(mut self, timestep: f32) -> Self {
+        self.timestep = timestep;
+        self
+    }
+
+    /// Enables or disables [`UnitCollision`].
+    pub(crate) fn with_collision(mut self, enabled: bool) -> Self {
+        self.collision = enabled;
+        self
+    }
+
+    /// Builds the [`App`].
+    ///
+    /// Each terrain entity carries its [`Id<Terrain>`], [`TilePos`] and [`Height`].
+    pub(crate) fn build(self) -> App {
+        let loam = Id::<Terrain>::from_name("loam");
+        let mut terrain_manifest = TerrainManifest::new();
+        terrain_manifest.insert(
+            "loam",
+            TerrainData {
+                walking_speed: WalkingSpeed::Uniform(1.0),
+            },
+        );
+
+        let scenes = HashMap::from_iter(
+            self.unit_manifest
+                .variants()
+                .into_iter()
+                .map(|unit_id| (unit_id, Handle::default())),
+        );
+
+        let mut app = App::new();
+        let mut map_geometry = MapGeometry::new(self.map_radius);
+        for hex in hexagon(Hex::ZERO, self.map_radius) {
+            let tile_pos = TilePos { hex };
+            let terrain_entity = app.world.spawn((loam, tile_pos, Height(0))).id();
+            map_geometry.add_terrain(tile_pos, terrain_entity);
+            map_geometry.update_height(tile_pos, Height(0));
+        }
+
+        app.insert_resource(FixedTime::new_from_secs(self.timestep))
+            .insert_resource(UnitCollision {
+                enabled: self.collision,
+            })
+            .insert_resource(map_geometry)
+            .insert_resource(self.unit_manifest)
+            .insert_resource(self.item_manifest)
+            .insert_resource(self.structure_manifest)
+            .insert_resource(terrain_manifest)
+            .insert_resource(UnitHandles {
+                scenes,
+                picking_mesh: Handle::<Mesh>::default(),
+            })
+            .init_resource::<Signals>()
+            .init_resource::<GoalWeights>()
+            .init_resource::<PickupReservations>()
+            .init_resource::<TechTree>()
+            .init_resource::<ColonyCenter>()
+            .init_resource::<Contracts>()
+            .add_event::<ItemTransferStarted>()
+            .add_event::<ItemTransferred>();
+
+        app
+    }
+}
+
+/// Spawns a unit of type `unit_id` at `tile_pos` in the `app`, using [`UnitCommandsExt::spawn_unit`](super::commands::UnitCommandsExt::spawn_unit).
+///
+/// # Panics
+///
+/// Panics if `tile_pos` is not on the map.
+pub(crate) fn spawn_unit(
+    app: &mut App,
+    tile_pos: TilePos,
+    unit_id: Id<Unit>,
+    goal: Goal,
+) -> Entity {
+    SpawnUnitCommand {
+        tile_pos,
+        unit_id,
+        goal,
+        faction: Faction::COLONY,
+    }
+    .spawn(&mut app.world)
+    .unwrap()
+}
@@ -4,12 +4,17 @@ use bevy::{
     reflect::{FromReflect, Reflect, TypeUuid},
     utils::HashMap,
 };
+use leafwing_abilities::prelude::Pool;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    asset_management::manifest::loader::RawManifest,
-    organisms::OrganismVariety,
-    units::{hunger::Diet, WanderingBehavior},
+    asset_management::manifest::{loader::RawManifest, Id},
+    organisms::{
+        energy::{Energy, EnergyPool},
+        lifecycle::Lifecycle,
+        OrganismId, OrganismVariety,
+    },
+    units::{hunger::Diet, impatience::ImpatienceConsequence, WanderingBehavior},
 };
 
 use super::Manifest;
@@ -29,10 +34,52 @@ pub struct UnitData {
     pub diet: Diet,
     /// How much impatience this unit can accumulate before getting too frustrated and picking a new task.
     pub max_impatience: u8,
+    /// What this unit does once its impatience reaches `max_impatience`.
+    #[serde(default)]
+    pub impatience_consequence: ImpatienceConsequence,
     /// How many actions will units of this type take while wandering before picking a new goal?
     ///
     /// This stores a [`WeightedIndex`](rand::distributions::WeightedIndex) to allow for multimodal distributions.
     pub wandering_behavior: WanderingBehavior,
+    /// How many tiles away units of this type will look for structures to pick up from or drop off at.
+    ///
+    /// Units must still walk next to the structure to interact with it.
+    #[serde(default = "UnitData::default_interaction_radius")]
+    pub interaction_radius: u32,
+    /// The maximum number of units of this type that can exist at once, if any.
+    ///
+    /// This is checked in addition to the global [`Population`](super::population::Population) cap.
+    #[serde(default)]
+    pub max_population: Option<u32>,
+}
+
+impl Default for UnitData {
+    /// A plain `ant` that eats `leuco_chunk`, with no lifecycle and a patient, short-wandering temperament.
+    fn default() -> Self {
+        UnitData {
+            organism_variety: OrganismVariety {
+                prototypical_form: OrganismId::Unit(Id::from_name("ant")),
+                lifecycle: Lifecycle::STATIC,
+                energy_pool: EnergyPool::new_full(Energy(100.), Energy(0.)),
+            },
+            diet: Diet::new(Id::from_name("leuco_chunk"), Energy(50.)),
+            max_impatience: 10,
+            impatience_consequence: ImpatienceConsequence::default(),
+            wandering_behavior: WanderingBehavior::from_iter([(1, 1.0)]),
+            interaction_radius: UnitData::DEFAULT_INTERACTION_RADIUS,
+            max_population: None,
+        }
+    }
+}
+
+impl UnitData {
+    /// The interaction radius used when none is specified: units only notice adjacent structures.
+    pub const DEFAULT_INTERACTION_RADIUS: u32 = 1;
+
+    /// The default value of [`UnitData::interaction_radius`], used when deserializing.
+    fn default_interaction_radius() -> u32 {
+        UnitData::DEFAULT_INTERACTION_RADIUS
+    }
 }
 
 /// The [`UnitManifest`] as seen in the manifest file.
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use emergence_lib::{
+    asset_management::{
+        localization::{Localization, RawLocalization},
+        manifest::{
+            loader::{deserialize_raw_manifest, RawManifest},
+            validation::ManifestSet,
+            Id, Manifest,
+        },
+    },
+    items::{item_manifest::RawItemManifest, recipe::RawRecipeManifest},
+    structures::structure_manifest::RawStructureManifest,
+    terrain::terrain_manifest::RawTerrainManifest,
+    units::unit_manifest::RawUnitManifest,
+};
+
+/// The directory that the game's assets are stored in.
+fn assets_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../emergence_game/assets")
+}
+
+/// Loads and processes the base game's manifest of type `M`, exactly as the asset loader does.
+fn load_base_game_manifest<M: RawManifest>() -> Manifest<M::Marker, M::Data> {
+    let path = assets_dir().join(M::path());
+    let bytes = std::fs::read(&path).unwrap_or_else(|error| panic!("{path:?}: {error}"));
+    let raw_manifest: M =
+        deserialize_raw_manifest(&bytes).unwrap_or_else(|error| panic!("{path:?}: {error}"));
+
+    raw_manifest.process()
+}
+
+#[test]
+fn base_game_manifests_are_valid() {
+    let item_manifest = load_base_game_manifest::<RawItemManifest>();
+    let recipe_manifest = load_base_game_manifest::<RawRecipeManifest>();
+    let structure_manifest = load_base_game_manifest::<RawStructureManifest>();
+    let terrain_manifest = load_base_game_manifest::<RawTerrainManifest>();
+    let unit_manifest = load_base_game_manifest::<RawUnitManifest>();
+
+    let locale_path = assets_dir().join("locales/en.locale.json");
+    let raw_localization: RawLocalization =
+        serde_json::from_slice(&std::fs::read(locale_path).unwrap()).unwrap();
+    let mut localization = Localization::default();
+    localization.load(&raw_localization);
+
+    let manifests = ManifestSet {
+        item_manifest: &item_manifest,
+        recipe_manifest: &recipe_manifest,
+        structure_manifest: &structure_manifest,
+        terrain_manifest: &terrain_manifest,
+        unit_manifest: &unit_manifest,
+        localization: Some(&localization),
+    };
+
+    if let Err(error) = manifests.validate() {
+        panic!("{error}");
+    }
+
+    // Buildings should wear down over time, so that units have repairs to do
+    for name in ["hatchery", "ant_hive", "storage", "quarry"] {
+        let structure_data = structure_manifest.get(Id::from_name(name));
+        assert!(
+            structure_data.health.decay_rate() > 0.,
+            "{name} never decays"
+        );
+    }
+
+    // Every entry should have a display string
+    for name in item_manifest
+        .names()
+        .into_iter()
+        .chain(structure_manifest.names())
+        .chain(terrain_manifest.names())
+        .chain(unit_manifest.names())
+    {
+        assert_ne!(localization.get(name), name, "{name} has no display string");
+    }
+}
@@ -4,9 +4,12 @@ use bevy::utils::{HashMap, HashSet};
 use emergence_lib::{
     asset_management::manifest::Id,
     items::{
-        inventory::Inventory,
+        inventory::{Inventory, ReservedFor},
         item_manifest::{ItemData, RawItemManifest},
-        recipe::{RawRecipeManifest, RecipeConditions, RecipeData, Threshold},
+        recipe::{
+            RawRecipeManifest, RecipeConditions, RecipeData, RecipeInput, SeasonalProfile,
+            Threshold, WorkerScaling,
+        },
         ItemCount,
     },
     organisms::{
@@ -18,13 +21,16 @@ use emergence_lib::{
     structures::{
         construction::Footprint,
         crafting::{ActiveRecipe, InputInventory},
+        health::Health,
         structure_manifest::{
-            ConstructionStrategy, RawStructureManifest, StructureData, StructureKind,
+            AdjacencyRequirement, ConstructionStrategy, RawStructureManifest, StructureData,
+            StructureKind, UpgradeCost,
         },
     },
-    terrain::terrain_manifest::{RawTerrainManifest, TerrainData},
+    terrain::terrain_manifest::{RawTerrainData, RawTerrainManifest, RawWalkingSpeed},
     units::{
         hunger::Diet,
+        impatience::ImpatienceConsequence,
         unit_manifest::{RawUnitManifest, UnitData},
         WanderingBehavior,
     },
@@ -36,8 +42,8 @@ fn can_serialize_item_manifest() {
     // Create a new raw item manifest
     let raw_item_manifest = RawItemManifest {
         items: HashMap::from_iter(vec![
-            ("test_item".to_string(), ItemData { stack_size: 1 }),
-            ("test_item_2".to_string(), ItemData { stack_size: 2 }),
+            ("test_item".to_string(), ItemData::new(1)),
+            ("test_item_2".to_string(), ItemData::new(2)),
         ]),
     };
 
@@ -59,11 +65,24 @@ fn can_serialize_terrain_manifest() {
         terrain_types: HashMap::from_iter(vec![
             (
                 "test_terrain".to_string(),
-                TerrainData { walking_speed: 1.0 },
+                RawTerrainData {
+                    walking_speed: RawWalkingSpeed::Uniform(1.0),
+                },
             ),
             (
                 "test_terrain2".to_string(),
-                TerrainData { walking_speed: 2.0 },
+                RawTerrainData {
+                    walking_speed: RawWalkingSpeed::Uniform(2.0),
+                },
+            ),
+            (
+                "test_terrain3".to_string(),
+                RawTerrainData {
+                    walking_speed: RawWalkingSpeed::PerUnit {
+                        default: 1.0,
+                        units: HashMap::from_iter(vec![("test_unit".to_string(), 0.5)]),
+                    },
+                },
             ),
         ]),
     };
@@ -99,6 +118,9 @@ fn can_serialize_unit_manifest() {
                         (8, 0.2),
                         (16, 0.1),
                     ]),
+                    interaction_radius: 2,
+                    max_population: None,
+                    impatience_consequence: ImpatienceConsequence::Abandon,
                 },
             ),
             (
@@ -112,6 +134,9 @@ fn can_serialize_unit_manifest() {
                     diet: Diet::new(Id::from_name("acacia_leaf"), Energy(0.)),
                     max_impatience: 0,
                     wandering_behavior: WanderingBehavior::from_iter([(0, 0.7), (16, 0.1)]),
+                    interaction_radius: 1,
+                    max_population: None,
+                    impatience_consequence: ImpatienceConsequence::Abandon,
                 },
             ),
         ]),
@@ -137,49 +162,83 @@ fn can_serialize_recipe_manifest() {
                 "acacia_leaf_production".to_string(),
                 RecipeData {
                     inputs: Vec::new(),
+                    fluid_inputs: Vec::new(),
                     outputs: vec![ItemCount::one(Id::from_name("acacia_leaf"))],
+                    fluid_outputs: Vec::new(),
                     craft_time: Duration::from_secs(3),
                     conditions: RecipeConditions::new(
                         0,
                         Threshold::new(Illuminance(5e3), Illuminance(6e4)),
                     ),
+                    worker_scaling: WorkerScaling::Linear,
+                    seasonal_profile: SeasonalProfile::UNIFORM,
                     energy: Some(Energy(20.)),
+                    prerequisites: Vec::new(),
+                    terrain_effect: None,
+                    mined_deposit: None,
+                    spawns: None,
                 },
             ),
             (
                 "leuco_chunk_production".to_string(),
                 RecipeData {
-                    inputs: vec![ItemCount::one(Id::from_name("acacia_leaf"))],
+                    inputs: vec![RecipeInput::one(Id::from_name("acacia_leaf"))],
+                    fluid_inputs: Vec::new(),
                     outputs: vec![ItemCount::one(Id::from_name("leuco_chunk"))],
+                    fluid_outputs: Vec::new(),
                     craft_time: Duration::from_secs(2),
                     conditions: RecipeConditions::NONE,
+                    worker_scaling: WorkerScaling::Linear,
+                    seasonal_profile: SeasonalProfile::UNIFORM,
                     energy: Some(Energy(40.)),
+                    prerequisites: Vec::new(),
+                    terrain_effect: None,
+                    mined_deposit: None,
+                    spawns: None,
                 },
             ),
             (
                 "ant_egg_production".to_string(),
                 RecipeData {
                     inputs: Vec::new(),
+                    fluid_inputs: Vec::new(),
                     outputs: vec![ItemCount::one(Id::from_name("ant_egg"))],
+                    fluid_outputs: Vec::new(),
                     craft_time: Duration::from_secs(10),
                     conditions: RecipeConditions {
                         workers_required: 2,
                         allowable_light_range: None,
+                        required_terrain: None,
                     },
+                    worker_scaling: WorkerScaling::Linear,
+                    seasonal_profile: SeasonalProfile::UNIFORM,
                     energy: None,
+                    prerequisites: Vec::new(),
+                    terrain_effect: None,
+                    mined_deposit: None,
+                    spawns: None,
                 },
             ),
             (
                 "hatch_ants".to_string(),
                 RecipeData {
-                    inputs: vec![ItemCount::one(Id::from_name("ant_egg"))],
+                    inputs: vec![RecipeInput::one(Id::from_name("ant_egg"))],
+                    fluid_inputs: Vec::new(),
                     outputs: Vec::new(),
+                    fluid_outputs: Vec::new(),
                     craft_time: Duration::from_secs(10),
                     conditions: RecipeConditions {
                         workers_required: 1,
                         allowable_light_range: None,
+                        required_terrain: None,
                     },
+                    worker_scaling: WorkerScaling::Linear,
+                    seasonal_profile: SeasonalProfile::UNIFORM,
                     energy: None,
+                    prerequisites: Vec::new(),
+                    terrain_effect: None,
+                    mined_deposit: None,
+                    spawns: Some(OrganismId::Unit(Id::from_name("ant"))),
                 },
             ),
         ]),
@@ -206,6 +265,7 @@ fn can_serialize_structure_manifest() {
             inventory: Inventory::new_from_item(Id::from_name("acacia_leaf"), 1),
         },
         allowed_terrain_types: HashSet::from_iter([Id::from_name("loam"), Id::from_name("muddy")]),
+        required_adjacency: HashSet::new(),
     };
 
     // Create a new raw structure manifest
@@ -221,6 +281,7 @@ fn can_serialize_structure_manifest() {
                     }),
                     kind: StructureKind::Crafting {
                         starting_recipe: ActiveRecipe::new(Id::from_name("leuco_chunk_production")),
+                        output_direction: None,
                     },
                     construction_strategy: ConstructionStrategy {
                         seedling: None,
@@ -232,8 +293,15 @@ fn can_serialize_structure_manifest() {
                             Id::from_name("loam"),
                             Id::from_name("muddy"),
                         ]),
+                        required_adjacency: HashSet::new(),
                     },
+                    upgrades_to: None,
+                    upgrade_cost: UpgradeCost::default(),
                     max_workers: 6,
+                    work_radius: 0,
+                    power_draw: 0.,
+                    power_supply: 0.,
+                    health: Health::default(),
                     footprint: Footprint::single(),
                 },
             ),
@@ -251,9 +319,16 @@ fn can_serialize_structure_manifest() {
                     }),
                     kind: StructureKind::Crafting {
                         starting_recipe: ActiveRecipe::new(Id::from_name("acacia_leaf_production")),
+                        output_direction: None,
                     },
                     construction_strategy: acacia_construction_strategy.clone(),
+                    upgrades_to: None,
+                    upgrade_cost: UpgradeCost::default(),
                     max_workers: 1,
+                    work_radius: 0,
+                    power_draw: 0.,
+                    power_supply: 0.,
+                    health: Health::default(),
                     footprint: Footprint::single(),
                 },
             ),
@@ -271,9 +346,16 @@ fn can_serialize_structure_manifest() {
                     }),
                     kind: StructureKind::Crafting {
                         starting_recipe: ActiveRecipe::new(Id::from_name("acacia_leaf_production")),
+                        output_direction: None,
                     },
                     construction_strategy: acacia_construction_strategy.clone(),
+                    upgrades_to: None,
+                    upgrade_cost: UpgradeCost::default(),
                     max_workers: 1,
+                    work_radius: 0,
+                    power_draw: 0.,
+                    power_supply: 0.,
+                    health: Health::default(),
                     footprint: Footprint::single(),
                 },
             ),
@@ -287,9 +369,16 @@ fn can_serialize_structure_manifest() {
                     }),
                     kind: StructureKind::Crafting {
                         starting_recipe: ActiveRecipe::new(Id::from_name("acacia_leaf_production")),
+                        output_direction: None,
                     },
                     construction_strategy: acacia_construction_strategy,
+                    upgrades_to: None,
+                    upgrade_cost: UpgradeCost::default(),
                     max_workers: 6,
+                    work_radius: 0,
+                    power_draw: 0.,
+                    power_supply: 0.,
+                    health: Health::default(),
                     footprint: Footprint::single(),
                 },
             ),
@@ -299,6 +388,7 @@ fn can_serialize_structure_manifest() {
                     organism_variety: None,
                     kind: StructureKind::Crafting {
                         starting_recipe: ActiveRecipe::new(Id::from_name("ant_egg_production")),
+                        output_direction: None,
                     },
                     construction_strategy: ConstructionStrategy {
                         seedling: None,
@@ -309,8 +399,15 @@ fn can_serialize_structure_manifest() {
                             Id::from_name("muddy"),
                             Id::from_name("rocky"),
                         ]),
+                        required_adjacency: HashSet::new(),
                     },
+                    upgrades_to: None,
+                    upgrade_cost: UpgradeCost::default(),
                     max_workers: 3,
+                    work_radius: 0,
+                    power_draw: 0.,
+                    power_supply: 0.,
+                    health: Health::default(),
                     footprint: Footprint::hexagon(1),
                 },
             ),
@@ -320,6 +417,7 @@ fn can_serialize_structure_manifest() {
                     organism_variety: None,
                     kind: StructureKind::Crafting {
                         starting_recipe: ActiveRecipe::new(Id::from_name("hatch_ants")),
+                        output_direction: None,
                     },
                     construction_strategy: ConstructionStrategy {
                         seedling: None,
@@ -330,8 +428,17 @@ fn can_serialize_structure_manifest() {
                             Id::from_name("muddy"),
                             Id::from_name("rocky"),
                         ]),
+                        required_adjacency: HashSet::from_iter([AdjacencyRequirement::Structure(
+                            Id::from_name("ant_hive"),
+                        )]),
                     },
+                    upgrades_to: None,
+                    upgrade_cost: UpgradeCost::default(),
                     max_workers: 6,
+                    work_radius: 0,
+                    power_draw: 0.,
+                    power_supply: 0.,
+                    health: Health::default(),
                     // Forms a crescent shape
                     footprint: Footprint::single(),
                 },
@@ -342,7 +449,7 @@ fn can_serialize_structure_manifest() {
                     organism_variety: None,
                     kind: StructureKind::Storage {
                         max_slot_count: 3,
-                        reserved_for: None,
+                        reserved_for: ReservedFor::Any,
                     },
                     construction_strategy: ConstructionStrategy {
                         seedling: None,
@@ -355,8 +462,15 @@ fn can_serialize_structure_manifest() {
                             Id::from_name("muddy"),
                             Id::from_name("rocky"),
                         ]),
+                        required_adjacency: HashSet::new(),
                     },
+                    upgrades_to: None,
+                    upgrade_cost: UpgradeCost::default(),
                     max_workers: 6,
+                    work_radius: 0,
+                    power_draw: 0.,
+                    power_supply: 0.,
+                    health: Health::default(),
                     footprint: Footprint::single(),
                 },
             ),
@@ -1,7 +1,7 @@
 // use common::{bevy_app, interaction_app, minimal_app, simulation_app};
 
 use emergence_lib::simulation::generation::GenerationConfig;
-use emergence_lib::testing::{interaction_app, minimal_app, simulation_app};
+use emergence_lib::testing::{interaction_app, minimal_app, simulation_app, unit_colony_app};
 
 #[test]
 fn minimal_app_can_update() {
@@ -26,3 +26,10 @@ fn interaction_app_can_update() {
 
     app.update()
 }
+
+#[test]
+fn unit_colony_app_can_update() {
+    let mut app = unit_colony_app(10, 10, 10);
+
+    app.update()
+}